@@ -0,0 +1,493 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Defines BLS12-381 signature primitives used by the Linera protocol.
+//!
+//! Signatures over the same value can be aggregated into a single signature that is
+//! verified against all the signers' public keys at once. Public keys are points of G1 and
+//! signatures are points of G2 (the "minimal public key size" variant).
+
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
+use blst::{
+    min_pk::{AggregateSignature, PublicKey, SecretKey, Signature},
+    BLST_ERROR,
+};
+use serde::{Deserialize, Serialize};
+
+use super::{BcsHashable, BcsSignable, CryptoError, CryptoHash, HasTypeName};
+use crate::doc_scalar;
+
+/// Name of the BLS12-381 scheme.
+const BLS12381_SCHEME_LABEL: &str = "BLS12-381";
+
+/// Length of a compressed BLS12-381 public key.
+const BLS12381_PUBLIC_KEY_SIZE: usize = 48;
+
+/// Length of a compressed BLS12-381 signature.
+const BLS12381_SIGNATURE_SIZE: usize = 96;
+
+/// Domain separation tag for signatures, using the proof-of-possession scheme.
+const SIGNATURE_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// Domain separation tag for proofs of possession.
+const PROOF_OF_POSSESSION_DST: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
+/// A BLS12-381 secret key.
+pub struct Bls12381SecretKey(SecretKey);
+
+/// A BLS12-381 public key.
+#[derive(Eq, PartialEq, Copy, Clone)]
+pub struct Bls12381PublicKey(pub PublicKey);
+
+/// A BLS12-381 signature, possibly aggregated from the signatures of several signers.
+#[derive(Eq, PartialEq, Copy, Clone)]
+pub struct Bls12381Signature(pub Signature);
+
+impl Hash for Bls12381PublicKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_bytes().hash(state);
+    }
+}
+
+impl Hash for Bls12381Signature {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_bytes().hash(state);
+    }
+}
+
+impl PartialOrd for Bls12381PublicKey {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Bls12381PublicKey {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.as_bytes().cmp(&other.as_bytes())
+    }
+}
+
+impl Bls12381PublicKey {
+    /// A fake public key used for testing.
+    #[cfg(all(with_testing, not(target_arch = "wasm32")))]
+    pub fn test_key(seed: u8) -> Self {
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(seed as u64);
+        Bls12381SecretKey::generate_from(&mut rng).public()
+    }
+
+    /// Returns the bytes of the public key in compressed representation.
+    pub fn as_bytes(&self) -> [u8; BLS12381_PUBLIC_KEY_SIZE] {
+        self.0.compress()
+    }
+
+    /// Decodes the bytes into the public key, checking that it is a valid point of G1.
+    /// Expects the bytes to be of compressed representation.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CryptoError> {
+        if bytes.len() != BLS12381_PUBLIC_KEY_SIZE {
+            return Err(CryptoError::IncorrectPublicKeySize {
+                scheme: BLS12381_SCHEME_LABEL,
+                len: bytes.len(),
+                expected: BLS12381_PUBLIC_KEY_SIZE,
+            });
+        }
+        let key = PublicKey::key_validate(bytes).map_err(CryptoError::Bls12381Error)?;
+        Ok(Self(key))
+    }
+
+    /// Checks that `proof` proves possession of the secret key for this public key.
+    ///
+    /// Aggregate signatures are only secure against rogue-key attacks if every public key
+    /// was registered along with such a proof.
+    pub fn verify_proof_of_possession(&self, proof: &Bls12381Signature) -> Result<(), CryptoError> {
+        let result = proof.0.verify(
+            true,
+            &self.as_bytes(),
+            PROOF_OF_POSSESSION_DST,
+            &[],
+            &self.0,
+            false,
+        );
+        check_result::<Self>(result)
+    }
+}
+
+impl Bls12381SecretKey {
+    /// Returns a public key for the given secret key.
+    pub fn public(&self) -> Bls12381PublicKey {
+        Bls12381PublicKey(self.0.sk_to_pk())
+    }
+
+    /// Copies the secret key.
+    ///
+    /// The `Clone` and `Copy` traits are deliberately not implemented for `Bls12381SecretKey` to prevent
+    /// accidental copies of secret keys.
+    pub fn copy(&self) -> Self {
+        Self(self.0.clone())
+    }
+
+    /// Generates a new secret key.
+    #[cfg(all(with_getrandom, with_testing))]
+    pub fn generate() -> Self {
+        let mut rng = rand::rngs::OsRng;
+        Self::generate_from(&mut rng)
+    }
+
+    /// Generates a new secret key from the given RNG. Use with care.
+    #[cfg(with_getrandom)]
+    pub fn generate_from<R: super::CryptoRng>(rng: &mut R) -> Self {
+        let mut ikm = [0u8; 32];
+        rng.fill_bytes(&mut ikm);
+        // UNWRAP: Key generation only fails if the input key material is shorter than 32 bytes.
+        Self(SecretKey::key_gen(&ikm, &[]).unwrap())
+    }
+
+    /// Returns a proof of possession of this secret key, to be published with the public key.
+    pub fn proof_of_possession(&self) -> Bls12381Signature {
+        let public_key = self.public().as_bytes();
+        Bls12381Signature(self.0.sign(&public_key, PROOF_OF_POSSESSION_DST, &[]))
+    }
+}
+
+impl Bls12381Signature {
+    /// Computes a BLS12-381 signature for `value` using the given `secret`.
+    /// It first serializes the `T` type and then creates the `CryptoHash` from the serialized bytes.
+    pub fn new<'de, T>(value: &T, secret: &Bls12381SecretKey) -> Self
+    where
+        T: BcsSignable<'de>,
+    {
+        let message = CryptoHash::new(value).as_bytes().0;
+        Bls12381Signature(secret.0.sign(&message, SIGNATURE_DST, &[]))
+    }
+
+    /// Checks a signature.
+    pub fn check<'de, T>(&self, value: &T, author: Bls12381PublicKey) -> Result<(), CryptoError>
+    where
+        T: BcsSignable<'de> + fmt::Debug,
+    {
+        let message = CryptoHash::new(value).as_bytes().0;
+        let result = self
+            .0
+            .verify(true, &message, SIGNATURE_DST, &[], &author.0, false);
+        check_result::<T>(result)
+    }
+
+    /// Aggregates signatures of the same value into a single signature.
+    ///
+    /// Returns `None` if there are no signatures.
+    pub fn aggregate<'a>(signatures: impl IntoIterator<Item = &'a Self>) -> Option<Self> {
+        let signatures = signatures
+            .into_iter()
+            .map(|signature| &signature.0)
+            .collect::<Vec<_>>();
+        // The signatures are validated when they are deserialized or checked.
+        let aggregate = AggregateSignature::aggregate(&signatures, false).ok()?;
+        Some(Bls12381Signature(aggregate.to_signature()))
+    }
+
+    /// Checks an aggregate signature of `value` against the public keys of all the signers.
+    pub fn check_aggregate<'a, 'de, T>(
+        &self,
+        value: &T,
+        authors: impl IntoIterator<Item = &'a Bls12381PublicKey>,
+    ) -> Result<(), CryptoError>
+    where
+        T: BcsSignable<'de> + fmt::Debug,
+    {
+        let message = CryptoHash::new(value).as_bytes().0;
+        let authors = authors.into_iter().map(|key| &key.0).collect::<Vec<_>>();
+        let result = self
+            .0
+            .fast_aggregate_verify(true, &message, SIGNATURE_DST, &authors);
+        check_result::<T>(result)
+    }
+
+    /// Returns the byte representation of the signature.
+    pub fn as_bytes(&self) -> [u8; BLS12381_SIGNATURE_SIZE] {
+        self.0.compress()
+    }
+
+    /// Creates a signature from its compressed byte representation, checking that it is a
+    /// valid point of G2.
+    pub fn from_slice<A: AsRef<[u8]>>(bytes: A) -> Result<Self, CryptoError> {
+        let bytes = bytes.as_ref();
+        if bytes.len() != BLS12381_SIGNATURE_SIZE {
+            return Err(CryptoError::IncorrectSignatureBytes {
+                scheme: BLS12381_SCHEME_LABEL,
+                len: bytes.len(),
+                expected: BLS12381_SIGNATURE_SIZE,
+            });
+        }
+        let signature = Signature::sig_validate(bytes, true).map_err(CryptoError::Bls12381Error)?;
+        Ok(Bls12381Signature(signature))
+    }
+}
+
+fn check_result<T: HasTypeName>(result: BLST_ERROR) -> Result<(), CryptoError> {
+    if result == BLST_ERROR::BLST_SUCCESS {
+        Ok(())
+    } else {
+        Err(CryptoError::InvalidSignature {
+            error: format!("{result:?}"),
+            type_name: T::type_name().to_string(),
+        })
+    }
+}
+
+impl fmt::Debug for Bls12381SecretKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<redacted for BLS12-381 secret key>")
+    }
+}
+
+impl PartialEq for Bls12381SecretKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.public() == other.public()
+    }
+}
+
+impl Eq for Bls12381SecretKey {}
+
+impl Serialize for Bls12381SecretKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::ser::Serializer,
+    {
+        // This is only used for JSON configuration.
+        assert!(serializer.is_human_readable());
+        serializer.serialize_str(&hex::encode(self.0.to_bytes()))
+    }
+}
+
+impl<'de> Deserialize<'de> for Bls12381SecretKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        // This is only used for JSON configuration.
+        assert!(deserializer.is_human_readable());
+        let str = String::deserialize(deserializer)?;
+        let bytes = hex::decode(&str).map_err(serde::de::Error::custom)?;
+        let sk = SecretKey::from_bytes(&bytes)
+            .map_err(|error| serde::de::Error::custom(format!("{error:?}")))?;
+        Ok(Bls12381SecretKey(sk))
+    }
+}
+
+impl Serialize for Bls12381PublicKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::ser::Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&hex::encode(self.as_bytes()))
+        } else {
+            let compact_pk = serde_utils::CompressedPublicKey(self.as_bytes());
+            serializer.serialize_newtype_struct("Bls12381PublicKey", &compact_pk)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Bls12381PublicKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            let value = hex::decode(s).map_err(serde::de::Error::custom)?;
+            Ok(Bls12381PublicKey::from_bytes(&value).map_err(serde::de::Error::custom)?)
+        } else {
+            #[derive(Deserialize)]
+            #[serde(rename = "Bls12381PublicKey")]
+            struct PublicKey(serde_utils::CompressedPublicKey);
+            let compact = PublicKey::deserialize(deserializer)?;
+            Ok(Bls12381PublicKey::from_bytes(&compact.0 .0).map_err(serde::de::Error::custom)?)
+        }
+    }
+}
+
+impl FromStr for Bls12381PublicKey {
+    type Err = CryptoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_bytes(&hex::decode(s)?)
+    }
+}
+
+impl fmt::Display for Bls12381PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.as_bytes()))
+    }
+}
+
+impl fmt::Debug for Bls12381PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..", hex::encode(&self.as_bytes()[0..9]))
+    }
+}
+
+impl BcsHashable<'_> for Bls12381PublicKey {}
+
+impl Serialize for Bls12381Signature {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::ser::Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&hex::encode(self.as_bytes()))
+        } else {
+            let compact = serde_utils::CompactSignature(self.as_bytes());
+            serializer.serialize_newtype_struct("Bls12381Signature", &compact)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Bls12381Signature {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            let value = hex::decode(s).map_err(serde::de::Error::custom)?;
+            Self::from_slice(&value).map_err(serde::de::Error::custom)
+        } else {
+            #[derive(Deserialize)]
+            #[serde(rename = "Bls12381Signature")]
+            struct Signature(serde_utils::CompactSignature);
+
+            let value = Signature::deserialize(deserializer)?;
+            Self::from_slice(value.0 .0.as_ref()).map_err(serde::de::Error::custom)
+        }
+    }
+}
+
+impl FromStr for Bls12381Signature {
+    type Err = CryptoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_slice(hex::decode(s)?)
+    }
+}
+
+impl fmt::Display for Bls12381Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.as_bytes()))
+    }
+}
+
+impl fmt::Debug for Bls12381Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..", hex::encode(&self.as_bytes()[0..9]))
+    }
+}
+
+doc_scalar!(Bls12381Signature, "A BLS12-381 signature value");
+doc_scalar!(Bls12381PublicKey, "A BLS12-381 public key value");
+
+mod serde_utils {
+    use serde::{Deserialize, Serialize};
+    use serde_with::serde_as;
+
+    use super::{BLS12381_PUBLIC_KEY_SIZE, BLS12381_SIGNATURE_SIZE};
+
+    /// Wrapper around compact signature serialization
+    /// so that we can implement custom serializer for it that uses fixed length.
+    #[serde_as]
+    #[derive(Serialize, Deserialize)]
+    #[serde(transparent)]
+    pub struct CompactSignature(#[serde_as(as = "[_; 96]")] pub [u8; BLS12381_SIGNATURE_SIZE]);
+
+    #[serde_as]
+    #[derive(Serialize, Deserialize)]
+    #[serde(transparent)]
+    pub struct CompressedPublicKey(#[serde_as(as = "[_; 48]")] pub [u8; BLS12381_PUBLIC_KEY_SIZE]);
+}
+
+#[cfg(with_testing)]
+mod tests {
+    use crate::crypto::{
+        bls12381::{Bls12381PublicKey, Bls12381SecretKey, Bls12381Signature},
+        TestString,
+    };
+
+    #[test]
+    fn test_signatures() {
+        let secret1 = Bls12381SecretKey::generate();
+        let secret2 = Bls12381SecretKey::generate();
+
+        let ts = TestString("hello".into());
+        let tsx = TestString("hellox".into());
+
+        let s = Bls12381Signature::new(&ts, &secret1);
+        assert!(s.check(&ts, secret1.public()).is_ok());
+        assert!(s.check(&ts, secret2.public()).is_err());
+        assert!(s.check(&tsx, secret1.public()).is_err());
+    }
+
+    #[test]
+    fn test_aggregate_signatures() {
+        let secrets = (0..4)
+            .map(|_| Bls12381SecretKey::generate())
+            .collect::<Vec<_>>();
+        let public_keys = secrets.iter().map(|s| s.public()).collect::<Vec<_>>();
+        let ts = TestString("hello".into());
+        let tsx = TestString("hellox".into());
+
+        let signatures = secrets[..3]
+            .iter()
+            .map(|secret| Bls12381Signature::new(&ts, secret))
+            .collect::<Vec<_>>();
+        let aggregate = Bls12381Signature::aggregate(&signatures).unwrap();
+        assert!(aggregate.check_aggregate(&ts, &public_keys[..3]).is_ok());
+        assert!(aggregate.check_aggregate(&tsx, &public_keys[..3]).is_err());
+        assert!(aggregate.check_aggregate(&ts, &public_keys[..2]).is_err());
+        assert!(aggregate.check_aggregate(&ts, &public_keys[1..]).is_err());
+        assert!(Bls12381Signature::aggregate(&[]).is_none());
+    }
+
+    #[test]
+    fn test_proof_of_possession() {
+        let secret1 = Bls12381SecretKey::generate();
+        let secret2 = Bls12381SecretKey::generate();
+        let proof = secret1.proof_of_possession();
+        assert!(secret1.public().verify_proof_of_possession(&proof).is_ok());
+        assert!(secret2.public().verify_proof_of_possession(&proof).is_err());
+        // A signature over the public key bytes with the wrong tag is not a proof.
+        let signature = Bls12381Signature::new(&TestString("hello".into()), &secret1);
+        assert!(secret1
+            .public()
+            .verify_proof_of_possession(&signature)
+            .is_err());
+    }
+
+    #[test]
+    fn test_serialization() {
+        let secret = Bls12381SecretKey::generate();
+        let s = serde_json::to_string(&secret).unwrap();
+        let secret2: Bls12381SecretKey = serde_json::from_str(&s).unwrap();
+        assert_eq!(secret, secret2);
+
+        let key = Bls12381PublicKey::test_key(0);
+        let s = serde_json::to_string(&key).unwrap();
+        assert_eq!(key, serde_json::from_str(&s).unwrap());
+        let s = bcs::to_bytes(&key).unwrap();
+        assert_eq!(s.len(), 48);
+        assert_eq!(key, bcs::from_bytes(&s).unwrap());
+        assert_eq!(key, key.to_string().parse().unwrap());
+
+        let sig = Bls12381Signature::new(&TestString("hello".into()), &secret);
+        let s = serde_json::to_string(&sig).unwrap();
+        assert_eq!(sig, serde_json::from_str(&s).unwrap());
+        let s = bcs::to_bytes(&sig).unwrap();
+        assert_eq!(s.len(), 96);
+        assert_eq!(sig, bcs::from_bytes(&s).unwrap());
+        assert_eq!(sig, sig.to_string().parse().unwrap());
+    }
+}
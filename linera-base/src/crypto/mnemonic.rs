@@ -0,0 +1,225 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Hierarchical deterministic derivation of account keys from a BIP-39 mnemonic.
+//!
+//! Ed25519 keys are derived with SLIP-10 along `m/44'/6060'/0'/0'/i'`, secp256k1 keys with
+//! BIP-32 along `m/44'/6060'/0'/0/i`, and EVM secp256k1 keys along the usual Ethereum path
+//! `m/44'/60'/0'/0/i`, so that wallets using the same mnemonic find the same addresses.
+
+use std::{fmt, str::FromStr};
+
+use hmac::{Hmac, Mac as _};
+use serde::{Deserialize, Serialize};
+use sha2::Sha512;
+
+use super::{
+    AccountSecretKey, CryptoError, CryptoRng, Ed25519SecretKey, EvmSecretKey, Secp256k1SecretKey,
+    SignatureScheme,
+};
+
+/// The coin type in the derivation paths of Ed25519 and secp256k1 account keys.
+pub const LINERA_COIN_TYPE: u32 = 6060;
+/// The coin type in the derivation paths of EVM account keys.
+const ETHEREUM_COIN_TYPE: u32 = 60;
+/// The flag of hardened derivation indices.
+const HARDENED: u32 = 1 << 31;
+
+/// The number of words of generated mnemonics.
+const MNEMONIC_WORD_COUNT: usize = 24;
+
+/// A BIP-39 mnemonic in English, from which account keys are derived.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Mnemonic(bip39::Mnemonic);
+
+impl Mnemonic {
+    /// Generates a new 24-word mnemonic from the given RNG.
+    pub fn generate_from<R: CryptoRng>(rng: &mut R) -> Self {
+        let mnemonic =
+            bip39::Mnemonic::generate_in_with(rng, bip39::Language::English, MNEMONIC_WORD_COUNT)
+                .expect("the word count is valid");
+        Mnemonic(mnemonic)
+    }
+
+    /// Returns the words of the mnemonic, separated by spaces.
+    pub fn phrase(&self) -> String {
+        self.0.to_string()
+    }
+
+    /// Derives the account key of the given scheme with the given index.
+    pub fn derive_key(&self, scheme: SignatureScheme, index: u32) -> AccountSecretKey {
+        let seed = self.0.to_seed("");
+        let seed = seed.as_slice();
+        match scheme {
+            SignatureScheme::Ed25519 => {
+                let path = [44, LINERA_COIN_TYPE, 0, 0, index];
+                let secret = derive_ed25519(seed, &path);
+                AccountSecretKey::Ed25519(Ed25519SecretKey(ed25519_dalek::SigningKey::from_bytes(
+                    &secret,
+                )))
+            }
+            SignatureScheme::Secp256k1 => {
+                let secret = derive_secp256k1(seed, LINERA_COIN_TYPE, index);
+                AccountSecretKey::Secp256k1(Secp256k1SecretKey(secret))
+            }
+            SignatureScheme::EvmSecp256k1 => {
+                let secret = derive_secp256k1(seed, ETHEREUM_COIN_TYPE, index);
+                AccountSecretKey::EvmSecp256k1(EvmSecretKey(secret))
+            }
+        }
+    }
+}
+
+/// Derives an Ed25519 secret key along the given path, with hardened indices only, as
+/// specified by SLIP-10.
+fn derive_ed25519(seed: &[u8], path: &[u32]) -> [u8; 32] {
+    let (mut secret, mut chain_code) = hmac_sha512(b"ed25519 seed", &[seed]);
+    for index in path {
+        let index = (index | HARDENED).to_be_bytes();
+        (secret, chain_code) = hmac_sha512(&chain_code, &[&[0], &secret, &index]);
+    }
+    secret
+}
+
+/// Derives a secp256k1 secret key along `m/44'/coin_type'/0'/0/index`, as specified by
+/// BIP-32.
+fn derive_secp256k1(seed: &[u8], coin_type: u32, index: u32) -> k256::ecdsa::SigningKey {
+    let path = format!("m/44'/{coin_type}'/0'/0/{index}")
+        .parse::<bip32::DerivationPath>()
+        .expect("the derivation path is valid");
+    bip32::XPrv::derive_from_path(seed, &path)
+        .expect("derivation fails with negligible probability")
+        .private_key()
+        .clone()
+}
+
+/// Returns the two halves of the HMAC-SHA512 of the concatenated `data` under `key`.
+fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> ([u8; 32], [u8; 32]) {
+    let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    for data in data {
+        mac.update(data);
+    }
+    let output = mac.finalize().into_bytes();
+    let mut left = [0; 32];
+    let mut right = [0; 32];
+    left.copy_from_slice(&output[..32]);
+    right.copy_from_slice(&output[32..]);
+    (left, right)
+}
+
+impl FromStr for Mnemonic {
+    type Err = CryptoError;
+
+    fn from_str(phrase: &str) -> Result<Self, Self::Err> {
+        let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+        bip39::Mnemonic::parse_in(bip39::Language::English, phrase)
+            .map(Mnemonic)
+            .map_err(|error| CryptoError::InvalidMnemonic(error.to_string()))
+    }
+}
+
+impl fmt::Debug for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The words are never printed.
+        write!(f, "Mnemonic(..)")
+    }
+}
+
+impl Serialize for Mnemonic {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.phrase())
+    }
+}
+
+impl<'de> Deserialize<'de> for Mnemonic {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let phrase = String::deserialize(deserializer)?;
+        phrase.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::InMemorySigner;
+
+    /// The mnemonic of the test vectors.
+    const PHRASE: &str = "test test test test test test test test test test test junk";
+
+    #[test]
+    fn test_evm_derivation() {
+        let mnemonic: Mnemonic = PHRASE.parse().unwrap();
+        let AccountSecretKey::EvmSecp256k1(secret) =
+            mnemonic.derive_key(SignatureScheme::EvmSecp256k1, 0)
+        else {
+            panic!("unexpected scheme");
+        };
+        // The first account of the usual development mnemonic.
+        assert_eq!(
+            secret.address().to_string().to_lowercase(),
+            "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+        );
+    }
+
+    #[test]
+    fn test_slip10_ed25519_derivation() {
+        // Test vector 1 of SLIP-10 for Ed25519, at `m/0'/1'`.
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let secret = derive_ed25519(&seed, &[0, 1]);
+        assert_eq!(
+            hex::encode(secret),
+            "b1d0bad404bf35da785a64ca1ac54b2617211d2777696fbffaf208f746ae84f2"
+        );
+    }
+
+    #[test]
+    fn test_mnemonic_derivation() {
+        let mut rng = Box::<dyn CryptoRng>::from(Some(42));
+        let mnemonic = Mnemonic::generate_from(&mut rng);
+        assert_eq!(mnemonic.phrase().split(' ').count(), 24);
+        let recovered: Mnemonic = mnemonic.phrase().parse().unwrap();
+        for scheme in [
+            SignatureScheme::Ed25519,
+            SignatureScheme::Secp256k1,
+            SignatureScheme::EvmSecp256k1,
+        ] {
+            let key = mnemonic.derive_key(scheme, 3);
+            assert_eq!(key.public().scheme(), scheme);
+            assert_eq!(recovered.derive_key(scheme, 3).public(), key.public());
+            assert_ne!(mnemonic.derive_key(scheme, 4).public(), key.public());
+        }
+        assert!("not a valid mnemonic".parse::<Mnemonic>().is_err());
+    }
+
+    #[test]
+    fn test_signer_from_mnemonic() {
+        let mnemonic: Mnemonic = PHRASE.parse().unwrap();
+        let mut signer = InMemorySigner::from_mnemonic(mnemonic.clone(), SignatureScheme::Ed25519);
+        let first = signer.generate_new();
+        let second = signer.generate_new();
+        assert_ne!(first, second);
+        assert_eq!(signer.mnemonic(), Some(mnemonic.clone()));
+
+        // The derivation state survives serialization.
+        let json = serde_json::to_string(&signer).unwrap();
+        let mut deserialized: InMemorySigner = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.keys(), signer.keys());
+        let third = deserialized.generate_new();
+
+        // Recovering from the mnemonic yields the same keys.
+        let mut recovered =
+            InMemorySigner::from_mnemonic(mnemonic.clone(), SignatureScheme::Ed25519);
+        assert_eq!(recovered.generate_new(), first);
+        assert_eq!(recovered.generate_new(), second);
+        assert_eq!(recovered.generate_new(), third);
+
+        // The scheme is part of the derivation.
+        let mut evm_signer = InMemorySigner::from_mnemonic(mnemonic, SignatureScheme::EvmSecp256k1);
+        let evm_key = evm_signer.generate_new();
+        assert_eq!(evm_key.scheme(), SignatureScheme::EvmSecp256k1);
+        assert_eq!(
+            deserialized.generate_new().scheme(),
+            SignatureScheme::Ed25519
+        );
+    }
+}
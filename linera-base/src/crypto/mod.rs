@@ -4,18 +4,26 @@
 
 //! Define the cryptographic primitives used by the Linera protocol.
 
+#[cfg(not(chain))]
+mod bls12381;
 mod ed25519;
 mod hash;
+#[cfg(with_getrandom)]
+mod mnemonic;
 #[allow(dead_code)]
 mod secp256k1;
 pub mod signer;
 use std::{fmt::Display, io, num::ParseIntError, str::FromStr};
 
 use alloy_primitives::FixedBytes;
+#[cfg(not(chain))]
+pub use bls12381::{Bls12381PublicKey, Bls12381SecretKey, Bls12381Signature};
 use custom_debug_derive::Debug;
 pub use ed25519::{Ed25519PublicKey, Ed25519SecretKey, Ed25519Signature};
 pub use hash::*;
 use linera_witty::{WitLoad, WitStore, WitType};
+#[cfg(with_getrandom)]
+pub use mnemonic::{Mnemonic, LINERA_COIN_TYPE};
 pub use secp256k1::{
     evm::{EvmPublicKey, EvmSecretKey, EvmSignature},
     Secp256k1PublicKey, Secp256k1SecretKey, Secp256k1Signature,
@@ -346,6 +354,12 @@ pub enum CryptoError {
     PublicKeyParseError(bcs::Error),
     #[error("could not parse signature: {0}")]
     SignatureParseError(bcs::Error),
+    #[cfg(not(chain))]
+    #[error("BLS12-381 error: {0:?}")]
+    Bls12381Error(blst::BLST_ERROR),
+    #[cfg(with_getrandom)]
+    #[error("invalid mnemonic: {0}")]
+    InvalidMnemonic(String),
 }
 
 #[cfg(with_getrandom)]
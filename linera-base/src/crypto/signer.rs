@@ -34,6 +34,18 @@ impl StdError for Box<dyn Error + '_> {
     }
 }
 
+/// The serialized content whose hash a client asks a [`Signer`] to sign.
+///
+/// Signers that enforce policies on what they sign, e.g. the chains or amounts involved,
+/// can decode the BCS bytes to inspect it.
+#[derive(Clone, Copy, Debug)]
+pub enum SignedContent<'a> {
+    /// The BCS bytes of the `ProposalContent` of a block proposal.
+    BlockProposal(&'a [u8]),
+    /// The BCS bytes of a `ProposedBlock` approved by an owner of a multi-owner chain.
+    BlockApproval(&'a [u8]),
+}
+
 /// A trait for signing keys.
 #[cfg_attr(not(web), trait_variant::make(Send))]
 pub trait Signer {
@@ -50,6 +62,16 @@ pub trait Signer {
         value: &CryptoHash,
     ) -> Result<AccountSignature, Self::Error>;
 
+    /// Creates a signature for the given `value` using the provided `owner`, where `value`
+    /// is the hash of `content`. Signers that don't inspect the content can sign `value`
+    /// as in [`Signer::sign`].
+    async fn sign_content(
+        &self,
+        owner: &AccountOwner,
+        value: &CryptoHash,
+        content: SignedContent<'_>,
+    ) -> Result<AccountSignature, Self::Error>;
+
     /// Returns whether the given `owner` is a known signer.
     async fn contains_key(&self, owner: &AccountOwner) -> Result<bool, Self::Error>;
 }
@@ -64,9 +86,9 @@ mod in_mem {
     use serde::{Deserialize, Serialize};
 
     #[cfg(with_getrandom)]
-    use crate::crypto::{AccountPublicKey, CryptoRng};
+    use crate::crypto::{AccountPublicKey, CryptoRng, Mnemonic, SignatureScheme};
     use crate::{
-        crypto::{AccountSecretKey, AccountSignature, CryptoHash, Signer},
+        crypto::{AccountSecretKey, AccountSignature, CryptoHash, SignedContent, Signer},
         identifiers::AccountOwner,
     };
 
@@ -101,11 +123,45 @@ mod in_mem {
             InMemorySigner(Arc::new(RwLock::new(InMemSignerInner::new())))
         }
 
-        /// Generates a new key pair from Signer's RNG. Use with care.
+        /// Creates a new [`InMemorySigner`] whose keys of the given `scheme` are derived
+        /// from `mnemonic`.
+        #[cfg(with_getrandom)]
+        pub fn from_mnemonic(mnemonic: Mnemonic, scheme: SignatureScheme) -> Self {
+            let mut inner = InMemSignerInner::new(None);
+            inner.derivation = Some(KeyDerivation {
+                mnemonic,
+                scheme,
+                next_index: 0,
+            });
+            InMemorySigner(Arc::new(RwLock::new(inner)))
+        }
+
+        /// Returns the mnemonic from which the keys are derived, if any.
+        #[cfg(with_getrandom)]
+        pub fn mnemonic(&self) -> Option<Mnemonic> {
+            let inner = self.0.read().unwrap();
+            inner
+                .derivation
+                .as_ref()
+                .map(|derivation| derivation.mnemonic.clone())
+        }
+
+        /// Generates a new key pair from Signer's RNG, or derives the next one from its
+        /// mnemonic if it has one. Use with care.
         #[cfg(with_getrandom)]
         pub fn generate_new(&mut self) -> AccountPublicKey {
             let mut inner = self.0.write().unwrap();
-            let secret = AccountSecretKey::generate_from(&mut inner.rng_state.prng);
+            let inner = &mut *inner;
+            let secret = match &mut inner.derivation {
+                Some(derivation) => {
+                    let secret = derivation
+                        .mnemonic
+                        .derive_key(derivation.scheme, derivation.next_index);
+                    derivation.next_index += 1;
+                    secret
+                }
+                None => AccountSecretKey::generate_from(&mut inner.rng_state.prng),
+            };
             if inner.rng_state.testing_seed.is_some() {
                 // Generate a new testing seed for the case when we need to store the PRNG state.
                 // It provides a "forward-secrecy" property for the testing seed.
@@ -131,6 +187,18 @@ mod in_mem {
         keys: BTreeMap<AccountOwner, AccountSecretKey>,
         #[cfg(with_getrandom)]
         rng_state: RngState,
+        #[cfg(with_getrandom)]
+        derivation: Option<KeyDerivation>,
+    }
+
+    /// The state of the derivation of keys from a mnemonic.
+    #[cfg(with_getrandom)]
+    #[derive(Debug, Serialize, Deserialize)]
+    struct KeyDerivation {
+        mnemonic: Mnemonic,
+        scheme: SignatureScheme,
+        /// The index of the next key to derive.
+        next_index: u32,
     }
 
     #[cfg(with_getrandom)]
@@ -160,6 +228,7 @@ mod in_mem {
             InMemSignerInner {
                 keys: BTreeMap::new(),
                 rng_state: RngState::new(prng_seed),
+                derivation: None,
             }
         }
 
@@ -202,6 +271,16 @@ mod in_mem {
             }
         }
 
+        /// Creates a signature for the given `value`, regardless of its content.
+        async fn sign_content(
+            &self,
+            owner: &AccountOwner,
+            value: &CryptoHash,
+            _content: SignedContent<'_>,
+        ) -> Result<AccountSignature, Error> {
+            self.sign(owner, value).await
+        }
+
         /// Returns whether the given `owner` is a known signer.
         async fn contains_key(&self, owner: &AccountOwner) -> Result<bool, Error> {
             Ok(self.0.read().unwrap().keys.contains_key(owner))
@@ -217,6 +296,8 @@ mod in_mem {
                 keys: BTreeMap::from_iter(input),
                 #[cfg(with_getrandom)]
                 rng_state: RngState::new(None),
+                #[cfg(with_getrandom)]
+                derivation: None,
             })))
         }
     }
@@ -261,6 +342,9 @@ mod in_mem {
                 keys: &'a Vec<(AccountOwner, Vec<u8>)>,
                 #[cfg(with_getrandom)]
                 prng_seed: Option<u64>,
+                #[cfg(with_getrandom)]
+                #[serde(skip_serializing_if = "Option::is_none")]
+                derivation: Option<&'a KeyDerivation>,
             }
 
             #[cfg(with_getrandom)]
@@ -270,6 +354,8 @@ mod in_mem {
                 keys: &self.keys(),
                 #[cfg(with_getrandom)]
                 prng_seed,
+                #[cfg(with_getrandom)]
+                derivation: self.derivation.as_ref(),
             };
 
             Inner::serialize(&inner, serializer)
@@ -286,6 +372,9 @@ mod in_mem {
                 keys: Vec<(AccountOwner, Vec<u8>)>,
                 #[cfg(with_getrandom)]
                 prng_seed: Option<u64>,
+                #[cfg(with_getrandom)]
+                #[serde(default)]
+                derivation: Option<KeyDerivation>,
             }
 
             let inner = Inner::deserialize(deserializer)?;
@@ -304,6 +393,8 @@ mod in_mem {
                 keys,
                 #[cfg(with_getrandom)]
                 rng_state: RngState::new(inner.prng_seed),
+                #[cfg(with_getrandom)]
+                derivation: inner.derivation,
             };
             Ok(signer)
         }
@@ -150,6 +150,57 @@ impl std::str::FromStr for Account {
     }
 }
 
+/// A native token other than the chain's main token. Only the issuing application can mint
+/// or burn it.
+#[derive(
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Copy,
+    Clone,
+    Serialize,
+    Deserialize,
+    WitLoad,
+    WitStore,
+    WitType,
+)]
+pub struct TokenId {
+    /// The application that is allowed to mint and burn this token.
+    pub issuer: ApplicationId,
+    /// The index distinguishing the tokens of the same issuer.
+    pub index: u32,
+}
+
+impl TokenId {
+    /// Creates a new [`TokenId`] for the given issuing application and index.
+    pub fn new(issuer: ApplicationId, index: u32) -> Self {
+        TokenId { issuer, index }
+    }
+}
+
+impl fmt::Display for TokenId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.issuer, self.index)
+    }
+}
+
+impl std::str::FromStr for TokenId {
+    type Err = anyhow::Error;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let (issuer, index) = string
+            .rsplit_once(':')
+            .context("Expecting a token ID formatted as `application-id:index`")?;
+        Ok(TokenId {
+            issuer: issuer.parse().context("Invalid ApplicationId")?,
+            index: index.parse().context("Invalid token index")?,
+        })
+    }
+}
+
 /// The unique identifier (UID) of a chain. This is currently computed as the hash value
 /// of a [`ChainDescription`].
 #[derive(
@@ -1118,6 +1169,7 @@ doc_scalar!(
     "A unique identifier for a user or an application."
 );
 doc_scalar!(Account, "An account");
+doc_scalar!(TokenId, "A native token issued by an application");
 doc_scalar!(
     BlobId,
     "A content-addressed blob ID i.e. the hash of the `BlobContent`"
@@ -1132,7 +1184,9 @@ mod tests {
     use super::{AccountOwner, BlobType};
     use crate::{
         data_types::{Amount, ChainDescription, ChainOrigin, Epoch, InitialChainConfig, Timestamp},
-        identifiers::{ApplicationId, CryptoHash, GenericApplicationId, StreamId, StreamName},
+        identifiers::{
+            ApplicationId, CryptoHash, GenericApplicationId, StreamId, StreamName, TokenId,
+        },
         ownership::ChainOwnership,
     };
 
@@ -1237,4 +1291,13 @@ mod tests {
         let stream_id2 = StreamId::from_str(&format!("{stream_id1}")).unwrap();
         assert_eq!(stream_id1, stream_id2);
     }
+
+    #[test]
+    fn token_id() {
+        let issuer = ApplicationId::new(CryptoHash::test_hash("test case"));
+        let token_id1 = TokenId::new(issuer, 7);
+        let token_id2 = TokenId::from_str(&format!("{token_id1}")).unwrap();
+        assert_eq!(token_id1, token_id2);
+        assert!(TokenId::from_str(&format!("{issuer}")).is_err());
+    }
 }
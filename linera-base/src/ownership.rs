@@ -11,7 +11,7 @@ use std::{
 
 use custom_debug_derive::Debug;
 use linera_witty::{WitLoad, WitStore, WitType};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use thiserror::Error;
 
 use crate::{
@@ -49,9 +49,7 @@ impl Default for TimeoutConfig {
 }
 
 /// Represents the owner(s) of a chain.
-#[derive(
-    PartialEq, Eq, Clone, Hash, Debug, Default, Serialize, Deserialize, WitLoad, WitStore, WitType,
-)]
+#[derive(PartialEq, Eq, Clone, Hash, Debug, Default, WitLoad, WitStore, WitType)]
 pub struct ChainOwnership {
     /// Super owners can propose fast blocks in the first round, and regular blocks in any round.
     #[debug(skip_if = BTreeSet::is_empty)]
@@ -67,6 +65,140 @@ pub struct ChainOwnership {
     pub open_multi_leader_rounds: bool,
     /// The timeout configuration: how long fast, multi-leader and single-leader rounds last.
     pub timeout_config: TimeoutConfig,
+    /// The total weight of the regular owners that must sign a block proposal, if any.
+    /// This is not part of the Wasm application interface.
+    #[debug(skip_if = Option::is_none)]
+    #[witty(skip)]
+    pub approval_threshold: Option<u64>,
+}
+
+/// How the multi-leader rounds of a [`ChainOwnership`] are open, and its approval
+/// threshold, in binary formats.
+///
+/// This takes the place of the `open_multi_leader_rounds` boolean that preceded approval
+/// thresholds. The first two variants are encoded like `false` and `true` in BCS, so the
+/// descriptions of the chains without a threshold, and thus their IDs, are unchanged.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MultiLeaderAccess {
+    /// The multi-leader rounds are limited to the chain owners, without threshold.
+    Restricted,
+    /// The multi-leader rounds are unrestricted, without threshold.
+    Open,
+    /// The multi-leader rounds are limited to the chain owners, with the given threshold.
+    RestrictedWithThreshold(u64),
+    /// The multi-leader rounds are unrestricted, with the given threshold.
+    OpenWithThreshold(u64),
+}
+
+impl MultiLeaderAccess {
+    fn new(open_multi_leader_rounds: bool, approval_threshold: Option<u64>) -> Self {
+        match (open_multi_leader_rounds, approval_threshold) {
+            (false, None) => MultiLeaderAccess::Restricted,
+            (true, None) => MultiLeaderAccess::Open,
+            (false, Some(threshold)) => MultiLeaderAccess::RestrictedWithThreshold(threshold),
+            (true, Some(threshold)) => MultiLeaderAccess::OpenWithThreshold(threshold),
+        }
+    }
+
+    fn open_multi_leader_rounds(self) -> bool {
+        matches!(
+            self,
+            MultiLeaderAccess::Open | MultiLeaderAccess::OpenWithThreshold(_)
+        )
+    }
+
+    fn approval_threshold(self) -> Option<u64> {
+        match self {
+            MultiLeaderAccess::Restricted | MultiLeaderAccess::Open => None,
+            MultiLeaderAccess::RestrictedWithThreshold(threshold)
+            | MultiLeaderAccess::OpenWithThreshold(threshold) => Some(threshold),
+        }
+    }
+}
+
+/// The fields of a [`ChainOwnership`] in human-readable formats.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "ChainOwnership")]
+struct ReadableChainOwnership {
+    super_owners: BTreeSet<AccountOwner>,
+    owners: BTreeMap<AccountOwner, u64>,
+    multi_leader_rounds: u32,
+    open_multi_leader_rounds: bool,
+    timeout_config: TimeoutConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    approval_threshold: Option<u64>,
+}
+
+/// The fields of a [`ChainOwnership`] in binary formats.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "ChainOwnership")]
+struct BinaryChainOwnership {
+    super_owners: BTreeSet<AccountOwner>,
+    owners: BTreeMap<AccountOwner, u64>,
+    multi_leader_rounds: u32,
+    multi_leader_access: MultiLeaderAccess,
+    timeout_config: TimeoutConfig,
+}
+
+impl Serialize for ChainOwnership {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let ownership = self.clone();
+        if serializer.is_human_readable() {
+            ReadableChainOwnership {
+                super_owners: ownership.super_owners,
+                owners: ownership.owners,
+                multi_leader_rounds: ownership.multi_leader_rounds,
+                open_multi_leader_rounds: ownership.open_multi_leader_rounds,
+                timeout_config: ownership.timeout_config,
+                approval_threshold: ownership.approval_threshold,
+            }
+            .serialize(serializer)
+        } else {
+            BinaryChainOwnership {
+                super_owners: ownership.super_owners,
+                owners: ownership.owners,
+                multi_leader_rounds: ownership.multi_leader_rounds,
+                multi_leader_access: MultiLeaderAccess::new(
+                    ownership.open_multi_leader_rounds,
+                    ownership.approval_threshold,
+                ),
+                timeout_config: ownership.timeout_config,
+            }
+            .serialize(serializer)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ChainOwnership {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            let ownership = ReadableChainOwnership::deserialize(deserializer)?;
+            Ok(ChainOwnership {
+                super_owners: ownership.super_owners,
+                owners: ownership.owners,
+                multi_leader_rounds: ownership.multi_leader_rounds,
+                open_multi_leader_rounds: ownership.open_multi_leader_rounds,
+                timeout_config: ownership.timeout_config,
+                approval_threshold: ownership.approval_threshold,
+            })
+        } else {
+            let ownership = BinaryChainOwnership::deserialize(deserializer)?;
+            Ok(ChainOwnership {
+                super_owners: ownership.super_owners,
+                owners: ownership.owners,
+                multi_leader_rounds: ownership.multi_leader_rounds,
+                open_multi_leader_rounds: ownership.multi_leader_access.open_multi_leader_rounds(),
+                timeout_config: ownership.timeout_config,
+                approval_threshold: ownership.multi_leader_access.approval_threshold(),
+            })
+        }
+    }
 }
 
 impl ChainOwnership {
@@ -78,6 +210,7 @@ impl ChainOwnership {
             multi_leader_rounds: 2,
             open_multi_leader_rounds: false,
             timeout_config: TimeoutConfig::default(),
+            approval_threshold: None,
         }
     }
 
@@ -89,6 +222,7 @@ impl ChainOwnership {
             multi_leader_rounds: 2,
             open_multi_leader_rounds: false,
             timeout_config: TimeoutConfig::default(),
+            approval_threshold: None,
         }
     }
 
@@ -104,6 +238,7 @@ impl ChainOwnership {
             multi_leader_rounds,
             open_multi_leader_rounds: false,
             timeout_config,
+            approval_threshold: None,
         }
     }
 
@@ -113,6 +248,34 @@ impl ChainOwnership {
         self
     }
 
+    /// Requires block proposals to be signed by regular owners with the given total weight.
+    pub fn with_approval_threshold(mut self, threshold: u64) -> Self {
+        self.approval_threshold = Some(threshold);
+        self
+    }
+
+    /// Returns the total weight of the given signers, counting each regular owner once.
+    /// Super owners and other signers have no weight.
+    pub fn approval_weight<'a>(&self, signers: impl IntoIterator<Item = &'a AccountOwner>) -> u64 {
+        let signers = signers.into_iter().collect::<BTreeSet<_>>();
+        signers
+            .into_iter()
+            .filter_map(|signer| self.owners.get(signer))
+            .fold(0, |total, weight| total.saturating_add(*weight))
+    }
+
+    /// Returns whether the given signers meet the approval threshold, if any.
+    pub fn is_approved_by<'a>(&self, signers: impl IntoIterator<Item = &'a AccountOwner>) -> bool {
+        self.approval_threshold
+            .is_none_or(|threshold| self.approval_weight(signers) >= threshold)
+    }
+
+    /// Returns `false` if there is an approval threshold that the regular owners cannot reach
+    /// together.
+    pub fn is_approval_threshold_reachable(&self) -> bool {
+        self.is_approved_by(self.owners.keys())
+    }
+
     /// Returns whether there are any owners or super owners or it is a public chain.
     pub fn is_active(&self) -> bool {
         !self.super_owners.is_empty()
@@ -227,6 +390,7 @@ mod tests {
             owners: BTreeMap::from_iter([(owner, 100)]),
             multi_leader_rounds: 10,
             open_multi_leader_rounds: false,
+            approval_threshold: None,
             timeout_config: TimeoutConfig {
                 fast_round_duration: Some(TimeDelta::from_secs(5)),
                 base_timeout: TimeDelta::from_secs(10),
@@ -257,6 +421,77 @@ mod tests {
             Some(TimeDelta::from_secs(18))
         );
     }
+
+    #[test]
+    fn test_ownership_approval_threshold() {
+        let owners = (0..3)
+            .map(|_| AccountOwner::from(Ed25519SecretKey::generate().public()))
+            .collect::<Vec<_>>();
+        let super_owner = AccountOwner::from(Secp256k1SecretKey::generate().public());
+        let mut ownership = ChainOwnership::multiple(
+            owners.iter().copied().zip([50, 30, 20]),
+            2,
+            TimeoutConfig::default(),
+        );
+        ownership.super_owners.insert(super_owner);
+        assert!(ownership.is_approved_by([]));
+
+        let ownership = ownership.with_approval_threshold(70);
+        assert!(ownership.is_approval_threshold_reachable());
+        assert!(!ownership.is_approved_by([&owners[0]]));
+        // Repeated signers and super owners add no weight.
+        assert!(!ownership.is_approved_by([&owners[0], &owners[0], &super_owner]));
+        assert!(ownership.is_approved_by([&owners[0], &owners[2]]));
+        assert_eq!(ownership.approval_weight(&owners), 100);
+
+        let ownership = ownership.with_approval_threshold(101);
+        assert!(!ownership.is_approval_threshold_reachable());
+    }
+
+    #[test]
+    fn test_ownership_serialization_without_threshold() {
+        // The layout of the ownership before approval thresholds.
+        #[derive(Serialize)]
+        struct LegacyChainOwnership {
+            super_owners: BTreeSet<AccountOwner>,
+            owners: BTreeMap<AccountOwner, u64>,
+            multi_leader_rounds: u32,
+            open_multi_leader_rounds: bool,
+            timeout_config: TimeoutConfig,
+        }
+
+        let owner = AccountOwner::from(Ed25519SecretKey::generate().public());
+        for open_multi_leader_rounds in [false, true] {
+            let mut ownership =
+                ChainOwnership::multiple([(owner, 100)], 2, TimeoutConfig::default());
+            ownership.open_multi_leader_rounds = open_multi_leader_rounds;
+            let legacy = LegacyChainOwnership {
+                super_owners: ownership.super_owners.clone(),
+                owners: ownership.owners.clone(),
+                multi_leader_rounds: ownership.multi_leader_rounds,
+                open_multi_leader_rounds,
+                timeout_config: ownership.timeout_config.clone(),
+            };
+            let bytes = bcs::to_bytes(&ownership).unwrap();
+            assert_eq!(bytes, bcs::to_bytes(&legacy).unwrap());
+            assert_eq!(
+                bcs::from_bytes::<ChainOwnership>(&bytes).unwrap(),
+                ownership
+            );
+
+            let ownership = ownership.with_approval_threshold(100);
+            let bytes = bcs::to_bytes(&ownership).unwrap();
+            assert_eq!(
+                bcs::from_bytes::<ChainOwnership>(&bytes).unwrap(),
+                ownership
+            );
+            let json = serde_json::to_string(&ownership).unwrap();
+            assert_eq!(
+                serde_json::from_str::<ChainOwnership>(&json).unwrap(),
+                ownership
+            );
+        }
+    }
 }
 
 doc_scalar!(ChainOwnership, "Represents the owner(s) of a chain");
@@ -153,6 +153,8 @@ fn chain_ownership_test_case() -> ChainOwnership {
             timeout_increment: TimeDelta::from_secs(3_600),
             fallback_duration: TimeDelta::from_secs(10_000),
         },
+        // The threshold is not part of the WIT representation.
+        approval_threshold: None,
     }
 }
 
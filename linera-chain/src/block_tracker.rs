@@ -9,12 +9,13 @@ use linera_base::prometheus_util::MeasureLatency;
 use linera_base::{
     data_types::{Amount, Blob, BlockHeight, Event, OracleResponse, Timestamp},
     ensure,
-    identifiers::{AccountOwner, BlobId, ChainId, StreamId},
+    identifiers::{AccountOwner, ApplicationId, BlobId, ChainId, StreamId},
 };
 use linera_execution::{
-    execution_state_actor::ExecutionStateActor, ExecutionRuntimeContext, ExecutionStateView,
-    MessageContext, OperationContext, OutgoingMessage, ResourceController, ResourceTracker,
-    SystemExecutionStateView, TransactionOutcome, TransactionTracker,
+    execution_state_actor::ExecutionStateActor, ExecutionError, ExecutionRuntimeContext,
+    ExecutionStateView, ExecutionTrace, MessageContext, Operation, OperationContext,
+    OutgoingMessage, ResourceController, ResourceTracker, SystemExecutionStateView,
+    TransactionOutcome, TransactionTracker,
 };
 use linera_views::context::Context;
 use tracing::instrument;
@@ -53,8 +54,13 @@ pub struct BlockExecutionTracker<'resources, 'blobs> {
     messages: Vec<Vec<OutgoingMessage>>,
     #[debug(skip_if = Vec::is_empty)]
     operation_results: Vec<OperationResult>,
+    #[debug(skip_if = Vec::is_empty)]
+    execution_traces: Vec<Vec<ExecutionTrace>>,
     // Index of the currently executed transaction in a block.
     transaction_index: u32,
+    // Fees paid in this block by applications sponsoring operations.
+    #[debug(skip_if = BTreeMap::is_empty)]
+    sponsored_fees: BTreeMap<ApplicationId, Amount>,
 
     // Blobs published in the block.
     published_blobs: BTreeMap<BlobId, &'blobs Blob>,
@@ -94,7 +100,9 @@ impl<'resources, 'blobs> BlockExecutionTracker<'resources, 'blobs> {
             blobs: Vec::new(),
             messages: Vec::new(),
             operation_results: Vec::new(),
+            execution_traces: Vec::new(),
             transaction_index: 0,
+            sponsored_fees: BTreeMap::new(),
             published_blobs,
             expected_outcomes_count: proposal.transactions.len(),
         })
@@ -117,6 +125,7 @@ impl<'resources, 'blobs> BlockExecutionTracker<'resources, 'blobs> {
     {
         let chain_execution_context = self.chain_execution_context(transaction);
         let mut txn_tracker = self.new_transaction_tracker()?;
+        let mut sponsorship = None;
 
         match transaction {
             Transaction::ReceiveMessages(incoming_bundle) => {
@@ -140,6 +149,10 @@ impl<'resources, 'blobs> BlockExecutionTracker<'resources, 'blobs> {
                     .await?
                     .track_block_size_of(&operation)
                     .with_execution_context(chain_execution_context)?;
+                sponsorship = self
+                    .start_sponsorship(operation, &mut chain.system)
+                    .await
+                    .with_execution_context(chain_execution_context)?;
                 #[cfg(with_metrics)]
                 let _operation_latency = metrics::OPERATION_EXECUTION_LATENCY.measure_latency();
                 let context = OperationContext {
@@ -167,6 +180,70 @@ impl<'resources, 'blobs> BlockExecutionTracker<'resources, 'blobs> {
             .with_execution_context(chain_execution_context)?;
         self.process_txn_outcome(txn_outcome, &mut chain.system, chain_execution_context)
             .await?;
+        if let Some((application_id, owner, budget)) = sponsorship {
+            self.finish_sponsorship(application_id, owner, budget, &mut chain.system)
+                .await
+                .with_execution_context(chain_execution_context)?;
+        }
+        Ok(())
+    }
+
+    /// If the operation targets an application that sponsors fees, debits the budget the
+    /// application is willing to pay for it and makes it the first source of funding.
+    async fn start_sponsorship<C>(
+        &mut self,
+        operation: &Operation,
+        view: &mut SystemExecutionStateView<C>,
+    ) -> Result<Option<(ApplicationId, AccountOwner, Amount)>, ExecutionError>
+    where
+        C: Context + Clone + Send + Sync + 'static,
+        C::Extra: ExecutionRuntimeContext,
+    {
+        let (Operation::User { application_id, .. }, Some(owner)) =
+            (operation, self.authenticated_signer)
+        else {
+            return Ok(None);
+        };
+        let spent_in_block = self
+            .sponsored_fees
+            .get(application_id)
+            .copied()
+            .unwrap_or_default();
+        let budget = view
+            .take_sponsor_budget(*application_id, owner, spent_in_block)
+            .await?;
+        if budget.is_zero() {
+            return Ok(None);
+        }
+        self.resource_controller.sponsor_budget = Some(budget);
+        Ok(Some((*application_id, owner, budget)))
+    }
+
+    /// Credits the unspent sponsor budget back to the application and records the fees it
+    /// paid.
+    async fn finish_sponsorship<C>(
+        &mut self,
+        application_id: ApplicationId,
+        owner: AccountOwner,
+        budget: Amount,
+        view: &mut SystemExecutionStateView<C>,
+    ) -> Result<(), ExecutionError>
+    where
+        C: Context + Clone + Send + Sync + 'static,
+        C::Extra: ExecutionRuntimeContext,
+    {
+        let remaining = self
+            .resource_controller
+            .sponsor_budget
+            .take()
+            .unwrap_or_default();
+        let spent = view
+            .return_sponsor_budget(application_id, owner, budget, remaining)
+            .await?;
+        self.sponsored_fees
+            .entry(application_id)
+            .or_default()
+            .try_add_assign(spent)?;
         Ok(())
     }
 
@@ -331,6 +408,7 @@ impl<'resources, 'blobs> BlockExecutionTracker<'resources, 'blobs> {
         self.events.push(txn_outcome.events);
         self.blobs.push(txn_outcome.blobs);
         self.messages.push(txn_outcome.outgoing_messages);
+        self.execution_traces.push(txn_outcome.execution_traces);
         if matches!(context, ChainExecutionContext::Operation(_)) {
             self.operation_results
                 .push(OperationResult(txn_outcome.operation_result));
@@ -397,6 +475,7 @@ impl<'resources, 'blobs> BlockExecutionTracker<'resources, 'blobs> {
             self.events,
             self.blobs,
             self.operation_results,
+            self.execution_traces,
         )
     }
 }
@@ -407,4 +486,5 @@ pub(crate) type FinalizeExecutionResult = (
     Vec<Vec<Event>>,
     Vec<Vec<Blob>>,
     Vec<OperationResult>,
+    Vec<Vec<ExecutionTrace>>,
 );
@@ -6,18 +6,27 @@ use custom_debug_derive::Debug;
 use linera_base::{
     crypto::{CryptoHash, ValidatorPublicKey, ValidatorSignature},
     data_types::Round,
+    ensure,
 };
 use linera_execution::committee::Committee;
 
 use super::CertificateValue;
-use crate::{data_types::LiteValue, ChainError};
+use crate::{
+    data_types::{AggregateSignature, LiteValue},
+    ChainError,
+};
 
 /// Generic type representing a certificate for `value` of type `T`.
+///
+/// A certificate is signed either by a list of individual validator signatures, or by a
+/// single aggregate signature.
 #[derive(Debug)]
 pub struct GenericCertificate<T: CertificateValue> {
     value: T,
     pub round: Round,
     signatures: Vec<(ValidatorPublicKey, ValidatorSignature)>,
+    #[debug(skip_if = Option::is_none)]
+    aggregate_signature: Option<AggregateSignature>,
 }
 
 impl<T: CertificateValue> GenericCertificate<T> {
@@ -32,6 +41,30 @@ impl<T: CertificateValue> GenericCertificate<T> {
             value,
             round,
             signatures,
+            aggregate_signature: None,
+        }
+    }
+
+    /// Creates a certificate signed by a single aggregate signature.
+    pub fn new_aggregated(value: T, round: Round, aggregate_signature: AggregateSignature) -> Self {
+        Self {
+            value,
+            round,
+            signatures: Vec::new(),
+            aggregate_signature: Some(aggregate_signature),
+        }
+    }
+
+    /// Creates a certificate from individual signatures and an optional aggregate signature.
+    pub fn from_parts(
+        value: T,
+        round: Round,
+        signatures: Vec<(ValidatorPublicKey, ValidatorSignature)>,
+        aggregate_signature: Option<AggregateSignature>,
+    ) -> Self {
+        Self {
+            aggregate_signature,
+            ..Self::new(value, round, signatures)
         }
     }
 
@@ -68,6 +101,11 @@ impl<T: CertificateValue> GenericCertificate<T> {
         &self.signatures
     }
 
+    /// Returns the aggregate signature, if the certificate is not signed individually.
+    pub fn aggregate_signature(&self) -> Option<&AggregateSignature> {
+        self.aggregate_signature.as_ref()
+    }
+
     #[cfg(with_testing)]
     pub fn signatures_mut(&mut self) -> &mut Vec<(ValidatorPublicKey, ValidatorSignature)> {
         &mut self.signatures
@@ -87,7 +125,10 @@ impl<T: CertificateValue> GenericCertificate<T> {
         &self.signatures
     }
 
-    /// Returns whether the validator is among the signatories of this certificate.
+    /// Returns whether the validator is among the individual signatories of this certificate.
+    ///
+    /// The signers of an aggregate signature can only be determined with the committee; see
+    /// [`AggregateSignature::is_signed_by`].
     pub fn is_signed_by(&self, validator_name: &ValidatorPublicKey) -> bool {
         self.signatures
             .binary_search_by(|(name, _)| name.cmp(validator_name))
@@ -99,13 +140,28 @@ impl<T: CertificateValue> GenericCertificate<T> {
     where
         T: CertificateValue,
     {
-        crate::data_types::check_signatures(
-            self.hash(),
-            T::KIND,
-            self.round,
-            &self.signatures,
-            committee,
-        )?;
+        match &self.aggregate_signature {
+            None => crate::data_types::check_signatures(
+                self.hash(),
+                T::KIND,
+                self.round,
+                &self.signatures,
+                committee,
+            )?,
+            Some(aggregate) => {
+                ensure!(
+                    self.signatures.is_empty(),
+                    ChainError::CertificateWithMixedSignatures
+                );
+                crate::data_types::check_aggregate_signature(
+                    self.hash(),
+                    T::KIND,
+                    self.round,
+                    aggregate,
+                    committee,
+                )?
+            }
+        }
         Ok(())
     }
 
@@ -117,6 +173,7 @@ impl<T: CertificateValue> GenericCertificate<T> {
             value: LiteValue::new(&self.value),
             round: self.round,
             signatures: std::borrow::Cow::Borrowed(&self.signatures),
+            aggregate_signature: self.aggregate_signature.clone(),
         }
     }
 }
@@ -127,6 +184,7 @@ impl<T: CertificateValue> Clone for GenericCertificate<T> {
             value: self.value.clone(),
             round: self.round,
             signatures: self.signatures.clone(),
+            aggregate_signature: self.aggregate_signature.clone(),
         }
     }
 }
@@ -139,5 +197,6 @@ impl<T: CertificateValue + Eq + PartialEq> PartialEq for GenericCertificate<T> {
         self.hash() == other.hash()
             && self.round == other.round
             && self.signatures == other.signatures
+            && self.aggregate_signature == other.aggregate_signature
     }
 }
@@ -7,13 +7,16 @@ use std::borrow::Cow;
 use linera_base::{
     crypto::{ValidatorPublicKey, ValidatorSignature},
     data_types::Round,
+    ensure,
 };
 use linera_execution::committee::Committee;
 use serde::{Deserialize, Serialize};
 
 use super::{CertificateValue, GenericCertificate};
 use crate::{
-    data_types::{check_signatures, LiteValue, LiteVote},
+    data_types::{
+        check_aggregate_signature, check_signatures, AggregateSignature, LiteValue, LiteVote,
+    },
     ChainError,
 };
 
@@ -27,6 +30,8 @@ pub struct LiteCertificate<'a> {
     pub round: Round,
     /// Signatures on the value.
     pub signatures: Cow<'a, [(ValidatorPublicKey, ValidatorSignature)]>,
+    /// The aggregate signature on the value, if it is not signed individually.
+    pub aggregate_signature: Option<AggregateSignature>,
 }
 
 impl LiteCertificate<'_> {
@@ -42,6 +47,21 @@ impl LiteCertificate<'_> {
             value,
             round,
             signatures,
+            aggregate_signature: None,
+        }
+    }
+
+    /// Creates a [`LiteCertificate`] signed by a single aggregate signature.
+    pub fn new_aggregated(
+        value: LiteValue,
+        round: Round,
+        aggregate_signature: AggregateSignature,
+    ) -> Self {
+        Self {
+            value,
+            round,
+            signatures: Cow::Owned(Vec::new()),
+            aggregate_signature: Some(aggregate_signature),
         }
     }
 
@@ -57,6 +77,7 @@ impl LiteCertificate<'_> {
                 value,
                 round,
                 signature,
+                aggregate_signature: _,
             },
         ) = votes.next()?;
         let mut signatures = vec![(public_key, signature)];
@@ -69,15 +90,70 @@ impl LiteCertificate<'_> {
         Some(LiteCertificate::new(value, round, signatures))
     }
 
+    /// Creates a [`LiteCertificate`] with a single aggregate signature from a list of votes
+    /// with their validator public keys, and verifies it. Only the votes with an aggregate
+    /// signature, from validators with an aggregate key, are aggregated, and no more of
+    /// them than needed for a quorum. Returns `None` if the votes are empty or don't have
+    /// matching values and rounds, if these votes are not a quorum, or if the aggregate
+    /// signature is invalid.
+    pub fn try_aggregate_votes(
+        votes: &[(ValidatorPublicKey, LiteVote)],
+        committee: &Committee,
+    ) -> Option<Self> {
+        let (_, first) = votes.first()?;
+        if votes.iter().any(|(_, vote)| {
+            vote.value.value_hash != first.value.value_hash || vote.round != first.round
+        }) {
+            return None;
+        }
+        let mut weight = 0;
+        let mut quorum = Vec::new();
+        for (public_key, vote) in votes {
+            if weight >= committee.quorum_threshold() {
+                break;
+            }
+            let Some(state) = committee.validators().get(public_key) else {
+                continue;
+            };
+            if vote.aggregate_signature.is_some() && state.aggregate_public_key.is_some() {
+                weight += state.votes;
+                quorum.push((public_key, vote));
+            }
+        }
+        if weight < committee.quorum_threshold() {
+            return None;
+        }
+        let aggregate_signature = AggregateSignature::from_votes(quorum, committee)?;
+        let certificate =
+            LiteCertificate::new_aggregated(first.value.clone(), first.round, aggregate_signature);
+        certificate.check(committee).ok()?;
+        Some(certificate)
+    }
+
     /// Verifies the certificate.
     pub fn check(&self, committee: &Committee) -> Result<&LiteValue, ChainError> {
-        check_signatures(
-            self.value.value_hash,
-            self.value.kind,
-            self.round,
-            &self.signatures,
-            committee,
-        )?;
+        match &self.aggregate_signature {
+            None => check_signatures(
+                self.value.value_hash,
+                self.value.kind,
+                self.round,
+                &self.signatures,
+                committee,
+            )?,
+            Some(aggregate) => {
+                ensure!(
+                    self.signatures.is_empty(),
+                    ChainError::CertificateWithMixedSignatures
+                );
+                check_aggregate_signature(
+                    self.value.value_hash,
+                    self.value.kind,
+                    self.round,
+                    aggregate,
+                    committee,
+                )?
+            }
+        }
         Ok(&self.value)
     }
 
@@ -96,11 +172,18 @@ impl LiteCertificate<'_> {
         {
             return None;
         }
-        Some(GenericCertificate::new(
-            value,
-            self.round,
-            self.signatures.into_owned(),
-        ))
+        match self.aggregate_signature {
+            None => Some(GenericCertificate::new(
+                value,
+                self.round,
+                self.signatures.into_owned(),
+            )),
+            Some(aggregate_signature) => Some(GenericCertificate::new_aggregated(
+                value,
+                self.round,
+                aggregate_signature,
+            )),
+        }
     }
 
     /// Returns a [`LiteCertificate`] that owns the list of signatures.
@@ -109,6 +192,7 @@ impl LiteCertificate<'_> {
             value: self.value.clone(),
             round: self.round,
             signatures: Cow::Owned(self.signatures.clone().into_owned()),
+            aggregate_signature: self.aggregate_signature.clone(),
         }
     }
 }
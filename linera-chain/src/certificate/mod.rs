@@ -19,7 +19,10 @@ use linera_base::{
 pub use lite::LiteCertificate;
 use serde::{Deserialize, Serialize};
 
-use crate::types::{ConfirmedBlock, Timeout, ValidatedBlock};
+use crate::{
+    data_types::AggregateSignature,
+    types::{ConfirmedBlock, Timeout, ValidatedBlock},
+};
 
 /// Certificate for a [`ValidatedBlock`] instance.
 /// A validated block certificate means the block is valid (but not necessarily finalized yet).
@@ -82,6 +85,14 @@ impl Certificate {
             Certificate::Timeout(cert) => cert.signatures(),
         }
     }
+
+    pub fn aggregate_signature(&self) -> Option<&AggregateSignature> {
+        match self {
+            Certificate::Validated(cert) => cert.aggregate_signature(),
+            Certificate::Confirmed(cert) => cert.aggregate_signature(),
+            Certificate::Timeout(cert) => cert.aggregate_signature(),
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, Hash, Eq, PartialEq)]
@@ -12,7 +12,10 @@ use serde::{
 };
 
 use super::{generic::GenericCertificate, Certificate};
-use crate::block::{ConversionError, Timeout};
+use crate::{
+    block::{ConversionError, Timeout},
+    data_types::AggregateSignature,
+};
 
 impl TryFrom<Certificate> for GenericCertificate<Timeout> {
     type Error = ConversionError;
@@ -37,6 +40,7 @@ impl Serialize for GenericCertificate<Timeout> {
         state.serialize_field("value", self.inner())?;
         state.serialize_field("round", &self.round)?;
         state.serialize_field("signatures", self.signatures())?;
+        state.serialize_field("aggregate_signature", &self.aggregate_signature())?;
         state.end()
     }
 }
@@ -52,12 +56,18 @@ impl<'de> Deserialize<'de> for GenericCertificate<Timeout> {
             value: Timeout,
             round: Round,
             signatures: Vec<(ValidatorPublicKey, ValidatorSignature)>,
+            aggregate_signature: Option<AggregateSignature>,
         }
         let inner = Inner::deserialize(deserializer)?;
         if !crate::data_types::is_strictly_ordered(&inner.signatures) {
             Err(serde::de::Error::custom("Vector is not strictly sorted"))
         } else {
-            Ok(Self::new(inner.value, inner.round, inner.signatures))
+            Ok(Self::from_parts(
+                inner.value,
+                inner.round,
+                inner.signatures,
+                inner.aggregate_signature,
+            ))
         }
     }
 }
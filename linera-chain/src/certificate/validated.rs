@@ -12,7 +12,10 @@ use serde::{
 };
 
 use super::{generic::GenericCertificate, Certificate};
-use crate::block::{Block, ConversionError, ValidatedBlock};
+use crate::{
+    block::{Block, ConversionError, ValidatedBlock},
+    data_types::AggregateSignature,
+};
 
 impl GenericCertificate<ValidatedBlock> {
     #[cfg(with_testing)]
@@ -45,10 +48,11 @@ impl From<GenericCertificate<ValidatedBlock>> for Certificate {
 
 impl Serialize for GenericCertificate<ValidatedBlock> {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        let mut state = serializer.serialize_struct("ValidatedBlockCertificate", 3)?;
+        let mut state = serializer.serialize_struct("ValidatedBlockCertificate", 4)?;
         state.serialize_field("value", self.inner())?;
         state.serialize_field("round", &self.round)?;
         state.serialize_field("signatures", self.signatures())?;
+        state.serialize_field("aggregate_signature", &self.aggregate_signature())?;
         state.end()
     }
 }
@@ -64,6 +68,7 @@ impl<'de> Deserialize<'de> for GenericCertificate<ValidatedBlock> {
             value: ValidatedBlock,
             round: Round,
             signatures: Vec<(ValidatorPublicKey, ValidatorSignature)>,
+            aggregate_signature: Option<AggregateSignature>,
         }
         let inner = Inner::deserialize(deserializer)?;
         if !crate::data_types::is_strictly_ordered(&inner.signatures) {
@@ -71,7 +76,12 @@ impl<'de> Deserialize<'de> for GenericCertificate<ValidatedBlock> {
                 "Signatures are not strictly ordered",
             ))
         } else {
-            Ok(Self::new(inner.value, inner.round, inner.signatures))
+            Ok(Self::from_parts(
+                inner.value,
+                inner.round,
+                inner.signatures,
+                inner.aggregate_signature,
+            ))
         }
     }
 }
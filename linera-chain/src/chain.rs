@@ -18,9 +18,9 @@ use linera_base::{
     ownership::ChainOwnership,
 };
 use linera_execution::{
-    committee::Committee, ExecutionRuntimeContext, ExecutionStateView, Message, Operation,
-    OutgoingMessage, Query, QueryContext, QueryOutcome, ResourceController, ResourceTracker,
-    ServiceRuntimeEndpoint, TransactionTracker,
+    committee::Committee, ExecutionRuntimeContext, ExecutionStateView, ExecutionTrace,
+    FeeMultiplier, Message, Operation, OutgoingMessage, Query, QueryContext, QueryOutcome,
+    ResourceController, ResourceTracker, ServiceRuntimeEndpoint, TransactionTracker,
 };
 use linera_views::{
     bucket_queue_view::BucketQueueView,
@@ -36,7 +36,7 @@ use serde::{Deserialize, Serialize};
 use tracing::instrument;
 
 use crate::{
-    block::{Block, ConfirmedBlock},
+    block::{Block, BlockHeader, ConfirmedBlock},
     block_tracker::BlockExecutionTracker,
     data_types::{
         BlockExecutionOutcome, ChainAndHeight, IncomingBundle, MessageBundle, ProposedBlock,
@@ -228,6 +228,9 @@ impl BundleInInbox {
 // of 100 seems reasonable for the storing of the data.
 const TIMESTAMPBUNDLE_BUCKET_SIZE: usize = 100;
 
+/// The number of most recent block heights for which execution traces are kept.
+pub const EXECUTION_TRACE_RETENTION: u64 = 1_000;
+
 /// A view accessing the state of a chain.
 #[cfg_attr(
     with_graphql,
@@ -284,6 +287,10 @@ where
 
     /// Blocks that have been verified but not executed yet, and that may not be contiguous.
     pub preprocessed_blocks: MapView<C, BlockHeight, CryptoHash>,
+    /// The execution traces of each transaction of the last [`EXECUTION_TRACE_RETENTION`]
+    /// executed blocks, if they are being recorded.
+    #[cfg_attr(with_graphql, graphql(skip))]
+    pub execution_traces: MapView<C, BlockHeight, Vec<Vec<ExecutionTrace>>>,
 }
 
 /// Block-chaining state.
@@ -306,15 +313,29 @@ impl ChainTipState {
     /// Checks that the proposed block is suitable, i.e. at the expected height and with the
     /// expected parent.
     pub fn verify_block_chaining(&self, new_block: &ProposedBlock) -> Result<(), ChainError> {
+        self.verify_chaining(new_block.height, new_block.previous_block_hash)
+    }
+
+    /// Checks that the block with the given header is at the expected height and with the
+    /// expected parent.
+    pub fn verify_header_chaining(&self, header: &BlockHeader) -> Result<(), ChainError> {
+        self.verify_chaining(header.height, header.previous_block_hash)
+    }
+
+    fn verify_chaining(
+        &self,
+        height: BlockHeight,
+        previous_block_hash: Option<CryptoHash>,
+    ) -> Result<(), ChainError> {
         ensure!(
-            new_block.height == self.next_block_height,
+            height == self.next_block_height,
             ChainError::UnexpectedBlockHeight {
                 expected_block_height: self.next_block_height,
-                found_block_height: new_block.height
+                found_block_height: height
             }
         );
         ensure!(
-            new_block.previous_block_hash == self.block_hash,
+            previous_block_hash == self.block_hash,
             ChainError::UnexpectedPreviousBlockHash
         );
         Ok(())
@@ -774,7 +795,14 @@ where
         round: Option<u32>,
         published_blobs: &[Blob],
         replaying_oracle_responses: Option<Vec<Vec<OracleResponse>>>,
-    ) -> Result<BlockExecutionOutcome, ChainError> {
+    ) -> Result<
+        (
+            BlockExecutionOutcome,
+            ResourceTracker,
+            Vec<Vec<ExecutionTrace>>,
+        ),
+        ChainError,
+    > {
         #[cfg(with_metrics)]
         let _execution_latency = metrics::BLOCK_EXECUTION_LATENCY.measure_latency();
         chain.system.timestamp.set(block.timestamp);
@@ -786,6 +814,13 @@ where
             .1
             .policy()
             .clone();
+        let dynamic_fees = policy.dynamic_fees;
+        let fee_multiplier = *chain.system.fee_multiplier.get();
+        let policy = if dynamic_fees.is_some() {
+            policy.with_fee_multiplier(fee_multiplier)
+        } else {
+            policy
+        };
 
         let mut resource_controller = ResourceController::new(
             Arc::new(policy),
@@ -821,6 +856,16 @@ where
                 .await?;
         }
 
+        if let Some(dynamic_fees) = dynamic_fees {
+            let tracker = &block_execution_tracker.resource_controller_mut().tracker;
+            let next_multiplier = dynamic_fees.next_fee_multiplier(fee_multiplier, tracker);
+            chain.system.fee_multiplier.set(next_multiplier);
+        } else if fee_multiplier != FeeMultiplier::ONE {
+            // Dynamic fees were turned off: don't let a stale multiplier apply if they are
+            // turned on again.
+            chain.system.fee_multiplier.set(FeeMultiplier::ONE);
+        }
+
         let recipients = block_execution_tracker.recipients();
         let heights = previous_message_blocks_view.multi_get(&recipients).await?;
         let mut recipient_heights = Vec::new();
@@ -867,10 +912,10 @@ where
             chain.crypto_hash_mut().await?
         };
 
-        let (messages, oracle_responses, events, blobs, operation_results) =
+        let (messages, oracle_responses, events, blobs, operation_results, execution_traces) =
             block_execution_tracker.finalize();
 
-        Ok(BlockExecutionOutcome {
+        let outcome = BlockExecutionOutcome {
             messages,
             previous_message_blocks,
             previous_event_blocks,
@@ -879,7 +924,8 @@ where
             events,
             blobs,
             operation_results,
-        })
+        };
+        Ok((outcome, resource_controller.tracker, execution_traces))
     }
 
     /// Executes a block: first the incoming messages, then the main operation.
@@ -896,6 +942,30 @@ where
         published_blobs: &[Blob],
         replaying_oracle_responses: Option<Vec<Vec<OracleResponse>>>,
     ) -> Result<BlockExecutionOutcome, ChainError> {
+        let (outcome, _) = self
+            .execute_block_and_track_resources(
+                block,
+                local_time,
+                round,
+                published_blobs,
+                replaying_oracle_responses,
+            )
+            .await?;
+        Ok(outcome)
+    }
+
+    /// Executes a block like [`ChainStateView::execute_block`], and also returns the
+    /// resources that the block used, including the fees that were charged.
+    ///
+    /// If execution traces are being recorded, they are stored in `execution_traces`.
+    pub async fn execute_block_and_track_resources(
+        &mut self,
+        block: &ProposedBlock,
+        local_time: Timestamp,
+        round: Option<u32>,
+        published_blobs: &[Blob],
+        replaying_oracle_responses: Option<Vec<Vec<OracleResponse>>>,
+    ) -> Result<(BlockExecutionOutcome, ResourceTracker), ChainError> {
         assert_eq!(
             block.chain_id,
             self.execution_state.context().extra().chain_id()
@@ -931,7 +1001,7 @@ where
             block,
         )?;
 
-        Self::execute_block_inner(
+        let (outcome, resources, execution_traces) = Self::execute_block_inner(
             &mut self.execution_state,
             &self.confirmed_log,
             &self.previous_message_blocks,
@@ -942,7 +1012,24 @@ where
             published_blobs,
             replaying_oracle_responses,
         )
-        .await
+        .await?;
+        if self.records_execution_traces() {
+            self.execution_traces
+                .insert(&block.height, execution_traces)?;
+            if let Some(height) = block.height.0.checked_sub(EXECUTION_TRACE_RETENTION) {
+                self.execution_traces.remove(&BlockHeight(height))?;
+            }
+        }
+        Ok((outcome, resources))
+    }
+
+    /// Returns whether the execution traces of the blocks are recorded.
+    pub fn records_execution_traces(&self) -> bool {
+        self.execution_state
+            .context()
+            .extra()
+            .execution_runtime_config()
+            .record_execution_traces
     }
 
     /// Applies an execution outcome to the chain, updating the outboxes, state hash and chain
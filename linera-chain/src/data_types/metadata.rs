@@ -6,12 +6,14 @@
 use async_graphql::SimpleObject;
 use linera_base::{
     crypto::CryptoHash,
-    data_types::{Amount, ApplicationPermissions},
+    data_types::{Amount, ApplicationPermissions, Timestamp},
     hex,
-    identifiers::{Account, AccountOwner, ApplicationId, ChainId},
+    identifiers::{Account, AccountOwner, ApplicationId, ChainId, TokenId},
     ownership::{ChainOwnership, TimeoutConfig},
 };
-use linera_execution::{system::AdminOperation, Message, SystemMessage, SystemOperation};
+use linera_execution::{
+    system::AdminOperation, Message, ScheduledOperationKey, SystemMessage, SystemOperation,
+};
 use serde::{Deserialize, Serialize};
 
 /// Timeout configuration metadata for GraphQL.
@@ -106,6 +108,14 @@ pub struct SystemOperationMetadata {
     pub epoch: Option<i32>,
     /// `UpdateStreams` operation details
     pub update_streams: Option<Vec<UpdateStreamMetadata>>,
+    /// Scheduled operation details (`ExecuteScheduledOperation`, `DiscardScheduledOperation`)
+    pub scheduled_operation: Option<ScheduledOperationMetadata>,
+    /// Allowance operation details (`Approve`, `Revoke`)
+    pub allowance: Option<AllowanceOperationMetadata>,
+    /// Upgrade application operation details
+    pub upgrade_application: Option<UpgradeApplicationOperationMetadata>,
+    /// Delete application operation details
+    pub delete_application: Option<DeleteApplicationOperationMetadata>,
 }
 
 impl SystemOperationMetadata {
@@ -125,6 +135,10 @@ impl SystemOperationMetadata {
             publish_module: None,
             epoch: None,
             update_streams: None,
+            scheduled_operation: None,
+            allowance: None,
+            upgrade_application: None,
+            delete_application: None,
         }
     }
 }
@@ -135,6 +149,7 @@ pub struct TransferOperationMetadata {
     pub owner: AccountOwner,
     pub recipient: Account,
     pub amount: Amount,
+    pub token_id: Option<TokenId>,
 }
 
 /// Claim operation metadata.
@@ -144,6 +159,7 @@ pub struct ClaimOperationMetadata {
     pub target_id: ChainId,
     pub recipient: Account,
     pub amount: Amount,
+    pub token_id: Option<TokenId>,
 }
 
 /// Open chain operation metadata.
@@ -162,6 +178,7 @@ pub struct ChangeOwnershipOperationMetadata {
     pub multi_leader_rounds: i32,
     pub open_multi_leader_rounds: bool,
     pub timeout_config: TimeoutConfigMetadata,
+    pub approval_threshold: Option<String>, // Using String to represent u64 safely in GraphQL
 }
 
 /// Owner with weight metadata.
@@ -192,6 +209,7 @@ pub struct CreateApplicationOperationMetadata {
     pub parameters_hex: String,
     pub instantiation_argument_hex: String,
     pub required_application_ids: Vec<ApplicationId>,
+    pub upgrade_authority: Option<AccountOwner>,
 }
 
 /// Publish data blob operation metadata.
@@ -220,6 +238,46 @@ pub struct UpdateStreamMetadata {
     pub next_index: i32,
 }
 
+/// Scheduled operation metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, SimpleObject)]
+pub struct ScheduledOperationMetadata {
+    pub timestamp: Timestamp,
+    pub index: String,
+}
+
+impl From<&ScheduledOperationKey> for ScheduledOperationMetadata {
+    fn from(key: &ScheduledOperationKey) -> Self {
+        ScheduledOperationMetadata {
+            timestamp: key.timestamp,
+            index: key.index.to_string(),
+        }
+    }
+}
+
+/// Approve or revoke operation metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, SimpleObject)]
+pub struct AllowanceOperationMetadata {
+    pub owner: AccountOwner,
+    pub spender: AccountOwner,
+    pub amount: Option<Amount>,
+    pub expiry: Option<Timestamp>,
+}
+
+/// Upgrade application operation metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, SimpleObject)]
+pub struct UpgradeApplicationOperationMetadata {
+    pub application_id: ApplicationId,
+    pub module_id: String,
+    pub migration_argument_hex: String,
+}
+
+/// Delete application operation metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, SimpleObject)]
+pub struct DeleteApplicationOperationMetadata {
+    pub application_id: ApplicationId,
+    pub refund_recipient: AccountOwner,
+}
+
 /// Structured representation of a system message for GraphQL.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, SimpleObject)]
 pub struct SystemMessageMetadata {
@@ -237,6 +295,7 @@ pub struct CreditMessageMetadata {
     pub target: AccountOwner,
     pub amount: Amount,
     pub source: AccountOwner,
+    pub token_id: Option<TokenId>,
 }
 
 /// Withdraw message metadata.
@@ -245,6 +304,7 @@ pub struct WithdrawMessageMetadata {
     pub owner: AccountOwner,
     pub amount: Amount,
     pub recipient: Account,
+    pub token_id: Option<TokenId>,
 }
 
 /// Structured representation of a message for GraphQL.
@@ -272,9 +332,24 @@ impl From<&SystemOperation> for SystemOperationMetadata {
                     owner: *owner,
                     recipient: *recipient,
                     amount: *amount,
+                    token_id: None,
                 }),
                 ..SystemOperationMetadata::new("Transfer")
             },
+            SystemOperation::TransferToken {
+                owner,
+                recipient,
+                amount,
+                token_id,
+            } => SystemOperationMetadata {
+                transfer: Some(TransferOperationMetadata {
+                    owner: *owner,
+                    recipient: *recipient,
+                    amount: *amount,
+                    token_id: Some(*token_id),
+                }),
+                ..SystemOperationMetadata::new("TransferToken")
+            },
             SystemOperation::Claim {
                 owner,
                 target_id,
@@ -286,9 +361,26 @@ impl From<&SystemOperation> for SystemOperationMetadata {
                     target_id: *target_id,
                     recipient: *recipient,
                     amount: *amount,
+                    token_id: None,
                 }),
                 ..SystemOperationMetadata::new("Claim")
             },
+            SystemOperation::ClaimToken {
+                owner,
+                target_id,
+                recipient,
+                amount,
+                token_id,
+            } => SystemOperationMetadata {
+                claim: Some(ClaimOperationMetadata {
+                    owner: *owner,
+                    target_id: *target_id,
+                    recipient: *recipient,
+                    amount: *amount,
+                    token_id: Some(*token_id),
+                }),
+                ..SystemOperationMetadata::new("ClaimToken")
+            },
             SystemOperation::OpenChain(config) => SystemOperationMetadata {
                 open_chain: Some(OpenChainOperationMetadata {
                     balance: config.balance,
@@ -306,6 +398,7 @@ impl From<&SystemOperation> for SystemOperationMetadata {
                 multi_leader_rounds,
                 open_multi_leader_rounds,
                 timeout_config,
+                approval_threshold,
             } => SystemOperationMetadata {
                 change_ownership: Some(ChangeOwnershipOperationMetadata {
                     super_owners: super_owners.clone(),
@@ -319,6 +412,7 @@ impl From<&SystemOperation> for SystemOperationMetadata {
                     multi_leader_rounds: *multi_leader_rounds as i32,
                     open_multi_leader_rounds: *open_multi_leader_rounds,
                     timeout_config: TimeoutConfigMetadata::from(timeout_config),
+                    approval_threshold: approval_threshold.map(|threshold| threshold.to_string()),
                 }),
                 ..SystemOperationMetadata::new("ChangeOwnership")
             },
@@ -337,6 +431,7 @@ impl From<&SystemOperation> for SystemOperationMetadata {
                 parameters,
                 instantiation_argument,
                 required_application_ids,
+                upgrade_authority,
             } => SystemOperationMetadata {
                 create_application: Some(CreateApplicationOperationMetadata {
                     module_id: serde_json::to_string(module_id)
@@ -344,6 +439,7 @@ impl From<&SystemOperation> for SystemOperationMetadata {
                     parameters_hex: hex::encode(parameters),
                     instantiation_argument_hex: hex::encode(instantiation_argument),
                     required_application_ids: required_application_ids.clone(),
+                    upgrade_authority: *upgrade_authority,
                 }),
                 ..SystemOperationMetadata::new("CreateApplication")
             },
@@ -387,6 +483,60 @@ impl From<&SystemOperation> for SystemOperationMetadata {
                 ),
                 ..SystemOperationMetadata::new("UpdateStreams")
             },
+            SystemOperation::ExecuteScheduledOperation(key) => SystemOperationMetadata {
+                scheduled_operation: Some(ScheduledOperationMetadata::from(key)),
+                ..SystemOperationMetadata::new("ExecuteScheduledOperation")
+            },
+            SystemOperation::DiscardScheduledOperation(key) => SystemOperationMetadata {
+                scheduled_operation: Some(ScheduledOperationMetadata::from(key)),
+                ..SystemOperationMetadata::new("DiscardScheduledOperation")
+            },
+            SystemOperation::Approve {
+                owner,
+                spender,
+                amount,
+                expiry,
+            } => SystemOperationMetadata {
+                allowance: Some(AllowanceOperationMetadata {
+                    owner: *owner,
+                    spender: *spender,
+                    amount: Some(*amount),
+                    expiry: *expiry,
+                }),
+                ..SystemOperationMetadata::new("Approve")
+            },
+            SystemOperation::Revoke { owner, spender } => SystemOperationMetadata {
+                allowance: Some(AllowanceOperationMetadata {
+                    owner: *owner,
+                    spender: *spender,
+                    amount: None,
+                    expiry: None,
+                }),
+                ..SystemOperationMetadata::new("Revoke")
+            },
+            SystemOperation::UpgradeApplication {
+                application_id,
+                module_id,
+                migration_argument,
+            } => SystemOperationMetadata {
+                upgrade_application: Some(UpgradeApplicationOperationMetadata {
+                    application_id: *application_id,
+                    module_id: serde_json::to_string(module_id)
+                        .unwrap_or_else(|_| format!("{:?}", module_id)),
+                    migration_argument_hex: hex::encode(migration_argument),
+                }),
+                ..SystemOperationMetadata::new("UpgradeApplication")
+            },
+            SystemOperation::DeleteApplication {
+                application_id,
+                refund_recipient,
+            } => SystemOperationMetadata {
+                delete_application: Some(DeleteApplicationOperationMetadata {
+                    application_id: *application_id,
+                    refund_recipient: *refund_recipient,
+                }),
+                ..SystemOperationMetadata::new("DeleteApplication")
+            },
         }
     }
 }
@@ -448,6 +598,22 @@ impl From<&SystemMessage> for SystemMessageMetadata {
                     target: *target,
                     amount: *amount,
                     source: *source,
+                    token_id: None,
+                }),
+                withdraw: None,
+            },
+            SystemMessage::CreditToken {
+                target,
+                amount,
+                source,
+                token_id,
+            } => SystemMessageMetadata {
+                system_message_type: "CreditToken".to_string(),
+                credit: Some(CreditMessageMetadata {
+                    target: *target,
+                    amount: *amount,
+                    source: *source,
+                    token_id: Some(*token_id),
                 }),
                 withdraw: None,
             },
@@ -462,6 +628,22 @@ impl From<&SystemMessage> for SystemMessageMetadata {
                     owner: *owner,
                     amount: *amount,
                     recipient: *recipient,
+                    token_id: None,
+                }),
+            },
+            SystemMessage::WithdrawToken {
+                owner,
+                amount,
+                recipient,
+                token_id,
+            } => SystemMessageMetadata {
+                system_message_type: "WithdrawToken".to_string(),
+                credit: None,
+                withdraw: Some(WithdrawMessageMetadata {
+                    owner: *owner,
+                    amount: *amount,
+                    recipient: *recipient,
+                    token_id: Some(*token_id),
                 }),
             },
         }
@@ -9,14 +9,17 @@ use custom_debug_derive::Debug;
 use linera_base::{
     bcs,
     crypto::{
-        AccountSignature, BcsHashable, BcsSignable, CryptoError, CryptoHash, Signer,
-        ValidatorPublicKey, ValidatorSecretKey, ValidatorSignature,
+        AccountSignature, BcsHashable, BcsSignable, Bls12381PublicKey, Bls12381SecretKey,
+        Bls12381Signature, CryptoError, CryptoHash, SignedContent, Signer, ValidatorPublicKey,
+        ValidatorSecretKey, ValidatorSignature,
     },
     data_types::{Amount, Blob, BlockHeight, Epoch, Event, OracleResponse, Round, Timestamp},
     doc_scalar, ensure, hex, hex_debug,
     identifiers::{Account, AccountOwner, ApplicationId, BlobId, ChainId, StreamId},
 };
-use linera_execution::{committee::Committee, Message, MessageKind, Operation, OutgoingMessage};
+use linera_execution::{
+    committee::Committee, Message, MessageKind, Operation, OutgoingMessage, ResourceTracker,
+};
 use serde::{Deserialize, Serialize};
 
 use crate::{
@@ -306,6 +309,9 @@ pub struct BlockProposal {
     pub signature: AccountSignature,
     #[debug(skip_if = Option::is_none)]
     pub original_proposal: Option<OriginalProposal>,
+    /// Signatures of other owners over the proposed block, if the chain requires approvals.
+    #[debug(skip_if = Vec::is_empty)]
+    pub approvals: Vec<AccountSignature>,
 }
 
 /// A message together with kind, authentication and grant information.
@@ -401,6 +407,35 @@ pub struct BlockExecutionOutcome {
     pub operation_results: Vec<OperationResult>,
 }
 
+/// The result of executing a [`ProposedBlock`] without committing it: the resources it used,
+/// the fees it was charged, and the messages and events it produced.
+#[derive(Debug, Clone, Serialize, Deserialize, SimpleObject)]
+pub struct FeeEstimate {
+    /// The resources used by the block.
+    pub resources: ResourceTracker,
+    /// The fees charged for these resources under the current resource control policy.
+    pub fees: Amount,
+    /// The list of outgoing messages for each transaction.
+    pub messages: Vec<Vec<OutgoingMessage>>,
+    /// The list of events produced by each transaction.
+    pub events: Vec<Vec<Event>>,
+    /// The execution result for each operation.
+    pub operation_results: Vec<OperationResult>,
+}
+
+impl FeeEstimate {
+    /// Creates an estimate from the outcome of a block's execution and the resources it used.
+    pub fn new(outcome: BlockExecutionOutcome, resources: ResourceTracker) -> Self {
+        FeeEstimate {
+            fees: resources.fees,
+            resources,
+            messages: outcome.messages,
+            events: outcome.events,
+            operation_results: outcome.operation_results,
+        }
+    }
+}
+
 /// The hash and chain ID of a `CertificateValue`.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
 pub struct LiteValue {
@@ -429,20 +464,32 @@ pub struct Vote<T> {
     pub value: T,
     pub round: Round,
     pub signature: ValidatorSignature,
+    /// A BLS12-381 signature of the same statement, if the validator has an aggregate key.
+    #[debug(skip_if = Option::is_none)]
+    pub aggregate_signature: Option<Bls12381Signature>,
 }
 
 impl<T> Vote<T> {
-    /// Use signing key to create a signed object.
-    pub fn new(value: T, round: Round, key_pair: &ValidatorSecretKey) -> Self
+    /// Use signing key to create a signed object. If an aggregate key is provided, the vote
+    /// is also signed with it, so that it can be part of an aggregate certificate.
+    pub fn new(
+        value: T,
+        round: Round,
+        key_pair: &ValidatorSecretKey,
+        aggregate_key: Option<&Bls12381SecretKey>,
+    ) -> Self
     where
         T: CertificateValue,
     {
         let hash_and_round = VoteValue(value.hash(), round, T::KIND);
         let signature = ValidatorSignature::new(&hash_and_round, key_pair);
+        let aggregate_signature =
+            aggregate_key.map(|secret| Bls12381Signature::new(&hash_and_round, secret));
         Self {
             value,
             round,
             signature,
+            aggregate_signature,
         }
     }
 
@@ -455,6 +502,7 @@ impl<T> Vote<T> {
             value: LiteValue::new(&self.value),
             round: self.round,
             signature: self.signature,
+            aggregate_signature: self.aggregate_signature,
         }
     }
 
@@ -471,6 +519,9 @@ pub struct LiteVote {
     pub value: LiteValue,
     pub round: Round,
     pub signature: ValidatorSignature,
+    /// A BLS12-381 signature of the same statement, if the validator has an aggregate key.
+    #[debug(skip_if = Option::is_none)]
+    pub aggregate_signature: Option<Bls12381Signature>,
 }
 
 impl LiteVote {
@@ -484,6 +535,7 @@ impl LiteVote {
             value,
             round: self.round,
             signature: self.signature,
+            aggregate_signature: self.aggregate_signature,
         })
     }
 
@@ -568,6 +620,21 @@ pub struct ProposalContent {
     pub outcome: Option<BlockExecutionOutcome>,
 }
 
+impl ProposalContent {
+    /// Signs the content as the given `owner`.
+    async fn sign<S: Signer + ?Sized>(
+        &self,
+        owner: &AccountOwner,
+        signer: &S,
+    ) -> Result<AccountSignature, S::Error> {
+        let bytes = bcs::to_bytes(self).expect("serialization should not fail");
+        let content = SignedContent::BlockProposal(&bytes);
+        signer
+            .sign_content(owner, &CryptoHash::new(self), content)
+            .await
+    }
+}
+
 impl BlockProposal {
     pub async fn new_initial<S: Signer + ?Sized>(
         owner: AccountOwner,
@@ -580,12 +647,13 @@ impl BlockProposal {
             block,
             outcome: None,
         };
-        let signature = signer.sign(&owner, &CryptoHash::new(&content)).await?;
+        let signature = content.sign(&owner, signer).await?;
 
         Ok(Self {
             content,
             signature,
             original_proposal: None,
+            approvals: Vec::new(),
         })
     }
 
@@ -600,12 +668,13 @@ impl BlockProposal {
             block: old_proposal.content.block,
             outcome: None,
         };
-        let signature = signer.sign(&owner, &CryptoHash::new(&content)).await?;
+        let signature = content.sign(&owner, signer).await?;
 
         Ok(Self {
             content,
             signature,
             original_proposal: Some(OriginalProposal::Fast(old_proposal.signature)),
+            approvals: old_proposal.approvals,
         })
     }
 
@@ -623,15 +692,22 @@ impl BlockProposal {
             round,
             outcome: Some(outcome),
         };
-        let signature = signer.sign(&owner, &CryptoHash::new(&content)).await?;
+        let signature = content.sign(&owner, signer).await?;
 
         Ok(Self {
             content,
             signature,
             original_proposal: Some(OriginalProposal::Regular { certificate }),
+            approvals: Vec::new(),
         })
     }
 
+    /// Attaches other owners' signatures over the proposed block.
+    pub fn with_approvals(mut self, approvals: Vec<AccountSignature>) -> Self {
+        self.approvals = approvals;
+        self
+    }
+
     /// Returns the `AccountOwner` that proposed the block.
     pub fn owner(&self) -> AccountOwner {
         match self.signature {
@@ -645,6 +721,17 @@ impl BlockProposal {
         self.signature.verify(&self.content)
     }
 
+    /// Verifies the approvals and returns the owners who signed the block, including the
+    /// proposer.
+    pub fn approval_signers(&self) -> Result<Vec<AccountOwner>, CryptoError> {
+        let mut signers = vec![self.owner()];
+        for approval in &self.approvals {
+            approval.verify(&self.content.block)?;
+            signers.push(approval.owner());
+        }
+        Ok(signers)
+    }
+
     pub fn required_blob_ids(&self) -> impl Iterator<Item = BlobId> + '_ {
         self.content.block.published_blob_ids().into_iter().chain(
             self.content
@@ -705,6 +792,7 @@ impl LiteVote {
             value,
             round,
             signature,
+            aggregate_signature: None,
         }
     }
 
@@ -713,6 +801,20 @@ impl LiteVote {
         let hash_and_round = VoteValue(self.value.value_hash, self.round, self.value.kind);
         Ok(self.signature.check(&hash_and_round, public_key)?)
     }
+
+    /// Verifies the aggregate signature in the vote, if any, against the validator's
+    /// aggregate public key.
+    pub fn check_aggregate_signature(
+        &self,
+        aggregate_public_key: Option<Bls12381PublicKey>,
+    ) -> Result<(), ChainError> {
+        let Some(aggregate_signature) = &self.aggregate_signature else {
+            return Ok(());
+        };
+        let public_key = aggregate_public_key.ok_or(ChainError::InvalidAggregateSigners)?;
+        let hash_and_round = VoteValue(self.value.value_hash, self.round, self.value.kind);
+        Ok(aggregate_signature.check(&hash_and_round, public_key)?)
+    }
 }
 
 pub struct SignatureAggregator<'a, T: CertificateValue> {
@@ -807,8 +909,98 @@ pub(crate) fn check_signatures(
     Ok(())
 }
 
+/// A single BLS12-381 signature aggregated from the votes of a quorum of validators.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AggregateSignature {
+    /// A bitmap of the signers, indexed by their position in the committee's validators.
+    #[debug(with = "hex_debug")]
+    #[serde(with = "serde_bytes")]
+    pub signers: Vec<u8>,
+    /// The aggregate of the signers' signatures.
+    pub signature: Bls12381Signature,
+}
+
+impl AggregateSignature {
+    /// Aggregates the BLS12-381 signatures of the given votes, without checking them.
+    ///
+    /// Returns `None` if any vote lacks an aggregate signature, if any voter is not in the
+    /// committee, or if there are no votes.
+    pub fn from_votes<'a>(
+        votes: impl IntoIterator<Item = (&'a ValidatorPublicKey, &'a LiteVote)>,
+        committee: &Committee,
+    ) -> Option<Self> {
+        let mut signers = vec![0; committee.validators().len().div_ceil(8)];
+        let mut signatures = Vec::new();
+        for (public_key, vote) in votes {
+            let index = committee
+                .validators()
+                .keys()
+                .position(|key| key == public_key)?;
+            signers[index / 8] |= 1 << (index % 8);
+            signatures.push(vote.aggregate_signature.as_ref()?);
+        }
+        let signature = Bls12381Signature::aggregate(signatures)?;
+        Some(Self { signers, signature })
+    }
+
+    /// Returns whether the validator is among the signers, according to the given committee.
+    pub fn is_signed_by(&self, validator: &ValidatorPublicKey, committee: &Committee) -> bool {
+        committee
+            .validators()
+            .keys()
+            .position(|key| key == validator)
+            .is_some_and(|index| self.is_signer(index))
+    }
+
+    fn is_signer(&self, index: usize) -> bool {
+        self.signers
+            .get(index / 8)
+            .is_some_and(|byte| byte & (1 << (index % 8)) != 0)
+    }
+}
+
+/// Verifies an aggregate certificate signature.
+pub(crate) fn check_aggregate_signature(
+    value_hash: CryptoHash,
+    certificate_kind: CertificateKind,
+    round: Round,
+    aggregate: &AggregateSignature,
+    committee: &Committee,
+) -> Result<(), ChainError> {
+    let validator_count = committee.validators().len();
+    ensure!(
+        aggregate.signers.len() == validator_count.div_ceil(8)
+            && (validator_count..aggregate.signers.len() * 8).all(|i| !aggregate.is_signer(i)),
+        ChainError::InvalidAggregateSigners
+    );
+    // Check the quorum.
+    let mut weight = 0;
+    let mut public_keys = Vec::new();
+    for (index, state) in committee.validators().values().enumerate() {
+        if aggregate.is_signer(index) {
+            let public_key = state
+                .aggregate_public_key
+                .ok_or(ChainError::InvalidAggregateSigners)?;
+            public_keys.push(public_key);
+            weight += state.votes;
+        }
+    }
+    ensure!(
+        weight >= committee.quorum_threshold(),
+        ChainError::CertificateRequiresQuorum
+    );
+    // A single pairing check verifies all the signatures.
+    let hash_and_round = VoteValue(value_hash, round, certificate_kind);
+    aggregate
+        .signature
+        .check_aggregate(&hash_and_round, &public_keys)?;
+    Ok(())
+}
+
 impl BcsSignable<'_> for ProposalContent {}
 
+impl BcsSignable<'_> for ProposedBlock {}
+
 impl BcsSignable<'_> for VoteValue {}
 
 doc_scalar!(
@@ -877,6 +1069,7 @@ mod signing {
             content: proposal,
             signature,
             original_proposal: None,
+            approvals: Vec::new(),
         };
         assert_eq!(block_proposal.owner(), public_key.into(),);
     }
@@ -20,7 +20,7 @@ mod pending_blobs;
 #[cfg(with_testing)]
 pub mod test;
 
-pub use chain::ChainStateView;
+pub use chain::{ChainStateView, ChainTipState};
 use data_types::{MessageBundle, PostedMessage};
 use linera_base::{
     bcs,
@@ -137,6 +137,10 @@ pub enum ChainError {
     CertificateValidatorReuse,
     #[error("Signatures in a certificate must form a quorum")]
     CertificateRequiresQuorum,
+    #[error("Aggregate signature signers must be committee members with aggregate keys")]
+    InvalidAggregateSigners,
+    #[error("A certificate must not have both individual and aggregate signatures")]
+    CertificateWithMixedSignatures,
     #[error("Internal error {0}")]
     InternalError(String),
     #[error("Block proposal has size {0} which is too large")]
@@ -157,6 +161,11 @@ pub enum ChainError {
     RoundDoesNotTimeOut,
     #[error("Not signing timeout certificate; current round times out at time {0}")]
     NotTimedOutYet(Timestamp),
+    #[error(
+        "Block proposal is approved by owners with total weight {weight}, \
+         but the chain requires {threshold}"
+    )]
+    InsufficientApprovals { weight: u64, threshold: u64 },
 }
 
 impl ChainError {
@@ -186,6 +195,8 @@ impl ChainError {
             | ChainError::MissingEarlierBlocks { .. }
             | ChainError::CertificateValidatorReuse
             | ChainError::CertificateRequiresQuorum
+            | ChainError::InvalidAggregateSigners
+            | ChainError::CertificateWithMixedSignatures
             | ChainError::BlockProposalTooLarge(_)
             | ChainError::ClosedChain
             | ChainError::EmptyBlock
@@ -194,6 +205,7 @@ impl ChainError {
             | ChainError::MissingOracleResponseList
             | ChainError::RoundDoesNotTimeOut
             | ChainError::NotTimedOutYet(_)
+            | ChainError::InsufficientApprovals { .. }
             | ChainError::MissingCrossChainUpdate { .. } => false,
             ChainError::ViewError(_)
             | ChainError::UnexpectedMessage { .. }
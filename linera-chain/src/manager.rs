@@ -73,7 +73,7 @@ use std::collections::BTreeMap;
 use custom_debug_derive::Debug;
 use futures::future::Either;
 use linera_base::{
-    crypto::{AccountPublicKey, CryptoError, ValidatorSecretKey},
+    crypto::{AccountPublicKey, Bls12381SecretKey, ValidatorSecretKey},
     data_types::{Blob, BlockHeight, Epoch, Round, Timestamp},
     ensure,
     identifiers::{AccountOwner, BlobId, ChainId},
@@ -364,6 +364,7 @@ where
         round: Round,
         epoch: Epoch,
         key_pair: Option<&ValidatorSecretKey>,
+        aggregate_key: Option<&Bls12381SecretKey>,
         local_time: Timestamp,
     ) -> Result<bool, ChainError> {
         let Some(key_pair) = key_pair else {
@@ -387,7 +388,7 @@ where
         }
         let value = Timeout::new(chain_id, height, epoch);
         self.timeout_vote
-            .set(Some(Vote::new(value, round, key_pair)));
+            .set(Some(Vote::new(value, round, key_pair, aggregate_key)));
         Ok(true)
     }
 
@@ -401,6 +402,7 @@ where
         height: BlockHeight,
         epoch: Epoch,
         key_pair: Option<&ValidatorSecretKey>,
+        aggregate_key: Option<&Bls12381SecretKey>,
     ) -> bool {
         let Some(key_pair) = key_pair else {
             return false; // We are not a validator.
@@ -410,8 +412,12 @@ where
         }
         let value = Timeout::new(chain_id, height, epoch);
         let last_regular_round = Round::SingleLeader(u32::MAX);
-        self.fallback_vote
-            .set(Some(Vote::new(value, last_regular_round, key_pair)));
+        self.fallback_vote.set(Some(Vote::new(
+            value,
+            last_regular_round,
+            key_pair,
+            aggregate_key,
+        )));
         true
     }
 
@@ -448,6 +454,7 @@ where
         proposal: BlockProposal,
         block: Block,
         key_pair: Option<&ValidatorSecretKey>,
+        aggregate_key: Option<&Bls12381SecretKey>,
         local_time: Timestamp,
         blobs: BTreeMap<BlobId, Blob>,
     ) -> Result<Option<ValidatedOrConfirmedVote>, ChainError> {
@@ -502,13 +509,13 @@ where
         if round.is_fast() {
             self.validated_vote.set(None);
             let value = ConfirmedBlock::new(block);
-            let vote = Vote::new(value, round, key_pair);
+            let vote = Vote::new(value, round, key_pair, aggregate_key);
             Ok(Some(Either::Right(
                 self.confirmed_vote.get_mut().insert(vote),
             )))
         } else {
             let value = ValidatedBlock::new(block);
-            let vote = Vote::new(value, round, key_pair);
+            let vote = Vote::new(value, round, key_pair, aggregate_key);
             Ok(Some(Either::Left(
                 self.validated_vote.get_mut().insert(vote),
             )))
@@ -520,6 +527,7 @@ where
         &mut self,
         validated: ValidatedBlockCertificate,
         key_pair: Option<&ValidatorSecretKey>,
+        aggregate_key: Option<&Bls12381SecretKey>,
         local_time: Timestamp,
         blobs: BTreeMap<BlobId, Blob>,
     ) -> Result<(), ViewError> {
@@ -532,7 +540,7 @@ where
                 return Ok(()); // We never vote in a past round.
             }
             // Vote to confirm.
-            let vote = Vote::new(confirmed_block, round, key_pair);
+            let vote = Vote::new(confirmed_block, round, key_pair, aggregate_key);
             // Ok to overwrite validation votes with confirmation votes at equal or higher round.
             self.confirmed_vote.set(Some(vote));
             self.validated_vote.set(None);
@@ -618,17 +626,41 @@ where
     }
 
     /// Returns whether the signer is a valid owner and allowed to propose a block in the
-    /// proposal's round.
-    pub fn verify_owner(
-        &self,
-        proposal_owner: &AccountOwner,
-        proposal_round: Round,
-    ) -> Result<bool, CryptoError> {
-        if self.ownership.get().super_owners.contains(proposal_owner) {
+    /// proposal's round. Fails if the chain requires approvals and the owners who signed the
+    /// proposed block don't meet the threshold.
+    pub fn verify_owner(&self, proposal: &BlockProposal) -> Result<bool, ChainError> {
+        if !self.is_round_proposer(&proposal.owner(), proposal.content.round) {
+            return Ok(false);
+        }
+        // Fallback rounds are not restricted to the owners, and a validated block has already
+        // been approved when it was first proposed.
+        if matches!(proposal.content.round, Round::Validator(_))
+            || matches!(
+                proposal.original_proposal,
+                Some(OriginalProposal::Regular { .. })
+            )
+        {
             return Ok(true);
         }
+        let ownership = self.ownership.get();
+        if let Some(threshold) = ownership.approval_threshold {
+            let signers = proposal.approval_signers()?;
+            let weight = ownership.approval_weight(&signers);
+            ensure!(
+                weight >= threshold,
+                ChainError::InsufficientApprovals { weight, threshold }
+            );
+        }
+        Ok(true)
+    }
 
-        Ok(match proposal_round {
+    /// Returns whether the owner is allowed to propose a block in the given round.
+    fn is_round_proposer(&self, proposal_owner: &AccountOwner, proposal_round: Round) -> bool {
+        if self.ownership.get().super_owners.contains(proposal_owner) {
+            return true;
+        }
+
+        match proposal_round {
             Round::Fast => {
                 false // Only super owners can propose in the first round.
             }
@@ -641,7 +673,7 @@ where
                 let Some(index) =
                     round_leader_index(r, *self.seed.get(), self.distribution.get().as_ref())
                 else {
-                    return Ok(false);
+                    return false;
                 };
                 self.ownership.get().owners.keys().nth(index) == Some(proposal_owner)
             }
@@ -651,11 +683,11 @@ where
                     *self.seed.get(),
                     self.fallback_distribution.get().as_ref(),
                 ) else {
-                    return Ok(false);
+                    return false;
                 };
                 self.fallback_owners.get().keys().nth(index) == Some(proposal_owner)
             }
-        })
+        }
     }
 
     /// Returns the leader who is allowed to propose a block in the given round, or `None` if every
@@ -169,6 +169,8 @@ impl<T: CertificateValue> VoteTestExt<T> for Vote<T> {
             network_address: "".to_string(),
             votes: 100,
             account_public_key: AccountPublicKey::test_key(1),
+            aggregate_public_key: None,
+            aggregate_key_proof: None,
         };
         let committee = Committee::new(
             vec![(public_key, state)].into_iter().collect(),
@@ -26,7 +26,8 @@ use linera_execution::{
     committee::{Committee, ValidatorState},
     test_utils::{ExpectedCall, MockApplication},
     BaseRuntime, ContractRuntime, ExecutionError, ExecutionRuntimeConfig, ExecutionRuntimeContext,
-    Operation, ResourceControlPolicy, ServiceRuntime, SystemOperation, TestExecutionRuntimeContext,
+    FeeMultiplier, Operation, ResourceControlPolicy, ServiceRuntime, SystemOperation,
+    TestExecutionRuntimeContext,
 };
 use linera_views::{
     context::{Context as _, MemoryContext, ViewContext},
@@ -146,6 +147,8 @@ fn committee_blob(policy: ResourceControlPolicy) -> Blob {
                 network_address: ValidatorPublicKey::test_key(1).to_string(),
                 votes: 1,
                 account_public_key: AccountPublicKey::test_key(1),
+                aggregate_public_key: None,
+                aggregate_key_proof: None,
             },
         )]),
         policy,
@@ -238,6 +241,57 @@ async fn test_block_size_limit() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Tests that the fee multiplier is reset once the policy no longer has dynamic fees.
+#[tokio::test]
+async fn test_fee_multiplier_reset_without_dynamic_fees() -> anyhow::Result<()> {
+    let mut env = TestEnvironment::new();
+    let time = Timestamp::from(0);
+    let config = env.make_open_chain_config();
+    let chain_desc = env.make_child_chain_description_with_config(3, config);
+    let chain_id = chain_desc.id();
+    let owner = chain_desc
+        .config()
+        .ownership
+        .all_owners()
+        .next()
+        .copied()
+        .unwrap();
+
+    let mut chain = ChainStateView::new(chain_id).await;
+    chain
+        .context()
+        .extra()
+        .add_blobs([committee_blob(ResourceControlPolicy::default())])
+        .await?;
+    chain
+        .context()
+        .extra()
+        .add_blobs(env.description_blobs())
+        .await?;
+    chain.initialize_if_needed(time).await?;
+    // A multiplier left over from when the policy had dynamic fees.
+    chain
+        .execution_state
+        .system
+        .fee_multiplier
+        .set(FeeMultiplier(1_500_000));
+
+    let block = make_first_block(chain_id)
+        .with_authenticated_signer(Some(owner))
+        .with_operation(SystemOperation::Transfer {
+            owner: AccountOwner::CHAIN,
+            recipient: Account::chain(env.admin_id()),
+            amount: Amount::ONE,
+        });
+    chain.execute_block(&block, time, None, &[], None).await?;
+    assert_eq!(
+        *chain.execution_state.system.fee_multiplier.get(),
+        FeeMultiplier::ONE
+    );
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_application_permissions() -> anyhow::Result<()> {
     let mut env = TestEnvironment::new();
@@ -6,6 +6,7 @@ use linera_base::{
     crypto::{AccountSecretKey, Ed25519SecretKey, Secp256k1SecretKey, ValidatorKeypair},
     data_types::Amount,
 };
+use linera_execution::ResourceControlPolicy;
 
 use super::*;
 use crate::{
@@ -145,6 +146,103 @@ fn test_certificates() {
         .is_err());
 }
 
+#[test]
+fn test_aggregate_certificates() {
+    let key_pairs = (0..4)
+        .map(|_| ValidatorKeypair::generate())
+        .collect::<Vec<_>>();
+    let aggregate_keys = (0..4)
+        .map(|_| Bls12381SecretKey::generate())
+        .collect::<Vec<_>>();
+    let account_key = AccountSecretKey::Ed25519(Ed25519SecretKey::generate()).public();
+    let committee = Committee::make_simple(
+        key_pairs
+            .iter()
+            .map(|key_pair| (key_pair.public_key, account_key))
+            .collect(),
+    );
+    let mut validators = committee.validators().clone();
+    for (key_pair, aggregate_key) in key_pairs.iter().zip(&aggregate_keys) {
+        let state = validators.get_mut(&key_pair.public_key).unwrap();
+        state.aggregate_public_key = Some(aggregate_key.public());
+        state.aggregate_key_proof = Some(aggregate_key.proof_of_possession());
+    }
+    let committee = Committee::new(validators, ResourceControlPolicy::default());
+
+    let block = BlockExecutionOutcome::default().with(make_first_block(dummy_chain_id(1)));
+    let value = ConfirmedBlock::new(block);
+    let mut votes = key_pairs
+        .iter()
+        .zip(&aggregate_keys)
+        .map(|(key_pair, aggregate_key)| {
+            let vote = Vote::new(
+                value.clone(),
+                Round::Fast,
+                &key_pair.secret_key,
+                Some(aggregate_key),
+            );
+            (key_pair.public_key, vote.lite())
+        })
+        .collect::<Vec<_>>();
+
+    // Two out of four validators are not a quorum.
+    assert!(LiteCertificate::try_aggregate_votes(&votes[..2], &committee).is_none());
+    let lite_certificate = LiteCertificate::try_aggregate_votes(&votes[..3], &committee).unwrap();
+    assert!(lite_certificate.signatures.is_empty());
+    let certificate = lite_certificate.with_value(value.clone()).unwrap();
+    assert!(certificate.check(&committee).is_ok());
+    let aggregate = certificate.aggregate_signature().unwrap();
+    assert!(aggregate.is_signed_by(&key_pairs[0].public_key, &committee));
+    assert!(!aggregate.is_signed_by(&key_pairs[3].public_key, &committee));
+
+    // Claiming an additional signer invalidates the signature.
+    let mut forged = aggregate.clone();
+    forged.signers = vec![0b1111];
+    let forged = GenericCertificate::new_aggregated(value.clone(), Round::Fast, forged);
+    assert!(forged.check(&committee).is_err());
+
+    // Signers outside the committee are rejected.
+    let mut forged = aggregate.clone();
+    forged.signers = vec![0b1_0111];
+    let forged = GenericCertificate::new_aggregated(value.clone(), Round::Fast, forged);
+    assert!(matches!(
+        forged.check(&committee),
+        Err(ChainError::InvalidAggregateSigners)
+    ));
+
+    // Only a quorum of the votes is aggregated.
+    let lite_certificate = LiteCertificate::try_aggregate_votes(&votes, &committee).unwrap();
+    let aggregate = lite_certificate.aggregate_signature.unwrap();
+    assert!(!aggregate.is_signed_by(&key_pairs[3].public_key, &committee));
+
+    // An aggregate signature by another validator is detected in the vote.
+    let aggregate_public_key = |index: usize| Some(aggregate_keys[index].public());
+    assert!(votes[0]
+        .1
+        .check_aggregate_signature(aggregate_public_key(0))
+        .is_ok());
+    assert!(votes[0]
+        .1
+        .check_aggregate_signature(aggregate_public_key(1))
+        .is_err());
+    assert!(votes[0].1.check_aggregate_signature(None).is_err());
+
+    // Votes without aggregate signatures fall back to individual signatures, unless the
+    // other votes are a quorum.
+    votes[0].1.aggregate_signature = None;
+    assert!(votes[0]
+        .1
+        .check_aggregate_signature(aggregate_public_key(1))
+        .is_ok());
+    assert!(LiteCertificate::try_aggregate_votes(&votes[..3], &committee).is_none());
+    let lite_certificate = LiteCertificate::try_aggregate_votes(&votes, &committee).unwrap();
+    let certificate = lite_certificate.with_value(value).unwrap();
+    assert!(certificate.check(&committee).is_ok());
+    let aggregate = certificate.aggregate_signature().unwrap();
+    assert!(!aggregate.is_signed_by(&key_pairs[0].public_key, &committee));
+    assert!(aggregate.is_signed_by(&key_pairs[3].public_key, &committee));
+}
+
 #[test]
 fn round_ordering() {
     assert!(Round::Fast < Round::MultiLeader(0));
@@ -3,6 +3,8 @@
 
 use std::{collections::HashSet, fmt, iter, path::PathBuf};
 
+#[cfg(not(web))]
+use linera_base::crypto::InMemorySigner;
 use linera_base::{
     data_types::{ApplicationPermissions, TimeDelta},
     identifiers::{AccountOwner, ApplicationId, ChainId},
@@ -19,20 +21,28 @@ use linera_core::{
 };
 use linera_execution::ResourceControlPolicy;
 
-#[cfg(not(web))]
-use crate::client_metrics::TimingConfig;
 use crate::util;
 
+/// The environment variable holding the passphrase of an encrypted keystore.
+pub const KEYSTORE_PASSPHRASE_ENV: &str = "LINERA_KEYSTORE_PASSPHRASE";
+
+#[cfg(not(web))]
+use crate::{client_metrics::TimingConfig, remote_signer::RemoteSigner, signer::ClientSigner};
+
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("I/O error: {0}")]
     IoError(#[from] std::io::Error),
     #[error("there are {public_keys} public keys but {weights} weights")]
     MisalignedWeights { public_keys: usize, weights: usize },
+    #[error("the approval threshold {threshold} exceeds the total weight of the owners")]
+    UnreachableApprovalThreshold { threshold: u64 },
     #[error("persistence error: {0}")]
     Persistence(#[from] Box<dyn std::error::Error + Send + Sync>),
     #[error("config error: {0}")]
     Config(#[from] crate::config::Error),
+    #[error("a token file is required to authenticate with the remote signer")]
+    MissingRemoteSignerToken,
 }
 
 #[cfg(feature = "fs")]
@@ -53,6 +63,25 @@ pub struct ClientContextOptions {
     #[arg(long = "keystore")]
     pub keystore_path: Option<PathBuf>,
 
+    /// Sets the file containing the passphrase of an encrypted keystore. Otherwise, the
+    /// passphrase is read from the `LINERA_KEYSTORE_PASSPHRASE` environment variable, or
+    /// prompted for when an encrypted keystore is opened. New keystores are encrypted
+    /// under the passphrase from the file or the environment, if any.
+    #[cfg(not(web))]
+    #[arg(long)]
+    pub keystore_passphrase_file: Option<PathBuf>,
+
+    /// Sets the URL of a signing daemon holding the keys, instead of the keystore.
+    #[cfg(not(web))]
+    #[arg(long, env = "LINERA_REMOTE_SIGNER")]
+    pub remote_signer: Option<String>,
+
+    /// Sets the file containing the bearer token of the signing daemon, as written by
+    /// `linera-signer serve`.
+    #[cfg(not(web))]
+    #[arg(long, env = "LINERA_REMOTE_SIGNER_TOKEN_FILE")]
+    pub remote_signer_token_file: Option<PathBuf>,
+
     /// Given an ASCII alphanumeric parameter `X`, read the wallet state and the wallet
     /// storage config from the environment variables `LINERA_WALLET_{X}` and
     /// `LINERA_STORAGE_{X}` instead of `LINERA_WALLET` and
@@ -210,6 +239,35 @@ impl ClientContextOptions {
         }
     }
 
+    /// Returns the keystore passphrase from the passphrase file or the environment, if
+    /// any.
+    #[cfg(not(web))]
+    pub fn keystore_passphrase(&self) -> Result<Option<String>, Error> {
+        if let Some(path) = &self.keystore_passphrase_file {
+            let passphrase = std::fs::read_to_string(path)?;
+            return Ok(Some(passphrase.trim_end_matches(['\n', '\r']).to_owned()));
+        }
+        Ok(std::env::var(KEYSTORE_PASSPHRASE_ENV).ok())
+    }
+
+    /// Returns the signer to use: the signing daemon if one is configured, otherwise the
+    /// `keystore`.
+    #[cfg(not(web))]
+    pub fn make_signer(&self, keystore: InMemorySigner) -> Result<ClientSigner, Error> {
+        let Some(url) = &self.remote_signer else {
+            return Ok(ClientSigner::InMemory(keystore));
+        };
+        let token_file = self
+            .remote_signer_token_file
+            .as_ref()
+            .ok_or(Error::MissingRemoteSignerToken)?;
+        let token = std::fs::read_to_string(token_file)?;
+        Ok(ClientSigner::Remote(RemoteSigner::new(
+            url.clone(),
+            token.trim(),
+        )))
+    }
+
     /// Creates [`TimingConfig`] with the corresponding values.
     #[cfg(not(web))]
     pub(crate) fn to_timing_config(&self) -> TimingConfig {
@@ -248,6 +306,11 @@ pub struct ChainOwnershipConfig {
     #[arg(long)]
     pub open_multi_leader_rounds: bool,
 
+    /// The total weight of the regular owners that must sign each block proposal. If this is
+    /// not given, a single owner's signature is enough.
+    #[arg(long)]
+    pub approval_threshold: Option<u64>,
+
     /// The duration of the fast round, in milliseconds.
     #[arg(long = "fast-round-ms", value_parser = util::parse_millis_delta)]
     pub fast_round_duration: Option<TimeDelta>,
@@ -290,6 +353,7 @@ impl TryFrom<ChainOwnershipConfig> for ChainOwnership {
             multi_leader_rounds,
             fast_round_duration,
             open_multi_leader_rounds,
+            approval_threshold,
             base_timeout,
             timeout_increment,
             fallback_duration,
@@ -312,13 +376,20 @@ impl TryFrom<ChainOwnershipConfig> for ChainOwnership {
             timeout_increment,
             fallback_duration,
         };
-        Ok(ChainOwnership {
+        let ownership = ChainOwnership {
             super_owners,
             owners,
             multi_leader_rounds,
             open_multi_leader_rounds,
             timeout_config,
-        })
+            approval_threshold,
+        };
+        if let Some(threshold) = approval_threshold {
+            if !ownership.is_approval_threshold_reachable() {
+                return Err(Error::UnreachableApprovalThreshold { threshold });
+            }
+        }
+        Ok(ownership)
     }
 }
 
@@ -5,7 +5,10 @@
 use std::iter::IntoIterator;
 
 use linera_base::{
-    crypto::{AccountPublicKey, BcsSignable, CryptoHash, ValidatorPublicKey, ValidatorSecretKey},
+    crypto::{
+        AccountPublicKey, BcsSignable, Bls12381PublicKey, Bls12381SecretKey, Bls12381Signature,
+        CryptoHash, ValidatorPublicKey, ValidatorSecretKey,
+    },
     data_types::{
         Amount, Blob, ChainDescription, ChainOrigin, Epoch, InitialChainConfig, NetworkDescription,
         Timestamp,
@@ -53,6 +56,26 @@ pub struct ValidatorConfig {
     pub account_key: AccountPublicKey,
     /// The network configuration for the validator.
     pub network: ValidatorPublicNetworkConfig,
+    /// The BLS12-381 public key of the validator, used for aggregate certificate signatures.
+    pub aggregate_public_key: Option<Bls12381PublicKey>,
+    /// The proof of possession of the secret key for `aggregate_public_key`.
+    pub aggregate_key_proof: Option<Bls12381Signature>,
+}
+
+impl ValidatorConfig {
+    /// Returns the aggregate public key and its proof of possession, if the proof is valid.
+    pub fn verified_aggregate_key(&self) -> Option<(Bls12381PublicKey, Bls12381Signature)> {
+        let public_key = self.aggregate_public_key?;
+        let proof = self.aggregate_key_proof?;
+        if let Err(error) = public_key.verify_proof_of_possession(&proof) {
+            tracing::warn!(
+                "Ignoring the aggregate key of validator {}: {error}",
+                self.public_key
+            );
+            return None;
+        }
+        Some((public_key, proof))
+    }
 }
 
 /// The private configuration of a validator service.
@@ -60,6 +83,8 @@ pub struct ValidatorConfig {
 pub struct ValidatorServerConfig {
     pub validator: ValidatorConfig,
     pub validator_secret: ValidatorSecretKey,
+    /// The BLS12-381 secret key of the validator, if it signs votes for aggregate certificates.
+    pub aggregate_secret: Option<Bls12381SecretKey>,
     pub internal_network: ValidatorInternalNetworkConfig,
 }
 
@@ -75,12 +100,15 @@ impl CommitteeConfig {
             .validators
             .into_iter()
             .map(|v| {
+                let aggregate_key = v.verified_aggregate_key();
                 (
                     v.public_key,
                     ValidatorState {
                         network_address: v.network.to_string(),
                         votes: 100,
                         account_public_key: v.account_key,
+                        aggregate_public_key: aggregate_key.map(|(public_key, _)| public_key),
+                        aggregate_key_proof: aggregate_key.map(|(_, proof)| proof),
                     },
                 )
             })
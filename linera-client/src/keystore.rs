@@ -0,0 +1,218 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! An encrypted format for files holding secret keys.
+//!
+//! The secrets are serialized in JSON and encrypted with XChaCha20-Poly1305, under a key
+//! derived from a passphrase with Argon2id and a random salt.
+
+#[cfg(feature = "fs")]
+use std::{ops::Deref, path::Path};
+
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    XChaCha20Poly1305, XNonce,
+};
+use linera_base::crypto::InMemorySigner;
+#[cfg(feature = "fs")]
+use linera_persistent::{self as persistent, Persist};
+use rand::RngCore as _;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// The current version of the format.
+const KEYSTORE_VERSION: u32 = 1;
+/// The size of the salt of the key derivation.
+const SALT_SIZE: usize = 16;
+/// The size of the nonce of the encryption.
+const NONCE_SIZE: usize = 24;
+/// The size of the key derived from the passphrase.
+const KEY_SIZE: usize = 32;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("unsupported keystore version {0}")]
+    UnsupportedVersion(u32),
+    #[error("invalid passphrase or corrupted keystore")]
+    Decryption,
+    #[error("failed to derive a key from the passphrase: {0}")]
+    KeyDerivation(argon2::Error),
+    #[error("invalid keystore content: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("the keystore is encrypted: a passphrase is required")]
+    MissingPassphrase,
+    #[cfg(feature = "fs")]
+    #[error("failed to read the secret: {0}")]
+    ReadSecret(std::io::Error),
+    #[cfg(feature = "fs")]
+    #[error(transparent)]
+    Persistence(#[from] persistent::file::Error),
+}
+
+/// Secrets encrypted under a passphrase.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EncryptedKeystore {
+    /// The version of the format.
+    version: u32,
+    /// The salt of the key derivation.
+    salt: Vec<u8>,
+    /// The nonce of the encryption.
+    nonce: Vec<u8>,
+    /// The encrypted JSON serialization of the secrets.
+    ciphertext: Vec<u8>,
+}
+
+impl EncryptedKeystore {
+    /// Encrypts the `secrets` under the given `passphrase`.
+    pub fn seal<T: Serialize>(secrets: &T, passphrase: &str) -> Result<Self, Error> {
+        let mut salt = vec![0; SALT_SIZE];
+        let mut nonce = vec![0; NONCE_SIZE];
+        rand::rngs::OsRng.fill_bytes(&mut salt);
+        rand::rngs::OsRng.fill_bytes(&mut nonce);
+        let cipher = Self::cipher(passphrase, &salt)?;
+        let plaintext = serde_json::to_vec(secrets)?;
+        let ciphertext = cipher
+            .encrypt(XNonce::from_slice(&nonce), plaintext.as_slice())
+            .expect("encryption should not fail");
+        Ok(Self {
+            version: KEYSTORE_VERSION,
+            salt,
+            nonce,
+            ciphertext,
+        })
+    }
+
+    /// Decrypts the secrets with the given `passphrase`.
+    pub fn open<T: DeserializeOwned>(&self, passphrase: &str) -> Result<T, Error> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(Error::UnsupportedVersion(self.version));
+        }
+        if self.nonce.len() != NONCE_SIZE {
+            return Err(Error::Decryption);
+        }
+        let cipher = Self::cipher(passphrase, &self.salt)?;
+        let plaintext = cipher
+            .decrypt(XNonce::from_slice(&self.nonce), self.ciphertext.as_slice())
+            .map_err(|_| Error::Decryption)?;
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    /// Returns the cipher for the key derived from `passphrase` and `salt`.
+    fn cipher(passphrase: &str, salt: &[u8]) -> Result<XChaCha20Poly1305, Error> {
+        let mut key = [0; KEY_SIZE];
+        argon2::Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .map_err(Error::KeyDerivation)?;
+        Ok(XChaCha20Poly1305::new_from_slice(&key).expect("the key has the right size"))
+    }
+}
+
+/// Reads a secret that must not appear on the command line: from `file` if given, else
+/// from the environment variable `env_var` if set, else from the terminal after printing
+/// `prompt`, without echoing it.
+#[cfg(feature = "fs")]
+pub fn read_secret(file: Option<&Path>, env_var: &str, prompt: &str) -> Result<String, Error> {
+    if let Some(file) = file {
+        let secret = fs_err::read_to_string(file).map_err(Error::ReadSecret)?;
+        return Ok(secret.trim_end_matches(['\n', '\r']).to_owned());
+    }
+    if let Ok(secret) = std::env::var(env_var) {
+        return Ok(secret);
+    }
+    rpassword::prompt_password(prompt).map_err(Error::ReadSecret)
+}
+
+/// The content of a keystore file: the keys of an [`InMemorySigner`], in clear or
+/// encrypted.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeystoreContent {
+    Encrypted(EncryptedKeystore),
+    Plain(InMemorySigner),
+}
+
+/// A keystore file holding the keys of an [`InMemorySigner`].
+///
+/// An encrypted keystore is decrypted when read and encrypted again under the same
+/// passphrase whenever it is persisted.
+#[cfg(feature = "fs")]
+pub struct Keystore {
+    file: persistent::File<KeystoreContent>,
+    signer: InMemorySigner,
+    passphrase: Option<String>,
+}
+
+#[cfg(feature = "fs")]
+impl Keystore {
+    /// Creates a keystore file at `path` holding the keys of `signer`, encrypted if a
+    /// `passphrase` is given. Nothing is written until the keystore is persisted.
+    pub fn create(
+        path: &Path,
+        signer: InMemorySigner,
+        passphrase: Option<String>,
+    ) -> Result<Self, Error> {
+        let file = persistent::File::new(path, KeystoreContent::Plain(signer.clone()))?;
+        Ok(Keystore {
+            file,
+            signer,
+            passphrase,
+        })
+    }
+
+    /// Reads the keystore file at `path`. The `passphrase` is required if the keystore is
+    /// encrypted, and ignored otherwise.
+    pub fn read(path: &Path, passphrase: Option<&str>) -> Result<Self, Error> {
+        let file = persistent::File::<KeystoreContent>::read(path)?;
+        let (signer, passphrase) = match &*file {
+            KeystoreContent::Encrypted(keystore) => {
+                let passphrase = passphrase.ok_or(Error::MissingPassphrase)?;
+                (keystore.open(passphrase)?, Some(passphrase.to_owned()))
+            }
+            KeystoreContent::Plain(signer) => (signer.clone(), None),
+        };
+        Ok(Keystore {
+            file,
+            signer,
+            passphrase,
+        })
+    }
+
+    /// Returns whether the keystore is encrypted.
+    pub fn is_encrypted(&self) -> bool {
+        self.passphrase.is_some()
+    }
+}
+
+#[cfg(feature = "fs")]
+impl Deref for Keystore {
+    type Target = InMemorySigner;
+
+    fn deref(&self) -> &InMemorySigner {
+        &self.signer
+    }
+}
+
+#[cfg(feature = "fs")]
+impl Persist for Keystore {
+    type Error = Error;
+
+    fn as_mut(&mut self) -> &mut InMemorySigner {
+        &mut self.signer
+    }
+
+    /// Writes the keys to disk, encrypting them if the keystore has a passphrase.
+    async fn persist(&mut self) -> Result<(), Error> {
+        *self.file = match &self.passphrase {
+            Some(passphrase) => {
+                KeystoreContent::Encrypted(EncryptedKeystore::seal(&self.signer, passphrase)?)
+            }
+            None => KeystoreContent::Plain(self.signer.clone()),
+        };
+        self.file.persist().await?;
+        Ok(())
+    }
+
+    /// Takes the keys out, releasing the lock on the keystore file.
+    fn into_value(self) -> InMemorySigner {
+        self.signer
+    }
+}
@@ -13,6 +13,12 @@ pub mod client_metrics;
 pub mod client_options;
 pub mod config;
 mod error;
+#[cfg(not(web))]
+pub mod keystore;
+#[cfg(not(web))]
+pub mod remote_signer;
+#[cfg(not(web))]
+pub mod signer;
 pub mod util;
 pub mod wallet;
 
@@ -0,0 +1,201 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A [`Signer`] that delegates to an external signing daemon, so that the keys of a client
+//! don't need to be stored in its keystore.
+//!
+//! The daemon serves JSON requests over HTTP, authenticated with a bearer token in the
+//! `Authorization` header:
+//!
+//! * `POST /v1/sign` with a [`SignRequest`] returns a [`SignResponse`]. The request may
+//!   contain the content whose hash is signed. Daemons enforcing policies on a key refuse
+//!   to sign for it without the content.
+//! * `POST /v1/contains_key` with a [`ContainsKeyRequest`] returns a
+//!   [`ContainsKeyResponse`].
+//!
+//! Failures are reported with an [`ErrorResponse`] and the status 401 if the token is
+//! missing or wrong, 404 if the daemon has no key for the owner, 403 if a policy forbids
+//! the signature, or 400 if the request is invalid.
+
+use linera_base::{
+    crypto::{AccountSignature, CryptoHash, SignedContent, Signer},
+    identifiers::AccountOwner,
+};
+use reqwest::StatusCode;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+/// The path of the signing endpoint.
+pub const SIGN_PATH: &str = "/v1/sign";
+/// The path of the endpoint checking for known keys.
+pub const CONTAINS_KEY_PATH: &str = "/v1/contains_key";
+
+/// A request to sign a value.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignRequest {
+    /// The owner of the signing key.
+    pub owner: AccountOwner,
+    /// The value to sign.
+    pub value: CryptoHash,
+    /// The content whose hash is `value`, if known.
+    pub content: Option<SignedPayload>,
+}
+
+/// The serialized content of a [`SignRequest`]. See [`SignedContent`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SignedPayload {
+    /// The BCS bytes of the `ProposalContent` of a block proposal.
+    BlockProposal(Vec<u8>),
+    /// The BCS bytes of a `ProposedBlock` approved by an owner of a multi-owner chain.
+    BlockApproval(Vec<u8>),
+}
+
+/// The response to a [`SignRequest`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SignResponse {
+    pub signature: AccountSignature,
+}
+
+/// A request to check whether the daemon holds the key of an owner.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ContainsKeyRequest {
+    pub owner: AccountOwner,
+}
+
+/// The response to a [`ContainsKeyRequest`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ContainsKeyResponse {
+    pub contains_key: bool,
+}
+
+/// The body of an unsuccessful response.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub error: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("the remote signer rejected the bearer token: {0}")]
+    Unauthorized(String),
+    #[error("no key found for the given owner")]
+    NoSuchOwner,
+    #[error("the remote signer refused to sign: {0}")]
+    Refused(String),
+    #[error("the remote signer failed with status {status}: {message}")]
+    Failed { status: u16, message: String },
+    #[error("failed to reach the remote signer: {0}")]
+    Http(#[from] reqwest::Error),
+}
+
+/// A [`Signer`] sending its requests to a signing daemon.
+#[derive(Clone)]
+pub struct RemoteSigner {
+    client: reqwest::Client,
+    url: String,
+    token: String,
+}
+
+impl std::fmt::Debug for RemoteSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteSigner")
+            .field("url", &self.url)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RemoteSigner {
+    /// Creates a signer for the daemon at the given base `url`, authenticating with the
+    /// bearer `token`.
+    pub fn new(url: impl Into<String>, token: impl Into<String>) -> Self {
+        let url = url.into().trim_end_matches('/').to_string();
+        Self {
+            client: reqwest::Client::new(),
+            url,
+            token: token.into(),
+        }
+    }
+
+    /// Sends the `request` to the endpoint at `path`.
+    async fn post<Request, Response>(
+        &self,
+        path: &str,
+        request: &Request,
+    ) -> Result<Response, Error>
+    where
+        Request: Serialize,
+        Response: DeserializeOwned,
+    {
+        let response = self
+            .client
+            .post(format!("{}{}", self.url, path))
+            .bearer_auth(&self.token)
+            .json(request)
+            .send()
+            .await?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response.json().await?);
+        }
+        let message = match response.json::<ErrorResponse>().await {
+            Ok(response) => response.error,
+            Err(_) => status.to_string(),
+        };
+        Err(match status {
+            StatusCode::UNAUTHORIZED => Error::Unauthorized(message),
+            StatusCode::NOT_FOUND => Error::NoSuchOwner,
+            StatusCode::FORBIDDEN => Error::Refused(message),
+            _ => Error::Failed {
+                status: status.as_u16(),
+                message,
+            },
+        })
+    }
+}
+
+impl Signer for RemoteSigner {
+    type Error = Error;
+
+    async fn sign(
+        &self,
+        owner: &AccountOwner,
+        value: &CryptoHash,
+    ) -> Result<AccountSignature, Error> {
+        let request = SignRequest {
+            owner: *owner,
+            value: *value,
+            content: None,
+        };
+        let response: SignResponse = self.post(SIGN_PATH, &request).await?;
+        Ok(response.signature)
+    }
+
+    async fn sign_content(
+        &self,
+        owner: &AccountOwner,
+        value: &CryptoHash,
+        content: SignedContent<'_>,
+    ) -> Result<AccountSignature, Error> {
+        let request = SignRequest {
+            owner: *owner,
+            value: *value,
+            content: Some(content.into()),
+        };
+        let response: SignResponse = self.post(SIGN_PATH, &request).await?;
+        Ok(response.signature)
+    }
+
+    async fn contains_key(&self, owner: &AccountOwner) -> Result<bool, Error> {
+        let request = ContainsKeyRequest { owner: *owner };
+        let response: ContainsKeyResponse = self.post(CONTAINS_KEY_PATH, &request).await?;
+        Ok(response.contains_key)
+    }
+}
+
+impl From<SignedContent<'_>> for SignedPayload {
+    fn from(content: SignedContent<'_>) -> Self {
+        match content {
+            SignedContent::BlockProposal(bytes) => SignedPayload::BlockProposal(bytes.to_vec()),
+            SignedContent::BlockApproval(bytes) => SignedPayload::BlockApproval(bytes.to_vec()),
+        }
+    }
+}
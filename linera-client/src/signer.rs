@@ -0,0 +1,71 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The [`Signer`] used by clients, selected by the [`ClientContextOptions`].
+//!
+//! [`ClientContextOptions`]: crate::client_options::ClientContextOptions
+
+use linera_base::{
+    crypto::{AccountSignature, CryptoHash, InMemorySigner, SignedContent, Signer},
+    identifiers::AccountOwner,
+};
+
+use crate::remote_signer::{self, RemoteSigner};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    InMemory(<InMemorySigner as Signer>::Error),
+    #[error(transparent)]
+    Remote(#[from] remote_signer::Error),
+}
+
+/// A signer using either the keys of the local keystore or those of a signing daemon.
+#[derive(Clone)]
+pub enum ClientSigner {
+    /// The keys are in the keystore.
+    InMemory(InMemorySigner),
+    /// The keys are held by a signing daemon.
+    Remote(RemoteSigner),
+}
+
+impl Signer for ClientSigner {
+    type Error = Error;
+
+    async fn sign(
+        &self,
+        owner: &AccountOwner,
+        value: &CryptoHash,
+    ) -> Result<AccountSignature, Error> {
+        match self {
+            ClientSigner::InMemory(signer) => {
+                signer.sign(owner, value).await.map_err(Error::InMemory)
+            }
+            ClientSigner::Remote(signer) => Ok(signer.sign(owner, value).await?),
+        }
+    }
+
+    async fn sign_content(
+        &self,
+        owner: &AccountOwner,
+        value: &CryptoHash,
+        content: SignedContent<'_>,
+    ) -> Result<AccountSignature, Error> {
+        match self {
+            ClientSigner::InMemory(signer) => signer
+                .sign_content(owner, value, content)
+                .await
+                .map_err(Error::InMemory),
+            ClientSigner::Remote(signer) => Ok(signer.sign_content(owner, value, content).await?),
+        }
+    }
+
+    async fn contains_key(&self, owner: &AccountOwner) -> Result<bool, Error> {
+        match self {
+            ClientSigner::InMemory(signer) => {
+                signer.contains_key(owner).await.map_err(Error::InMemory)
+            }
+            ClientSigner::Remote(signer) => Ok(signer.contains_key(owner).await?),
+        }
+    }
+}
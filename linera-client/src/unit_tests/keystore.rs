@@ -0,0 +1,45 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use linera_base::crypto::{InMemorySigner, Mnemonic, SignatureScheme};
+use linera_persistent::{Persist as _, PersistExt as _};
+
+use crate::keystore::{self, Keystore};
+
+/// Tests that an encrypted keystore can only be read with its passphrase, and that it
+/// keeps deriving keys from its mnemonic.
+#[tokio::test]
+async fn test_encrypted_keystore_file() -> anyhow::Result<()> {
+    let tmp_dir = tempfile::tempdir()?;
+    let path = tmp_dir.path().join("keystore.json");
+    let mnemonic: Mnemonic =
+        "test test test test test test test test test test test junk".parse()?;
+
+    let passphrase = Some("passphrase".to_string());
+    let signer = InMemorySigner::from_mnemonic(mnemonic, SignatureScheme::Ed25519);
+    let mut keystore = Keystore::create(&path, signer, passphrase)?;
+    let first = keystore.mutate(|signer| signer.generate_new()).await?;
+    assert!(keystore.is_encrypted());
+    drop(keystore);
+
+    // The keys are not stored in clear.
+    let content = std::fs::read_to_string(&path)?;
+    assert!(!content.contains("junk"));
+
+    assert!(matches!(
+        Keystore::read(&path, None),
+        Err(keystore::Error::MissingPassphrase)
+    ));
+    assert!(matches!(
+        Keystore::read(&path, Some("wrong passphrase")),
+        Err(keystore::Error::Decryption)
+    ));
+
+    let mut keystore = Keystore::read(&path, Some("passphrase"))?;
+    assert!(keystore.mnemonic().is_some());
+    let second = keystore.mutate(|signer| signer.generate_new()).await?;
+    assert_ne!(first, second);
+    let keys = keystore.into_value().keys();
+    assert_eq!(keys.len(), 2);
+    Ok(())
+}
@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod chain_listener;
+#[cfg(feature = "fs")]
+mod keystore;
 mod util;
 #[cfg(feature = "fs")]
 mod wallet;
@@ -24,6 +24,8 @@ pub fn make_genesis_config(builder: &TestBuilder<MemoryStorageBuilder>) -> Genes
             public_key: *public_key,
             network: network.clone(),
             account_key: state.account_public_key,
+            aggregate_public_key: None,
+            aggregate_key_proof: None,
         })
         .collect();
     let mut genesis_chains = builder.genesis_chains().into_iter();
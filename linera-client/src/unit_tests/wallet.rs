@@ -64,6 +64,7 @@ async fn test_save_wallet_with_pending_blobs() -> anyhow::Result<()> {
             previous_block_hash: None,
         },
         blobs: vec![Blob::new_data(b"blob".to_vec())],
+        approvals: Vec::new(),
     });
     let mut context = ClientContext::new_test_client_context(storage, wallet, signer);
     context.save_wallet().await?;
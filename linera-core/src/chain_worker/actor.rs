@@ -18,7 +18,7 @@ use linera_base::{
     identifiers::{ApplicationId, BlobId, ChainId},
 };
 use linera_chain::{
-    data_types::{BlockProposal, MessageBundle, ProposedBlock},
+    data_types::{BlockProposal, FeeEstimate, MessageBundle, ProposedBlock},
     types::{Block, ConfirmedBlockCertificate, TimeoutCertificate, ValidatedBlockCertificate},
     ChainStateView,
 };
@@ -83,6 +83,15 @@ where
         callback: oneshot::Sender<Result<(Block, ChainInfoResponse), WorkerError>>,
     },
 
+    /// Execute a block without persisting anything, and estimate its fees.
+    EstimateBlockExecution {
+        block: ProposedBlock,
+        round: Option<u32>,
+        published_blobs: Vec<Blob>,
+        #[debug(skip)]
+        callback: oneshot::Sender<Result<FeeEstimate, WorkerError>>,
+    },
+
     /// Process a leader timeout issued for this multi-owner chain.
     ProcessTimeout {
         certificate: TimeoutCertificate,
@@ -5,7 +5,10 @@
 
 use std::sync::Arc;
 
-use linera_base::{crypto::ValidatorSecretKey, time::Duration};
+use linera_base::{
+    crypto::{Bls12381SecretKey, ValidatorSecretKey},
+    time::Duration,
+};
 
 use crate::CHAIN_INFO_MAX_RECEIVED_LOG_ENTRIES;
 
@@ -15,6 +18,8 @@ pub struct ChainWorkerConfig {
     /// The signature key pair of the validator. The key may be missing for replicas
     /// without voting rights (possibly with a partial view of chains).
     pub key_pair: Option<Arc<ValidatorSecretKey>>,
+    /// The BLS12-381 key of the validator, if it also signs votes for aggregate certificates.
+    pub aggregate_key: Option<Arc<Bls12381SecretKey>>,
     /// Whether inactive chains are allowed in storage.
     pub allow_inactive_chains: bool,
     /// Whether new messages from deprecated epochs are allowed.
@@ -51,12 +56,24 @@ impl ChainWorkerConfig {
     pub fn key_pair(&self) -> Option<&ValidatorSecretKey> {
         self.key_pair.as_ref().map(Arc::as_ref)
     }
+
+    /// Configures the `aggregate_key` in this [`ChainWorkerConfig`].
+    pub fn with_aggregate_key(mut self, aggregate_key: Option<Bls12381SecretKey>) -> Self {
+        self.aggregate_key = aggregate_key.map(Arc::new);
+        self
+    }
+
+    /// Gets a reference to the [`Bls12381SecretKey`], if available.
+    pub fn aggregate_key(&self) -> Option<&Bls12381SecretKey> {
+        self.aggregate_key.as_ref().map(Arc::as_ref)
+    }
 }
 
 impl Default for ChainWorkerConfig {
     fn default() -> Self {
         Self {
             key_pair: None,
+            aggregate_key: None,
             allow_inactive_chains: false,
             allow_messages_from_deprecated_epochs: false,
             long_lived_services: false,
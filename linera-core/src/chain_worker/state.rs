@@ -22,8 +22,8 @@ use linera_base::{
 };
 use linera_chain::{
     data_types::{
-        BlockExecutionOutcome, BlockProposal, IncomingBundle, MessageAction, MessageBundle,
-        OriginalProposal, ProposalContent, ProposedBlock,
+        BlockExecutionOutcome, BlockProposal, FeeEstimate, IncomingBundle, MessageAction,
+        MessageBundle, OriginalProposal, ProposalContent, ProposedBlock,
     },
     manager,
     types::{Block, ConfirmedBlockCertificate, TimeoutCertificate, ValidatedBlockCertificate},
@@ -145,6 +145,17 @@ where
                         .await,
                 )
                 .is_ok(),
+            ChainWorkerRequest::EstimateBlockExecution {
+                block,
+                round,
+                published_blobs,
+                callback,
+            } => callback
+                .send(
+                    self.estimate_block_execution(block, round, &published_blobs)
+                        .await,
+                )
+                .is_ok(),
             ChainWorkerRequest::ProcessTimeout {
                 certificate,
                 callback,
@@ -610,6 +621,7 @@ where
                 },
             original_proposal,
             signature: _,
+            approvals: _,
         } = proposal;
 
         let mut maybe_blobs = self
@@ -696,6 +708,7 @@ where
         self.chain.manager.create_final_vote(
             certificate,
             self.config.key_pair(),
+            self.config.aggregate_key(),
             self.storage.clock().current_time(),
             blobs,
         )?;
@@ -854,9 +867,13 @@ where
             .await?;
         let oracle_responses = Some(block.body.oracle_responses.clone());
         let (proposed_block, outcome) = block.clone().into_proposal();
-        let verified_outcome = if let Some(mut execution_state) =
+        let cached_execution_state = if chain.records_execution_traces() {
+            // Execute the block again so that its execution traces are recorded.
+            None
+        } else {
             self.execution_state_cache.remove(&outcome.state_hash)
-        {
+        };
+        let verified_outcome = if let Some(mut execution_state) = cached_execution_state {
             chain.execution_state = execution_state
                 .with_context(|ctx| {
                     chain
@@ -1148,11 +1165,17 @@ where
         let epoch = chain.execution_state.system.epoch.get();
         let chain_id = chain.chain_id();
         let key_pair = self.config.key_pair();
+        let aggregate_key = self.config.aggregate_key();
         let local_time = self.storage.clock().current_time();
-        if chain
-            .manager
-            .create_timeout_vote(chain_id, height, round, *epoch, key_pair, local_time)?
-        {
+        if chain.manager.create_timeout_vote(
+            chain_id,
+            height,
+            round,
+            *epoch,
+            key_pair,
+            aggregate_key,
+            local_time,
+        )? {
             self.save().await?;
         }
         Ok(())
@@ -1173,9 +1196,10 @@ where
                 let chain_id = chain.chain_id();
                 let height = chain.tip_state.get().next_block_height;
                 let key_pair = self.config.key_pair();
+                let aggregate_key = self.config.aggregate_key();
                 if chain
                     .manager
-                    .vote_fallback(chain_id, height, *epoch, key_pair)
+                    .vote_fallback(chain_id, height, *epoch, key_pair, aggregate_key)
                 {
                     self.save().await?;
                 }
@@ -1313,6 +1337,34 @@ where
         Ok((outcome.with(block), response))
     }
 
+    /// Executes a block without persisting any changes to the state or caching the result,
+    /// and returns the resources and fees it used.
+    #[instrument(target = "telemetry_only", skip_all, fields(
+        chain_id = %self.chain_id(),
+        block_height = %block.height
+    ))]
+    pub(super) async fn estimate_block_execution(
+        &mut self,
+        block: ProposedBlock,
+        round: Option<u32>,
+        published_blobs: &[Blob],
+    ) -> Result<FeeEstimate, WorkerError> {
+        let local_time = self.storage.clock().current_time();
+        self.chain.initialize_if_needed(local_time).await?;
+        let (_, committee) = self.chain.current_committee()?;
+        block.check_proposal_size(committee.policy().maximum_block_proposal_size)?;
+
+        let (outcome, resources) = Box::pin(self.chain.execute_block_and_track_resources(
+            &block,
+            local_time,
+            round,
+            published_blobs,
+            None,
+        ))
+        .await?;
+        Ok(FeeEstimate::new(outcome, resources))
+    }
+
     /// Validates and executes a block proposed to extend this chain.
     #[instrument(target = "telemetry_only", skip_all, fields(
         chain_id = %self.chain_id(),
@@ -1332,6 +1384,7 @@ where
             content,
             original_proposal,
             signature: _,
+            approvals: _,
         } = &proposal;
         let block = &content.block;
         let chain = &self.chain;
@@ -1342,7 +1395,7 @@ where
         block.check_proposal_size(policy.maximum_block_proposal_size)?;
         // Check the authentication of the block.
         ensure!(
-            chain.manager.verify_owner(&owner, proposal.content.round)?,
+            chain.manager.verify_owner(&proposal)?,
             WorkerError::InvalidOwner
         );
         let old_round = self.chain.manager.current_round();
@@ -1366,6 +1419,7 @@ where
                     },
                     signature: *signature,
                     original_proposal: None,
+                    approvals: Vec::new(),
                 };
                 let super_owner = original_proposal.owner();
                 ensure!(
@@ -1446,8 +1500,9 @@ where
             .get_required_blobs(proposal.expected_blob_ids(), &created_blobs)
             .await?;
         let key_pair = self.config.key_pair();
+        let aggregate_key = self.config.aggregate_key();
         let manager = &mut self.chain.manager;
-        match manager.create_vote(proposal, block, key_pair, local_time, blobs)? {
+        match manager.create_vote(proposal, block, key_pair, aggregate_key, local_time, blobs)? {
             // Cache the value we voted on, so the client doesn't have to send it again.
             Some(Either::Left(vote)) => {
                 self.block_values.insert(Cow::Borrowed(vote.value.inner()));
@@ -4,7 +4,7 @@
 
 use std::{collections::BTreeSet, sync::Arc};
 
-use linera_base::data_types::Blob;
+use linera_base::{crypto::AccountSignature, data_types::Blob};
 use linera_chain::data_types::ProposedBlock;
 use tokio::sync::Mutex;
 
@@ -62,7 +62,27 @@ impl ChainClientState {
             block.published_blob_ids(),
             BTreeSet::from_iter(blobs.iter().map(Blob::id))
         );
-        self.pending_proposal = Some(PendingProposal { block, blobs });
+        self.pending_proposal = Some(PendingProposal {
+            block,
+            blobs,
+            approvals: Vec::new(),
+        });
+    }
+
+    /// Adds approvals to the pending proposal, skipping owners who already approved it.
+    pub(super) fn add_pending_approvals(&mut self, approvals: Vec<AccountSignature>) {
+        let Some(pending) = &mut self.pending_proposal else {
+            return;
+        };
+        for approval in approvals {
+            if !pending
+                .approvals
+                .iter()
+                .any(|existing| existing.owner() == approval.owner())
+            {
+                pending.approvals.push(approval);
+            }
+        }
     }
 
     pub(super) fn update_from_info(&mut self, info: &ChainInfo) {
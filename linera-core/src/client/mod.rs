@@ -20,7 +20,10 @@ use futures::{
 use linera_base::prometheus_util::MeasureLatency as _;
 use linera_base::{
     abi::Abi,
-    crypto::{signer, AccountPublicKey, CryptoHash, Signer, ValidatorPublicKey},
+    crypto::{
+        signer, AccountPublicKey, AccountSignature, CryptoHash, SignedContent, Signer,
+        ValidatorPublicKey,
+    },
     data_types::{
         Amount, ApplicationPermissions, ArithmeticError, Blob, BlobContent, BlockHeight,
         ChainDescription, Epoch, Round, Timestamp,
@@ -37,8 +40,8 @@ use linera_base::{
 use linera_base::{data_types::Bytecode, vm::VmRuntime};
 use linera_chain::{
     data_types::{
-        BlockProposal, ChainAndHeight, IncomingBundle, LiteVote, MessageAction, ProposedBlock,
-        Transaction,
+        BlockProposal, ChainAndHeight, FeeEstimate, IncomingBundle, LiteVote, MessageAction,
+        ProposedBlock, Transaction,
     },
     manager::LockingBlock,
     types::{
@@ -619,13 +622,31 @@ impl<Env: Environment> Client<Env> {
             committee,
             |vote: &LiteVote| (vote.value.value_hash, vote.round),
             |remote_node| {
+                let public_key = remote_node.public_key;
+                let aggregate_public_key = committee
+                    .validators()
+                    .get(&public_key)
+                    .and_then(|state| state.aggregate_public_key);
                 let mut updater = ValidatorUpdater {
                     remote_node,
                     local_node: self.local_node.clone(),
                     admin_id: self.admin_id,
                 };
                 let action = action.clone();
-                Box::pin(async move { updater.send_chain_update(action).await })
+                Box::pin(async move {
+                    let mut vote = updater.send_chain_update(action).await?;
+                    // An invalid aggregate signature is dropped, so that it can't prevent
+                    // aggregating the other votes. The individual signature was checked.
+                    if let Err(error) = vote.check_aggregate_signature(aggregate_public_key) {
+                        warn!(
+                            validator = %public_key,
+                            %error,
+                            "Dropping an invalid aggregate signature from a vote"
+                        );
+                        vote.aggregate_signature = None;
+                    }
+                    Ok::<_, ChainClientError>(vote)
+                })
             },
             self.options.grace_period,
         )
@@ -639,18 +660,32 @@ impl<Env: Environment> Client<Env> {
                 expected_round: action.round(),
             }
         );
-        // Certificate is valid because
+        // If a quorum of the voters sent valid aggregate signatures, combine them into a single
+        // signature. This is verified once here, and we fall back to individual signatures if
+        // it fails. Otherwise, the certificate is valid because
         // * `communicate_with_quorum` ensured a sufficient "weight" of
         // (non-error) answers were returned by validators.
         // * each answer is a vote signed by the expected validator.
-        let certificate = LiteCertificate::try_from_votes(votes)
-            .ok_or_else(|| {
-                ChainClientError::InternalError("Vote values or rounds don't match; this is a bug")
-            })?
-            .with_value(value)
-            .ok_or_else(|| {
-                ChainClientError::ProtocolError("A quorum voted for an unexpected value")
-            })?;
+        let certificate = match LiteCertificate::try_aggregate_votes(&votes, committee) {
+            Some(certificate) => Some(certificate),
+            None => {
+                if votes
+                    .iter()
+                    .any(|(_, vote)| vote.aggregate_signature.is_some())
+                {
+                    warn!(
+                        "Failed to aggregate the votes of a quorum; falling back to \
+                         individual signatures"
+                    );
+                }
+                LiteCertificate::try_from_votes(votes)
+            }
+        }
+        .ok_or_else(|| {
+            ChainClientError::InternalError("Vote values or rounds don't match; this is a bug")
+        })?
+        .with_value(value)
+        .ok_or_else(|| ChainClientError::ProtocolError("A quorum voted for an unexpected value"))?;
         Ok(certificate)
     }
 
@@ -1276,7 +1311,7 @@ impl<Env: Environment> Client<Env> {
 
     /// Attempts to execute the block locally. If any incoming message execution fails, that
     /// message is rejected and execution is retried, until the block accepts only messages
-    /// that succeed.
+    /// that succeed. Failing scheduled operations are discarded in the same way.
     // TODO(#2806): Measure how failing messages affect the execution times.
     #[tracing::instrument(level = "trace", skip(self, block))]
     async fn stage_block_execution_and_discard_failing_messages(
@@ -1324,6 +1359,24 @@ impl<Env: Environment> Client<Env> {
                     message.action = MessageAction::Reject;
                     continue;
                 }
+                if let ChainError::ExecutionError(error, ChainExecutionContext::Operation(index)) =
+                    &**chain_error
+                {
+                    if let Some(Transaction::ExecuteOperation(Operation::System(operation))) =
+                        block.transactions.get_mut(*index as usize)
+                    {
+                        if let SystemOperation::ExecuteScheduledOperation(key) = **operation {
+                            // A failing scheduled operation would block the chain forever:
+                            // discard it instead, refunding its budget.
+                            info!(
+                                %error, ?key,
+                                "Scheduled operation failed to execute locally and will be discarded."
+                            );
+                            **operation = SystemOperation::DiscardScheduledOperation(key);
+                            continue;
+                        }
+                    }
+                }
             }
             return result;
         }
@@ -1893,6 +1946,24 @@ impl<Env: Environment> ChainClient<Env> {
         Ok(Some(SystemOperation::UpdateStreams(updates).into()))
     }
 
+    /// Returns an `ExecuteScheduledOperation` operation for each operation that was scheduled
+    /// on this client's chain and is due according to the local clock.
+    #[instrument(level = "trace")]
+    async fn collect_scheduled_operations(&self) -> Result<Vec<Operation>, ChainClientError> {
+        let local_time = self.storage_client().clock().current_time();
+        let keys = self
+            .chain_state_view()
+            .await?
+            .execution_state
+            .system
+            .due_scheduled_operations(local_time)
+            .await?;
+        Ok(keys
+            .into_iter()
+            .map(|key| SystemOperation::ExecuteScheduledOperation(key).into())
+            .collect())
+    }
+
     #[instrument(level = "trace")]
     async fn chain_info_with_committees(&self) -> Result<Box<ChainInfo>, LocalNodeError> {
         self.client.chain_info_with_committees(self.chain_id).await
@@ -2599,8 +2670,8 @@ impl<Env: Environment> ChainClient<Env> {
     }
 
     /// Creates a vector of transactions which, in addition to the provided operations,
-    /// also contains epoch changes, receiving message bundles and event stream updates
-    /// (if there are any to be processed).
+    /// also contains epoch changes, receiving message bundles, event stream updates and due
+    /// scheduled operations (if there are any to be processed).
     /// This should be called when executing a block, in order to make sure that any pending
     /// messages or events are included in it.
     #[instrument(level = "trace", skip(operations))]
@@ -2610,6 +2681,7 @@ impl<Env: Environment> ChainClient<Env> {
     ) -> Result<Vec<Transaction>, ChainClientError> {
         let incoming_bundles = self.pending_message_bundles().await?;
         let stream_updates = self.collect_stream_updates().await?;
+        let scheduled_operations = self.collect_scheduled_operations().await?;
         Ok(self
             .collect_epoch_changes()
             .await?
@@ -2623,6 +2695,7 @@ impl<Env: Environment> ChainClient<Env> {
             .chain(
                 stream_updates
                     .into_iter()
+                    .chain(scheduled_operations)
                     .map(Transaction::ExecuteOperation),
             )
             .chain(operations.into_iter().map(Transaction::ExecuteOperation))
@@ -2720,6 +2793,62 @@ impl<Env: Environment> ChainClient<Env> {
         }
     }
 
+    /// Executes the given operations in a block on top of the current local chain state,
+    /// without signing, proposing or persisting anything, and returns the resources and fees
+    /// the block would use, as well as the messages and events it would produce.
+    ///
+    /// Unlike [`ChainClient::execute_operations`], this does not include any pending incoming
+    /// messages in the block.
+    #[instrument(level = "trace", skip(operations, blobs))]
+    pub async fn estimate_operations(
+        &self,
+        operations: Vec<Operation>,
+        blobs: Vec<Blob>,
+    ) -> Result<FeeEstimate, ChainClientError> {
+        let info = self.chain_info().await?;
+        let transactions = operations
+            .into_iter()
+            .map(Transaction::ExecuteOperation)
+            .collect::<Vec<_>>();
+        let timestamp = self.next_timestamp(&transactions, info.timestamp);
+        let block = ProposedBlock {
+            epoch: info.epoch,
+            chain_id: self.chain_id,
+            transactions,
+            previous_block_hash: info.block_hash,
+            height: info.next_block_height,
+            authenticated_signer: self.preferred_owner,
+            timestamp,
+        };
+        self.estimate_block(block, blobs).await
+    }
+
+    /// Executes a proposed block on top of the current local chain state, without signing,
+    /// proposing or persisting anything, and returns the resources and fees it would use, as
+    /// well as the messages and events it would produce.
+    #[instrument(level = "trace", skip(block, published_blobs))]
+    pub async fn estimate_block(
+        &self,
+        block: ProposedBlock,
+        published_blobs: Vec<Blob>,
+    ) -> Result<FeeEstimate, ChainClientError> {
+        loop {
+            let result = self
+                .client
+                .local_node
+                .estimate_block_execution(block.clone(), None, published_blobs.clone())
+                .await;
+            if let Err(LocalNodeError::BlobsNotFound(blob_ids)) = &result {
+                let validators = self.client.validator_nodes().await?;
+                self.client
+                    .update_local_node_with_blobs_from(blob_ids.clone(), &validators)
+                    .await?;
+                continue; // We found the missing blob: retry.
+            }
+            return Ok(result?);
+        }
+    }
+
     /// Queries a system application.
     #[instrument(level = "trace", skip(query))]
     pub async fn query_system_application(
@@ -2992,7 +3121,7 @@ impl<Env: Environment> ChainClient<Env> {
         let local_node = &self.client.local_node;
         // Otherwise we have to re-propose the highest validated block, if there is one.
         let pending_proposal = self.pending_proposal();
-        let (block, blobs) = if let Some(locking) = &info.manager.requested_locking {
+        let (block, blobs, approvals) = if let Some(locking) = &info.manager.requested_locking {
             match &**locking {
                 LockingBlock::Regular(certificate) => {
                     let blob_ids = certificate.block().required_blob_ids();
@@ -3003,7 +3132,7 @@ impl<Env: Environment> ChainClient<Env> {
                             ChainClientError::InternalError("Missing local locking blobs")
                         })?;
                     debug!("Retrying locking block from round {}", certificate.round);
-                    (certificate.block().clone(), blobs)
+                    (certificate.block().clone(), blobs, Vec::new())
                 }
                 LockingBlock::Fast(proposal) => {
                     let proposed_block = proposal.content.block.clone();
@@ -3020,7 +3149,7 @@ impl<Env: Environment> ChainClient<Env> {
                         .await?
                         .0;
                     debug!("Retrying locking block from fast round.");
-                    (block, blobs)
+                    (block, blobs, Vec::new())
                 }
             }
         } else if let Some(pending_proposal) = pending_proposal {
@@ -3037,7 +3166,7 @@ impl<Env: Environment> ChainClient<Env> {
                 .stage_block_execution(proposed_block, round, pending_proposal.blobs.clone())
                 .await?;
             debug!("Proposing the local pending block.");
-            (block, pending_proposal.blobs)
+            (block, pending_proposal.blobs, pending_proposal.approvals)
         } else {
             return Ok(ClientOutcome::Committed(None)); // Nothing to do.
         };
@@ -3074,7 +3203,8 @@ impl<Env: Environment> ChainClient<Env> {
             Box::new(
                 BlockProposal::new_initial(owner, round, proposed_block.clone(), self.signer())
                     .await
-                    .map_err(ChainClientError::signer_failure)?,
+                    .map_err(ChainClientError::signer_failure)?
+                    .with_approvals(approvals),
             )
         };
         if !already_handled_locally {
@@ -3236,6 +3366,50 @@ impl<Env: Environment> ChainClient<Env> {
         ))
     }
 
+    /// Signs the given block as an owner of this chain. On chains with an approval threshold,
+    /// the proposer must collect such approvals before the block can be committed.
+    #[instrument(level = "trace", skip(block))]
+    pub async fn approve_block(
+        &self,
+        block: &ProposedBlock,
+    ) -> Result<AccountSignature, ChainClientError> {
+        ensure!(
+            block.chain_id == self.chain_id,
+            ChainClientError::BlockProposalError("The block belongs to a different chain")
+        );
+        let owner = self.identity().await?;
+        let bytes = bcs::to_bytes(block)?;
+        self.signer()
+            .sign_content(
+                &owner,
+                &CryptoHash::new(block),
+                SignedContent::BlockApproval(&bytes),
+            )
+            .await
+            .map_err(ChainClientError::signer_failure)
+    }
+
+    /// Adds other owners' approvals to the pending block. They are attached to the next
+    /// proposal of that block.
+    #[instrument(level = "trace", skip(approvals))]
+    pub fn add_block_approvals(
+        &self,
+        approvals: Vec<AccountSignature>,
+    ) -> Result<(), ChainClientError> {
+        let Some(pending_proposal) = self.pending_proposal() else {
+            return Err(ChainClientError::BlockProposalError(
+                "There is no pending block to approve",
+            ));
+        };
+        for approval in &approvals {
+            approval
+                .verify(&pending_proposal.block)
+                .map_err(ChainError::from)?;
+        }
+        self.update_state(|state| state.add_pending_approvals(approvals.clone()));
+        Ok(())
+    }
+
     /// Clears the information on any operation that previously failed.
     #[cfg(with_testing)]
     #[instrument(level = "trace")]
@@ -3266,6 +3440,7 @@ impl<Env: Environment> ChainClient<Env> {
             multi_leader_rounds: 2,
             open_multi_leader_rounds: false,
             timeout_config: TimeoutConfig::default(),
+            approval_threshold: None,
         })
         .await
     }
@@ -3292,6 +3467,7 @@ impl<Env: Environment> ChainClient<Env> {
                 multi_leader_rounds: ownership.multi_leader_rounds,
                 open_multi_leader_rounds: ownership.open_multi_leader_rounds,
                 timeout_config: ownership.timeout_config,
+                approval_threshold: ownership.approval_threshold,
             })];
             match self.execute_block(operations, vec![]).await? {
                 ExecuteBlockOutcome::Executed(certificate) => {
@@ -3323,6 +3499,7 @@ impl<Env: Environment> ChainClient<Env> {
             multi_leader_rounds: ownership.multi_leader_rounds,
             open_multi_leader_rounds: ownership.open_multi_leader_rounds,
             timeout_config: ownership.timeout_config.clone(),
+            approval_threshold: ownership.approval_threshold,
         })
         .await
     }
@@ -3482,6 +3659,7 @@ impl<Env: Environment> ChainClient<Env> {
                 parameters,
                 instantiation_argument,
                 required_application_ids,
+                None,
             )
             .await?
             .map(|(app_id, cert)| (app_id.with_abi(), cert)))
@@ -3504,12 +3682,14 @@ impl<Env: Environment> ChainClient<Env> {
         parameters: Vec<u8>,
         instantiation_argument: Vec<u8>,
         required_application_ids: Vec<ApplicationId>,
+        upgrade_authority: Option<AccountOwner>,
     ) -> Result<ClientOutcome<(ApplicationId, ConfirmedBlockCertificate)>, ChainClientError> {
         self.execute_operation(SystemOperation::CreateApplication {
             module_id,
             parameters,
             instantiation_argument,
             required_application_ids,
+            upgrade_authority,
         })
         .await?
         .try_map(|certificate| {
@@ -3533,6 +3713,41 @@ impl<Env: Environment> ChainClient<Env> {
         })
     }
 
+    /// Upgrades an application to a new module, keeping its storage. On the application's
+    /// creator chain, the signer must be the application's upgrade authority. Other chains
+    /// apply the upgrades announced by the creator chain, in the same order and with the same
+    /// migration argument.
+    #[instrument(level = "trace", skip(self, migration_argument))]
+    pub async fn upgrade_application(
+        &self,
+        application_id: ApplicationId,
+        module_id: ModuleId,
+        migration_argument: Vec<u8>,
+    ) -> Result<ClientOutcome<ConfirmedBlockCertificate>, ChainClientError> {
+        self.execute_operation(SystemOperation::UpgradeApplication {
+            application_id,
+            module_id,
+            migration_argument,
+        })
+        .await
+    }
+
+    /// Deletes all the storage of an application on this chain, and credits the refund for
+    /// the released bytes to `refund_recipient`. The signer must be an owner of the chain or
+    /// the application's upgrade authority.
+    #[instrument(level = "trace", skip(self))]
+    pub async fn delete_application(
+        &self,
+        application_id: ApplicationId,
+        refund_recipient: AccountOwner,
+    ) -> Result<ClientOutcome<ConfirmedBlockCertificate>, ChainClientError> {
+        self.execute_operation(SystemOperation::DeleteApplication {
+            application_id,
+            refund_recipient,
+        })
+        .await
+    }
+
     /// Creates a new committee and starts using it (admin chains only).
     #[instrument(level = "trace", skip(committee))]
     pub async fn stage_new_committee(
@@ -4203,11 +4418,14 @@ impl Drop for AbortOnDrop {
     }
 }
 
-/// A pending proposed block, together with its published blobs.
+/// A pending proposed block, together with its published blobs and the other owners'
+/// approvals collected so far.
 #[derive(Clone, Serialize, Deserialize)]
 pub struct PendingProposal {
     pub block: ProposedBlock,
     pub blobs: Vec<Blob>,
+    #[serde(default)]
+    pub approvals: Vec<AccountSignature>,
 }
 
 enum ReceiveCertificateMode {
@@ -10,6 +10,7 @@ mod chain_worker;
 pub mod client;
 pub mod data_types;
 pub mod join_set_ext;
+pub mod light_client;
 mod local_node;
 pub mod node;
 pub mod notifier;
@@ -0,0 +1,378 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A light client, verifying the history of chains from their certificates without
+//! executing any block.
+//!
+//! The committees are tracked from the admin chain: starting from the genesis committee,
+//! every verified admin block creating a new epoch announces the hash of the `Committee`
+//! blob of that epoch, and every verified admin block removing an epoch revokes it. The
+//! certificates of other chains are then checked against the committee of their epoch, and
+//! their blocks against the tip of their chain.
+//!
+//! Only the admin chain is verified from its first block. Other chains are followed from
+//! the first block presented to the light client, which must be certified by the committee
+//! of an epoch that hasn't been removed.
+
+#[cfg(test)]
+#[path = "unit_tests/light_client_tests.rs"]
+mod unit_tests;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use linera_base::{
+    crypto::CryptoHash,
+    data_types::{ArithmeticError, Blob, BlockHeight, Epoch, Event, NetworkDescription},
+    ensure,
+    identifiers::{BlobId, BlobType, ChainId, StreamId},
+};
+use linera_chain::{
+    types::{BlockHeader, ConfirmedBlockCertificate},
+    ChainError, ChainTipState,
+};
+use linera_execution::{
+    committee::Committee,
+    system::{EPOCH_STREAM_NAME, REMOVED_EPOCH_STREAM_NAME},
+    OutgoingMessage,
+};
+use thiserror::Error;
+use tracing::debug;
+
+use crate::{
+    client::DEFAULT_CERTIFICATE_DOWNLOAD_BATCH_SIZE,
+    data_types::ChainInfoQuery,
+    node::{NodeError, ValidatorNode},
+};
+
+#[derive(Debug, Error)]
+pub enum LightClientError {
+    #[error(transparent)]
+    ChainError(#[from] ChainError),
+    #[error(transparent)]
+    NodeError(#[from] NodeError),
+    #[error(transparent)]
+    ArithmeticError(#[from] ArithmeticError),
+    #[error("invalid committee blob: {0}")]
+    BcsError(#[from] bcs::Error),
+    #[error("no committee is known for epoch {epoch}, used by a block of chain {chain_id}")]
+    UnknownEpoch { chain_id: ChainId, epoch: Epoch },
+    #[error("epoch {epoch}, used by the first verified block of chain {chain_id}, was removed")]
+    RemovedEpoch { chain_id: ChainId, epoch: Epoch },
+    #[error("block of chain {chain_id} is in epoch {epoch}, before the epoch {chain_epoch} of its parent")]
+    EpochRegression {
+        chain_id: ChainId,
+        epoch: Epoch,
+        chain_epoch: Epoch,
+    },
+    #[error("blob {0} is not the genesis committee blob of the network")]
+    InvalidGenesisCommitteeBlob(BlobId),
+    #[error("blob {0} is not an expected committee blob")]
+    UnexpectedCommitteeBlob(BlobId),
+    #[error("expected a certificate for chain {expected}, found one for chain {found}")]
+    UnexpectedChain { expected: ChainId, found: ChainId },
+}
+
+/// A block whose certificate has been verified by a [`LightClient`].
+#[derive(Clone, Debug)]
+pub struct VerifiedBlock(ConfirmedBlockCertificate);
+
+impl VerifiedBlock {
+    /// Returns the hash of the block.
+    pub fn hash(&self) -> CryptoHash {
+        self.0.hash()
+    }
+
+    /// Returns the header of the block.
+    pub fn header(&self) -> &BlockHeader {
+        &self.0.block().header
+    }
+
+    /// Returns the messages sent by each transaction of the block.
+    pub fn outgoing_messages(&self) -> &[Vec<OutgoingMessage>] {
+        &self.0.block().body.messages
+    }
+
+    /// Returns the events emitted by each transaction of the block.
+    pub fn events(&self) -> &[Vec<Event>] {
+        &self.0.block().body.events
+    }
+
+    /// Returns the verified certificate.
+    pub fn certificate(&self) -> &ConfirmedBlockCertificate {
+        &self.0
+    }
+
+    /// Returns the verified certificate.
+    pub fn into_certificate(self) -> ConfirmedBlockCertificate {
+        self.0
+    }
+}
+
+/// The verified state of a chain followed by a [`LightClient`].
+#[derive(Clone, Debug, Default)]
+struct FollowedChain {
+    /// The tip of the chain, as of the latest verified block.
+    tip_state: ChainTipState,
+    /// The epoch of the latest verified block. Epochs never decrease along a chain.
+    epoch: Epoch,
+}
+
+impl FollowedChain {
+    /// Returns the state of a chain followed from the block with the given header. The
+    /// counters of the tip only account for the blocks verified from there.
+    fn starting_at(header: &BlockHeader) -> Self {
+        FollowedChain {
+            tip_state: ChainTipState {
+                block_hash: header.previous_block_hash,
+                next_block_height: header.height,
+                ..ChainTipState::default()
+            },
+            epoch: header.epoch,
+        }
+    }
+}
+
+/// A client following chains by verifying the certificates of their blocks, without
+/// executing them.
+///
+/// A chain that isn't followed yet can only start at a block from an epoch that hasn't been
+/// removed, since the committees of removed epochs are no longer trusted. Blocks of followed
+/// chains from removed epochs are still accepted, as chains only migrate to a new epoch once
+/// they have processed it. A chain's epoch can never decrease, so such blocks can't follow a
+/// block from a later epoch.
+#[derive(Clone, Debug)]
+pub struct LightClient {
+    /// The ID of the admin chain.
+    admin_chain_id: ChainId,
+    /// The committees of the epochs created by the verified admin blocks.
+    committees: BTreeMap<Epoch, Committee>,
+    /// The committee blobs announced by verified admin blocks, but not received yet.
+    pending_committees: BTreeMap<Epoch, BlobId>,
+    /// The epochs removed by the verified admin blocks.
+    removed_epochs: BTreeSet<Epoch>,
+    /// The verified state of the followed chains.
+    chains: BTreeMap<ChainId, FollowedChain>,
+}
+
+impl LightClient {
+    /// Creates a light client trusting the given committee for the first epoch.
+    pub fn new(admin_chain_id: ChainId, genesis_committee: Committee) -> Self {
+        LightClient {
+            admin_chain_id,
+            committees: BTreeMap::from([(Epoch::ZERO, genesis_committee)]),
+            pending_committees: BTreeMap::new(),
+            removed_epochs: BTreeSet::new(),
+            chains: BTreeMap::new(),
+        }
+    }
+
+    /// Creates a light client for the network with the given description, from the blob of
+    /// its genesis committee.
+    pub fn from_network_description(
+        description: &NetworkDescription,
+        genesis_committee_blob: &Blob,
+    ) -> Result<Self, LightClientError> {
+        let blob_id = genesis_committee_blob.id();
+        ensure!(
+            blob_id == BlobId::new(description.genesis_committee_blob_hash, BlobType::Committee),
+            LightClientError::InvalidGenesisCommitteeBlob(blob_id)
+        );
+        let committee = bcs::from_bytes(genesis_committee_blob.bytes())?;
+        Ok(Self::new(description.admin_chain_id, committee))
+    }
+
+    /// Returns the ID of the admin chain.
+    pub fn admin_chain_id(&self) -> ChainId {
+        self.admin_chain_id
+    }
+
+    /// Returns the known committees, by epoch.
+    pub fn committees(&self) -> &BTreeMap<Epoch, Committee> {
+        &self.committees
+    }
+
+    /// Returns the epochs removed by the admin chain.
+    pub fn removed_epochs(&self) -> &BTreeSet<Epoch> {
+        &self.removed_epochs
+    }
+
+    /// Returns the tip of the given chain, as of its latest verified block.
+    pub fn tip_state(&self, chain_id: ChainId) -> Option<&ChainTipState> {
+        self.chains.get(&chain_id).map(|chain| &chain.tip_state)
+    }
+
+    /// Returns the IDs of the committee blobs announced by the admin chain that are still
+    /// needed to verify blocks of their epochs.
+    pub fn missing_committee_blobs(&self) -> Vec<BlobId> {
+        self.pending_committees.values().copied().collect()
+    }
+
+    /// Adds the committee of a new epoch, announced by a verified admin block.
+    pub fn add_committee_blob(&mut self, blob: &Blob) -> Result<(), LightClientError> {
+        let blob_id = blob.id();
+        let epoch = self
+            .pending_committees
+            .iter()
+            .find_map(|(epoch, pending_id)| (*pending_id == blob_id).then_some(*epoch))
+            .ok_or(LightClientError::UnexpectedCommitteeBlob(blob_id))?;
+        let committee = bcs::from_bytes(blob.bytes())?;
+        self.pending_committees.remove(&epoch);
+        self.committees.insert(epoch, committee);
+        debug!("Light client learned the committee of epoch {epoch}");
+        Ok(())
+    }
+
+    /// Verifies a certificate against the committee of its epoch, and its block against the
+    /// tip of its chain. Blocks of a chain must be processed in order. The admin chain is
+    /// followed from height zero, and other chains from their first processed block.
+    pub fn process_certificate(
+        &mut self,
+        certificate: ConfirmedBlockCertificate,
+    ) -> Result<VerifiedBlock, LightClientError> {
+        let block = certificate.block();
+        let chain_id = block.header.chain_id;
+        let epoch = block.header.epoch;
+        let committee = self
+            .committees
+            .get(&epoch)
+            .ok_or(LightClientError::UnknownEpoch { chain_id, epoch })?;
+        certificate.check(committee)?;
+
+        let mut chain = match self.chains.get(&chain_id) {
+            Some(chain) => chain.clone(),
+            None if chain_id == self.admin_chain_id => FollowedChain::default(),
+            None => {
+                ensure!(
+                    !self.removed_epochs.contains(&epoch),
+                    LightClientError::RemovedEpoch { chain_id, epoch }
+                );
+                FollowedChain::starting_at(&block.header)
+            }
+        };
+        chain.tip_state.verify_header_chaining(&block.header)?;
+        ensure!(
+            chain.tip_state.block_hash.is_none() || epoch >= chain.epoch,
+            LightClientError::EpochRegression {
+                chain_id,
+                epoch,
+                chain_epoch: chain.epoch,
+            }
+        );
+        chain
+            .tip_state
+            .update_counters(&block.body.transactions, &block.body.messages)?;
+        chain.tip_state.block_hash = Some(certificate.hash());
+        chain.tip_state.next_block_height = block.header.height.try_add_one()?;
+        chain.epoch = epoch;
+
+        if chain_id == self.admin_chain_id {
+            self.process_admin_block(&certificate)?;
+        }
+        self.chains.insert(chain_id, chain);
+        Ok(VerifiedBlock(certificate))
+    }
+
+    /// Records the committees created and the epochs removed by a verified admin block.
+    fn process_admin_block(
+        &mut self,
+        certificate: &ConfirmedBlockCertificate,
+    ) -> Result<(), LightClientError> {
+        let block = certificate.block();
+        let epoch_stream_id = StreamId::system(EPOCH_STREAM_NAME);
+        let removed_epoch_stream_id = StreamId::system(REMOVED_EPOCH_STREAM_NAME);
+        for event in block.body.events.iter().flatten() {
+            if event.stream_id == removed_epoch_stream_id {
+                self.removed_epochs.insert(Epoch(event.index));
+                debug!("Light client learned the removal of epoch {}", event.index);
+                continue;
+            }
+            if event.stream_id != epoch_stream_id {
+                continue;
+            }
+            let blob_hash: CryptoHash = bcs::from_bytes(&event.value)?;
+            let blob_id = BlobId::new(blob_hash, BlobType::Committee);
+            self.pending_committees.insert(Epoch(event.index), blob_id);
+            if let Some(blob) = block.created_blobs().get(&blob_id) {
+                self.add_committee_blob(blob)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Downloads the admin blocks and the committees that are not known yet from `node`.
+    pub async fn synchronize_committees(
+        &mut self,
+        node: &impl ValidatorNode,
+    ) -> Result<Vec<VerifiedBlock>, LightClientError> {
+        self.download_blocks(node, self.admin_chain_id).await
+    }
+
+    /// Downloads the blocks of the given chain that are not verified yet from `node`, after
+    /// synchronizing the committees, and returns them once verified. A chain that isn't
+    /// followed yet is only downloaded from its latest block.
+    pub async fn synchronize_chain(
+        &mut self,
+        node: &impl ValidatorNode,
+        chain_id: ChainId,
+    ) -> Result<Vec<VerifiedBlock>, LightClientError> {
+        let admin_blocks = self.synchronize_committees(node).await?;
+        if chain_id == self.admin_chain_id {
+            return Ok(admin_blocks);
+        }
+        self.download_blocks(node, chain_id).await
+    }
+
+    /// Downloads and verifies the blocks of `chain_id` above the verified tip, up to the
+    /// height reported by `node`. Without a verified tip, the admin chain is downloaded from
+    /// its first block and other chains from their latest one.
+    async fn download_blocks(
+        &mut self,
+        node: &impl ValidatorNode,
+        chain_id: ChainId,
+    ) -> Result<Vec<VerifiedBlock>, LightClientError> {
+        let info = node
+            .handle_chain_info_query(ChainInfoQuery::new(chain_id))
+            .await?
+            .info;
+        let mut next_height = match self.tip_state(chain_id) {
+            Some(tip_state) => tip_state.next_block_height,
+            None if chain_id == self.admin_chain_id => BlockHeight::ZERO,
+            None => info
+                .next_block_height
+                .try_sub_one()
+                .unwrap_or(BlockHeight::ZERO),
+        };
+        let mut blocks = Vec::new();
+        while next_height < info.next_block_height {
+            let heights = (next_height.0..info.next_block_height.0)
+                .take(DEFAULT_CERTIFICATE_DOWNLOAD_BATCH_SIZE as usize)
+                .map(BlockHeight)
+                .collect::<Vec<_>>();
+            let certificates = node
+                .download_certificates_by_heights(chain_id, heights.clone())
+                .await?;
+            ensure!(
+                certificates.len() == heights.len(),
+                NodeError::MissingCertificatesByHeights { chain_id, heights }
+            );
+            for certificate in certificates {
+                let found = certificate.block().header.chain_id;
+                ensure!(
+                    found == chain_id,
+                    LightClientError::UnexpectedChain {
+                        expected: chain_id,
+                        found,
+                    }
+                );
+                let block = self.process_certificate(certificate)?;
+                for blob_id in self.missing_committee_blobs() {
+                    let content = node.download_blob(blob_id).await?;
+                    self.add_committee_blob(&Blob::new(content))?;
+                }
+                next_height = block.header().height.try_add_one()?;
+                blocks.push(block);
+            }
+        }
+        Ok(blocks)
+    }
+}
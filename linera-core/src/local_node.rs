@@ -14,7 +14,7 @@ use linera_base::{
     identifiers::{BlobId, ChainId},
 };
 use linera_chain::{
-    data_types::{BlockProposal, ProposedBlock},
+    data_types::{BlockProposal, FeeEstimate, ProposedBlock},
     types::{Block, GenericCertificate},
     ChainStateView,
 };
@@ -146,6 +146,20 @@ where
             .await?)
     }
 
+    #[instrument(level = "trace", skip_all)]
+    pub async fn estimate_block_execution(
+        &self,
+        block: ProposedBlock,
+        round: Option<u32>,
+        published_blobs: Vec<Blob>,
+    ) -> Result<FeeEstimate, LocalNodeError> {
+        Ok(self
+            .node
+            .state
+            .estimate_block_execution(block, round, published_blobs)
+            .await?)
+    }
+
     /// Reads blobs from storage.
     pub async fn read_blobs_from_storage(
         &self,
@@ -298,6 +298,57 @@ where
     Ok(())
 }
 
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "storage-service", test_case(ServiceStorageBuilder::new(); "storage_service"))]
+#[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
+#[cfg_attr(feature = "dynamodb", test_case(DynamoDbStorageBuilder::default(); "dynamo_db"))]
+#[cfg_attr(feature = "scylladb", test_case(ScyllaDbStorageBuilder::default(); "scylla_db"))]
+#[test_log::test(tokio::test)]
+async fn test_estimate_fees_of_transfer<B>(storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    let signer = InMemorySigner::new(None);
+    let mut builder = TestBuilder::new(storage_builder, 4, 1, signer)
+        .await?
+        .with_policy(ResourceControlPolicy::all_categories());
+    let sender = builder.add_root_chain(1, Amount::from_tokens(4)).await?;
+    let receiver = builder.add_root_chain(2, Amount::ZERO).await?;
+    let transfer = SystemOperation::Transfer {
+        owner: AccountOwner::CHAIN,
+        recipient: Account::chain(receiver.chain_id()),
+        amount: Amount::from_tokens(3),
+    };
+
+    let estimate = sender
+        .estimate_operations(vec![Operation::system(transfer.clone())], vec![])
+        .await?;
+    assert!(estimate.fees > Amount::ZERO);
+    assert_eq!(estimate.resources.operations, 1);
+    assert_eq!(estimate.resources.messages, 1);
+    assert_eq!(estimate.messages.len(), 1);
+    assert_eq!(estimate.messages[0].len(), 1);
+    assert_eq!(estimate.messages[0][0].destination, receiver.chain_id());
+
+    // Nothing was persisted.
+    assert_eq!(
+        sender.chain_info().await?.next_block_height,
+        BlockHeight::ZERO
+    );
+    assert_eq!(sender.local_balance().await?, Amount::from_tokens(4));
+
+    // The estimate matches the fees actually charged.
+    sender
+        .execute_operation(transfer)
+        .await
+        .unwrap_ok_committed();
+    assert_eq!(
+        sender.local_balance().await?,
+        Amount::from_tokens(1).try_sub(estimate.fees)?
+    );
+    Ok(())
+}
+
 #[test_case(MemoryStorageBuilder::default(); "memory")]
 #[cfg_attr(feature = "storage-service", test_case(ServiceStorageBuilder::new(); "storage_service"))]
 #[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
@@ -1520,6 +1571,7 @@ where
         multi_leader_rounds: 10,
         open_multi_leader_rounds: false,
         timeout_config: TimeoutConfig::default(),
+        approval_threshold: None,
     });
     client2_a
         .execute_operation(owner_change_op.clone())
@@ -1643,6 +1695,7 @@ where
         multi_leader_rounds: 10,
         open_multi_leader_rounds: false,
         timeout_config: TimeoutConfig::default(),
+        approval_threshold: None,
     });
     client1
         .execute_operation(owner_change_op.clone())
@@ -1730,6 +1783,7 @@ where
         multi_leader_rounds: 10,
         open_multi_leader_rounds: false,
         timeout_config: TimeoutConfig::default(),
+        approval_threshold: None,
     });
 
     client3_a
@@ -2365,6 +2419,7 @@ where
         multi_leader_rounds: 10,
         open_multi_leader_rounds: false,
         timeout_config,
+        approval_threshold: None,
     };
     client0.change_ownership(ownership).await.unwrap();
     let mut client1 = builder
@@ -2835,3 +2890,80 @@ where
 
     Ok(())
 }
+
+#[test_case(MemoryStorageBuilder::default(); "memory")]
+#[cfg_attr(feature = "storage-service", test_case(ServiceStorageBuilder::new(); "storage_service"))]
+#[cfg_attr(feature = "rocksdb", test_case(RocksDbStorageBuilder::new().await; "rocks_db"))]
+#[cfg_attr(feature = "dynamodb", test_case(DynamoDbStorageBuilder::default(); "dynamo_db"))]
+#[cfg_attr(feature = "scylladb", test_case(ScyllaDbStorageBuilder::default(); "scylla_db"))]
+#[test_log::test(tokio::test)]
+async fn test_approval_threshold<B>(storage_builder: B) -> anyhow::Result<()>
+where
+    B: StorageBuilder,
+{
+    // Configure a chain with two owners that both need to sign each block.
+    let signer = InMemorySigner::new(None);
+    let mut builder = TestBuilder::new(storage_builder, 4, 0, signer).await?;
+    let client0 = builder.add_root_chain(1, Amount::from_tokens(10)).await?;
+    let chain_id = client0.chain_id();
+    let owner0 = client0.identity().await.unwrap();
+    let owner1 = builder.signer.generate_new().into();
+
+    let ownership =
+        ChainOwnership::multiple([(owner0, 50), (owner1, 50)], 10, TimeoutConfig::default())
+            .with_approval_threshold(100);
+    client0
+        .change_ownership(ownership)
+        .await
+        .unwrap_ok_committed();
+    let mut client1 = builder
+        .make_client(
+            chain_id,
+            client0.chain_info().await?.block_hash,
+            BlockHeight::from(1),
+        )
+        .await?;
+    client1.set_preferred_owner(owner1);
+    client1.synchronize_from_validators().await.unwrap();
+
+    // Without client 1's approval, the block is rejected.
+    let recipient = Account::burn_address(chain_id);
+    let result = client0
+        .transfer_to_account(AccountOwner::CHAIN, Amount::from_tokens(3), recipient)
+        .await;
+    assert!(
+        matches!(
+            &result,
+            Err(ChainClientError::LocalNodeError(
+                LocalNodeError::WorkerError(WorkerError::ChainError(err))
+            )) if matches!(**err, ChainError::InsufficientApprovals { weight: 50, threshold: 100 })
+        ),
+        "Unexpected result: {:?}",
+        result,
+    );
+    let pending_block = client0.pending_proposal().unwrap().block;
+
+    // An approval for a different block is not accepted.
+    let mut other_block = pending_block.clone();
+    other_block.timestamp = other_block
+        .timestamp
+        .saturating_add(TimeDelta::from_micros(1));
+    let wrong_approval = client1.approve_block(&other_block).await?;
+    assert!(client0.add_block_approvals(vec![wrong_approval]).is_err());
+
+    // With the approval, the pending block is committed.
+    let approval = client1.approve_block(&pending_block).await?;
+    client0.add_block_approvals(vec![approval])?;
+    let certificate = client0
+        .process_pending_block()
+        .await
+        .unwrap_ok_committed()
+        .unwrap();
+    assert_eq!(certificate.block().header.height, BlockHeight::from(1));
+    assert_eq!(
+        client0.local_balance().await.unwrap(),
+        Amount::from_tokens(7)
+    );
+
+    Ok(())
+}
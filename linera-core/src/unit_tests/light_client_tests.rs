@@ -0,0 +1,143 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use assert_matches::assert_matches;
+use linera_base::{
+    crypto::InMemorySigner,
+    data_types::{Amount, Blob, BlockHeight, Epoch},
+    identifiers::{Account, AccountOwner},
+};
+use linera_chain::ChainError;
+use linera_execution::{committee::Committee, Message, ResourceControlPolicy, SystemMessage};
+
+use super::{LightClient, LightClientError};
+use crate::{
+    node::ValidatorNode as _,
+    test_utils::{ClientOutcomeResultExt as _, MemoryStorageBuilder, TestBuilder},
+};
+
+#[test_log::test(tokio::test)]
+async fn test_light_client_follows_committees() -> anyhow::Result<()> {
+    let signer = InMemorySigner::new(None);
+    let mut builder = TestBuilder::new(MemoryStorageBuilder::default(), 4, 0, signer).await?;
+    let admin = builder.add_root_chain(0, Amount::from_tokens(3)).await?;
+    let user = builder.add_root_chain(1, Amount::ZERO).await?;
+    let admin_id = admin.chain_id();
+    let user_id = user.chain_id();
+
+    admin
+        .transfer_to_account(AccountOwner::CHAIN, Amount::ONE, Account::chain(user_id))
+        .await
+        .unwrap_ok_committed();
+    let validators = builder.initial_committee.validators().clone();
+    let committee = Committee::new(validators, ResourceControlPolicy::only_fuel());
+    admin
+        .stage_new_committee(committee.clone())
+        .await
+        .unwrap_ok_committed();
+    user.synchronize_from_validators().await?;
+    user.process_inbox().await?;
+    user.transfer_to_account(AccountOwner::CHAIN, Amount::ONE, Account::chain(admin_id))
+        .await
+        .unwrap_ok_committed();
+    assert_eq!(user.chain_info().await?.epoch, Epoch(1));
+
+    let network_description = builder.network_description().unwrap();
+    let genesis_committee_blob = Blob::new_committee(bcs::to_bytes(&builder.initial_committee)?);
+    let mut light_client =
+        LightClient::from_network_description(network_description, &genesis_committee_blob)?;
+    let node = builder.node(0);
+
+    // The latest block of the user chain, in the new epoch, is only verified once its
+    // committee is known from the admin chain. The earlier blocks aren't downloaded.
+    let user_certificates = node
+        .download_certificates_by_heights(user_id, vec![BlockHeight::ZERO])
+        .await?;
+    let user_blocks = light_client.synchronize_chain(&node, user_id).await?;
+    assert_eq!(light_client.committees().get(&Epoch(1)), Some(&committee));
+    assert!(light_client.missing_committee_blobs().is_empty());
+    assert_eq!(
+        light_client.tip_state(admin_id).unwrap().next_block_height,
+        admin.chain_info().await?.next_block_height
+    );
+    let user_info = user.chain_info().await?;
+    assert_eq!(user_blocks.len(), 1);
+    assert_eq!(
+        user_blocks[0].header().height,
+        user_info.next_block_height.try_sub_one()?
+    );
+    assert_eq!(
+        light_client.tip_state(user_id).unwrap().block_hash,
+        user_info.block_hash
+    );
+    assert_eq!(user_blocks.last().unwrap().header().epoch, Epoch(1));
+
+    // The transfer to the admin chain is among the verified outgoing messages.
+    let messages = user_blocks
+        .last()
+        .unwrap()
+        .outgoing_messages()
+        .iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].destination, admin_id);
+    assert_matches!(
+        messages[0].message,
+        Message::System(SystemMessage::Credit { amount, .. }) if amount == Amount::ONE
+    );
+
+    // Certificates must extend the verified tip of their chain.
+    assert_matches!(
+        light_client.process_certificate(user_certificates[0].clone()),
+        Err(LightClientError::ChainError(
+            ChainError::UnexpectedBlockHeight { .. }
+        ))
+    );
+
+    // Further blocks of the user chain are verified from its tip.
+    user.transfer_to_account(AccountOwner::CHAIN, Amount::ONE, Account::chain(admin_id))
+        .await
+        .unwrap_ok_committed();
+    let new_user_blocks = light_client.synchronize_chain(&node, user_id).await?;
+    assert_eq!(new_user_blocks.len(), 1);
+    assert_eq!(
+        new_user_blocks[0].header().previous_block_hash,
+        user_info.block_hash
+    );
+
+    // Once the first epoch is removed, chains can't start being followed from one of its
+    // blocks anymore, but the followed chains still can be.
+    admin.revoke_epochs(Epoch::ZERO).await.unwrap_ok_committed();
+    light_client.synchronize_committees(&node).await?;
+    assert!(light_client.removed_epochs().contains(&Epoch::ZERO));
+    assert_matches!(
+        light_client.process_certificate(user_certificates[0].clone()),
+        Err(LightClientError::ChainError(
+            ChainError::UnexpectedBlockHeight { .. }
+        ))
+    );
+    let mut removal_light_client =
+        LightClient::from_network_description(network_description, &genesis_committee_blob)?;
+    removal_light_client.synchronize_committees(&node).await?;
+    assert_matches!(
+        removal_light_client.process_certificate(user_certificates[0].clone()),
+        Err(LightClientError::RemovedEpoch {
+            epoch: Epoch::ZERO,
+            ..
+        })
+    );
+
+    // A light client that doesn't know the committees of the admin chain can't verify
+    // blocks of later epochs.
+    let mut light_client = LightClient::new(admin_id, builder.initial_committee.clone());
+    let certificate = user_blocks.last().unwrap().certificate().clone();
+    assert_matches!(
+        light_client.process_certificate(certificate),
+        Err(LightClientError::UnknownEpoch {
+            epoch: Epoch(1),
+            ..
+        })
+    );
+    Ok(())
+}
@@ -1002,6 +1002,10 @@ where
         self.admin_description.as_ref()
     }
 
+    pub fn network_description(&self) -> Option<&NetworkDescription> {
+        self.network_description.as_ref()
+    }
+
     pub fn make_node_provider(&self) -> NodeProvider<B::Storage> {
         self.node_provider.clone()
     }
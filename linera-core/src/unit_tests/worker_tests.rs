@@ -3321,6 +3321,7 @@ where
             multi_leader_rounds: 0,
             open_multi_leader_rounds: false,
             timeout_config: TimeoutConfig::default(),
+            approval_threshold: None,
         })
         .with_authenticated_signer(Some(owner0));
     let (block0, _) = env
@@ -3573,6 +3574,7 @@ where
                 fast_round_duration: Some(TimeDelta::from_secs(5)),
                 ..TimeoutConfig::default()
             },
+            approval_threshold: None,
         });
     let (block0, _) = env
         .worker()
@@ -3678,6 +3680,7 @@ where
                 fast_round_duration: Some(TimeDelta::from_secs(5)),
                 ..TimeoutConfig::default()
             },
+            approval_threshold: None,
         });
     let (change_ownership_block, _) = env
         .worker()
@@ -3757,6 +3760,7 @@ where
                 fast_round_duration: Some(TimeDelta::from_millis(5)),
                 ..TimeoutConfig::default()
             },
+            approval_threshold: None,
         });
     let (block0, _) = env
         .worker()
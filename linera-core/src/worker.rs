@@ -10,7 +10,7 @@ use std::{
 
 use futures::future::Either;
 use linera_base::{
-    crypto::{CryptoError, CryptoHash, ValidatorPublicKey, ValidatorSecretKey},
+    crypto::{Bls12381SecretKey, CryptoError, CryptoHash, ValidatorPublicKey, ValidatorSecretKey},
     data_types::{ApplicationDescription, ArithmeticError, Blob, BlockHeight, Epoch, Round},
     doc_scalar,
     hashed::Hashed,
@@ -19,7 +19,7 @@ use linera_base::{
 #[cfg(with_testing)]
 use linera_chain::ChainExecutionContext;
 use linera_chain::{
-    data_types::{BlockExecutionOutcome, BlockProposal, MessageBundle, ProposedBlock},
+    data_types::{BlockExecutionOutcome, BlockProposal, FeeEstimate, MessageBundle, ProposedBlock},
     types::{
         Block, CertificateValue, ConfirmedBlock, ConfirmedBlockCertificate, GenericCertificate,
         LiteCertificate, Timeout, TimeoutCertificate, ValidatedBlock, ValidatedBlockCertificate,
@@ -421,6 +421,14 @@ where
         }
     }
 
+    /// Returns an instance that also signs its votes with the given BLS12-381 key, so that
+    /// clients can aggregate them into a single certificate signature.
+    #[instrument(level = "trace", skip(self, aggregate_key))]
+    pub fn with_aggregate_key(mut self, aggregate_key: Option<Bls12381SecretKey>) -> Self {
+        self.chain_worker_config = self.chain_worker_config.with_aggregate_key(aggregate_key);
+        self
+    }
+
     #[instrument(level = "trace", skip(self, value))]
     pub fn with_allow_inactive_chains(mut self, value: bool) -> Self {
         self.chain_worker_config.allow_inactive_chains = value;
@@ -627,6 +635,26 @@ where
         .await
     }
 
+    /// Executes a block proposal without persisting anything, and returns the resources and
+    /// fees it would use.
+    #[instrument(level = "trace", skip(self, block))]
+    pub async fn estimate_block_execution(
+        &self,
+        block: ProposedBlock,
+        round: Option<u32>,
+        published_blobs: Vec<Blob>,
+    ) -> Result<FeeEstimate, WorkerError> {
+        self.query_chain_worker(block.chain_id, move |callback| {
+            ChainWorkerRequest::EstimateBlockExecution {
+                block,
+                round,
+                published_blobs,
+                callback,
+            }
+        })
+        .await
+    }
+
     /// Executes a [`Query`] for an application's state on a specific chain.
     #[instrument(
         level = "trace",
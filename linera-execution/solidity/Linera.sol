@@ -131,6 +131,7 @@ library Linera {
         uint32 multi_leader_rounds;
         bool open_multi_leader_rounds;
         TimeoutConfig timeout_config;
+        opt_uint64 approval_threshold;
     }
 
     function chainownership_from(LineraTypes.ChainOwnership memory entry)
@@ -150,7 +151,16 @@ library Linera {
         for (uint256 i=0; i<len2; i++) {
             owners[i] = accountownerweight_from(entry.owners[i]);
         }
-        return ChainOwnership(super_owners, owners, entry.multi_leader_rounds, entry.open_multi_leader_rounds, timeoutconfig_from(entry.timeout_config));
+        LineraTypes.MultiLeaderAccess memory access = entry.multi_leader_access;
+        bool open_multi_leader_rounds = access.choice == 1 || access.choice == 3;
+        opt_uint64 memory approval_threshold;
+        if (access.choice == 2) {
+            approval_threshold = opt_uint64(true, access.restricted_with_threshold);
+        }
+        if (access.choice == 3) {
+            approval_threshold = opt_uint64(true, access.open_with_threshold);
+        }
+        return ChainOwnership(super_owners, owners, entry.multi_leader_rounds, open_multi_leader_rounds, timeoutconfig_from(entry.timeout_config), approval_threshold);
     }
 
     struct opt_uint32 {
@@ -166,6 +176,11 @@ library Linera {
         return opt_uint32(entry.has_value, entry.value);
     }
 
+    struct opt_uint64 {
+        bool has_value;
+        uint64 value;
+    }
+
     struct ApplicationId {
         bytes32 application_description_hash;
     }
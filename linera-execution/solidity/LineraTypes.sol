@@ -516,7 +516,7 @@ library LineraTypes {
         AccountOwner[] super_owners;
         key_values_AccountOwner_uint64[] owners;
         uint32 multi_leader_rounds;
-        bool open_multi_leader_rounds;
+        MultiLeaderAccess multi_leader_access;
         TimeoutConfig timeout_config;
     }
 
@@ -528,7 +528,7 @@ library LineraTypes {
         bytes memory result = bcs_serialize_seq_AccountOwner(input.super_owners);
         result = abi.encodePacked(result, bcs_serialize_seq_key_values_AccountOwner_uint64(input.owners));
         result = abi.encodePacked(result, bcs_serialize_uint32(input.multi_leader_rounds));
-        result = abi.encodePacked(result, bcs_serialize_bool(input.open_multi_leader_rounds));
+        result = abi.encodePacked(result, bcs_serialize_MultiLeaderAccess(input.multi_leader_access));
         return abi.encodePacked(result, bcs_serialize_TimeoutConfig(input.timeout_config));
     }
 
@@ -544,11 +544,11 @@ library LineraTypes {
         (new_pos, owners) = bcs_deserialize_offset_seq_key_values_AccountOwner_uint64(new_pos, input);
         uint32 multi_leader_rounds;
         (new_pos, multi_leader_rounds) = bcs_deserialize_offset_uint32(new_pos, input);
-        bool open_multi_leader_rounds;
-        (new_pos, open_multi_leader_rounds) = bcs_deserialize_offset_bool(new_pos, input);
+        MultiLeaderAccess memory multi_leader_access;
+        (new_pos, multi_leader_access) = bcs_deserialize_offset_MultiLeaderAccess(new_pos, input);
         TimeoutConfig memory timeout_config;
         (new_pos, timeout_config) = bcs_deserialize_offset_TimeoutConfig(new_pos, input);
-        return (new_pos, ChainOwnership(super_owners, owners, multi_leader_rounds, open_multi_leader_rounds, timeout_config));
+        return (new_pos, ChainOwnership(super_owners, owners, multi_leader_rounds, multi_leader_access, timeout_config));
     }
 
     function bcs_deserialize_ChainOwnership(bytes memory input)
@@ -1293,6 +1293,100 @@ library LineraTypes {
         return value;
     }
 
+    struct MultiLeaderAccess {
+        uint8 choice;
+        // choice=0 corresponds to Restricted
+        // choice=1 corresponds to Open
+        // choice=2 corresponds to RestrictedWithThreshold
+        uint64 restricted_with_threshold;
+        // choice=3 corresponds to OpenWithThreshold
+        uint64 open_with_threshold;
+    }
+
+    function MultiLeaderAccess_case_restricted()
+        internal
+        pure
+        returns (MultiLeaderAccess memory)
+    {
+        uint64 restricted_with_threshold;
+        uint64 open_with_threshold;
+        return MultiLeaderAccess(uint8(0), restricted_with_threshold, open_with_threshold);
+    }
+
+    function MultiLeaderAccess_case_open()
+        internal
+        pure
+        returns (MultiLeaderAccess memory)
+    {
+        uint64 restricted_with_threshold;
+        uint64 open_with_threshold;
+        return MultiLeaderAccess(uint8(1), restricted_with_threshold, open_with_threshold);
+    }
+
+    function MultiLeaderAccess_case_restricted_with_threshold(uint64 restricted_with_threshold)
+        internal
+        pure
+        returns (MultiLeaderAccess memory)
+    {
+        uint64 open_with_threshold;
+        return MultiLeaderAccess(uint8(2), restricted_with_threshold, open_with_threshold);
+    }
+
+    function MultiLeaderAccess_case_open_with_threshold(uint64 open_with_threshold)
+        internal
+        pure
+        returns (MultiLeaderAccess memory)
+    {
+        uint64 restricted_with_threshold;
+        return MultiLeaderAccess(uint8(3), restricted_with_threshold, open_with_threshold);
+    }
+
+    function bcs_serialize_MultiLeaderAccess(MultiLeaderAccess memory input)
+        internal
+        pure
+        returns (bytes memory)
+    {
+        if (input.choice == 2) {
+            return abi.encodePacked(input.choice, bcs_serialize_uint64(input.restricted_with_threshold));
+        }
+        if (input.choice == 3) {
+            return abi.encodePacked(input.choice, bcs_serialize_uint64(input.open_with_threshold));
+        }
+        return abi.encodePacked(input.choice);
+    }
+
+    function bcs_deserialize_offset_MultiLeaderAccess(uint256 pos, bytes memory input)
+        internal
+        pure
+        returns (uint256, MultiLeaderAccess memory)
+    {
+        uint256 new_pos;
+        uint8 choice;
+        (new_pos, choice) = bcs_deserialize_offset_uint8(pos, input);
+        uint64 restricted_with_threshold;
+        if (choice == 2) {
+            (new_pos, restricted_with_threshold) = bcs_deserialize_offset_uint64(new_pos, input);
+        }
+        uint64 open_with_threshold;
+        if (choice == 3) {
+            (new_pos, open_with_threshold) = bcs_deserialize_offset_uint64(new_pos, input);
+        }
+        require(choice < 4);
+        return (new_pos, MultiLeaderAccess(choice, restricted_with_threshold, open_with_threshold));
+    }
+
+    function bcs_deserialize_MultiLeaderAccess(bytes memory input)
+        internal
+        pure
+        returns (MultiLeaderAccess memory)
+    {
+        uint256 new_pos;
+        MultiLeaderAccess memory value;
+        (new_pos, value) = bcs_deserialize_offset_MultiLeaderAccess(0, input);
+        require(new_pos == input.length, "incomplete deserialization");
+        return value;
+    }
+
     struct OptionAccountOwner {
         opt_AccountOwner value;
     }
@@ -4,7 +4,9 @@
 
 use std::{borrow::Cow, collections::BTreeMap, str::FromStr};
 
-use linera_base::crypto::{AccountPublicKey, CryptoError, ValidatorPublicKey};
+use linera_base::crypto::{
+    AccountPublicKey, Bls12381PublicKey, Bls12381Signature, CryptoError, ValidatorPublicKey,
+};
 use serde::{Deserialize, Serialize};
 
 use crate::policy::ResourceControlPolicy;
@@ -55,6 +57,22 @@ pub struct ValidatorState {
     pub votes: u64,
     /// The public key of the account associated with the validator.
     pub account_public_key: AccountPublicKey,
+    /// The BLS12-381 public key used to verify aggregate certificate signatures, if any.
+    pub aggregate_public_key: Option<Bls12381PublicKey>,
+    /// The proof of possession of the secret key for `aggregate_public_key`.
+    pub aggregate_key_proof: Option<Bls12381Signature>,
+}
+
+impl ValidatorState {
+    /// Returns `false` if the aggregate public key is not accompanied by a valid proof of
+    /// possession. Without these proofs, aggregate signatures are open to rogue-key attacks.
+    pub fn has_valid_aggregate_key(&self) -> bool {
+        match (&self.aggregate_public_key, &self.aggregate_key_proof) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(public_key), Some(proof)) => public_key.verify_proof_of_possession(proof).is_ok(),
+        }
+    }
 }
 
 /// A set of validators (identified by their public keys) and their voting rights.
@@ -145,6 +163,11 @@ impl TryFrom<CommitteeFull<'static>> for Committee {
                 "invalid committee: validity_threshold is {}; should be {}",
                 validity_threshold, committee.validity_threshold,
             ))
+        } else if let Some(public_key) = committee.validator_with_invalid_aggregate_key() {
+            Err(format!(
+                "invalid committee: the aggregate key of validator {public_key} \
+                 has no valid proof of possession",
+            ))
         } else {
             Ok(committee)
         }
@@ -246,6 +269,8 @@ impl Committee {
                         network_address: "Tcp:localhost:8080".to_string(),
                         votes: 100,
                         account_public_key: account_key,
+                        aggregate_public_key: None,
+                        aggregate_key_proof: None,
                     },
                 )
             })
@@ -253,6 +278,15 @@ impl Committee {
         Committee::new(map, ResourceControlPolicy::default())
     }
 
+    /// Returns a validator whose aggregate public key has no valid proof of possession, if
+    /// any.
+    pub fn validator_with_invalid_aggregate_key(&self) -> Option<ValidatorPublicKey> {
+        self.validators
+            .iter()
+            .find(|(_, state)| !state.has_valid_aggregate_key())
+            .map(|(public_key, _)| *public_key)
+    }
+
     pub fn weight(&self, author: &ValidatorPublicKey) -> u64 {
         match self.validators.get(author) {
             Some(state) => state.votes,
@@ -29,6 +29,8 @@ pub enum EvmExecutionError {
     MissingFunction(String),
     #[error("Incorrect contract creation: {0}")]
     IncorrectContractCreation(String),
+    #[error("EVM applications cannot be upgraded")]
+    UpgradeNotSupported,
     #[error("The operation should contain the evm selector and so have length 4 or more")]
     OperationIsTooShort,
     #[error("Transact error {0}")]
@@ -1158,6 +1158,10 @@ where
         Ok(())
     }
 
+    fn migrate(&mut self, _argument: Vec<u8>) -> Result<(), ExecutionError> {
+        Err(EvmExecutionError::UpgradeNotSupported.into())
+    }
+
     fn execute_operation(&mut self, operation: Vec<u8>) -> Result<Vec<u8>, ExecutionError> {
         self.db.set_contract_address()?;
         ensure_message_length(operation.len(), 4)?;
@@ -143,6 +143,7 @@ where
 
 pub enum UserAction {
     Instantiate(OperationContext, Vec<u8>),
+    Migrate(OperationContext, Vec<u8>),
     Operation(OperationContext, Vec<u8>),
     Message(MessageContext, Vec<u8>),
     ProcessStreams(ProcessStreamsContext, Vec<StreamUpdate>),
@@ -152,6 +153,7 @@ impl UserAction {
     pub(crate) fn signer(&self) -> Option<AccountOwner> {
         match self {
             UserAction::Instantiate(context, _) => context.authenticated_signer,
+            UserAction::Migrate(context, _) => context.authenticated_signer,
             UserAction::Operation(context, _) => context.authenticated_signer,
             UserAction::ProcessStreams(_, _) => None,
             UserAction::Message(context, _) => context.authenticated_signer,
@@ -161,6 +163,7 @@ impl UserAction {
     pub(crate) fn height(&self) -> BlockHeight {
         match self {
             UserAction::Instantiate(context, _) => context.height,
+            UserAction::Migrate(context, _) => context.height,
             UserAction::Operation(context, _) => context.height,
             UserAction::ProcessStreams(context, _) => context.height,
             UserAction::Message(context, _) => context.height,
@@ -170,6 +173,7 @@ impl UserAction {
     pub(crate) fn round(&self) -> Option<u32> {
         match self {
             UserAction::Instantiate(context, _) => context.round,
+            UserAction::Migrate(context, _) => context.round,
             UserAction::Operation(context, _) => context.round,
             UserAction::ProcessStreams(context, _) => context.round,
             UserAction::Message(context, _) => context.round,
@@ -179,6 +183,7 @@ impl UserAction {
     pub(crate) fn timestamp(&self) -> Timestamp {
         match self {
             UserAction::Instantiate(context, _) => context.timestamp,
+            UserAction::Migrate(context, _) => context.timestamp,
             UserAction::Operation(context, _) => context.timestamp,
             UserAction::ProcessStreams(context, _) => context.timestamp,
             UserAction::Message(context, _) => context.timestamp,
@@ -207,7 +212,10 @@ where
                 application_id,
                 bytes,
             } => {
-                let ExecutionRuntimeConfig {} = self.context().extra().execution_runtime_config();
+                // Services are not traced.
+                let ExecutionRuntimeConfig {
+                    record_execution_traces: _,
+                } = self.context().extra().execution_runtime_config();
                 let outcome = match endpoint {
                     Some(endpoint) => {
                         self.query_user_application_with_long_lived_service(
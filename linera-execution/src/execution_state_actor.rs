@@ -15,11 +15,11 @@ use linera_base::{
         Timestamp,
     },
     ensure, hex_debug, hex_vec_debug, http,
-    identifiers::{Account, AccountOwner, BlobId, BlobType, ChainId, EventId, StreamId},
+    identifiers::{Account, AccountOwner, BlobId, BlobType, ChainId, EventId, StreamId, TokenId},
     ownership::ChainOwnership,
     time::Instant,
 };
-use linera_views::{batch::Batch, context::Context, views::View};
+use linera_views::{batch::Batch, context::Context, store::RangeQuery, views::View};
 use oneshot::Sender;
 use reqwest::{header::HeaderMap, Client, Url};
 
@@ -29,10 +29,10 @@ use crate::{
     system::{CreateApplicationResult, OpenChainConfig},
     util::{OracleResponseExt as _, RespondExt as _},
     ApplicationDescription, ApplicationId, ExecutionError, ExecutionRuntimeConfig,
-    ExecutionRuntimeContext, ExecutionStateView, Message, MessageContext, MessageKind, ModuleId,
-    Operation, OperationContext, OutgoingMessage, ProcessStreamsContext, QueryContext,
-    QueryOutcome, ResourceController, SystemMessage, TransactionTracker, UserContractCode,
-    UserServiceCode,
+    ExecutionRuntimeContext, ExecutionStateView, FeeSponsorship, Message, MessageContext,
+    MessageKind, ModuleId, Operation, OperationContext, OutgoingMessage, ProcessStreamsContext,
+    QueryContext, QueryOutcome, ResourceController, ScheduledOperation, ScheduledOperationKey,
+    SystemMessage, SystemOperation, TransactionTracker, UserContractCode, UserServiceCode,
 };
 
 /// Actor for handling requests to the execution state.
@@ -96,6 +96,7 @@ where
     ) -> Result<(UserContractCode, ApplicationDescription), ExecutionError> {
         #[cfg(with_metrics)]
         let _latency = metrics::LOAD_CONTRACT_LATENCY.measure_latency();
+        self.state.system.check_not_tombstoned(id).await?;
         let blob_id = id.description_blob_id();
         let description = match self.txn_tracker.get_blob_content(&blob_id) {
             Some(blob) => bcs::from_bytes(blob.bytes())?,
@@ -106,6 +107,11 @@ where
                     .await?
             }
         };
+        let description = self
+            .state
+            .system
+            .with_current_module(id, description, self.txn_tracker)
+            .await?;
         let code = self
             .state
             .context()
@@ -121,6 +127,7 @@ where
     ) -> Result<(UserServiceCode, ApplicationDescription), ExecutionError> {
         #[cfg(with_metrics)]
         let _latency = metrics::LOAD_SERVICE_LATENCY.measure_latency();
+        self.state.system.check_not_tombstoned(id).await?;
         let blob_id = id.description_blob_id();
         let description = match self.txn_tracker.get_blob_content(&blob_id) {
             Some(blob) => bcs::from_bytes(blob.bytes())?,
@@ -131,6 +138,11 @@ where
                     .await?
             }
         };
+        let description = self
+            .state
+            .system
+            .with_current_module(id, description, self.txn_tracker)
+            .await?;
         let code = self
             .state
             .context()
@@ -174,6 +186,28 @@ where
                 callback.respond(balance);
             }
 
+            OwnerTokenBalance {
+                owner,
+                token_id,
+                callback,
+            } => {
+                let balance = self.state.system.token_balance(&owner, token_id).await?;
+                callback.respond(balance);
+            }
+
+            Allowance {
+                owner,
+                spender,
+                callback,
+            } => {
+                let allowance = self
+                    .state
+                    .system
+                    .spendable_allowance(&owner, &spender)
+                    .await?;
+                callback.respond(allowance);
+            }
+
             OwnerBalances { callback } => {
                 let balances = self.state.system.balances.index_values().await?;
                 callback.respond(balances.into_iter().collect());
@@ -188,6 +222,7 @@ where
                 source,
                 destination,
                 amount,
+                token_id,
                 signer,
                 application_id,
                 callback,
@@ -195,7 +230,14 @@ where
                 let maybe_message = self
                     .state
                     .system
-                    .transfer(signer, Some(application_id), source, destination, amount)
+                    .transfer(
+                        signer,
+                        Some(application_id),
+                        source,
+                        destination,
+                        amount,
+                        token_id,
+                    )
                     .await?;
                 self.txn_tracker.add_outgoing_messages(maybe_message);
                 callback.respond(());
@@ -205,6 +247,7 @@ where
                 source,
                 destination,
                 amount,
+                token_id,
                 signer,
                 application_id,
                 callback,
@@ -219,12 +262,69 @@ where
                         source.chain_id,
                         destination,
                         amount,
+                        token_id,
                     )
                     .await?;
                 self.txn_tracker.add_outgoing_messages(maybe_message);
                 callback.respond(());
             }
 
+            MintToken {
+                token_id,
+                recipient,
+                amount,
+                application_id,
+                callback,
+            } => {
+                self.state
+                    .system
+                    .mint_token(application_id, token_id, recipient, amount)
+                    .await?;
+                callback.respond(());
+            }
+
+            BurnToken {
+                token_id,
+                owner,
+                amount,
+                signer,
+                application_id,
+                callback,
+            } => {
+                self.state
+                    .system
+                    .burn_token(signer, application_id, token_id, owner, amount)
+                    .await?;
+                callback.respond(());
+            }
+
+            ScheduleOperation {
+                application_id,
+                creator,
+                timestamp,
+                bytes,
+                budget,
+                callback,
+            } => {
+                self.state
+                    .system
+                    .schedule_operation(application_id, creator, timestamp, bytes, budget)
+                    .await?;
+                callback.respond(());
+            }
+
+            SponsorFees {
+                application_id,
+                sponsorship,
+                callback,
+            } => {
+                self.state
+                    .system
+                    .sponsor_fees(application_id, sponsorship)
+                    .await?;
+                callback.respond(());
+            }
+
             SystemTimestamp { callback } => {
                 let timestamp = *self.state.system.timestamp.get();
                 callback.respond(timestamp);
@@ -297,16 +397,70 @@ where
                 callback.respond(result);
             }
 
+            FindKeysByRange {
+                id,
+                key_prefix,
+                query,
+                callback,
+            } => {
+                let view = self.state.users.try_load_entry(&id).await?;
+                let result = match view {
+                    Some(view) => view.find_keys_by_range(&key_prefix, &query).await?,
+                    None => Vec::new(),
+                };
+                callback.respond(result);
+            }
+
+            FindKeyValuesByRange {
+                id,
+                key_prefix,
+                query,
+                callback,
+            } => {
+                let view = self.state.users.try_load_entry(&id).await?;
+                let result = match view {
+                    Some(view) => view.find_key_values_by_range(&key_prefix, &query).await?,
+                    None => Vec::new(),
+                };
+                callback.respond(result);
+            }
+
             WriteBatch {
                 id,
                 batch,
                 callback,
             } => {
-                let mut view = self.state.users.try_load_entry_mut(&id).await?;
-                view.write_batch(batch).await?;
+                // An application that deleted its storage may still run until the end of
+                // the transaction, but its writes are discarded.
+                if !self
+                    .state
+                    .system
+                    .tombstoned_applications
+                    .contains(&id)
+                    .await?
+                {
+                    let fees = self
+                        .resource_controller
+                        .policy()
+                        .bytes_written_price(batch.size() as u64)?;
+                    self.state.system.record_storage_fees(id, fees).await?;
+                    let mut view = self.state.users.try_load_entry_mut(&id).await?;
+                    view.write_batch(batch).await?;
+                }
                 callback.respond(());
             }
 
+            SelfDestruct {
+                application_id,
+                refund_recipient,
+                callback,
+            } => {
+                let released_bytes = self
+                    .delete_application(application_id, refund_recipient)
+                    .await?;
+                callback.respond(released_bytes);
+            }
+
             OpenChain {
                 ownership,
                 balance,
@@ -376,6 +530,7 @@ where
                         module_id,
                         parameters,
                         required_application_ids,
+                        None,
                         self.txn_tracker,
                     )
                     .await?;
@@ -714,6 +869,37 @@ where
         }
     }
 
+    /// Deletes all the storage of the application on this chain, marks it as tombstoned,
+    /// and credits the refund for the released bytes to `refund_recipient`. The refund never
+    /// exceeds the fees paid for writing to the application's storage. Returns the number of
+    /// released bytes.
+    async fn delete_application(
+        &mut self,
+        application_id: ApplicationId,
+        refund_recipient: AccountOwner,
+    ) -> Result<u32, ExecutionError> {
+        let storage_fees = self
+            .state
+            .system
+            .tombstone_application(application_id)
+            .await?;
+        let released_bytes = {
+            let mut view = self.state.users.try_load_entry_mut(&application_id).await?;
+            let released_bytes = view.total_size().sum();
+            let mut batch = Batch::new();
+            batch.delete_key_prefix(Vec::new());
+            view.write_batch(batch).await?;
+            released_bytes
+        };
+        let refund = self
+            .resource_controller
+            .policy()
+            .bytes_stored_refund(u64::from(released_bytes))?
+            .min(storage_fees);
+        self.state.system.credit(&refund_recipient, refund).await?;
+        Ok(released_bytes)
+    }
+
     pub(crate) async fn run_user_action(
         &mut self,
         application_id: ApplicationId,
@@ -721,9 +907,17 @@ where
         refund_grant_to: Option<Account>,
         grant: Option<&mut Amount>,
     ) -> Result<(), ExecutionError> {
-        let ExecutionRuntimeConfig {} = self.state.context().extra().execution_runtime_config();
-        self.run_user_action_with_runtime(application_id, action, refund_grant_to, grant)
-            .await
+        let ExecutionRuntimeConfig {
+            record_execution_traces,
+        } = self.state.context().extra().execution_runtime_config();
+        self.run_user_action_with_runtime(
+            application_id,
+            action,
+            refund_grant_to,
+            grant,
+            record_execution_traces,
+        )
+        .await
     }
 
     async fn run_user_action_with_runtime(
@@ -732,19 +926,23 @@ where
         action: UserAction,
         refund_grant_to: Option<Account>,
         grant: Option<&mut Amount>,
+        record_execution_trace: bool,
     ) -> Result<(), ExecutionError> {
         let chain_id = self.state.context().extra().chain_id();
         let mut cloned_grant = grant.as_ref().map(|x| **x);
+        // The runtime keeps the sponsor budget apart, so that it only pays for fees.
+        let sponsor_budget = self.resource_controller.sponsor_budget.take();
         let initial_balance = self
             .resource_controller
             .with_state_and_grant(&mut self.state.system, cloned_grant.as_mut())
             .await?
             .balance()?;
-        let controller = ResourceController::new(
+        let mut controller = ResourceController::new(
             self.resource_controller.policy().clone(),
             self.resource_controller.tracker,
             initial_balance,
         );
+        controller.sponsor_budget = sponsor_budget;
         let (execution_state_sender, mut execution_state_receiver) =
             futures::channel::mpsc::unbounded();
 
@@ -757,6 +955,7 @@ where
                 refund_grant_to,
                 controller,
                 &action,
+                record_execution_trace,
             );
 
             async move {
@@ -773,15 +972,19 @@ where
             self.handle_request(request).await?;
         }
 
-        let (result, controller) = contract_runtime_task.join().await?;
+        let (result, controller, trace) = contract_runtime_task.join().await?;
 
         self.txn_tracker.add_operation_result(result);
+        if let Some(trace) = trace {
+            self.txn_tracker.add_execution_trace(trace);
+        }
 
         self.resource_controller
             .with_state_and_grant(&mut self.state.system, grant)
             .await?
-            .merge_balance(initial_balance, controller.balance()?)?;
+            .merge_balance(initial_balance, controller.account)?;
         self.resource_controller.tracker = controller.tracker;
+        self.resource_controller.sponsor_budget = controller.sponsor_budget;
 
         Ok(())
     }
@@ -794,6 +997,53 @@ where
         assert_eq!(context.chain_id, self.state.context().extra().chain_id());
         match operation {
             Operation::System(op) => {
+                if let SystemOperation::ExecuteScheduledOperation(key) = *op {
+                    self.execute_scheduled_operation(context, key).await?;
+                    self.process_subscriptions(context.into()).await?;
+                    return Ok(());
+                }
+                if let SystemOperation::UpgradeApplication {
+                    application_id,
+                    module_id,
+                    migration_argument,
+                } = *op
+                {
+                    self.state
+                        .system
+                        .upgrade_application(
+                            context,
+                            application_id,
+                            module_id,
+                            &migration_argument,
+                            self.txn_tracker,
+                        )
+                        .await?;
+                    self.run_user_action(
+                        application_id,
+                        UserAction::Migrate(context, migration_argument),
+                        context.refund_grant_to(),
+                        None,
+                    )
+                    .await?;
+                    self.process_subscriptions(context.into()).await?;
+                    return Ok(());
+                }
+                if let SystemOperation::DeleteApplication {
+                    application_id,
+                    refund_recipient,
+                } = *op
+                {
+                    self.state
+                        .system
+                        .check_application_deletion(context, application_id, self.txn_tracker)
+                        .await?;
+                    let released_bytes = self
+                        .delete_application(application_id, refund_recipient)
+                        .await?;
+                    self.resource_controller
+                        .track_stored_bytes_released(released_bytes)?;
+                    return Ok(());
+                }
                 let new_application = self
                     .state
                     .system
@@ -827,6 +1077,50 @@ where
         Ok(())
     }
 
+    /// Executes a due scheduled operation on behalf of the application that scheduled it.
+    ///
+    /// There is no authenticated signer, and the fees are paid from the operation's prepaid
+    /// budget only: The block's signer is not charged. The unspent budget is credited back
+    /// to the application's account.
+    async fn execute_scheduled_operation(
+        &mut self,
+        context: OperationContext,
+        key: ScheduledOperationKey,
+    ) -> Result<(), ExecutionError> {
+        let ScheduledOperation {
+            application_id,
+            bytes,
+            mut budget,
+            ..
+        } = self
+            .state
+            .system
+            .take_due_scheduled_operation(key, context.timestamp)
+            .await?;
+        let context = OperationContext {
+            authenticated_signer: None,
+            ..context
+        };
+        let signer_account = self.resource_controller.account.take();
+        let result = self
+            .run_user_action(
+                application_id,
+                UserAction::Operation(context, bytes),
+                None,
+                Some(&mut budget),
+            )
+            .await;
+        self.resource_controller.account = signer_account;
+        result?;
+        if budget > Amount::ZERO {
+            self.state
+                .system
+                .credit(&AccountOwner::from(application_id), budget)
+                .await?;
+        }
+        Ok(())
+    }
+
     pub async fn execute_message(
         &mut self,
         context: MessageContext,
@@ -986,6 +1280,20 @@ pub enum ExecutionRequest {
         callback: Sender<Amount>,
     },
 
+    OwnerTokenBalance {
+        owner: AccountOwner,
+        token_id: TokenId,
+        #[debug(skip)]
+        callback: Sender<Amount>,
+    },
+
+    Allowance {
+        owner: AccountOwner,
+        spender: AccountOwner,
+        #[debug(skip)]
+        callback: Sender<Amount>,
+    },
+
     OwnerBalances {
         #[debug(skip)]
         callback: Sender<Vec<(AccountOwner, Amount)>>,
@@ -1001,6 +1309,8 @@ pub enum ExecutionRequest {
         destination: Account,
         amount: Amount,
         #[debug(skip_if = Option::is_none)]
+        token_id: Option<TokenId>,
+        #[debug(skip_if = Option::is_none)]
         signer: Option<AccountOwner>,
         application_id: ApplicationId,
         #[debug(skip)]
@@ -1012,12 +1322,52 @@ pub enum ExecutionRequest {
         destination: Account,
         amount: Amount,
         #[debug(skip_if = Option::is_none)]
+        token_id: Option<TokenId>,
+        #[debug(skip_if = Option::is_none)]
         signer: Option<AccountOwner>,
         application_id: ApplicationId,
         #[debug(skip)]
         callback: Sender<()>,
     },
 
+    MintToken {
+        token_id: TokenId,
+        recipient: AccountOwner,
+        amount: Amount,
+        application_id: ApplicationId,
+        #[debug(skip)]
+        callback: Sender<()>,
+    },
+
+    BurnToken {
+        token_id: TokenId,
+        owner: AccountOwner,
+        amount: Amount,
+        #[debug(skip_if = Option::is_none)]
+        signer: Option<AccountOwner>,
+        application_id: ApplicationId,
+        #[debug(skip)]
+        callback: Sender<()>,
+    },
+
+    ScheduleOperation {
+        application_id: ApplicationId,
+        creator: Option<AccountOwner>,
+        timestamp: Timestamp,
+        #[debug(with = hex_debug)]
+        bytes: Vec<u8>,
+        budget: Amount,
+        #[debug(skip)]
+        callback: Sender<()>,
+    },
+
+    SponsorFees {
+        application_id: ApplicationId,
+        sponsorship: FeeSponsorship,
+        #[debug(skip)]
+        callback: Sender<()>,
+    },
+
     SystemTimestamp {
         #[debug(skip)]
         callback: Sender<Timestamp>,
@@ -1074,6 +1424,24 @@ pub enum ExecutionRequest {
         callback: Sender<Vec<(Vec<u8>, Vec<u8>)>>,
     },
 
+    FindKeysByRange {
+        id: ApplicationId,
+        #[debug(with = hex_debug)]
+        key_prefix: Vec<u8>,
+        query: RangeQuery,
+        #[debug(skip)]
+        callback: Sender<Vec<Vec<u8>>>,
+    },
+
+    FindKeyValuesByRange {
+        id: ApplicationId,
+        #[debug(with = hex_debug)]
+        key_prefix: Vec<u8>,
+        query: RangeQuery,
+        #[debug(skip)]
+        callback: Sender<Vec<(Vec<u8>, Vec<u8>)>>,
+    },
+
     WriteBatch {
         id: ApplicationId,
         batch: Batch,
@@ -1081,6 +1449,13 @@ pub enum ExecutionRequest {
         callback: Sender<()>,
     },
 
+    SelfDestruct {
+        application_id: ApplicationId,
+        refund_recipient: AccountOwner,
+        #[debug(skip)]
+        callback: Sender<u32>,
+    },
+
     OpenChain {
         ownership: ChainOwnership,
         #[debug(skip_if = Amount::is_zero)]
@@ -0,0 +1,54 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Attribution of the fuel consumed by Wasm contracts to the functions that consumed it.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    sync::{Arc, Mutex},
+};
+
+/// The fuel consumed by Wasm contracts, attributed to the stacks of guest functions that
+/// were executing when it was charged.
+///
+/// Only contracts executed with Wasmtime are profiled. Clones share the same samples.
+#[derive(Clone, Debug, Default)]
+pub struct FuelProfile {
+    /// The fuel consumed by each stack, with frames listed from the outermost to the
+    /// innermost and separated by `;`.
+    stacks: Arc<Mutex<BTreeMap<String, u64>>>,
+}
+
+impl FuelProfile {
+    /// Creates a new empty [`FuelProfile`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attributes `fuel` to the given `stack` of function names.
+    pub(crate) fn record(&self, stack: String, fuel: u64) {
+        let mut stacks = self.stacks.lock().unwrap();
+        let total = stacks.entry(stack).or_default();
+        *total = total.saturating_add(fuel);
+    }
+
+    /// Returns the total fuel recorded so far.
+    pub fn total_fuel(&self) -> u64 {
+        self.stacks
+            .lock()
+            .unwrap()
+            .values()
+            .fold(0, |total, fuel| total.saturating_add(*fuel))
+    }
+
+    /// Returns the profile in the folded-stack format read by flamegraph tools: one line per
+    /// stack, followed by the fuel it consumed.
+    pub fn to_folded(&self) -> String {
+        let mut folded = String::new();
+        for (stack, fuel) in self.stacks.lock().unwrap().iter() {
+            writeln!(folded, "{stack} {fuel}").expect("writing to a `String` should not fail");
+        }
+        folded
+    }
+}
@@ -7,19 +7,31 @@ use linera_base::{
     crypto::ValidatorPublicKey,
     data_types::{Amount, ChainDescription, Epoch, Timestamp},
     doc_scalar,
-    identifiers::{AccountOwner, ChainId},
+    identifiers::{AccountOwner, ApplicationId, ChainId, TokenId},
     ownership::ChainOwnership,
 };
 use linera_views::{context::Context, map_view::MapView};
 
 use crate::{
     committee::{Committee, ValidatorState},
-    policy::ResourceControlPolicy,
-    system::UserData,
-    ExecutionStateView, SystemExecutionStateView,
+    policy::{FeeMultiplier, ResourceControlPolicy},
+    system::{Allowance, FeeSponsorship, UserData},
+    ExecutionStateView, ExecutionTrace, ResourceTracker, SystemExecutionStateView,
 };
 
 doc_scalar!(UserData, "Optional user message attached to a transfer");
+doc_scalar!(
+    FeeMultiplier,
+    "A factor applied to all prices of a resource control policy, in millionths"
+);
+doc_scalar!(
+    ExecutionTrace,
+    "The call tree recorded while executing a user action"
+);
+doc_scalar!(
+    ResourceTracker,
+    "The resources used by the execution of a block or a transaction"
+);
 
 async_graphql::scalar!(
     ResourceControlPolicy,
@@ -100,6 +112,41 @@ impl<C: Send + Sync + Context> SystemExecutionStateView<C> {
         &self.balances
     }
 
+    #[graphql(derived(name = "token_balance"))]
+    async fn _token_balance(
+        &self,
+        owner: AccountOwner,
+        token_id: TokenId,
+    ) -> Result<Amount, async_graphql::Error> {
+        Ok(self
+            .token_balances
+            .get(&(owner, token_id))
+            .await?
+            .unwrap_or_default())
+    }
+
+    #[graphql(derived(name = "allowance"))]
+    async fn _allowance(
+        &self,
+        owner: AccountOwner,
+        spender: AccountOwner,
+    ) -> Result<Option<Allowance>, async_graphql::Error> {
+        Ok(self.allowances.get(&(owner, spender)).await?)
+    }
+
+    #[graphql(derived(name = "fee_sponsorship"))]
+    async fn _fee_sponsorship(
+        &self,
+        application_id: ApplicationId,
+    ) -> Result<Option<FeeSponsorship>, async_graphql::Error> {
+        Ok(self.fee_sponsorships.get(&application_id).await?)
+    }
+
+    #[graphql(derived(name = "fee_multiplier"))]
+    async fn _fee_multiplier(&self) -> &FeeMultiplier {
+        self.fee_multiplier.get()
+    }
+
     #[graphql(derived(name = "timestamp"))]
     async fn _timestamp(&self) -> &Timestamp {
         self.timestamp.get()
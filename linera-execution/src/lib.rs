@@ -8,6 +8,7 @@ pub mod committee;
 pub mod evm;
 mod execution;
 pub mod execution_state_actor;
+mod fuel_profile;
 #[cfg(with_graphql)]
 mod graphql;
 mod policy;
@@ -16,6 +17,7 @@ mod runtime;
 pub mod system;
 #[cfg(with_testing)]
 pub mod test_utils;
+mod trace;
 mod transaction_tracker;
 mod util;
 mod wasm;
@@ -30,7 +32,7 @@ use derive_more::Display;
 use js_sys::wasm_bindgen::JsValue;
 use linera_base::{
     abi::Abi,
-    crypto::{BcsHashable, CryptoHash},
+    crypto::{BcsHashable, CryptoHash, ValidatorPublicKey},
     data_types::{
         Amount, ApplicationDescription, ApplicationPermissions, ArithmeticError, Blob, BlockHeight,
         Bytecode, DecompressionError, Epoch, NetworkDescription, SendMessageRequest, StreamUpdate,
@@ -39,13 +41,13 @@ use linera_base::{
     doc_scalar, hex_debug, http,
     identifiers::{
         Account, AccountOwner, ApplicationId, BlobId, BlobType, ChainId, DataBlobHash, EventId,
-        GenericApplicationId, ModuleId, StreamName,
+        GenericApplicationId, ModuleId, StreamName, TokenId,
     },
     ownership::ChainOwnership,
     task,
     vm::VmRuntime,
 };
-use linera_views::{batch::Batch, ViewError};
+use linera_views::{batch::Batch, store::RangeQuery, ViewError};
 use serde::{Deserialize, Serialize};
 use system::AdminOperation;
 use thiserror::Error;
@@ -65,15 +67,18 @@ pub use crate::{
     committee::Committee,
     execution::{ExecutionStateView, ServiceRuntimeEndpoint},
     execution_state_actor::{ExecutionRequest, ExecutionStateActor},
-    policy::ResourceControlPolicy,
+    fuel_profile::FuelProfile,
+    policy::{DynamicFeePolicy, FeeMultiplier, ResourceControlPolicy},
     resources::{BalanceHolder, ResourceController, ResourceTracker},
     runtime::{
         ContractSyncRuntimeHandle, ServiceRuntimeRequest, ServiceSyncRuntime,
         ServiceSyncRuntimeHandle,
     },
     system::{
+        Allowance, ApplicationUpgrade, FeeSponsorship, ScheduledOperation, ScheduledOperationKey,
         SystemExecutionStateView, SystemMessage, SystemOperation, SystemQuery, SystemResponse,
     },
+    trace::{ExecutionTrace, TraceEntry, TraceFrame},
     transaction_tracker::{TransactionOutcome, TransactionTracker},
 };
 
@@ -300,6 +305,31 @@ pub enum ExecutionError {
         balance: Amount,
         account: AccountOwner,
     },
+    #[error("The amount must not exceed the balance of token {token_id} in the account {account}: {balance}")]
+    InsufficientTokenBalance {
+        token_id: TokenId,
+        balance: Amount,
+        account: AccountOwner,
+    },
+    #[error("Application {application_id} is not the issuer of token {token_id}")]
+    UnauthorizedTokenIssuer {
+        token_id: TokenId,
+        application_id: ApplicationId,
+    },
+    #[error("Approval of spending from an owned account must be authenticated by the owner")]
+    UnauthenticatedApprovalOwner,
+    #[error("The transferred amount must not exceed the allowance of {spender} on the account {owner}: {allowance}")]
+    InsufficientAllowance {
+        owner: AccountOwner,
+        spender: AccountOwner,
+        allowance: Amount,
+    },
+    #[error("The allowance of {spender} on the account {owner} expired at {expiry}")]
+    AllowanceExpired {
+        owner: AccountOwner,
+        spender: AccountOwner,
+        expiry: Timestamp,
+    },
     #[error("Required execution fees exceeded the total funding available. Fees {fees}, available balance: {balance}")]
     FeesExceedFunding { fees: Amount, balance: Amount },
     #[error("Claim must have positive amount")]
@@ -312,6 +342,8 @@ pub enum ExecutionError {
     InvalidCommitteeEpoch { expected: Epoch, provided: Epoch },
     #[error("Failed to remove committee")]
     InvalidCommitteeRemoval,
+    #[error("The aggregate key of validator {0} has no valid proof of possession")]
+    InvalidAggregateKey(ValidatorPublicKey),
     #[error("No recorded response for oracle query")]
     MissingOracleResponse,
     #[error("process_streams was not called for all stream updates")]
@@ -320,6 +352,35 @@ pub enum ExecutionError {
     InternalError(&'static str),
     #[error("UpdateStreams is outdated")]
     OutdatedUpdateStreams,
+    #[error("Scheduled operation {0:?} does not exist")]
+    ScheduledOperationNotFound(ScheduledOperationKey),
+    #[error(
+        "Scheduled operation is due at {due}, which is later than the block timestamp {timestamp}"
+    )]
+    ScheduledOperationNotDue {
+        due: Timestamp,
+        timestamp: Timestamp,
+    },
+    #[error("Scheduled operation {0:?} can only be discarded by its creator or a chain owner")]
+    UnauthorizedScheduledOperationDiscard(ScheduledOperationKey),
+    #[error(
+        "Application {0} can only be upgraded by its upgrade authority, or as announced by its \
+         creator chain"
+    )]
+    UnauthorizedApplicationUpgrade(ApplicationId),
+    #[error("Application {0} can only be upgraded from a Wasm module to another Wasm module")]
+    IncompatibleApplicationUpgrade(ApplicationId),
+    #[error("Application {0} can only be deleted by a chain owner or its upgrade authority")]
+    UnauthorizedApplicationDeletion(ApplicationId),
+    #[error("The storage of application {0} has been deleted on this chain")]
+    ApplicationTombstoned(ApplicationId),
+    #[error("The approval threshold {0} exceeds the total weight of the chain owners")]
+    UnreachableApprovalThreshold(u64),
+    #[error("{error}")]
+    Traced {
+        error: Box<ExecutionError>,
+        trace: Box<ExecutionTrace>,
+    },
 }
 
 impl ExecutionError {
@@ -364,20 +425,35 @@ impl ExecutionError {
             | ExecutionError::IncorrectTransferAmount
             | ExecutionError::UnauthenticatedTransferOwner
             | ExecutionError::InsufficientBalance { .. }
+            | ExecutionError::InsufficientTokenBalance { .. }
+            | ExecutionError::UnauthorizedTokenIssuer { .. }
+            | ExecutionError::UnauthenticatedApprovalOwner
+            | ExecutionError::InsufficientAllowance { .. }
+            | ExecutionError::AllowanceExpired { .. }
             | ExecutionError::FeesExceedFunding { .. }
             | ExecutionError::IncorrectClaimAmount
             | ExecutionError::UnauthenticatedClaimOwner
             | ExecutionError::AdminOperationOnNonAdminChain
             | ExecutionError::InvalidCommitteeEpoch { .. }
             | ExecutionError::InvalidCommitteeRemoval
+            | ExecutionError::InvalidAggregateKey(_)
             | ExecutionError::MissingOracleResponse
             | ExecutionError::UnprocessedStreams
             | ExecutionError::OutdatedUpdateStreams
+            | ExecutionError::ScheduledOperationNotFound(_)
+            | ExecutionError::ScheduledOperationNotDue { .. }
+            | ExecutionError::UnauthorizedScheduledOperationDiscard(_)
+            | ExecutionError::UnauthorizedApplicationUpgrade(_)
+            | ExecutionError::IncompatibleApplicationUpgrade(_)
+            | ExecutionError::UnauthorizedApplicationDeletion(_)
+            | ExecutionError::ApplicationTombstoned(_)
+            | ExecutionError::UnreachableApprovalThreshold(_)
             | ExecutionError::ViewError(ViewError::NotFound(_)) => false,
             #[cfg(with_wasm_runtime)]
             ExecutionError::WasmError(_) => false,
             #[cfg(with_revm)]
             ExecutionError::EvmError(..) => false,
+            ExecutionError::Traced { error, .. } => error.is_local(),
             ExecutionError::MissingRuntimeResponse
             | ExecutionError::ViewError(_)
             | ExecutionError::ReqwestError(_)
@@ -388,6 +464,38 @@ impl ExecutionError {
             | ExecutionError::IoError(_) => true,
         }
     }
+
+    /// Attaches the trace of the failed user action to this error. Errors about missing
+    /// blobs and events are left as they are, since nodes handle them by downloading the
+    /// missing data and retrying.
+    pub(crate) fn with_trace(self, trace: ExecutionTrace) -> Self {
+        match self {
+            ExecutionError::BlobsNotFound(_)
+            | ExecutionError::EventsNotFound(_)
+            | ExecutionError::Traced { .. } => self,
+            error => ExecutionError::Traced {
+                error: Box::new(error),
+                trace: Box::new(trace),
+            },
+        }
+    }
+
+    /// Returns the trace of the user action that failed with this error, if execution
+    /// traces were being recorded.
+    pub fn trace(&self) -> Option<&ExecutionTrace> {
+        match self {
+            ExecutionError::Traced { trace, .. } => Some(trace.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Returns this error without the execution trace that may be attached to it.
+    pub fn without_trace(&self) -> &ExecutionError {
+        match self {
+            ExecutionError::Traced { error, .. } => error.without_trace(),
+            error => error,
+        }
+    }
 }
 
 /// The public entry points provided by the contract part of an application.
@@ -395,6 +503,9 @@ pub trait UserContract {
     /// Instantiate the application state on the chain that owns the application.
     fn instantiate(&mut self, argument: Vec<u8>) -> Result<(), ExecutionError>;
 
+    /// Migrates the application state after the application was upgraded to this module.
+    fn migrate(&mut self, argument: Vec<u8>) -> Result<(), ExecutionError>;
+
     /// Applies an operation from the current block.
     fn execute_operation(&mut self, operation: Vec<u8>) -> Result<Vec<u8>, ExecutionError>;
 
@@ -416,7 +527,10 @@ pub trait UserService {
 
 /// Configuration options for the execution runtime available to applications.
 #[derive(Clone, Copy, Default)]
-pub struct ExecutionRuntimeConfig {}
+pub struct ExecutionRuntimeConfig {
+    /// Whether to record an [`ExecutionTrace`] of each user action.
+    pub record_execution_traces: bool,
+}
 
 /// Requirements for the `extra` field in our state views (and notably the
 /// [`ExecutionStateView`]).
@@ -598,6 +712,20 @@ pub trait BaseRuntime {
     /// Reads the owner balance.
     fn read_owner_balance(&mut self, owner: AccountOwner) -> Result<Amount, ExecutionError>;
 
+    /// Reads the owner's balance of a token issued by an application.
+    fn read_owner_token_balance(
+        &mut self,
+        owner: AccountOwner,
+        token_id: TokenId,
+    ) -> Result<Amount, ExecutionError>;
+
+    /// Reads the amount that `spender` can currently transfer out of the `owner`'s account.
+    fn read_allowance(
+        &mut self,
+        owner: AccountOwner,
+        spender: AccountOwner,
+    ) -> Result<Amount, ExecutionError>;
+
     /// Reads the balances from all owners.
     fn read_owner_balances(&mut self) -> Result<Vec<(AccountOwner, Amount)>, ExecutionError>;
 
@@ -692,6 +820,14 @@ pub trait BaseRuntime {
         promise: &Self::FindKeysByPrefix,
     ) -> Result<Vec<Vec<u8>>, ExecutionError>;
 
+    /// Creates the promise to access keys having a specific prefix, within the bounds of
+    /// the `query`. The promise is resolved with `find_keys_by_prefix_wait`.
+    fn find_keys_by_range_new(
+        &mut self,
+        key_prefix: Vec<u8>,
+        query: RangeQuery,
+    ) -> Result<Self::FindKeysByPrefix, ExecutionError>;
+
     /// Reads the data from the key/values having a specific prefix.
     #[cfg(feature = "test")]
     #[expect(clippy::type_complexity)]
@@ -716,6 +852,14 @@ pub trait BaseRuntime {
         promise: &Self::FindKeyValuesByPrefix,
     ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ExecutionError>;
 
+    /// Creates the promise to access key/values having a specific prefix, within the
+    /// bounds of the `query`. The promise is resolved with `find_key_values_by_prefix_wait`.
+    fn find_key_values_by_range_new(
+        &mut self,
+        key_prefix: Vec<u8>,
+        query: RangeQuery,
+    ) -> Result<Self::FindKeyValuesByPrefix, ExecutionError>;
+
     /// Makes an HTTP request to the given URL and returns the answer, if any.
     fn perform_http_request(
         &mut self,
@@ -778,22 +922,69 @@ pub trait ContractRuntime: BaseRuntime {
     /// Schedules a message to be sent.
     fn send_message(&mut self, message: SendMessageRequest<Vec<u8>>) -> Result<(), ExecutionError>;
 
-    /// Transfers amount from source to destination.
+    /// Transfers amount from source to destination, in the given token or in the native
+    /// token if `token_id` is `None`.
     fn transfer(
         &mut self,
         source: AccountOwner,
         destination: Account,
         amount: Amount,
+        token_id: Option<TokenId>,
     ) -> Result<(), ExecutionError>;
 
-    /// Claims amount from source to destination.
+    /// Claims amount from source to destination, in the given token or in the native token
+    /// if `token_id` is `None`.
     fn claim(
         &mut self,
         source: Account,
         destination: Account,
         amount: Amount,
+        token_id: Option<TokenId>,
+    ) -> Result<(), ExecutionError>;
+
+    /// Mints amount of a token issued by the current application into an account on the
+    /// current chain.
+    fn mint_token(
+        &mut self,
+        token_id: TokenId,
+        recipient: AccountOwner,
+        amount: Amount,
+    ) -> Result<(), ExecutionError>;
+
+    /// Burns amount of a token issued by the current application from an account on the
+    /// current chain.
+    fn burn_token(
+        &mut self,
+        token_id: TokenId,
+        owner: AccountOwner,
+        amount: Amount,
+    ) -> Result<(), ExecutionError>;
+
+    /// Schedules an operation of the current application to be executed in the first block
+    /// with a timestamp no earlier than `timestamp`. The `budget` for its execution fees is
+    /// debited from the application's account immediately.
+    fn schedule_operation_at(
+        &mut self,
+        timestamp: Timestamp,
+        operation: Vec<u8>,
+        budget: Amount,
     ) -> Result<(), ExecutionError>;
 
+    /// Makes the current application pay the execution fees of the operations targeting it,
+    /// from its own account, up to `block_budget` per block and `owner_budget` in total for
+    /// each block signer. Zero budgets end the sponsorship.
+    fn sponsor_fees(
+        &mut self,
+        block_budget: Amount,
+        owner_budget: Amount,
+    ) -> Result<(), ExecutionError>;
+
+    /// Deletes all the storage of the current application on this chain, and credits the
+    /// refund for the released bytes to `refund_recipient`. The application can no longer
+    /// be executed on this chain afterwards, and its later writes in the current transaction
+    /// are discarded.
+    fn self_destruct(&mut self, refund_recipient: AccountOwner) -> Result<(), ExecutionError>;
+
     /// Calls another application. Forwarded sessions will now be visible to
     /// `callee_id` (but not to the caller any more).
     fn try_call_application(
@@ -1281,6 +1472,8 @@ impl Operation {
             SystemOperation::ProcessNewEpoch(_)
                 | SystemOperation::ProcessRemovedEpoch(_)
                 | SystemOperation::UpdateStreams(_)
+                | SystemOperation::ExecuteScheduledOperation(_)
+                | SystemOperation::DiscardScheduledOperation(_)
         )
     }
 }
@@ -9,6 +9,10 @@
 //! It also sets overarching limits such as the maximum fuel allowed per block,
 //! the maximum block size, and limits on concurrent operations.
 
+#[cfg(test)]
+#[path = "unit_tests/policy_tests.rs"]
+mod tests;
+
 use std::{collections::BTreeSet, fmt};
 
 use linera_base::{
@@ -19,7 +23,7 @@ use linera_base::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::ExecutionError;
+use crate::{ExecutionError, ResourceTracker};
 
 /// A collection of prices and limits associated with block execution.
 #[derive(Eq, PartialEq, Hash, Clone, Debug, Serialize, Deserialize)]
@@ -49,6 +53,9 @@ pub struct ResourceControlPolicy {
     /// The price of increasing storage by a byte.
     // TODO(#1536): This is not fully supported.
     pub byte_stored: Amount,
+    /// The amount refunded for each stored byte that is released when the storage of an
+    /// application is deleted, up to the fees paid for writing to that storage.
+    pub byte_stored_refund: Amount,
     /// The base price of adding an operation to a block.
     pub operation: Amount,
     /// The additional price for each byte in the argument of a user operation.
@@ -93,6 +100,101 @@ pub struct ResourceControlPolicy {
     pub http_request_timeout_ms: u64,
     /// The list of hosts that contracts and services can send HTTP requests to.
     pub http_request_allow_list: BTreeSet<String>,
+    /// If set, all prices are scaled by a multiplier that follows the recent load of each
+    /// chain.
+    pub dynamic_fees: Option<DynamicFeePolicy>,
+}
+
+/// The parameters of the EIP-1559-style dynamic base fee.
+///
+/// After each block, the fee multiplier of the chain moves towards the load of that block
+/// relative to the targets: it increases if the block used more fuel or bytes than the
+/// targets and decreases otherwise, by at most one `FEE_MULTIPLIER_CHANGE_DENOMINATOR`th
+/// per block. The multiplier never drops below one, so the prices of the policy are the
+/// prices of an idle chain.
+#[derive(Eq, PartialEq, Hash, Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct DynamicFeePolicy {
+    /// The Wasm and EVM fuel per block at which the fee multiplier stays constant.
+    pub target_fuel_per_block: u64,
+    /// The number of bytes read and written per block at which the fee multiplier stays
+    /// constant.
+    pub target_bytes_per_block: u64,
+    /// The maximum fee multiplier.
+    pub maximum_multiplier: u64,
+}
+
+/// The maximum relative change of the fee multiplier from one block to the next is one over
+/// this value.
+pub const FEE_MULTIPLIER_CHANGE_DENOMINATOR: u64 = 8;
+
+impl DynamicFeePolicy {
+    /// Returns the fee multiplier to use after a block with the given resource usage, if the
+    /// multiplier was `current` during that block.
+    pub fn next_fee_multiplier(
+        &self,
+        current: FeeMultiplier,
+        tracker: &ResourceTracker,
+    ) -> FeeMultiplier {
+        let fuel = tracker.wasm_fuel.saturating_add(tracker.evm_fuel);
+        let bytes = tracker.bytes_read.saturating_add(tracker.bytes_written);
+        // The load of the block relative to the targets, in parts per million, between
+        // zero and twice the target.
+        let load = Self::relative_load(fuel, self.target_fuel_per_block)
+            .max(Self::relative_load(bytes, self.target_bytes_per_block));
+        let current = u128::from(current.0);
+        let scale = u128::from(FeeMultiplier::SCALE);
+        let denominator = u128::from(FEE_MULTIPLIER_CHANGE_DENOMINATOR) * scale;
+        let next = if load >= scale {
+            current.saturating_add(current * (load - scale) / denominator)
+        } else {
+            current.saturating_sub(current * (scale - load) / denominator)
+        };
+        let maximum = self.maximum_multiplier.saturating_mul(FeeMultiplier::SCALE);
+        let next = u64::try_from(next).unwrap_or(u64::MAX);
+        FeeMultiplier(next.clamp(FeeMultiplier::SCALE, maximum.max(FeeMultiplier::SCALE)))
+    }
+
+    fn relative_load(used: u64, target: u64) -> u128 {
+        let scale = u128::from(FeeMultiplier::SCALE);
+        if target == 0 {
+            return scale;
+        }
+        (u128::from(used) * scale / u128::from(target)).min(2 * scale)
+    }
+}
+
+/// A factor applied to all prices of a [`ResourceControlPolicy`], in millionths.
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct FeeMultiplier(pub u64);
+
+impl FeeMultiplier {
+    /// The value of a multiplier of one.
+    pub const SCALE: u64 = 1_000_000;
+    /// The multiplier that leaves prices unchanged.
+    pub const ONE: FeeMultiplier = FeeMultiplier(Self::SCALE);
+
+    /// Returns the given amount multiplied by this, saturating on overflow.
+    pub fn apply(&self, amount: Amount) -> Amount {
+        let amount = u128::from(amount);
+        let scale = u128::from(Self::SCALE);
+        let multiplier = u128::from(self.0);
+        let scaled = (amount / scale)
+            .saturating_mul(multiplier)
+            .saturating_add(amount % scale * multiplier / scale);
+        Amount::from_attos(scaled)
+    }
+}
+
+impl Default for FeeMultiplier {
+    fn default() -> Self {
+        Self::ONE
+    }
+}
+
+impl fmt::Display for FeeMultiplier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{:06}", self.0 / Self::SCALE, self.0 % Self::SCALE)
+    }
 }
 
 impl fmt::Display for ResourceControlPolicy {
@@ -110,6 +212,7 @@ impl fmt::Display for ResourceControlPolicy {
             blob_byte_read,
             blob_byte_published,
             byte_stored,
+            byte_stored_refund,
             operation,
             operation_byte,
             message,
@@ -130,6 +233,7 @@ impl fmt::Display for ResourceControlPolicy {
             maximum_http_response_bytes,
             http_request_allow_list,
             http_request_timeout_ms,
+            dynamic_fees,
         } = self;
         write!(
             f,
@@ -146,6 +250,7 @@ impl fmt::Display for ResourceControlPolicy {
             {blob_byte_read:.2} cost of reading blobs, per byte\n\
             {blob_byte_published:.2} cost of publishing blobs, per byte\n\
             {byte_stored:.2} cost per byte stored\n\
+            {byte_stored_refund:.2} refund per stored byte released\n\
             {operation:.2} per operation\n\
             {operation_byte:.2} per byte in the argument of an operation\n\
             {service_as_oracle_query:.2} per query to a service as an oracle\n\
@@ -168,6 +273,20 @@ impl fmt::Display for ResourceControlPolicy {
             {http_request_timeout_ms} ms timeout for HTTP requests\n\
             HTTP hosts allowed for contracts and services: {http_request_allow_list:#?}\n",
         )?;
+        match dynamic_fees {
+            None => writeln!(f, "Static prices")?,
+            Some(DynamicFeePolicy {
+                target_fuel_per_block,
+                target_bytes_per_block,
+                maximum_multiplier,
+            }) => write!(
+                f,
+                "Dynamic prices:\n\
+                {target_fuel_per_block} target fuel per block\n\
+                {target_bytes_per_block} target bytes read and written per block\n\
+                {maximum_multiplier} maximum price multiplier\n",
+            )?,
+        }
         Ok(())
     }
 }
@@ -196,6 +315,7 @@ impl ResourceControlPolicy {
             blob_byte_read: Amount::ZERO,
             blob_byte_published: Amount::ZERO,
             byte_stored: Amount::ZERO,
+            byte_stored_refund: Amount::ZERO,
             operation: Amount::ZERO,
             operation_byte: Amount::ZERO,
             message: Amount::ZERO,
@@ -216,6 +336,20 @@ impl ResourceControlPolicy {
             maximum_http_response_bytes: u64::MAX,
             http_request_timeout_ms: u64::MAX,
             http_request_allow_list: BTreeSet::new(),
+            dynamic_fees: None,
+        }
+    }
+
+    /// Returns dynamic fee parameters that target half of the limits of this policy, as in
+    /// EIP-1559.
+    pub fn default_dynamic_fees(&self) -> DynamicFeePolicy {
+        let maximum_bytes_per_block = self
+            .maximum_bytes_read_per_block
+            .saturating_add(self.maximum_bytes_written_per_block);
+        DynamicFeePolicy {
+            target_fuel_per_block: self.maximum_wasm_fuel_per_block / 2,
+            target_bytes_per_block: maximum_bytes_per_block / 2,
+            maximum_multiplier: 100,
         }
     }
 
@@ -275,6 +409,7 @@ impl ResourceControlPolicy {
             read_operation: Amount::from_micros(10),
             write_operation: Amount::from_micros(20),
             byte_stored: Amount::from_nanos(10),
+            byte_stored_refund: Amount::ZERO,
             message_byte: Amount::from_nanos(100),
             operation_byte: Amount::from_nanos(10),
             operation: Amount::from_micros(10),
@@ -295,6 +430,37 @@ impl ResourceControlPolicy {
             maximum_http_response_bytes: 10_000,
             http_request_timeout_ms: 20_000,
             http_request_allow_list: BTreeSet::new(),
+            dynamic_fees: None,
+        }
+    }
+
+    /// Returns this policy with all prices multiplied by `multiplier`. The limits are
+    /// unchanged.
+    pub fn with_fee_multiplier(self, multiplier: FeeMultiplier) -> Self {
+        if multiplier == FeeMultiplier::ONE {
+            return self;
+        }
+        let apply = |amount| multiplier.apply(amount);
+        Self {
+            wasm_fuel_unit: apply(self.wasm_fuel_unit),
+            evm_fuel_unit: apply(self.evm_fuel_unit),
+            read_operation: apply(self.read_operation),
+            write_operation: apply(self.write_operation),
+            byte_runtime: apply(self.byte_runtime),
+            byte_read: apply(self.byte_read),
+            byte_written: apply(self.byte_written),
+            blob_read: apply(self.blob_read),
+            blob_published: apply(self.blob_published),
+            blob_byte_read: apply(self.blob_byte_read),
+            blob_byte_published: apply(self.blob_byte_published),
+            byte_stored: apply(self.byte_stored),
+            operation: apply(self.operation),
+            operation_byte: apply(self.operation_byte),
+            message: apply(self.message),
+            message_byte: apply(self.message_byte),
+            service_as_oracle_query: apply(self.service_as_oracle_query),
+            http_request: apply(self.http_request),
+            ..self
         }
     }
 
@@ -376,6 +542,11 @@ impl ResourceControlPolicy {
         self.byte_stored.try_mul(count as u128)
     }
 
+    /// Returns the refund for releasing `count` stored bytes.
+    pub(crate) fn bytes_stored_refund(&self, count: u64) -> Result<Amount, ArithmeticError> {
+        self.byte_stored_refund.try_mul(count as u128)
+    }
+
     /// Returns how much it would cost to perform `count` queries to services running as oracles.
     pub(crate) fn service_as_oracle_queries_price(
         &self,
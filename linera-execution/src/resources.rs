@@ -14,7 +14,7 @@ use linera_base::{
     vm::VmRuntime,
 };
 use linera_views::{context::Context, ViewError};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::{ExecutionError, Message, Operation, ResourceControlPolicy, SystemExecutionStateView};
 
@@ -26,6 +26,10 @@ pub struct ResourceController<Account = Amount, Tracker = ResourceTracker> {
     pub tracker: Tracker,
     /// The account paying for the resource usage.
     pub account: Account,
+    /// The fees prepaid by an application sponsoring the current operation, if any. They are
+    /// used before any other source of funding, but never for message grants, since unused
+    /// grants are refunded to the signer.
+    pub sponsor_budget: Option<Amount>,
 }
 
 impl<Account, Tracker> ResourceController<Account, Tracker> {
@@ -35,6 +39,7 @@ impl<Account, Tracker> ResourceController<Account, Tracker> {
             policy,
             tracker,
             account,
+            sponsor_budget: None,
         }
     }
 
@@ -106,7 +111,7 @@ mod tests {
 /// a specific execution flow. This could be the execution of a block,
 /// the processing of a single message, or a specific phase within these
 /// broader operations.
-#[derive(Copy, Debug, Clone, Default)]
+#[derive(Copy, Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ResourceTracker {
     /// The total size of the block so far.
     pub block_size: u64,
@@ -150,6 +155,8 @@ pub struct ResourceTracker {
     pub service_oracle_execution: Duration,
     /// The amount allocated to message grants.
     pub grants: Amount,
+    /// The total fees charged so far, not including message grants.
+    pub fees: Amount,
 }
 
 impl ResourceTracker {
@@ -176,10 +183,15 @@ where
     Account: BalanceHolder,
     Tracker: AsRef<ResourceTracker> + AsMut<ResourceTracker>,
 {
-    /// Obtains the balance of the account. The only possible error is an arithmetic
-    /// overflow, which should not happen in practice due to final token supply.
+    /// Obtains the balance of the account, including the budget of a sponsoring
+    /// application. The only possible error is an arithmetic overflow, which should not
+    /// happen in practice due to final token supply.
     pub fn balance(&self) -> Result<Amount, ArithmeticError> {
-        self.account.balance()
+        let mut balance = self.account.balance()?;
+        if let Some(sponsor_budget) = self.sponsor_budget {
+            balance.try_add_assign(sponsor_budget)?;
+        }
+        Ok(balance)
     }
 
     /// Operates a 3-way merge by transferring the difference between `initial`
@@ -200,14 +212,33 @@ where
         Ok(())
     }
 
-    /// Subtracts an amount from a balance and reports an error if that is impossible.
+    /// Subtracts fees from a balance and reports an error if that is impossible. The budget
+    /// of a sponsoring application is used first.
     fn update_balance(&mut self, fees: Amount) -> Result<(), ExecutionError> {
+        let sponsored = self
+            .sponsor_budget
+            .map_or(Amount::ZERO, |sponsor_budget| sponsor_budget.min(fees));
         self.account
-            .try_sub_assign(fees)
+            .try_sub_assign(fees.saturating_sub(sponsored))
             .map_err(|_| ExecutionError::FeesExceedFunding {
                 fees,
                 balance: self.balance().unwrap_or(Amount::MAX),
             })?;
+        if let Some(sponsor_budget) = &mut self.sponsor_budget {
+            sponsor_budget.try_sub_assign(sponsored)?;
+        }
+        self.tracker.as_mut().fees.try_add_assign(fees)?;
+        Ok(())
+    }
+
+    /// Subtracts an amount from a balance and reports an error if that is impossible.
+    fn debit(&mut self, amount: Amount) -> Result<(), ExecutionError> {
+        self.account
+            .try_sub_assign(amount)
+            .map_err(|_| ExecutionError::FeesExceedFunding {
+                fees: amount,
+                balance: self.balance().unwrap_or(Amount::MAX),
+            })?;
         Ok(())
     }
 
@@ -221,10 +252,10 @@ where
             .min(maximum_fuel_per_block.saturating_sub(fuel))
     }
 
-    /// Tracks the allocation of a grant.
+    /// Tracks the allocation of a grant. Grants are never paid by a sponsoring application.
     pub fn track_grant(&mut self, grant: Amount) -> Result<(), ExecutionError> {
         self.tracker.as_mut().grants.try_add_assign(grant)?;
-        self.update_balance(grant)
+        self.debit(grant)
     }
 
     /// Tracks the execution of an operation in block.
@@ -501,7 +532,6 @@ where
 
     /// Tracks a change in the number of bytes stored.
     // TODO(#1536): This is not fully implemented.
-    #[allow(dead_code)]
     pub(crate) fn track_stored_bytes(&mut self, delta: i32) -> Result<(), ExecutionError> {
         self.tracker.as_mut().bytes_stored = self
             .tracker
@@ -512,6 +542,13 @@ where
         Ok(())
     }
 
+    /// Tracks the release of `count` stored bytes, when the storage of an application is
+    /// deleted.
+    pub(crate) fn track_stored_bytes_released(&mut self, count: u32) -> Result<(), ExecutionError> {
+        let delta = i32::try_from(count).map_err(|_| ArithmeticError::Overflow)?;
+        self.track_stored_bytes(-delta)
+    }
+
     /// Returns the remaining time services can spend executing as oracles.
     pub(crate) fn remaining_service_oracle_execution_time(
         &self,
@@ -597,9 +634,9 @@ impl ResourceController<Option<AccountOwner>, ResourceTracker> {
     /// Provides a reference to the current execution state and obtains a temporary object
     /// where the accounting functions of [`ResourceController`] are available.
     pub async fn with_state<'a, C>(
-        &mut self,
+        &'a mut self,
         view: &'a mut SystemExecutionStateView<C>,
-    ) -> Result<ResourceController<Sources<'a>, &mut ResourceTracker>, ViewError>
+    ) -> Result<ResourceController<Sources<'a>, &'a mut ResourceTracker>, ViewError>
     where
         C: Context + Clone + Send + Sync + 'static,
     {
@@ -610,14 +647,18 @@ impl ResourceController<Option<AccountOwner>, ResourceTracker> {
     /// and obtains a temporary object where the accounting functions of
     /// [`ResourceController`] are available.
     pub async fn with_state_and_grant<'a, C>(
-        &mut self,
+        &'a mut self,
         view: &'a mut SystemExecutionStateView<C>,
         grant: Option<&'a mut Amount>,
-    ) -> Result<ResourceController<Sources<'a>, &mut ResourceTracker>, ViewError>
+    ) -> Result<ResourceController<Sources<'a>, &'a mut ResourceTracker>, ViewError>
     where
         C: Context + Clone + Send + Sync + 'static,
     {
         let mut sources = Vec::new();
+        // A sponsoring application pays before anyone else.
+        if let Some(sponsor_budget) = &mut self.sponsor_budget {
+            sources.push(sponsor_budget);
+        }
         // First, use the grant (e.g. for messages) and otherwise use the chain account
         // (e.g. for blocks and operations).
         if let Some(grant) = grant {
@@ -637,6 +678,7 @@ impl ResourceController<Option<AccountOwner>, ResourceTracker> {
             policy: self.policy.clone(),
             tracker: &mut self.tracker,
             account: Sources { sources },
+            sponsor_budget: None,
         })
     }
 }
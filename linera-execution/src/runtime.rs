@@ -17,12 +17,13 @@ use linera_base::{
     ensure, http,
     identifiers::{
         Account, AccountOwner, ChainId, EventId, GenericApplicationId, StreamId, StreamName,
+        TokenId,
     },
     ownership::ChainOwnership,
     time::Instant,
     vm::VmRuntime,
 };
-use linera_views::batch::Batch;
+use linera_views::{batch::Batch, store::RangeQuery};
 use oneshot::Receiver;
 
 use crate::{
@@ -30,11 +31,13 @@ use crate::{
     execution_state_actor::{ExecutionRequest, ExecutionStateSender},
     resources::ResourceController,
     system::CreateApplicationResult,
+    trace::{ExecutionTrace, ExecutionTraceRecorder, TraceEntry},
     util::{ReceiverExt, UnboundedSenderExt},
     ApplicationDescription, ApplicationId, BaseRuntime, ContractRuntime, DataBlobHash,
-    ExecutionError, FinalizeContext, Message, MessageContext, MessageKind, ModuleId, Operation,
-    OutgoingMessage, QueryContext, QueryOutcome, ServiceRuntime, UserContractCode,
-    UserContractInstance, UserServiceCode, UserServiceInstance, MAX_STREAM_NAME_LEN,
+    ExecutionError, FeeSponsorship, FinalizeContext, Message, MessageContext, MessageKind,
+    ModuleId, Operation, OutgoingMessage, QueryContext, QueryOutcome, ServiceRuntime,
+    UserContractCode, UserContractInstance, UserServiceCode, UserServiceInstance,
+    MAX_STREAM_NAME_LEN,
 };
 
 #[cfg(test)]
@@ -122,6 +125,9 @@ pub struct SyncRuntimeInternal<UserInstance: WithContext> {
     refund_grant_to: Option<Account>,
     /// Controller to track fuel and storage consumption.
     resource_controller: ResourceController,
+    /// The trace of this execution, if execution traces are being recorded.
+    #[debug(skip_if = Option::is_none)]
+    execution_trace: Option<ExecutionTraceRecorder>,
     /// Additional context for the runtime.
     user_context: UserInstance::UserContext,
 }
@@ -327,6 +333,7 @@ impl<UserInstance: WithContext> SyncRuntimeInternal<UserInstance> {
             deadline,
             refund_grant_to,
             resource_controller,
+            execution_trace: None,
             scheduled_operations: Vec::new(),
             user_context,
         }
@@ -349,6 +356,10 @@ impl<UserInstance: WithContext> SyncRuntimeInternal<UserInstance> {
     ///
     /// Ensures the application's ID is also tracked in the `active_applications` set.
     fn push_application(&mut self, status: ApplicationStatus) {
+        let fuel_used = self.fuel_used();
+        if let Some(recorder) = &mut self.execution_trace {
+            recorder.enter(status.id, fuel_used);
+        }
         self.active_applications.insert(status.id);
         self.call_stack.push(status);
     }
@@ -366,9 +377,27 @@ impl<UserInstance: WithContext> SyncRuntimeInternal<UserInstance> {
             .pop()
             .expect("Can't remove application from empty call stack");
         assert!(self.active_applications.remove(&status.id));
+        let fuel_used = self.fuel_used();
+        if let Some(recorder) = &mut self.execution_trace {
+            recorder.exit(status.caller_id.is_some(), fuel_used);
+        }
         status
     }
 
+    /// Returns the total amount of fuel consumed so far.
+    fn fuel_used(&self) -> u64 {
+        let tracker = &self.resource_controller.tracker;
+        tracker.wasm_fuel.saturating_add(tracker.evm_fuel)
+    }
+
+    /// Records an entry in the execution trace of the current application, if execution
+    /// traces are being recorded.
+    fn record_trace(&mut self, entry: impl FnOnce() -> TraceEntry) {
+        if let Some(recorder) = &mut self.execution_trace {
+            recorder.record(entry());
+        }
+    }
+
     /// Ensures that a call to `application_id` is not-reentrant.
     ///
     /// Returns an error if there already is an entry for `application_id` in the call stack.
@@ -633,6 +662,42 @@ where
         Ok(balance)
     }
 
+    fn read_owner_token_balance(
+        &mut self,
+        owner: AccountOwner,
+        token_id: TokenId,
+    ) -> Result<Amount, ExecutionError> {
+        let mut this = self.inner();
+        let balance = this
+            .execution_state_sender
+            .send_request(|callback| ExecutionRequest::OwnerTokenBalance {
+                owner,
+                token_id,
+                callback,
+            })?
+            .recv_response()?;
+        this.resource_controller.track_runtime_balance()?;
+        Ok(balance)
+    }
+
+    fn read_allowance(
+        &mut self,
+        owner: AccountOwner,
+        spender: AccountOwner,
+    ) -> Result<Amount, ExecutionError> {
+        let mut this = self.inner();
+        let allowance = this
+            .execution_state_sender
+            .send_request(|callback| ExecutionRequest::Allowance {
+                owner,
+                spender,
+                callback,
+            })?
+            .recv_response()?;
+        this.resource_controller.track_runtime_balance()?;
+        Ok(allowance)
+    }
+
     fn read_owner_balances(&mut self) -> Result<Vec<(AccountOwner, Amount)>, ExecutionError> {
         let mut this = self.inner();
         let owner_balances = this
@@ -669,6 +734,9 @@ where
         let mut this = self.inner();
         let id = this.current_application().id;
         this.resource_controller.track_read_operation()?;
+        this.record_trace(|| TraceEntry::Read {
+            keys: vec![key.clone()],
+        });
         let receiver = this
             .execution_state_sender
             .send_request(move |callback| ExecutionRequest::ContainsKey { id, key, callback })?;
@@ -691,6 +759,7 @@ where
         let mut this = self.inner();
         let id = this.current_application().id;
         this.resource_controller.track_read_operation()?;
+        this.record_trace(|| TraceEntry::Read { keys: keys.clone() });
         let receiver = this
             .execution_state_sender
             .send_request(move |callback| ExecutionRequest::ContainsKeys { id, keys, callback })?;
@@ -716,6 +785,7 @@ where
         let mut this = self.inner();
         let id = this.current_application().id;
         this.resource_controller.track_read_operation()?;
+        this.record_trace(|| TraceEntry::Read { keys: keys.clone() });
         let receiver = this.execution_state_sender.send_request(move |callback| {
             ExecutionRequest::ReadMultiValuesBytes { id, keys, callback }
         })?;
@@ -747,6 +817,9 @@ where
         let mut this = self.inner();
         let id = this.current_application().id;
         this.resource_controller.track_read_operation()?;
+        this.record_trace(|| TraceEntry::Read {
+            keys: vec![key.clone()],
+        });
         let receiver = this
             .execution_state_sender
             .send_request(move |callback| ExecutionRequest::ReadValueBytes { id, key, callback })?;
@@ -778,6 +851,9 @@ where
         let mut this = self.inner();
         let id = this.current_application().id;
         this.resource_controller.track_read_operation()?;
+        this.record_trace(|| TraceEntry::FindByPrefix {
+            key_prefix: key_prefix.clone(),
+        });
         let receiver = this.execution_state_sender.send_request(move |callback| {
             ExecutionRequest::FindKeysByPrefix {
                 id,
@@ -808,6 +884,30 @@ where
         Ok(keys)
     }
 
+    fn find_keys_by_range_new(
+        &mut self,
+        key_prefix: Vec<u8>,
+        query: RangeQuery,
+    ) -> Result<Self::FindKeysByPrefix, ExecutionError> {
+        let mut this = self.inner();
+        let id = this.current_application().id;
+        this.resource_controller.track_read_operation()?;
+        this.record_trace(|| TraceEntry::FindByRange {
+            key_prefix: key_prefix.clone(),
+            query: query.clone(),
+        });
+        let receiver = this.execution_state_sender.send_request(move |callback| {
+            ExecutionRequest::FindKeysByRange {
+                id,
+                key_prefix,
+                query,
+                callback,
+            }
+        })?;
+        let state = this.view_user_states.entry(id).or_default();
+        state.find_keys_queries.register(receiver)
+    }
+
     fn find_key_values_by_prefix_new(
         &mut self,
         key_prefix: Vec<u8>,
@@ -815,6 +915,9 @@ where
         let mut this = self.inner();
         let id = this.current_application().id;
         this.resource_controller.track_read_operation()?;
+        this.record_trace(|| TraceEntry::FindByPrefix {
+            key_prefix: key_prefix.clone(),
+        });
         let receiver = this.execution_state_sender.send_request(move |callback| {
             ExecutionRequest::FindKeyValuesByPrefix {
                 id,
@@ -843,6 +946,30 @@ where
         Ok(key_values)
     }
 
+    fn find_key_values_by_range_new(
+        &mut self,
+        key_prefix: Vec<u8>,
+        query: RangeQuery,
+    ) -> Result<Self::FindKeyValuesByPrefix, ExecutionError> {
+        let mut this = self.inner();
+        let id = this.current_application().id;
+        this.resource_controller.track_read_operation()?;
+        this.record_trace(|| TraceEntry::FindByRange {
+            key_prefix: key_prefix.clone(),
+            query: query.clone(),
+        });
+        let receiver = this.execution_state_sender.send_request(move |callback| {
+            ExecutionRequest::FindKeyValuesByRange {
+                id,
+                key_prefix,
+                query,
+                callback,
+            }
+        })?;
+        let state = this.view_user_states.entry(id).or_default();
+        state.find_key_values_queries.register(receiver)
+    }
+
     fn perform_http_request(
         &mut self,
         request: http::Request,
@@ -935,24 +1062,27 @@ impl ContractSyncRuntime {
         refund_grant_to: Option<Account>,
         resource_controller: ResourceController,
         action: &UserAction,
+        record_execution_trace: bool,
     ) -> Self {
-        SyncRuntime(Some(ContractSyncRuntimeHandle::from(
-            SyncRuntimeInternal::new(
-                chain_id,
-                action.height(),
-                action.round(),
-                if let UserAction::Message(context, _) = action {
-                    Some(context.into())
-                } else {
-                    None
-                },
-                execution_state_sender,
-                None,
-                refund_grant_to,
-                resource_controller,
-                action.timestamp(),
-            ),
-        )))
+        let mut runtime = SyncRuntimeInternal::new(
+            chain_id,
+            action.height(),
+            action.round(),
+            if let UserAction::Message(context, _) = action {
+                Some(context.into())
+            } else {
+                None
+            },
+            execution_state_sender,
+            None,
+            refund_grant_to,
+            resource_controller,
+            action.timestamp(),
+        );
+        if record_execution_trace {
+            runtime.execution_trace = Some(ExecutionTraceRecorder::default());
+        }
+        SyncRuntime(Some(ContractSyncRuntimeHandle::from(runtime)))
     }
 
     pub(crate) fn preload_contract(
@@ -980,20 +1110,45 @@ impl ContractSyncRuntime {
     }
 
     /// Main entry point to start executing a user action.
+    ///
+    /// Also returns the trace of the execution, if it was recorded. If the action fails, the
+    /// trace is attached to the error.
     pub(crate) fn run_action(
         mut self,
         application_id: ApplicationId,
         chain_id: ChainId,
         action: UserAction,
-    ) -> Result<(Option<Vec<u8>>, ResourceController), ExecutionError> {
-        let result = self
+    ) -> Result<(Option<Vec<u8>>, ResourceController, Option<ExecutionTrace>), ExecutionError> {
+        let result = match self
             .deref_mut()
-            .run_action(application_id, chain_id, action)?;
-        let runtime = self
+            .run_action(application_id, chain_id, action)
+        {
+            Ok(result) => result,
+            Err(error) => {
+                let trace = {
+                    let mut runtime = self.deref().inner();
+                    let fuel_used = runtime.fuel_used();
+                    runtime
+                        .execution_trace
+                        .take()
+                        .map(|recorder| recorder.finish(fuel_used))
+                };
+                return Err(match trace {
+                    Some(trace) => error.with_trace(trace),
+                    None => error,
+                });
+            }
+        };
+        let mut runtime = self
             .into_inner()
             .expect("Runtime clones should have been freed by now");
+        let fuel_used = runtime.fuel_used();
+        let trace = runtime
+            .execution_trace
+            .take()
+            .map(|recorder| recorder.finish(fuel_used));
 
-        Ok((result, runtime.resource_controller))
+        Ok((result, runtime.resource_controller, trace))
     }
 }
 
@@ -1022,6 +1177,7 @@ impl ContractSyncRuntimeHandle {
             UserAction::Instantiate(_context, argument) => {
                 code.instantiate(argument).map(|()| None)
             }
+            UserAction::Migrate(_context, argument) => code.migrate(argument).map(|()| None),
             UserAction::Operation(_context, operation) => {
                 code.execute_operation(operation).map(Option::Some)
             }
@@ -1171,6 +1327,12 @@ impl ContractRuntime for ContractSyncRuntimeHandle {
         } else {
             MessageKind::Simple
         };
+        this.record_trace(|| TraceEntry::SendMessage {
+            destination: message.destination,
+            authenticated: message.authenticated,
+            is_tracked: message.is_tracked,
+            grant,
+        });
 
         this.execution_state_sender
             .send_request(|callback| ExecutionRequest::AddOutgoingMessage {
@@ -1197,17 +1359,25 @@ impl ContractRuntime for ContractSyncRuntimeHandle {
         source: AccountOwner,
         destination: Account,
         amount: Amount,
+        token_id: Option<TokenId>,
     ) -> Result<(), ExecutionError> {
-        let this = self.inner();
+        let mut this = self.inner();
         let current_application = this.current_application();
         let application_id = current_application.id;
         let signer = current_application.signer;
+        this.record_trace(|| TraceEntry::Transfer {
+            source,
+            destination,
+            amount,
+            token_id,
+        });
 
         this.execution_state_sender
             .send_request(|callback| ExecutionRequest::Transfer {
                 source,
                 destination,
                 amount,
+                token_id,
                 signer,
                 application_id,
                 callback,
@@ -1221,6 +1391,7 @@ impl ContractRuntime for ContractSyncRuntimeHandle {
         source: Account,
         destination: Account,
         amount: Amount,
+        token_id: Option<TokenId>,
     ) -> Result<(), ExecutionError> {
         let this = self.inner();
         let current_application = this.current_application();
@@ -1232,6 +1403,7 @@ impl ContractRuntime for ContractSyncRuntimeHandle {
                 source,
                 destination,
                 amount,
+                token_id,
                 signer,
                 application_id,
                 callback,
@@ -1240,6 +1412,115 @@ impl ContractRuntime for ContractSyncRuntimeHandle {
         Ok(())
     }
 
+    fn mint_token(
+        &mut self,
+        token_id: TokenId,
+        recipient: AccountOwner,
+        amount: Amount,
+    ) -> Result<(), ExecutionError> {
+        let this = self.inner();
+        let application_id = this.current_application().id;
+
+        this.execution_state_sender
+            .send_request(|callback| ExecutionRequest::MintToken {
+                token_id,
+                recipient,
+                amount,
+                application_id,
+                callback,
+            })?
+            .recv_response()?;
+        Ok(())
+    }
+
+    fn burn_token(
+        &mut self,
+        token_id: TokenId,
+        owner: AccountOwner,
+        amount: Amount,
+    ) -> Result<(), ExecutionError> {
+        let this = self.inner();
+        let current_application = this.current_application();
+        let application_id = current_application.id;
+        let signer = current_application.signer;
+
+        this.execution_state_sender
+            .send_request(|callback| ExecutionRequest::BurnToken {
+                token_id,
+                owner,
+                amount,
+                signer,
+                application_id,
+                callback,
+            })?
+            .recv_response()?;
+        Ok(())
+    }
+
+    fn schedule_operation_at(
+        &mut self,
+        timestamp: Timestamp,
+        operation: Vec<u8>,
+        budget: Amount,
+    ) -> Result<(), ExecutionError> {
+        let mut this = self.inner();
+        let application_id = this.current_application().id;
+        let creator = this.current_application().signer;
+        let operation_len = operation.len() as u64;
+
+        this.execution_state_sender
+            .send_request(|callback| ExecutionRequest::ScheduleOperation {
+                application_id,
+                creator,
+                timestamp,
+                bytes: operation,
+                budget,
+                callback,
+            })?
+            .recv_response()?;
+        // The operation is kept in the chain state until it is executed.
+        this.resource_controller
+            .track_bytes_written(operation_len)?;
+        Ok(())
+    }
+
+    fn sponsor_fees(
+        &mut self,
+        block_budget: Amount,
+        owner_budget: Amount,
+    ) -> Result<(), ExecutionError> {
+        let this = self.inner();
+        let application_id = this.current_application().id;
+
+        this.execution_state_sender
+            .send_request(|callback| ExecutionRequest::SponsorFees {
+                application_id,
+                sponsorship: FeeSponsorship {
+                    block_budget,
+                    owner_budget,
+                },
+                callback,
+            })?
+            .recv_response()?;
+        Ok(())
+    }
+
+    fn self_destruct(&mut self, refund_recipient: AccountOwner) -> Result<(), ExecutionError> {
+        let mut this = self.inner();
+        let application_id = this.current_application().id;
+
+        let released_bytes = this
+            .execution_state_sender
+            .send_request(|callback| ExecutionRequest::SelfDestruct {
+                application_id,
+                refund_recipient,
+                callback,
+            })?
+            .recv_response()?;
+        this.resource_controller
+            .track_stored_bytes_released(released_bytes)
+    }
+
     fn try_call_application(
         &mut self,
         authenticated: bool,
@@ -1268,7 +1549,7 @@ impl ContractRuntime for ContractSyncRuntimeHandle {
         );
         let application_id = GenericApplicationId::User(this.current_application().id);
         let stream_id = StreamId {
-            stream_name,
+            stream_name: stream_name.clone(),
             application_id,
         };
         let value_len = value.len() as u64;
@@ -1282,6 +1563,11 @@ impl ContractRuntime for ContractSyncRuntimeHandle {
             .recv_response()?;
         // TODO(#365): Consider separate event fee categories.
         this.resource_controller.track_bytes_written(value_len)?;
+        this.record_trace(|| TraceEntry::Emit {
+            stream_name,
+            index,
+            value_len,
+        });
         Ok(index)
     }
 
@@ -1530,6 +1816,10 @@ impl ContractRuntime for ContractSyncRuntimeHandle {
         )?;
         this.resource_controller
             .track_bytes_written(batch.size() as u64)?;
+        this.record_trace(|| TraceEntry::WriteBatch {
+            num_operations: batch.num_operations() as u64,
+            size: batch.size() as u64,
+        });
         this.execution_state_sender
             .send_request(|callback| ExecutionRequest::WriteBatch {
                 id,
@@ -16,15 +16,20 @@ use linera_base::{
         ChainDescription, ChainOrigin, Epoch, InitialChainConfig, OracleResponse, Timestamp,
     },
     ensure, hex_debug,
-    identifiers::{Account, AccountOwner, BlobId, BlobType, ChainId, EventId, ModuleId, StreamId},
+    identifiers::{
+        Account, AccountOwner, BlobId, BlobType, ChainId, EventId, ModuleId, StreamId, TokenId,
+    },
     ownership::{ChainOwnership, TimeoutConfig},
+    vm::VmRuntime,
 };
 use linera_views::{
+    common::CustomSerialize,
     context::Context,
-    map_view::HashedMapView,
+    map_view::{HashedCustomMapView, HashedMapView},
     register_view::HashedRegisterView,
     set_view::HashedSetView,
     views::{ClonableView, HashableView, ReplaceContext, View},
+    ViewError,
 };
 use serde::{Deserialize, Serialize};
 
@@ -32,14 +37,32 @@ use serde::{Deserialize, Serialize};
 use crate::test_utils::SystemExecutionState;
 use crate::{
     committee::Committee, util::OracleResponseExt as _, ApplicationDescription, ApplicationId,
-    ExecutionError, ExecutionRuntimeContext, MessageContext, MessageKind, OperationContext,
-    OutgoingMessage, QueryContext, QueryOutcome, ResourceController, TransactionTracker,
+    ExecutionError, ExecutionRuntimeContext, FeeMultiplier, MessageContext, MessageKind,
+    OperationContext, OutgoingMessage, QueryContext, QueryOutcome, ResourceController,
+    TransactionTracker,
 };
 
 /// The event stream name for new epochs and committees.
 pub static EPOCH_STREAM_NAME: &[u8] = &[0];
 /// The event stream name for removed epochs.
 pub static REMOVED_EPOCH_STREAM_NAME: &[u8] = &[1];
+/// The prefix of the event stream names announcing the upgrade authority and the upgrades of
+/// an application, followed by the hash of its description.
+pub static UPGRADE_AUTHORITY_STREAM_PREFIX: &[u8] = &[2];
+
+/// Returns the stream on which the creator chain of an application announces its upgrade
+/// authority, if any, at index zero, and then each upgrade of the application, as the
+/// new module ID and the migration argument, at the following indices.
+pub fn upgrade_authority_stream_id(application_id: ApplicationId) -> StreamId {
+    let mut stream_name = UPGRADE_AUTHORITY_STREAM_PREFIX.to_vec();
+    stream_name.extend_from_slice(
+        application_id
+            .application_description_hash
+            .as_bytes()
+            .as_slice(),
+    );
+    StreamId::system(stream_name)
+}
 
 /// The number of times the [`SystemOperation::OpenChain`] was executed.
 #[cfg(with_metrics)]
@@ -78,6 +101,11 @@ pub struct SystemExecutionStateView<C> {
     pub balance: HashedRegisterView<C, Amount>,
     /// Balances attributed to a given owner.
     pub balances: HashedMapView<C, AccountOwner, Amount>,
+    /// Balances of the tokens issued by applications, by owner and token.
+    pub token_balances: HashedMapView<C, (AccountOwner, TokenId), Amount>,
+    /// The amounts that owners allowed other owners or applications to spend from their
+    /// accounts, by owner and spender.
+    pub allowances: HashedMapView<C, (AccountOwner, AccountOwner), Allowance>,
     /// The timestamp of the most recent block.
     pub timestamp: HashedRegisterView<C, Timestamp>,
     /// Whether this chain has been closed.
@@ -88,6 +116,28 @@ pub struct SystemExecutionStateView<C> {
     pub used_blobs: HashedSetView<C, BlobId>,
     /// The event stream subscriptions of applications on this chain.
     pub event_subscriptions: HashedMapView<C, (ChainId, StreamId), EventSubscriptions>,
+    /// Operations scheduled by applications for execution at a later time, ordered by
+    /// their due timestamp.
+    pub scheduled_operations: HashedCustomMapView<C, ScheduledOperationKey, ScheduledOperation>,
+    /// The index of the next scheduled operation.
+    pub next_scheduled_operation_index: HashedRegisterView<C, u64>,
+    /// The applications that pay the execution fees of the operations targeting them.
+    pub fee_sponsorships: HashedMapView<C, ApplicationId, FeeSponsorship>,
+    /// The total fees that each sponsoring application paid for each block signer, while
+    /// its sponsorship lasts.
+    pub sponsored_fees: HashedMapView<C, (ApplicationId, AccountOwner), Amount>,
+    /// The factor applied to the prices of the current committee's policy, if it uses
+    /// dynamic fees. It follows the load of the recent blocks of this chain.
+    pub fee_multiplier: HashedRegisterView<C, FeeMultiplier>,
+    /// The upgrades of the applications on this chain, in the order they were executed.
+    /// The last one determines the module that an application currently runs.
+    pub application_upgrades: HashedMapView<C, ApplicationId, Vec<ApplicationUpgrade>>,
+    /// The applications whose storage was deleted on this chain. They can no longer be
+    /// executed here.
+    pub tombstoned_applications: HashedSetView<C, ApplicationId>,
+    /// The fees paid for writing to the storage of each application on this chain. They
+    /// cap the refund when the storage is deleted.
+    pub storage_fees: HashedMapView<C, ApplicationId, Amount>,
 }
 
 impl<C: Context, C2: Context> ReplaceContext<C2> for SystemExecutionStateView<C> {
@@ -105,11 +155,24 @@ impl<C: Context, C2: Context> ReplaceContext<C2> for SystemExecutionStateView<C>
             ownership: self.ownership.with_context(ctx.clone()).await,
             balance: self.balance.with_context(ctx.clone()).await,
             balances: self.balances.with_context(ctx.clone()).await,
+            token_balances: self.token_balances.with_context(ctx.clone()).await,
+            allowances: self.allowances.with_context(ctx.clone()).await,
             timestamp: self.timestamp.with_context(ctx.clone()).await,
             closed: self.closed.with_context(ctx.clone()).await,
             application_permissions: self.application_permissions.with_context(ctx.clone()).await,
             used_blobs: self.used_blobs.with_context(ctx.clone()).await,
             event_subscriptions: self.event_subscriptions.with_context(ctx.clone()).await,
+            scheduled_operations: self.scheduled_operations.with_context(ctx.clone()).await,
+            next_scheduled_operation_index: self
+                .next_scheduled_operation_index
+                .with_context(ctx.clone())
+                .await,
+            fee_sponsorships: self.fee_sponsorships.with_context(ctx.clone()).await,
+            sponsored_fees: self.sponsored_fees.with_context(ctx.clone()).await,
+            fee_multiplier: self.fee_multiplier.with_context(ctx.clone()).await,
+            application_upgrades: self.application_upgrades.with_context(ctx.clone()).await,
+            tombstoned_applications: self.tombstoned_applications.with_context(ctx.clone()).await,
+            storage_fees: self.storage_fees.with_context(ctx.clone()).await,
         }
     }
 }
@@ -124,6 +187,95 @@ pub struct EventSubscriptions {
     pub applications: BTreeSet<ApplicationId>,
 }
 
+/// Identifies an operation scheduled by an application. Keys are ordered by due timestamp
+/// first, then by the order in which the operations were scheduled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ScheduledOperationKey {
+    /// The earliest block timestamp at which the operation can be executed.
+    pub timestamp: Timestamp,
+    /// A chain-wide sequence number, to distinguish operations with the same timestamp.
+    pub index: u64,
+}
+
+impl CustomSerialize for ScheduledOperationKey {
+    fn to_custom_bytes(&self) -> Result<Vec<u8>, ViewError> {
+        // Big-endian encoding, so that the keys are sorted by timestamp, then index.
+        let bytes = (
+            self.timestamp.micros().to_be_bytes(),
+            self.index.to_be_bytes(),
+        );
+        Ok(bcs::to_bytes(&bytes)?)
+    }
+
+    fn from_custom_bytes(bytes: &[u8]) -> Result<Self, ViewError> {
+        let (timestamp, index): ([u8; 8], [u8; 8]) = bcs::from_bytes(bytes)?;
+        Ok(ScheduledOperationKey {
+            timestamp: Timestamp::from(u64::from_be_bytes(timestamp)),
+            index: u64::from_be_bytes(index),
+        })
+    }
+}
+
+/// An operation that an application scheduled for execution at a later time.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledOperation {
+    /// The application that scheduled the operation, and that will execute it.
+    pub application_id: ApplicationId,
+    /// The authenticated signer of the transaction that scheduled the operation, if any.
+    /// Besides the chain owners, only they can discard the operation.
+    pub creator: Option<AccountOwner>,
+    /// The serialized operation.
+    #[serde(with = "serde_bytes")]
+    #[debug(with = "hex_debug")]
+    pub bytes: Vec<u8>,
+    /// The fees prepaid by the application. Whatever is not spent when executing the
+    /// operation is credited back to the application's account.
+    pub budget: Amount,
+}
+
+/// An amount of native tokens that a spender is allowed to transfer out of an owner's
+/// account.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, async_graphql::SimpleObject,
+)]
+pub struct Allowance {
+    /// The remaining amount that the spender may transfer.
+    pub amount: Amount,
+    /// The block timestamp from which on the allowance can no longer be used, if any.
+    pub expiry: Option<Timestamp>,
+}
+
+impl Allowance {
+    /// Returns whether the allowance can no longer be used in a block with the given
+    /// timestamp.
+    pub fn is_expired(&self, timestamp: Timestamp) -> bool {
+        self.expiry.is_some_and(|expiry| expiry <= timestamp)
+    }
+}
+
+/// The limits within which an application pays the execution fees of the operations
+/// targeting it, from its own account.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, async_graphql::SimpleObject,
+)]
+pub struct FeeSponsorship {
+    /// The maximum amount of fees the application pays per block.
+    pub block_budget: Amount,
+    /// The maximum total amount of fees the application pays for each block signer.
+    pub owner_budget: Amount,
+}
+
+/// An upgrade of an application to a new module, keeping its storage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApplicationUpgrade {
+    /// The module the application ran before the upgrade.
+    pub previous_module_id: ModuleId,
+    /// The module the application runs since the upgrade.
+    pub module_id: ModuleId,
+    /// The height of the block that upgraded the application.
+    pub height: BlockHeight,
+}
+
 /// The initial configuration for a new chain.
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
 pub struct OpenChainConfig {
@@ -195,6 +347,9 @@ pub enum SystemOperation {
         open_multi_leader_rounds: bool,
         /// The timeout configuration: how long fast, multi-leader and single-leader rounds last.
         timeout_config: TimeoutConfig,
+        /// The total weight of the regular owners that must sign a block proposal, if any.
+        #[debug(skip_if = Option::is_none)]
+        approval_threshold: Option<u64>,
     },
     /// Changes the application permissions configuration on this chain.
     ChangeApplicationPermissions(ApplicationPermissions),
@@ -215,6 +370,9 @@ pub enum SystemOperation {
         instantiation_argument: Vec<u8>,
         #[debug(skip_if = Vec::is_empty)]
         required_application_ids: Vec<ApplicationId>,
+        /// The owner allowed to upgrade the application later, if any.
+        #[debug(skip_if = Option::is_none)]
+        upgrade_authority: Option<AccountOwner>,
     },
     /// Operations that are only allowed on the admin chain.
     Admin(AdminOperation),
@@ -224,6 +382,65 @@ pub enum SystemOperation {
     ProcessRemovedEpoch(Epoch),
     /// Updates the event stream trackers.
     UpdateStreams(Vec<(ChainId, StreamId, u32)>),
+    /// Executes an operation that an application scheduled for a time no later than the
+    /// block's timestamp. The execution fees are paid from the operation's prepaid budget.
+    ExecuteScheduledOperation(ScheduledOperationKey),
+    /// Removes a due scheduled operation without executing it, e.g. because it failed, and
+    /// refunds its prepaid budget to the scheduling application. This must be signed by the
+    /// operation's creator or by an owner of the chain.
+    DiscardScheduledOperation(ScheduledOperationKey),
+    /// Allows `spender` to transfer up to `amount` units of the native token out of the
+    /// `owner`'s account, until the optional `expiry` timestamp. This replaces any previous
+    /// allowance for the same spender.
+    Approve {
+        owner: AccountOwner,
+        spender: AccountOwner,
+        amount: Amount,
+        #[debug(skip_if = Option::is_none)]
+        expiry: Option<Timestamp>,
+    },
+    /// Removes the allowance of `spender` on the `owner`'s account.
+    Revoke {
+        owner: AccountOwner,
+        spender: AccountOwner,
+    },
+    /// Makes an application run the given module on this chain from now on, keeping its
+    /// storage, then calls the `migrate` entrypoint of the new module with the
+    /// `migration_argument`. On the application's creator chain, the operation must be
+    /// signed by the application's upgrade authority, and the upgrade is announced to the
+    /// other chains. Other chains apply the announced upgrades in the same order.
+    UpgradeApplication {
+        application_id: ApplicationId,
+        module_id: ModuleId,
+        #[serde(with = "serde_bytes")]
+        #[debug(with = "hex_debug", skip_if = Vec::is_empty)]
+        migration_argument: Vec<u8>,
+    },
+    /// Deletes all the storage of an application on this chain, and credits the refund for
+    /// the released bytes to `refund_recipient`. The application can no longer be executed
+    /// on this chain afterwards. Only the owners of the chain and the application's upgrade
+    /// authority can delete it.
+    DeleteApplication {
+        application_id: ApplicationId,
+        refund_recipient: AccountOwner,
+    },
+    /// Transfers `amount` units of the given token from the given owner's account to the
+    /// recipient, like `Transfer` does for the native token.
+    TransferToken {
+        owner: AccountOwner,
+        recipient: Account,
+        amount: Amount,
+        token_id: TokenId,
+    },
+    /// Claims `amount` units of the given token from the given owner's account in the remote
+    /// `target` chain, like `Claim` does for the native token.
+    ClaimToken {
+        owner: AccountOwner,
+        target_id: ChainId,
+        recipient: Account,
+        amount: Amount,
+        token_id: TokenId,
+    },
 }
 
 /// Operations that are only allowed on the admin chain.
@@ -259,6 +476,70 @@ pub enum SystemMessage {
         amount: Amount,
         recipient: Account,
     },
+    /// Credits `amount` units of the given token to the account `target`, like `Credit`
+    /// does for the native token.
+    CreditToken {
+        target: AccountOwner,
+        amount: Amount,
+        source: AccountOwner,
+        token_id: TokenId,
+    },
+    /// Withdraws `amount` units of the given token from the account and starts a transfer to
+    /// credit the recipient, like `Withdraw` does for the native token.
+    WithdrawToken {
+        owner: AccountOwner,
+        amount: Amount,
+        recipient: Account,
+        token_id: TokenId,
+    },
+}
+
+impl SystemMessage {
+    /// Returns a message crediting `amount` units of the given token, or of the native token
+    /// if `token_id` is `None`, to the account `target`.
+    fn credit(
+        target: AccountOwner,
+        amount: Amount,
+        source: AccountOwner,
+        token_id: Option<TokenId>,
+    ) -> Self {
+        match token_id {
+            None => SystemMessage::Credit {
+                target,
+                amount,
+                source,
+            },
+            Some(token_id) => SystemMessage::CreditToken {
+                target,
+                amount,
+                source,
+                token_id,
+            },
+        }
+    }
+
+    /// Returns a message withdrawing `amount` units of the given token, or of the native
+    /// token if `token_id` is `None`, from the account `owner`.
+    fn withdraw(
+        owner: AccountOwner,
+        amount: Amount,
+        recipient: Account,
+        token_id: Option<TokenId>,
+    ) -> Self {
+        match token_id {
+            None => SystemMessage::Withdraw {
+                owner,
+                amount,
+                recipient,
+            },
+            Some(token_id) => SystemMessage::WithdrawToken {
+                owner,
+                amount,
+                recipient,
+                token_id,
+            },
+        }
+    }
 }
 
 /// A query to the system state.
@@ -367,14 +648,23 @@ where
                 multi_leader_rounds,
                 open_multi_leader_rounds,
                 timeout_config,
+                approval_threshold,
             } => {
-                self.ownership.set(ChainOwnership {
+                let ownership = ChainOwnership {
                     super_owners: super_owners.into_iter().collect(),
                     owners: owners.into_iter().collect(),
                     multi_leader_rounds,
                     open_multi_leader_rounds,
                     timeout_config,
-                });
+                    approval_threshold,
+                };
+                if let Some(threshold) = approval_threshold {
+                    ensure!(
+                        ownership.is_approval_threshold_reachable(),
+                        ExecutionError::UnreachableApprovalThreshold(threshold)
+                    );
+                }
+                self.ownership.set(ownership);
             }
             ChangeApplicationPermissions(application_permissions) => {
                 self.application_permissions.set(application_permissions);
@@ -386,7 +676,32 @@ where
                 recipient,
             } => {
                 let maybe_message = self
-                    .transfer(context.authenticated_signer, None, owner, recipient, amount)
+                    .transfer(
+                        context.authenticated_signer,
+                        None,
+                        owner,
+                        recipient,
+                        amount,
+                        None,
+                    )
+                    .await?;
+                txn_tracker.add_outgoing_messages(maybe_message);
+            }
+            TransferToken {
+                owner,
+                amount,
+                recipient,
+                token_id,
+            } => {
+                let maybe_message = self
+                    .transfer(
+                        context.authenticated_signer,
+                        None,
+                        owner,
+                        recipient,
+                        amount,
+                        Some(token_id),
+                    )
                     .await?;
                 txn_tracker.add_outgoing_messages(maybe_message);
             }
@@ -404,6 +719,27 @@ where
                         target_id,
                         recipient,
                         amount,
+                        None,
+                    )
+                    .await?;
+                txn_tracker.add_outgoing_messages(maybe_message);
+            }
+            ClaimToken {
+                owner,
+                target_id,
+                recipient,
+                amount,
+                token_id,
+            } => {
+                let maybe_message = self
+                    .claim(
+                        context.authenticated_signer,
+                        None,
+                        owner,
+                        target_id,
+                        recipient,
+                        amount,
+                        Some(token_id),
                     )
                     .await?;
                 txn_tracker.add_outgoing_messages(maybe_message);
@@ -423,8 +759,11 @@ where
                     AdminOperation::CreateCommittee { epoch, blob_hash } => {
                         self.check_next_epoch(epoch)?;
                         let blob_id = BlobId::new(blob_hash, BlobType::Committee);
-                        let committee =
+                        let committee: Committee =
                             bcs::from_bytes(self.read_blob_content(blob_id).await?.bytes())?;
+                        if let Some(public_key) = committee.validator_with_invalid_aggregate_key() {
+                            return Err(ExecutionError::InvalidAggregateKey(public_key));
+                        }
                         self.blob_used(txn_tracker, blob_id).await?;
                         self.committees.get_mut().insert(epoch, committee);
                         self.epoch.set(epoch);
@@ -457,6 +796,7 @@ where
                 parameters,
                 instantiation_argument,
                 required_application_ids,
+                upgrade_authority,
             } => {
                 let CreateApplicationResult { app_id } = self
                     .create_application(
@@ -465,6 +805,7 @@ where
                         module_id,
                         parameters,
                         required_application_ids,
+                        upgrade_authority,
                         txn_tracker,
                     )
                     .await?;
@@ -570,6 +911,64 @@ where
                     ExecutionError::EventsNotFound(missing_events)
                 );
             }
+            ExecuteScheduledOperation(_) => {
+                return Err(ExecutionError::InternalError(
+                    "Scheduled operations must be executed by the execution state actor",
+                ));
+            }
+            UpgradeApplication { .. } => {
+                return Err(ExecutionError::InternalError(
+                    "Application upgrades must be executed by the execution state actor",
+                ));
+            }
+            DeleteApplication { .. } => {
+                return Err(ExecutionError::InternalError(
+                    "Application deletions must be executed by the execution state actor",
+                ));
+            }
+            DiscardScheduledOperation(key) => {
+                let ScheduledOperation {
+                    application_id,
+                    creator,
+                    budget,
+                    ..
+                } = self.due_scheduled_operation(key, context.timestamp).await?;
+                ensure!(
+                    context.authenticated_signer.is_some_and(|signer| {
+                        creator == Some(signer) || self.ownership.get().verify_owner(&signer)
+                    }),
+                    ExecutionError::UnauthorizedScheduledOperationDiscard(key)
+                );
+                self.scheduled_operations.remove(&key)?;
+                if budget > Amount::ZERO {
+                    self.credit(&AccountOwner::from(application_id), budget)
+                        .await?;
+                }
+            }
+            Approve {
+                owner,
+                spender,
+                amount,
+                expiry,
+            } => {
+                ensure!(
+                    context.authenticated_signer == Some(owner),
+                    ExecutionError::UnauthenticatedApprovalOwner
+                );
+                if amount.is_zero() {
+                    self.allowances.remove(&(owner, spender))?;
+                } else {
+                    self.allowances
+                        .insert(&(owner, spender), Allowance { amount, expiry })?;
+                }
+            }
+            Revoke { owner, spender } => {
+                ensure!(
+                    context.authenticated_signer == Some(owner),
+                    ExecutionError::UnauthenticatedApprovalOwner
+                );
+                self.allowances.remove(&(owner, spender))?;
+            }
         }
 
         Ok(new_application)
@@ -586,7 +985,11 @@ where
         Ok(())
     }
 
-    async fn credit(&mut self, owner: &AccountOwner, amount: Amount) -> Result<(), ExecutionError> {
+    pub(crate) async fn credit(
+        &mut self,
+        owner: &AccountOwner,
+        amount: Amount,
+    ) -> Result<(), ExecutionError> {
         if owner == &AccountOwner::CHAIN {
             let new_balance = self.balance.get().saturating_add(amount);
             self.balance.set(new_balance);
@@ -597,25 +1000,44 @@ where
         Ok(())
     }
 
+    /// Credits an [`Amount`] of the given token, or of the native token if `token_id` is
+    /// `None`, to an account's balance.
+    pub(crate) async fn credit_token(
+        &mut self,
+        owner: &AccountOwner,
+        token_id: Option<TokenId>,
+        amount: Amount,
+    ) -> Result<(), ExecutionError> {
+        let Some(token_id) = token_id else {
+            return self.credit(owner, amount).await;
+        };
+        if amount.is_zero() {
+            return Ok(());
+        }
+        let balance = self
+            .token_balances
+            .get_mut_or_default(&(*owner, token_id))
+            .await?;
+        *balance = balance.saturating_add(amount);
+        Ok(())
+    }
+
     async fn credit_or_send_message(
         &mut self,
         source: AccountOwner,
         recipient: Account,
         amount: Amount,
+        token_id: Option<TokenId>,
     ) -> Result<Option<OutgoingMessage>, ExecutionError> {
         let source_chain_id = self.context().extra().chain_id();
         if recipient.chain_id == source_chain_id {
             // Handle same-chain transfer locally.
             let target = recipient.owner;
-            self.credit(&target, amount).await?;
+            self.credit_token(&target, token_id, amount).await?;
             Ok(None)
         } else {
             // Handle cross-chain transfer with message.
-            let message = SystemMessage::Credit {
-                amount,
-                source,
-                target: recipient.owner,
-            };
+            let message = SystemMessage::credit(recipient.owner, amount, source, token_id);
             Ok(Some(
                 OutgoingMessage::new(recipient.chain_id, message).with_kind(MessageKind::Tracked),
             ))
@@ -629,7 +1051,12 @@ where
         source: AccountOwner,
         recipient: Account,
         amount: Amount,
+        token_id: Option<TokenId>,
     ) -> Result<Option<OutgoingMessage>, ExecutionError> {
+        ensure!(
+            amount > Amount::ZERO,
+            ExecutionError::IncorrectTransferAmount
+        );
         if source == AccountOwner::CHAIN {
             ensure!(
                 authenticated_signer.is_some()
@@ -639,21 +1066,99 @@ where
                         .verify_owner(&authenticated_signer.unwrap()),
                 ExecutionError::UnauthenticatedTransferOwner
             );
-        } else {
+        } else if authenticated_signer != Some(source)
+            && authenticated_application_id.map(AccountOwner::from) != Some(source)
+        {
             ensure!(
-                authenticated_signer == Some(source)
-                    || authenticated_application_id.map(AccountOwner::from) == Some(source),
+                token_id.is_none(),
                 ExecutionError::UnauthenticatedTransferOwner
             );
+            self.spend_allowance(
+                authenticated_signer,
+                authenticated_application_id,
+                source,
+                amount,
+            )
+            .await?;
         }
-        ensure!(
-            amount > Amount::ZERO,
-            ExecutionError::IncorrectTransferAmount
-        );
-        self.debit(&source, amount).await?;
-        self.credit_or_send_message(source, recipient, amount).await
+        self.debit_token(&source, token_id, amount).await?;
+        self.credit_or_send_message(source, recipient, amount, token_id)
+            .await
+    }
+
+    /// Returns the allowance of `spender` on the `owner`'s account, if any.
+    pub async fn allowance(
+        &self,
+        owner: &AccountOwner,
+        spender: &AccountOwner,
+    ) -> Result<Option<Allowance>, ExecutionError> {
+        Ok(self.allowances.get(&(*owner, *spender)).await?)
+    }
+
+    /// Returns the amount that `spender` can currently transfer out of the `owner`'s
+    /// account, i.e. zero if there is no allowance or if it has expired.
+    pub async fn spendable_allowance(
+        &self,
+        owner: &AccountOwner,
+        spender: &AccountOwner,
+    ) -> Result<Amount, ExecutionError> {
+        let timestamp = *self.timestamp.get();
+        Ok(self
+            .allowance(owner, spender)
+            .await?
+            .filter(|allowance| !allowance.is_expired(timestamp))
+            .map_or(Amount::ZERO, |allowance| allowance.amount))
+    }
+
+    /// Deducts `amount` from the allowance that the `owner` gave to the authenticated
+    /// application or, if the application has none or it has expired, to the authenticated
+    /// signer.
+    async fn spend_allowance(
+        &mut self,
+        authenticated_signer: Option<AccountOwner>,
+        authenticated_application_id: Option<ApplicationId>,
+        owner: AccountOwner,
+        amount: Amount,
+    ) -> Result<(), ExecutionError> {
+        let timestamp = *self.timestamp.get();
+        let spenders = authenticated_application_id
+            .map(AccountOwner::from)
+            .into_iter()
+            .chain(authenticated_signer);
+        let mut expired = None;
+        for spender in spenders {
+            let key = (owner, spender);
+            let Some(allowance) = self.allowances.get_mut(&key).await? else {
+                continue;
+            };
+            if let Some(expiry) = allowance.expiry.filter(|expiry| *expiry <= timestamp) {
+                expired.get_or_insert(ExecutionError::AllowanceExpired {
+                    owner,
+                    spender,
+                    expiry,
+                });
+                continue;
+            }
+            allowance.amount.try_sub_assign(amount).map_err(|_| {
+                ExecutionError::InsufficientAllowance {
+                    owner,
+                    spender,
+                    allowance: allowance.amount,
+                }
+            })?;
+            if allowance.amount.is_zero() {
+                self.allowances.remove(&key)?;
+            }
+            return Ok(());
+        }
+        Err(expired.unwrap_or(ExecutionError::UnauthenticatedTransferOwner))
     }
 
+    /// Claims `amount` from the `source` account on the chain `target_id`.
+    ///
+    /// Allowances don't apply to claims: they are stored on the chain of the account, where
+    /// the resulting `Withdraw` message no longer tells whether a spender or the owner
+    /// authenticated the claim. Spenders transfer from the account's own chain instead.
     pub async fn claim(
         &mut self,
         authenticated_signer: Option<AccountOwner>,
@@ -662,6 +1167,7 @@ where
         target_id: ChainId,
         recipient: Account,
         amount: Amount,
+        token_id: Option<TokenId>,
     ) -> Result<Option<OutgoingMessage>, ExecutionError> {
         ensure!(
             authenticated_signer == Some(source)
@@ -673,15 +1179,12 @@ where
         let current_chain_id = self.context().extra().chain_id();
         if target_id == current_chain_id {
             // Handle same-chain claim locally by processing the withdraw operation directly
-            self.debit(&source, amount).await?;
-            self.credit_or_send_message(source, recipient, amount).await
+            self.debit_token(&source, token_id, amount).await?;
+            self.credit_or_send_message(source, recipient, amount, token_id)
+                .await
         } else {
             // Handle cross-chain claim with Withdraw message
-            let message = SystemMessage::Withdraw {
-                amount,
-                owner: source,
-                recipient,
-            };
+            let message = SystemMessage::withdraw(source, amount, recipient, token_id);
             Ok(Some(
                 OutgoingMessage::new(target_id, message)
                     .with_authenticated_signer(authenticated_signer),
@@ -720,6 +1223,267 @@ where
         Ok(())
     }
 
+    /// Debits an [`Amount`] of the given token, or of the native token if `token_id` is
+    /// `None`, from an account's balance.
+    async fn debit_token(
+        &mut self,
+        account: &AccountOwner,
+        token_id: Option<TokenId>,
+        amount: Amount,
+    ) -> Result<(), ExecutionError> {
+        let Some(token_id) = token_id else {
+            return self.debit(account, amount).await;
+        };
+        let key = (*account, token_id);
+        let balance = self.token_balances.get_mut(&key).await?.ok_or_else(|| {
+            ExecutionError::InsufficientTokenBalance {
+                token_id,
+                balance: Amount::ZERO,
+                account: *account,
+            }
+        })?;
+        balance
+            .try_sub_assign(amount)
+            .map_err(|_| ExecutionError::InsufficientTokenBalance {
+                token_id,
+                balance: *balance,
+                account: *account,
+            })?;
+        if balance.is_zero() {
+            self.token_balances.remove(&key)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the balance of the given token in an account.
+    pub async fn token_balance(
+        &self,
+        owner: &AccountOwner,
+        token_id: TokenId,
+    ) -> Result<Amount, ExecutionError> {
+        Ok(self
+            .token_balances
+            .get(&(*owner, token_id))
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// Mints an `amount` of a token into the `recipient` account on this chain. Only the
+    /// token's issuer is allowed to do this.
+    pub async fn mint_token(
+        &mut self,
+        application_id: ApplicationId,
+        token_id: TokenId,
+        recipient: AccountOwner,
+        amount: Amount,
+    ) -> Result<(), ExecutionError> {
+        ensure!(
+            token_id.issuer == application_id,
+            ExecutionError::UnauthorizedTokenIssuer {
+                token_id,
+                application_id,
+            }
+        );
+        self.credit_token(&recipient, Some(token_id), amount).await
+    }
+
+    /// Burns an `amount` of a token from the `owner` account on this chain. Only the token's
+    /// issuer is allowed to do this, and only with the owner's authorization.
+    pub async fn burn_token(
+        &mut self,
+        authenticated_signer: Option<AccountOwner>,
+        application_id: ApplicationId,
+        token_id: TokenId,
+        owner: AccountOwner,
+        amount: Amount,
+    ) -> Result<(), ExecutionError> {
+        ensure!(
+            token_id.issuer == application_id,
+            ExecutionError::UnauthorizedTokenIssuer {
+                token_id,
+                application_id,
+            }
+        );
+        if owner == AccountOwner::CHAIN {
+            ensure!(
+                authenticated_signer
+                    .is_some_and(|signer| self.ownership.get().verify_owner(&signer)),
+                ExecutionError::UnauthenticatedTransferOwner
+            );
+        } else {
+            ensure!(
+                authenticated_signer == Some(owner) || AccountOwner::from(application_id) == owner,
+                ExecutionError::UnauthenticatedTransferOwner
+            );
+        }
+        self.debit_token(&owner, Some(token_id), amount).await
+    }
+
+    /// Schedules an operation of the given application for execution in a block with a
+    /// timestamp no earlier than `timestamp`. The `budget` for the execution fees is debited
+    /// from the application's account right away.
+    pub async fn schedule_operation(
+        &mut self,
+        application_id: ApplicationId,
+        creator: Option<AccountOwner>,
+        timestamp: Timestamp,
+        bytes: Vec<u8>,
+        budget: Amount,
+    ) -> Result<ScheduledOperationKey, ExecutionError> {
+        if budget > Amount::ZERO {
+            self.debit(&AccountOwner::from(application_id), budget)
+                .await?;
+        }
+        let index = *self.next_scheduled_operation_index.get();
+        self.next_scheduled_operation_index
+            .set(index.checked_add(1).ok_or(ArithmeticError::Overflow)?);
+        let key = ScheduledOperationKey { timestamp, index };
+        let operation = ScheduledOperation {
+            application_id,
+            creator,
+            bytes,
+            budget,
+        };
+        self.scheduled_operations.insert(&key, operation)?;
+        Ok(key)
+    }
+
+    /// Makes an application pay the execution fees of the operations targeting it, within
+    /// the given budgets. Zero budgets end the sponsorship. Otherwise, the fees paid so far
+    /// for each signer keep counting towards the new owner budget.
+    pub async fn sponsor_fees(
+        &mut self,
+        application_id: ApplicationId,
+        sponsorship: FeeSponsorship,
+    ) -> Result<(), ExecutionError> {
+        if sponsorship.block_budget.is_zero() || sponsorship.owner_budget.is_zero() {
+            self.end_fee_sponsorship(application_id).await?;
+        } else {
+            self.fee_sponsorships.insert(&application_id, sponsorship)?;
+        }
+        Ok(())
+    }
+
+    /// Ends the fee sponsorship of an application and forgets the fees it paid for each
+    /// signer.
+    async fn end_fee_sponsorship(
+        &mut self,
+        application_id: ApplicationId,
+    ) -> Result<(), ExecutionError> {
+        self.fee_sponsorships.remove(&application_id)?;
+        for key in self.sponsored_fees.indices().await? {
+            if key.0 == application_id {
+                self.sponsored_fees.remove(&key)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Debits and returns the amount that an application is willing to pay for the fees of
+    /// an operation signed by `owner`, given that it already paid `spent_in_block` in the
+    /// current block. Returns zero if the application is not sponsoring fees.
+    pub async fn take_sponsor_budget(
+        &mut self,
+        application_id: ApplicationId,
+        owner: AccountOwner,
+        spent_in_block: Amount,
+    ) -> Result<Amount, ExecutionError> {
+        let Some(sponsorship) = self.fee_sponsorships.get(&application_id).await? else {
+            return Ok(Amount::ZERO);
+        };
+        let spent_for_owner = self
+            .sponsored_fees
+            .get(&(application_id, owner))
+            .await?
+            .unwrap_or_default();
+        let sponsor = AccountOwner::from(application_id);
+        let balance = self.balances.get(&sponsor).await?.unwrap_or_default();
+        let budget = sponsorship
+            .block_budget
+            .saturating_sub(spent_in_block)
+            .min(sponsorship.owner_budget.saturating_sub(spent_for_owner))
+            .min(balance);
+        if budget > Amount::ZERO {
+            self.debit(&sponsor, budget).await?;
+        }
+        Ok(budget)
+    }
+
+    /// Credits the `remaining` part of a sponsor `budget` back to the application, records
+    /// the fees it paid for `owner`, and returns them.
+    pub async fn return_sponsor_budget(
+        &mut self,
+        application_id: ApplicationId,
+        owner: AccountOwner,
+        budget: Amount,
+        remaining: Amount,
+    ) -> Result<Amount, ExecutionError> {
+        let spent = budget.try_sub(remaining)?;
+        if remaining > Amount::ZERO {
+            self.credit(&AccountOwner::from(application_id), remaining)
+                .await?;
+        }
+        if spent > Amount::ZERO {
+            let spent_for_owner = self
+                .sponsored_fees
+                .get_mut_or_default(&(application_id, owner))
+                .await?;
+            spent_for_owner.try_add_assign(spent)?;
+        }
+        Ok(spent)
+    }
+
+    /// Removes and returns the scheduled operation with the given key. Returns an error if it
+    /// does not exist or is not due yet at the given block timestamp.
+    pub async fn take_due_scheduled_operation(
+        &mut self,
+        key: ScheduledOperationKey,
+        timestamp: Timestamp,
+    ) -> Result<ScheduledOperation, ExecutionError> {
+        let operation = self.due_scheduled_operation(key, timestamp).await?;
+        self.scheduled_operations.remove(&key)?;
+        Ok(operation)
+    }
+
+    /// Returns the scheduled operation with the given key. Returns an error if it does not
+    /// exist or is not due yet at the given block timestamp.
+    async fn due_scheduled_operation(
+        &self,
+        key: ScheduledOperationKey,
+        timestamp: Timestamp,
+    ) -> Result<ScheduledOperation, ExecutionError> {
+        ensure!(
+            key.timestamp <= timestamp,
+            ExecutionError::ScheduledOperationNotDue {
+                due: key.timestamp,
+                timestamp,
+            }
+        );
+        self.scheduled_operations
+            .get(&key)
+            .await?
+            .ok_or(ExecutionError::ScheduledOperationNotFound(key))
+    }
+
+    /// Returns the keys of all scheduled operations that are due at the given timestamp, in
+    /// the order in which they should be executed.
+    pub async fn due_scheduled_operations(
+        &self,
+        timestamp: Timestamp,
+    ) -> Result<Vec<ScheduledOperationKey>, ExecutionError> {
+        let mut keys = Vec::new();
+        self.scheduled_operations
+            .for_each_index_while(|key| {
+                if key.timestamp > timestamp {
+                    return Ok(false);
+                }
+                keys.push(key);
+                Ok(true)
+            })
+            .await?;
+        Ok(keys)
+    }
+
     /// Executes a cross-chain message that represents the recipient's side of an operation.
     pub async fn execute_message(
         &mut self,
@@ -737,6 +1501,15 @@ where
                 let receiver = if context.is_bouncing { source } else { target };
                 self.credit(&receiver, amount).await?;
             }
+            CreditToken {
+                amount,
+                source,
+                target,
+                token_id,
+            } => {
+                let receiver = if context.is_bouncing { source } else { target };
+                self.credit_token(&receiver, Some(token_id), amount).await?;
+            }
             Withdraw {
                 amount,
                 owner,
@@ -744,7 +1517,21 @@ where
             } => {
                 self.debit(&owner, amount).await?;
                 if let Some(message) = self
-                    .credit_or_send_message(owner, recipient, amount)
+                    .credit_or_send_message(owner, recipient, amount, None)
+                    .await?
+                {
+                    outcome.push(message);
+                }
+            }
+            WithdrawToken {
+                amount,
+                owner,
+                recipient,
+                token_id,
+            } => {
+                self.debit_token(&owner, Some(token_id), amount).await?;
+                if let Some(message) = self
+                    .credit_or_send_message(owner, recipient, amount, Some(token_id))
                     .await?
                 {
                     outcome.push(message);
@@ -821,6 +1608,12 @@ where
         timestamp: Timestamp,
         txn_tracker: &mut TransactionTracker,
     ) -> Result<ChainId, ExecutionError> {
+        if let Some(threshold) = config.ownership.approval_threshold {
+            ensure!(
+                config.ownership.is_approval_threshold_reachable(),
+                ExecutionError::UnreachableApprovalThreshold(threshold)
+            );
+        }
         let chain_index = txn_tracker.next_chain_index();
         let chain_origin = ChainOrigin::Child {
             parent,
@@ -862,6 +1655,7 @@ where
         module_id: ModuleId,
         parameters: Vec<u8>,
         required_application_ids: Vec<ApplicationId>,
+        upgrade_authority: Option<AccountOwner>,
         txn_tracker: &mut TransactionTracker,
     ) -> Result<CreateApplicationResult, ExecutionError> {
         let application_index = txn_tracker.next_application_index();
@@ -888,9 +1682,19 @@ where
         self.used_blobs.insert(&blob.id())?;
         txn_tracker.add_created_blob(blob);
 
-        Ok(CreateApplicationResult {
-            app_id: ApplicationId::from(&application_description),
-        })
+        // The upgrade authority is not part of the description, so that it doesn't change
+        // the application ID. Without an authority, the application can never be upgraded,
+        // so nothing needs to be announced.
+        let app_id = ApplicationId::from(&application_description);
+        if let Some(upgrade_authority) = upgrade_authority {
+            txn_tracker.add_event(
+                upgrade_authority_stream_id(app_id),
+                0,
+                bcs::to_bytes(&upgrade_authority)?,
+            );
+        }
+
+        Ok(CreateApplicationResult { app_id })
     }
 
     async fn check_required_applications(
@@ -934,6 +1738,222 @@ where
         Ok(description)
     }
 
+    /// Makes the application run the module `module_id` on this chain from now on.
+    ///
+    /// On the creator chain, the upgrade must be signed by the upgrade authority, and it is
+    /// announced on the application's [`upgrade_authority_stream_id`]. Other chains must
+    /// apply the next upgrade announced there, with the same migration argument.
+    pub async fn upgrade_application(
+        &mut self,
+        context: OperationContext,
+        application_id: ApplicationId,
+        module_id: ModuleId,
+        migration_argument: &[u8],
+        txn_tracker: &mut TransactionTracker,
+    ) -> Result<(), ExecutionError> {
+        let description = self
+            .describe_application(application_id, txn_tracker)
+            .await?;
+        let mut upgrades = self
+            .application_upgrades
+            .get(&application_id)
+            .await?
+            .unwrap_or_default();
+        let index = u32::try_from(upgrades.len() + 1).map_err(|_| ArithmeticError::Overflow)?;
+        let announcement = bcs::to_bytes(&(module_id, migration_argument))?;
+        if context.chain_id == description.creator_chain_id {
+            let upgrade_authority = self.upgrade_authority(&description, txn_tracker).await?;
+            ensure!(
+                context.authenticated_signer == Some(upgrade_authority),
+                ExecutionError::UnauthorizedApplicationUpgrade(application_id)
+            );
+            txn_tracker.add_event(
+                upgrade_authority_stream_id(application_id),
+                index,
+                announcement,
+            );
+        } else {
+            let announced = self
+                .read_upgrade_event(&description, index, txn_tracker)
+                .await?;
+            ensure!(
+                announced == announcement,
+                ExecutionError::UnauthorizedApplicationUpgrade(application_id)
+            );
+        }
+        // EVM contracts keep their code in their storage, so only Wasm modules can be swapped.
+        ensure!(
+            module_id.vm_runtime == VmRuntime::Wasm
+                && description.module_id.vm_runtime == VmRuntime::Wasm,
+            ExecutionError::IncompatibleApplicationUpgrade(application_id)
+        );
+        for blob_id in self.check_bytecode_blobs(&module_id, txn_tracker).await? {
+            self.blob_used(txn_tracker, blob_id).await?;
+        }
+        let previous_module_id = upgrades
+            .last()
+            .map_or(description.module_id, |upgrade| upgrade.module_id);
+        upgrades.push(ApplicationUpgrade {
+            previous_module_id,
+            module_id,
+            height: context.height,
+        });
+        self.application_upgrades
+            .insert(&application_id, upgrades)?;
+        Ok(())
+    }
+
+    /// Checks that the authenticated signer of the operation may delete the storage of the
+    /// application, i.e. that it is an owner of the chain or the application's upgrade
+    /// authority.
+    pub async fn check_application_deletion(
+        &mut self,
+        context: OperationContext,
+        application_id: ApplicationId,
+        txn_tracker: &mut TransactionTracker,
+    ) -> Result<(), ExecutionError> {
+        let Some(signer) = context.authenticated_signer else {
+            return Err(ExecutionError::UnauthorizedApplicationDeletion(
+                application_id,
+            ));
+        };
+        if self.ownership.get().verify_owner(&signer) {
+            return Ok(());
+        }
+        let description = self
+            .describe_application(application_id, txn_tracker)
+            .await?;
+        let upgrade_authority = self.upgrade_authority(&description, txn_tracker).await?;
+        ensure!(
+            signer == upgrade_authority,
+            ExecutionError::UnauthorizedApplicationDeletion(application_id)
+        );
+        Ok(())
+    }
+
+    /// Records fees paid for writing to the storage of an application.
+    pub async fn record_storage_fees(
+        &mut self,
+        application_id: ApplicationId,
+        fees: Amount,
+    ) -> Result<(), ExecutionError> {
+        if fees > Amount::ZERO {
+            self.storage_fees
+                .get_mut_or_default(&application_id)
+                .await?
+                .try_add_assign(fees)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the upgrade authority that the creator chain announced for the application.
+    /// Fails if the application was created without one.
+    async fn upgrade_authority(
+        &self,
+        description: &ApplicationDescription,
+        txn_tracker: &mut TransactionTracker,
+    ) -> Result<AccountOwner, ExecutionError> {
+        let bytes = self.read_upgrade_event(description, 0, txn_tracker).await?;
+        Ok(bcs::from_bytes(&bytes)?)
+    }
+
+    /// Returns the event with the given index on the application's
+    /// [`upgrade_authority_stream_id`] of its creator chain.
+    async fn read_upgrade_event(
+        &self,
+        description: &ApplicationDescription,
+        index: u32,
+        txn_tracker: &mut TransactionTracker,
+    ) -> Result<Vec<u8>, ExecutionError> {
+        let event_id = EventId {
+            chain_id: description.creator_chain_id,
+            stream_id: upgrade_authority_stream_id(ApplicationId::from(description)),
+            index,
+        };
+        txn_tracker
+            .oracle(|| async {
+                let bytes = self.get_event(event_id.clone()).await?;
+                Ok(OracleResponse::Event(event_id.clone(), bytes))
+            })
+            .await?
+            .to_event(&event_id)
+    }
+
+    /// Marks the application as deleted on this chain, and ends its event subscriptions and
+    /// its fee sponsorship. Returns the fees paid for writing to its storage.
+    pub async fn tombstone_application(
+        &mut self,
+        application_id: ApplicationId,
+    ) -> Result<Amount, ExecutionError> {
+        self.check_not_tombstoned(application_id).await?;
+        self.tombstoned_applications.insert(&application_id)?;
+        let storage_fees = self
+            .storage_fees
+            .get(&application_id)
+            .await?
+            .unwrap_or_default();
+        self.storage_fees.remove(&application_id)?;
+        self.end_fee_sponsorship(application_id).await?;
+        for (key, mut subscriptions) in self.event_subscriptions.index_values().await? {
+            if !subscriptions.applications.remove(&application_id) {
+                continue;
+            }
+            if subscriptions.applications.is_empty() {
+                self.event_subscriptions.remove(&key)?;
+            } else {
+                self.event_subscriptions.insert(&key, subscriptions)?;
+            }
+        }
+        Ok(storage_fees)
+    }
+
+    /// Returns an error if the storage of the application was deleted on this chain.
+    pub async fn check_not_tombstoned(
+        &self,
+        application_id: ApplicationId,
+    ) -> Result<(), ExecutionError> {
+        ensure!(
+            !self
+                .tombstoned_applications
+                .contains(&application_id)
+                .await?,
+            ExecutionError::ApplicationTombstoned(application_id)
+        );
+        Ok(())
+    }
+
+    /// Returns the given description of the application `id`, with the module that the
+    /// application currently runs on this chain.
+    ///
+    /// After an upgrade, the returned description no longer hashes to `id`, so callers must
+    /// keep using `id` to identify the application. The contract and service caches of
+    /// `linera-storage` rely on this: they are keyed by the synthetic `ApplicationId` that
+    /// the returned description hashes to, which is what keeps the code of the new module
+    /// from being served from the cache entry of the previous one.
+    pub async fn with_current_module(
+        &mut self,
+        id: ApplicationId,
+        mut description: ApplicationDescription,
+        txn_tracker: &mut TransactionTracker,
+    ) -> Result<ApplicationDescription, ExecutionError> {
+        let Some(upgrade) = self
+            .application_upgrades
+            .get(&id)
+            .await?
+            .and_then(|upgrades| upgrades.last().copied())
+        else {
+            return Ok(description);
+        };
+        for blob_id in self
+            .check_bytecode_blobs(&upgrade.module_id, txn_tracker)
+            .await?
+        {
+            self.blob_used(txn_tracker, blob_id).await?;
+        }
+        description.module_id = upgrade.module_id;
+        Ok(description)
+    }
+
     /// Retrieves the recursive dependencies of applications and applies a topological sort.
     pub async fn find_dependencies(
         &mut self,
@@ -119,6 +119,9 @@ impl<Runtime> Drop for MockApplicationInstance<Runtime> {
 type InstantiateHandler = Box<
     dyn FnOnce(&mut ContractSyncRuntimeHandle, Vec<u8>) -> Result<(), ExecutionError> + Send + Sync,
 >;
+type MigrateHandler = Box<
+    dyn FnOnce(&mut ContractSyncRuntimeHandle, Vec<u8>) -> Result<(), ExecutionError> + Send + Sync,
+>;
 type ExecuteOperationHandler = Box<
     dyn FnOnce(&mut ContractSyncRuntimeHandle, Vec<u8>) -> Result<Vec<u8>, ExecutionError>
         + Send
@@ -145,6 +148,8 @@ type HandleQueryHandler = Box<
 pub enum ExpectedCall {
     /// An expected call to [`UserContract::instantiate`].
     Instantiate(#[debug(skip)] InstantiateHandler),
+    /// An expected call to [`UserContract::migrate`].
+    Migrate(#[debug(skip)] MigrateHandler),
     /// An expected call to [`UserContract::execute_operation`].
     ExecuteOperation(#[debug(skip)] ExecuteOperationHandler),
     /// An expected call to [`UserContract::execute_message`].
@@ -161,6 +166,7 @@ impl Display for ExpectedCall {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         let name = match self {
             ExpectedCall::Instantiate(_) => "instantiate",
+            ExpectedCall::Migrate(_) => "migrate",
             ExpectedCall::ExecuteOperation(_) => "execute_operation",
             ExpectedCall::ExecuteMessage(_) => "execute_message",
             ExpectedCall::ProcessStreams(_) => "process_streams",
@@ -184,6 +190,17 @@ impl ExpectedCall {
         ExpectedCall::Instantiate(Box::new(handler))
     }
 
+    /// Creates an [`ExpectedCall`] to the [`MockApplicationInstance`]'s
+    /// [`UserContract::migrate`] implementation, which is handled by the provided `handler`.
+    pub fn migrate(
+        handler: impl FnOnce(&mut ContractSyncRuntimeHandle, Vec<u8>) -> Result<(), ExecutionError>
+            + Send
+            + Sync
+            + 'static,
+    ) -> Self {
+        ExpectedCall::Migrate(Box::new(handler))
+    }
+
     /// Creates an [`ExpectedCall`] to the [`MockApplicationInstance`]'s
     /// [`UserContract::execute_operation`] implementation, which is handled by the provided
     /// `handler`.
@@ -288,6 +305,16 @@ impl UserContract for MockApplicationInstance<ContractSyncRuntimeHandle> {
         }
     }
 
+    fn migrate(&mut self, argument: Vec<u8>) -> Result<(), ExecutionError> {
+        match self.next_expected_call() {
+            Some(ExpectedCall::Migrate(handler)) => handler(&mut self.runtime, argument),
+            Some(unexpected_call) => {
+                panic!("Expected a call to `migrate`, got a call to `{unexpected_call}` instead.")
+            }
+            None => panic!("Unexpected call to `migrate`"),
+        }
+    }
+
     fn execute_operation(&mut self, operation: Vec<u8>) -> Result<Vec<u8>, ExecutionError> {
         match self.next_expected_call() {
             Some(ExpectedCall::ExecuteOperation(handler)) => handler(&mut self.runtime, operation),
@@ -0,0 +1,126 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Structured traces of the execution of user applications.
+
+use linera_base::{
+    data_types::Amount,
+    identifiers::{Account, AccountOwner, ApplicationId, ChainId, StreamName, TokenId},
+};
+use linera_views::store::RangeQuery;
+use serde::{Deserialize, Serialize};
+
+/// The call tree recorded while executing a user action.
+///
+/// The first frame is the application the action was addressed to. It is followed by one
+/// frame for each application that was finalized at the end of the action.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionTrace {
+    pub frames: Vec<TraceFrame>,
+}
+
+/// What an application did while it was at the top of the call stack.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceFrame {
+    /// The application being executed.
+    pub application_id: ApplicationId,
+    /// The fuel consumed in this frame, including the fuel consumed by nested calls.
+    pub fuel: u64,
+    /// The recorded entries, in execution order.
+    pub entries: Vec<TraceEntry>,
+}
+
+/// A single step recorded in a [`TraceFrame`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TraceEntry {
+    /// A call to another application.
+    Call {
+        authenticated: bool,
+        frame: TraceFrame,
+    },
+    /// A message sent to another chain.
+    SendMessage {
+        destination: ChainId,
+        authenticated: bool,
+        is_tracked: bool,
+        grant: Amount,
+    },
+    /// An event emitted on one of the application's streams.
+    Emit {
+        stream_name: StreamName,
+        index: u32,
+        value_len: u64,
+    },
+    /// A transfer of tokens.
+    Transfer {
+        source: AccountOwner,
+        destination: Account,
+        amount: Amount,
+        token_id: Option<TokenId>,
+    },
+    /// A read of the given keys from the application's storage.
+    Read { keys: Vec<Vec<u8>> },
+    /// A scan of the application's storage for the keys starting with `key_prefix`.
+    FindByPrefix { key_prefix: Vec<u8> },
+    /// A scan of the application's storage for the keys starting with `key_prefix`, within
+    /// the bounds of the `query`.
+    FindByRange {
+        key_prefix: Vec<u8>,
+        query: RangeQuery,
+    },
+    /// A batch of writes to the application's storage.
+    WriteBatch { num_operations: u64, size: u64 },
+}
+
+/// Builds an [`ExecutionTrace`] from the events reported by the runtime.
+#[derive(Debug, Default)]
+pub(crate) struct ExecutionTraceRecorder {
+    /// The frames of the applications currently on the call stack, with the fuel used when
+    /// each of them started.
+    stack: Vec<(TraceFrame, u64)>,
+    /// The trace of the frames that are already complete.
+    trace: ExecutionTrace,
+}
+
+impl ExecutionTraceRecorder {
+    /// Opens a frame for `application_id`, which starts executing after `fuel_used` units of
+    /// fuel have been consumed.
+    pub(crate) fn enter(&mut self, application_id: ApplicationId, fuel_used: u64) {
+        let frame = TraceFrame {
+            application_id,
+            fuel: 0,
+            entries: Vec::new(),
+        };
+        self.stack.push((frame, fuel_used));
+    }
+
+    /// Closes the current frame, and attaches it to its caller, if any.
+    pub(crate) fn exit(&mut self, authenticated: bool, fuel_used: u64) {
+        let Some((mut frame, fuel_at_start)) = self.stack.pop() else {
+            return;
+        };
+        frame.fuel = fuel_used.saturating_sub(fuel_at_start);
+        match self.stack.last_mut() {
+            Some((caller, _)) => caller.entries.push(TraceEntry::Call {
+                authenticated,
+                frame,
+            }),
+            None => self.trace.frames.push(frame),
+        }
+    }
+
+    /// Records an entry in the current frame.
+    pub(crate) fn record(&mut self, entry: TraceEntry) {
+        if let Some((frame, _)) = self.stack.last_mut() {
+            frame.entries.push(entry);
+        }
+    }
+
+    /// Closes any frame left open and returns the trace.
+    pub(crate) fn finish(mut self, fuel_used: u64) -> ExecutionTrace {
+        while !self.stack.is_empty() {
+            self.exit(false, fuel_used);
+        }
+        self.trace
+    }
+}
@@ -14,7 +14,7 @@ use linera_base::{
     identifiers::{ApplicationId, BlobId, ChainId, StreamId},
 };
 
-use crate::{ExecutionError, OutgoingMessage};
+use crate::{ExecutionError, ExecutionTrace, OutgoingMessage};
 
 type AppStreamUpdates = BTreeMap<(ChainId, StreamId), (u32, u32)>;
 
@@ -54,6 +54,9 @@ pub struct TransactionTracker {
     streams_to_process: BTreeMap<ApplicationId, AppStreamUpdates>,
     /// Published blobs this transaction refers to by [`BlobId`].
     blobs_published: BTreeSet<BlobId>,
+    /// Traces of the user actions executed in this transaction, if they are being recorded.
+    #[debug(skip_if = Vec::is_empty)]
+    execution_traces: Vec<ExecutionTrace>,
 }
 
 /// The [`TransactionTracker`] contents after a transaction has finished.
@@ -73,6 +76,9 @@ pub struct TransactionOutcome {
     pub operation_result: Vec<u8>,
     /// Blobs published by this transaction.
     pub blobs_published: BTreeSet<BlobId>,
+    /// Traces of the user actions executed in this transaction, if they were recorded.
+    #[debug(skip_if = Vec::is_empty)]
+    pub execution_traces: Vec<ExecutionTrace>,
 }
 
 impl TransactionTracker {
@@ -171,6 +177,10 @@ impl TransactionTracker {
         self.operation_result = result
     }
 
+    pub fn add_execution_trace(&mut self, trace: ExecutionTrace) {
+        self.execution_traces.push(trace);
+    }
+
     /// In replay mode, returns the next recorded oracle response. Otherwise executes `f` and
     /// records and returns the result. `f` is the implementation of the actual oracle and is
     /// only called in validation mode, so it does not have to be fully deterministic.
@@ -294,6 +304,7 @@ impl TransactionTracker {
             operation_result,
             streams_to_process,
             blobs_published,
+            execution_traces,
         } = self;
         ensure!(
             streams_to_process.is_empty(),
@@ -318,6 +329,7 @@ impl TransactionTracker {
             blobs,
             operation_result: operation_result.unwrap_or_default(),
             blobs_published,
+            execution_traces,
         })
     }
 }
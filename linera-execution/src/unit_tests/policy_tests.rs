@@ -0,0 +1,79 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Dynamic fee unit tests.
+
+use linera_base::data_types::Amount;
+
+use super::{DynamicFeePolicy, FeeMultiplier, ResourceControlPolicy};
+use crate::ResourceTracker;
+
+fn dynamic_fees() -> DynamicFeePolicy {
+    DynamicFeePolicy {
+        target_fuel_per_block: 1_000,
+        target_bytes_per_block: 1_000,
+        maximum_multiplier: 2,
+    }
+}
+
+#[test]
+fn fee_multiplier_follows_block_load() {
+    let dynamic_fees = dynamic_fees();
+    let idle = ResourceTracker::default();
+    let on_target = ResourceTracker {
+        wasm_fuel: 600,
+        evm_fuel: 400,
+        ..ResourceTracker::default()
+    };
+    let congested = ResourceTracker {
+        bytes_read: 5_000,
+        ..ResourceTracker::default()
+    };
+    let half_full = ResourceTracker {
+        wasm_fuel: 500,
+        ..ResourceTracker::default()
+    };
+
+    // The multiplier never drops below one.
+    let multiplier = dynamic_fees.next_fee_multiplier(FeeMultiplier::ONE, &idle);
+    assert_eq!(multiplier, FeeMultiplier::ONE);
+
+    // At most 12.5% more per block, even if the block is far above the target.
+    let multiplier = dynamic_fees.next_fee_multiplier(multiplier, &congested);
+    assert_eq!(multiplier, FeeMultiplier(1_125_000));
+
+    let multiplier = dynamic_fees.next_fee_multiplier(multiplier, &on_target);
+    assert_eq!(multiplier, FeeMultiplier(1_125_000));
+
+    let multiplier = dynamic_fees.next_fee_multiplier(multiplier, &half_full);
+    assert_eq!(multiplier, FeeMultiplier(1_054_688));
+
+    // The multiplier is capped by the maximum.
+    let multiplier = dynamic_fees.next_fee_multiplier(FeeMultiplier(1_950_000), &congested);
+    assert_eq!(multiplier, FeeMultiplier(2_000_000));
+}
+
+#[test]
+fn fee_multiplier_scales_prices_but_not_limits() {
+    let policy = ResourceControlPolicy {
+        dynamic_fees: Some(dynamic_fees()),
+        ..ResourceControlPolicy::testnet()
+    };
+    let scaled = policy.clone().with_fee_multiplier(FeeMultiplier(1_500_000));
+    assert_eq!(scaled.wasm_fuel_unit, Amount::from_nanos(15));
+    assert_eq!(scaled.operation, Amount::from_micros(15));
+    assert_eq!(scaled.service_as_oracle_query, Amount::from_millis(15));
+    assert_eq!(
+        scaled.maximum_wasm_fuel_per_block,
+        policy.maximum_wasm_fuel_per_block
+    );
+    assert_eq!(scaled.dynamic_fees, policy.dynamic_fees);
+    assert_eq!(
+        policy.clone().with_fee_multiplier(FeeMultiplier::ONE),
+        policy
+    );
+    assert_eq!(
+        FeeMultiplier(1_500_000).apply(Amount::from_attos(3)),
+        Amount::from_attos(4)
+    );
+}
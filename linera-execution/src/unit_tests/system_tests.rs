@@ -1,13 +1,22 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use linera_base::data_types::{Blob, BlockHeight, Bytecode};
+use std::sync::Arc;
+
+use assert_matches::assert_matches;
 #[cfg(with_testing)]
 use linera_base::vm::VmRuntime;
+use linera_base::{
+    crypto::{Bls12381SecretKey, ValidatorPublicKey},
+    data_types::{Blob, BlockHeight, Bytecode},
+};
 use linera_views::context::MemoryContext;
 
 use super::*;
-use crate::{test_utils::dummy_chain_description, ExecutionStateView, TestExecutionRuntimeContext};
+use crate::{
+    test_utils::dummy_chain_description, ExecutionStateView, Message, Operation,
+    ResourceControlPolicy, ResourceTracker, TestExecutionRuntimeContext,
+};
 
 /// Returns an execution state view and a matching operation context, for epoch 1, with root
 /// chain 0 as the admin ID and one empty committee.
@@ -67,6 +76,7 @@ async fn application_message_index() -> anyhow::Result<()> {
         parameters: vec![],
         instantiation_argument: vec![],
         required_application_ids: vec![],
+        upgrade_authority: None,
     };
     let mut txn_tracker = TransactionTracker::default();
     view.context()
@@ -109,6 +119,110 @@ async fn open_chain_message_index() {
     );
 }
 
+#[tokio::test]
+async fn open_chain_rejects_unreachable_approval_threshold() {
+    let (mut view, context) = new_view_and_context().await;
+    let owner = linera_base::crypto::AccountPublicKey::test_key(0).into();
+    let ownership = ChainOwnership::single(owner).with_approval_threshold(101);
+    let config = OpenChainConfig {
+        ownership,
+        balance: Amount::ZERO,
+        application_permissions: Default::default(),
+    };
+    let mut txn_tracker = TransactionTracker::default();
+    let operation = SystemOperation::OpenChain(config);
+    let mut controller = ResourceController::default();
+    let result = view
+        .system
+        .execute_operation(context, operation, &mut txn_tracker, &mut controller)
+        .await;
+    assert_matches!(
+        result,
+        Err(ExecutionError::UnreachableApprovalThreshold(101))
+    );
+}
+
+/// Tests that committees are only created if their aggregate keys come with valid proofs
+/// of possession.
+#[tokio::test]
+async fn create_committee_checks_aggregate_keys() -> anyhow::Result<()> {
+    let description = dummy_chain_description(0);
+    let context = OperationContext {
+        chain_id: ChainId::from(&description),
+        authenticated_signer: None,
+        height: BlockHeight::from(7),
+        round: Some(0),
+        timestamp: Default::default(),
+    };
+    let mut view = SystemExecutionState {
+        admin_id: Some(description.id()),
+        description: Some(description),
+        epoch: Epoch(0),
+        ..SystemExecutionState::default()
+    }
+    .into_view()
+    .await;
+
+    let aggregate_key = Bls12381SecretKey::generate();
+    let other_key = Bls12381SecretKey::generate();
+    let validator_key = ValidatorPublicKey::test_key(1);
+    let account_key = linera_base::crypto::AccountPublicKey::test_key(1);
+    let mut validators = Committee::make_simple(vec![(validator_key, account_key)])
+        .validators()
+        .clone();
+    let state = validators.get_mut(&validator_key).unwrap();
+    state.aggregate_public_key = Some(aggregate_key.public());
+    state.aggregate_key_proof = Some(other_key.proof_of_possession());
+    let invalid_blob = Blob::new_committee(bcs::to_bytes(&Committee::new(
+        validators.clone(),
+        ResourceControlPolicy::default(),
+    ))?);
+    validators
+        .get_mut(&validator_key)
+        .unwrap()
+        .aggregate_key_proof = Some(aggregate_key.proof_of_possession());
+    let committee = Committee::new(validators, ResourceControlPolicy::default());
+    let valid_blob = Blob::new_committee(bcs::to_bytes(&committee)?);
+    view.context()
+        .extra()
+        .add_blobs([invalid_blob.clone(), valid_blob.clone()])
+        .await?;
+
+    let mut controller = ResourceController::default();
+    let operation = SystemOperation::Admin(AdminOperation::CreateCommittee {
+        epoch: Epoch(1),
+        blob_hash: invalid_blob.id().hash,
+    });
+    let result = view
+        .system
+        .execute_operation(
+            context,
+            operation,
+            &mut TransactionTracker::default(),
+            &mut controller,
+        )
+        .await;
+    assert_matches!(result, Err(ExecutionError::InvalidAggregateKey(key)) if key == validator_key);
+
+    let operation = SystemOperation::Admin(AdminOperation::CreateCommittee {
+        epoch: Epoch(1),
+        blob_hash: valid_blob.id().hash,
+    });
+    view.system
+        .execute_operation(
+            context,
+            operation,
+            &mut TransactionTracker::default(),
+            &mut controller,
+        )
+        .await?;
+    assert_eq!(
+        view.system.committees.get().get(&Epoch(1)),
+        Some(&committee)
+    );
+    Ok(())
+}
+
 /// Tests if an account is removed from storage if it is drained.
 #[tokio::test]
 async fn empty_accounts_are_removed() -> anyhow::Result<()> {
@@ -129,3 +243,523 @@ async fn empty_accounts_are_removed() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Tests that scheduled operations escrow their budget, become due at their timestamp, and
+/// refund the budget when discarded by their creator or a chain owner.
+#[tokio::test]
+async fn scheduled_operations_are_discarded_with_refund() -> anyhow::Result<()> {
+    let application_id = ApplicationId::new(CryptoHash::test_hash("application description"));
+    let owner = AccountOwner::from(application_id);
+    let creator = AccountOwner::from(CryptoHash::test_hash("creator"));
+    let chain_owner = AccountOwner::from(CryptoHash::test_hash("chain owner"));
+    let stranger = AccountOwner::from(CryptoHash::test_hash("stranger"));
+    let budget = Amount::from_tokens(2);
+    let (mut view, mut context) = new_view_and_context().await;
+    view.system
+        .ownership
+        .set(ChainOwnership::single(chain_owner));
+    view.system.credit(&owner, Amount::from_tokens(5)).await?;
+
+    let later = view
+        .system
+        .schedule_operation(
+            application_id,
+            Some(creator),
+            Timestamp::from(20),
+            vec![2],
+            budget,
+        )
+        .await?;
+    let sooner = view
+        .system
+        .schedule_operation(
+            application_id,
+            Some(creator),
+            Timestamp::from(10),
+            vec![1],
+            Amount::ZERO,
+        )
+        .await?;
+    assert_eq!(
+        view.system.balances.get(&owner).await?,
+        Some(Amount::from_tokens(3))
+    );
+
+    assert!(view
+        .system
+        .due_scheduled_operations(Timestamp::from(9))
+        .await?
+        .is_empty());
+    assert_eq!(
+        view.system
+            .due_scheduled_operations(Timestamp::from(20))
+            .await?,
+        vec![sooner, later]
+    );
+
+    context.timestamp = Timestamp::from(15);
+    context.authenticated_signer = Some(creator);
+    let mut controller = ResourceController::default();
+    let result = view
+        .system
+        .execute_operation(
+            context,
+            SystemOperation::DiscardScheduledOperation(later),
+            &mut TransactionTracker::default(),
+            &mut controller,
+        )
+        .await;
+    assert_matches!(result, Err(ExecutionError::ScheduledOperationNotDue { .. }));
+
+    context.timestamp = Timestamp::from(20);
+    for signer in [None, Some(stranger)] {
+        let result = view
+            .system
+            .execute_operation(
+                OperationContext {
+                    authenticated_signer: signer,
+                    ..context
+                },
+                SystemOperation::DiscardScheduledOperation(later),
+                &mut TransactionTracker::default(),
+                &mut controller,
+            )
+            .await;
+        assert_matches!(
+            result,
+            Err(ExecutionError::UnauthorizedScheduledOperationDiscard(key)) if key == later
+        );
+    }
+    view.system
+        .execute_operation(
+            context,
+            SystemOperation::DiscardScheduledOperation(later),
+            &mut TransactionTracker::default(),
+            &mut controller,
+        )
+        .await?;
+    assert_eq!(
+        view.system.balances.get(&owner).await?,
+        Some(Amount::from_tokens(5))
+    );
+    assert_eq!(
+        view.system
+            .due_scheduled_operations(Timestamp::from(20))
+            .await?,
+        vec![sooner]
+    );
+
+    view.system
+        .execute_operation(
+            OperationContext {
+                authenticated_signer: Some(chain_owner),
+                ..context
+            },
+            SystemOperation::DiscardScheduledOperation(sooner),
+            &mut TransactionTracker::default(),
+            &mut controller,
+        )
+        .await?;
+    assert!(view
+        .system
+        .due_scheduled_operations(Timestamp::from(20))
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+/// Tests that only the issuer can mint and burn a token, and that token balances are kept
+/// separately from the native balances.
+#[tokio::test]
+async fn tokens_are_minted_transferred_and_burned() -> anyhow::Result<()> {
+    let issuer = ApplicationId::new(CryptoHash::test_hash("issuer"));
+    let other = ApplicationId::new(CryptoHash::test_hash("other"));
+    let token_id = TokenId::new(issuer, 0);
+    let owner = AccountOwner::from(CryptoHash::test_hash("owner"));
+    let recipient = AccountOwner::from(CryptoHash::test_hash("recipient"));
+    let (mut view, context) = new_view_and_context().await;
+
+    let result = view
+        .system
+        .mint_token(other, token_id, owner, Amount::from_tokens(5))
+        .await;
+    assert_matches!(result, Err(ExecutionError::UnauthorizedTokenIssuer { .. }));
+    view.system
+        .mint_token(issuer, token_id, owner, Amount::from_tokens(5))
+        .await?;
+
+    let maybe_message = view
+        .system
+        .transfer(
+            Some(owner),
+            None,
+            owner,
+            Account::new(context.chain_id, recipient),
+            Amount::from_tokens(2),
+            Some(token_id),
+        )
+        .await?;
+    assert!(maybe_message.is_none());
+    assert_eq!(
+        view.system.token_balance(&owner, token_id).await?,
+        Amount::from_tokens(3)
+    );
+    assert_eq!(
+        view.system.token_balance(&recipient, token_id).await?,
+        Amount::from_tokens(2)
+    );
+    assert!(view.system.balances.indices().await?.is_empty());
+
+    // Tokens sent to other chains are credited by a token-specific message.
+    let other_chain_id = dummy_chain_description(6).id();
+    let maybe_message = view
+        .system
+        .transfer(
+            Some(owner),
+            None,
+            owner,
+            Account::new(other_chain_id, recipient),
+            Amount::ONE,
+            Some(token_id),
+        )
+        .await?;
+    assert_matches!(
+        maybe_message.map(|message| message.message),
+        Some(Message::System(SystemMessage::CreditToken {
+            target,
+            amount,
+            source,
+            token_id: credited_token_id,
+        })) if target == recipient
+            && amount == Amount::ONE
+            && source == owner
+            && credited_token_id == token_id
+    );
+    assert_eq!(
+        view.system.token_balance(&owner, token_id).await?,
+        Amount::from_tokens(2)
+    );
+
+    let result = view
+        .system
+        .burn_token(None, issuer, token_id, owner, Amount::ONE)
+        .await;
+    assert_matches!(result, Err(ExecutionError::UnauthenticatedTransferOwner));
+    let result = view
+        .system
+        .burn_token(
+            Some(recipient),
+            issuer,
+            token_id,
+            recipient,
+            Amount::from_tokens(3),
+        )
+        .await;
+    assert_matches!(result, Err(ExecutionError::InsufficientTokenBalance { .. }));
+    view.system
+        .burn_token(
+            Some(recipient),
+            issuer,
+            token_id,
+            recipient,
+            Amount::from_tokens(2),
+        )
+        .await?;
+    assert!(view
+        .system
+        .token_balances
+        .get(&(recipient, token_id))
+        .await?
+        .is_none());
+
+    Ok(())
+}
+
+/// Tests that an approved spender can transfer up to its allowance out of an owner's
+/// account until the allowance expires or is revoked.
+#[tokio::test]
+async fn allowances_are_spent_expired_and_revoked() -> anyhow::Result<()> {
+    let owner = AccountOwner::from(CryptoHash::test_hash("owner"));
+    let recipient = AccountOwner::from(CryptoHash::test_hash("recipient"));
+    let application_id = ApplicationId::new(CryptoHash::test_hash("billing application"));
+    let spender = AccountOwner::from(application_id);
+    let (mut view, mut context) = new_view_and_context().await;
+    view.system.credit(&owner, Amount::from_tokens(10)).await?;
+    let mut controller = ResourceController::default();
+    let approve = SystemOperation::Approve {
+        owner,
+        spender,
+        amount: Amount::from_tokens(3),
+        expiry: Some(Timestamp::from(100)),
+    };
+
+    context.authenticated_signer = Some(recipient);
+    let result = view
+        .system
+        .execute_operation(
+            context,
+            approve.clone(),
+            &mut TransactionTracker::default(),
+            &mut controller,
+        )
+        .await;
+    assert_matches!(result, Err(ExecutionError::UnauthenticatedApprovalOwner));
+
+    context.authenticated_signer = Some(owner);
+    view.system
+        .execute_operation(
+            context,
+            approve,
+            &mut TransactionTracker::default(),
+            &mut controller,
+        )
+        .await?;
+
+    let recipient_account = Account::new(context.chain_id, recipient);
+    view.system
+        .transfer(
+            None,
+            Some(application_id),
+            owner,
+            recipient_account,
+            Amount::from_tokens(2),
+            None,
+        )
+        .await?;
+    assert_eq!(
+        view.system.spendable_allowance(&owner, &spender).await?,
+        Amount::ONE
+    );
+    assert_eq!(
+        view.system.balances.get(&recipient).await?,
+        Some(Amount::from_tokens(2))
+    );
+
+    let result = view
+        .system
+        .transfer(
+            None,
+            Some(application_id),
+            owner,
+            recipient_account,
+            Amount::ZERO,
+            None,
+        )
+        .await;
+    assert_matches!(result, Err(ExecutionError::IncorrectTransferAmount));
+    assert_eq!(
+        view.system.spendable_allowance(&owner, &spender).await?,
+        Amount::ONE
+    );
+
+    let result = view
+        .system
+        .transfer(
+            None,
+            Some(application_id),
+            owner,
+            recipient_account,
+            Amount::from_tokens(2),
+            None,
+        )
+        .await;
+    assert_matches!(result, Err(ExecutionError::InsufficientAllowance { .. }));
+
+    view.system.timestamp.set(Timestamp::from(100));
+    assert_eq!(
+        view.system.spendable_allowance(&owner, &spender).await?,
+        Amount::ZERO
+    );
+    let result = view
+        .system
+        .transfer(
+            None,
+            Some(application_id),
+            owner,
+            recipient_account,
+            Amount::ONE,
+            None,
+        )
+        .await;
+    assert_matches!(result, Err(ExecutionError::AllowanceExpired { .. }));
+
+    view.system
+        .execute_operation(
+            context,
+            SystemOperation::Revoke { owner, spender },
+            &mut TransactionTracker::default(),
+            &mut controller,
+        )
+        .await?;
+    assert!(view.system.allowance(&owner, &spender).await?.is_none());
+    let result = view
+        .system
+        .transfer(
+            None,
+            Some(application_id),
+            owner,
+            recipient_account,
+            Amount::ONE,
+            None,
+        )
+        .await;
+    assert_matches!(result, Err(ExecutionError::UnauthenticatedTransferOwner));
+
+    Ok(())
+}
+
+/// Tests that a transfer falls back to the signer's allowance when the application's
+/// allowance has expired.
+#[tokio::test]
+async fn expired_application_allowances_fall_back_to_the_signer() -> anyhow::Result<()> {
+    let owner = AccountOwner::from(CryptoHash::test_hash("owner"));
+    let signer = AccountOwner::from(CryptoHash::test_hash("signer"));
+    let application_id = ApplicationId::new(CryptoHash::test_hash("billing application"));
+    let (mut view, context) = new_view_and_context().await;
+    view.system.credit(&owner, Amount::from_tokens(10)).await?;
+    view.system.allowances.insert(
+        &(owner, AccountOwner::from(application_id)),
+        Allowance {
+            amount: Amount::from_tokens(5),
+            expiry: Some(Timestamp::from(100)),
+        },
+    )?;
+    view.system.allowances.insert(
+        &(owner, signer),
+        Allowance {
+            amount: Amount::from_tokens(2),
+            expiry: None,
+        },
+    )?;
+    view.system.timestamp.set(Timestamp::from(100));
+
+    let recipient_account = Account::new(context.chain_id, signer);
+    view.system
+        .transfer(
+            Some(signer),
+            Some(application_id),
+            owner,
+            recipient_account,
+            Amount::ONE,
+            None,
+        )
+        .await?;
+    assert_eq!(
+        view.system.spendable_allowance(&owner, &signer).await?,
+        Amount::ONE
+    );
+    assert_eq!(
+        view.system.balances.get(&owner).await?,
+        Some(Amount::from_tokens(9))
+    );
+
+    Ok(())
+}
+
+/// Tests that a sponsoring application pays fees first, within its per-block and
+/// per-owner budgets, and gets the unspent part of its budget back.
+#[tokio::test]
+async fn sponsored_fees_are_limited_by_budgets() -> anyhow::Result<()> {
+    let application_id = ApplicationId::new(CryptoHash::test_hash("sponsor"));
+    let sponsor = AccountOwner::from(application_id);
+    let owner = AccountOwner::from(CryptoHash::test_hash("owner"));
+    let (mut view, _) = new_view_and_context().await;
+    view.system
+        .credit(&sponsor, Amount::from_tokens(10))
+        .await?;
+    view.system.balance.set(Amount::from_tokens(10));
+
+    assert_eq!(
+        view.system
+            .take_sponsor_budget(application_id, owner, Amount::ZERO)
+            .await?,
+        Amount::ZERO
+    );
+    let sponsorship = FeeSponsorship {
+        block_budget: Amount::from_tokens(3),
+        owner_budget: Amount::from_tokens(4),
+    };
+    view.system
+        .sponsor_fees(application_id, sponsorship)
+        .await?;
+
+    let budget = view
+        .system
+        .take_sponsor_budget(application_id, owner, Amount::ONE)
+        .await?;
+    assert_eq!(budget, Amount::from_tokens(2));
+    assert_eq!(
+        view.system.balances.get(&sponsor).await?,
+        Some(Amount::from_tokens(8))
+    );
+
+    let policy = ResourceControlPolicy {
+        operation: Amount::ONE,
+        ..ResourceControlPolicy::default()
+    };
+    let mut controller =
+        ResourceController::new(Arc::new(policy), ResourceTracker::default(), Some(owner));
+    controller.sponsor_budget = Some(budget);
+    let operation = Operation::system(SystemOperation::CloseChain);
+    controller
+        .with_state(&mut view.system)
+        .await?
+        .track_operation(&operation)?;
+    assert_eq!(controller.sponsor_budget, Some(Amount::ONE));
+    assert_eq!(*view.system.balance.get(), Amount::from_tokens(10));
+
+    let spent = view
+        .system
+        .return_sponsor_budget(application_id, owner, budget, Amount::ONE)
+        .await?;
+    assert_eq!(spent, Amount::ONE);
+    assert_eq!(
+        view.system.balances.get(&sponsor).await?,
+        Some(Amount::from_tokens(9))
+    );
+    assert_eq!(
+        view.system
+            .sponsored_fees
+            .get(&(application_id, owner))
+            .await?,
+        Some(Amount::ONE)
+    );
+
+    // Only three tokens of the owner budget are left.
+    assert_eq!(
+        view.system
+            .take_sponsor_budget(application_id, owner, Amount::ZERO)
+            .await?,
+        Amount::from_tokens(3)
+    );
+
+    // Ending the sponsorship forgets the fees paid for each signer.
+    view.system
+        .sponsor_fees(
+            application_id,
+            FeeSponsorship {
+                block_budget: Amount::ZERO,
+                ..sponsorship
+            },
+        )
+        .await?;
+    assert!(view.system.sponsored_fees.indices().await?.is_empty());
+    view.system
+        .sponsor_fees(
+            application_id,
+            FeeSponsorship {
+                block_budget: Amount::from_tokens(5),
+                ..sponsorship
+            },
+        )
+        .await?;
+    assert_eq!(
+        view.system
+            .take_sponsor_budget(application_id, owner, Amount::ZERO)
+            .await?,
+        Amount::from_tokens(4)
+    );
+
+    Ok(())
+}
@@ -4,12 +4,13 @@
 //! Wasm entrypoints for contracts and services.
 
 use linera_base::data_types::StreamUpdate;
-use linera_witty::wit_import;
+use linera_witty::{wit_import, RuntimeError};
 
 /// WIT entrypoints for application contracts.
 #[wit_import(package = "linera:app")]
 pub trait ContractEntrypoints {
     fn instantiate(argument: Vec<u8>);
+    fn migrate(argument: Vec<u8>);
     fn execute_operation(operation: Vec<u8>) -> Vec<u8>;
     fn execute_message(message: Vec<u8>);
     fn process_streams(streams: Vec<StreamUpdate>);
@@ -21,3 +22,12 @@ pub trait ContractEntrypoints {
 pub trait ServiceEntrypoints {
     fn handle_query(argument: Vec<u8>) -> Vec<u8>;
 }
+
+/// Returns whether `error` is due to a contract module not exporting the optional `migrate`
+/// entrypoint, which modules built before upgrades were supported do not have.
+pub(crate) fn is_missing_migrate_entrypoint(error: &RuntimeError) -> bool {
+    matches!(
+        error,
+        RuntimeError::FunctionNotFound(name) if name.ends_with("#migrate")
+    )
+}
@@ -30,7 +30,7 @@ use wasm_instrument::{gas_metering, parity_wasm};
 #[cfg(with_wasmer)]
 use wasmer::{WasmerContractInstance, WasmerServiceInstance};
 #[cfg(with_wasmtime)]
-use wasmtime::{WasmtimeContractInstance, WasmtimeServiceInstance};
+use wasmtime::{WasmtimeContractInstance, WasmtimeFuelProfiler, WasmtimeServiceInstance};
 
 pub use self::{
     entrypoints::{ContractEntrypoints, ServiceEntrypoints},
@@ -76,7 +76,10 @@ pub enum WasmContractModule {
         module: ::wasmer::Module,
     },
     #[cfg(with_wasmtime)]
-    Wasmtime { module: ::wasmtime::Module },
+    Wasmtime {
+        module: ::wasmtime::Module,
+        fuel_profiler: Option<std::sync::Arc<WasmtimeFuelProfiler>>,
+    },
 }
 
 impl WasmContractModule {
@@ -120,9 +123,14 @@ impl UserContractModule for WasmContractModule {
 
         let instance: UserContractInstance = match self {
             #[cfg(with_wasmtime)]
-            WasmContractModule::Wasmtime { module } => {
-                Box::new(WasmtimeContractInstance::prepare(module, runtime)?)
-            }
+            WasmContractModule::Wasmtime {
+                module,
+                fuel_profiler,
+            } => Box::new(WasmtimeContractInstance::prepare(
+                module,
+                fuel_profiler.as_ref(),
+                runtime,
+            )?),
             #[cfg(with_wasmer)]
             WasmContractModule::Wasmer { engine, module } => Box::new(
                 WasmerContractInstance::prepare(engine.clone(), module, runtime)?,
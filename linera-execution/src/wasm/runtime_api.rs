@@ -8,11 +8,14 @@ use linera_base::{
         Amount, ApplicationPermissions, BlockHeight, Bytecode, SendMessageRequest, Timestamp,
     },
     http,
-    identifiers::{Account, AccountOwner, ApplicationId, ChainId, StreamName},
+    identifiers::{Account, AccountOwner, ApplicationId, ChainId, StreamName, TokenId},
     ownership::{ChainOwnership, ChangeApplicationPermissionsError, CloseChainError},
     vm::VmRuntime,
 };
-use linera_views::batch::{Batch, WriteOperation};
+use linera_views::{
+    batch::{Batch, WriteOperation},
+    store::{Direction, RangeQuery},
+};
 use linera_witty::{wit_export, Instance, RuntimeError};
 use tracing::log;
 
@@ -167,6 +170,34 @@ where
             .map_err(|error| RuntimeError::Custom(error.into()))
     }
 
+    /// Returns the balance of a token issued by an application in one of the accounts on
+    /// this chain.
+    fn read_owner_token_balance(
+        caller: &mut Caller,
+        owner: AccountOwner,
+        token_id: TokenId,
+    ) -> Result<Amount, RuntimeError> {
+        caller
+            .user_data_mut()
+            .runtime
+            .read_owner_token_balance(owner, token_id)
+            .map_err(|error| RuntimeError::Custom(error.into()))
+    }
+
+    /// Returns the amount that `spender` can currently transfer out of the `owner`'s
+    /// account.
+    fn read_allowance(
+        caller: &mut Caller,
+        owner: AccountOwner,
+        spender: AccountOwner,
+    ) -> Result<Amount, RuntimeError> {
+        caller
+            .user_data_mut()
+            .runtime
+            .read_allowance(owner, spender)
+            .map_err(|error| RuntimeError::Custom(error.into()))
+    }
+
     /// Returns the balances of all accounts on the chain.
     fn read_owner_balances(
         caller: &mut Caller,
@@ -380,6 +411,66 @@ where
             .find_key_values_by_prefix_wait(&promise)
             .map_err(|error| RuntimeError::Custom(error.into()))
     }
+
+    /// Creates a new promise to search for keys that start with the `key_prefix`, within
+    /// the bounds of the range. The promise is resolved with `find_keys_wait`.
+    fn find_keys_by_range_new(
+        caller: &mut Caller,
+        key_prefix: Vec<u8>,
+        start: Option<Vec<u8>>,
+        end: Option<Vec<u8>>,
+        limit: Option<u32>,
+        reverse: bool,
+    ) -> Result<u32, RuntimeError> {
+        let mut data = caller.user_data_mut();
+        let query = range_query(start, end, limit, reverse);
+        let promise = data
+            .runtime
+            .find_keys_by_range_new(key_prefix, query)
+            .map_err(|error| RuntimeError::Custom(error.into()))?;
+
+        Ok(data.register_promise(promise))
+    }
+
+    /// Creates a new promise to search for entries whose keys start with the `key_prefix`,
+    /// within the bounds of the range. The promise is resolved with `find_key_values_wait`.
+    fn find_key_values_by_range_new(
+        caller: &mut Caller,
+        key_prefix: Vec<u8>,
+        start: Option<Vec<u8>>,
+        end: Option<Vec<u8>>,
+        limit: Option<u32>,
+        reverse: bool,
+    ) -> Result<u32, RuntimeError> {
+        let mut data = caller.user_data_mut();
+        let query = range_query(start, end, limit, reverse);
+        let promise = data
+            .runtime
+            .find_key_values_by_range_new(key_prefix, query)
+            .map_err(|error| RuntimeError::Custom(error.into()))?;
+
+        Ok(data.register_promise(promise))
+    }
+}
+
+/// Builds the [`RangeQuery`] described by the arguments of a range search.
+fn range_query(
+    start: Option<Vec<u8>>,
+    end: Option<Vec<u8>>,
+    limit: Option<u32>,
+    reverse: bool,
+) -> RangeQuery {
+    let direction = if reverse {
+        Direction::Reverse
+    } else {
+        Direction::Forward
+    };
+    RangeQuery {
+        start,
+        end,
+        limit: limit.map(|limit| limit as usize),
+        direction,
+    }
 }
 
 /// An implementation of the system API made available to contracts.
@@ -454,7 +545,7 @@ where
         caller
             .user_data_mut()
             .runtime
-            .transfer(source, destination, amount)
+            .transfer(source, destination, amount, None)
             .map_err(|error| RuntimeError::Custom(error.into()))
     }
 
@@ -468,7 +559,112 @@ where
         caller
             .user_data_mut()
             .runtime
-            .claim(source, destination, amount)
+            .claim(source, destination, amount, None)
+            .map_err(|error| RuntimeError::Custom(error.into()))
+    }
+
+    /// Transfers an `amount` of a token issued by an application from `source` owner account
+    /// (or the current chain's balance) to `destination`.
+    fn transfer_token(
+        caller: &mut Caller,
+        source: AccountOwner,
+        destination: Account,
+        amount: Amount,
+        token_id: TokenId,
+    ) -> Result<(), RuntimeError> {
+        caller
+            .user_data_mut()
+            .runtime
+            .transfer(source, destination, amount, Some(token_id))
+            .map_err(|error| RuntimeError::Custom(error.into()))
+    }
+
+    /// Claims an `amount` of a token issued by an application from a `source` account to a
+    /// `destination` account.
+    fn claim_token(
+        caller: &mut Caller,
+        source: Account,
+        destination: Account,
+        amount: Amount,
+        token_id: TokenId,
+    ) -> Result<(), RuntimeError> {
+        caller
+            .user_data_mut()
+            .runtime
+            .claim(source, destination, amount, Some(token_id))
+            .map_err(|error| RuntimeError::Custom(error.into()))
+    }
+
+    /// Mints an `amount` of a token issued by the current application into the `recipient`
+    /// account on the current chain.
+    fn mint_token(
+        caller: &mut Caller,
+        token_id: TokenId,
+        recipient: AccountOwner,
+        amount: Amount,
+    ) -> Result<(), RuntimeError> {
+        caller
+            .user_data_mut()
+            .runtime
+            .mint_token(token_id, recipient, amount)
+            .map_err(|error| RuntimeError::Custom(error.into()))
+    }
+
+    /// Burns an `amount` of a token issued by the current application from the `owner`
+    /// account on the current chain.
+    fn burn_token(
+        caller: &mut Caller,
+        token_id: TokenId,
+        owner: AccountOwner,
+        amount: Amount,
+    ) -> Result<(), RuntimeError> {
+        caller
+            .user_data_mut()
+            .runtime
+            .burn_token(token_id, owner, amount)
+            .map_err(|error| RuntimeError::Custom(error.into()))
+    }
+
+    /// Schedules an `operation` of this application to be executed in a block with a
+    /// timestamp no earlier than `timestamp`, prepaying a `budget` for its fees from the
+    /// application's account.
+    fn schedule_operation_at(
+        caller: &mut Caller,
+        timestamp: Timestamp,
+        operation: Vec<u8>,
+        budget: Amount,
+    ) -> Result<(), RuntimeError> {
+        caller
+            .user_data_mut()
+            .runtime
+            .schedule_operation_at(timestamp, operation, budget)
+            .map_err(|error| RuntimeError::Custom(error.into()))
+    }
+
+    /// Makes this application pay the execution fees of the operations targeting it, up to
+    /// `block_budget` per block and `owner_budget` in total for each block signer.
+    fn sponsor_fees(
+        caller: &mut Caller,
+        block_budget: Amount,
+        owner_budget: Amount,
+    ) -> Result<(), RuntimeError> {
+        caller
+            .user_data_mut()
+            .runtime
+            .sponsor_fees(block_budget, owner_budget)
+            .map_err(|error| RuntimeError::Custom(error.into()))
+    }
+
+    /// Deletes all the storage of this application on the current chain, and credits the
+    /// refund for the released bytes to `refund_recipient`.
+    fn self_destruct(
+        caller: &mut Caller,
+        refund_recipient: AccountOwner,
+    ) -> Result<(), RuntimeError> {
+        caller
+            .user_data_mut()
+            .runtime
+            .self_destruct(refund_recipient)
             .map_err(|error| RuntimeError::Custom(error.into()))
     }
 
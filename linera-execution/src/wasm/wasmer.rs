@@ -13,6 +13,7 @@ use linera_witty::{
 use tokio::sync::Mutex;
 
 use super::{
+    entrypoints::is_missing_migrate_entrypoint,
     module_cache::ModuleCache,
     runtime_api::{BaseRuntimeApi, ContractRuntimeApi, RuntimeApiData, ServiceRuntimeApi},
     ContractEntrypoints, ServiceEntrypoints, WasmExecutionError,
@@ -134,6 +135,15 @@ where
         Ok(())
     }
 
+    fn migrate(&mut self, argument: Vec<u8>) -> Result<(), ExecutionError> {
+        match ContractEntrypoints::new(&mut self.instance).migrate(argument) {
+            Err(error) if !is_missing_migrate_entrypoint(&error) => {
+                Err(WasmExecutionError::from(error).into())
+            }
+            _ => Ok(()),
+        }
+    }
+
     fn execute_operation(&mut self, operation: Vec<u8>) -> Result<Vec<u8>, ExecutionError> {
         Ok(ContractEntrypoints::new(&mut self.instance)
             .execute_operation(operation)
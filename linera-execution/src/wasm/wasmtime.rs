@@ -3,21 +3,30 @@
 
 //! Code specific to the usage of the [Wasmtime](https://wasmtime.dev/) runtime.
 
-use std::sync::LazyLock;
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock},
+};
 
-use linera_base::data_types::{Bytecode, StreamUpdate};
-use linera_witty::{wasmtime::EntrypointInstance, ExportTo};
+use linera_base::{
+    data_types::{Bytecode, StreamUpdate},
+    vm::VmRuntime,
+};
+use linera_witty::{wasmtime::EntrypointInstance, ExportTo, RuntimeError};
 use tokio::sync::Mutex;
-use wasmtime::{Config, Engine, Linker, Module, Store};
+use wasm_instrument::parity_wasm::{self, elements::ImportCountType};
+use wasmtime::{Caller, Config, Engine, Linker, Module, Store, WasmBacktrace};
 
 use super::{
+    add_metering,
+    entrypoints::is_missing_migrate_entrypoint,
     module_cache::ModuleCache,
     runtime_api::{BaseRuntimeApi, ContractRuntimeApi, RuntimeApiData, ServiceRuntimeApi},
     ContractEntrypoints, ServiceEntrypoints, WasmExecutionError,
 };
 use crate::{
     wasm::{WasmContractModule, WasmServiceModule},
-    ContractRuntime, ExecutionError, ServiceRuntime,
+    BaseRuntime, ContractRuntime, ExecutionError, FuelProfile, ServiceRuntime,
 };
 
 /// An [`Engine`] instance configured to run application contracts.
@@ -55,30 +64,150 @@ pub struct WasmtimeServiceInstance<Runtime> {
     instance: EntrypointInstance<RuntimeApiData<Runtime>>,
 }
 
+/// Attributes the fuel consumed by the instances of a contract module to its functions.
+pub struct WasmtimeFuelProfiler {
+    /// The names of the functions of the module before it was instrumented, by index.
+    function_names: HashMap<u32, String>,
+    /// The index of the `consume-fuel` function imported by the metering instrumentation.
+    metering_function_index: u32,
+    /// Where the fuel consumption is recorded.
+    profile: FuelProfile,
+}
+
+impl WasmtimeFuelProfiler {
+    /// Creates a new [`WasmtimeFuelProfiler`] using the name section of the provided bytecode,
+    /// which must not be instrumented yet.
+    fn new(contract_bytecode: &Bytecode, profile: FuelProfile) -> Result<Self, WasmExecutionError> {
+        let module = parity_wasm::deserialize_buffer::<parity_wasm::elements::Module>(
+            &contract_bytecode.bytes,
+        )?;
+        // The metering instrumentation appends its import after the other imported functions.
+        let metering_function_index = module.import_count(ImportCountType::Function) as u32;
+        let module = module
+            .parse_names()
+            .unwrap_or_else(|(_errors, module)| module);
+        let function_names = module
+            .names_section()
+            .and_then(|names| names.functions())
+            .map(|functions| {
+                functions
+                    .names()
+                    .iter()
+                    .map(|(index, name)| (index, name.replace(';', ":")))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(WasmtimeFuelProfiler {
+            function_names,
+            metering_function_index,
+            profile,
+        })
+    }
+
+    /// Attributes `fuel` to the stack of guest functions in `backtrace`.
+    fn record(&self, backtrace: &WasmBacktrace, fuel: u64) {
+        let stack = backtrace
+            .frames()
+            .iter()
+            .rev()
+            .map(|frame| self.function_name(frame.func_index()))
+            .collect::<Vec<_>>();
+        let stack = if stack.is_empty() {
+            "[unknown]".to_owned()
+        } else {
+            stack.join(";")
+        };
+        self.profile.record(stack, fuel);
+    }
+
+    /// Returns the name of the function at `index` in the instrumented module.
+    fn function_name(&self, index: u32) -> String {
+        let original_index = if index > self.metering_function_index {
+            index - 1
+        } else {
+            index
+        };
+        self.function_names
+            .get(&original_index)
+            .cloned()
+            .unwrap_or_else(|| format!("func[{original_index}]"))
+    }
+}
+
 impl WasmContractModule {
     /// Creates a new [`WasmContractModule`] using Wasmtime with the provided bytecode files.
     pub async fn from_wasmtime(contract_bytecode: Bytecode) -> Result<Self, WasmExecutionError> {
-        let mut contract_cache = CONTRACT_CACHE.lock().await;
-        let module = contract_cache
-            .get_or_insert_with(contract_bytecode, |bytecode| {
-                Module::new(&CONTRACT_ENGINE, bytecode)
-            })
-            .map_err(WasmExecutionError::LoadContractModule)?;
-        Ok(WasmContractModule::Wasmtime { module })
+        Ok(WasmContractModule::Wasmtime {
+            module: compile_contract(contract_bytecode).await?,
+            fuel_profiler: None,
+        })
+    }
+
+    /// Creates a new [`WasmContractModule`] using Wasmtime with the provided bytecode,
+    /// recording the fuel consumed by each of its functions in `profile`.
+    ///
+    /// The bytecode must not be instrumented for fuel metering yet. Profiling captures a
+    /// backtrace every time fuel is consumed, so it slows execution down considerably.
+    pub async fn from_wasmtime_with_fuel_profile(
+        contract_bytecode: Bytecode,
+        profile: FuelProfile,
+    ) -> Result<Self, WasmExecutionError> {
+        let fuel_profiler = WasmtimeFuelProfiler::new(&contract_bytecode, profile)?;
+        Ok(WasmContractModule::Wasmtime {
+            module: compile_contract(add_metering(contract_bytecode)?).await?,
+            fuel_profiler: Some(Arc::new(fuel_profiler)),
+        })
     }
 }
 
+/// Compiles the provided contract bytecode, or reuses a previously compiled module.
+async fn compile_contract(contract_bytecode: Bytecode) -> Result<Module, WasmExecutionError> {
+    let mut contract_cache = CONTRACT_CACHE.lock().await;
+    contract_cache
+        .get_or_insert_with(contract_bytecode, |bytecode| {
+            Module::new(&CONTRACT_ENGINE, bytecode)
+        })
+        .map_err(WasmExecutionError::LoadContractModule)
+}
+
 impl<Runtime> WasmtimeContractInstance<Runtime>
 where
     Runtime: ContractRuntime + 'static,
 {
     /// Prepares a runtime instance to call into the Wasm contract.
-    pub fn prepare(contract_module: &Module, runtime: Runtime) -> Result<Self, WasmExecutionError> {
+    pub fn prepare(
+        contract_module: &Module,
+        fuel_profiler: Option<&Arc<WasmtimeFuelProfiler>>,
+        runtime: Runtime,
+    ) -> Result<Self, WasmExecutionError> {
         let mut linker = Linker::new(&CONTRACT_ENGINE);
 
         BaseRuntimeApi::export_to(&mut linker)?;
         ContractRuntimeApi::export_to(&mut linker)?;
 
+        if let Some(fuel_profiler) = fuel_profiler {
+            let fuel_profiler = fuel_profiler.clone();
+            linker.allow_shadowing(true);
+            linker
+                .func_wrap(
+                    "linera:app/contract-runtime-api",
+                    "consume-fuel",
+                    move |mut caller: Caller<'_, RuntimeApiData<Runtime>>,
+                          fuel: u64|
+                          -> anyhow::Result<()> {
+                        fuel_profiler.record(&WasmBacktrace::capture(&caller), fuel);
+                        caller
+                            .data_mut()
+                            .runtime_mut()
+                            .consume_fuel(fuel, VmRuntime::Wasm)
+                            .map_err(|error| RuntimeError::Custom(error.into()))?;
+                        Ok(())
+                    },
+                )
+                .map_err(RuntimeError::Wasmtime)?;
+        }
+
         let user_data = RuntimeApiData::new(runtime);
         let mut store = Store::new(&CONTRACT_ENGINE, user_data);
         let instance = linker
@@ -138,6 +267,15 @@ where
         Ok(())
     }
 
+    fn migrate(&mut self, argument: Vec<u8>) -> Result<(), ExecutionError> {
+        match ContractEntrypoints::new(&mut self.instance).migrate(argument) {
+            Err(error) if !is_missing_migrate_entrypoint(&error) => {
+                Err(WasmExecutionError::from(error).into())
+            }
+            _ => Ok(()),
+        }
+    }
+
     fn execute_operation(&mut self, operation: Vec<u8>) -> Result<Vec<u8>, ExecutionError> {
         let result = ContractEntrypoints::new(&mut self.instance)
             .execute_operation(operation)
@@ -71,6 +71,7 @@ async fn test_transfer_system_api(
                     chain_id: dummy_chain_description(0).id(),
                 },
                 amount,
+                None,
             )?;
             Ok(vec![])
         },
@@ -156,6 +157,7 @@ async fn test_unauthorized_transfer_system_api(
                     chain_id: dummy_chain_description(0).id(),
                 },
                 amount,
+                None,
             )?;
             Ok(vec![])
         },
@@ -240,6 +242,7 @@ async fn test_claim_system_api(
                     chain_id: claimer_chain_id,
                 },
                 amount,
+                None,
             )?;
             Ok(vec![])
         },
@@ -369,6 +372,7 @@ async fn test_unauthorized_claims(
                     chain_id: claimer_chain_id,
                 },
                 amount,
+                None,
             )?;
             Ok(vec![])
         },
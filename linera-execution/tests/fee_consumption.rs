@@ -215,6 +215,7 @@ async fn test_fee_consumption(
         byte_read: Amount::from_tokens(7),
         byte_written: Amount::from_tokens(11),
         byte_stored: Amount::from_tokens(13),
+        byte_stored_refund: Amount::from_tokens(5),
         operation: Amount::from_tokens(17),
         operation_byte: Amount::from_tokens(19),
         message: Amount::from_tokens(23),
@@ -239,6 +240,7 @@ async fn test_fee_consumption(
         blob_byte_read: Amount::from_tokens(101),
         blob_byte_published: Amount::from_tokens(103),
         http_request_allow_list: BTreeSet::new(),
+        dynamic_fees: None,
     };
 
     let consumed_fees = spends
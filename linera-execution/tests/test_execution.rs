@@ -3,29 +3,33 @@
 
 #![allow(clippy::field_reassign_with_default)]
 
-use std::{collections::BTreeMap, vec};
+use std::{collections::BTreeMap, iter, sync::Arc, vec};
 
 use assert_matches::assert_matches;
 use linera_base::{
     crypto::{AccountPublicKey, ValidatorPublicKey},
     data_types::{
-        Amount, ApplicationPermissions, Blob, BlockHeight, ChainDescription, ChainOrigin, Epoch,
-        InitialChainConfig, Resources, SendMessageRequest, Timestamp,
+        Amount, ApplicationDescription, ApplicationPermissions, Blob, BlockHeight,
+        ChainDescription, ChainOrigin, Epoch, Event, InitialChainConfig, OracleResponse, Resources,
+        SendMessageRequest, Timestamp,
     },
-    identifiers::{Account, AccountOwner, BlobType},
+    identifiers::{Account, AccountOwner, BlobType, EventId},
     ownership::ChainOwnership,
+    vm::VmRuntime,
 };
 use linera_execution::{
     committee::Committee,
+    system::upgrade_authority_stream_id,
     test_utils::{
         blob_oracle_responses, create_dummy_message_context, create_dummy_operation_context,
-        create_dummy_user_application_registrations, dummy_chain_description,
-        dummy_chain_description_with_ownership_and_balance, ExpectedCall, RegisterMockApplication,
-        SystemExecutionState,
+        create_dummy_user_application_description, create_dummy_user_application_registrations,
+        dummy_chain_description, dummy_chain_description_with_ownership_and_balance, ExpectedCall,
+        RegisterMockApplication, SystemExecutionState,
     },
-    BaseRuntime, ContractRuntime, ExecutionError, ExecutionRuntimeContext, ExecutionStateActor,
-    Message, Operation, OperationContext, OutgoingMessage, Query, QueryContext, QueryOutcome,
-    QueryResponse, ResourceController, SystemOperation, TransactionTracker,
+    BaseRuntime, ContractRuntime, ExecutionError, ExecutionRuntimeConfig, ExecutionRuntimeContext,
+    ExecutionStateActor, ExecutionTrace, Message, Operation, OperationContext, OutgoingMessage,
+    Query, QueryContext, QueryOutcome, QueryResponse, ResourceControlPolicy, ResourceController,
+    ResourceTracker, SystemOperation, TraceEntry, TraceFrame, TransactionTracker,
 };
 use linera_views::{batch::Batch, context::Context, views::View};
 use test_case::test_case;
@@ -1006,6 +1010,156 @@ async fn test_message_from_deeper_call() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Tests that the execution trace records the calls, messages and writes of the applications,
+/// when execution traces are enabled.
+#[tokio::test]
+async fn test_execution_trace_of_cross_application_call() -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(0);
+    let config = ExecutionRuntimeConfig {
+        record_execution_traces: true,
+    };
+    let mut view = state.into_view_with(chain_id, config).await;
+
+    let (caller_id, caller_application, caller_blobs) = view.register_mock_application(0).await?;
+    let (target_id, target_application, target_blobs) = view.register_mock_application(1).await?;
+
+    caller_application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            runtime.try_call_application(/* authenticated */ false, target_id, vec![])?;
+            Ok(vec![])
+        },
+    ));
+
+    let destination = dummy_chain_description(1).id();
+    let dummy_message = SendMessageRequest {
+        destination,
+        authenticated: false,
+        is_tracked: true,
+        grant: Resources::default(),
+        message: b"msg".to_vec(),
+    };
+    let mut batch = Batch::new();
+    batch.put_key_value_bytes(b"key".to_vec(), b"value".to_vec());
+    let batch_size = batch.size() as u64;
+
+    target_application.expect_call(ExpectedCall::execute_operation(|runtime, _argument| {
+        runtime.send_message(dummy_message)?;
+        runtime.write_batch(batch)?;
+        Ok(vec![])
+    }));
+
+    target_application.expect_call(ExpectedCall::default_finalize());
+    caller_application.expect_call(ExpectedCall::default_finalize());
+
+    let context = create_dummy_operation_context(chain_id);
+    let mut controller = ResourceController::default();
+    let mut txn_tracker =
+        TransactionTracker::new_replaying_blobs(caller_blobs.iter().chain(&target_blobs));
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            Operation::User {
+                application_id: caller_id,
+                bytes: vec![],
+            },
+        )
+        .await?;
+
+    let frame = |application_id, entries| TraceFrame {
+        application_id,
+        fuel: 0,
+        entries,
+    };
+    let target_frame = frame(
+        target_id,
+        vec![
+            TraceEntry::SendMessage {
+                destination,
+                authenticated: false,
+                is_tracked: true,
+                grant: Amount::ZERO,
+            },
+            TraceEntry::WriteBatch {
+                num_operations: 1,
+                size: batch_size,
+            },
+        ],
+    );
+    let expected_trace = ExecutionTrace {
+        frames: vec![
+            frame(
+                caller_id,
+                vec![TraceEntry::Call {
+                    authenticated: false,
+                    frame: target_frame,
+                }],
+            ),
+            frame(target_id, vec![]),
+            frame(caller_id, vec![]),
+        ],
+    };
+
+    let txn_outcome = txn_tracker.into_outcome().unwrap();
+    assert_eq!(txn_outcome.execution_traces, vec![expected_trace]);
+
+    Ok(())
+}
+
+/// Tests that the execution trace of a failing operation is returned with its error.
+#[tokio::test]
+async fn test_execution_trace_of_failing_operation() -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(0);
+    let config = ExecutionRuntimeConfig {
+        record_execution_traces: true,
+    };
+    let mut view = state.into_view_with(chain_id, config).await;
+
+    let (application_id, application, blobs) = view.register_mock_application(0).await?;
+    let error_message = "Operation failed";
+    let mut batch = Batch::new();
+    batch.put_key_value_bytes(b"key".to_vec(), b"value".to_vec());
+    let batch_size = batch.size() as u64;
+
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            runtime.write_batch(batch)?;
+            Err(ExecutionError::UserError(error_message.to_owned()))
+        },
+    ));
+
+    let context = create_dummy_operation_context(chain_id);
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::new_replaying_blobs(blobs);
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            Operation::User {
+                application_id,
+                bytes: vec![],
+            },
+        )
+        .await;
+
+    let error = result.unwrap_err();
+    assert_matches!(
+        error.without_trace(),
+        ExecutionError::UserError(message) if message == error_message
+    );
+    let expected_trace = ExecutionTrace {
+        frames: vec![TraceFrame {
+            application_id,
+            fuel: 0,
+            entries: vec![TraceEntry::WriteBatch {
+                num_operations: 1,
+                size: batch_size,
+            }],
+        }],
+    };
+    assert_eq!(error.trace(), Some(&expected_trace));
+
+    Ok(())
+}
+
 /// Tests if multiple messages are scheduled to be sent by different applications to different
 /// chains.
 ///
@@ -1193,7 +1347,7 @@ async fn test_open_chain() -> anyhow::Result<()> {
         move |runtime, _operation| {
             assert_eq!(runtime.chain_ownership()?, ownership);
             let destination = Account::chain(dummy_chain_description(2).id());
-            runtime.transfer(AccountOwner::CHAIN, destination, Amount::ONE)?;
+            runtime.transfer(AccountOwner::CHAIN, destination, Amount::ONE, None)?;
             let application_permissions = child_application_permissions.clone();
             let chain_id =
                 runtime.open_chain(child_ownership, application_permissions, Amount::ONE)?;
@@ -1330,6 +1484,642 @@ async fn test_close_chain() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Tests upgrading an application to a new module on its creator chain, which keeps the
+/// application's storage and announces the upgrade to other chains.
+#[tokio::test]
+async fn test_upgrade_application() -> anyhow::Result<()> {
+    // The dummy applications are created on this chain.
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(1);
+    let mut view = state.into_view().await;
+    let authority = AccountOwner::from(AccountPublicKey::test_key(1));
+
+    let (description, contract_blob, service_blob) = create_dummy_user_application_description(0);
+    let blobs = [
+        Blob::new_application_description(&description).id(),
+        contract_blob.id(),
+        service_blob.id(),
+    ];
+    let (application_id, application) = view
+        .register_mock_application_with(description.clone(), contract_blob, service_blob)
+        .await?;
+
+    let (new_description, new_contract_blob, new_service_blob) =
+        create_dummy_user_application_description(1);
+    let new_module_id = new_description.module_id;
+    let new_blobs = [new_contract_blob.id(), new_service_blob.id()];
+    // The creator chain announced the upgrade authority when creating the application.
+    let authority_event_id = EventId {
+        chain_id: description.creator_chain_id,
+        stream_id: upgrade_authority_stream_id(application_id),
+        index: 0,
+    };
+    let authority_event = OracleResponse::Event(authority_event_id, bcs::to_bytes(&authority)?);
+    let upgraded_description = ApplicationDescription {
+        module_id: new_module_id,
+        ..description
+    };
+    let (_, upgraded_application) = view
+        .register_mock_application_with(upgraded_description, new_contract_blob, new_service_blob)
+        .await?;
+
+    // The first version of the application writes to its storage.
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            let mut batch = Batch::new();
+            batch.put_key_value_bytes(vec![0], vec![1]);
+            runtime.write_batch(batch)?;
+            Ok(vec![])
+        },
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
+
+    let context = OperationContext {
+        authenticated_signer: Some(authority),
+        ..create_dummy_operation_context(chain_id)
+    };
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::new_replaying_blobs(blobs);
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            Operation::User {
+                application_id,
+                bytes: vec![],
+            },
+        )
+        .await?;
+
+    // Only the upgrade authority can upgrade the application.
+    let operation = SystemOperation::UpgradeApplication {
+        application_id,
+        module_id: new_module_id,
+        migration_argument: vec![2],
+    };
+    let unauthorized_context = OperationContext {
+        authenticated_signer: Some(AccountOwner::from(AccountPublicKey::test_key(2))),
+        ..context
+    };
+    let mut txn_tracker = TransactionTracker::new_replaying(vec![authority_event.clone()]);
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(unauthorized_context, operation.clone().into())
+        .await;
+    assert_matches!(
+        result,
+        Err(ExecutionError::UnauthorizedApplicationUpgrade(id)) if id == application_id
+    );
+
+    // The new module is migrated and sees the storage written by the previous one.
+    upgraded_application.expect_call(ExpectedCall::migrate(move |runtime, argument| {
+        assert_eq!(argument, vec![2]);
+        assert_eq!(runtime.read_value_bytes(vec![0])?, Some(vec![1]));
+        Ok(())
+    }));
+    upgraded_application.expect_call(ExpectedCall::default_finalize());
+
+    let mut txn_tracker = TransactionTracker::new_replaying(
+        iter::once(authority_event)
+            .chain(new_blobs.into_iter().map(OracleResponse::Blob))
+            .collect(),
+    );
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(context, operation.into())
+        .await?;
+    assert_eq!(
+        txn_tracker.into_outcome()?.events,
+        vec![Event {
+            stream_id: upgrade_authority_stream_id(application_id),
+            index: 1,
+            value: bcs::to_bytes(&(new_module_id, vec![2u8]))?,
+        }]
+    );
+
+    // Later operations are executed by the new module.
+    upgraded_application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            assert_eq!(runtime.read_value_bytes(vec![0])?, Some(vec![1]));
+            Ok(vec![])
+        },
+    ));
+    upgraded_application.expect_call(ExpectedCall::default_finalize());
+
+    let mut txn_tracker = TransactionTracker::new_replaying(Vec::new());
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            Operation::User {
+                application_id,
+                bytes: vec![],
+            },
+        )
+        .await?;
+
+    let upgrades = view
+        .system
+        .application_upgrades
+        .get(&application_id)
+        .await?
+        .expect("the upgrade should be recorded");
+    assert_eq!(upgrades.len(), 1);
+    assert_eq!(upgrades[0].previous_module_id, description.module_id);
+    assert_eq!(upgrades[0].module_id, new_module_id);
+    Ok(())
+}
+
+/// Tests that other chains apply the upgrades announced by the creator chain of an
+/// application, without the signature of the upgrade authority.
+#[tokio::test]
+async fn test_upgrade_application_on_other_chain() -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(0);
+    let mut view = state.into_view().await;
+
+    let (description, contract_blob, service_blob) = create_dummy_user_application_description(0);
+    let blobs = [
+        Blob::new_application_description(&description).id(),
+        contract_blob.id(),
+        service_blob.id(),
+    ];
+    let (application_id, _application) = view
+        .register_mock_application_with(description.clone(), contract_blob, service_blob)
+        .await?;
+    assert_ne!(description.creator_chain_id, chain_id);
+
+    let (new_description, new_contract_blob, new_service_blob) =
+        create_dummy_user_application_description(1);
+    let new_module_id = new_description.module_id;
+    let new_blobs = [new_contract_blob.id(), new_service_blob.id()];
+    let (_, upgraded_application) = view
+        .register_mock_application_with(
+            ApplicationDescription {
+                module_id: new_module_id,
+                ..description.clone()
+            },
+            new_contract_blob,
+            new_service_blob,
+        )
+        .await?;
+
+    // The creator chain announced the first upgrade of the application.
+    let upgrade_event_id = EventId {
+        chain_id: description.creator_chain_id,
+        stream_id: upgrade_authority_stream_id(application_id),
+        index: 1,
+    };
+    let upgrade_event = OracleResponse::Event(
+        upgrade_event_id,
+        bcs::to_bytes(&(new_module_id, vec![2u8]))?,
+    );
+
+    // Any signer can apply the upgrade, but only with the announced migration argument.
+    let context = OperationContext {
+        authenticated_signer: Some(AccountOwner::from(AccountPublicKey::test_key(2))),
+        ..create_dummy_operation_context(chain_id)
+    };
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::new_replaying(
+        blobs
+            .into_iter()
+            .map(OracleResponse::Blob)
+            .chain(iter::once(upgrade_event.clone()))
+            .collect(),
+    );
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            SystemOperation::UpgradeApplication {
+                application_id,
+                module_id: new_module_id,
+                migration_argument: vec![3],
+            }
+            .into(),
+        )
+        .await;
+    assert_matches!(
+        result,
+        Err(ExecutionError::UnauthorizedApplicationUpgrade(id)) if id == application_id
+    );
+
+    upgraded_application.expect_call(ExpectedCall::migrate(move |_runtime, argument| {
+        assert_eq!(argument, vec![2]);
+        Ok(())
+    }));
+    upgraded_application.expect_call(ExpectedCall::default_finalize());
+
+    let mut txn_tracker = TransactionTracker::new_replaying(
+        iter::once(upgrade_event)
+            .chain(new_blobs.into_iter().map(OracleResponse::Blob))
+            .collect(),
+    );
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            SystemOperation::UpgradeApplication {
+                application_id,
+                module_id: new_module_id,
+                migration_argument: vec![2],
+            }
+            .into(),
+        )
+        .await?;
+    // Only the creator chain announces upgrades.
+    assert!(txn_tracker.into_outcome()?.events.is_empty());
+
+    let upgrades = view
+        .system
+        .application_upgrades
+        .get(&application_id)
+        .await?
+        .expect("the upgrade should be recorded");
+    assert_eq!(upgrades.len(), 1);
+    assert_eq!(upgrades[0].module_id, new_module_id);
+    Ok(())
+}
+
+/// Tests that a due scheduled operation runs without an authenticated signer, that its fees
+/// are paid from its budget only, and that the unused budget is refunded to the application.
+#[tokio::test]
+async fn test_execute_scheduled_operation() -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(0);
+    let mut view = state.into_view().await;
+    let (application_id, application, blobs) = view.register_mock_application(0).await?;
+    let application_owner = AccountOwner::from(application_id);
+    let signer = AccountOwner::from(AccountPublicKey::test_key(1));
+    let chain_balance = Amount::from_tokens(10);
+    let signer_balance = Amount::from_tokens(5);
+    let budget = Amount::from_micros(1_000);
+    view.system.balance.set(chain_balance);
+    view.system.balances.insert(&signer, signer_balance)?;
+    view.system
+        .credit(&application_owner, Amount::from_micros(3_000))
+        .await?;
+
+    let mut keys = Vec::new();
+    for bytes in [vec![1], vec![2]] {
+        let key = view
+            .system
+            .schedule_operation(
+                application_id,
+                Some(signer),
+                Timestamp::from(10),
+                bytes,
+                budget,
+            )
+            .await?;
+        keys.push(key);
+    }
+    assert_eq!(
+        view.system.balances.get(&application_owner).await?,
+        Some(Amount::from_micros(1_000))
+    );
+
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, operation| {
+            assert_eq!(operation, vec![1]);
+            assert_eq!(runtime.authenticated_signer()?, None);
+            runtime.consume_fuel(300, VmRuntime::Wasm)?;
+            Ok(vec![])
+        },
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, operation| {
+            assert_eq!(operation, vec![2]);
+            runtime.consume_fuel(2_000, VmRuntime::Wasm)?;
+            Ok(vec![])
+        },
+    ));
+
+    let context = OperationContext {
+        authenticated_signer: Some(signer),
+        timestamp: Timestamp::from(10),
+        ..create_dummy_operation_context(chain_id)
+    };
+    let mut controller = ResourceController::new(
+        Arc::new(ResourceControlPolicy::only_fuel()),
+        ResourceTracker::default(),
+        Some(signer),
+    );
+    let mut txn_tracker = TransactionTracker::new_replaying_blobs(blobs);
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            SystemOperation::ExecuteScheduledOperation(keys[0]).into(),
+        )
+        .await?;
+
+    // The fuel was paid from the budget, and the rest of the budget was refunded.
+    assert_eq!(
+        view.system.balances.get(&application_owner).await?,
+        Some(Amount::from_micros(1_700))
+    );
+    assert_eq!(*view.system.balance.get(), chain_balance);
+    assert_eq!(
+        view.system.balances.get(&signer).await?,
+        Some(signer_balance)
+    );
+    assert_eq!(controller.account, Some(signer));
+
+    // An operation consuming more fuel than its budget fails, without charging the chain or
+    // the block's signer.
+    let mut txn_tracker = TransactionTracker::new_replaying_blobs(blobs);
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            SystemOperation::ExecuteScheduledOperation(keys[1]).into(),
+        )
+        .await;
+    assert_matches!(result, Err(ExecutionError::FeesExceedFunding { .. }));
+    assert_eq!(*view.system.balance.get(), chain_balance);
+    assert_eq!(
+        view.system.balances.get(&signer).await?,
+        Some(signer_balance)
+    );
+    Ok(())
+}
+
+/// Tests that the budget of a sponsoring application pays for fuel but not for message
+/// grants, since unused grants are refunded to the signer.
+#[tokio::test]
+async fn test_sponsor_budget_does_not_pay_grants() -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(0);
+    let mut view = state.into_view().await;
+    let (application_id, application, blobs) = view.register_mock_application(0).await?;
+    let signer = AccountOwner::from(AccountPublicKey::test_key(1));
+    let signer_balance = Amount::from_tokens(5);
+    let sponsor_budget = Amount::ONE;
+    view.system.balance.set(Amount::ZERO);
+    view.system.balances.insert(&signer, signer_balance)?;
+
+    let grant = Resources {
+        wasm_fuel: 1_000,
+        ..Resources::default()
+    };
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            runtime.consume_fuel(300, VmRuntime::Wasm)?;
+            runtime.send_message(SendMessageRequest {
+                destination: dummy_chain_description(1).id(),
+                authenticated: false,
+                is_tracked: false,
+                grant,
+                message: vec![],
+            })?;
+            Ok(vec![])
+        },
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
+
+    let context = OperationContext {
+        authenticated_signer: Some(signer),
+        ..create_dummy_operation_context(chain_id)
+    };
+    let mut controller = ResourceController::new(
+        Arc::new(ResourceControlPolicy::only_fuel()),
+        ResourceTracker::default(),
+        Some(signer),
+    );
+    controller.sponsor_budget = Some(sponsor_budget);
+    let mut txn_tracker = TransactionTracker::new_replaying_blobs(blobs);
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            Operation::User {
+                application_id,
+                bytes: vec![],
+            },
+        )
+        .await?;
+
+    // The sponsor paid for the fuel only.
+    assert_eq!(
+        controller.sponsor_budget,
+        Some(sponsor_budget.try_sub(Amount::from_micros(300))?)
+    );
+    // The signer paid for the grant, so getting it back cannot increase their balance.
+    let outcome = txn_tracker.into_outcome()?;
+    let grant = outcome.outgoing_messages[0].grant;
+    assert_eq!(grant, Amount::from_micros(1_000));
+    assert_eq!(
+        outcome.outgoing_messages[0].refund_grant_to,
+        Some(Account::new(chain_id, signer))
+    );
+    assert_eq!(
+        view.system.balances.get(&signer).await?,
+        Some(signer_balance.try_sub(grant)?)
+    );
+    assert_eq!(*view.system.balance.get(), Amount::ZERO);
+    Ok(())
+}
+
+/// Tests an application deleting its own storage, with a refund for the released bytes that is
+/// capped at the fees paid for writing them.
+#[tokio::test]
+async fn test_self_destruct() -> anyhow::Result<()> {
+    let (mut state, chain_id) = SystemExecutionState::dummy_chain_state(0);
+    state.balance = Amount::from_tokens(1);
+    let mut view = state.into_view().await;
+    let (application_id, application, blobs) = view.register_mock_application(0).await?;
+    let recipient = AccountOwner::from(AccountPublicKey::test_key(1));
+
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            let mut batch = Batch::new();
+            batch.put_key_value_bytes(vec![0, 1], vec![2, 3, 4]);
+            runtime.write_batch(batch)?;
+            Ok(vec![])
+        },
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            runtime.self_destruct(recipient)?;
+            // Writes after the deletion are discarded.
+            let mut batch = Batch::new();
+            batch.put_key_value_bytes(vec![5], vec![6]);
+            runtime.write_batch(batch)?;
+            Ok(vec![])
+        },
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
+
+    let policy = ResourceControlPolicy {
+        byte_written: Amount::from_micros(1),
+        byte_stored_refund: Amount::from_tokens(1),
+        ..ResourceControlPolicy::default()
+    };
+    let mut controller =
+        ResourceController::new(Arc::new(policy), ResourceTracker::default(), None);
+    let context = create_dummy_operation_context(chain_id);
+    let operation = Operation::User {
+        application_id,
+        bytes: vec![],
+    };
+    let mut txn_tracker = TransactionTracker::new_replaying_blobs(blobs);
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(context, operation.clone())
+        .await?;
+    let mut txn_tracker = TransactionTracker::new_replaying(Vec::new());
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(context, operation.clone())
+        .await?;
+
+    // The two bytes of the key and the three bytes of the value are released, but only the
+    // fees paid for writing them are refunded.
+    assert_eq!(
+        view.system.balances.get(&recipient).await?,
+        Some(Amount::from_micros(5))
+    );
+    assert_eq!(controller.tracker().bytes_stored, -5);
+    assert_eq!(view.system.storage_fees.get(&application_id).await?, None);
+    let storage = view
+        .users
+        .try_load_entry(&application_id)
+        .await?
+        .expect("the application's storage should still be listed");
+    assert_eq!(storage.total_size().sum(), 0);
+    assert!(storage.find_keys_by_prefix(&[]).await?.is_empty());
+    drop(storage);
+
+    // The application can no longer be executed on this chain.
+    let mut txn_tracker = TransactionTracker::new_replaying(Vec::new());
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(context, operation)
+        .await;
+    assert_matches!(
+        result,
+        Err(ExecutionError::ApplicationTombstoned(id)) if id == application_id
+    );
+    Ok(())
+}
+
+/// Tests that the writes of an application to its storage are discarded after it deletes it,
+/// including the ones still pending in the same call.
+#[tokio::test]
+async fn test_write_after_self_destruct() -> anyhow::Result<()> {
+    let (state, chain_id) = SystemExecutionState::dummy_chain_state(0);
+    let mut view = state.into_view().await;
+    let (application_id, application, blobs) = view.register_mock_application(0).await?;
+    let recipient = AccountOwner::from(AccountPublicKey::test_key(1));
+
+    application.expect_call(ExpectedCall::execute_operation(
+        move |runtime, _operation| {
+            let mut batch = Batch::new();
+            batch.put_key_value_bytes(vec![0, 1], vec![2, 3, 4]);
+            runtime.write_batch(batch)?;
+            runtime.self_destruct(recipient)?;
+            let mut batch = Batch::new();
+            batch.put_key_value_bytes(vec![5], vec![6]);
+            runtime.write_batch(batch)?;
+            Ok(vec![])
+        },
+    ));
+    application.expect_call(ExpectedCall::default_finalize());
+
+    let mut controller = ResourceController::default();
+    let context = create_dummy_operation_context(chain_id);
+    let mut txn_tracker = TransactionTracker::new_replaying_blobs(blobs);
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            Operation::User {
+                application_id,
+                bytes: vec![],
+            },
+        )
+        .await?;
+
+    assert!(
+        view.system
+            .tombstoned_applications
+            .contains(&application_id)
+            .await?
+    );
+    if let Some(storage) = view.users.try_load_entry(&application_id).await? {
+        assert_eq!(storage.total_size().sum(), 0);
+        assert!(storage.find_keys_by_prefix(&[]).await?.is_empty());
+    }
+    Ok(())
+}
+
+/// Tests that only the owners of a chain and the upgrade authority of an application can delete
+/// its storage.
+#[tokio::test]
+async fn test_delete_application() -> anyhow::Result<()> {
+    let (mut state, chain_id) = SystemExecutionState::dummy_chain_state(0);
+    let chain_owner = AccountOwner::from(AccountPublicKey::test_key(3));
+    state.ownership = ChainOwnership::single(chain_owner);
+    let mut view = state.into_view().await;
+    let authority = AccountOwner::from(AccountPublicKey::test_key(1));
+
+    let (description, contract_blob, service_blob) = create_dummy_user_application_description(0);
+    let (application_id, _application) = view
+        .register_mock_application_with(description.clone(), contract_blob, service_blob)
+        .await?;
+    let authority_event_id = EventId {
+        chain_id: description.creator_chain_id,
+        stream_id: upgrade_authority_stream_id(application_id),
+        index: 0,
+    };
+    let authority_event = OracleResponse::Event(authority_event_id, bcs::to_bytes(&authority)?);
+
+    let operation = SystemOperation::DeleteApplication {
+        application_id,
+        refund_recipient: authority,
+    };
+    let context = OperationContext {
+        authenticated_signer: Some(authority),
+        ..create_dummy_operation_context(chain_id)
+    };
+    let unauthorized_context = OperationContext {
+        authenticated_signer: Some(AccountOwner::from(AccountPublicKey::test_key(2))),
+        ..context
+    };
+    let mut controller = ResourceController::default();
+    let mut txn_tracker = TransactionTracker::new_replaying(vec![authority_event.clone()]);
+    let result = ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(unauthorized_context, operation.clone().into())
+        .await;
+    assert_matches!(
+        result,
+        Err(ExecutionError::UnauthorizedApplicationDeletion(id)) if id == application_id
+    );
+
+    let mut txn_tracker = TransactionTracker::new_replaying(vec![authority_event]);
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(context, operation.into())
+        .await?;
+    assert!(
+        view.system
+            .tombstoned_applications
+            .contains(&application_id)
+            .await?
+    );
+
+    // A chain owner can delete an application without looking up its upgrade authority.
+    let (description, contract_blob, service_blob) = create_dummy_user_application_description(1);
+    let (application_id, _application) = view
+        .register_mock_application_with(description, contract_blob, service_blob)
+        .await?;
+    let operation = SystemOperation::DeleteApplication {
+        application_id,
+        refund_recipient: chain_owner,
+    };
+    let owner_context = OperationContext {
+        authenticated_signer: Some(chain_owner),
+        ..context
+    };
+    let mut txn_tracker = TransactionTracker::new_replaying(Vec::new());
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(owner_context, operation.into())
+        .await?;
+    assert!(
+        view.system
+            .tombstoned_applications
+            .contains(&application_id)
+            .await?
+    );
+    Ok(())
+}
+
 /// Tests an application attempting to transfer the tokens in the chain's balance while executing
 /// messages.
 #[test_case(
@@ -1387,7 +2177,7 @@ async fn test_message_receipt_spending_chain_balance(
     };
 
     application.expect_call(ExpectedCall::execute_message(move |runtime, _operation| {
-        runtime.transfer(receiver_chain_account, recipient, amount)?;
+        runtime.transfer(receiver_chain_account, recipient, amount, None)?;
         Ok(())
     }));
     application.expect_call(ExpectedCall::default_finalize());
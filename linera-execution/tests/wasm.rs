@@ -5,7 +5,11 @@
 
 use std::sync::Arc;
 
+#[cfg(with_wasmtime)]
+use linera_base::data_types::Bytecode;
 use linera_base::data_types::{Amount, Blob, BlockHeight, Timestamp};
+#[cfg(with_wasmtime)]
+use linera_execution::FuelProfile;
 use linera_execution::{
     test_utils::{
         create_dummy_user_application_description, dummy_chain_description, SystemExecutionState,
@@ -144,3 +148,80 @@ async fn test_fuel_for_counter_wasm_application(
     assert!(operations.is_empty());
     Ok(())
 }
+
+/// Test that the fuel consumed by the "counter" example application is attributed to the
+/// functions of its Wasm module when profiling with Wasmtime.
+#[cfg(with_wasmtime)]
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn test_fuel_profile_for_counter_wasm_application() -> anyhow::Result<()> {
+    let chain_description = dummy_chain_description(0);
+    let chain_id = chain_description.id();
+    let state = SystemExecutionState {
+        description: Some(chain_description),
+        ..Default::default()
+    };
+    let mut view = state
+        .into_view_with(chain_id, ExecutionRuntimeConfig::default())
+        .await;
+    let (app_desc, contract_blob, service_blob) = create_dummy_user_application_description(1);
+    let app_id = From::from(&app_desc);
+    let app_desc_blob_id = Blob::new_application_description(&app_desc).id();
+    let contract_blob_id = contract_blob.id();
+    let service_blob_id = service_blob.id();
+
+    let profile = FuelProfile::new();
+    let contract = WasmContractModule::from_wasmtime_with_fuel_profile(
+        Bytecode::load_from_file("tests/fixtures/counter_contract.wasm")?,
+        profile.clone(),
+    )
+    .await?;
+    {
+        let pinned = view.context().extra().user_contracts().pin();
+        pinned.insert(app_id, contract.into());
+    }
+
+    view.context()
+        .extra()
+        .add_blobs([
+            contract_blob,
+            service_blob,
+            Blob::new_application_description(&app_desc),
+        ])
+        .await?;
+
+    let context = OperationContext {
+        chain_id,
+        height: BlockHeight(0),
+        round: Some(0),
+        authenticated_signer: None,
+        timestamp: Default::default(),
+    };
+    let policy = ResourceControlPolicy {
+        wasm_fuel_unit: Amount::from_attos(1),
+        ..ResourceControlPolicy::default()
+    };
+    *view.system.balance.get_mut() = Amount::from_tokens(1);
+    let mut controller =
+        ResourceController::new(Arc::new(policy), ResourceTracker::default(), None);
+    let mut txn_tracker = TransactionTracker::new_replaying_blobs(vec![
+        app_desc_blob_id,
+        contract_blob_id,
+        service_blob_id,
+    ]);
+    ExecutionStateActor::new(&mut view, &mut txn_tracker, &mut controller)
+        .execute_operation(
+            context,
+            Operation::user_without_abi(app_id, &2_u64).unwrap(),
+        )
+        .await?;
+
+    assert_eq!(profile.total_fuel(), controller.tracker.wasm_fuel);
+    let folded = profile.to_folded();
+    assert!(!folded.is_empty());
+    for line in folded.lines() {
+        let (stack, fuel) = line.rsplit_once(' ').unwrap();
+        assert!(!stack.is_empty());
+        fuel.parse::<u64>()?;
+    }
+    Ok(())
+}
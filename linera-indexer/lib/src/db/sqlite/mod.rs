@@ -297,8 +297,16 @@ impl SqliteDatabase {
                     SystemOperation::ProcessNewEpoch(_) => "ProcessNewEpoch",
                     SystemOperation::ProcessRemovedEpoch(_) => "ProcessRemovedEpoch",
                     SystemOperation::UpdateStreams(_) => "UpdateStreams",
+                    SystemOperation::ExecuteScheduledOperation(_) => "ExecuteScheduledOperation",
+                    SystemOperation::DiscardScheduledOperation(_) => "DiscardScheduledOperation",
+                    SystemOperation::Approve { .. } => "Approve",
+                    SystemOperation::Revoke { .. } => "Revoke",
                     SystemOperation::ChangeOwnership { .. } => "ChangeOwnership",
                     SystemOperation::VerifyBlob { .. } => "VerifyBlob",
+                    SystemOperation::UpgradeApplication { .. } => "UpgradeApplication",
+                    SystemOperation::DeleteApplication { .. } => "DeleteApplication",
+                    SystemOperation::TransferToken { .. } => "TransferToken",
+                    SystemOperation::ClaimToken { .. } => "ClaimToken",
                 };
                 ("System", None, Some(sys_op_type))
             }
@@ -1045,6 +1053,32 @@ impl SqliteDatabase {
                         Some(owner.to_string()),
                         Some(recipient.to_string()),
                     ),
+                    SystemMessage::CreditToken {
+                        target,
+                        amount,
+                        source,
+                        ..
+                    } => (
+                        "CreditToken",
+                        Some(target.to_string()),
+                        Some(*amount),
+                        Some(source.to_string()),
+                        None,
+                        None,
+                    ),
+                    SystemMessage::WithdrawToken {
+                        owner,
+                        amount,
+                        recipient,
+                        ..
+                    } => (
+                        "WithdrawToken",
+                        None,
+                        Some(*amount),
+                        None,
+                        Some(owner.to_string()),
+                        Some(recipient.to_string()),
+                    ),
                 };
 
                 MessageClassification {
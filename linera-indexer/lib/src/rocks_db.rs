@@ -68,6 +68,7 @@ impl RocksDbRunner {
             spawn_mode,
             path_with_guard,
             max_stream_queries: config.client.max_stream_queries,
+            secondary: false,
         };
         let store_config = RocksDbStoreConfig {
             inner_config,
@@ -247,6 +247,11 @@ impl TryFrom<BlockProposal> for api::BlockProposal {
                 .original_proposal
                 .map(|cert| bincode::serialize(&cert))
                 .transpose()?,
+            approvals: block_proposal
+                .approvals
+                .into_iter()
+                .map(Into::into)
+                .collect(),
         })
     }
 }
@@ -267,6 +272,11 @@ impl TryFrom<api::BlockProposal> for BlockProposal {
                 .original_proposal
                 .map(|bytes| bincode::deserialize(&bytes))
                 .transpose()?,
+            approvals: block_proposal
+                .approvals
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
         })
     }
 }
@@ -357,8 +367,15 @@ impl TryFrom<api::LiteCertificate> for HandleLiteCertRequest<'_> {
         };
         let signatures = bincode::deserialize(&certificate.signatures)?;
         let round = bincode::deserialize(&certificate.round)?;
+        let aggregate_signature = certificate
+            .aggregate_signature
+            .map(|bytes| bincode::deserialize(&bytes))
+            .transpose()?;
         Ok(Self {
-            certificate: LiteCertificate::new(value, round, signatures),
+            certificate: LiteCertificate {
+                aggregate_signature,
+                ..LiteCertificate::new(value, round, signatures)
+            },
             wait_for_outgoing_messages: certificate.wait_for_outgoing_messages,
         })
     }
@@ -375,6 +392,12 @@ impl TryFrom<HandleLiteCertRequest<'_>> for api::LiteCertificate {
             signatures: bincode::serialize(&request.certificate.signatures)?,
             wait_for_outgoing_messages: request.wait_for_outgoing_messages,
             kind: request.certificate.value.kind as i32,
+            aggregate_signature: request
+                .certificate
+                .aggregate_signature
+                .as_ref()
+                .map(bincode::serialize)
+                .transpose()?,
         })
     }
 }
@@ -488,11 +511,20 @@ impl TryFrom<api::Certificate> for TimeoutCertificate {
     fn try_from(certificate: api::Certificate) -> Result<Self, Self::Error> {
         let round = bincode::deserialize(&certificate.round)?;
         let signatures = bincode::deserialize(&certificate.signatures)?;
+        let aggregate_signature = certificate
+            .aggregate_signature
+            .map(|bytes| bincode::deserialize(&bytes))
+            .transpose()?;
         let cert_type = certificate.kind;
 
         if cert_type == api::CertificateKind::Timeout as i32 {
             let value: Timeout = bincode::deserialize(&certificate.value)?;
-            Ok(TimeoutCertificate::new(value, round, signatures))
+            Ok(TimeoutCertificate::from_parts(
+                value,
+                round,
+                signatures,
+                aggregate_signature,
+            ))
         } else {
             Err(GrpcProtoConversionError::InvalidCertificateType)
         }
@@ -505,11 +537,20 @@ impl TryFrom<api::Certificate> for ValidatedBlockCertificate {
     fn try_from(certificate: api::Certificate) -> Result<Self, Self::Error> {
         let round = bincode::deserialize(&certificate.round)?;
         let signatures = bincode::deserialize(&certificate.signatures)?;
+        let aggregate_signature = certificate
+            .aggregate_signature
+            .map(|bytes| bincode::deserialize(&bytes))
+            .transpose()?;
         let cert_type = certificate.kind;
 
         if cert_type == api::CertificateKind::Validated as i32 {
             let value: ValidatedBlock = bincode::deserialize(&certificate.value)?;
-            Ok(ValidatedBlockCertificate::new(value, round, signatures))
+            Ok(ValidatedBlockCertificate::from_parts(
+                value,
+                round,
+                signatures,
+                aggregate_signature,
+            ))
         } else {
             Err(GrpcProtoConversionError::InvalidCertificateType)
         }
@@ -522,11 +563,20 @@ impl TryFrom<api::Certificate> for ConfirmedBlockCertificate {
     fn try_from(certificate: api::Certificate) -> Result<Self, Self::Error> {
         let round = bincode::deserialize(&certificate.round)?;
         let signatures = bincode::deserialize(&certificate.signatures)?;
+        let aggregate_signature = certificate
+            .aggregate_signature
+            .map(|bytes| bincode::deserialize(&bytes))
+            .transpose()?;
         let cert_type = certificate.kind;
 
         if cert_type == api::CertificateKind::Confirmed as i32 {
             let value: ConfirmedBlock = bincode::deserialize(&certificate.value)?;
-            Ok(ConfirmedBlockCertificate::new(value, round, signatures))
+            Ok(ConfirmedBlockCertificate::from_parts(
+                value,
+                round,
+                signatures,
+                aggregate_signature,
+            ))
         } else {
             Err(GrpcProtoConversionError::InvalidCertificateType)
         }
@@ -539,6 +589,10 @@ impl TryFrom<TimeoutCertificate> for api::Certificate {
     fn try_from(certificate: TimeoutCertificate) -> Result<Self, Self::Error> {
         let round = bincode::serialize(&certificate.round)?;
         let signatures = bincode::serialize(certificate.signatures())?;
+        let aggregate_signature = certificate
+            .aggregate_signature()
+            .map(bincode::serialize)
+            .transpose()?;
 
         let value = bincode::serialize(certificate.value())?;
 
@@ -547,6 +601,7 @@ impl TryFrom<TimeoutCertificate> for api::Certificate {
             round,
             signatures,
             kind: api::CertificateKind::Timeout as i32,
+            aggregate_signature,
         })
     }
 }
@@ -557,6 +612,10 @@ impl TryFrom<ConfirmedBlockCertificate> for api::Certificate {
     fn try_from(certificate: ConfirmedBlockCertificate) -> Result<Self, Self::Error> {
         let round = bincode::serialize(&certificate.round)?;
         let signatures = bincode::serialize(certificate.signatures())?;
+        let aggregate_signature = certificate
+            .aggregate_signature()
+            .map(bincode::serialize)
+            .transpose()?;
 
         let value = bincode::serialize(certificate.value())?;
 
@@ -565,6 +624,7 @@ impl TryFrom<ConfirmedBlockCertificate> for api::Certificate {
             round,
             signatures,
             kind: api::CertificateKind::Confirmed as i32,
+            aggregate_signature,
         })
     }
 }
@@ -575,6 +635,10 @@ impl TryFrom<ValidatedBlockCertificate> for api::Certificate {
     fn try_from(certificate: ValidatedBlockCertificate) -> Result<Self, Self::Error> {
         let round = bincode::serialize(&certificate.round)?;
         let signatures = bincode::serialize(certificate.signatures())?;
+        let aggregate_signature = certificate
+            .aggregate_signature()
+            .map(bincode::serialize)
+            .transpose()?;
 
         let value = bincode::serialize(certificate.value())?;
 
@@ -583,6 +647,7 @@ impl TryFrom<ValidatedBlockCertificate> for api::Certificate {
             round,
             signatures,
             kind: api::CertificateKind::Validated as i32,
+            aggregate_signature,
         })
     }
 }
@@ -941,6 +1006,10 @@ impl TryFrom<Certificate> for api::Certificate {
     fn try_from(certificate: Certificate) -> Result<Self, Self::Error> {
         let round = bincode::serialize(&certificate.round())?;
         let signatures = bincode::serialize(certificate.signatures())?;
+        let aggregate_signature = certificate
+            .aggregate_signature()
+            .map(bincode::serialize)
+            .transpose()?;
 
         let (kind, value) = match certificate {
             Certificate::Confirmed(confirmed) => (
@@ -962,6 +1031,7 @@ impl TryFrom<Certificate> for api::Certificate {
             round,
             signatures,
             kind: kind as i32,
+            aggregate_signature,
         })
     }
 }
@@ -972,16 +1042,35 @@ impl TryFrom<api::Certificate> for Certificate {
     fn try_from(certificate: api::Certificate) -> Result<Self, Self::Error> {
         let round = bincode::deserialize(&certificate.round)?;
         let signatures = bincode::deserialize(&certificate.signatures)?;
+        let aggregate_signature = certificate
+            .aggregate_signature
+            .map(|bytes| bincode::deserialize(&bytes))
+            .transpose()?;
 
         let value = if certificate.kind == api::CertificateKind::Confirmed as i32 {
             let value: ConfirmedBlock = bincode::deserialize(&certificate.value)?;
-            Certificate::Confirmed(ConfirmedBlockCertificate::new(value, round, signatures))
+            Certificate::Confirmed(ConfirmedBlockCertificate::from_parts(
+                value,
+                round,
+                signatures,
+                aggregate_signature,
+            ))
         } else if certificate.kind == api::CertificateKind::Validated as i32 {
             let value: ValidatedBlock = bincode::deserialize(&certificate.value)?;
-            Certificate::Validated(ValidatedBlockCertificate::new(value, round, signatures))
+            Certificate::Validated(ValidatedBlockCertificate::from_parts(
+                value,
+                round,
+                signatures,
+                aggregate_signature,
+            ))
         } else if certificate.kind == api::CertificateKind::Timeout as i32 {
             let value: Timeout = bincode::deserialize(&certificate.value)?;
-            Certificate::Timeout(TimeoutCertificate::new(value, round, signatures))
+            Certificate::Timeout(TimeoutCertificate::from_parts(
+                value,
+                round,
+                signatures,
+                aggregate_signature,
+            ))
         } else {
             return Err(GrpcProtoConversionError::InvalidCertificateType);
         };
@@ -1040,11 +1129,14 @@ pub mod tests {
     use std::{borrow::Cow, fmt::Debug};
 
     use linera_base::{
-        crypto::{AccountSecretKey, BcsSignable, CryptoHash, Secp256k1SecretKey, ValidatorKeypair},
+        crypto::{
+            AccountSecretKey, BcsSignable, Bls12381SecretKey, Bls12381Signature, CryptoHash,
+            Secp256k1SecretKey, ValidatorKeypair,
+        },
         data_types::{Amount, Blob, Epoch, Round, Timestamp},
     };
     use linera_chain::{
-        data_types::{BlockExecutionOutcome, OriginalProposal, ProposedBlock},
+        data_types::{AggregateSignature, BlockExecutionOutcome, OriginalProposal, ProposedBlock},
         test::make_first_block,
         types::CertificateKind,
     };
@@ -1215,6 +1307,7 @@ pub mod tests {
                 key_pair.public_key,
                 ValidatorSignature::new(&Foo("test".into()), &key_pair.secret_key),
             )]),
+            aggregate_signature: None,
         };
         let request = HandleLiteCertRequest {
             certificate,
@@ -1222,6 +1315,26 @@ pub mod tests {
         };
 
         round_trip_check::<_, api::LiteCertificate>(request);
+
+        let aggregate_signature = AggregateSignature {
+            signers: vec![0b101],
+            signature: Bls12381Signature::new(&Foo("test".into()), &Bls12381SecretKey::generate()),
+        };
+        let certificate = LiteCertificate::new_aggregated(
+            LiteValue {
+                value_hash: CryptoHash::new(&Foo("value".into())),
+                chain_id: dummy_chain_id(0),
+                kind: CertificateKind::Confirmed,
+            },
+            Round::Fast,
+            aggregate_signature,
+        );
+        let request = HandleLiteCertRequest {
+            certificate,
+            wait_for_outgoing_messages: false,
+        };
+
+        round_trip_check::<_, api::LiteCertificate>(request);
     }
 
     #[test]
@@ -1244,6 +1357,21 @@ pub mod tests {
         let request = HandleValidatedCertificateRequest { certificate };
 
         round_trip_check::<_, api::HandleValidatedCertificateRequest>(request);
+
+        let certificate = ValidatedBlockCertificate::new_aggregated(
+            ValidatedBlock::new(BlockExecutionOutcome::default().with(get_block())),
+            Round::MultiLeader(3),
+            AggregateSignature {
+                signers: vec![0b11],
+                signature: Bls12381Signature::new(
+                    &Foo("test".into()),
+                    &Bls12381SecretKey::generate(),
+                ),
+            },
+        );
+        let request = HandleValidatedCertificateRequest { certificate };
+
+        round_trip_check::<_, api::HandleValidatedCertificateRequest>(request);
     }
 
     #[test]
@@ -1292,6 +1420,7 @@ pub mod tests {
             },
             signature: key_pair.sign(&Foo("test".into())),
             original_proposal: Some(OriginalProposal::Regular { certificate }),
+            approvals: vec![key_pair.sign(&Foo("approved".into()))],
         };
 
         round_trip_check::<_, api::BlockProposal>(block_proposal);
@@ -6,7 +6,7 @@ use linera_base::{
     crypto::{AccountPublicKey, AccountSignature, CryptoHash, TestString},
     data_types::{BlobContent, ChainDescription, ChainOrigin, OracleResponse, Round},
     identifiers::{Account, AccountOwner, BlobType, GenericApplicationId},
-    ownership::ChainOwnership,
+    ownership::{ChainOwnership, MultiLeaderAccess},
     vm::VmRuntime,
 };
 use linera_chain::{
@@ -51,6 +51,15 @@ fn get_registry() -> Result<Registry> {
             &evm_secret_key,
         );
         tracer.trace_value(&mut samples, &evm_signature)?;
+
+        // The same holds for BLS12-381 keys and signatures.
+        let bls_secret_key = linera_base::crypto::Bls12381SecretKey::generate();
+        tracer.trace_value(&mut samples, &bls_secret_key.public())?;
+        let bls_signature = linera_base::crypto::Bls12381Signature::new(
+            &TestString::new("signature".to_string()),
+            &bls_secret_key,
+        );
+        tracer.trace_value(&mut samples, &bls_signature)?;
     }
     // 2. Trace the main entry point(s) + every enum separately.
     tracer.trace_type::<AccountPublicKey>(&samples)?;
@@ -76,6 +85,7 @@ fn get_registry() -> Result<Registry> {
     tracer.trace_type::<ChainDescription>(&samples)?;
     tracer.trace_type::<ChainOrigin>(&samples)?;
     tracer.trace_type::<ChainOwnership>(&samples)?;
+    tracer.trace_type::<MultiLeaderAccess>(&samples)?;
     tracer.trace_type::<GenericApplicationId>(&samples)?;
     tracer.trace_type::<LockingBlock>(&samples)?;
     tracer.trace_type::<ChainManagerInfo>(&samples)?;
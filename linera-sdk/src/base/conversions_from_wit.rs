@@ -132,6 +132,7 @@ macro_rules! impl_from_wit {
                     multi_leader_rounds,
                     open_multi_leader_rounds,
                     timeout_config: timeout_config.into(),
+                    approval_threshold: None,
                 }
             }
         }
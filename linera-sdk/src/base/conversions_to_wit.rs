@@ -7,7 +7,7 @@ use linera_base::{
     crypto::CryptoHash,
     data_types::{BlockHeight, Timestamp},
     http,
-    identifiers::{AccountOwner, ApplicationId, ChainId, DataBlobHash},
+    identifiers::{AccountOwner, ApplicationId, ChainId, DataBlobHash, TokenId},
 };
 
 use crate::{
@@ -96,6 +96,15 @@ macro_rules! impl_to_wit {
             }
         }
 
+        impl From<TokenId> for $wit_base_api::TokenId {
+            fn from(token_id: TokenId) -> Self {
+                $wit_base_api::TokenId {
+                    issuer: token_id.issuer.into(),
+                    index: token_id.index,
+                }
+            }
+        }
+
         impl From<http::Request> for $wit_base_api::HttpRequest {
             fn from(request: http::Request) -> Self {
                 $wit_base_api::HttpRequest {
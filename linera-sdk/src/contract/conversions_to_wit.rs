@@ -7,9 +7,10 @@ use linera_base::{
     crypto::CryptoHash,
     data_types::{
         Amount, ApplicationPermissions, Bytecode, Resources, SendMessageRequest, TimeDelta,
+        Timestamp,
     },
     identifiers::{
-        Account, AccountOwner, ApplicationId, ChainId, DataBlobHash, ModuleId, StreamName,
+        Account, AccountOwner, ApplicationId, ChainId, DataBlobHash, ModuleId, StreamName, TokenId,
     },
     ownership::{ChainOwnership, TimeoutConfig},
     vm::VmRuntime,
@@ -172,6 +173,23 @@ impl From<TimeDelta> for wit_contract_api::TimeDelta {
     }
 }
 
+impl From<Timestamp> for wit_contract_api::Timestamp {
+    fn from(timestamp: Timestamp) -> Self {
+        Self {
+            inner0: timestamp.micros(),
+        }
+    }
+}
+
+impl From<TokenId> for wit_contract_api::TokenId {
+    fn from(token_id: TokenId) -> Self {
+        wit_contract_api::TokenId {
+            issuer: token_id.issuer.into(),
+            index: token_id.index,
+        }
+    }
+}
+
 impl From<TimeoutConfig> for wit_contract_api::TimeoutConfig {
     fn from(config: TimeoutConfig) -> Self {
         let TimeoutConfig {
@@ -224,6 +242,7 @@ impl From<ChainOwnership> for wit_contract_api::ChainOwnership {
             multi_leader_rounds,
             open_multi_leader_rounds,
             timeout_config,
+            approval_threshold: _,
         } = ownership;
         Self {
             super_owners: super_owners.into_iter().map(Into::into).collect(),
@@ -11,7 +11,7 @@ use linera_base::{
     },
     ensure, http,
     identifiers::{
-        Account, AccountOwner, ApplicationId, ChainId, DataBlobHash, ModuleId, StreamName,
+        Account, AccountOwner, ApplicationId, ChainId, DataBlobHash, ModuleId, StreamName, TokenId,
     },
     ownership::{
         AccountPermissionError, ChainOwnership, ChangeApplicationPermissionsError, CloseChainError,
@@ -130,6 +130,18 @@ where
         base_wit::read_owner_balance(owner.into()).into()
     }
 
+    /// Returns the balance of a token issued by an application in one of the accounts on
+    /// this chain.
+    pub fn owner_token_balance(&mut self, owner: AccountOwner, token_id: TokenId) -> Amount {
+        base_wit::read_owner_token_balance(owner.into(), token_id.into()).into()
+    }
+
+    /// Returns the amount of native tokens that `spender` can currently transfer out of the
+    /// `owner`'s account on this chain.
+    pub fn allowance(&mut self, owner: AccountOwner, spender: AccountOwner) -> Amount {
+        base_wit::read_allowance(owner.into(), spender.into()).into()
+    }
+
     /// Retrieves the owner configuration for the current chain.
     pub fn chain_ownership(&mut self) -> ChainOwnership {
         base_wit::get_chain_ownership().into()
@@ -234,6 +246,90 @@ where
         contract_wit::claim(source.into(), destination.into(), amount.into())
     }
 
+    /// Transfers an `amount` of a token issued by an application from `source` owner account
+    /// (or the current chain's balance) to `destination`.
+    pub fn transfer_token(
+        &mut self,
+        source: AccountOwner,
+        destination: Account,
+        amount: Amount,
+        token_id: TokenId,
+    ) {
+        contract_wit::transfer_token(
+            source.into(),
+            destination.into(),
+            amount.into(),
+            token_id.into(),
+        )
+    }
+
+    /// Claims an `amount` of a token issued by an application from a `source` account to a
+    /// `destination` account.
+    pub fn claim_token(
+        &mut self,
+        source: Account,
+        destination: Account,
+        amount: Amount,
+        token_id: TokenId,
+    ) {
+        contract_wit::claim_token(
+            source.into(),
+            destination.into(),
+            amount.into(),
+            token_id.into(),
+        )
+    }
+
+    /// Mints an `amount` of a token issued by this application into the `recipient` account
+    /// on this chain.
+    pub fn mint_token(&mut self, token_id: TokenId, recipient: AccountOwner, amount: Amount) {
+        contract_wit::mint_token(token_id.into(), recipient.into(), amount.into())
+    }
+
+    /// Burns an `amount` of a token issued by this application from the `owner` account on
+    /// this chain. The owner must have authorized the current execution, as for transfers.
+    pub fn burn_token(&mut self, token_id: TokenId, owner: AccountOwner, amount: Amount) {
+        contract_wit::burn_token(token_id.into(), owner.into(), amount.into())
+    }
+
+    /// Schedules an `operation` of this application to be executed automatically in the first
+    /// block of this chain with a timestamp no earlier than `timestamp`.
+    ///
+    /// The `budget` for the execution fees is debited from this application's account right
+    /// away, and whatever is not spent is credited back after execution. The scheduled
+    /// operation is executed without an authenticated signer.
+    pub fn schedule_operation_at(
+        &mut self,
+        timestamp: Timestamp,
+        operation: Application::Operation,
+        budget: Amount,
+    ) {
+        let bytes = <Application as ContractAbi>::serialize_operation(&operation)
+            .expect("Failed to serialize scheduled `Operation`");
+        contract_wit::schedule_operation_at(timestamp.into(), &bytes, budget.into())
+    }
+
+    /// Makes this application pay the execution fees of the operations targeting it, so that
+    /// block signers don't need any tokens to use it.
+    ///
+    /// The fees are debited from this application's account, up to `block_budget` per block
+    /// and up to `owner_budget` in total for each block signer. Zero budgets end the
+    /// sponsorship.
+    pub fn sponsor_fees(&mut self, block_budget: Amount, owner_budget: Amount) {
+        contract_wit::sponsor_fees(block_budget.into(), owner_budget.into())
+    }
+
+    /// Deletes all the storage of this application on the current chain, and credits the
+    /// refund for the released bytes to `refund_recipient`, as set by the resource control
+    /// policy. The refund never exceeds the fees this application paid for writing to its
+    /// storage on this chain.
+    ///
+    /// The application can no longer be called on this chain afterwards, and anything it
+    /// writes to its storage until the end of the transaction is discarded.
+    pub fn self_destruct(&mut self, refund_recipient: AccountOwner) {
+        contract_wit::self_destruct(refund_recipient.into())
+    }
+
     /// Calls another application.
     // ANCHOR: call_application
     pub fn call_application<A: ContractAbi + Send>(
@@ -17,6 +17,7 @@ use linera_base::{
     ensure, http,
     identifiers::{
         Account, AccountOwner, ApplicationId, BlobId, ChainId, DataBlobHash, ModuleId, StreamName,
+        TokenId,
     },
     ownership::{
         AccountPermissionError, ChainOwnership, ChangeApplicationPermissionsError, CloseChainError,
@@ -65,15 +66,21 @@ where
     timestamp: Option<Timestamp>,
     chain_balance: Option<Amount>,
     owner_balances: Option<HashMap<AccountOwner, Amount>>,
+    token_balances: HashMap<(AccountOwner, TokenId), Amount>,
+    allowances: HashMap<(AccountOwner, AccountOwner), Amount>,
     chain_ownership: Option<ChainOwnership>,
     can_close_chain: Option<bool>,
     can_change_application_permissions: Option<bool>,
     call_application_handler: Option<CallApplicationHandler>,
     send_message_requests: Arc<Mutex<Vec<SendMessageRequest<Application::Message>>>>,
     outgoing_transfers: HashMap<Account, Amount>,
+    outgoing_token_transfers: HashMap<(Account, TokenId), Amount>,
     created_events: BTreeMap<StreamName, Vec<Vec<u8>>>,
     events: BTreeMap<(ChainId, StreamName, u32), Vec<u8>>,
     claim_requests: Vec<ClaimRequest>,
+    scheduled_operations: Vec<(Timestamp, Application::Operation, Amount)>,
+    fee_sponsorship: Option<(Amount, Amount)>,
+    self_destruct_refund_recipient: Option<AccountOwner>,
     expected_service_queries: VecDeque<(ApplicationId, String, String)>,
     expected_http_requests: VecDeque<(http::Request, http::Response)>,
     expected_read_data_blob_requests: VecDeque<(DataBlobHash, Vec<u8>)>,
@@ -114,15 +121,21 @@ where
             timestamp: None,
             chain_balance: None,
             owner_balances: None,
+            token_balances: HashMap::new(),
+            allowances: HashMap::new(),
             chain_ownership: None,
             can_close_chain: None,
             can_change_application_permissions: None,
             call_application_handler: None,
             send_message_requests: Arc::default(),
             outgoing_transfers: HashMap::new(),
+            outgoing_token_transfers: HashMap::new(),
             created_events: BTreeMap::new(),
             events: BTreeMap::new(),
             claim_requests: Vec::new(),
+            scheduled_operations: Vec::new(),
+            fee_sponsorship: None,
+            self_destruct_refund_recipient: None,
             expected_service_queries: VecDeque::new(),
             expected_http_requests: VecDeque::new(),
             expected_read_data_blob_requests: VecDeque::new(),
@@ -561,14 +574,226 @@ where
             source,
             amount,
             destination,
+            token_id: None,
         });
     }
 
+    /// Configures the balance of a token in one account on the chain to use during the test.
+    pub fn with_owner_token_balance(
+        mut self,
+        owner: AccountOwner,
+        token_id: TokenId,
+        balance: Amount,
+    ) -> Self {
+        self.set_owner_token_balance(owner, token_id, balance);
+        self
+    }
+
+    /// Configures the balance of a token in one account on the chain to use during the test.
+    pub fn set_owner_token_balance(
+        &mut self,
+        owner: AccountOwner,
+        token_id: TokenId,
+        balance: Amount,
+    ) -> &mut Self {
+        self.token_balances.insert((owner, token_id), balance);
+        self
+    }
+
+    /// Returns the balance of a token in one of the accounts on this chain. Balances that
+    /// were not configured are zero.
+    pub fn owner_token_balance(&mut self, owner: AccountOwner, token_id: TokenId) -> Amount {
+        self.token_balances
+            .get(&(owner, token_id))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Configures the amount that `spender` can transfer out of the `owner`'s account during
+    /// the test.
+    pub fn with_allowance(
+        mut self,
+        owner: AccountOwner,
+        spender: AccountOwner,
+        allowance: Amount,
+    ) -> Self {
+        self.set_allowance(owner, spender, allowance);
+        self
+    }
+
+    /// Configures the amount that `spender` can transfer out of the `owner`'s account during
+    /// the test.
+    pub fn set_allowance(
+        &mut self,
+        owner: AccountOwner,
+        spender: AccountOwner,
+        allowance: Amount,
+    ) -> &mut Self {
+        self.allowances.insert((owner, spender), allowance);
+        self
+    }
+
+    /// Returns the amount that `spender` can transfer out of the `owner`'s account.
+    /// Allowances that were not configured are zero.
+    pub fn allowance(&mut self, owner: AccountOwner, spender: AccountOwner) -> Amount {
+        self.allowances
+            .get(&(owner, spender))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Transfers an `amount` of a token from `source` owner account (or the current chain's
+    /// balance) to `destination`.
+    pub fn transfer_token(
+        &mut self,
+        source: AccountOwner,
+        destination: Account,
+        amount: Amount,
+        token_id: TokenId,
+    ) {
+        self.debit_token(source, token_id, amount);
+
+        if Some(destination.chain_id) == self.chain_id {
+            self.credit_token(destination.owner, token_id, amount);
+        } else {
+            let destination_entry = self
+                .outgoing_token_transfers
+                .entry((destination, token_id))
+                .or_default();
+            *destination_entry = destination_entry
+                .try_add(amount)
+                .expect("Outgoing transfer value overflow");
+        }
+    }
+
+    /// Claims an `amount` of a token from a `source` account to a `destination` account.
+    pub fn claim_token(
+        &mut self,
+        source: Account,
+        destination: Account,
+        amount: Amount,
+        token_id: TokenId,
+    ) {
+        if Some(source.chain_id) == self.chain_id {
+            self.debit_token(source.owner, token_id, amount);
+
+            if Some(destination.chain_id) == self.chain_id {
+                self.credit_token(destination.owner, token_id, amount);
+            }
+        }
+
+        self.claim_requests.push(ClaimRequest {
+            source,
+            amount,
+            destination,
+            token_id: Some(token_id),
+        });
+    }
+
+    /// Mints an `amount` of a token issued by this application into the `recipient` account.
+    pub fn mint_token(&mut self, token_id: TokenId, recipient: AccountOwner, amount: Amount) {
+        assert_eq!(
+            token_id.issuer,
+            self.application_id().forget_abi(),
+            "Only the issuing application can mint a token"
+        );
+        self.credit_token(recipient, token_id, amount);
+    }
+
+    /// Burns an `amount` of a token issued by this application from the `owner` account.
+    pub fn burn_token(&mut self, token_id: TokenId, owner: AccountOwner, amount: Amount) {
+        assert_eq!(
+            token_id.issuer,
+            self.application_id().forget_abi(),
+            "Only the issuing application can burn a token"
+        );
+        self.debit_token(owner, token_id, amount);
+    }
+
+    /// Debits an `amount` of a token from a `source` owner account (or the current chain's
+    /// balance).
+    fn debit_token(&mut self, source: AccountOwner, token_id: TokenId, amount: Amount) {
+        let source_balance = self.token_balances.entry((source, token_id)).or_default();
+
+        *source_balance = source_balance
+            .try_sub(amount)
+            .expect("Insufficient funds in source account");
+    }
+
+    /// Credits an `amount` of a token into a `destination` owner account (or the current
+    /// chain's balance).
+    fn credit_token(&mut self, destination: AccountOwner, token_id: TokenId, amount: Amount) {
+        let destination_balance = self
+            .token_balances
+            .entry((destination, token_id))
+            .or_default();
+
+        *destination_balance = destination_balance
+            .try_add(amount)
+            .expect("Account balance overflow");
+    }
+
+    /// Returns the outgoing transfers of tokens scheduled during the test so far.
+    pub fn outgoing_token_transfers(&self) -> &HashMap<(Account, TokenId), Amount> {
+        &self.outgoing_token_transfers
+    }
+
     /// Returns the list of claims made during the test so far.
     pub fn claim_requests(&self) -> &[ClaimRequest] {
         &self.claim_requests
     }
 
+    /// Schedules an `operation` of this application to be executed automatically in the first
+    /// block of this chain with a timestamp no earlier than `timestamp`, prepaying `budget`
+    /// from this application's account.
+    pub fn schedule_operation_at(
+        &mut self,
+        timestamp: Timestamp,
+        operation: Application::Operation,
+        budget: Amount,
+    ) {
+        if budget > Amount::ZERO {
+            let application_id = self.application_id().forget_abi();
+            self.debit(AccountOwner::from(application_id), budget);
+        }
+        self.scheduled_operations
+            .push((timestamp, operation, budget));
+    }
+
+    /// Returns the operations scheduled during the test so far, with their due timestamps
+    /// and prepaid budgets.
+    pub fn scheduled_operations(&self) -> &[(Timestamp, Application::Operation, Amount)] {
+        &self.scheduled_operations
+    }
+
+    /// Makes this application pay the execution fees of the operations targeting it, up to
+    /// `block_budget` per block and `owner_budget` in total for each block signer. Zero
+    /// budgets end the sponsorship.
+    pub fn sponsor_fees(&mut self, block_budget: Amount, owner_budget: Amount) {
+        self.fee_sponsorship = (!block_budget.is_zero() && !owner_budget.is_zero())
+            .then_some((block_budget, owner_budget));
+    }
+
+    /// Returns the per-block and per-owner budgets with which this application currently
+    /// sponsors fees, if it does.
+    pub fn fee_sponsorship(&self) -> Option<(Amount, Amount)> {
+        self.fee_sponsorship
+    }
+
+    /// Deletes the storage of this application on the current chain, crediting the refund
+    /// to `refund_recipient`.
+    ///
+    /// The mock storage is left untouched, so that tests can still inspect it.
+    pub fn self_destruct(&mut self, refund_recipient: AccountOwner) {
+        self.self_destruct_refund_recipient = Some(refund_recipient);
+    }
+
+    /// Returns the recipient of the storage refund if the application self-destructed
+    /// during the test.
+    pub fn self_destruct_refund_recipient(&self) -> Option<AccountOwner> {
+        self.self_destruct_refund_recipient
+    }
+
     /// Configures the chain ownership configuration to return during the test.
     pub fn with_chain_ownership(mut self, chain_ownership: ChainOwnership) -> Self {
         self.chain_ownership = Some(chain_ownership);
@@ -1080,4 +1305,5 @@ pub struct ClaimRequest {
     source: Account,
     destination: Account,
     amount: Amount,
+    token_id: Option<TokenId>,
 }
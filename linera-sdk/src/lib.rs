@@ -110,6 +110,12 @@ pub trait Contract: WithContractAbi + ContractAbi + Sized {
     /// Instantiates the application on the chain that created it.
     async fn instantiate(&mut self, argument: Self::InstantiationArgument);
 
+    /// Migrates the application's state after the application was upgraded to this module.
+    ///
+    /// This is called once on each chain where the application is upgraded, with the raw
+    /// migration argument of the upgrade operation.
+    async fn migrate(&mut self, _argument: Vec<u8>) {}
+
     /// Applies an operation from the current block.
     async fn execute_operation(&mut self, operation: Self::Operation) -> Self::Response;
 
@@ -9,7 +9,7 @@ use linera_base::{
     abi::ServiceAbi,
     data_types::{Amount, BlockHeight, Timestamp},
     http,
-    identifiers::{AccountOwner, ApplicationId, ChainId, DataBlobHash},
+    identifiers::{AccountOwner, ApplicationId, ChainId, DataBlobHash, TokenId},
 };
 use serde::Serialize;
 
@@ -119,6 +119,18 @@ where
         base_wit::read_owner_balance(owner.into()).into()
     }
 
+    /// Returns the balance of a token issued by an application in one of the accounts on
+    /// this chain.
+    pub fn owner_token_balance(&self, owner: AccountOwner, token_id: TokenId) -> Amount {
+        base_wit::read_owner_token_balance(owner.into(), token_id.into()).into()
+    }
+
+    /// Returns the amount of native tokens that `spender` can currently transfer out of the
+    /// `owner`'s account on this chain.
+    pub fn allowance(&self, owner: AccountOwner, spender: AccountOwner) -> Amount {
+        base_wit::read_allowance(owner.into(), spender.into()).into()
+    }
+
     /// Returns the balances of all accounts on the chain.
     pub fn owner_balances(&self) -> Vec<(AccountOwner, Amount)> {
         Self::fetch_value_through_cache(&self.owner_balances, || {
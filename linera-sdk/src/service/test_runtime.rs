@@ -13,7 +13,7 @@ use linera_base::{
     abi::ServiceAbi,
     data_types::{Amount, BlockHeight, Timestamp},
     hex, http,
-    identifiers::{AccountOwner, ApplicationId, ChainId, DataBlobHash},
+    identifiers::{AccountOwner, ApplicationId, ChainId, DataBlobHash, TokenId},
 };
 use serde::{de::DeserializeOwned, Serialize};
 
@@ -32,6 +32,8 @@ where
     timestamp: Mutex<Option<Timestamp>>,
     chain_balance: Mutex<Option<Amount>>,
     owner_balances: Mutex<Option<HashMap<AccountOwner, Amount>>>,
+    token_balances: Mutex<HashMap<(AccountOwner, TokenId), Amount>>,
+    allowances: Mutex<HashMap<(AccountOwner, AccountOwner), Amount>>,
     query_application_handler: Mutex<Option<QueryApplicationHandler>>,
     expected_http_requests: Mutex<VecDeque<(http::Request, http::Response)>>,
     blobs: Mutex<Option<HashMap<DataBlobHash, Vec<u8>>>>,
@@ -63,6 +65,8 @@ where
             timestamp: Mutex::new(None),
             chain_balance: Mutex::new(None),
             owner_balances: Mutex::new(None),
+            token_balances: Mutex::new(HashMap::new()),
+            allowances: Mutex::new(HashMap::new()),
             query_application_handler: Mutex::new(None),
             expected_http_requests: Mutex::new(VecDeque::new()),
             blobs: Mutex::new(None),
@@ -284,6 +288,80 @@ where
             })
     }
 
+    /// Configures the balance of a token in one account on the chain to use during the test.
+    pub fn with_owner_token_balance(
+        self,
+        owner: AccountOwner,
+        token_id: TokenId,
+        balance: Amount,
+    ) -> Self {
+        self.set_owner_token_balance(owner, token_id, balance);
+        self
+    }
+
+    /// Configures the balance of a token in one account on the chain to use during the test.
+    pub fn set_owner_token_balance(
+        &self,
+        owner: AccountOwner,
+        token_id: TokenId,
+        balance: Amount,
+    ) -> &Self {
+        self.token_balances
+            .lock()
+            .unwrap()
+            .insert((owner, token_id), balance);
+        self
+    }
+
+    /// Returns the balance of a token in one of the accounts on this chain. Balances that
+    /// were not configured are zero.
+    pub fn owner_token_balance(&self, owner: AccountOwner, token_id: TokenId) -> Amount {
+        self.token_balances
+            .lock()
+            .unwrap()
+            .get(&(owner, token_id))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Configures the amount that `spender` can transfer out of the `owner`'s account during
+    /// the test.
+    pub fn with_allowance(
+        self,
+        owner: AccountOwner,
+        spender: AccountOwner,
+        allowance: Amount,
+    ) -> Self {
+        self.set_allowance(owner, spender, allowance);
+        self
+    }
+
+    /// Configures the amount that `spender` can transfer out of the `owner`'s account during
+    /// the test.
+    pub fn set_allowance(
+        &self,
+        owner: AccountOwner,
+        spender: AccountOwner,
+        allowance: Amount,
+    ) -> &Self {
+        self.allowances
+            .lock()
+            .unwrap()
+            .insert((owner, spender), allowance);
+        self
+    }
+
+    /// Returns the amount that `spender` can transfer out of the `owner`'s account.
+    /// Allowances that were not configured are zero.
+    pub fn allowance(&self, owner: AccountOwner, spender: AccountOwner) -> Amount {
+        self.allowances
+            .lock()
+            .unwrap()
+            .get(&(owner, spender))
+            .copied()
+            .unwrap_or_default()
+    }
+
     /// Returns the balances of all accounts on the chain.
     pub fn owner_balances(&self) -> Vec<(AccountOwner, Amount)> {
         self.owner_balances
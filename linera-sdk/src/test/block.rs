@@ -117,6 +117,7 @@ impl BlockBuilder {
             multi_leader_rounds,
             open_multi_leader_rounds,
             timeout_config,
+            approval_threshold: None,
         })
     }
 
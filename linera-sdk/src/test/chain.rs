@@ -18,7 +18,7 @@ use linera_base::{
     crypto::{AccountPublicKey, AccountSecretKey},
     data_types::{
         Amount, ApplicationDescription, Blob, BlockHeight, Bytecode, ChainDescription,
-        CompressedBytecode, Epoch,
+        CompressedBytecode, Epoch, Timestamp,
     },
     identifiers::{AccountOwner, ApplicationId, ChainId, ModuleId},
     vm::VmRuntime,
@@ -362,6 +362,32 @@ impl ActiveChain {
         .await;
     }
 
+    /// Executes all operations scheduled on this chain that are due at `timestamp`.
+    ///
+    /// Adds a block with the given timestamp to this microchain that executes them.
+    pub async fn handle_scheduled_operations(&self, timestamp: Timestamp) {
+        let keys = self
+            .validator
+            .worker()
+            .chain_state_view(self.id())
+            .await
+            .expect("Failed to query chain state view")
+            .execution_state
+            .system
+            .due_scheduled_operations(timestamp)
+            .await
+            .expect("Failed to query chain's scheduled operations");
+        assert!(!keys.is_empty(), "No scheduled operations are due");
+
+        self.add_block(|block| {
+            block.with_timestamp(timestamp);
+            for key in keys {
+                block.with_system_operation(SystemOperation::ExecuteScheduledOperation(key));
+            }
+        })
+        .await;
+    }
+
     /// Publishes the module in the crate calling this method to this microchain.
     ///
     /// Searches the Cargo manifest for binaries that end with `contract` and `service`, builds
@@ -551,6 +577,7 @@ impl ActiveChain {
                     parameters: parameters.clone(),
                     instantiation_argument,
                     required_application_ids: required_application_ids.clone(),
+                    upgrade_authority: None,
                 });
             })
             .await;
@@ -15,7 +15,7 @@ use futures::FutureExt as _;
 use linera_views::{
     batch::Batch,
     memory::MemoryStore,
-    store::{ReadableKeyValueStore, WritableKeyValueStore},
+    store::{RangeQuery, ReadableKeyValueStore, WritableKeyValueStore},
 };
 
 /// A mock [`KeyValueStore`] implementation using a [`MemoryStore`].
@@ -176,6 +176,35 @@ impl MockKeyValueStore {
         self.find_key_values_promises.take(promise)
     }
 
+    /// Finds keys in the storage that start with `key_prefix` and lie within the bounds of
+    /// the `query`, returning a promise to retrieve the final value with [`find_keys_wait`].
+    pub(crate) fn find_keys_by_range_new(&self, key_prefix: &[u8], query: &RangeQuery) -> u32 {
+        self.find_keys_promises.register(
+            self.store
+                .find_keys_by_range(key_prefix, query)
+                .now_or_never()
+                .expect("Memory store should never wait for anything")
+                .expect("Memory store should never fail"),
+        )
+    }
+
+    /// Finds key-value pairs in the storage in which the key starts with `key_prefix` and
+    /// lies within the bounds of the `query`, returning a promise to retrieve the final
+    /// value with [`find_key_values_wait`].
+    pub(crate) fn find_key_values_by_range_new(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> u32 {
+        self.find_key_values_promises.register(
+            self.store
+                .find_key_values_by_range(key_prefix, query)
+                .now_or_never()
+                .expect("Memory store should never wait for anything")
+                .expect("Memory store should never fail"),
+        )
+    }
+
     /// Writes a `batch` of operations to storage.
     pub(crate) fn write_batch(&self, batch: Batch) {
         self.store
@@ -9,7 +9,7 @@ use std::sync::Arc;
 use linera_base::ensure;
 use linera_views::{
     batch::Batch,
-    store::{ReadableKeyValueStore, WithError, WritableKeyValueStore},
+    store::{Direction, RangeQuery, ReadableKeyValueStore, WithError, WritableKeyValueStore},
 };
 use thiserror::Error;
 
@@ -183,6 +183,34 @@ impl ReadableKeyValueStore for KeyValueStore {
         yield_once().await;
         Ok(self.wit_api.find_key_values_wait(promise))
     }
+
+    async fn find_keys_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<Vec<u8>>, KeyValueStoreError> {
+        ensure!(
+            key_prefix.len() <= Self::MAX_KEY_SIZE,
+            KeyValueStoreError::KeyTooLong
+        );
+        let promise = self.wit_api.find_keys_by_range_new(key_prefix, query);
+        yield_once().await;
+        Ok(self.wit_api.find_keys_wait(promise))
+    }
+
+    async fn find_key_values_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, KeyValueStoreError> {
+        ensure!(
+            key_prefix.len() <= Self::MAX_KEY_SIZE,
+            KeyValueStoreError::KeyTooLong
+        );
+        let promise = self.wit_api.find_key_values_by_range_new(key_prefix, query);
+        yield_once().await;
+        Ok(self.wit_api.find_key_values_wait(promise))
+    }
 }
 
 impl WritableKeyValueStore for KeyValueStore {
@@ -335,6 +363,42 @@ impl WitInterface {
         }
     }
 
+    /// Creates a promise for finding keys having a specified prefix in the key-value store,
+    /// within the bounds of the `query`
+    fn find_keys_by_range_new(&self, key_prefix: &[u8], query: &RangeQuery) -> u32 {
+        let (start, end) = (query.start.as_deref(), query.end.as_deref());
+        let (limit, reverse) = wit_range_limit_and_direction(query);
+        match self {
+            WitInterface::Contract => {
+                contract_wit::find_keys_by_range_new(key_prefix, start, end, limit, reverse)
+            }
+            WitInterface::Service => {
+                service_wit::find_keys_by_range_new(key_prefix, start, end, limit, reverse)
+            }
+            #[cfg(with_testing)]
+            WitInterface::Mock { store, .. } => store.find_keys_by_range_new(key_prefix, query),
+        }
+    }
+
+    /// Creates a promise for finding the key/values having a specified prefix in the
+    /// key-value store, within the bounds of the `query`
+    fn find_key_values_by_range_new(&self, key_prefix: &[u8], query: &RangeQuery) -> u32 {
+        let (start, end) = (query.start.as_deref(), query.end.as_deref());
+        let (limit, reverse) = wit_range_limit_and_direction(query);
+        match self {
+            WitInterface::Contract => {
+                contract_wit::find_key_values_by_range_new(key_prefix, start, end, limit, reverse)
+            }
+            WitInterface::Service => {
+                service_wit::find_key_values_by_range_new(key_prefix, start, end, limit, reverse)
+            }
+            #[cfg(with_testing)]
+            WitInterface::Mock { store, .. } => {
+                store.find_key_values_by_range_new(key_prefix, query)
+            }
+        }
+    }
+
     /// Calls the `write_batch` WIT function.
     fn write_batch(&self, batch: Batch) {
         match self {
@@ -369,6 +433,15 @@ impl WitInterface {
 /// by Linera applications.
 pub type ViewStorageContext = linera_views::context::ViewContext<(), KeyValueStore>;
 
+/// Returns the limit and the direction of a [`RangeQuery`] as expected by the WIT functions.
+/// Limits that do not fit in a `u32` are saturated.
+fn wit_range_limit_and_direction(query: &RangeQuery) -> (Option<u32>, bool) {
+    let limit = query
+        .limit
+        .map(|limit| u32::try_from(limit).unwrap_or(u32::MAX));
+    (limit, query.direction == Direction::Reverse)
+}
+
 #[cfg(all(test, not(target_arch = "wasm32")))]
 mod tests {
     use super::*;
@@ -5,7 +5,9 @@ use graphql_client::GraphQLQuery;
 use linera_base::{
     crypto::CryptoHash,
     data_types::{Amount, Blob, BlockHeight, ChainDescription, OracleResponse, Round, Timestamp},
-    identifiers::{Account, AccountOwner, BlobId, ChainId, GenericApplicationId, StreamName},
+    identifiers::{
+        Account, AccountOwner, BlobId, ChainId, GenericApplicationId, StreamName, TokenId,
+    },
 };
 use thiserror::Error;
 
@@ -149,7 +151,7 @@ mod from {
     };
     use linera_execution::{
         system::{AdminOperation, OpenChainConfig},
-        OutgoingMessage,
+        OutgoingMessage, ScheduledOperationKey,
     };
 
     use super::*;
@@ -174,6 +176,27 @@ mod from {
                     amount: transfer.amount,
                 })
             }
+            "TransferToken" => {
+                let transfer = system_op.transfer.ok_or_else(|| {
+                    ConversionError::UnexpectedCertificateType(
+                        "Missing transfer metadata for TransferToken operation".to_string(),
+                    )
+                })?;
+                let token_id = transfer.token_id.ok_or_else(|| {
+                    ConversionError::UnexpectedCertificateType(
+                        "Missing token ID for TransferToken operation".to_string(),
+                    )
+                })?;
+                Ok(SystemOperation::TransferToken {
+                    owner: transfer.owner,
+                    recipient: Account {
+                        chain_id: transfer.recipient.chain_id,
+                        owner: transfer.recipient.owner,
+                    },
+                    amount: transfer.amount,
+                    token_id,
+                })
+            }
             "Claim" => {
                 let claim = system_op.claim.ok_or_else(|| {
                     ConversionError::UnexpectedCertificateType(
@@ -190,6 +213,28 @@ mod from {
                     amount: claim.amount,
                 })
             }
+            "ClaimToken" => {
+                let claim = system_op.claim.ok_or_else(|| {
+                    ConversionError::UnexpectedCertificateType(
+                        "Missing claim metadata for ClaimToken operation".to_string(),
+                    )
+                })?;
+                let token_id = claim.token_id.ok_or_else(|| {
+                    ConversionError::UnexpectedCertificateType(
+                        "Missing token ID for ClaimToken operation".to_string(),
+                    )
+                })?;
+                Ok(SystemOperation::ClaimToken {
+                    owner: claim.owner,
+                    target_id: claim.target_id,
+                    recipient: Account {
+                        chain_id: claim.recipient.chain_id,
+                        owner: claim.recipient.owner,
+                    },
+                    amount: claim.amount,
+                    token_id,
+                })
+            }
             "OpenChain" => {
                 let open_chain = system_op.open_chain.ok_or_else(|| {
                     ConversionError::UnexpectedCertificateType(
@@ -289,6 +334,16 @@ mod from {
                     multi_leader_rounds: change_ownership.multi_leader_rounds as u32,
                     open_multi_leader_rounds: change_ownership.open_multi_leader_rounds,
                     timeout_config,
+                    approval_threshold: change_ownership
+                        .approval_threshold
+                        .map(|s| {
+                            s.parse::<u64>().map_err(|_| {
+                                ConversionError::UnexpectedCertificateType(
+                                    "Invalid approval_threshold value".to_string(),
+                                )
+                            })
+                        })
+                        .transpose()?,
                 })
             }
             "ChangeApplicationPermissions" => {
@@ -384,6 +439,7 @@ mod from {
                     parameters,
                     instantiation_argument,
                     required_application_ids,
+                    upgrade_authority: create_application.upgrade_authority,
                 })
             }
             "Admin" => {
@@ -475,6 +531,102 @@ mod from {
 
                 Ok(SystemOperation::UpdateStreams(streams))
             }
+            "ExecuteScheduledOperation" | "DiscardScheduledOperation" => {
+                let scheduled_operation = system_op.scheduled_operation.ok_or_else(|| {
+                    ConversionError::UnexpectedCertificateType(
+                        "Missing scheduled_operation metadata".to_string(),
+                    )
+                })?;
+                let index = scheduled_operation.index.parse().map_err(|_| {
+                    ConversionError::UnexpectedCertificateType("Invalid index format".to_string())
+                })?;
+                let key = ScheduledOperationKey {
+                    timestamp: scheduled_operation.timestamp,
+                    index,
+                };
+                if system_op.system_operation_type == "ExecuteScheduledOperation" {
+                    Ok(SystemOperation::ExecuteScheduledOperation(key))
+                } else {
+                    Ok(SystemOperation::DiscardScheduledOperation(key))
+                }
+            }
+            "Approve" | "Revoke" => {
+                let allowance = system_op.allowance.ok_or_else(|| {
+                    ConversionError::UnexpectedCertificateType(
+                        "Missing allowance metadata".to_string(),
+                    )
+                })?;
+                if system_op.system_operation_type == "Approve" {
+                    let amount = allowance.amount.ok_or_else(|| {
+                        ConversionError::UnexpectedCertificateType(
+                            "Missing amount in Approve operation".to_string(),
+                        )
+                    })?;
+                    Ok(SystemOperation::Approve {
+                        owner: allowance.owner,
+                        spender: allowance.spender,
+                        amount,
+                        expiry: allowance.expiry,
+                    })
+                } else {
+                    Ok(SystemOperation::Revoke {
+                        owner: allowance.owner,
+                        spender: allowance.spender,
+                    })
+                }
+            }
+            "UpgradeApplication" => {
+                let upgrade_application = system_op.upgrade_application.ok_or_else(|| {
+                    ConversionError::UnexpectedCertificateType(
+                        "Missing upgrade_application metadata".to_string(),
+                    )
+                })?;
+
+                let application_id: RealApplicationId =
+                    upgrade_application.application_id.parse().map_err(|_| {
+                        ConversionError::UnexpectedCertificateType(
+                            "Invalid application_id format".to_string(),
+                        )
+                    })?;
+
+                let module_id: ModuleId = upgrade_application.module_id.parse().map_err(|_| {
+                    ConversionError::UnexpectedCertificateType(
+                        "Invalid module_id format".to_string(),
+                    )
+                })?;
+
+                let migration_argument = hex::decode(upgrade_application.migration_argument_hex)
+                    .map_err(|_| {
+                        ConversionError::UnexpectedCertificateType(
+                            "Invalid hex in migration_argument_hex".to_string(),
+                        )
+                    })?;
+
+                Ok(SystemOperation::UpgradeApplication {
+                    application_id,
+                    module_id,
+                    migration_argument,
+                })
+            }
+            "DeleteApplication" => {
+                let delete_application = system_op.delete_application.ok_or_else(|| {
+                    ConversionError::UnexpectedCertificateType(
+                        "Missing delete_application metadata".to_string(),
+                    )
+                })?;
+
+                let application_id: RealApplicationId =
+                    delete_application.application_id.parse().map_err(|_| {
+                        ConversionError::UnexpectedCertificateType(
+                            "Invalid application_id format".to_string(),
+                        )
+                    })?;
+
+                Ok(SystemOperation::DeleteApplication {
+                    application_id,
+                    refund_recipient: delete_application.refund_recipient,
+                })
+            }
             _ => Err(ConversionError::UnexpectedCertificateType(format!(
                 "Unknown system operation type: {}",
                 system_op.system_operation_type
@@ -5,7 +5,10 @@ use std::{borrow::Cow, num::NonZeroU16, path::PathBuf};
 
 use chrono::{DateTime, Utc};
 use linera_base::{
-    crypto::{AccountPublicKey, CryptoHash, ValidatorPublicKey},
+    crypto::{
+        AccountPublicKey, Bls12381PublicKey, Bls12381Signature, CryptoHash, SignatureScheme,
+        ValidatorPublicKey,
+    },
     data_types::{Amount, BlockHeight, Epoch},
     identifiers::{Account, AccountOwner, ApplicationId, ChainId, ModuleId, StreamId},
     time::Duration,
@@ -162,8 +165,9 @@ impl BenchmarkCommand {
 
 #[cfg(feature = "kubernetes")]
 use crate::cli_wrappers::local_kubernetes_net::BuildMode;
-use crate::util::{
-    DEFAULT_PAUSE_AFTER_GQL_MUTATIONS_SECS, DEFAULT_PAUSE_AFTER_LINERA_SERVICE_SECS,
+use crate::{
+    storage::StorageConfig,
+    util::{DEFAULT_PAUSE_AFTER_GQL_MUTATIONS_SECS, DEFAULT_PAUSE_AFTER_LINERA_SERVICE_SECS},
 };
 
 #[derive(Clone, clap::Subcommand)]
@@ -299,6 +303,31 @@ pub enum ClientCommand {
         account: Option<Account>,
     },
 
+    /// Simulate the execution of one block made of the given operations, without proposing
+    /// it, then show the resources and fees it would use, and the messages and events it
+    /// would produce.
+    ///
+    /// NOTE: The estimate does not reflect messages that have not been synchronized from
+    /// validators yet. Call `linera sync` first to do so.
+    Estimate {
+        /// The chain to execute the operations on. If omitted, uses the default chain of the
+        /// wallet.
+        chain_id: Option<ChainId>,
+
+        /// The list of operations to execute, as a JSON string.
+        #[arg(long)]
+        json_operations: Option<String>,
+
+        /// Path to a JSON file containing the list of operations to execute.
+        #[arg(long)]
+        json_operations_path: Option<PathBuf>,
+
+        /// Write the fuel consumed by each function of the Wasm contracts to this file, in
+        /// the folded-stack format used by flamegraph tools. Requires Wasmtime.
+        #[arg(long)]
+        fuel_profile: Option<PathBuf>,
+    },
+
     /// (DEPRECATED) Synchronize the local state of the chain with a quorum validators, then query the
     /// local balance.
     ///
@@ -375,6 +404,14 @@ pub enum ClientCommand {
         #[arg(long, default_value = "1")]
         votes: u64,
 
+        /// The BLS12-381 public key of the validator, used for aggregate certificate signatures.
+        #[arg(long, requires = "aggregate_key_proof")]
+        aggregate_key: Option<Bls12381PublicKey>,
+
+        /// The proof of possession of the secret key for `--aggregate-key`.
+        #[arg(long, requires = "aggregate_key")]
+        aggregate_key_proof: Option<Bls12381Signature>,
+
         /// Skip the version and genesis config checks.
         #[arg(long)]
         skip_online_check: bool,
@@ -440,6 +477,11 @@ pub enum ClientCommand {
         #[arg(long)]
         byte_stored: Option<Amount>,
 
+        /// Set the refund per stored byte released when the storage of an application is
+        /// deleted.
+        #[arg(long)]
+        byte_stored_refund: Option<Amount>,
+
         /// Set the base price of sending an operation from a block..
         #[arg(long)]
         operation: Option<Amount>,
@@ -520,6 +562,23 @@ pub enum ClientCommand {
         /// Set the list of hosts that contracts and services can send HTTP requests to.
         #[arg(long)]
         http_request_allow_list: Option<Vec<String>>,
+
+        /// Enable dynamic fees, and set the fuel per block at which prices stay constant.
+        #[arg(long)]
+        dynamic_fee_target_fuel_per_block: Option<u64>,
+
+        /// Enable dynamic fees, and set the bytes read and written per block at which prices
+        /// stay constant.
+        #[arg(long)]
+        dynamic_fee_target_bytes_per_block: Option<u64>,
+
+        /// Enable dynamic fees, and set the maximum factor by which prices can increase.
+        #[arg(long)]
+        dynamic_fee_maximum_multiplier: Option<u64>,
+
+        /// Disable dynamic fees. This takes precedence over the other dynamic fee options.
+        #[arg(long)]
+        disable_dynamic_fees: bool,
     },
 
     /// Run benchmarks to test network performance.
@@ -614,6 +673,12 @@ pub enum ClientCommand {
         #[arg(long)]
         byte_stored_price: Option<Amount>,
 
+        /// Set the refund per stored byte released when the storage of an application is
+        /// deleted.
+        /// (This will overwrite value from `--policy-config`)
+        #[arg(long)]
+        byte_stored_refund: Option<Amount>,
+
         /// Set the base price of sending an operation from a block..
         /// (This will overwrite value from `--policy-config`)
         #[arg(long)]
@@ -709,6 +774,22 @@ pub enum ClientCommand {
         #[arg(long)]
         http_request_allow_list: Option<Vec<String>>,
 
+        /// Enable dynamic fees, and set the fuel per block at which prices stay constant.
+        /// (This will overwrite value from `--policy-config`)
+        #[arg(long)]
+        dynamic_fee_target_fuel_per_block: Option<u64>,
+
+        /// Enable dynamic fees, and set the bytes read and written per block at which prices
+        /// stay constant.
+        /// (This will overwrite value from `--policy-config`)
+        #[arg(long)]
+        dynamic_fee_target_bytes_per_block: Option<u64>,
+
+        /// Enable dynamic fees, and set the maximum factor by which prices can increase.
+        /// (This will overwrite value from `--policy-config`)
+        #[arg(long)]
+        dynamic_fee_maximum_multiplier: Option<u64>,
+
         /// Force this wallet to generate keys using a PRNG and a given seed. USE FOR
         /// TESTING ONLY.
         #[arg(long)]
@@ -859,6 +940,11 @@ pub enum ClientCommand {
         /// The list of required dependencies of application, if any.
         #[arg(long, num_args(0..))]
         required_application_ids: Option<Vec<ApplicationId>>,
+
+        /// The owner allowed to upgrade the application to a new module. The application
+        /// cannot be upgraded otherwise.
+        #[arg(long)]
+        upgrade_authority: Option<AccountOwner>,
     },
 
     /// Create an application, and publish the required module.
@@ -896,6 +982,53 @@ pub enum ClientCommand {
         /// The list of required dependencies of application, if any.
         #[arg(long, num_args(0..))]
         required_application_ids: Option<Vec<ApplicationId>>,
+
+        /// The owner allowed to upgrade the application to a new module. The application
+        /// cannot be upgraded otherwise.
+        #[arg(long)]
+        upgrade_authority: Option<AccountOwner>,
+    },
+
+    /// Upgrade an application to a new module, keeping its storage. On the application's
+    /// creator chain, the signer must be the application's upgrade authority. Other chains
+    /// apply the upgrades announced by the creator chain, in the same order and with the same
+    /// migration argument.
+    UpgradeApplication {
+        /// The ID of the application to upgrade.
+        application_id: ApplicationId,
+
+        /// The module ID of the new version of the application.
+        module_id: ModuleId,
+
+        /// An optional chain ID to execute the upgrade on. The default chain of the wallet
+        /// is used otherwise.
+        chain_id: Option<ChainId>,
+
+        /// The argument passed to the migration entrypoint of the new module, as a JSON
+        /// string.
+        #[arg(long)]
+        json_argument: Option<String>,
+
+        /// Path to a JSON file containing the migration argument.
+        #[arg(long)]
+        json_argument_path: Option<PathBuf>,
+    },
+
+    /// Delete all the storage of an application on a chain. The application can no longer be
+    /// executed on that chain afterwards. The signer must be an owner of the chain or the
+    /// application's upgrade authority.
+    DeleteApplication {
+        /// The ID of the application to delete.
+        application_id: ApplicationId,
+
+        /// An optional chain ID to delete the application from. The default chain of the
+        /// wallet is used otherwise.
+        chain_id: Option<ChainId>,
+
+        /// The owner credited with the refund for the released storage, which never exceeds
+        /// the fees paid for writing it. The chain's balance is credited otherwise.
+        #[arg(long)]
+        refund_recipient: Option<AccountOwner>,
     },
 
     /// Create an unassigned key pair.
@@ -979,6 +1112,7 @@ impl ClientCommand {
             | ClientCommand::ShowNetworkDescription
             | ClientCommand::LocalBalance { .. }
             | ClientCommand::QueryBalance { .. }
+            | ClientCommand::Estimate { .. }
             | ClientCommand::SyncBalance { .. }
             | ClientCommand::Sync { .. }
             | ClientCommand::ProcessInbox { .. }
@@ -996,6 +1130,8 @@ impl ClientCommand {
             | ClientCommand::ReadDataBlob { .. }
             | ClientCommand::CreateApplication { .. }
             | ClientCommand::PublishAndCreate { .. }
+            | ClientCommand::UpgradeApplication { .. }
+            | ClientCommand::DeleteApplication { .. }
             | ClientCommand::Keygen
             | ClientCommand::Assign { .. }
             | ClientCommand::Wallet { .. }
@@ -1041,6 +1177,55 @@ pub enum DatabaseToolCommand {
 
     /// List the chain IDs in the database
     ListChainIds,
+
+    /// Take a snapshot of the namespace into a new namespace of the database
+    Snapshot {
+        /// The namespace receiving the snapshot.
+        #[arg(long)]
+        snapshot_namespace: String,
+    },
+
+    /// Export the namespace, including all its root keys, to a checksummed archive file
+    Export {
+        /// The path of the archive file to create.
+        #[arg(long)]
+        output: PathBuf,
+    },
+
+    /// Import an archive file created by `linera storage export` into a new namespace
+    Import {
+        /// The path of the archive file to import.
+        #[arg(long)]
+        input: PathBuf,
+    },
+
+    /// Migrate all the namespaces of a storage to another one, possibly of a different
+    /// backend. The entries are copied in verified batches, and an interrupted migration
+    /// resumes from the progress file. A RocksDB source is read through secondary
+    /// instances, so that a validator running on `dualwriterocksdbscylladb:`, the only
+    /// storage writing to both databases, can keep using it. Other sources must not be
+    /// written to during the migration
+    Migrate {
+        /// The storage to migrate from. Its namespace is ignored.
+        #[arg(long)]
+        from: StorageConfig,
+
+        /// The storage to migrate to. Its namespace is ignored.
+        #[arg(long)]
+        to: StorageConfig,
+
+        /// The file recording the progress of the migration.
+        #[arg(long, default_value = "migration_progress.json")]
+        progress_path: PathBuf,
+
+        /// The maximal number of entries copied in a batch.
+        #[arg(long, default_value = "1000")]
+        batch_size: usize,
+
+        /// The number of times a batch is copied before failing the migration.
+        #[arg(long, default_value = "3")]
+        max_attempts: usize,
+    },
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -1191,7 +1376,8 @@ pub enum WalletCommand {
     /// Change the wallet default chain.
     SetDefault { chain_id: ChainId },
 
-    /// Initialize a wallet from the genesis configuration.
+    /// Initialize a wallet from the genesis configuration. An existing keystore, e.g. one
+    /// made with `linera wallet create`, is used instead of creating a new one.
     Init {
         /// The path to the genesis configuration for a Linera deployment. Either this or `--faucet`
         /// must be specified.
@@ -1234,6 +1420,56 @@ pub enum WalletCommand {
 
     /// Forgets the specified chain, including the associated key pair.
     ForgetChain { chain_id: ChainId },
+
+    /// Create a keystore whose keys are derived from a new mnemonic, and print the
+    /// mnemonic. The keystore is encrypted if a keystore passphrase is set.
+    Create {
+        /// The signature scheme of the derived keys.
+        #[arg(long, value_enum, default_value = "ed25519")]
+        key_scheme: KeyScheme,
+    },
+
+    /// Check that the keystore passphrase decrypts the keystore, and list the owners of
+    /// its keys.
+    Unlock,
+
+    /// Print the mnemonic from which the keys of the keystore are derived.
+    ExportMnemonic,
+
+    /// Create a keystore whose keys are derived from an existing mnemonic. The keystore is
+    /// encrypted if a keystore passphrase is set. The mnemonic is read from the
+    /// `LINERA_MNEMONIC` environment variable, or prompted for.
+    Recover {
+        /// The signature scheme of the derived keys, i.e. the one they were created with.
+        #[arg(long, value_enum, default_value = "ed25519")]
+        key_scheme: KeyScheme,
+
+        /// The number of keys to derive, i.e. the number of keys generated with the
+        /// mnemonic so far.
+        #[arg(long, default_value = "1")]
+        num_keys: u32,
+    },
+}
+
+/// The signature scheme of the keys derived from a mnemonic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum KeyScheme {
+    /// Ed25519 keys.
+    Ed25519,
+    /// secp256k1 keys.
+    Secp256k1,
+    /// secp256k1 keys with Ethereum addresses.
+    EvmSecp256k1,
+}
+
+impl From<KeyScheme> for SignatureScheme {
+    fn from(scheme: KeyScheme) -> Self {
+        match scheme {
+            KeyScheme::Ed25519 => SignatureScheme::Ed25519,
+            KeyScheme::Secp256k1 => SignatureScheme::Secp256k1,
+            KeyScheme::EvmSecp256k1 => SignatureScheme::EvmSecp256k1,
+        }
+    }
 }
 
 #[derive(Clone, clap::Subcommand)]
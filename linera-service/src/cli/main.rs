@@ -27,7 +27,10 @@ pub static malloc_conf: &[u8] = b"prof:true,prof_active:true,lg_prof_sample:19\0
 use std::{
     collections::{BTreeMap, BTreeSet},
     env,
-    path::PathBuf,
+    fs::File,
+    io::{BufReader, BufWriter, Write as _},
+    marker::PhantomData,
+    path::{Path, PathBuf},
     process,
     sync::Arc,
 };
@@ -38,7 +41,7 @@ use chrono::Utc;
 use colored::Colorize;
 use futures::{lock::Mutex, FutureExt as _, StreamExt};
 use linera_base::{
-    crypto::{InMemorySigner, Signer},
+    crypto::{CryptoRng, InMemorySigner, Mnemonic, Signer},
     data_types::{ApplicationPermissions, Timestamp},
     identifiers::{AccountOwner, ChainId},
     listen_for_shutdown_signals,
@@ -49,8 +52,9 @@ use linera_client::{
     benchmark::BenchmarkConfig,
     chain_listener::{ChainListener, ChainListenerConfig, ClientContext as _},
     client_context::ClientContext,
-    client_options::ClientContextOptions,
+    client_options::{ClientContextOptions, KEYSTORE_PASSPHRASE_ENV},
     config::{CommitteeConfig, GenesisConfig},
+    keystore::{self, Keystore},
     wallet::{UserChain, Wallet},
 };
 use linera_core::{
@@ -59,7 +63,8 @@ use linera_core::{
 };
 use linera_execution::{
     committee::{Committee, ValidatorState},
-    WasmRuntime, WithWasmDefault as _,
+    DynamicFeePolicy, ExecutionRuntimeConfig, FuelProfile, Operation, WasmRuntime,
+    WithWasmDefault as _,
 };
 use linera_faucet_server::{FaucetConfig, FaucetService};
 #[cfg(with_metrics)]
@@ -76,11 +81,15 @@ use linera_service::{
     cli_wrappers::{self, local_net::PathProvider, ClientWrapper, Network, OnClientDrop},
     node_service::NodeService,
     project::{self, Project},
-    storage::{CommonStorageOptions, Runnable, RunnableWithStore, StorageConfig},
+    storage::{CommonStorageOptions, Runnable, RunnableWithStore, StorageConfig, StoreConfig},
     util, wallet,
 };
 use linera_storage::{DbStorage, Storage};
-use linera_views::store::{KeyValueDatabase, KeyValueStore};
+use linera_views::{
+    archive,
+    migration::{self, MigrationOptions, MigrationProgress},
+    store::{KeyValueDatabase, KeyValueStore},
+};
 use serde_json::Value;
 use tempfile::NamedTempFile;
 use tokio::{
@@ -108,6 +117,30 @@ fn read_json(string: Option<String>, path: Option<PathBuf>) -> anyhow::Result<Ve
     Ok(serde_json::to_vec(&value)?)
 }
 
+/// Returns the dynamic fee parameters of `policy` with the given values overwritten. Setting
+/// any of them enables dynamic fees, with the default parameters for the others.
+fn updated_dynamic_fees(
+    policy: &linera_execution::ResourceControlPolicy,
+    target_fuel_per_block: Option<u64>,
+    target_bytes_per_block: Option<u64>,
+    maximum_multiplier: Option<u64>,
+) -> Option<DynamicFeePolicy> {
+    if target_fuel_per_block.is_none()
+        && target_bytes_per_block.is_none()
+        && maximum_multiplier.is_none()
+    {
+        return policy.dynamic_fees;
+    }
+    let existing = policy
+        .dynamic_fees
+        .unwrap_or_else(|| policy.default_dynamic_fees());
+    Some(DynamicFeePolicy {
+        target_fuel_per_block: target_fuel_per_block.unwrap_or(existing.target_fuel_per_block),
+        target_bytes_per_block: target_bytes_per_block.unwrap_or(existing.target_bytes_per_block),
+        maximum_multiplier: maximum_multiplier.unwrap_or(existing.maximum_multiplier),
+    })
+}
+
 #[async_trait]
 impl Runnable for Job {
     type Output = anyhow::Result<()>;
@@ -133,7 +166,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 let chain_client = context.make_chain_client(sender.chain_id);
                 info!(
@@ -169,7 +202,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
                 let chain_client = context.make_chain_client(chain_id);
@@ -218,7 +251,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
                 let chain_client = context.make_chain_client(chain_id);
@@ -265,7 +298,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 let ownership = context.ownership(chain_id).await?;
                 let json = serde_json::to_string_pretty(&ownership)?;
@@ -286,7 +319,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 context.change_ownership(chain_id, ownership_config).await?
             }
@@ -296,7 +329,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 context.set_preferred_owner(chain_id, owner).await?
             }
@@ -309,7 +342,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
                 let chain_client = context.make_chain_client(chain_id);
@@ -342,7 +375,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 let chain_client = context.make_chain_client(chain_id);
                 info!("Closing chain {}", chain_id);
@@ -380,7 +413,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 let account = account.unwrap_or_else(|| context.default_account());
                 let chain_client = context.make_chain_client(account.chain_id);
@@ -397,7 +430,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 let account = account.unwrap_or_else(|| context.default_account());
                 let chain_client = context.make_chain_client(account.chain_id);
@@ -412,12 +445,51 @@ impl Runnable for Job {
                 println!("{}", balance);
             }
 
+            Estimate {
+                chain_id,
+                json_operations,
+                json_operations_path,
+                fuel_profile,
+            } => {
+                let profile = fuel_profile.is_some().then(FuelProfile::new);
+                let storage = match &profile {
+                    Some(profile) => storage.with_fuel_profile(profile.clone()),
+                    None => storage,
+                };
+                let context = ClientContext::new(
+                    storage,
+                    options.context_options.clone(),
+                    wallet,
+                    options.context_options.make_signer(signer.into_value())?,
+                );
+                let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
+                let chain_client = context.make_chain_client(chain_id);
+                let operations: Vec<Operation> =
+                    serde_json::from_slice(&read_json(json_operations, json_operations_path)?)
+                        .context("Expected a JSON list of operations")?;
+                info!(
+                    "Estimating the execution of {} operations on chain {}",
+                    operations.len(),
+                    chain_id
+                );
+                let time_start = Instant::now();
+                let estimate = chain_client.estimate_operations(operations, vec![]).await?;
+                let time_total = time_start.elapsed();
+                info!("Estimate obtained after {} ms", time_total.as_millis());
+                if let (Some(path), Some(profile)) = (fuel_profile, profile) {
+                    std::fs::write(&path, profile.to_folded())
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    info!("Fuel profile written to {}", path.display());
+                }
+                println!("{}", serde_json::to_string_pretty(&estimate)?);
+            }
+
             SyncBalance { account } => {
                 let mut context = ClientContext::new(
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 let account = account.unwrap_or_else(|| context.default_account());
                 let chain_client = context.make_chain_client(account.chain_id);
@@ -441,7 +513,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
                 let chain_client = context.make_chain_client(chain_id);
@@ -461,7 +533,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
                 let chain_client = context.make_chain_client(chain_id);
@@ -485,7 +557,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 let node = context.make_node_provider().make_node(&address)?;
                 let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
@@ -513,7 +585,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
                 println!("Querying validators about chain {chain_id}.\n");
@@ -587,7 +659,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
 
                 if chains.is_empty() {
@@ -612,16 +684,26 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
 
                 let context = Arc::new(Mutex::new(context));
                 let mut context = context.lock().await;
+                if let SetValidator {
+                    aggregate_key: Some(aggregate_key),
+                    aggregate_key_proof: Some(proof),
+                    ..
+                } = &command
+                {
+                    aggregate_key.verify_proof_of_possession(proof)?;
+                }
                 if let SetValidator {
                     public_key: _,
                     account_key: _,
                     address,
                     votes: _,
+                    aggregate_key: _,
+                    aggregate_key_proof: _,
                     skip_online_check: false,
                 } = &command
                 {
@@ -657,6 +739,8 @@ impl Runnable for Job {
                                     account_key,
                                     address,
                                     votes,
+                                    aggregate_key,
+                                    aggregate_key_proof,
                                     skip_online_check: _,
                                 } => {
                                     validators.insert(
@@ -665,6 +749,8 @@ impl Runnable for Job {
                                             network_address: address,
                                             votes,
                                             account_public_key: account_key,
+                                            aggregate_public_key: aggregate_key,
+                                            aggregate_key_proof,
                                         },
                                     );
                                 }
@@ -687,6 +773,7 @@ impl Runnable for Job {
                                     blob_byte_read,
                                     blob_byte_published,
                                     byte_stored,
+                                    byte_stored_refund,
                                     operation,
                                     operation_byte,
                                     message,
@@ -707,8 +794,22 @@ impl Runnable for Job {
                                     maximum_http_response_bytes,
                                     http_request_timeout_ms,
                                     http_request_allow_list,
+                                    dynamic_fee_target_fuel_per_block,
+                                    dynamic_fee_target_bytes_per_block,
+                                    dynamic_fee_maximum_multiplier,
+                                    disable_dynamic_fees,
                                 } => {
                                     let existing_policy = policy.clone();
+                                    let dynamic_fees = if disable_dynamic_fees {
+                                        None
+                                    } else {
+                                        updated_dynamic_fees(
+                                            &existing_policy,
+                                            dynamic_fee_target_fuel_per_block,
+                                            dynamic_fee_target_bytes_per_block,
+                                            dynamic_fee_maximum_multiplier,
+                                        )
+                                    };
                                     policy = linera_execution::ResourceControlPolicy {
                                         wasm_fuel_unit: wasm_fuel_unit
                                             .unwrap_or(existing_policy.wasm_fuel_unit),
@@ -732,6 +833,8 @@ impl Runnable for Job {
                                             .unwrap_or(existing_policy.blob_byte_published),
                                         byte_stored: byte_stored
                                             .unwrap_or(existing_policy.byte_stored),
+                                        byte_stored_refund: byte_stored_refund
+                                            .unwrap_or(existing_policy.byte_stored_refund),
                                         operation: operation.unwrap_or(existing_policy.operation),
                                         operation_byte: operation_byte
                                             .unwrap_or(existing_policy.operation_byte),
@@ -779,6 +882,7 @@ impl Runnable for Job {
                                         http_request_allow_list: http_request_allow_list
                                             .map(BTreeSet::from_iter)
                                             .unwrap_or(existing_policy.http_request_allow_list),
+                                        dynamic_fees,
                                     };
                                     info!("{policy}");
                                     if committee.policy() == &policy {
@@ -812,7 +916,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
 
                 let chain_client = context.make_chain_client(context.wallet.genesis_admin_chain());
@@ -880,7 +984,7 @@ impl Runnable for Job {
                         storage.clone(),
                         options.context_options.clone(),
                         wallet,
-                        signer.into_value(),
+                        options.context_options.make_signer(signer.into_value())?,
                     );
                     let (chain_clients, blocks_infos) = context
                         .prepare_for_benchmark(
@@ -1244,7 +1348,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
 
                 let mut join_set = JoinSet::new();
@@ -1274,7 +1378,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
 
                 let default_chain = context.wallet().default_chain();
@@ -1306,7 +1410,7 @@ impl Runnable for Job {
                     storage.clone(),
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
 
                 let chain_id = chain_id.unwrap_or_else(|| context.first_non_admin_chain());
@@ -1346,7 +1450,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
 
                 let start_time = Instant::now();
@@ -1372,7 +1476,7 @@ impl Runnable for Job {
                     storage.clone(),
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 let start_time = Instant::now();
                 let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
@@ -1391,7 +1495,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
 
                 let start_time = Instant::now();
@@ -1412,7 +1516,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
 
                 let start_time = Instant::now();
@@ -1431,12 +1535,13 @@ impl Runnable for Job {
                 json_argument,
                 json_argument_path,
                 required_application_ids,
+                upgrade_authority,
             } => {
                 let mut context = ClientContext::new(
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
 
                 let start_time = Instant::now();
@@ -1463,6 +1568,7 @@ impl Runnable for Job {
                                     parameters,
                                     argument,
                                     required_application_ids.unwrap_or_default(),
+                                    upgrade_authority,
                                 )
                                 .await
                         }
@@ -1487,12 +1593,13 @@ impl Runnable for Job {
                 json_argument,
                 json_argument_path,
                 required_application_ids,
+                upgrade_authority,
             } => {
                 let mut context = ClientContext::new(
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
 
                 let start_time = Instant::now();
@@ -1518,6 +1625,7 @@ impl Runnable for Job {
                                     parameters,
                                     argument,
                                     required_application_ids.unwrap_or_default(),
+                                    upgrade_authority,
                                 )
                                 .await
                         }
@@ -1532,12 +1640,92 @@ impl Runnable for Job {
                 println!("{}", application_id);
             }
 
+            UpgradeApplication {
+                application_id,
+                module_id,
+                chain_id,
+                json_argument,
+                json_argument_path,
+            } => {
+                let mut context = ClientContext::new(
+                    storage,
+                    options.context_options.clone(),
+                    wallet,
+                    options.context_options.make_signer(signer.into_value())?,
+                );
+
+                let start_time = Instant::now();
+                let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
+                info!("Upgrading application {application_id} on chain {chain_id}");
+                let chain_client = context.make_chain_client(chain_id);
+                let argument = read_json(json_argument, json_argument_path)?;
+
+                info!("Synchronizing");
+                context.process_inbox(&chain_client).await?;
+
+                let certificate = context
+                    .apply_client_command(&chain_client, move |chain_client| {
+                        let argument = argument.clone();
+                        let chain_client = chain_client.clone();
+                        async move {
+                            chain_client
+                                .upgrade_application(application_id, module_id, argument)
+                                .await
+                        }
+                    })
+                    .await
+                    .context("Failed to upgrade application")?;
+                debug!("{:?}", certificate);
+                info!("{}", "Application upgraded successfully!".green().bold());
+                info!(
+                    "Application upgraded in {} ms",
+                    start_time.elapsed().as_millis()
+                );
+            }
+
+            DeleteApplication {
+                application_id,
+                chain_id,
+                refund_recipient,
+            } => {
+                let mut context = ClientContext::new(
+                    storage,
+                    options.context_options.clone(),
+                    wallet,
+                    options.context_options.make_signer(signer.into_value())?,
+                );
+
+                let start_time = Instant::now();
+                let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
+                let refund_recipient = refund_recipient.unwrap_or(AccountOwner::CHAIN);
+                info!("Deleting application {application_id} from chain {chain_id}");
+                let chain_client = context.make_chain_client(chain_id);
+
+                let certificate = context
+                    .apply_client_command(&chain_client, move |chain_client| {
+                        let chain_client = chain_client.clone();
+                        async move {
+                            chain_client
+                                .delete_application(application_id, refund_recipient)
+                                .await
+                        }
+                    })
+                    .await
+                    .context("Failed to delete application")?;
+                debug!("{:?}", certificate);
+                info!("{}", "Application deleted successfully!".green().bold());
+                info!(
+                    "Application deleted in {} ms",
+                    start_time.elapsed().as_millis()
+                );
+            }
+
             Assign { owner, chain_id } => {
                 let mut context = ClientContext::new(
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 let start_time = Instant::now();
                 info!(
@@ -1568,7 +1756,7 @@ impl Runnable for Job {
                         storage,
                         options.context_options.clone(),
                         wallet,
-                        signer.into_value(),
+                        options.context_options.make_signer(signer.into_value())?,
                     );
                     let start_time = Instant::now();
                     let publisher = publisher.unwrap_or_else(|| context.default_chain());
@@ -1599,6 +1787,7 @@ impl Runnable for Job {
                                         parameters,
                                         argument,
                                         required_application_ids.unwrap_or_default(),
+                                        None,
                                     )
                                     .await
                             }
@@ -1620,7 +1809,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 let start_time = Instant::now();
                 let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
@@ -1653,7 +1842,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 let owner = public_key.into();
                 info!(
@@ -1690,7 +1879,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 let faucet = cli_wrappers::Faucet::new(faucet_url);
                 let committee = faucet.current_committee().await?;
@@ -1706,7 +1895,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 let start_time = Instant::now();
                 context.client.track_chain(chain_id);
@@ -1728,7 +1917,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
                 let chain_state_view = context
@@ -1753,7 +1942,7 @@ impl Runnable for Job {
                     storage,
                     options.context_options.clone(),
                     wallet,
-                    signer.into_value(),
+                    options.context_options.make_signer(signer.into_value())?,
                 );
                 let chain_id = chain_id.unwrap_or_else(|| context.default_chain());
                 let chain_client = context.make_chain_client(chain_id);
@@ -1805,6 +1994,11 @@ struct ClientOptions {
     #[arg(long)]
     wasm_runtime: Option<WasmRuntime>,
 
+    /// Record the execution traces of the blocks executed locally, so that they can be
+    /// queried from the node service.
+    #[arg(long, env = "LINERA_RECORD_EXECUTION_TRACES")]
+    record_execution_traces: bool,
+
     /// The number of Tokio worker threads to use.
     #[arg(long, env = "LINERA_CLIENT_TOKIO_THREADS")]
     tokio_threads: Option<usize>,
@@ -1823,18 +2017,27 @@ impl ClientOptions {
         <ClientOptions as clap::Parser>::parse()
     }
 
-    async fn run_with_storage<R: Runnable>(&self, job: R) -> Result<R::Output, Error> {
+    async fn run_with_storage<R: Runnable + Send>(&self, job: R) -> Result<R::Output, Error> {
         let storage_config = self.storage_config()?;
         debug!("Running command using storage configuration: {storage_config}");
         let store_config =
             storage_config.add_common_storage_options(&self.common_storage_options)?;
-        let output =
-            Box::pin(store_config.run_with_storage(self.wasm_runtime.with_wasm_default(), job))
-                .await?;
+        let execution_runtime_config = ExecutionRuntimeConfig {
+            record_execution_traces: self.record_execution_traces,
+        };
+        let output = Box::pin(store_config.run_with_storage(
+            self.wasm_runtime.with_wasm_default(),
+            execution_runtime_config,
+            job,
+        ))
+        .await?;
         Ok(output)
     }
 
-    async fn run_with_store<R: RunnableWithStore>(&self, job: R) -> Result<R::Output, Error> {
+    async fn run_with_store<R: RunnableWithStore + Send>(
+        &self,
+        job: R,
+    ) -> Result<R::Output, Error> {
         let storage_config = self.storage_config()?;
         debug!("Running command using storage configuration: {storage_config}");
         let store_config =
@@ -1857,8 +2060,17 @@ impl ClientOptions {
         Ok(persistent::File::read(&self.wallet_path()?)?)
     }
 
-    fn signer(&self) -> Result<persistent::File<InMemorySigner>, Error> {
-        Ok(persistent::File::read(&self.keystore_path()?)?)
+    fn signer(&self) -> Result<Keystore, Error> {
+        let keystore_path = self.keystore_path()?;
+        let passphrase = self.context_options.keystore_passphrase()?;
+        match Keystore::read(&keystore_path, passphrase.as_deref()) {
+            Err(keystore::Error::MissingPassphrase) => {
+                let passphrase =
+                    keystore::read_secret(None, KEYSTORE_PASSPHRASE_ENV, "Keystore passphrase: ")?;
+                Ok(Keystore::read(&keystore_path, Some(&passphrase))?)
+            }
+            result => Ok(result?),
+        }
     }
 
     fn suffix(&self) -> String {
@@ -1949,17 +2161,19 @@ impl ClientOptions {
         })?)
     }
 
-    pub fn create_keystore(
-        &self,
-        testing_prng_seed: Option<u64>,
-    ) -> Result<persistent::File<InMemorySigner>, Error> {
+    pub fn create_keystore(&self, testing_prng_seed: Option<u64>) -> Result<Keystore, Error> {
+        self.create_keystore_with(InMemorySigner::new(testing_prng_seed))
+    }
+
+    /// Creates a keystore holding the keys of `signer`, encrypted under the keystore
+    /// passphrase if one is set.
+    fn create_keystore_with(&self, signer: InMemorySigner) -> Result<Keystore, Error> {
         let keystore_path = self.keystore_path()?;
         if keystore_path.exists() {
             bail!("Keystore already exists: {}", keystore_path.display());
         }
-        Ok(persistent::File::read_or_create(&keystore_path, || {
-            Ok(InMemorySigner::new(testing_prng_seed))
-        })?)
+        let passphrase = self.context_options.keystore_passphrase()?;
+        Ok(Keystore::create(&keystore_path, signer, passphrase)?)
     }
 }
 
@@ -2051,11 +2265,161 @@ impl RunnableWithStore for DatabaseToolJob<'_> {
                     println!("{}", id);
                 }
             }
+            DatabaseToolCommand::Snapshot { snapshot_namespace } => {
+                D::snapshot(&config, &namespace, snapshot_namespace).await?;
+                info!(
+                    "Snapshot {snapshot_namespace} of namespace {namespace} taken in {} ms",
+                    start_time.elapsed().as_millis()
+                );
+            }
+            DatabaseToolCommand::Export { output } => {
+                ensure!(
+                    !output.exists(),
+                    "The file {} already exists",
+                    output.display()
+                );
+                // The archive is written to a temporary file in the same directory, which
+                // is only renamed once complete. A failed export leaves no partial archive.
+                let directory = match output.parent() {
+                    Some(parent) if !parent.as_os_str().is_empty() => parent,
+                    _ => Path::new("."),
+                };
+                let mut file = NamedTempFile::new_in(directory)?;
+                let mut writer = BufWriter::new(file.as_file_mut());
+                // Exporting a snapshot keeps the archive consistent even if the namespace
+                // is written to in the meantime.
+                let snapshot = format!("{namespace}_export_{}", Utc::now().timestamp_millis());
+                D::snapshot(&config, &namespace, &snapshot).await?;
+                let result = archive::export_namespace::<D>(&config, &snapshot, &mut writer).await;
+                D::delete(&config, &snapshot).await?;
+                let summary = result?;
+                writer.flush()?;
+                drop(writer);
+                file.as_file().sync_all()?;
+                file.persist_noclobber(output)?;
+                info!(
+                    "Namespace {namespace} exported to {} in {} ms: {} root keys, {} entries",
+                    output.display(),
+                    start_time.elapsed().as_millis(),
+                    summary.num_root_keys,
+                    summary.num_entries,
+                );
+            }
+            DatabaseToolCommand::Import { input } => {
+                let file = BufReader::new(File::open(input)?);
+                let summary = archive::import_namespace::<D>(&config, &namespace, file).await?;
+                info!(
+                    "Namespace {namespace} imported from {} in {} ms: {} root keys, {} entries",
+                    input.display(),
+                    start_time.elapsed().as_millis(),
+                    summary.num_root_keys,
+                    summary.num_entries,
+                );
+            }
+            DatabaseToolCommand::Migrate { .. } => {
+                unreachable!("migrations involve two storages and are run separately")
+            }
         }
         Ok(0)
     }
 }
 
+/// Opens the source storage of a migration, then runs a [`MigrationJob`] on the target.
+struct MigrationSourceJob {
+    target: StoreConfig,
+    options: MigrationOptions,
+    progress_path: PathBuf,
+}
+
+#[async_trait]
+impl RunnableWithStore for MigrationSourceJob {
+    type Output = i32;
+
+    async fn run<D>(
+        self,
+        config: D::Config,
+        _namespace: String,
+    ) -> Result<Self::Output, anyhow::Error>
+    where
+        D: KeyValueDatabase + Clone + Send + Sync + 'static,
+        D::Store: KeyValueStore + Clone + Send + Sync + 'static,
+        D::Error: Send + Sync,
+    {
+        let job = MigrationJob::<D> {
+            source_config: config,
+            options: self.options,
+            progress_path: self.progress_path,
+            _source: PhantomData,
+        };
+        Box::pin(self.target.run_with_store(job)).await
+    }
+}
+
+/// Migrates all the namespaces of the source database `D1` to the target storage.
+struct MigrationJob<D1: KeyValueDatabase> {
+    source_config: D1::Config,
+    options: MigrationOptions,
+    progress_path: PathBuf,
+    _source: PhantomData<fn() -> D1>,
+}
+
+#[async_trait]
+impl<D1> RunnableWithStore for MigrationJob<D1>
+where
+    D1: KeyValueDatabase + Clone + Send + Sync + 'static,
+    D1::Store: KeyValueStore + Clone + Send + Sync + 'static,
+    D1::Error: Send + Sync,
+{
+    type Output = i32;
+
+    async fn run<D2>(
+        self,
+        config: D2::Config,
+        _namespace: String,
+    ) -> Result<Self::Output, anyhow::Error>
+    where
+        D2: KeyValueDatabase + Clone + Send + Sync + 'static,
+        D2::Store: KeyValueStore + Clone + Send + Sync + 'static,
+        D2::Error: Send + Sync,
+    {
+        let start_time = Instant::now();
+        let mut progress = if self.progress_path.exists() {
+            let progress: MigrationProgress = util::read_json(&self.progress_path)?;
+            info!(
+                "Resuming the migration after {} completed namespaces",
+                progress.completed_namespaces.len()
+            );
+            progress
+        } else {
+            MigrationProgress::default()
+        };
+        let summary = migration::migrate::<D1, D2>(
+            &self.source_config,
+            &config,
+            &self.options,
+            &mut progress,
+            |progress| save_migration_progress(&self.progress_path, progress),
+        )
+        .await?;
+        info!(
+            "Migration completed in {} ms: {} namespaces, {} root keys, {} entries written, {} entries deleted",
+            start_time.elapsed().as_millis(),
+            summary.num_namespaces,
+            summary.num_root_keys,
+            summary.num_written_entries,
+            summary.num_deleted_entries,
+        );
+        Ok(0)
+    }
+}
+
+/// Saves the progress of a migration, replacing the previous one atomically.
+fn save_migration_progress(path: &Path, progress: &MigrationProgress) -> std::io::Result<()> {
+    let temp_path = path.with_extension("tmp");
+    std::fs::write(&temp_path, serde_json::to_vec_pretty(progress)?)?;
+    std::fs::rename(temp_path, path)
+}
+
 async fn kill_all_processes(pids: &[u32]) {
     for &pid in pids {
         info!("Killing benchmark process (pid {})", pid);
@@ -2184,6 +2548,7 @@ async fn run(options: &ClientOptions) -> Result<i32, Error> {
             byte_read_price,
             byte_written_price,
             byte_stored_price,
+            byte_stored_refund,
             blob_read_price,
             blob_published_price,
             blob_byte_read_price,
@@ -2208,6 +2573,9 @@ async fn run(options: &ClientOptions) -> Result<i32, Error> {
             maximum_http_response_bytes,
             http_request_timeout_ms,
             http_request_allow_list,
+            dynamic_fee_target_fuel_per_block,
+            dynamic_fee_target_bytes_per_block,
+            dynamic_fee_maximum_multiplier,
             testing_prng_seed,
             network_name,
         } => {
@@ -2215,6 +2583,12 @@ async fn run(options: &ClientOptions) -> Result<i32, Error> {
             let committee_config: CommitteeConfig = util::read_json(committee_config_path)
                 .expect("Unable to read committee config file");
             let existing_policy = policy_config.into_policy();
+            let dynamic_fees = updated_dynamic_fees(
+                &existing_policy,
+                *dynamic_fee_target_fuel_per_block,
+                *dynamic_fee_target_bytes_per_block,
+                *dynamic_fee_maximum_multiplier,
+            );
             let policy = linera_execution::ResourceControlPolicy {
                 wasm_fuel_unit: wasm_fuel_unit_price.unwrap_or(existing_policy.wasm_fuel_unit),
                 evm_fuel_unit: evm_fuel_unit_price.unwrap_or(existing_policy.evm_fuel_unit),
@@ -2229,6 +2603,8 @@ async fn run(options: &ClientOptions) -> Result<i32, Error> {
                 blob_byte_published: blob_byte_published_price
                     .unwrap_or(existing_policy.blob_byte_published),
                 byte_stored: byte_stored_price.unwrap_or(existing_policy.byte_stored),
+                byte_stored_refund: byte_stored_refund
+                    .unwrap_or(existing_policy.byte_stored_refund),
                 operation: operation_price.unwrap_or(existing_policy.operation),
                 operation_byte: operation_byte_price.unwrap_or(existing_policy.operation_byte),
                 message: message_price.unwrap_or(existing_policy.message),
@@ -2265,6 +2641,7 @@ async fn run(options: &ClientOptions) -> Result<i32, Error> {
                     .as_ref()
                     .map(|list| list.iter().cloned().collect())
                     .unwrap_or(existing_policy.http_request_allow_list),
+                dynamic_fees,
             };
             let timestamp = start_timestamp.map_or_else(Timestamp::now, |st| {
                 let micros =
@@ -2464,6 +2841,29 @@ async fn run(options: &ClientOptions) -> Result<i32, Error> {
             }
         },
 
+        ClientCommand::Storage(DatabaseToolCommand::Migrate {
+            from,
+            to,
+            progress_path,
+            batch_size,
+            max_attempts,
+        }) => {
+            // A validator may keep writing to the source through dual writes.
+            let source = from
+                .add_common_storage_options(&options.common_storage_options)?
+                .with_rocksdb_secondary();
+            let target = to.add_common_storage_options(&options.common_storage_options)?;
+            let job = MigrationSourceJob {
+                target,
+                options: MigrationOptions {
+                    batch_size: *batch_size,
+                    max_attempts: *max_attempts,
+                },
+                progress_path: progress_path.clone(),
+            };
+            Ok(Box::pin(source.run_with_store(job)).await?)
+        }
+
         ClientCommand::Storage(command) => {
             Ok(options.run_with_store(DatabaseToolJob(command)).await?)
         }
@@ -2574,8 +2974,17 @@ Make sure to use a Linera client compatible with this network.
                     }
                     (_, _) => bail!("Either --faucet or --genesis must be specified, but not both"),
                 };
-                let mut keystore = options.create_keystore(*testing_prng_seed)?;
-                keystore.persist().await?;
+                if options.keystore_path()?.exists() {
+                    ensure!(
+                        testing_prng_seed.is_none(),
+                        "Cannot use --testing-prng-seed with an existing keystore"
+                    );
+                    // Check that the keystore can be read before creating the wallet.
+                    options.signer()?;
+                } else {
+                    let mut keystore = options.create_keystore(*testing_prng_seed)?;
+                    keystore.persist().await?;
+                }
                 options.create_wallet(genesis_config)?.persist().await?;
                 options.initialize_storage().boxed().await?;
                 options.run_with_storage(Job(options.clone())).await??;
@@ -2590,6 +2999,53 @@ Make sure to use a Linera client compatible with this network.
                 options.run_with_storage(Job(options.clone())).await??;
                 Ok(0)
             }
+
+            WalletCommand::Create { key_scheme } => {
+                let mut rng = Box::<dyn CryptoRng>::from(None::<u64>);
+                let mnemonic = Mnemonic::generate_from(&mut rng);
+                let signer = InMemorySigner::from_mnemonic(mnemonic.clone(), (*key_scheme).into());
+                let mut keystore = options.create_keystore_with(signer)?;
+                keystore.persist().await?;
+                if !keystore.is_encrypted() {
+                    warn!("The keystore is not encrypted: set a keystore passphrase to encrypt it");
+                }
+                println!("{}", mnemonic.phrase());
+                Ok(0)
+            }
+
+            WalletCommand::Unlock => {
+                let keystore = options.signer()?;
+                ensure!(keystore.is_encrypted(), "The keystore is not encrypted");
+                for (owner, _) in keystore.keys() {
+                    println!("{owner}");
+                }
+                Ok(0)
+            }
+
+            WalletCommand::ExportMnemonic => {
+                let keystore = options.signer()?;
+                let mnemonic = keystore
+                    .mnemonic()
+                    .context("The keys of the keystore are not derived from a mnemonic")?;
+                println!("{}", mnemonic.phrase());
+                Ok(0)
+            }
+
+            WalletCommand::Recover {
+                key_scheme,
+                num_keys,
+            } => {
+                let mnemonic: Mnemonic =
+                    keystore::read_secret(None, "LINERA_MNEMONIC", "Mnemonic: ")?.parse()?;
+                let signer = InMemorySigner::from_mnemonic(mnemonic, (*key_scheme).into());
+                let mut keystore = options.create_keystore_with(signer)?;
+                for _ in 0..*num_keys {
+                    let public_key = keystore.as_mut().generate_new();
+                    println!("{}", AccountOwner::from(public_key));
+                }
+                keystore.persist().await?;
+                Ok(0)
+            }
         },
 
         _ => {
@@ -95,6 +95,10 @@ impl StorageConfigProvider {
             InnerStorageConfig::RocksDb { .. } => {
                 anyhow::bail!("Not possible to work with RocksDB")
             }
+            #[cfg(feature = "redb")]
+            InnerStorageConfig::Redb { .. } => {
+                anyhow::bail!("Not possible to work with redb")
+            }
             #[cfg(feature = "storage-service")]
             InnerStorageConfig::Service { .. } => Ok(Database::Service),
             #[cfg(feature = "dynamodb")]
@@ -103,6 +107,12 @@ impl StorageConfigProvider {
             InnerStorageConfig::ScyllaDb { .. } => Ok(Database::ScyllaDb),
             #[cfg(all(feature = "rocksdb", feature = "scylladb"))]
             InnerStorageConfig::DualRocksDbScyllaDb { .. } => Ok(Database::DualRocksDbScyllaDb),
+            InnerStorageConfig::Compressed { .. } => {
+                anyhow::bail!("Not possible to work with compressed storage")
+            }
+            InnerStorageConfig::Encrypted { .. } => {
+                anyhow::bail!("Not possible to work with encrypted storage")
+            }
         }
     }
 }
@@ -113,6 +113,7 @@ async fn make_testing_config(database: Database) -> Result<InnerStorageConfig> {
                     path_with_guard: rocksdb_config.inner_config.path_with_guard,
                     spawn_mode,
                     uri: scylla_config.inner_config.uri,
+                    dual_writes: false,
                 })
             }
             #[cfg(not(all(feature = "rocksdb", feature = "scylladb")))]
@@ -9,6 +9,7 @@ use common::{ExporterCancellationSignal, ExporterError};
 use exporter_service::ExporterService;
 use futures::FutureExt;
 use linera_base::listen_for_shutdown_signals;
+use linera_execution::ExecutionRuntimeConfig;
 #[cfg(with_metrics)]
 use linera_metrics::monitoring_server;
 use linera_rpc::NodeOptions;
@@ -176,7 +177,10 @@ impl ExporterOptions {
                 .storage_config
                 .add_common_storage_options(&self.common_storage_options)
                 .unwrap();
-            store_config.run_with_storage(None, context).boxed().await
+            store_config
+                .run_with_storage(None, ExecutionRuntimeConfig::default(), context)
+                .boxed()
+                .await
         };
 
         runtime.block_on(future)?.map_err(|e| e.into())
@@ -367,6 +367,8 @@ mod test {
             network_address: destination.address(),
             votes: 0,
             account_public_key: AccountPublicKey::test_key(0),
+            aggregate_public_key: None,
+            aggregate_key_proof: None,
         };
         let (notifier, block_processor_handle) = start_block_processor_task(
             storage.clone(),
@@ -419,6 +421,8 @@ mod test {
             network_address: destination.address(),
             votes: 0,
             account_public_key: AccountPublicKey::test_key(1),
+            aggregate_public_key: None,
+            aggregate_key_proof: None,
         };
         let mut two_validators = single_validator.clone();
         two_validators.insert(Secp256k1PublicKey::test_key(1), validator_state);
@@ -4,17 +4,20 @@
 use std::{borrow::Cow, future::IntoFuture, iter, net::SocketAddr, num::NonZeroU16, sync::Arc};
 
 use async_graphql::{
-    futures_util::Stream, resolver_utils::ContainerType, Error, MergedObject, OutputType,
-    ScalarType, Schema, SimpleObject, Subscription,
+    futures_util::Stream, resolver_utils::ContainerType, Error, ErrorExtensions as _, MergedObject,
+    OutputType, ScalarType, Schema, SimpleObject, Subscription,
 };
 use async_graphql_axum::{GraphQLRequest, GraphQLResponse, GraphQLSubscription};
 use axum::{extract::Path, http::StatusCode, response, response::IntoResponse, Extension, Router};
 use futures::{lock::Mutex, Future, FutureExt as _};
 use linera_base::{
+    bcs,
     crypto::{CryptoError, CryptoHash},
     data_types::{
-        Amount, ApplicationDescription, ApplicationPermissions, Bytecode, Epoch, TimeDelta,
+        Amount, ApplicationDescription, ApplicationPermissions, BlockHeight, Bytecode, Epoch,
+        TimeDelta,
     },
+    hex,
     identifiers::{
         Account, AccountOwner, ApplicationId, ChainId, IndexAndEvent, ModuleId, StreamId,
     },
@@ -23,18 +26,20 @@ use linera_base::{
     BcsHexParseError,
 };
 use linera_chain::{
+    data_types::FeeEstimate,
     types::{ConfirmedBlock, GenericCertificate},
-    ChainStateView,
+    ChainError, ChainStateView,
 };
 use linera_client::chain_listener::{ChainListener, ChainListenerConfig, ClientContext};
 use linera_core::{
     client::{ChainClient, ChainClientError},
     data_types::ClientOutcome,
-    worker::Notification,
+    local_node::LocalNodeError,
+    worker::{Notification, WorkerError},
 };
 use linera_execution::{
-    committee::Committee, system::AdminOperation, Operation, Query, QueryOutcome, QueryResponse,
-    SystemOperation,
+    committee::Committee, system::AdminOperation, ExecutionTrace, Operation, Query, QueryOutcome,
+    QueryResponse, SystemOperation,
 };
 #[cfg(with_metrics)]
 use linera_metrics::monitoring_server;
@@ -405,6 +410,7 @@ where
             multi_leader_rounds: 2,
             open_multi_leader_rounds: false,
             timeout_config: TimeoutConfig::default(),
+            approval_threshold: None,
         };
         self.execute_system_operation(operation, chain_id).await
     }
@@ -437,6 +443,11 @@ where
             default = 86_400_000
         )]
         fallback_duration_ms: u64,
+        #[graphql(
+            desc = "The total weight of the owners that must sign each block proposal; \
+                    default: a single owner's signature is enough"
+        )]
+        approval_threshold: Option<u64>,
     ) -> Result<CryptoHash, Error> {
         let operation = SystemOperation::ChangeOwnership {
             super_owners: Vec::new(),
@@ -449,6 +460,7 @@ where
                 timeout_increment: TimeDelta::from_millis(timeout_increment_ms),
                 fallback_duration: TimeDelta::from_millis(fallback_duration_ms),
             },
+            approval_threshold,
         };
         self.execute_system_operation(operation, chain_id).await
     }
@@ -555,6 +567,7 @@ where
         parameters: String,
         instantiation_argument: String,
         required_application_ids: Vec<ApplicationId>,
+        upgrade_authority: Option<AccountOwner>,
     ) -> Result<ApplicationId, Error> {
         self.apply_client_command(&chain_id, move |client| {
             let parameters = parameters.as_bytes().to_vec();
@@ -567,6 +580,7 @@ where
                         parameters,
                         instantiation_argument,
                         required_application_ids,
+                        upgrade_authority,
                     )
                     .await
                     .map_err(Error::from)
@@ -576,6 +590,48 @@ where
         })
         .await
     }
+
+    /// Deletes all the storage of an application on the chain, and credits the refund for
+    /// the released bytes to `refund_recipient`. The signer must be an owner of the chain or
+    /// the application's upgrade authority.
+    async fn delete_application(
+        &self,
+        chain_id: ChainId,
+        application_id: ApplicationId,
+        refund_recipient: AccountOwner,
+    ) -> Result<CryptoHash, Error> {
+        self.apply_client_command(&chain_id, move |client| async move {
+            let result = client
+                .delete_application(application_id, refund_recipient)
+                .await
+                .map_err(Error::from)
+                .map(|outcome| outcome.map(|certificate| certificate.hash()));
+            (result, client)
+        })
+        .await
+    }
+
+    /// Upgrades an application to a new module, keeping its storage.
+    async fn upgrade_application(
+        &self,
+        chain_id: ChainId,
+        application_id: ApplicationId,
+        module_id: ModuleId,
+        migration_argument: String,
+    ) -> Result<CryptoHash, Error> {
+        self.apply_client_command(&chain_id, move |client| {
+            let migration_argument = migration_argument.as_bytes().to_vec();
+            async move {
+                let result = client
+                    .upgrade_application(application_id, module_id, migration_argument)
+                    .await
+                    .map_err(Error::from)
+                    .map(|outcome| outcome.map(|certificate| certificate.hash()));
+                (result, client)
+            }
+        })
+        .await
+    }
 }
 
 #[async_graphql::Object(cache_control(no_cache))]
@@ -680,12 +736,74 @@ where
         Ok(values)
     }
 
+    /// Executes a block made of the given operations on top of the chain's current state,
+    /// without proposing or persisting it, and returns the resources and fees it would use,
+    /// as well as the messages and events it would produce. The operations are given as
+    /// hex-encoded BCS. If execution traces are recorded and a user operation fails, its trace
+    /// is returned in the `executionTrace` extension of the error.
+    async fn estimate_fees(
+        &self,
+        chain_id: ChainId,
+        operations: Vec<String>,
+    ) -> Result<FeeEstimate, Error> {
+        let operations = operations
+            .iter()
+            .map(|operation| {
+                let bytes = hex::decode(operation).map_err(BcsHexParseError::from)?;
+                bcs::from_bytes(&bytes).map_err(BcsHexParseError::from)
+            })
+            .collect::<Result<Vec<Operation>, _>>()?;
+        let client = self.context.lock().await.make_chain_client(chain_id);
+        client
+            .estimate_operations(operations, vec![])
+            .await
+            .map_err(|error| {
+                let trace = execution_trace(&error).map(ScalarType::to_value);
+                let graphql_error = Error::new(error.to_string());
+                match trace {
+                    Some(trace) => graphql_error
+                        .extend_with(|_, extensions| extensions.set("executionTrace", trace)),
+                    None => graphql_error,
+                }
+            })
+    }
+
+    /// Returns the traces of the user actions executed by a transaction of the block at the
+    /// given height, if execution traces were recorded when this client executed it.
+    async fn execution_traces(
+        &self,
+        chain_id: ChainId,
+        height: BlockHeight,
+        transaction_index: u32,
+    ) -> Result<Option<Vec<ExecutionTrace>>, Error> {
+        let client = self.context.lock().await.make_chain_client(chain_id);
+        let view = client.chain_state_view().await?;
+        let Some(traces) = view.execution_traces.get(&height).await? else {
+            return Ok(None);
+        };
+        Ok(traces.into_iter().nth(transaction_index as usize))
+    }
+
     /// Returns the version information on this node service.
     async fn version(&self) -> linera_version::VersionInfo {
         linera_version::VersionInfo::default()
     }
 }
 
+/// Returns the trace of the user action that made a block fail, if it was recorded.
+fn execution_trace(error: &ChainClientError) -> Option<&ExecutionTrace> {
+    let ChainClientError::LocalNodeError(LocalNodeError::WorkerError(WorkerError::ChainError(
+        error,
+    ))) = error
+    else {
+        return None;
+    };
+    let ChainError::ExecutionError(error, _) = &**error else {
+        return None;
+    };
+    error.trace()
+}
+
 // What follows is a hack to add a chain_id field to `ChainStateView` based on
 // https://async-graphql.github.io/async-graphql/en/merging_objects.html
 
@@ -29,6 +29,7 @@ use futures::{FutureExt as _, SinkExt, StreamExt};
 use linera_base::listen_for_shutdown_signals;
 use linera_client::config::ValidatorServerConfig;
 use linera_core::{node::NodeError, JoinSetExt as _};
+use linera_execution::ExecutionRuntimeConfig;
 #[cfg(with_metrics)]
 use linera_metrics::monitoring_server;
 use linera_rpc::{
@@ -493,7 +494,11 @@ impl ProxyOptions {
             .storage_config
             .add_common_storage_options(&self.common_storage_options)?;
         store_config
-            .run_with_storage(None, ProxyContext::from_options(self)?)
+            .run_with_storage(
+                None,
+                ExecutionRuntimeConfig::default(),
+                ProxyContext::from_options(self)?,
+            )
             .boxed()
             .await?
     }
@@ -35,12 +35,12 @@ use anyhow::{bail, Context};
 use async_trait::async_trait;
 use futures::{stream::FuturesUnordered, FutureExt as _, StreamExt, TryFutureExt as _};
 use linera_base::{
-    crypto::{CryptoRng, Ed25519SecretKey},
+    crypto::{Bls12381SecretKey, CryptoRng, Ed25519SecretKey},
     listen_for_shutdown_signals,
 };
 use linera_client::config::{CommitteeConfig, ValidatorConfig, ValidatorServerConfig};
 use linera_core::{worker::WorkerState, JoinSetExt as _, CHAIN_INFO_MAX_RECEIVED_LOG_ENTRIES};
-use linera_execution::{WasmRuntime, WithWasmDefault};
+use linera_execution::{ExecutionRuntimeConfig, WasmRuntime, WithWasmDefault};
 #[cfg(with_metrics)]
 use linera_metrics::monitoring_server;
 use linera_persistent::{self as persistent, Persist};
@@ -94,6 +94,12 @@ impl ServerContext {
             Some(self.server_config.validator_secret.copy()),
             storage,
         )
+        .with_aggregate_key(
+            self.server_config
+                .aggregate_secret
+                .as_ref()
+                .map(Bls12381SecretKey::copy),
+        )
         .with_allow_inactive_chains(false)
         .with_allow_messages_from_deprecated_epochs(false)
         .with_grace_period(self.grace_period)
@@ -291,6 +297,11 @@ struct ValidatorOptions {
     #[serde(default)]
     block_exporters: Vec<ExporterServiceConfig>,
 
+    /// Whether to generate a BLS12-381 key, so that certificates can carry a single
+    /// aggregate signature.
+    #[serde(default)]
+    aggregate_signatures: bool,
+
     /// The network protocol for the frontend.
     external_protocol: NetworkProtocol,
 
@@ -311,6 +322,9 @@ fn make_server_config<R: CryptoRng>(
 ) -> anyhow::Result<persistent::File<ValidatorServerConfig>> {
     let validator_keypair = ValidatorKeypair::generate_from(rng);
     let account_secret = AccountSecretKey::Ed25519(Ed25519SecretKey::generate_from(rng));
+    let aggregate_secret = options
+        .aggregate_signatures
+        .then(|| Bls12381SecretKey::generate_from(rng));
     let public_key = validator_keypair.public_key;
     let network = ValidatorPublicNetworkConfig {
         protocol: options.external_protocol,
@@ -328,12 +342,17 @@ fn make_server_config<R: CryptoRng>(
         network,
         public_key,
         account_key: account_secret.public(),
+        aggregate_public_key: aggregate_secret.as_ref().map(Bls12381SecretKey::public),
+        aggregate_key_proof: aggregate_secret
+            .as_ref()
+            .map(Bls12381SecretKey::proof_of_possession),
     };
     Ok(persistent::File::new(
         path,
         ValidatorServerConfig {
             validator,
             validator_secret: validator_keypair.secret_key,
+            aggregate_secret,
             internal_network,
         },
     )?)
@@ -526,7 +545,7 @@ async fn run(options: ServerOptions) {
                 .add_common_storage_options(&common_storage_options)
                 .unwrap();
             store_config
-                .run_with_storage(wasm_runtime, job)
+                .run_with_storage(wasm_runtime, ExecutionRuntimeConfig::default(), job)
                 .boxed()
                 .await
                 .unwrap()
@@ -689,6 +708,7 @@ mod test {
                     host: "exporter".into(),
                     port: 12000
                 }],
+                aggregate_signatures: false,
                 shards: vec![
                     ShardConfig {
                         host: "host1".into(),
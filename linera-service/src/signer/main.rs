@@ -0,0 +1,354 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A signing daemon holding the keys of Linera clients in an encrypted keystore.
+//!
+//! Clients select it with `--remote-signer` and authenticate with the bearer token that
+//! the daemon writes to its token file. The protocol is described in
+//! [`linera_client::remote_signer`].
+
+use std::{
+    collections::BTreeMap,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{bail, ensure, Context as _};
+use axum::{
+    extract::{Request, State},
+    http::{header::AUTHORIZATION, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use linera_base::{
+    crypto::{AccountSecretKey, CryptoRng},
+    data_types::Amount,
+    identifiers::{AccountOwner, ChainId},
+    listen_for_shutdown_signals,
+};
+use linera_client::{
+    keystore::{self, EncryptedKeystore},
+    remote_signer::{
+        ContainsKeyRequest, ContainsKeyResponse, ErrorResponse, SignRequest, SignResponse,
+        CONTAINS_KEY_PATH, SIGN_PATH,
+    },
+};
+use linera_persistent::{self as persistent, Persist};
+use policy::{KeyPolicy, PolicyError};
+use rand::RngCore as _;
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+mod policy;
+
+#[cfg(test)]
+mod tests;
+
+/// Options for running the signing daemon.
+#[derive(clap::Parser, Debug)]
+#[command(
+    name = "Linera Signer",
+    about = "A signing daemon holding the keys of Linera clients",
+    version = linera_version::VersionInfo::default_clap_str(),
+)]
+struct SignerOptions {
+    /// The file storing the encrypted keys and their policies.
+    #[arg(long, env = "LINERA_SIGNER_KEYSTORE")]
+    keystore: PathBuf,
+
+    /// The file containing the passphrase of the keystore. Otherwise, the passphrase is
+    /// read from the `LINERA_SIGNER_PASSPHRASE` environment variable, or prompted for.
+    #[arg(long)]
+    passphrase_file: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: SignerCommand,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum SignerCommand {
+    /// Creates an empty keystore.
+    Init,
+
+    /// Generates a new key and prints its owner.
+    Generate {
+        #[command(flatten)]
+        policy: PolicyOptions,
+    },
+
+    /// Replaces the policy of a key.
+    SetPolicy {
+        /// The owner of the key.
+        owner: AccountOwner,
+
+        #[command(flatten)]
+        policy: PolicyOptions,
+    },
+
+    /// Lists the owners of the keys and their policies.
+    List,
+
+    /// Serves signing requests.
+    Serve {
+        /// The address to listen on. Only local clients should be able to reach it.
+        #[arg(long, default_value = "127.0.0.1:8090")]
+        listen: SocketAddr,
+
+        /// The file containing the bearer token that clients must present. A random token
+        /// is written to it, readable only by the current user, if it doesn't exist.
+        #[arg(long, env = "LINERA_SIGNER_TOKEN_FILE")]
+        token_file: PathBuf,
+    },
+}
+
+/// The policy of a key.
+#[derive(clap::Args, Debug)]
+struct PolicyOptions {
+    /// Restricts the key to signing blocks of the given chain. Can be repeated.
+    #[arg(long = "allowed-chain")]
+    allowed_chains: Vec<ChainId>,
+
+    /// The maximum amount of native tokens that a block signed by the key may move out
+    /// of the chain.
+    #[arg(long)]
+    max_transfer_amount: Option<Amount>,
+}
+
+impl From<PolicyOptions> for KeyPolicy {
+    fn from(options: PolicyOptions) -> Self {
+        KeyPolicy {
+            allowed_chains: options.allowed_chains.into_iter().collect(),
+            max_transfer_amount: options.max_transfer_amount,
+        }
+    }
+}
+
+/// A key held by the daemon.
+#[derive(Serialize, Deserialize)]
+struct SignerKey {
+    secret: AccountSecretKey,
+    #[serde(default)]
+    policy: KeyPolicy,
+}
+
+impl SignerKey {
+    fn owner(&self) -> AccountOwner {
+        self.secret.public().into()
+    }
+}
+
+/// The decrypted content of the keystore.
+#[derive(Default, Serialize, Deserialize)]
+struct SignerKeys {
+    keys: Vec<SignerKey>,
+}
+
+/// The keys used to answer the requests, indexed by owner.
+type SignerState = Arc<BTreeMap<AccountOwner, SignerKey>>;
+
+#[derive(Debug, thiserror::Error)]
+enum SignerError {
+    #[error("no key found for owner {0}")]
+    NoSuchOwner(AccountOwner),
+    #[error(transparent)]
+    Policy(#[from] PolicyError),
+}
+
+impl IntoResponse for SignerError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            SignerError::NoSuchOwner(_) => StatusCode::NOT_FOUND,
+            SignerError::Policy(error) if error.is_invalid_request() => StatusCode::BAD_REQUEST,
+            SignerError::Policy(_) => StatusCode::FORBIDDEN,
+        };
+        let error = self.to_string();
+        (status, Json(ErrorResponse { error })).into_response()
+    }
+}
+
+/// Signs the requested value if the policy of the key allows it.
+async fn sign(
+    State(keys): State<SignerState>,
+    Json(request): Json<SignRequest>,
+) -> Result<Json<SignResponse>, SignerError> {
+    let key = keys
+        .get(&request.owner)
+        .ok_or(SignerError::NoSuchOwner(request.owner))?;
+    if let Err(error) = key.policy.check(&request.value, request.content.as_ref()) {
+        info!(
+            "Refused to sign {} for {}: {error}",
+            request.value, request.owner
+        );
+        return Err(error.into());
+    }
+    let signature = key.secret.sign_prehash(request.value);
+    Ok(Json(SignResponse { signature }))
+}
+
+/// Returns whether the daemon holds the requested key.
+async fn contains_key(
+    State(keys): State<SignerState>,
+    Json(request): Json<ContainsKeyRequest>,
+) -> Json<ContainsKeyResponse> {
+    let contains_key = keys.contains_key(&request.owner);
+    Json(ContainsKeyResponse { contains_key })
+}
+
+/// Rejects the requests that don't carry the bearer `token`.
+async fn authenticate(State(token): State<Arc<String>>, request: Request, next: Next) -> Response {
+    let presented = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match presented {
+        Some(presented) if constant_time_eq(presented.as_bytes(), token.as_bytes()) => {
+            next.run(request).await
+        }
+        _ => {
+            let error = "missing or invalid bearer token".to_owned();
+            (StatusCode::UNAUTHORIZED, Json(ErrorResponse { error })).into_response()
+        }
+    }
+}
+
+/// Compares two byte strings in a time that doesn't depend on where they differ.
+fn constant_time_eq(left: &[u8], right: &[u8]) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .zip(right)
+            .fold(0, |difference, (left, right)| difference | (left ^ right))
+            == 0
+}
+
+/// Returns the router serving the signing requests with the given keys to the clients
+/// presenting the bearer `token`.
+fn router(keys: SignerKeys, token: String) -> Router {
+    let state: SignerState = Arc::new(
+        keys.keys
+            .into_iter()
+            .map(|key| (key.owner(), key))
+            .collect(),
+    );
+    Router::new()
+        .route(SIGN_PATH, post(sign))
+        .route(CONTAINS_KEY_PATH, post(contains_key))
+        .route_layer(middleware::from_fn_with_state(
+            Arc::new(token),
+            authenticate,
+        ))
+        .with_state(state)
+}
+
+/// Reads the bearer token from `path`, or writes a new random token there if the file
+/// doesn't exist.
+fn read_or_create_token(path: &Path) -> anyhow::Result<String> {
+    if path.exists() {
+        let token = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read token file {}", path.display()))?;
+        let token = token.trim().to_owned();
+        ensure!(!token.is_empty(), "Empty token file: {}", path.display());
+        return Ok(token);
+    }
+    let mut bytes = [0u8; 32];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    let token = hex::encode(bytes);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options
+        .open(path)
+        .with_context(|| format!("Failed to create token file {}", path.display()))?;
+    std::io::Write::write_all(&mut file, token.as_bytes())?;
+    info!("Wrote a new bearer token to {}", path.display());
+    Ok(token)
+}
+
+impl SignerOptions {
+    /// Reads the passphrase of the keystore, without taking it from the command line.
+    fn passphrase(&self) -> Result<String, keystore::Error> {
+        keystore::read_secret(
+            self.passphrase_file.as_deref(),
+            "LINERA_SIGNER_PASSPHRASE",
+            "Keystore passphrase: ",
+        )
+    }
+
+    async fn run(self) -> anyhow::Result<()> {
+        let passphrase = self.passphrase()?;
+        match self.command {
+            SignerCommand::Init => {
+                if self.keystore.exists() {
+                    bail!("Keystore already exists: {}", self.keystore.display());
+                }
+                let keystore = EncryptedKeystore::seal(&SignerKeys::default(), &passphrase)?;
+                let mut file = persistent::File::new(&self.keystore, keystore)?;
+                file.persist().await?;
+                info!("Created keystore {}", self.keystore.display());
+            }
+
+            SignerCommand::Generate { policy } => {
+                let mut file = persistent::File::<EncryptedKeystore>::read(&self.keystore)?;
+                let mut keys: SignerKeys = file.open(&passphrase)?;
+                let mut rng = Box::<dyn CryptoRng>::from(None::<u64>);
+                let key = SignerKey {
+                    secret: AccountSecretKey::generate_from(&mut rng),
+                    policy: policy.into(),
+                };
+                let owner = key.owner();
+                keys.keys.push(key);
+                *file = EncryptedKeystore::seal(&keys, &passphrase)?;
+                file.persist().await?;
+                println!("{owner}");
+            }
+
+            SignerCommand::SetPolicy { owner, policy } => {
+                let mut file = persistent::File::<EncryptedKeystore>::read(&self.keystore)?;
+                let mut keys: SignerKeys = file.open(&passphrase)?;
+                let key = keys
+                    .keys
+                    .iter_mut()
+                    .find(|key| key.owner() == owner)
+                    .with_context(|| format!("No key found for owner {owner}"))?;
+                key.policy = policy.into();
+                *file = EncryptedKeystore::seal(&keys, &passphrase)?;
+                file.persist().await?;
+            }
+
+            SignerCommand::List => {
+                let file = persistent::File::<EncryptedKeystore>::read(&self.keystore)?;
+                let keys: SignerKeys = file.open(&passphrase)?;
+                for key in &keys.keys {
+                    println!("{}: {:?}", key.owner(), key.policy);
+                }
+            }
+
+            SignerCommand::Serve { listen, token_file } => {
+                let file = persistent::File::<EncryptedKeystore>::read(&self.keystore)?;
+                let keys: SignerKeys = file.open(&passphrase)?;
+                let token = read_or_create_token(&token_file)?;
+                info!("Serving {} keys on {listen}", keys.keys.len());
+                let shutdown_notifier = CancellationToken::new();
+                tokio::spawn(listen_for_shutdown_signals(shutdown_notifier.clone()));
+                let listener = tokio::net::TcpListener::bind(listen).await?;
+                axum::serve(listener, router(keys, token))
+                    .with_graceful_shutdown(shutdown_notifier.cancelled_owned())
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    linera_base::tracing::init("linera-signer");
+    let options = <SignerOptions as clap::Parser>::parse();
+    options.run().await
+}
@@ -0,0 +1,149 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The restrictions on what the keys of the signing daemon may sign.
+
+use std::collections::BTreeSet;
+
+use linera_base::{crypto::CryptoHash, data_types::Amount, identifiers::ChainId};
+use linera_chain::data_types::{ProposalContent, ProposedBlock};
+use linera_client::remote_signer::SignedPayload;
+use linera_execution::{Operation, SystemOperation};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PolicyError {
+    #[error("the key has a policy and can only sign values whose content is known")]
+    MissingContent,
+    #[error("the content does not match the value to sign")]
+    ContentMismatch,
+    #[error("invalid content: {0}")]
+    InvalidContent(#[from] bcs::Error),
+    #[error("the key is not allowed to sign blocks of chain {0}")]
+    ChainNotAllowed(ChainId),
+    #[error("the block transfers {amount} tokens but the key is limited to {limit}")]
+    TransferLimitExceeded { amount: Amount, limit: Amount },
+    #[error("the key has a transfer limit and can't sign a {0} operation")]
+    UnboundedOperation(&'static str),
+}
+
+impl PolicyError {
+    /// Returns whether the request itself is invalid, rather than forbidden.
+    pub fn is_invalid_request(&self) -> bool {
+        matches!(
+            self,
+            PolicyError::ContentMismatch | PolicyError::InvalidContent(_)
+        )
+    }
+}
+
+/// The restrictions on the blocks that a key may sign.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyPolicy {
+    /// The chains whose blocks the key may sign. Any chain if empty.
+    #[serde(default)]
+    pub allowed_chains: BTreeSet<ChainId>,
+    /// The maximum amount of native tokens that a block signed by the key may move out
+    /// of the chain, through transfers, claims, allowances and new chains. Keys with a
+    /// limit refuse the operations whose effect on the balances can't be bounded, like
+    /// user operations and ownership changes.
+    #[serde(default)]
+    pub max_transfer_amount: Option<Amount>,
+}
+
+impl KeyPolicy {
+    /// Returns whether the key may sign anything.
+    pub fn is_unrestricted(&self) -> bool {
+        self.allowed_chains.is_empty() && self.max_transfer_amount.is_none()
+    }
+
+    /// Checks that the key may sign `value`, which must be the hash of `content`.
+    pub fn check(
+        &self,
+        value: &CryptoHash,
+        content: Option<&SignedPayload>,
+    ) -> Result<(), PolicyError> {
+        let Some(content) = content else {
+            if self.is_unrestricted() {
+                return Ok(());
+            }
+            return Err(PolicyError::MissingContent);
+        };
+        let block = match content {
+            SignedPayload::BlockProposal(bytes) => {
+                let proposal = bcs::from_bytes::<ProposalContent>(bytes)?;
+                if CryptoHash::new(&proposal) != *value {
+                    return Err(PolicyError::ContentMismatch);
+                }
+                proposal.block
+            }
+            SignedPayload::BlockApproval(bytes) => {
+                let block = bcs::from_bytes::<ProposedBlock>(bytes)?;
+                if CryptoHash::new(&block) != *value {
+                    return Err(PolicyError::ContentMismatch);
+                }
+                block
+            }
+        };
+        self.check_block(&block)
+    }
+
+    /// Checks that the key may sign the given block.
+    fn check_block(&self, block: &ProposedBlock) -> Result<(), PolicyError> {
+        if !self.allowed_chains.is_empty() && !self.allowed_chains.contains(&block.chain_id) {
+            return Err(PolicyError::ChainNotAllowed(block.chain_id));
+        }
+        if let Some(limit) = self.max_transfer_amount {
+            let mut amount = Amount::ZERO;
+            for operation in block.operations() {
+                amount = amount.saturating_add(native_outflow(operation)?);
+            }
+            if amount > limit {
+                return Err(PolicyError::TransferLimitExceeded { amount, limit });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the amount of native tokens that the operation may move out of the chain, or
+/// an error if it can't be bounded.
+///
+/// Only the operations listed here are accepted: application code can move any amount,
+/// and a change of ownership or permissions hands the chain over to other keys.
+fn native_outflow(operation: &Operation) -> Result<Amount, PolicyError> {
+    let Operation::System(operation) = operation else {
+        return Err(PolicyError::UnboundedOperation("user"));
+    };
+    match operation.as_ref() {
+        SystemOperation::Transfer { amount, .. } | SystemOperation::Claim { amount, .. } => {
+            Ok(*amount)
+        }
+        SystemOperation::Approve { amount, .. } => Ok(*amount),
+        SystemOperation::OpenChain(config) => Ok(config.balance),
+        SystemOperation::TransferToken { .. }
+        | SystemOperation::ClaimToken { .. }
+        | SystemOperation::Revoke { .. }
+        | SystemOperation::PublishModule { .. }
+        | SystemOperation::PublishDataBlob { .. }
+        | SystemOperation::VerifyBlob { .. }
+        | SystemOperation::ProcessNewEpoch(_)
+        | SystemOperation::ProcessRemovedEpoch(_)
+        | SystemOperation::UpdateStreams(_)
+        | SystemOperation::DiscardScheduledOperation(_) => Ok(Amount::ZERO),
+        SystemOperation::ChangeOwnership { .. } => {
+            Err(PolicyError::UnboundedOperation("ownership change"))
+        }
+        SystemOperation::ChangeApplicationPermissions(_) => Err(PolicyError::UnboundedOperation(
+            "application permissions change",
+        )),
+        SystemOperation::CloseChain => Err(PolicyError::UnboundedOperation("chain closing")),
+        SystemOperation::CreateApplication { .. }
+        | SystemOperation::UpgradeApplication { .. }
+        | SystemOperation::DeleteApplication { .. }
+        | SystemOperation::ExecuteScheduledOperation(_) => {
+            Err(PolicyError::UnboundedOperation("application"))
+        }
+        SystemOperation::Admin(_) => Err(PolicyError::UnboundedOperation("admin")),
+    }
+}
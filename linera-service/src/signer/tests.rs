@@ -0,0 +1,152 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::BTreeSet;
+
+use linera_base::{
+    crypto::{AccountSecretKey, CryptoHash, Signer, TestString},
+    data_types::Amount,
+    identifiers::{AccountOwner, ApplicationId, ChainId},
+    ownership::ChainOwnership,
+};
+use linera_chain::test::{make_first_block, BlockTestExt as _};
+use linera_client::{
+    keystore::{self, EncryptedKeystore},
+    remote_signer::{self, RemoteSigner},
+};
+use linera_execution::{Operation, SystemOperation};
+
+use super::{policy::KeyPolicy, router, SignerKey, SignerKeys};
+
+const TOKEN: &str = "token";
+
+/// Serves the given keys on a local port and returns its URL.
+async fn serve(keys: SignerKeys) -> anyhow::Result<String> {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+    tokio::spawn(async move { axum::serve(listener, router(keys, TOKEN.to_owned())).await });
+    Ok(format!("http://{address}"))
+}
+
+fn make_key(policy: KeyPolicy) -> (AccountOwner, SignerKey) {
+    let key = SignerKey {
+        secret: AccountSecretKey::generate(),
+        policy,
+    };
+    (key.owner(), key)
+}
+
+#[tokio::test]
+async fn test_remote_signer_policies() -> anyhow::Result<()> {
+    let allowed_chain = ChainId(CryptoHash::test_hash("allowed"));
+    let other_chain = ChainId(CryptoHash::test_hash("other"));
+    let (free_owner, free_key) = make_key(KeyPolicy::default());
+    let (restricted_owner, restricted_key) = make_key(KeyPolicy {
+        allowed_chains: BTreeSet::from([allowed_chain]),
+        max_transfer_amount: Some(Amount::from_tokens(10)),
+    });
+    let keys = SignerKeys {
+        keys: vec![free_key, restricted_key],
+    };
+    let url = serve(keys).await?;
+    let signer = RemoteSigner::new(&url, TOKEN);
+
+    // Requests need the bearer token.
+    let intruder = RemoteSigner::new(&url, "wrong token");
+    assert!(matches!(
+        intruder.contains_key(&free_owner).await,
+        Err(remote_signer::Error::Unauthorized(_))
+    ));
+
+    assert!(signer.contains_key(&free_owner).await?);
+    assert!(signer.contains_key(&restricted_owner).await?);
+    let unknown_owner = AccountOwner::from(AccountSecretKey::generate().public());
+    assert!(!signer.contains_key(&unknown_owner).await?);
+
+    // Keys without a policy sign anything.
+    let value = CryptoHash::new(&TestString::new("value"));
+    let signature = signer.sign(&free_owner, &value).await?;
+    assert_eq!(signature, signer.sign(&free_owner, &value).await?);
+    assert!(matches!(
+        signer.sign(&unknown_owner, &value).await,
+        Err(remote_signer::Error::NoSuchOwner)
+    ));
+
+    // Keys with a policy need to know what they sign.
+    assert!(matches!(
+        signer.sign(&restricted_owner, &value).await,
+        Err(remote_signer::Error::Refused(_))
+    ));
+
+    let block = make_first_block(allowed_chain).with_simple_transfer(other_chain, Amount::ONE);
+    let proposal = block
+        .clone()
+        .into_first_proposal(restricted_owner, &signer)
+        .await?;
+    assert_eq!(proposal.owner(), restricted_owner);
+    proposal.check_signature()?;
+
+    let block = make_first_block(other_chain).with_simple_transfer(allowed_chain, Amount::ONE);
+    assert!(matches!(
+        block
+            .clone()
+            .into_first_proposal(restricted_owner, &signer)
+            .await,
+        Err(remote_signer::Error::Refused(_))
+    ));
+    block.into_first_proposal(free_owner, &signer).await?;
+
+    let block = make_first_block(allowed_chain)
+        .with_simple_transfer(other_chain, Amount::from_tokens(6))
+        .with_simple_transfer(other_chain, Amount::from_tokens(5));
+    assert!(matches!(
+        block.into_first_proposal(restricted_owner, &signer).await,
+        Err(remote_signer::Error::Refused(_))
+    ));
+
+    // Keys with a transfer limit refuse the operations they can't bound.
+    let ownership = ChainOwnership::single(free_owner);
+    let change_ownership = SystemOperation::ChangeOwnership {
+        super_owners: Vec::new(),
+        owners: ownership.owners.into_iter().collect(),
+        multi_leader_rounds: ownership.multi_leader_rounds,
+        open_multi_leader_rounds: ownership.open_multi_leader_rounds,
+        timeout_config: ownership.timeout_config,
+        approval_threshold: None,
+    };
+    let user_operation = Operation::User {
+        application_id: ApplicationId::new(CryptoHash::test_hash("application")),
+        bytes: Vec::new(),
+    };
+    let change_permissions = SystemOperation::ChangeApplicationPermissions(Default::default());
+    for operation in [
+        Operation::system(change_ownership),
+        Operation::system(change_permissions),
+        user_operation,
+    ] {
+        let block = make_first_block(allowed_chain).with_operation(operation);
+        assert!(matches!(
+            block
+                .clone()
+                .into_first_proposal(restricted_owner, &signer)
+                .await,
+            Err(remote_signer::Error::Refused(_))
+        ));
+        block.into_first_proposal(free_owner, &signer).await?;
+    }
+    Ok(())
+}
+
+#[test]
+fn test_encrypted_keystore() -> anyhow::Result<()> {
+    let (owner, key) = make_key(KeyPolicy::default());
+    let keystore = EncryptedKeystore::seal(&SignerKeys { keys: vec![key] }, "passphrase")?;
+    let keys: SignerKeys = keystore.open("passphrase")?;
+    assert_eq!(keys.keys.len(), 1);
+    assert_eq!(keys.keys[0].owner(), owner);
+    assert!(matches!(
+        keystore.open::<SignerKeys>("wrong passphrase"),
+        Err(keystore::Error::Decryption)
+    ));
+    Ok(())
+}
@@ -5,22 +5,29 @@ use std::{fmt, path::PathBuf, str::FromStr};
 
 use anyhow::{anyhow, bail};
 use async_trait::async_trait;
-use linera_client::config::GenesisConfig;
-use linera_execution::WasmRuntime;
+use linera_client::{config::GenesisConfig, keystore};
+use linera_execution::{ExecutionRuntimeConfig, WasmRuntime};
 use linera_storage::{DbStorage, Storage, DEFAULT_NAMESPACE};
 #[cfg(feature = "storage-service")]
 use linera_storage_service::{
     client::StorageServiceDatabase,
     common::{StorageServiceStoreConfig, StorageServiceStoreInternalConfig},
 };
+#[cfg(any(feature = "rocksdb", feature = "redb"))]
+use linera_views::common::PathWithGuard;
 #[cfg(feature = "dynamodb")]
 use linera_views::dynamo_db::{DynamoDbDatabase, DynamoDbStoreConfig, DynamoDbStoreInternalConfig};
+#[cfg(feature = "redb")]
+use linera_views::redb_db::{RedbDatabase, RedbStoreConfig, RedbStoreInternalConfig};
 #[cfg(feature = "rocksdb")]
 use linera_views::rocks_db::{
-    PathWithGuard, RocksDbDatabase, RocksDbSpawnMode, RocksDbStoreConfig,
-    RocksDbStoreInternalConfig,
+    RocksDbDatabase, RocksDbSpawnMode, RocksDbStoreConfig, RocksDbStoreInternalConfig,
 };
 use linera_views::{
+    compression::{
+        CompressionAlgorithm, CompressionConfig, CompressionDatabase, StorageCompressionConfig,
+    },
+    encryption::{EncryptionConfig, EncryptionDatabase, EncryptionKey, StorageEncryptionConfig},
     lru_caching::StorageCacheConfig,
     memory::{MemoryDatabase, MemoryStoreConfig},
     store::{KeyValueDatabase, KeyValueStore},
@@ -30,7 +37,7 @@ use tracing::error;
 #[cfg(all(feature = "rocksdb", feature = "scylladb"))]
 use {
     linera_storage::ChainStatesFirstAssignment,
-    linera_views::backends::dual::{DualDatabase, DualStoreConfig},
+    linera_views::backends::dual::{DualDatabase, DualStoreConfig, DualWriteAssignment},
     std::path::Path,
 };
 #[cfg(feature = "scylladb")]
@@ -65,8 +72,35 @@ pub struct CommonStorageOptions {
     /// The replication factor for the keyspace
     #[arg(long, default_value = "1", global = true)]
     pub storage_replication_factor: u32,
+
+    /// The minimal size of a value, in bytes, for it to be compressed when using a
+    /// compressed storage.
+    #[arg(long, default_value = "256", global = true)]
+    pub storage_min_compression_size: usize,
+
+    /// The file containing the secret key, in hexadecimal, of an encrypted storage.
+    /// Otherwise, the key is read from the `LINERA_STORAGE_ENCRYPTION_KEY` environment
+    /// variable if set, and derived from a passphrase if not.
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "storage_encryption_passphrase_file"
+    )]
+    pub storage_encryption_key_file: Option<PathBuf>,
+
+    /// The file containing the passphrase from which the secret key of an encrypted
+    /// storage is derived. Otherwise, the passphrase is read from the
+    /// `LINERA_STORAGE_ENCRYPTION_PASSPHRASE` environment variable, or prompted for.
+    #[arg(long, global = true)]
+    pub storage_encryption_passphrase_file: Option<PathBuf>,
 }
 
+/// The environment variable holding the secret key of an encrypted storage.
+const STORAGE_ENCRYPTION_KEY_ENV: &str = "LINERA_STORAGE_ENCRYPTION_KEY";
+
+/// The environment variable holding the passphrase of an encrypted storage.
+const STORAGE_ENCRYPTION_PASSPHRASE_ENV: &str = "LINERA_STORAGE_ENCRYPTION_PASSPHRASE";
+
 impl CommonStorageOptions {
     pub fn storage_cache_config(&self) -> StorageCacheConfig {
         StorageCacheConfig {
@@ -75,6 +109,47 @@ impl CommonStorageOptions {
             max_cache_entries: self.storage_max_cache_entries,
         }
     }
+
+    pub fn storage_compression_config(
+        &self,
+        algorithm: CompressionAlgorithm,
+    ) -> StorageCompressionConfig {
+        StorageCompressionConfig {
+            algorithm,
+            min_compression_size: self.storage_min_compression_size,
+        }
+    }
+
+    /// The encryption of the storage with the given namespace. The secret key or the
+    /// passphrase is never taken from the command line. A key derived from a passphrase is
+    /// salted with the namespace.
+    pub fn storage_encryption_config(
+        &self,
+        namespace: &str,
+    ) -> Result<StorageEncryptionConfig, anyhow::Error> {
+        let key = if self.storage_encryption_key_file.is_some()
+            || std::env::var_os(STORAGE_ENCRYPTION_KEY_ENV).is_some()
+        {
+            let key = keystore::read_secret(
+                self.storage_encryption_key_file.as_deref(),
+                STORAGE_ENCRYPTION_KEY_ENV,
+                "Storage encryption key: ",
+            )?;
+            key.trim()
+                .parse::<EncryptionKey>()
+                .map_err(|error| anyhow!("Invalid storage encryption key: {error}"))?
+        } else {
+            let passphrase = keystore::read_secret(
+                self.storage_encryption_passphrase_file.as_deref(),
+                STORAGE_ENCRYPTION_PASSPHRASE_ENV,
+                "Storage passphrase: ",
+            )?;
+            let salt = format!("linera-storage:{namespace}");
+            EncryptionKey::from_passphrase(passphrase.as_bytes(), salt.as_bytes())
+                .map_err(|error| anyhow!("Failed to derive the storage key: {error}"))?
+        };
+        Ok(StorageEncryptionConfig { key })
+    }
 }
 
 /// The configuration of the key value store in use.
@@ -98,6 +173,12 @@ pub enum StoreConfig {
         config: RocksDbStoreConfig,
         namespace: String,
     },
+    /// The redb key value store
+    #[cfg(feature = "redb")]
+    Redb {
+        config: RedbStoreConfig,
+        namespace: String,
+    },
     /// The DynamoDB key value store
     #[cfg(feature = "dynamodb")]
     DynamoDb {
@@ -114,6 +195,19 @@ pub enum StoreConfig {
     DualRocksDbScyllaDb {
         config: DualStoreConfig<RocksDbStoreConfig, ScyllaDbStoreConfig>,
         namespace: String,
+        /// Whether all the writes go to both stores, while migrating from RocksDB to
+        /// ScyllaDB.
+        dual_writes: bool,
+    },
+    /// A key value store whose values are compressed
+    Compressed {
+        compression_config: StorageCompressionConfig,
+        config: Box<StoreConfig>,
+    },
+    /// A key value store whose keys and values are encrypted
+    Encrypted {
+        encryption_config: StorageEncryptionConfig,
+        config: Box<StoreConfig>,
     },
 }
 
@@ -141,6 +235,12 @@ pub enum InnerStorageConfig {
         /// Whether to use `block_in_place` or `spawn_blocking`.
         spawn_mode: RocksDbSpawnMode,
     },
+    /// The redb description.
+    #[cfg(feature = "redb")]
+    Redb {
+        /// The path to the directory of the database.
+        path: PathBuf,
+    },
     /// The DynamoDB description.
     #[cfg(feature = "dynamodb")]
     DynamoDb {
@@ -161,9 +261,45 @@ pub enum InnerStorageConfig {
         spawn_mode: RocksDbSpawnMode,
         /// The URI for accessing the database.
         uri: String,
+        /// Whether all the writes go to both databases, which are read from RocksDB.
+        dual_writes: bool,
+    },
+    /// The description of a storage whose values are compressed.
+    Compressed {
+        /// The compression algorithm.
+        algorithm: CompressionAlgorithm,
+        /// The description of the underlying storage.
+        inner: Box<InnerStorageConfig>,
+    },
+    /// The description of a storage whose keys and values are encrypted.
+    Encrypted {
+        /// The description of the underlying storage.
+        inner: Box<InnerStorageConfig>,
     },
 }
 
+impl InnerStorageConfig {
+    /// Whether this storage is a database that can be wrapped with compression or
+    /// encryption.
+    fn supports_wrapping(&self) -> bool {
+        match self {
+            #[cfg(feature = "storage-service")]
+            InnerStorageConfig::Service { .. } => true,
+            #[cfg(feature = "rocksdb")]
+            InnerStorageConfig::RocksDb { .. } => true,
+            #[cfg(feature = "redb")]
+            InnerStorageConfig::Redb { .. } => true,
+            #[cfg(feature = "dynamodb")]
+            InnerStorageConfig::DynamoDb { .. } => true,
+            #[cfg(feature = "scylladb")]
+            InnerStorageConfig::ScyllaDb { .. } => true,
+            #[cfg(all(feature = "rocksdb", feature = "scylladb"))]
+            InnerStorageConfig::DualRocksDbScyllaDb { .. } => true,
+            _ => false,
+        }
+    }
+}
+
 /// The description of a storage implementation.
 #[derive(Clone, Debug)]
 #[cfg_attr(any(test), derive(Eq, PartialEq))]
@@ -179,17 +315,77 @@ const MEMORY: &str = "memory:";
 const STORAGE_SERVICE: &str = "service:";
 #[cfg(feature = "rocksdb")]
 const ROCKS_DB: &str = "rocksdb:";
+#[cfg(feature = "redb")]
+const REDB: &str = "redb:";
 #[cfg(feature = "dynamodb")]
 const DYNAMO_DB: &str = "dynamodb:";
 #[cfg(feature = "scylladb")]
 const SCYLLA_DB: &str = "scylladb:";
 #[cfg(all(feature = "rocksdb", feature = "scylladb"))]
 const DUAL_ROCKS_DB_SCYLLA_DB: &str = "dualrocksdbscylladb:";
+#[cfg(all(feature = "rocksdb", feature = "scylladb"))]
+const DUAL_WRITE_ROCKS_DB_SCYLLA_DB: &str = "dualwriterocksdbscylladb:";
+const COMPRESSED: &str = "compressed:";
+const ENCRYPTED: &str = "encrypted:";
 
 impl FromStr for StorageConfig {
     type Err = anyhow::Error;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if let Some(s) = input.strip_prefix(COMPRESSED) {
+            let Some((algorithm, inner)) = s.split_once(':') else {
+                bail!(
+                    "For compression, the formatting has to be compressed:algorithm:storage, \
+example compressed:zstd:rocksdb:foo.db"
+                );
+            };
+            let algorithm = algorithm
+                .parse::<CompressionAlgorithm>()
+                .map_err(|error| anyhow!(error))?;
+            let StorageConfig {
+                inner_storage_config,
+                namespace,
+            } = inner.parse()?;
+            // Compressing encrypted values would be useless, so the compression is
+            // applied before the encryption.
+            let supported = match &inner_storage_config {
+                InnerStorageConfig::Encrypted { inner } => inner.supports_wrapping(),
+                inner_storage_config => inner_storage_config.supports_wrapping(),
+            };
+            if !supported {
+                bail!("Compression is not supported for the storage {inner}");
+            }
+            let inner_storage_config = InnerStorageConfig::Compressed {
+                algorithm,
+                inner: Box::new(inner_storage_config),
+            };
+            return Ok(StorageConfig {
+                inner_storage_config,
+                namespace,
+            });
+        }
+        if let Some(inner) = input.strip_prefix(ENCRYPTED) {
+            let StorageConfig {
+                inner_storage_config,
+                namespace,
+            } = inner.parse()?;
+            if let InnerStorageConfig::Compressed { .. } = inner_storage_config {
+                bail!(
+                    "Compression must be applied before encryption, for instance \
+compressed:lz4:encrypted:rocksdb:foo.db"
+                );
+            }
+            if !inner_storage_config.supports_wrapping() {
+                bail!("Encryption is not supported for the storage {inner}");
+            }
+            let inner_storage_config = InnerStorageConfig::Encrypted {
+                inner: Box::new(inner_storage_config),
+            };
+            return Ok(StorageConfig {
+                inner_storage_config,
+                namespace,
+            });
+        }
         if let Some(s) = input.strip_prefix(MEMORY) {
             let parts = s.split(':').collect::<Vec<_>>();
             if parts.len() == 1 {
@@ -279,6 +475,28 @@ example service:tcp:127.0.0.1:7878:table_do_my_test"
             }
             bail!("We should have one, two or three parts");
         }
+        #[cfg(feature = "redb")]
+        if let Some(s) = input.strip_prefix(REDB) {
+            if s.is_empty() {
+                bail!(
+                    "For redb, the formatting has to be redb:directory or redb:directory:namespace"
+                );
+            }
+            let parts = s.split(':').collect::<Vec<_>>();
+            if parts.len() > 2 {
+                bail!("We should have one or two parts");
+            }
+            let path = parts[0].to_string().into();
+            let namespace = match parts.get(1) {
+                Some(namespace) => namespace.to_string(),
+                None => DEFAULT_NAMESPACE.to_string(),
+            };
+            let inner_storage_config = InnerStorageConfig::Redb { path };
+            return Ok(StorageConfig {
+                inner_storage_config,
+                namespace,
+            });
+        }
         #[cfg(feature = "dynamodb")]
         if let Some(s) = input.strip_prefix(DYNAMO_DB) {
             let mut parts = s.splitn(2, ':');
@@ -350,11 +568,19 @@ example service:tcp:127.0.0.1:7878:table_do_my_test"
             });
         }
         #[cfg(all(feature = "rocksdb", feature = "scylladb"))]
-        if let Some(s) = input.strip_prefix(DUAL_ROCKS_DB_SCYLLA_DB) {
+        if let Some((s, dual_writes)) = input
+            .strip_prefix(DUAL_ROCKS_DB_SCYLLA_DB)
+            .map(|s| (s, false))
+            .or_else(|| {
+                input
+                    .strip_prefix(DUAL_WRITE_ROCKS_DB_SCYLLA_DB)
+                    .map(|s| (s, true))
+            })
+        {
             let parts = s.split(':').collect::<Vec<_>>();
             if parts.len() != 5 && parts.len() != 6 {
                 bail!(
-                    "For DualRocksDbScyllaDb, the formatting has to be dualrocksdbscylladb:directory:mode:tcp:hostname:port:namespace"
+                    "For DualRocksDbScyllaDb, the formatting has to be dualrocksdbscylladb:directory:mode:tcp:hostname:port:namespace, or dualwriterocksdbscylladb:... to write to both databases"
                 );
             }
             let path = Path::new(parts[0]);
@@ -379,6 +605,7 @@ example service:tcp:127.0.0.1:7878:table_do_my_test"
                 path_with_guard,
                 spawn_mode,
                 uri,
+                dual_writes,
             };
             let namespace = if parts.len() == 5 {
                 DEFAULT_NAMESPACE.to_string()
@@ -395,12 +622,16 @@ example service:tcp:127.0.0.1:7878:table_do_my_test"
         error!("Also available is linera-storage-service");
         #[cfg(feature = "rocksdb")]
         error!("Also available is RocksDB");
+        #[cfg(feature = "redb")]
+        error!("Also available is redb");
         #[cfg(feature = "dynamodb")]
         error!("Also available is DynamoDB");
         #[cfg(feature = "scylladb")]
         error!("Also available is ScyllaDB");
         #[cfg(all(feature = "rocksdb", feature = "scylladb"))]
-        error!("Also available is DualRocksDbScyllaDb");
+        error!("Also available is DualRocksDbScyllaDb, with or without dual writes");
+        error!("Any storage other than memory can be prefixed with {COMPRESSED}algorithm:");
+        error!("Any storage other than memory can be prefixed with {ENCRYPTED}");
         Err(anyhow!("The input has not matched: {input}"))
     }
 }
@@ -410,9 +641,7 @@ impl StorageConfig {
         match &mut self.inner_storage_config {
             #[cfg(all(feature = "rocksdb", feature = "scylladb"))]
             InnerStorageConfig::DualRocksDbScyllaDb {
-                path_with_guard,
-                spawn_mode: _,
-                uri: _,
+                path_with_guard, ..
             } => {
                 let shard_str = format!("shard_{}", _shard);
                 path_with_guard.path_buf.push(shard_str);
@@ -461,6 +690,7 @@ impl StorageConfig {
                     spawn_mode: *spawn_mode,
                     path_with_guard,
                     max_stream_queries: options.storage_max_stream_queries,
+                    secondary: false,
                 };
                 let config = RocksDbStoreConfig {
                     inner_config,
@@ -468,6 +698,19 @@ impl StorageConfig {
                 };
                 Ok(StoreConfig::RocksDb { config, namespace })
             }
+            #[cfg(feature = "redb")]
+            InnerStorageConfig::Redb { path } => {
+                let path_with_guard = PathWithGuard::new(path.to_path_buf());
+                let inner_config = RedbStoreInternalConfig {
+                    path_with_guard,
+                    max_stream_queries: options.storage_max_stream_queries,
+                };
+                let config = RedbStoreConfig {
+                    inner_config,
+                    storage_cache_config: options.storage_cache_config(),
+                };
+                Ok(StoreConfig::Redb { config, namespace })
+            }
             #[cfg(feature = "dynamodb")]
             InnerStorageConfig::DynamoDb { use_dynamodb_local } => {
                 let inner_config = DynamoDbStoreInternalConfig {
@@ -500,11 +743,13 @@ impl StorageConfig {
                 path_with_guard,
                 spawn_mode,
                 uri,
+                dual_writes,
             } => {
                 let inner_config = RocksDbStoreInternalConfig {
                     spawn_mode: *spawn_mode,
                     path_with_guard: path_with_guard.clone(),
                     max_stream_queries: options.storage_max_stream_queries,
+                    secondary: false,
                 };
                 let first_config = RocksDbStoreConfig {
                     inner_config,
@@ -526,7 +771,35 @@ impl StorageConfig {
                     first_config,
                     second_config,
                 };
-                Ok(StoreConfig::DualRocksDbScyllaDb { config, namespace })
+                Ok(StoreConfig::DualRocksDbScyllaDb {
+                    config,
+                    namespace,
+                    dual_writes: *dual_writes,
+                })
+            }
+            InnerStorageConfig::Compressed { algorithm, inner } => {
+                let inner = StorageConfig {
+                    inner_storage_config: (**inner).clone(),
+                    namespace,
+                };
+                let config = inner.add_common_storage_options(options)?;
+                let compression_config = options.storage_compression_config(*algorithm);
+                Ok(StoreConfig::Compressed {
+                    compression_config,
+                    config: Box::new(config),
+                })
+            }
+            InnerStorageConfig::Encrypted { inner } => {
+                let encryption_config = options.storage_encryption_config(&namespace)?;
+                let inner = StorageConfig {
+                    inner_storage_config: (**inner).clone(),
+                    namespace,
+                };
+                let config = inner.add_common_storage_options(options)?;
+                Ok(StoreConfig::Encrypted {
+                    encryption_config,
+                    config: Box::new(config),
+                })
             }
         }
     }
@@ -548,6 +821,10 @@ impl fmt::Display for StorageConfig {
                 let spawn_mode = spawn_mode.to_string();
                 write!(f, "rocksdb:{}:{}:{}", path.display(), spawn_mode, namespace)
             }
+            #[cfg(feature = "redb")]
+            InnerStorageConfig::Redb { path } => {
+                write!(f, "redb:{}:{}", path.display(), namespace)
+            }
             #[cfg(feature = "dynamodb")]
             InnerStorageConfig::DynamoDb { use_dynamodb_local } => match use_dynamodb_local {
                 true => write!(f, "dynamodb:{}:dynamodb_local", namespace),
@@ -562,16 +839,37 @@ impl fmt::Display for StorageConfig {
                 path_with_guard,
                 spawn_mode,
                 uri,
+                dual_writes,
             } => {
+                let prefix = if *dual_writes {
+                    DUAL_WRITE_ROCKS_DB_SCYLLA_DB
+                } else {
+                    DUAL_ROCKS_DB_SCYLLA_DB
+                };
                 write!(
                     f,
-                    "dualrocksdbscylladb:{}:{}:tcp:{}:{}",
+                    "{}{}:{}:tcp:{}:{}",
+                    prefix,
                     path_with_guard.path_buf.display(),
                     spawn_mode,
                     uri,
                     namespace
                 )
             }
+            InnerStorageConfig::Compressed { algorithm, inner } => {
+                let inner = StorageConfig {
+                    inner_storage_config: (**inner).clone(),
+                    namespace: namespace.clone(),
+                };
+                write!(f, "compressed:{}:{}", algorithm, inner)
+            }
+            InnerStorageConfig::Encrypted { inner } => {
+                let inner = StorageConfig {
+                    inner_storage_config: (**inner).clone(),
+                    namespace: namespace.clone(),
+                };
+                write!(f, "encrypted:{}", inner)
+            }
         }
     }
 }
@@ -601,13 +899,46 @@ pub trait RunnableWithStore {
 }
 
 impl StoreConfig {
+    /// Opens the RocksDB databases of this storage as uncached secondary instances. They
+    /// can then be read while another process writes to them, and every read catches up
+    /// with these writes.
+    pub fn with_rocksdb_secondary(self) -> Self {
+        match self {
+            #[cfg(feature = "rocksdb")]
+            StoreConfig::RocksDb {
+                mut config,
+                namespace,
+            } => {
+                config.inner_config.secondary = true;
+                config.storage_cache_config.max_cache_entries = 0;
+                StoreConfig::RocksDb { config, namespace }
+            }
+            StoreConfig::Compressed {
+                compression_config,
+                config,
+            } => StoreConfig::Compressed {
+                compression_config,
+                config: Box::new(config.with_rocksdb_secondary()),
+            },
+            StoreConfig::Encrypted {
+                encryption_config,
+                config,
+            } => StoreConfig::Encrypted {
+                encryption_config,
+                config: Box::new(config.with_rocksdb_secondary()),
+            },
+            config => config,
+        }
+    }
+
     pub async fn run_with_storage<Job>(
         self,
         wasm_runtime: Option<WasmRuntime>,
+        execution_runtime_config: ExecutionRuntimeConfig,
         job: Job,
     ) -> Result<Job::Output, anyhow::Error>
     where
-        Job: Runnable,
+        Job: Runnable + Send,
     {
         match self {
             StoreConfig::Memory {
@@ -620,59 +951,74 @@ impl StoreConfig {
                     &namespace,
                     wasm_runtime,
                 )
-                .await?;
+                .await?
+                .with_execution_runtime_config(execution_runtime_config);
                 let genesis_config = crate::util::read_json::<GenesisConfig>(genesis_path)?;
                 // Memory storage must be initialized every time.
                 genesis_config.initialize_storage(&mut storage).await?;
                 Ok(job.run(storage).await)
             }
-            #[cfg(feature = "storage-service")]
-            StoreConfig::StorageService { config, namespace } => {
-                let storage = DbStorage::<StorageServiceDatabase, _>::connect(
-                    &config,
-                    &namespace,
+            config => {
+                let job = StorageJob {
                     wasm_runtime,
-                )
-                .await?;
-                Ok(job.run(storage).await)
-            }
-            #[cfg(feature = "rocksdb")]
-            StoreConfig::RocksDb { config, namespace } => {
-                let storage =
-                    DbStorage::<RocksDbDatabase, _>::connect(&config, &namespace, wasm_runtime)
-                        .await?;
-                Ok(job.run(storage).await)
-            }
-            #[cfg(feature = "dynamodb")]
-            StoreConfig::DynamoDb { config, namespace } => {
-                let storage =
-                    DbStorage::<DynamoDbDatabase, _>::connect(&config, &namespace, wasm_runtime)
-                        .await?;
-                Ok(job.run(storage).await)
-            }
-            #[cfg(feature = "scylladb")]
-            StoreConfig::ScyllaDb { config, namespace } => {
-                let storage =
-                    DbStorage::<ScyllaDbDatabase, _>::connect(&config, &namespace, wasm_runtime)
-                        .await?;
-                Ok(job.run(storage).await)
+                    execution_runtime_config,
+                    job,
+                };
+                config.run_with_store(job).await
             }
-            #[cfg(all(feature = "rocksdb", feature = "scylladb"))]
-            StoreConfig::DualRocksDbScyllaDb { config, namespace } => {
-                let storage = DbStorage::<
-                    DualDatabase<RocksDbDatabase, ScyllaDbDatabase, ChainStatesFirstAssignment>,
-                    _,
-                >::connect(&config, &namespace, wasm_runtime)
-                .await?;
-                Ok(job.run(storage).await)
+        }
+    }
+
+    pub async fn run_with_store<Job>(self, job: Job) -> Result<Job::Output, anyhow::Error>
+    where
+        Job: RunnableWithStore + Send,
+    {
+        match self {
+            StoreConfig::Compressed {
+                compression_config,
+                config,
+            } => match *config {
+                // Values are compressed before being encrypted.
+                StoreConfig::Encrypted {
+                    encryption_config,
+                    config,
+                } => {
+                    let job = CompressedJob {
+                        compression_config,
+                        job,
+                    };
+                    let job = EncryptedJob {
+                        encryption_config,
+                        job,
+                    };
+                    config.run_with_plain_store(job).await
+                }
+                config => {
+                    let job = CompressedJob {
+                        compression_config,
+                        job,
+                    };
+                    config.run_with_plain_store(job).await
+                }
+            },
+            StoreConfig::Encrypted {
+                encryption_config,
+                config,
+            } => {
+                let job = EncryptedJob {
+                    encryption_config,
+                    job,
+                };
+                config.run_with_plain_store(job).await
             }
+            config => config.run_with_plain_store(job).await,
         }
     }
 
     #[allow(unused_variables)]
-    pub async fn run_with_store<Job>(self, job: Job) -> Result<Job::Output, anyhow::Error>
+    async fn run_with_plain_store<Job>(self, job: Job) -> Result<Job::Output, anyhow::Error>
     where
-        Job: RunnableWithStore,
+        Job: RunnableWithStore + Send,
     {
         match self {
             StoreConfig::Memory { .. } => {
@@ -686,6 +1032,10 @@ impl StoreConfig {
             StoreConfig::RocksDb { config, namespace } => {
                 Ok(job.run::<RocksDbDatabase>(config, namespace).await?)
             }
+            #[cfg(feature = "redb")]
+            StoreConfig::Redb { config, namespace } => {
+                Ok(job.run::<RedbDatabase>(config, namespace).await?)
+            }
             #[cfg(feature = "dynamodb")]
             StoreConfig::DynamoDb { config, namespace } => {
                 Ok(job.run::<DynamoDbDatabase>(config, namespace).await?)
@@ -695,11 +1045,29 @@ impl StoreConfig {
                 Ok(job.run::<ScyllaDbDatabase>(config, namespace).await?)
             }
             #[cfg(all(feature = "rocksdb", feature = "scylladb"))]
-            StoreConfig::DualRocksDbScyllaDb { config, namespace } => Ok(job
+            StoreConfig::DualRocksDbScyllaDb {
+                config,
+                namespace,
+                dual_writes: false,
+            } => Ok(job
                 .run::<DualDatabase<RocksDbDatabase, ScyllaDbDatabase, ChainStatesFirstAssignment>>(
                     config, namespace,
                 )
                 .await?),
+            #[cfg(all(feature = "rocksdb", feature = "scylladb"))]
+            StoreConfig::DualRocksDbScyllaDb {
+                config,
+                namespace,
+                dual_writes: true,
+            } => Ok(job
+                .run::<DualDatabase<RocksDbDatabase, ScyllaDbDatabase, DualWriteAssignment>>(
+                    config, namespace,
+                )
+                .await?),
+            StoreConfig::Compressed { .. } | StoreConfig::Encrypted { .. } => Err(anyhow!(
+                "Compression and encryption are only supported on top of a database, with \
+the compression applied first"
+            )),
         }
     }
 
@@ -708,6 +1076,103 @@ impl StoreConfig {
     }
 }
 
+/// Runs a [`Runnable`] on a storage connected to the database given to it.
+struct StorageJob<Job> {
+    wasm_runtime: Option<WasmRuntime>,
+    execution_runtime_config: ExecutionRuntimeConfig,
+    job: Job,
+}
+
+#[async_trait]
+impl<Job> RunnableWithStore for StorageJob<Job>
+where
+    Job: Runnable + Send,
+{
+    type Output = Job::Output;
+
+    async fn run<D>(
+        self,
+        config: D::Config,
+        namespace: String,
+    ) -> Result<Self::Output, anyhow::Error>
+    where
+        D: KeyValueDatabase + Clone + Send + Sync + 'static,
+        D::Store: KeyValueStore + Clone + Send + Sync + 'static,
+        D::Error: Send + Sync,
+    {
+        let storage = DbStorage::<D, _>::connect(&config, &namespace, self.wasm_runtime)
+            .await?
+            .with_execution_runtime_config(self.execution_runtime_config);
+        Ok(self.job.run(storage).await)
+    }
+}
+
+/// Runs a job on the database given to it, with its values compressed.
+struct CompressedJob<Job> {
+    compression_config: StorageCompressionConfig,
+    job: Job,
+}
+
+#[async_trait]
+impl<Job> RunnableWithStore for CompressedJob<Job>
+where
+    Job: RunnableWithStore + Send,
+{
+    type Output = Job::Output;
+
+    async fn run<D>(
+        self,
+        config: D::Config,
+        namespace: String,
+    ) -> Result<Self::Output, anyhow::Error>
+    where
+        D: KeyValueDatabase + Clone + Send + Sync + 'static,
+        D::Store: KeyValueStore + Clone + Send + Sync + 'static,
+        D::Error: Send + Sync,
+    {
+        let config = CompressionConfig {
+            inner_config: config,
+            storage_compression_config: self.compression_config,
+        };
+        self.job
+            .run::<CompressionDatabase<D>>(config, namespace)
+            .await
+    }
+}
+
+/// Runs a job on the database given to it, with its keys and values encrypted.
+struct EncryptedJob<Job> {
+    encryption_config: StorageEncryptionConfig,
+    job: Job,
+}
+
+#[async_trait]
+impl<Job> RunnableWithStore for EncryptedJob<Job>
+where
+    Job: RunnableWithStore + Send,
+{
+    type Output = Job::Output;
+
+    async fn run<D>(
+        self,
+        config: D::Config,
+        namespace: String,
+    ) -> Result<Self::Output, anyhow::Error>
+    where
+        D: KeyValueDatabase + Clone + Send + Sync + 'static,
+        D::Store: KeyValueStore + Clone + Send + Sync + 'static,
+        D::Error: Send + Sync,
+    {
+        let config = EncryptionConfig {
+            inner_config: config,
+            storage_encryption_config: self.encryption_config,
+        };
+        self.job
+            .run::<EncryptionDatabase<D>>(config, namespace)
+            .await
+    }
+}
+
 struct InitializeStorageJob<'a>(&'a GenesisConfig);
 
 #[async_trait]
@@ -806,6 +1271,127 @@ fn test_rocks_db_storage_config_from_str() {
     );
 }
 
+#[cfg(feature = "redb")]
+#[test]
+fn test_redb_storage_config_from_str() {
+    assert!(StorageConfig::from_str("redb:").is_err());
+    assert_eq!(
+        StorageConfig::from_str("redb:foo_dir").unwrap(),
+        StorageConfig {
+            inner_storage_config: InnerStorageConfig::Redb {
+                path: "foo_dir".into(),
+            },
+            namespace: DEFAULT_NAMESPACE.to_string()
+        }
+    );
+    let config = StorageConfig::from_str("redb:foo_dir:chosen_namespace").unwrap();
+    assert_eq!(
+        config,
+        StorageConfig {
+            inner_storage_config: InnerStorageConfig::Redb {
+                path: "foo_dir".into(),
+            },
+            namespace: "chosen_namespace".into()
+        }
+    );
+    assert_eq!(
+        StorageConfig::from_str(&config.to_string()).unwrap(),
+        config
+    );
+    assert!(StorageConfig::from_str("redb:foo_dir:chosen_namespace:extra").is_err());
+}
+
+#[cfg(feature = "rocksdb")]
+#[test]
+fn test_compressed_storage_config_from_str() {
+    let config = StorageConfig::from_str("compressed:lz4:rocksdb:foo.db").unwrap();
+    assert_eq!(
+        config,
+        StorageConfig {
+            inner_storage_config: InnerStorageConfig::Compressed {
+                algorithm: CompressionAlgorithm::Lz4,
+                inner: Box::new(InnerStorageConfig::RocksDb {
+                    path: "foo.db".into(),
+                    spawn_mode: RocksDbSpawnMode::SpawnBlocking,
+                }),
+            },
+            namespace: DEFAULT_NAMESPACE.to_string()
+        }
+    );
+    assert_eq!(
+        StorageConfig::from_str(&config.to_string()).unwrap(),
+        config
+    );
+    assert_eq!(
+        StorageConfig::from_str(
+            "compressed:zstd-19:rocksdb:foo.db:block_in_place:chosen_namespace"
+        )
+        .unwrap(),
+        StorageConfig {
+            inner_storage_config: InnerStorageConfig::Compressed {
+                algorithm: CompressionAlgorithm::Zstd { level: 19 },
+                inner: Box::new(InnerStorageConfig::RocksDb {
+                    path: "foo.db".into(),
+                    spawn_mode: RocksDbSpawnMode::BlockInPlace,
+                }),
+            },
+            namespace: "chosen_namespace".into()
+        }
+    );
+    assert!(StorageConfig::from_str("compressed:lz4").is_err());
+    assert!(StorageConfig::from_str("compressed:gzip:rocksdb:foo.db").is_err());
+    assert!(StorageConfig::from_str("compressed:lz4:memory:genesis.json").is_err());
+    assert!(StorageConfig::from_str("compressed:lz4:compressed:lz4:rocksdb:foo.db").is_err());
+}
+
+#[cfg(feature = "rocksdb")]
+#[test]
+fn test_encrypted_storage_config_from_str() {
+    let config =
+        StorageConfig::from_str("encrypted:rocksdb:foo.db:block_in_place:chosen_namespace")
+            .unwrap();
+    assert_eq!(
+        config,
+        StorageConfig {
+            inner_storage_config: InnerStorageConfig::Encrypted {
+                inner: Box::new(InnerStorageConfig::RocksDb {
+                    path: "foo.db".into(),
+                    spawn_mode: RocksDbSpawnMode::BlockInPlace,
+                }),
+            },
+            namespace: "chosen_namespace".into()
+        }
+    );
+    assert_eq!(
+        StorageConfig::from_str(&config.to_string()).unwrap(),
+        config
+    );
+    assert!(StorageConfig::from_str("encrypted:memory:genesis.json").is_err());
+    assert!(StorageConfig::from_str("encrypted:encrypted:rocksdb:foo.db").is_err());
+    assert!(StorageConfig::from_str("encrypted:compressed:lz4:rocksdb:foo.db").is_err());
+    assert!(StorageConfig::from_str("compressed:lz4:encrypted:encrypted:rocksdb:foo.db").is_err());
+    let config = StorageConfig::from_str("compressed:lz4:encrypted:rocksdb:foo.db").unwrap();
+    assert_eq!(
+        config,
+        StorageConfig {
+            inner_storage_config: InnerStorageConfig::Compressed {
+                algorithm: CompressionAlgorithm::Lz4,
+                inner: Box::new(InnerStorageConfig::Encrypted {
+                    inner: Box::new(InnerStorageConfig::RocksDb {
+                        path: "foo.db".into(),
+                        spawn_mode: RocksDbSpawnMode::SpawnBlocking,
+                    }),
+                }),
+            },
+            namespace: DEFAULT_NAMESPACE.to_string()
+        }
+    );
+    assert_eq!(
+        StorageConfig::from_str(&config.to_string()).unwrap(),
+        config
+    );
+}
+
 #[cfg(feature = "dynamodb")]
 #[test]
 fn test_aws_storage_config_from_str() {
@@ -879,3 +1465,42 @@ fn test_scylla_db_storage_config_from_str() {
     assert!(StorageConfig::from_str("scylladb:tcp:address1:tcp:/address2").is_err());
     assert!(StorageConfig::from_str("scylladb:wrong").is_err());
 }
+
+#[cfg(all(feature = "rocksdb", feature = "scylladb"))]
+#[test]
+fn test_dual_rocks_db_scylla_db_storage_config_from_str() {
+    let input = "dualrocksdbscylladb:foo.db:spawn_blocking:tcp:db_hostname:230:linera";
+    let storage_config = StorageConfig::from_str(input).unwrap();
+    assert_eq!(
+        storage_config,
+        StorageConfig {
+            inner_storage_config: InnerStorageConfig::DualRocksDbScyllaDb {
+                path_with_guard: PathWithGuard::new("foo.db".into()),
+                spawn_mode: RocksDbSpawnMode::SpawnBlocking,
+                uri: "db_hostname:230".to_string(),
+                dual_writes: false,
+            },
+            namespace: "linera".to_string()
+        }
+    );
+    assert_eq!(storage_config.to_string(), input);
+    let input = "dualwriterocksdbscylladb:foo.db:spawn_blocking:tcp:db_hostname:230:linera";
+    let storage_config = StorageConfig::from_str(input).unwrap();
+    assert_eq!(
+        storage_config,
+        StorageConfig {
+            inner_storage_config: InnerStorageConfig::DualRocksDbScyllaDb {
+                path_with_guard: PathWithGuard::new("foo.db".into()),
+                spawn_mode: RocksDbSpawnMode::SpawnBlocking,
+                uri: "db_hostname:230".to_string(),
+                dual_writes: true,
+            },
+            namespace: "linera".to_string()
+        }
+    );
+    assert_eq!(storage_config.to_string(), input);
+    let input = format!("encrypted:{input}");
+    let storage_config = StorageConfig::from_str(&input).unwrap();
+    assert_eq!(storage_config.to_string(), input);
+    assert!(StorageConfig::from_str("dualwriterocksdbscylladb:foo.db:spawn_blocking").is_err());
+}
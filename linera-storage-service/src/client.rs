@@ -18,8 +18,12 @@ use linera_views::metering::MeteredDatabase;
 use linera_views::store::TestKeyValueDatabase;
 use linera_views::{
     batch::{Batch, WriteOperation},
+    common::copy_namespace,
     lru_caching::LruCachingDatabase,
-    store::{KeyValueDatabase, ReadableKeyValueStore, WithError, WritableKeyValueStore},
+    store::{
+        Direction, KeyValueDatabase, RangeQuery, ReadableKeyValueStore, WithError,
+        WritableKeyValueStore,
+    },
     FutureSyncExt as _,
 };
 use serde::de::DeserializeOwned;
@@ -37,9 +41,10 @@ use crate::{
         ReplyFindKeyValuesByPrefix, ReplyFindKeysByPrefix, ReplyListAll, ReplyListRootKeys,
         ReplyReadMultiValues, ReplyReadValue, ReplySpecificChunk, RequestContainsKey,
         RequestContainsKeys, RequestCreateNamespace, RequestDeleteNamespace,
-        RequestExistsNamespace, RequestFindKeyValuesByPrefix, RequestFindKeysByPrefix,
-        RequestListRootKeys, RequestReadMultiValues, RequestReadValue, RequestSpecificChunk,
-        RequestWriteBatchExtended, Statement,
+        RequestExistsNamespace, RequestFindKeyValuesByPrefix, RequestFindKeyValuesByRange,
+        RequestFindKeysByPrefix, RequestFindKeysByRange, RequestListRootKeys,
+        RequestReadMultiValues, RequestReadValue, RequestSpecificChunk, RequestWriteBatchExtended,
+        Statement,
     },
 };
 
@@ -278,6 +283,88 @@ impl ReadableKeyValueStore for StorageServiceStoreInternal {
             self.read_entries(message_index, num_chunks).await
         }
     }
+
+    async fn find_keys_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<Vec<u8>>, StorageServiceStoreError> {
+        ensure!(
+            key_prefix.len() <= MAX_KEY_SIZE,
+            StorageServiceStoreError::KeyTooLong
+        );
+        let mut full_key_prefix = self.start_key.clone();
+        full_key_prefix.extend(key_prefix);
+        let query = RequestFindKeysByRange {
+            key_prefix: full_key_prefix,
+            start: query.start.clone(),
+            end: query.end.clone(),
+            limit: query.limit.map(|limit| limit as u64),
+            reverse: query.direction == Direction::Reverse,
+        };
+        let request = tonic::Request::new(query);
+        let channel = self.channel.clone();
+        let mut client = StorageServiceClient::new(channel);
+        let _guard = self.acquire().await;
+        let response = client
+            .process_find_keys_by_range(request)
+            .make_sync()
+            .await?;
+        let response = response.into_inner();
+        let ReplyFindKeysByPrefix {
+            keys,
+            message_index,
+            num_chunks,
+        } = response;
+        if num_chunks == 0 {
+            Ok(keys)
+        } else {
+            self.read_entries(message_index, num_chunks).await
+        }
+    }
+
+    async fn find_key_values_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, StorageServiceStoreError> {
+        ensure!(
+            key_prefix.len() <= MAX_KEY_SIZE,
+            StorageServiceStoreError::KeyTooLong
+        );
+        let mut full_key_prefix = self.start_key.clone();
+        full_key_prefix.extend(key_prefix);
+        let query = RequestFindKeyValuesByRange {
+            key_prefix: full_key_prefix,
+            start: query.start.clone(),
+            end: query.end.clone(),
+            limit: query.limit.map(|limit| limit as u64),
+            reverse: query.direction == Direction::Reverse,
+        };
+        let request = tonic::Request::new(query);
+        let channel = self.channel.clone();
+        let mut client = StorageServiceClient::new(channel);
+        let _guard = self.acquire().await;
+        let response = client
+            .process_find_key_values_by_range(request)
+            .make_sync()
+            .await?;
+        let response = response.into_inner();
+        let ReplyFindKeyValuesByPrefix {
+            key_values,
+            message_index,
+            num_chunks,
+        } = response;
+        if num_chunks == 0 {
+            let key_values = key_values
+                .into_iter()
+                .map(|x| (x.key, x.value))
+                .collect::<Vec<_>>();
+            Ok(key_values)
+        } else {
+            self.read_entries(message_index, num_chunks).await
+        }
+    }
 }
 
 impl WritableKeyValueStore for StorageServiceStoreInternal {
@@ -588,6 +675,14 @@ impl KeyValueDatabase for StorageServiceDatabaseInternal {
         let _response = client.process_delete_namespace(request).make_sync().await?;
         Ok(())
     }
+
+    async fn snapshot(
+        config: &Self::Config,
+        namespace: &str,
+        snapshot: &str,
+    ) -> Result<(), StorageServiceStoreError> {
+        copy_namespace::<Self>(config, namespace, snapshot).await
+    }
 }
 
 #[cfg(with_testing)]
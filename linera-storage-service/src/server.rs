@@ -8,7 +8,9 @@ use linera_storage_service::common::{KeyPrefix, MAX_PAYLOAD_SIZE};
 use linera_views::{
     batch::Batch,
     memory::{MemoryDatabase, MemoryStoreConfig},
-    store::{KeyValueDatabase, ReadableKeyValueStore, WritableKeyValueStore},
+    store::{
+        Direction, KeyValueDatabase, RangeQuery, ReadableKeyValueStore, WritableKeyValueStore,
+    },
 };
 #[cfg(with_rocksdb)]
 use linera_views::{
@@ -30,8 +32,9 @@ use crate::key_value_store::{
     ReplyFindKeyValuesByPrefix, ReplyFindKeysByPrefix, ReplyListAll, ReplyListRootKeys,
     ReplyReadMultiValues, ReplyReadValue, ReplySpecificChunk, RequestContainsKey,
     RequestContainsKeys, RequestCreateNamespace, RequestDeleteNamespace, RequestExistsNamespace,
-    RequestFindKeyValuesByPrefix, RequestFindKeysByPrefix, RequestListRootKeys,
-    RequestReadMultiValues, RequestReadValue, RequestSpecificChunk, RequestWriteBatchExtended,
+    RequestFindKeyValuesByPrefix, RequestFindKeyValuesByRange, RequestFindKeysByPrefix,
+    RequestFindKeysByRange, RequestListRootKeys, RequestReadMultiValues, RequestReadValue,
+    RequestSpecificChunk, RequestWriteBatchExtended,
 };
 
 pub mod key_value_store {
@@ -57,6 +60,25 @@ struct PendingBigReads {
     big_reads: BTreeMap<i64, BigRead>,
 }
 
+/// Rebuilds a range query from the fields of a request.
+fn range_query(
+    start: Option<Vec<u8>>,
+    end: Option<Vec<u8>>,
+    limit: Option<u64>,
+    reverse: bool,
+) -> RangeQuery {
+    RangeQuery {
+        start,
+        end,
+        limit: limit.map(|limit| usize::try_from(limit).unwrap_or(usize::MAX)),
+        direction: if reverse {
+            Direction::Reverse
+        } else {
+            Direction::Forward
+        },
+    }
+}
+
 struct StorageServer {
     store: LocalStore,
     pending_big_puts: Arc<RwLock<BTreeMap<Vec<u8>, Vec<u8>>>>,
@@ -164,6 +186,52 @@ impl StorageServer {
         }
     }
 
+    pub async fn find_keys_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<Vec<u8>>, Status> {
+        match &self.store {
+            LocalStore::Memory(store) => {
+                store
+                    .find_keys_by_range(key_prefix, query)
+                    .await
+                    .map_err(|e| {
+                        Status::unknown(format!("Memory error {:?} at find_keys_by_range", e))
+                    })
+            }
+            #[cfg(with_rocksdb)]
+            LocalStore::RocksDb(store) => store
+                .find_keys_by_range(key_prefix, query)
+                .await
+                .map_err(|e| {
+                    Status::unknown(format!("RocksDB error {:?} at find_keys_by_range", e))
+                }),
+        }
+    }
+
+    pub async fn find_key_values_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Status> {
+        match &self.store {
+            LocalStore::Memory(store) => store
+                .find_key_values_by_range(key_prefix, query)
+                .await
+                .map_err(|e| {
+                    Status::unknown(format!("Memory error {:?} at find_key_values_by_range", e))
+                }),
+            #[cfg(with_rocksdb)]
+            LocalStore::RocksDb(store) => store
+                .find_key_values_by_range(key_prefix, query)
+                .await
+                .map_err(|e| {
+                    Status::unknown(format!("RocksDB error {:?} at find_key_values_by_range", e))
+                }),
+        }
+    }
+
     pub async fn write_batch(&self, batch: Batch) -> Result<(), Status> {
         match &self.store {
             LocalStore::Memory(store) => store
@@ -444,6 +512,82 @@ impl StorageService for StorageServer {
         Ok(Response::new(response))
     }
 
+    #[instrument(target = "store_server", skip_all, err, fields(key_prefix_len = ?request.get_ref().key_prefix.len()))]
+    async fn process_find_keys_by_range(
+        &self,
+        request: Request<RequestFindKeysByRange>,
+    ) -> Result<Response<ReplyFindKeysByPrefix>, Status> {
+        let request = request.into_inner();
+        let RequestFindKeysByRange {
+            key_prefix,
+            start,
+            end,
+            limit,
+            reverse,
+        } = request;
+        let query = range_query(start, end, limit, reverse);
+        let keys = self.find_keys_by_range(&key_prefix, &query).await?;
+        let size = keys.iter().map(|x| x.len()).sum::<usize>();
+        let response = if size < MAX_PAYLOAD_SIZE {
+            ReplyFindKeysByPrefix {
+                keys,
+                message_index: 0,
+                num_chunks: 0,
+            }
+        } else {
+            let (message_index, num_chunks) = self.insert_pending_read(keys).await;
+            ReplyFindKeysByPrefix {
+                keys: Vec::default(),
+                message_index,
+                num_chunks,
+            }
+        };
+        Ok(Response::new(response))
+    }
+
+    #[instrument(target = "store_server", skip_all, err, fields(key_prefix_len = ?request.get_ref().key_prefix.len()))]
+    async fn process_find_key_values_by_range(
+        &self,
+        request: Request<RequestFindKeyValuesByRange>,
+    ) -> Result<Response<ReplyFindKeyValuesByPrefix>, Status> {
+        let request = request.into_inner();
+        let RequestFindKeyValuesByRange {
+            key_prefix,
+            start,
+            end,
+            limit,
+            reverse,
+        } = request;
+        let query = range_query(start, end, limit, reverse);
+        let key_values = self.find_key_values_by_range(&key_prefix, &query).await?;
+        let size = key_values
+            .iter()
+            .map(|x| x.0.len() + x.1.len())
+            .sum::<usize>();
+        let response = if size < MAX_PAYLOAD_SIZE {
+            let key_values = key_values
+                .into_iter()
+                .map(|x| KeyValue {
+                    key: x.0,
+                    value: x.1,
+                })
+                .collect::<Vec<_>>();
+            ReplyFindKeyValuesByPrefix {
+                key_values,
+                message_index: 0,
+                num_chunks: 0,
+            }
+        } else {
+            let (message_index, num_chunks) = self.insert_pending_read(key_values).await;
+            ReplyFindKeyValuesByPrefix {
+                key_values: Vec::default(),
+                message_index,
+                num_chunks,
+            }
+        };
+        Ok(Response::new(response))
+    }
+
     #[instrument(target = "store_server", skip_all, err, fields(n_statements = ?request.get_ref().statements.len()))]
     async fn process_write_batch_extended(
         &self,
@@ -657,6 +801,7 @@ async fn main() {
                 spawn_mode,
                 path_with_guard,
                 max_stream_queries,
+                secondary: false,
             };
             let storage_cache_config = StorageCacheConfig {
                 max_cache_size,
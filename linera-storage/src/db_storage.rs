@@ -16,7 +16,7 @@ use linera_chain::{
     ChainStateView,
 };
 use linera_execution::{
-    BlobState, ExecutionRuntimeConfig, UserContractCode, UserServiceCode, WasmRuntime,
+    BlobState, ExecutionRuntimeConfig, FuelProfile, UserContractCode, UserServiceCode, WasmRuntime,
 };
 use linera_views::{
     backends::dual::{DualStoreRootKeyAssignment, StoreInUse},
@@ -305,6 +305,7 @@ pub struct DbStorage<Database, Clock = WallClock> {
     user_contracts: Arc<papaya::HashMap<ApplicationId, UserContractCode>>,
     user_services: Arc<papaya::HashMap<ApplicationId, UserServiceCode>>,
     execution_runtime_config: ExecutionRuntimeConfig,
+    fuel_profile: Option<FuelProfile>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -963,6 +964,15 @@ where
         self.wasm_runtime
     }
 
+    fn fuel_profile(&self) -> Option<FuelProfile> {
+        self.fuel_profile.clone()
+    }
+
+    fn with_fuel_profile(mut self, profile: FuelProfile) -> Self {
+        self.fuel_profile = Some(profile);
+        self
+    }
+
     #[instrument(target = "telemetry_only", skip_all)]
     async fn block_exporter_context(
         &self,
@@ -1049,8 +1059,18 @@ impl<Database, C> DbStorage<Database, C> {
             user_contracts: Arc::new(papaya::HashMap::new()),
             user_services: Arc::new(papaya::HashMap::new()),
             execution_runtime_config: ExecutionRuntimeConfig::default(),
+            fuel_profile: None,
         }
     }
+
+    /// Sets the configuration of the runtime used to execute the chains of this storage.
+    pub fn with_execution_runtime_config(
+        mut self,
+        execution_runtime_config: ExecutionRuntimeConfig,
+    ) -> Self {
+        self.execution_runtime_config = execution_runtime_config;
+        self
+    }
 }
 
 impl<Database> DbStorage<Database, WallClock>
@@ -24,8 +24,8 @@ use linera_chain::{
 };
 use linera_execution::{
     committee::Committee, system::EPOCH_STREAM_NAME, BlobState, ExecutionError,
-    ExecutionRuntimeConfig, ExecutionRuntimeContext, TransactionTracker, UserContractCode,
-    UserServiceCode, WasmRuntime,
+    ExecutionRuntimeConfig, ExecutionRuntimeContext, FuelProfile, TransactionTracker,
+    UserContractCode, UserServiceCode, WasmRuntime,
 };
 #[cfg(with_revm)]
 use linera_execution::{
@@ -265,6 +265,16 @@ pub trait Storage: Sized {
     /// Selects the WebAssembly runtime to use for applications (if any).
     fn wasm_runtime(&self) -> Option<WasmRuntime>;
 
+    /// Returns the profile in which the fuel consumed by Wasm contracts is recorded, if any.
+    ///
+    /// Profiling is only supported by Wasmtime.
+    fn fuel_profile(&self) -> Option<FuelProfile>;
+
+    /// Records the fuel consumed by the Wasm contracts loaded from this storage in `profile`.
+    ///
+    /// Contracts that were already loaded are not profiled.
+    fn with_fuel_profile(self, profile: FuelProfile) -> Self;
+
     /// Creates a [`UserContractCode`] instance using the bytecode in storage referenced
     /// by the `application_description`.
     async fn load_contract(
@@ -301,6 +311,17 @@ pub trait Storage: Sized {
                         let Some(wasm_runtime) = self.wasm_runtime() else {
                             panic!("A Wasm runtime is required to load user applications.");
                         };
+                        #[cfg(with_wasmtime)]
+                        if let Some(profile) = self.fuel_profile() {
+                            if matches!(wasm_runtime, WasmRuntime::Wasmtime) {
+                                return Ok(WasmContractModule::from_wasmtime_with_fuel_profile(
+                                    contract_bytecode,
+                                    profile,
+                                )
+                                .await?
+                                .into());
+                            }
+                        }
                         Ok(WasmContractModule::new(contract_bytecode, wasm_runtime)
                            .await?
                            .into())
@@ -330,6 +330,83 @@ fn generate_crypto_hash_code(input: ItemStruct) -> TokenStream2 {
     }
 }
 
+fn generate_provable_fields_code(input: ItemStruct) -> TokenStream2 {
+    let Constraints {
+        input_constraints,
+        impl_generics,
+        type_generics,
+    } = Constraints::get(&input);
+    let struct_name = &input.ident;
+
+    let field_types = input.fields.iter().map(|field| &field.ty);
+    let names = input
+        .fields
+        .iter()
+        .map(|field| field.ident.as_ref().unwrap())
+        .collect::<Vec<_>>();
+    let name_strings = names.iter().map(|name| name.to_string());
+
+    quote! {
+        impl #impl_generics linera_views::views::proof::ProvableFieldsView for #struct_name #type_generics
+        where
+            #(#field_types: linera_views::views::HashableView,)*
+            #(#input_constraints,)*
+            Self: linera_views::views::View,
+        {
+            const FIELD_NAMES: &'static [&'static str] = &[#(#name_strings),*];
+
+            async fn field_hashes(&self) -> Result<Vec<Vec<u8>>, linera_views::ViewError> {
+                use linera_views::views::HashableView;
+                Ok(vec![#(self.#names.hash().await?.as_ref().to_vec()),*])
+            }
+
+            async fn prove_field(
+                &self,
+                name: &str,
+                proof: linera_views::views::proof::ViewProof,
+            ) -> Result<linera_views::views::proof::ViewProof, linera_views::ViewError> {
+                use linera_views::views::proof::ProvableFieldsView;
+                let field_hashes = self.field_hashes().await?;
+                proof.with_field(Self::FIELD_NAMES, field_hashes, name)
+            }
+        }
+    }
+}
+
+fn generate_provable_crypto_hash_code(input: ItemStruct) -> TokenStream2 {
+    let Constraints {
+        input_constraints,
+        impl_generics,
+        type_generics,
+    } = Constraints::get(&input);
+    let field_types = input.fields.iter().map(|field| &field.ty);
+    let struct_name = &input.ident;
+    let hash_type = syn::Ident::new(&format!("{struct_name}Hash"), Span::call_site());
+    quote! {
+        impl #impl_generics linera_views::views::proof::ProvableCryptoHashView
+        for #struct_name #type_generics
+        where
+            #(#field_types: linera_views::views::HashableView,)*
+            #(#input_constraints,)*
+            Self: linera_views::views::View,
+        {
+            fn crypto_hash_from_hash(hash: &[u8]) -> Option<linera_base::crypto::CryptoHash> {
+                use linera_base::crypto::{BcsHashable, CryptoHash};
+                use linera_views::{
+                    generic_array::GenericArray,
+                    sha3::{digest::OutputSizeUser, Sha3_256},
+                };
+                use serde::{Serialize, Deserialize};
+                #[derive(Serialize, Deserialize)]
+                struct #hash_type(GenericArray<u8, <Sha3_256 as OutputSizeUser>::OutputSize>);
+                impl<'de> BcsHashable<'de> for #hash_type {}
+                let hash = GenericArray::from_exact_iter(hash.iter().copied())?;
+                Some(CryptoHash::new(&#hash_type(hash)))
+            }
+        }
+    }
+}
+
 fn generate_clonable_view_code(input: ItemStruct) -> TokenStream2 {
     let Constraints {
         input_constraints,
@@ -374,7 +451,8 @@ pub fn derive_view(input: TokenStream) -> TokenStream {
 pub fn derive_hash_view(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as ItemStruct);
     let mut stream = generate_view_code(input.clone(), false);
-    stream.extend(generate_hash_view_code(input));
+    stream.extend(generate_hash_view_code(input.clone()));
+    stream.extend(generate_provable_fields_code(input));
     stream.into()
 }
 
@@ -391,7 +469,9 @@ pub fn derive_crypto_hash_view(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as ItemStruct);
     let mut stream = generate_view_code(input.clone(), false);
     stream.extend(generate_hash_view_code(input.clone()));
-    stream.extend(generate_crypto_hash_code(input));
+    stream.extend(generate_provable_fields_code(input.clone()));
+    stream.extend(generate_crypto_hash_code(input.clone()));
+    stream.extend(generate_provable_crypto_hash_code(input));
     stream.into()
 }
 
@@ -401,7 +481,9 @@ pub fn derive_crypto_hash_root_view(input: TokenStream) -> TokenStream {
     let mut stream = generate_view_code(input.clone(), true);
     stream.extend(generate_root_view_code(input.clone()));
     stream.extend(generate_hash_view_code(input.clone()));
-    stream.extend(generate_crypto_hash_code(input));
+    stream.extend(generate_provable_fields_code(input.clone()));
+    stream.extend(generate_crypto_hash_code(input.clone()));
+    stream.extend(generate_provable_crypto_hash_code(input));
     stream.into()
 }
 
@@ -411,7 +493,8 @@ pub fn derive_hashable_root_view(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as ItemStruct);
     let mut stream = generate_view_code(input.clone(), true);
     stream.extend(generate_root_view_code(input.clone()));
-    stream.extend(generate_hash_view_code(input));
+    stream.extend(generate_hash_view_code(input.clone()));
+    stream.extend(generate_provable_fields_code(input));
     stream.into()
 }
 
@@ -9,7 +9,10 @@ fn main() {
         with_dynamodb: { all(not(target_arch = "wasm32"), feature = "dynamodb") },
         with_indexeddb: { all(web, feature = "indexeddb") },
         with_rocksdb: { all(not(target_arch = "wasm32"), feature = "rocksdb") },
+        with_redb: { all(not(target_arch = "wasm32"), feature = "redb") },
         with_scylladb: { all(not(target_arch = "wasm32"), feature = "scylladb") },
         with_graphql: { not(web) },
+        with_zstd: { not(target_arch = "wasm32") },
+        with_encryption: { any(not(target_arch = "wasm32"), web) },
     };
 }
@@ -0,0 +1,434 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Exports a namespace of a [`KeyValueDatabase`] to a portable archive, and imports it
+//! back into any backend.
+//!
+//! An archive starts with a header made of [`ARCHIVE_MAGIC`] and a version byte. It is
+//! followed by a sequence of records, each prefixed by its length as a little-endian
+//! `u32`: a root key record starts the entries of a root key, and the final record holds
+//! the number of entries and the SHA3-256 checksum of all the preceding bytes.
+
+use std::{
+    io::{Read, Write},
+    pin::pin,
+};
+
+use futures::TryStreamExt as _;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest as _, Sha3_256};
+use thiserror::Error;
+
+use crate::{
+    batch::Batch,
+    store::{KeyValueDatabase, KeyValueStore, KeyValueStoreError, ReadableKeyValueStore},
+    ViewError,
+};
+
+/// The bytes starting every archive.
+pub const ARCHIVE_MAGIC: &[u8; 8] = b"LINERAKV";
+
+/// The version of the archive format.
+const ARCHIVE_VERSION: u8 = 1;
+
+/// The number of entries read and written at a time.
+const PAGE_SIZE: usize = 1000;
+
+/// The records following the header of an archive.
+#[derive(Debug, Serialize, Deserialize)]
+enum ArchiveRecord {
+    /// Starts the entries of a root key.
+    RootKey(Vec<u8>),
+    /// An entry of the current root key.
+    Entry { key: Vec<u8>, value: Vec<u8> },
+    /// Ends the archive. The checksum is not part of the checksummed bytes.
+    End {
+        num_entries: u64,
+        checksum: [u8; 32],
+    },
+}
+
+/// The content of an archive.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ArchiveSummary {
+    /// The number of root keys.
+    pub num_root_keys: u64,
+    /// The total number of entries.
+    pub num_entries: u64,
+}
+
+/// The error type for the archives.
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    /// An error from the database.
+    #[error(transparent)]
+    ViewError(#[from] ViewError),
+
+    /// Input output error.
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    /// Serialization error with BCS.
+    #[error(transparent)]
+    BcsError(#[from] bcs::Error),
+
+    /// The header of the archive is invalid.
+    #[error("this is not a storage archive")]
+    InvalidHeader,
+
+    /// The archive was written by an unsupported version.
+    #[error("unsupported archive version {0}")]
+    UnsupportedVersion(u8),
+
+    /// A record is too long to be written.
+    #[error("the archive record is too long")]
+    RecordTooLong,
+
+    /// The archive ends before its final record.
+    #[error("the archive is truncated")]
+    Truncated,
+
+    /// The records are not in the expected order.
+    #[error("unexpected record in the archive")]
+    UnexpectedRecord,
+
+    /// The archive does not match its checksum.
+    #[error("the archive is corrupted: checksum mismatch")]
+    ChecksumMismatch,
+}
+
+impl<E: KeyValueStoreError> From<E> for ArchiveError {
+    fn from(error: E) -> Self {
+        Self::ViewError(error.into())
+    }
+}
+
+/// Writes the records of an archive while computing its checksum.
+struct ArchiveWriter<W> {
+    writer: W,
+    hasher: Sha3_256,
+    summary: ArchiveSummary,
+}
+
+impl<W: Write> ArchiveWriter<W> {
+    fn new(writer: W) -> Result<Self, ArchiveError> {
+        let mut archive = Self {
+            writer,
+            hasher: Sha3_256::new(),
+            summary: ArchiveSummary::default(),
+        };
+        archive.write_bytes(ARCHIVE_MAGIC)?;
+        archive.write_bytes(&[ARCHIVE_VERSION])?;
+        Ok(archive)
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), ArchiveError> {
+        self.hasher.update(bytes);
+        self.writer.write_all(bytes)?;
+        Ok(())
+    }
+
+    fn write_record(&mut self, record: &ArchiveRecord) -> Result<(), ArchiveError> {
+        match record {
+            ArchiveRecord::RootKey(_) => self.summary.num_root_keys += 1,
+            ArchiveRecord::Entry { .. } => self.summary.num_entries += 1,
+            ArchiveRecord::End { .. } => {}
+        }
+        let bytes = bcs::to_bytes(record)?;
+        let len = u32::try_from(bytes.len()).map_err(|_| ArchiveError::RecordTooLong)?;
+        self.write_bytes(&len.to_le_bytes())?;
+        self.write_bytes(&bytes)
+    }
+
+    fn finish(mut self) -> Result<ArchiveSummary, ArchiveError> {
+        let checksum = self.hasher.clone().finalize().into();
+        let num_entries = self.summary.num_entries;
+        self.write_record(&ArchiveRecord::End {
+            num_entries,
+            checksum,
+        })?;
+        self.writer.flush()?;
+        Ok(self.summary)
+    }
+}
+
+/// Reads the records of an archive while computing its checksum.
+struct ArchiveReader<R> {
+    reader: R,
+    hasher: Sha3_256,
+}
+
+impl<R: Read> ArchiveReader<R> {
+    fn new(reader: R) -> Result<Self, ArchiveError> {
+        let mut archive = Self {
+            reader,
+            hasher: Sha3_256::new(),
+        };
+        let mut magic = [0; ARCHIVE_MAGIC.len()];
+        archive.read_bytes(&mut magic)?;
+        if &magic != ARCHIVE_MAGIC {
+            return Err(ArchiveError::InvalidHeader);
+        }
+        let mut version = [0];
+        archive.read_bytes(&mut version)?;
+        if version[0] != ARCHIVE_VERSION {
+            return Err(ArchiveError::UnsupportedVersion(version[0]));
+        }
+        Ok(archive)
+    }
+
+    fn read_bytes(&mut self, bytes: &mut [u8]) -> Result<(), ArchiveError> {
+        self.reader.read_exact(bytes).map_err(|error| {
+            if error.kind() == std::io::ErrorKind::UnexpectedEof {
+                ArchiveError::Truncated
+            } else {
+                error.into()
+            }
+        })?;
+        self.hasher.update(bytes);
+        Ok(())
+    }
+
+    /// Reads the next record. The checksum of the final record is checked against the
+    /// preceding bytes.
+    fn read_record(&mut self) -> Result<ArchiveRecord, ArchiveError> {
+        let checksum: [u8; 32] = self.hasher.clone().finalize().into();
+        let mut len = [0; 4];
+        self.read_bytes(&mut len)?;
+        let len = u32::from_le_bytes(len) as usize;
+        // The length is not trusted before the checksum is verified, so the buffer grows
+        // with the bytes actually read.
+        let mut bytes = Vec::new();
+        (&mut self.reader)
+            .take(len as u64)
+            .read_to_end(&mut bytes)?;
+        if bytes.len() < len {
+            return Err(ArchiveError::Truncated);
+        }
+        self.hasher.update(&bytes);
+        let record = bcs::from_bytes(&bytes)?;
+        if let ArchiveRecord::End {
+            checksum: expected_checksum,
+            ..
+        } = &record
+        {
+            if *expected_checksum != checksum {
+                return Err(ArchiveError::ChecksumMismatch);
+            }
+        }
+        Ok(record)
+    }
+}
+
+/// Writes the namespace `namespace` of the database to `writer` as an archive, including
+/// all its root keys.
+///
+/// The entries are read root key by root key. To get a consistent archive of a namespace
+/// that is written to, export a [`KeyValueDatabase::snapshot`] of it.
+pub async fn export_namespace<D>(
+    config: &D::Config,
+    namespace: &str,
+    writer: impl Write,
+) -> Result<ArchiveSummary, ArchiveError>
+where
+    D: KeyValueDatabase,
+    D::Store: ReadableKeyValueStore,
+{
+    let root_keys = D::list_root_keys(config, namespace).await?;
+    let database = D::connect(config, namespace).await?;
+    let mut archive = ArchiveWriter::new(writer)?;
+    for root_key in root_keys {
+        let store = database.open_shared(&root_key)?;
+        archive.write_record(&ArchiveRecord::RootKey(root_key))?;
+        let mut entries = pin!(store.find_key_values_by_prefix_stream(&[], PAGE_SIZE));
+        while let Some((key, value)) = entries.try_next().await? {
+            archive.write_record(&ArchiveRecord::Entry { key, value })?;
+        }
+    }
+    archive.finish()
+}
+
+/// Creates the namespace `namespace` of the database and fills it with the content of
+/// the archive read from `reader`. Returns an error if the namespace exists.
+///
+/// If the archive turns out to be invalid, the namespace is deleted.
+pub async fn import_namespace<D>(
+    config: &D::Config,
+    namespace: &str,
+    reader: impl Read,
+) -> Result<ArchiveSummary, ArchiveError>
+where
+    D: KeyValueDatabase,
+    D::Store: KeyValueStore,
+{
+    D::create(config, namespace).await?;
+    let result = import_records::<D>(config, namespace, reader).await;
+    if result.is_err() {
+        D::delete(config, namespace).await?;
+    }
+    result
+}
+
+async fn import_records<D>(
+    config: &D::Config,
+    namespace: &str,
+    reader: impl Read,
+) -> Result<ArchiveSummary, ArchiveError>
+where
+    D: KeyValueDatabase,
+    D::Store: KeyValueStore,
+{
+    let database = D::connect(config, namespace).await?;
+    let mut archive = ArchiveReader::new(reader)?;
+    let mut summary = ArchiveSummary::default();
+    let mut store = None;
+    let mut batch = Batch::new();
+    loop {
+        match archive.read_record()? {
+            ArchiveRecord::RootKey(root_key) => {
+                if let Some(store) = &store {
+                    flush_batch::<D::Store>(store, &mut batch).await?;
+                }
+                store = Some(database.open_exclusive(&root_key)?);
+                summary.num_root_keys += 1;
+            }
+            ArchiveRecord::Entry { key, value } => {
+                let Some(store) = &store else {
+                    return Err(ArchiveError::UnexpectedRecord);
+                };
+                batch.put_key_value_bytes(key, value);
+                summary.num_entries += 1;
+                if batch.num_operations() == PAGE_SIZE {
+                    flush_batch::<D::Store>(store, &mut batch).await?;
+                }
+            }
+            ArchiveRecord::End { num_entries, .. } => {
+                if num_entries != summary.num_entries {
+                    return Err(ArchiveError::ChecksumMismatch);
+                }
+                if let Some(store) = &store {
+                    flush_batch::<D::Store>(store, &mut batch).await?;
+                }
+                return Ok(summary);
+            }
+        }
+    }
+}
+
+async fn flush_batch<S: KeyValueStore>(store: &S, batch: &mut Batch) -> Result<(), ArchiveError> {
+    if !batch.is_empty() {
+        store.write_batch(std::mem::take(batch)).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        memory::MemoryDatabase,
+        random::{generate_test_namespace, make_deterministic_rng},
+        store::{TestKeyValueDatabase as _, WritableKeyValueStore as _},
+        test_utils::get_random_key_values,
+    };
+
+    async fn create_namespace(
+        config: &<MemoryDatabase as KeyValueDatabase>::Config,
+    ) -> (String, Vec<(Vec<u8>, Vec<(Vec<u8>, Vec<u8>)>)>) {
+        let namespace = generate_test_namespace();
+        let database = MemoryDatabase::recreate_and_connect(config, &namespace)
+            .await
+            .unwrap();
+        let mut rng = make_deterministic_rng();
+        let mut content = Vec::new();
+        for root_key in [vec![], vec![1], vec![2, 3]] {
+            let key_values = get_random_key_values(&mut rng, 1500);
+            let store = database.open_exclusive(&root_key).unwrap();
+            let mut batch = Batch::new();
+            for (key, value) in &key_values {
+                batch.put_key_value_bytes(key.clone(), value.clone());
+            }
+            store.write_batch(batch).await.unwrap();
+            let mut key_values = key_values;
+            key_values.sort();
+            content.push((root_key, key_values));
+        }
+        (namespace, content)
+    }
+
+    async fn read_content(
+        config: &<MemoryDatabase as KeyValueDatabase>::Config,
+        namespace: &str,
+    ) -> Vec<(Vec<u8>, Vec<(Vec<u8>, Vec<u8>)>)> {
+        let database = MemoryDatabase::connect(config, namespace).await.unwrap();
+        let mut content = Vec::new();
+        for root_key in MemoryDatabase::list_root_keys(config, namespace)
+            .await
+            .unwrap()
+        {
+            let store = database.open_shared(&root_key).unwrap();
+            let key_values = store.find_key_values_by_prefix(&[]).await.unwrap();
+            content.push((root_key, key_values));
+        }
+        content
+    }
+
+    #[tokio::test]
+    async fn test_archive_round_trip() {
+        let config = MemoryDatabase::new_test_config().await.unwrap();
+        let (namespace, content) = create_namespace(&config).await;
+        let mut bytes = Vec::new();
+        let summary = export_namespace::<MemoryDatabase>(&config, &namespace, &mut bytes)
+            .await
+            .unwrap();
+        assert_eq!(summary.num_root_keys, 3);
+        assert_eq!(summary.num_entries, 4500);
+
+        let restored = generate_test_namespace();
+        let restored_summary =
+            import_namespace::<MemoryDatabase>(&config, &restored, bytes.as_slice())
+                .await
+                .unwrap();
+        assert_eq!(restored_summary, summary);
+        assert_eq!(read_content(&config, &restored).await, content);
+
+        // Importing into an existing namespace is an error.
+        assert!(
+            import_namespace::<MemoryDatabase>(&config, &restored, bytes.as_slice())
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_archive_corruption() {
+        let config = MemoryDatabase::new_test_config().await.unwrap();
+        let (namespace, _) = create_namespace(&config).await;
+        let mut bytes = Vec::new();
+        export_namespace::<MemoryDatabase>(&config, &namespace, &mut bytes)
+            .await
+            .unwrap();
+
+        let mut corrupted = bytes.clone();
+        let position = corrupted.len() / 2;
+        corrupted[position] ^= 1;
+        let restored = generate_test_namespace();
+        assert!(
+            import_namespace::<MemoryDatabase>(&config, &restored, corrupted.as_slice())
+                .await
+                .is_err()
+        );
+        assert!(!MemoryDatabase::exists(&config, &restored).await.unwrap());
+
+        let truncated = &bytes[..bytes.len() - 10];
+        assert!(matches!(
+            import_namespace::<MemoryDatabase>(&config, &restored, truncated).await,
+            Err(ArchiveError::Truncated)
+        ));
+
+        assert!(matches!(
+            import_namespace::<MemoryDatabase>(&config, &restored, &b"NOTANARCHIVE"[..]).await,
+            Err(ArchiveError::InvalidHeader)
+        ));
+    }
+}
@@ -0,0 +1,528 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Adds transparent compression of values to a given store.
+//!
+//! Every value is stored with a one-byte header indicating how it was compressed. Values
+//! smaller than a threshold, or that do not shrink when compressed, are stored as they
+//! are after the header. Since all the values carry a header, a database must be used
+//! with compression from its creation on.
+
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    batch::{Batch, WriteOperation},
+    store::{
+        KeyValueDatabase, KeyValueStoreError, RangeQuery, ReadableKeyValueStore, WithError,
+        WritableKeyValueStore,
+    },
+};
+#[cfg(with_testing)]
+use crate::{memory::MemoryDatabase, store::TestKeyValueDatabase};
+
+#[cfg(with_metrics)]
+mod metrics {
+    use std::sync::LazyLock;
+
+    use linera_base::prometheus_util::register_int_counter_vec;
+    use prometheus::IntCounterVec;
+
+    /// The total size of the values before compression
+    pub static UNCOMPRESSED_BYTES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+        register_int_counter_vec(
+            "compression_uncompressed_bytes",
+            "Total size of the written values before compression",
+            &[],
+        )
+    });
+
+    /// The total size of the values after compression
+    pub static COMPRESSED_BYTES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+        register_int_counter_vec(
+            "compression_compressed_bytes",
+            "Total size of the written values after compression",
+            &[],
+        )
+    });
+}
+
+/// The header of a value stored without compression.
+const UNCOMPRESSED_TAG: u8 = 0;
+/// The header of a value compressed with LZ4.
+const LZ4_TAG: u8 = 1;
+/// The header of a value compressed with Zstandard.
+#[cfg(with_zstd)]
+const ZSTD_TAG: u8 = 2;
+
+/// The default compression level for Zstandard.
+pub const DEFAULT_ZSTD_LEVEL: i32 = 3;
+
+/// The compression algorithms supported by the [`CompressionDatabase`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompressionAlgorithm {
+    /// LZ4, which is fast and available on all platforms.
+    Lz4,
+    /// Zstandard with the given compression level, which compresses better.
+    #[cfg(with_zstd)]
+    Zstd {
+        /// The compression level.
+        level: i32,
+    },
+}
+
+impl fmt::Display for CompressionAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CompressionAlgorithm::Lz4 => write!(f, "lz4"),
+            #[cfg(with_zstd)]
+            CompressionAlgorithm::Zstd { level } => write!(f, "zstd-{level}"),
+        }
+    }
+}
+
+impl FromStr for CompressionAlgorithm {
+    type Err = String;
+
+    /// Parses `lz4`, `zstd` or `zstd-LEVEL`.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "lz4" => Ok(CompressionAlgorithm::Lz4),
+            #[cfg(with_zstd)]
+            "zstd" => Ok(CompressionAlgorithm::Zstd {
+                level: DEFAULT_ZSTD_LEVEL,
+            }),
+            #[cfg(with_zstd)]
+            _ if input.starts_with("zstd-") => {
+                let level = input["zstd-".len()..]
+                    .parse()
+                    .map_err(|_| format!("invalid Zstandard level in {input:?}"))?;
+                Ok(CompressionAlgorithm::Zstd { level })
+            }
+            _ => Err(format!("unknown compression algorithm {input:?}")),
+        }
+    }
+}
+
+/// The parametrization of the compression.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageCompressionConfig {
+    /// The algorithm used to compress values.
+    pub algorithm: CompressionAlgorithm,
+    /// The minimal size of a value, in bytes, for it to be compressed.
+    pub min_compression_size: usize,
+}
+
+/// The default minimal size of a value for it to be compressed.
+pub const DEFAULT_MIN_COMPRESSION_SIZE: usize = 256;
+
+impl StorageCompressionConfig {
+    /// Creates a configuration for the given algorithm with the default threshold.
+    pub fn new(algorithm: CompressionAlgorithm) -> Self {
+        Self {
+            algorithm,
+            min_compression_size: DEFAULT_MIN_COMPRESSION_SIZE,
+        }
+    }
+}
+
+/// A key-value database with transparent compression of values.
+#[derive(Clone)]
+pub struct CompressionDatabase<D> {
+    /// The inner database.
+    database: D,
+    /// The configuration.
+    config: StorageCompressionConfig,
+}
+
+/// A key-value store with transparent compression of values.
+#[derive(Clone)]
+pub struct CompressionStore<S> {
+    /// The inner store.
+    store: S,
+    /// The configuration.
+    config: StorageCompressionConfig,
+}
+
+/// The composed error type built from the inner error type.
+#[derive(Error, Debug)]
+pub enum CompressionError<E> {
+    /// inner store error
+    #[error(transparent)]
+    InnerStoreError(#[from] E),
+
+    /// The value has no header
+    #[error("the value has no compression header")]
+    MissingHeader,
+
+    /// The header of the value is unknown
+    #[error("unknown compression header {0}")]
+    UnknownHeader(u8),
+
+    /// The value could not be decompressed with LZ4
+    #[error(transparent)]
+    Lz4Error(#[from] lz4_flex::block::DecompressError),
+
+    /// The value could not be compressed or decompressed with Zstandard
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+}
+
+impl<E: KeyValueStoreError> From<bcs::Error> for CompressionError<E> {
+    fn from(error: bcs::Error) -> Self {
+        let error = E::from(error);
+        CompressionError::InnerStoreError(error)
+    }
+}
+
+impl<E: KeyValueStoreError + 'static> KeyValueStoreError for CompressionError<E> {
+    const BACKEND: &'static str = "compression";
+}
+
+impl<D> WithError for CompressionDatabase<D>
+where
+    D: WithError,
+    D::Error: 'static,
+{
+    type Error = CompressionError<D::Error>;
+}
+
+impl<S> WithError for CompressionStore<S>
+where
+    S: WithError,
+    S::Error: 'static,
+{
+    type Error = CompressionError<S::Error>;
+}
+
+impl<S> ReadableKeyValueStore for CompressionStore<S>
+where
+    S: ReadableKeyValueStore,
+    S::Error: 'static,
+{
+    // Compression does not change the keys.
+    const MAX_KEY_SIZE: usize = S::MAX_KEY_SIZE;
+
+    fn max_stream_queries(&self) -> usize {
+        self.store.max_stream_queries()
+    }
+
+    async fn read_value_bytes(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+        let value = self.store.read_value_bytes(key).await?;
+        value.map(|value| Self::decompress(&value)).transpose()
+    }
+
+    async fn contains_key(&self, key: &[u8]) -> Result<bool, Self::Error> {
+        Ok(self.store.contains_key(key).await?)
+    }
+
+    async fn contains_keys(&self, keys: Vec<Vec<u8>>) -> Result<Vec<bool>, Self::Error> {
+        Ok(self.store.contains_keys(keys).await?)
+    }
+
+    async fn read_multi_values_bytes(
+        &self,
+        keys: Vec<Vec<u8>>,
+    ) -> Result<Vec<Option<Vec<u8>>>, Self::Error> {
+        let values = self.store.read_multi_values_bytes(keys).await?;
+        values
+            .into_iter()
+            .map(|value| value.map(|value| Self::decompress(&value)).transpose())
+            .collect()
+    }
+
+    async fn find_keys_by_prefix(&self, key_prefix: &[u8]) -> Result<Vec<Vec<u8>>, Self::Error> {
+        Ok(self.store.find_keys_by_prefix(key_prefix).await?)
+    }
+
+    async fn find_key_values_by_prefix(
+        &self,
+        key_prefix: &[u8],
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
+        let key_values = self.store.find_key_values_by_prefix(key_prefix).await?;
+        Self::decompress_key_values(key_values)
+    }
+
+    async fn find_keys_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        Ok(self.store.find_keys_by_range(key_prefix, query).await?)
+    }
+
+    async fn find_key_values_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
+        let key_values = self
+            .store
+            .find_key_values_by_range(key_prefix, query)
+            .await?;
+        Self::decompress_key_values(key_values)
+    }
+}
+
+impl<S> WritableKeyValueStore for CompressionStore<S>
+where
+    S: WritableKeyValueStore,
+    S::Error: 'static,
+{
+    // One byte is used by the header. Values are never stored larger than that.
+    const MAX_VALUE_SIZE: usize = S::MAX_VALUE_SIZE - 1;
+
+    async fn write_batch(&self, batch: Batch) -> Result<(), Self::Error> {
+        let mut operations = Vec::with_capacity(batch.operations.len());
+        for operation in batch.operations {
+            let operation = match operation {
+                WriteOperation::Put { key, value } => {
+                    let value = self.compress(&value)?;
+                    WriteOperation::Put { key, value }
+                }
+                operation => operation,
+            };
+            operations.push(operation);
+        }
+        Ok(self.store.write_batch(Batch { operations }).await?)
+    }
+
+    async fn clear_journal(&self) -> Result<(), Self::Error> {
+        Ok(self.store.clear_journal().await?)
+    }
+}
+
+/// The configuration type for the `CompressionDatabase`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompressionConfig<C> {
+    /// The inner configuration of the `CompressionDatabase`.
+    pub inner_config: C,
+    /// The compression being used.
+    pub storage_compression_config: StorageCompressionConfig,
+}
+
+impl<D> KeyValueDatabase for CompressionDatabase<D>
+where
+    D: KeyValueDatabase,
+    D::Error: 'static,
+{
+    type Config = CompressionConfig<D::Config>;
+
+    type Store = CompressionStore<D::Store>;
+
+    fn get_name() -> String {
+        format!("compression {}", D::get_name())
+    }
+
+    async fn connect(config: &Self::Config, namespace: &str) -> Result<Self, Self::Error> {
+        let database = D::connect(&config.inner_config, namespace).await?;
+        Ok(CompressionDatabase {
+            database,
+            config: config.storage_compression_config.clone(),
+        })
+    }
+
+    fn open_shared(&self, root_key: &[u8]) -> Result<Self::Store, Self::Error> {
+        let store = self.database.open_shared(root_key)?;
+        Ok(CompressionStore::new(store, self.config.clone()))
+    }
+
+    fn open_exclusive(&self, root_key: &[u8]) -> Result<Self::Store, Self::Error> {
+        let store = self.database.open_exclusive(root_key)?;
+        Ok(CompressionStore::new(store, self.config.clone()))
+    }
+
+    async fn list_all(config: &Self::Config) -> Result<Vec<String>, Self::Error> {
+        Ok(D::list_all(&config.inner_config).await?)
+    }
+
+    async fn list_root_keys(
+        config: &Self::Config,
+        namespace: &str,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        Ok(D::list_root_keys(&config.inner_config, namespace).await?)
+    }
+
+    async fn delete_all(config: &Self::Config) -> Result<(), Self::Error> {
+        Ok(D::delete_all(&config.inner_config).await?)
+    }
+
+    async fn exists(config: &Self::Config, namespace: &str) -> Result<bool, Self::Error> {
+        Ok(D::exists(&config.inner_config, namespace).await?)
+    }
+
+    async fn create(config: &Self::Config, namespace: &str) -> Result<(), Self::Error> {
+        Ok(D::create(&config.inner_config, namespace).await?)
+    }
+
+    async fn delete(config: &Self::Config, namespace: &str) -> Result<(), Self::Error> {
+        Ok(D::delete(&config.inner_config, namespace).await?)
+    }
+
+    async fn snapshot(
+        config: &Self::Config,
+        namespace: &str,
+        snapshot: &str,
+    ) -> Result<(), Self::Error> {
+        Ok(D::snapshot(&config.inner_config, namespace, snapshot).await?)
+    }
+}
+
+impl<S> CompressionStore<S>
+where
+    S: WithError,
+{
+    /// Creates a new key-value store that compresses the values of the given store.
+    pub fn new(store: S, config: StorageCompressionConfig) -> Self {
+        Self { store, config }
+    }
+
+    /// Returns the value with its header, compressed if this is worth it.
+    fn compress(&self, value: &[u8]) -> Result<Vec<u8>, CompressionError<S::Error>> {
+        let compressed = if value.len() < self.config.min_compression_size {
+            None
+        } else {
+            let compressed = match self.config.algorithm {
+                CompressionAlgorithm::Lz4 => {
+                    let mut compressed = vec![LZ4_TAG];
+                    compressed.extend(lz4_flex::compress_prepend_size(value));
+                    compressed
+                }
+                #[cfg(with_zstd)]
+                CompressionAlgorithm::Zstd { level } => {
+                    let mut compressed = vec![ZSTD_TAG];
+                    compressed.extend(zstd::bulk::compress(value, level)?);
+                    compressed
+                }
+            };
+            // The header is counted so that the stored value never grows.
+            (compressed.len() <= value.len()).then_some(compressed)
+        };
+        let stored = compressed.unwrap_or_else(|| {
+            let mut stored = Vec::with_capacity(value.len() + 1);
+            stored.push(UNCOMPRESSED_TAG);
+            stored.extend_from_slice(value);
+            stored
+        });
+        #[cfg(with_metrics)]
+        {
+            metrics::UNCOMPRESSED_BYTES
+                .with_label_values(&[])
+                .inc_by(value.len() as u64);
+            metrics::COMPRESSED_BYTES
+                .with_label_values(&[])
+                .inc_by(stored.len() as u64);
+        }
+        Ok(stored)
+    }
+
+    /// Returns the original value from a stored one.
+    fn decompress(stored: &[u8]) -> Result<Vec<u8>, CompressionError<S::Error>> {
+        let (tag, data) = stored
+            .split_first()
+            .ok_or(CompressionError::MissingHeader)?;
+        match *tag {
+            UNCOMPRESSED_TAG => Ok(data.to_vec()),
+            LZ4_TAG => Ok(lz4_flex::decompress_size_prepended(data)?),
+            #[cfg(with_zstd)]
+            ZSTD_TAG => Ok(zstd::stream::decode_all(data)?),
+            tag => Err(CompressionError::UnknownHeader(tag)),
+        }
+    }
+
+    fn decompress_key_values(
+        key_values: Vec<(Vec<u8>, Vec<u8>)>,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, CompressionError<S::Error>> {
+        key_values
+            .into_iter()
+            .map(|(key, value)| Ok((key, Self::decompress(&value)?)))
+            .collect()
+    }
+}
+
+/// A memory database with compression.
+#[cfg(with_testing)]
+pub type CompressionMemoryDatabase = CompressionDatabase<MemoryDatabase>;
+
+#[cfg(with_testing)]
+impl<D> TestKeyValueDatabase for CompressionDatabase<D>
+where
+    D: TestKeyValueDatabase,
+    D::Error: 'static,
+{
+    async fn new_test_config() -> Result<CompressionConfig<D::Config>, Self::Error> {
+        let inner_config = D::new_test_config().await?;
+        let storage_compression_config = StorageCompressionConfig {
+            algorithm: CompressionAlgorithm::Lz4,
+            min_compression_size: 16,
+        };
+        Ok(CompressionConfig {
+            inner_config,
+            storage_compression_config,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr as _;
+
+    use super::{CompressionAlgorithm, CompressionStore, StorageCompressionConfig};
+    use crate::{
+        batch::Batch,
+        memory::MemoryStore,
+        store::{ReadableKeyValueStore as _, WritableKeyValueStore as _},
+    };
+
+    async fn check_compression(algorithm: CompressionAlgorithm) {
+        let inner = MemoryStore::new_for_testing();
+        let store = CompressionStore::new(inner.clone(), StorageCompressionConfig::new(algorithm));
+        let small = vec![7; 10];
+        let large = vec![7; 10000];
+        let mut batch = Batch::new();
+        batch.put_key_value_bytes(vec![0], small.clone());
+        batch.put_key_value_bytes(vec![1], large.clone());
+        store.write_batch(batch).await.unwrap();
+
+        let stored_small = inner.read_value_bytes(&[0]).await.unwrap().unwrap();
+        assert_eq!(stored_small.len(), small.len() + 1);
+        let stored_large = inner.read_value_bytes(&[1]).await.unwrap().unwrap();
+        assert!(stored_large.len() < large.len() / 10);
+
+        assert_eq!(store.read_value_bytes(&[0]).await.unwrap(), Some(small));
+        assert_eq!(
+            store
+                .read_multi_values_bytes(vec![vec![1], vec![2]])
+                .await
+                .unwrap(),
+            vec![Some(large.clone()), None]
+        );
+        let key_values = store.find_key_values_by_prefix(&[1]).await.unwrap();
+        assert_eq!(key_values, vec![(Vec::new(), large)]);
+    }
+
+    #[tokio::test]
+    async fn test_lz4_compression() {
+        check_compression(CompressionAlgorithm::Lz4).await;
+    }
+
+    #[tokio::test]
+    async fn test_zstd_compression() {
+        check_compression(CompressionAlgorithm::from_str("zstd").unwrap()).await;
+    }
+
+    #[test]
+    fn test_algorithm_from_str() {
+        for algorithm in [
+            CompressionAlgorithm::Lz4,
+            CompressionAlgorithm::Zstd { level: 19 },
+        ] {
+            let parsed = CompressionAlgorithm::from_str(&algorithm.to_string()).unwrap();
+            assert_eq!(parsed, algorithm);
+        }
+        assert!(CompressionAlgorithm::from_str("gzip").is_err());
+        assert!(CompressionAlgorithm::from_str("zstd-high").is_err());
+    }
+}
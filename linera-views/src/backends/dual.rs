@@ -11,7 +11,7 @@ use crate::store::TestKeyValueDatabase;
 use crate::{
     batch::Batch,
     store::{
-        KeyValueDatabase, KeyValueStoreError, ReadableKeyValueStore, WithError,
+        KeyValueDatabase, KeyValueStoreError, RangeQuery, ReadableKeyValueStore, WithError,
         WritableKeyValueStore,
     },
 };
@@ -43,6 +43,8 @@ pub enum StoreInUse {
     First,
     /// The second store.
     Second,
+    /// Both stores: the entries are read from the first store and written to both.
+    Both,
 }
 
 /// The trait for a (static) root key assignment.
@@ -52,13 +54,26 @@ pub trait DualStoreRootKeyAssignment {
     fn assigned_store(root_key: &[u8]) -> Result<StoreInUse, bcs::Error>;
 }
 
-/// A partition opened in one of the two databases.
+/// A root key assignment writing every root key to both databases and reading it from
+/// the first one. This keeps the second database up to date while a deployment is
+/// migrated from the first database to the second one.
+pub struct DualWriteAssignment;
+
+impl DualStoreRootKeyAssignment for DualWriteAssignment {
+    fn assigned_store(_root_key: &[u8]) -> Result<StoreInUse, bcs::Error> {
+        Ok(StoreInUse::Both)
+    }
+}
+
+/// A partition opened in one or both of the two databases.
 #[derive(Clone)]
 pub enum DualStore<S1, S2> {
     /// The first store.
     First(S1),
     /// The second store.
     Second(S2),
+    /// Both stores, read from the first one.
+    Both(S1, S2),
 }
 
 impl<D1, D2, A> WithError for DualDatabase<D1, D2, A>
@@ -91,14 +106,14 @@ where
 
     fn max_stream_queries(&self) -> usize {
         match self {
-            Self::First(store) => store.max_stream_queries(),
+            Self::First(store) | Self::Both(store, _) => store.max_stream_queries(),
             Self::Second(store) => store.max_stream_queries(),
         }
     }
 
     async fn read_value_bytes(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
         let result = match self {
-            Self::First(store) => store
+            Self::First(store) | Self::Both(store, _) => store
                 .read_value_bytes(key)
                 .await
                 .map_err(DualStoreError::First)?,
@@ -112,7 +127,7 @@ where
 
     async fn contains_key(&self, key: &[u8]) -> Result<bool, Self::Error> {
         let result = match self {
-            Self::First(store) => store
+            Self::First(store) | Self::Both(store, _) => store
                 .contains_key(key)
                 .await
                 .map_err(DualStoreError::First)?,
@@ -126,7 +141,7 @@ where
 
     async fn contains_keys(&self, keys: Vec<Vec<u8>>) -> Result<Vec<bool>, Self::Error> {
         let result = match self {
-            Self::First(store) => store
+            Self::First(store) | Self::Both(store, _) => store
                 .contains_keys(keys)
                 .await
                 .map_err(DualStoreError::First)?,
@@ -143,7 +158,7 @@ where
         keys: Vec<Vec<u8>>,
     ) -> Result<Vec<Option<Vec<u8>>>, Self::Error> {
         let result = match self {
-            Self::First(store) => store
+            Self::First(store) | Self::Both(store, _) => store
                 .read_multi_values_bytes(keys)
                 .await
                 .map_err(DualStoreError::First)?,
@@ -157,7 +172,7 @@ where
 
     async fn find_keys_by_prefix(&self, key_prefix: &[u8]) -> Result<Vec<Vec<u8>>, Self::Error> {
         let result = match self {
-            Self::First(store) => store
+            Self::First(store) | Self::Both(store, _) => store
                 .find_keys_by_prefix(key_prefix)
                 .await
                 .map_err(DualStoreError::First)?,
@@ -174,7 +189,7 @@ where
         key_prefix: &[u8],
     ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
         let result = match self {
-            Self::First(store) => store
+            Self::First(store) | Self::Both(store, _) => store
                 .find_key_values_by_prefix(key_prefix)
                 .await
                 .map_err(DualStoreError::First)?,
@@ -185,6 +200,42 @@ where
         };
         Ok(result)
     }
+
+    async fn find_keys_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        let result = match self {
+            Self::First(store) | Self::Both(store, _) => store
+                .find_keys_by_range(key_prefix, query)
+                .await
+                .map_err(DualStoreError::First)?,
+            Self::Second(store) => store
+                .find_keys_by_range(key_prefix, query)
+                .await
+                .map_err(DualStoreError::Second)?,
+        };
+        Ok(result)
+    }
+
+    async fn find_key_values_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
+        let result = match self {
+            Self::First(store) | Self::Both(store, _) => store
+                .find_key_values_by_range(key_prefix, query)
+                .await
+                .map_err(DualStoreError::First)?,
+            Self::Second(store) => store
+                .find_key_values_by_range(key_prefix, query)
+                .await
+                .map_err(DualStoreError::Second)?,
+        };
+        Ok(result)
+    }
 }
 
 impl<S1, S2> WritableKeyValueStore for DualStore<S1, S2>
@@ -204,6 +255,17 @@ where
                 .write_batch(batch)
                 .await
                 .map_err(DualStoreError::Second)?,
+            Self::Both(first_store, second_store) => {
+                // The first store is the reference: it is written to first.
+                first_store
+                    .write_batch(batch.clone())
+                    .await
+                    .map_err(DualStoreError::First)?;
+                second_store
+                    .write_batch(batch)
+                    .await
+                    .map_err(DualStoreError::Second)?;
+            }
         }
         Ok(())
     }
@@ -215,6 +277,16 @@ where
                 .clear_journal()
                 .await
                 .map_err(DualStoreError::Second)?,
+            Self::Both(first_store, second_store) => {
+                first_store
+                    .clear_journal()
+                    .await
+                    .map_err(DualStoreError::First)?;
+                second_store
+                    .clear_journal()
+                    .await
+                    .map_err(DualStoreError::Second)?;
+            }
         }
         Ok(())
     }
@@ -264,6 +336,17 @@ where
                     .map_err(DualStoreError::Second)?;
                 Ok(DualStore::Second(store))
             }
+            StoreInUse::Both => {
+                let first_store = self
+                    .first_database
+                    .open_shared(root_key)
+                    .map_err(DualStoreError::First)?;
+                let second_store = self
+                    .second_database
+                    .open_shared(root_key)
+                    .map_err(DualStoreError::Second)?;
+                Ok(DualStore::Both(first_store, second_store))
+            }
         }
     }
 
@@ -283,6 +366,17 @@ where
                     .map_err(DualStoreError::Second)?;
                 Ok(DualStore::Second(store))
             }
+            StoreInUse::Both => {
+                let first_store = self
+                    .first_database
+                    .open_exclusive(root_key)
+                    .map_err(DualStoreError::First)?;
+                let second_store = self
+                    .second_database
+                    .open_exclusive(root_key)
+                    .map_err(DualStoreError::Second)?;
+                Ok(DualStore::Both(first_store, second_store))
+            }
         }
     }
 
@@ -316,6 +410,9 @@ where
                 .await
                 .map_err(DualStoreError::Second)?,
         );
+        // With dual writes, the same root keys are in both databases.
+        root_keys.sort();
+        root_keys.dedup();
         Ok(root_keys)
     }
 
@@ -360,6 +457,20 @@ where
             .map_err(DualStoreError::Second)?;
         Ok(())
     }
+
+    async fn snapshot(
+        config: &Self::Config,
+        namespace: &str,
+        snapshot: &str,
+    ) -> Result<(), Self::Error> {
+        D1::snapshot(&config.first_config, namespace, snapshot)
+            .await
+            .map_err(DualStoreError::First)?;
+        D2::snapshot(&config.second_config, namespace, snapshot)
+            .await
+            .map_err(DualStoreError::Second)?;
+        Ok(())
+    }
 }
 
 #[cfg(with_testing)]
@@ -42,12 +42,12 @@ use crate::metering::MeteredDatabase;
 use crate::store::TestKeyValueDatabase;
 use crate::{
     batch::SimpleUnorderedBatch,
-    common::get_uleb128_size,
+    common::{copy_namespace, get_uleb128_size},
     journaling::{JournalConsistencyError, JournalingKeyValueDatabase},
     lru_caching::{LruCachingConfig, LruCachingDatabase},
     store::{
-        DirectWritableKeyValueStore, KeyValueDatabase, KeyValueStoreError, ReadableKeyValueStore,
-        WithError,
+        DirectWritableKeyValueStore, Direction, KeyValueDatabase, KeyValueStoreError, RangeQuery,
+        ReadableKeyValueStore, WithError,
     },
     value_splitting::{ValueSplittingDatabase, ValueSplittingError},
     FutureSyncExt as _,
@@ -511,6 +511,14 @@ impl KeyValueDatabase for DynamoDbDatabaseInternal {
             .await?;
         Ok(())
     }
+
+    async fn snapshot(
+        config: &Self::Config,
+        namespace: &str,
+        snapshot: &str,
+    ) -> Result<(), DynamoDbStoreInternalError> {
+        copy_namespace::<JournalingKeyValueDatabase<Self>>(config, namespace, snapshot).await
+    }
 }
 
 impl DynamoDbDatabaseInternal {
@@ -617,6 +625,107 @@ impl DynamoDbStoreInternal {
         Ok(response)
     }
 
+    /// Queries a page of the keys in the interval `[lower_bound, upper_bound]`. The upper
+    /// bound is inclusive since DynamoDB only supports `BETWEEN` for bounded intervals.
+    async fn get_range_query_output(
+        &self,
+        attribute_str: &str,
+        start_key: &[u8],
+        lower_bound: &[u8],
+        upper_bound: &[u8],
+        query: &RangeQuery,
+        start_key_map: Option<HashMap<String, AttributeValue>>,
+    ) -> Result<QueryOutput, DynamoDbStoreInternalError> {
+        let _guard = self.acquire().await;
+        let start_key = start_key.to_vec();
+        // One more item than the limit, as the upper bound itself may be returned.
+        let page_limit = query
+            .limit
+            .map(|limit| i32::try_from(limit.saturating_add(1)).unwrap_or(i32::MAX));
+        let response = self
+            .client
+            .query()
+            .table_name(&self.namespace)
+            .projection_expression(attribute_str)
+            .key_condition_expression(format!(
+                "{PARTITION_ATTRIBUTE} = :partition and {KEY_ATTRIBUTE} BETWEEN :lower and :upper"
+            ))
+            .expression_attribute_values(":partition", AttributeValue::B(Blob::new(start_key)))
+            .expression_attribute_values(":lower", AttributeValue::B(Blob::new(lower_bound)))
+            .expression_attribute_values(":upper", AttributeValue::B(Blob::new(upper_bound)))
+            .scan_index_forward(query.direction == Direction::Forward)
+            .set_limit(page_limit)
+            .set_exclusive_start_key(start_key_map)
+            .send()
+            .boxed_sync()
+            .await?;
+        Ok(response)
+    }
+
+    async fn get_range_responses(
+        &self,
+        attribute: &str,
+        start_key: &[u8],
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<QueryResponses, DynamoDbStoreInternalError> {
+        check_key_size(key_prefix)?;
+        let prefix_len = key_prefix.len();
+        let mut lower_bound = key_prefix.to_vec();
+        if let Some(start) = &query.start {
+            lower_bound.extend(start);
+        }
+        let upper_bound = match &query.end {
+            Some(end) => {
+                let mut upper_bound = key_prefix.to_vec();
+                upper_bound.extend(end);
+                upper_bound
+            }
+            None => {
+                // Every key having the prefix is at most the prefix padded with `u8::MAX`
+                // up to the maximal key size.
+                let mut upper_bound = key_prefix.to_vec();
+                upper_bound.resize(MAX_KEY_SIZE.max(prefix_len), u8::MAX);
+                upper_bound
+            }
+        };
+        let limit = query.limit.unwrap_or(usize::MAX);
+        let mut responses = Vec::new();
+        let mut count = 0;
+        let mut start_key_map = None;
+        loop {
+            let response = self
+                .get_range_query_output(
+                    attribute,
+                    start_key,
+                    &lower_bound,
+                    &upper_bound,
+                    query,
+                    start_key_map,
+                )
+                .await?;
+            for item in response.items.iter().flatten() {
+                if query.contains(extract_key(prefix_len, item)?) {
+                    count += 1;
+                }
+            }
+            let last_evaluated = response.last_evaluated_key.clone();
+            responses.push(response);
+            match last_evaluated {
+                Some(value) if count < limit => {
+                    start_key_map = Some(value);
+                }
+                _ => {
+                    break;
+                }
+            }
+        }
+        Ok(QueryResponses {
+            prefix_len,
+            responses,
+        })
+    }
+
     async fn read_value_bytes_general(
         &self,
         key_db: HashMap<String, AttributeValue>,
@@ -797,6 +906,56 @@ impl ReadableKeyValueStore for DynamoDbStoreInternal {
             .map(|entry| entry.map(|(key, value)| (key.to_vec(), value.to_vec())))
             .collect()
     }
+
+    async fn find_keys_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<Vec<u8>>, DynamoDbStoreInternalError> {
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let result_queries = self
+            .get_range_responses(KEY_ATTRIBUTE, &self.start_key, key_prefix, query)
+            .await?;
+        let limit = query.limit.unwrap_or(usize::MAX);
+        let mut keys = Vec::new();
+        for key in result_queries.keys() {
+            let key = key?;
+            if keys.len() == limit {
+                break;
+            }
+            if query.contains(key) {
+                keys.push(key.to_vec());
+            }
+        }
+        Ok(keys)
+    }
+
+    async fn find_key_values_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, DynamoDbStoreInternalError> {
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let result_queries = self
+            .get_range_responses(KEY_VALUE_ATTRIBUTE, &self.start_key, key_prefix, query)
+            .await?;
+        let limit = query.limit.unwrap_or(usize::MAX);
+        let mut key_values = Vec::new();
+        for entry in result_queries.key_values() {
+            let (key, value) = entry?;
+            if key_values.len() == limit {
+                break;
+            }
+            if query.contains(key) {
+                key_values.push((key.to_vec(), value.to_vec()));
+            }
+        }
+        Ok(key_values)
+    }
 }
 
 impl DirectWritableKeyValueStore for DynamoDbStoreInternal {
@@ -0,0 +1,736 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Adds encryption at rest to a given store.
+//!
+//! Values are encrypted with XChaCha20-Poly1305 under a random nonce, using the key and
+//! the root key as associated data so that a value cannot be moved to another key.
+//!
+//! Keys are encrypted deterministically: every byte of a key is masked with a keyed hash
+//! of the bytes preceding it. The encryption of a prefix is thus a prefix of the
+//! encryption of the key, and prefix searches and deletions work on the inner store. The
+//! ordering of the keys is lost, so the searches are sorted after decryption, and the
+//! streams read their whole prefix at once instead of page by page.
+//!
+//! For the same reason, a range query costs a full scan of the common prefix of its
+//! bounds: every key (and value) sharing that prefix is read from the inner store and
+//! decrypted before the bounds, the direction and the limit are applied. A query with a
+//! small limit, or whose bounds only share a short prefix, is thus as expensive as a
+//! prefix search over the whole view.
+//!
+//! This masking only hides the content of the keys from someone who cannot choose them.
+//! Whoever can read the inner store learns:
+//! * the length of every key and root key,
+//! * which keys are equal, and the length of the prefix shared by any two keys,
+//! * the order of the writes and deletions applied to each key.
+//!
+//! Since the mask of a byte only depends on the bytes preceding it, whoever can also
+//! have chosen keys written, for instance through an application, can recover the other
+//! keys sharing a prefix with them byte by byte. Only the values are encrypted
+//! securely.
+
+use std::{fmt, str::FromStr, sync::Arc};
+
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+use futures::{stream, Stream, TryStreamExt as _};
+use rand::RngCore as _;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest as _, Sha3_256};
+use thiserror::Error;
+
+use crate::{
+    batch::{Batch, WriteOperation},
+    store::{
+        KeyValueDatabase, KeyValueStoreError, RangeQuery, ReadableKeyValueStore, WithError,
+        WritableKeyValueStore,
+    },
+};
+#[cfg(with_testing)]
+use crate::{memory::MemoryDatabase, store::TestKeyValueDatabase};
+
+/// The size of the nonce stored in front of every encrypted value.
+const NONCE_SIZE: usize = 24;
+/// The size of the authentication tag appended to every encrypted value.
+const TAG_SIZE: usize = 16;
+
+/// The domain of the hash deriving the cipher of the values.
+const VALUE_DOMAIN: &[u8] = b"linera-views value encryption";
+/// The domain of the hash masking the keys.
+const KEY_DOMAIN: &[u8] = b"linera-views key encryption";
+/// The domain of the hash masking the root keys.
+const ROOT_KEY_DOMAIN: &[u8] = b"linera-views root key encryption";
+
+/// The size of an [`EncryptionKey`], in bytes.
+pub const ENCRYPTION_KEY_SIZE: usize = 32;
+
+/// The secret key of an encrypted database.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptionKey([u8; ENCRYPTION_KEY_SIZE]);
+
+impl EncryptionKey {
+    /// Creates a key from its bytes.
+    pub fn new(bytes: [u8; ENCRYPTION_KEY_SIZE]) -> Self {
+        Self(bytes)
+    }
+
+    /// Derives a key from a passphrase with Argon2id. The `salt` must be at least 8 bytes
+    /// long, and the same salt must be used every time the database is opened.
+    pub fn from_passphrase(passphrase: &[u8], salt: &[u8]) -> Result<Self, argon2::Error> {
+        let mut bytes = [0; ENCRYPTION_KEY_SIZE];
+        argon2::Argon2::default().hash_password_into(passphrase, salt, &mut bytes)?;
+        Ok(Self(bytes))
+    }
+}
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // The key is never printed.
+        write!(f, "EncryptionKey(..)")
+    }
+}
+
+impl FromStr for EncryptionKey {
+    type Err = String;
+
+    /// Parses a key written in hexadecimal.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let bytes = hex::decode(input).map_err(|error| error.to_string())?;
+        let bytes = bytes
+            .try_into()
+            .map_err(|_| format!("an encryption key must have {ENCRYPTION_KEY_SIZE} bytes"))?;
+        Ok(Self(bytes))
+    }
+}
+
+/// The parametrization of the encryption.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageEncryptionConfig {
+    /// The secret key of the database.
+    pub key: EncryptionKey,
+}
+
+/// The secrets derived from an [`EncryptionKey`].
+struct EncryptionSecrets {
+    /// The cipher of the values.
+    cipher: XChaCha20Poly1305,
+    /// The hash state masking the keys.
+    key_hasher: Sha3_256,
+    /// The hash state masking the root keys.
+    root_key_hasher: Sha3_256,
+}
+
+impl EncryptionSecrets {
+    fn new(key: &EncryptionKey) -> Self {
+        let keyed_hasher = |domain: &[u8]| {
+            let mut hasher = Sha3_256::new();
+            hasher.update(domain);
+            hasher.update(key.0);
+            hasher
+        };
+        let value_key = keyed_hasher(VALUE_DOMAIN).finalize();
+        Self {
+            cipher: XChaCha20Poly1305::new(&value_key),
+            key_hasher: keyed_hasher(KEY_DOMAIN),
+            root_key_hasher: keyed_hasher(ROOT_KEY_DOMAIN),
+        }
+    }
+}
+
+/// Masks the `plaintext` bytes following the ones absorbed by `hasher`.
+fn mask(hasher: &Sha3_256, plaintext: &[u8]) -> Vec<u8> {
+    let mut hasher = hasher.clone();
+    plaintext
+        .iter()
+        .map(|byte| {
+            let masked = byte ^ hasher.clone().finalize()[0];
+            hasher.update([*byte]);
+            masked
+        })
+        .collect()
+}
+
+/// Unmasks the `masked` bytes following the ones absorbed by `hasher`.
+fn unmask(hasher: &Sha3_256, masked: &[u8]) -> Vec<u8> {
+    let mut hasher = hasher.clone();
+    masked
+        .iter()
+        .map(|byte| {
+            let plaintext = byte ^ hasher.clone().finalize()[0];
+            hasher.update([plaintext]);
+            plaintext
+        })
+        .collect()
+}
+
+/// Returns the prefix shared by all the suffixes selected by `query`, that is, the
+/// common prefix of its bounds.
+fn common_prefix(query: &RangeQuery) -> &[u8] {
+    match (&query.start, &query.end) {
+        (Some(start), Some(end)) => {
+            let length = start
+                .iter()
+                .zip(end)
+                .take_while(|(byte1, byte2)| byte1 == byte2)
+                .count();
+            &start[..length]
+        }
+        _ => &[],
+    }
+}
+
+/// A key-value database with encryption at rest.
+#[derive(Clone)]
+pub struct EncryptionDatabase<D> {
+    /// The inner database.
+    database: D,
+    /// The secrets of the database.
+    secrets: Arc<EncryptionSecrets>,
+}
+
+/// A key-value store with encryption at rest.
+///
+/// Its range queries scan the whole common prefix of their bounds, whatever their limit.
+#[derive(Clone)]
+pub struct EncryptionStore<S> {
+    /// The inner store.
+    store: S,
+    /// The secrets of the database.
+    secrets: Arc<EncryptionSecrets>,
+    /// The root key of the store, used to authenticate the values.
+    root_key: Vec<u8>,
+}
+
+/// The composed error type built from the inner error type.
+#[derive(Error, Debug)]
+pub enum EncryptionError<E> {
+    /// inner store error
+    #[error(transparent)]
+    InnerStoreError(#[from] E),
+
+    /// The value is too short to be an encrypted value
+    #[error("the stored value is too short to be encrypted")]
+    TruncatedValue,
+
+    /// The value could not be decrypted
+    #[error("the stored value could not be decrypted: wrong key or corrupted value")]
+    DecryptionFailure,
+}
+
+impl<E: KeyValueStoreError> From<bcs::Error> for EncryptionError<E> {
+    fn from(error: bcs::Error) -> Self {
+        let error = E::from(error);
+        EncryptionError::InnerStoreError(error)
+    }
+}
+
+impl<E: KeyValueStoreError + 'static> KeyValueStoreError for EncryptionError<E> {
+    const BACKEND: &'static str = "encryption";
+}
+
+impl<D> WithError for EncryptionDatabase<D>
+where
+    D: WithError,
+    D::Error: 'static,
+{
+    type Error = EncryptionError<D::Error>;
+}
+
+impl<S> WithError for EncryptionStore<S>
+where
+    S: WithError,
+    S::Error: 'static,
+{
+    type Error = EncryptionError<S::Error>;
+}
+
+impl<S> ReadableKeyValueStore for EncryptionStore<S>
+where
+    S: ReadableKeyValueStore,
+    S::Error: 'static,
+{
+    // Masking does not change the length of the keys.
+    const MAX_KEY_SIZE: usize = S::MAX_KEY_SIZE;
+
+    fn max_stream_queries(&self) -> usize {
+        self.store.max_stream_queries()
+    }
+
+    async fn read_value_bytes(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+        let masked_key = mask(&self.secrets.key_hasher, key);
+        let value = self.store.read_value_bytes(&masked_key).await?;
+        value.map(|value| self.decrypt(key, &value)).transpose()
+    }
+
+    async fn contains_key(&self, key: &[u8]) -> Result<bool, Self::Error> {
+        let masked_key = mask(&self.secrets.key_hasher, key);
+        Ok(self.store.contains_key(&masked_key).await?)
+    }
+
+    async fn contains_keys(&self, keys: Vec<Vec<u8>>) -> Result<Vec<bool>, Self::Error> {
+        let masked_keys = keys
+            .iter()
+            .map(|key| mask(&self.secrets.key_hasher, key))
+            .collect();
+        Ok(self.store.contains_keys(masked_keys).await?)
+    }
+
+    async fn read_multi_values_bytes(
+        &self,
+        keys: Vec<Vec<u8>>,
+    ) -> Result<Vec<Option<Vec<u8>>>, Self::Error> {
+        let masked_keys = keys
+            .iter()
+            .map(|key| mask(&self.secrets.key_hasher, key))
+            .collect();
+        let values = self.store.read_multi_values_bytes(masked_keys).await?;
+        keys.iter()
+            .zip(values)
+            .map(|(key, value)| value.map(|value| self.decrypt(key, &value)).transpose())
+            .collect()
+    }
+
+    async fn find_keys_by_prefix(&self, key_prefix: &[u8]) -> Result<Vec<Vec<u8>>, Self::Error> {
+        let mut hasher = self.secrets.key_hasher.clone();
+        let masked_prefix = mask(&hasher, key_prefix);
+        hasher.update(key_prefix);
+        let masked_suffixes = self.store.find_keys_by_prefix(&masked_prefix).await?;
+        let mut keys = masked_suffixes
+            .iter()
+            .map(|suffix| unmask(&hasher, suffix))
+            .collect::<Vec<_>>();
+        keys.sort_unstable();
+        Ok(keys)
+    }
+
+    async fn find_key_values_by_prefix(
+        &self,
+        key_prefix: &[u8],
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
+        let mut hasher = self.secrets.key_hasher.clone();
+        let masked_prefix = mask(&hasher, key_prefix);
+        hasher.update(key_prefix);
+        let masked_key_values = self.store.find_key_values_by_prefix(&masked_prefix).await?;
+        let mut key_values = masked_key_values
+            .into_iter()
+            .map(|(suffix, value)| {
+                let suffix = unmask(&hasher, &suffix);
+                let key = [key_prefix, &suffix[..]].concat();
+                let value = self.decrypt(&key, &value)?;
+                Ok((suffix, value))
+            })
+            .collect::<Result<Vec<_>, Self::Error>>()?;
+        key_values.sort_unstable_by(|(key1, _), (key2, _)| key1.cmp(key2));
+        Ok(key_values)
+    }
+
+    // The masked keys are not ordered, so the bounded queries read all the keys sharing
+    // the common prefix of the bounds. The limit can't be passed to the inner store, since
+    // the first masked keys it returns are not the first keys of the range.
+    async fn find_keys_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let common_prefix = common_prefix(query);
+        let suffixes = self
+            .find_keys_by_prefix(&[key_prefix, common_prefix].concat())
+            .await?;
+        let keys = suffixes
+            .into_iter()
+            .map(|suffix| [common_prefix, &suffix[..]].concat())
+            .collect();
+        Ok(query.select(keys, |key| key.as_slice()))
+    }
+
+    async fn find_key_values_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let common_prefix = common_prefix(query);
+        let suffix_values = self
+            .find_key_values_by_prefix(&[key_prefix, common_prefix].concat())
+            .await?;
+        let key_values = suffix_values
+            .into_iter()
+            .map(|(suffix, value)| ([common_prefix, &suffix[..]].concat(), value))
+            .collect();
+        Ok(query.select(key_values, |(key, _)| key.as_slice()))
+    }
+
+    // The pages cannot be read separately from the inner store, so the whole prefix is
+    // read once rather than once per page.
+    fn find_keys_by_prefix_stream<'a>(
+        &'a self,
+        key_prefix: &'a [u8],
+        _page_size: usize,
+    ) -> impl Stream<Item = Result<Vec<u8>, Self::Error>> + 'a {
+        stream::once(self.find_keys_by_prefix(key_prefix))
+            .map_ok(|keys| stream::iter(keys.into_iter().map(Ok)))
+            .try_flatten()
+    }
+
+    fn find_key_values_by_prefix_stream<'a>(
+        &'a self,
+        key_prefix: &'a [u8],
+        _page_size: usize,
+    ) -> impl Stream<Item = Result<(Vec<u8>, Vec<u8>), Self::Error>> + 'a {
+        stream::once(self.find_key_values_by_prefix(key_prefix))
+            .map_ok(|key_values| stream::iter(key_values.into_iter().map(Ok)))
+            .try_flatten()
+    }
+}
+
+impl<S> WritableKeyValueStore for EncryptionStore<S>
+where
+    S: WritableKeyValueStore,
+    S::Error: 'static,
+{
+    // The nonce and the authentication tag are stored with every value.
+    const MAX_VALUE_SIZE: usize = S::MAX_VALUE_SIZE - NONCE_SIZE - TAG_SIZE;
+
+    async fn write_batch(&self, batch: Batch) -> Result<(), Self::Error> {
+        let mut operations = Vec::with_capacity(batch.operations.len());
+        for operation in batch.operations {
+            let operation = match operation {
+                WriteOperation::Delete { key } => WriteOperation::Delete {
+                    key: mask(&self.secrets.key_hasher, &key),
+                },
+                WriteOperation::DeletePrefix { key_prefix } => WriteOperation::DeletePrefix {
+                    key_prefix: mask(&self.secrets.key_hasher, &key_prefix),
+                },
+                WriteOperation::Put { key, value } => {
+                    let value = self.encrypt(&key, &value);
+                    let key = mask(&self.secrets.key_hasher, &key);
+                    WriteOperation::Put { key, value }
+                }
+            };
+            operations.push(operation);
+        }
+        Ok(self.store.write_batch(Batch { operations }).await?)
+    }
+
+    async fn clear_journal(&self) -> Result<(), Self::Error> {
+        Ok(self.store.clear_journal().await?)
+    }
+}
+
+/// The configuration type for the `EncryptionDatabase`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EncryptionConfig<C> {
+    /// The inner configuration of the `EncryptionDatabase`.
+    pub inner_config: C,
+    /// The encryption being used.
+    pub storage_encryption_config: StorageEncryptionConfig,
+}
+
+impl<D> KeyValueDatabase for EncryptionDatabase<D>
+where
+    D: KeyValueDatabase,
+    D::Error: 'static,
+{
+    type Config = EncryptionConfig<D::Config>;
+
+    type Store = EncryptionStore<D::Store>;
+
+    fn get_name() -> String {
+        format!("encryption {}", D::get_name())
+    }
+
+    async fn connect(config: &Self::Config, namespace: &str) -> Result<Self, Self::Error> {
+        let database = D::connect(&config.inner_config, namespace).await?;
+        let secrets = Arc::new(EncryptionSecrets::new(
+            &config.storage_encryption_config.key,
+        ));
+        Ok(EncryptionDatabase { database, secrets })
+    }
+
+    fn open_shared(&self, root_key: &[u8]) -> Result<Self::Store, Self::Error> {
+        let masked_root_key = mask(&self.secrets.root_key_hasher, root_key);
+        let store = self.database.open_shared(&masked_root_key)?;
+        Ok(self.make_store(store, root_key))
+    }
+
+    fn open_exclusive(&self, root_key: &[u8]) -> Result<Self::Store, Self::Error> {
+        let masked_root_key = mask(&self.secrets.root_key_hasher, root_key);
+        let store = self.database.open_exclusive(&masked_root_key)?;
+        Ok(self.make_store(store, root_key))
+    }
+
+    async fn list_all(config: &Self::Config) -> Result<Vec<String>, Self::Error> {
+        Ok(D::list_all(&config.inner_config).await?)
+    }
+
+    async fn list_root_keys(
+        config: &Self::Config,
+        namespace: &str,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        let secrets = EncryptionSecrets::new(&config.storage_encryption_config.key);
+        let masked_root_keys = D::list_root_keys(&config.inner_config, namespace).await?;
+        Ok(masked_root_keys
+            .iter()
+            .map(|root_key| unmask(&secrets.root_key_hasher, root_key))
+            .collect())
+    }
+
+    async fn delete_all(config: &Self::Config) -> Result<(), Self::Error> {
+        Ok(D::delete_all(&config.inner_config).await?)
+    }
+
+    async fn exists(config: &Self::Config, namespace: &str) -> Result<bool, Self::Error> {
+        Ok(D::exists(&config.inner_config, namespace).await?)
+    }
+
+    async fn create(config: &Self::Config, namespace: &str) -> Result<(), Self::Error> {
+        Ok(D::create(&config.inner_config, namespace).await?)
+    }
+
+    async fn delete(config: &Self::Config, namespace: &str) -> Result<(), Self::Error> {
+        Ok(D::delete(&config.inner_config, namespace).await?)
+    }
+
+    async fn snapshot(
+        config: &Self::Config,
+        namespace: &str,
+        snapshot: &str,
+    ) -> Result<(), Self::Error> {
+        Ok(D::snapshot(&config.inner_config, namespace, snapshot).await?)
+    }
+}
+
+impl<D> EncryptionDatabase<D> {
+    fn make_store<S>(&self, store: S, root_key: &[u8]) -> EncryptionStore<S> {
+        EncryptionStore {
+            store,
+            secrets: self.secrets.clone(),
+            root_key: root_key.to_vec(),
+        }
+    }
+}
+
+impl<S> EncryptionStore<S>
+where
+    S: WithError,
+{
+    /// Creates a new key-value store that encrypts the keys and values of the given store.
+    pub fn new(store: S, config: &StorageEncryptionConfig) -> Self {
+        Self {
+            store,
+            secrets: Arc::new(EncryptionSecrets::new(&config.key)),
+            root_key: Vec::new(),
+        }
+    }
+
+    /// Returns the data authenticated together with the value of `key`.
+    fn associated_data(&self, key: &[u8]) -> Vec<u8> {
+        let mut data = Vec::with_capacity(4 + self.root_key.len() + key.len());
+        data.extend((self.root_key.len() as u32).to_le_bytes());
+        data.extend_from_slice(&self.root_key);
+        data.extend_from_slice(key);
+        data
+    }
+
+    /// Returns the encryption of the `value` of `key`, prefixed with a random nonce.
+    fn encrypt(&self, key: &[u8], value: &[u8]) -> Vec<u8> {
+        let mut nonce = [0; NONCE_SIZE];
+        rand::rngs::OsRng.fill_bytes(&mut nonce);
+        let payload = Payload {
+            msg: value,
+            aad: &self.associated_data(key),
+        };
+        let ciphertext = self
+            .secrets
+            .cipher
+            .encrypt(XNonce::from_slice(&nonce), payload)
+            .expect("encryption only fails for values larger than 256 GiB");
+        [&nonce[..], &ciphertext[..]].concat()
+    }
+
+    /// Returns the value of `key` from its encryption.
+    fn decrypt(&self, key: &[u8], stored: &[u8]) -> Result<Vec<u8>, EncryptionError<S::Error>> {
+        if stored.len() < NONCE_SIZE + TAG_SIZE {
+            return Err(EncryptionError::TruncatedValue);
+        }
+        let (nonce, ciphertext) = stored.split_at(NONCE_SIZE);
+        let payload = Payload {
+            msg: ciphertext,
+            aad: &self.associated_data(key),
+        };
+        self.secrets
+            .cipher
+            .decrypt(XNonce::from_slice(nonce), payload)
+            .map_err(|_| EncryptionError::DecryptionFailure)
+    }
+}
+
+/// A memory database with encryption.
+#[cfg(with_testing)]
+pub type EncryptionMemoryDatabase = EncryptionDatabase<MemoryDatabase>;
+
+#[cfg(with_testing)]
+impl<D> TestKeyValueDatabase for EncryptionDatabase<D>
+where
+    D: TestKeyValueDatabase,
+    D::Error: 'static,
+{
+    async fn new_test_config() -> Result<EncryptionConfig<D::Config>, Self::Error> {
+        let inner_config = D::new_test_config().await?;
+        let storage_encryption_config = StorageEncryptionConfig {
+            key: EncryptionKey::new([7; ENCRYPTION_KEY_SIZE]),
+        };
+        Ok(EncryptionConfig {
+            inner_config,
+            storage_encryption_config,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr as _;
+
+    use futures::TryStreamExt as _;
+
+    use super::{EncryptionKey, EncryptionStore, StorageEncryptionConfig};
+    use crate::{
+        batch::Batch,
+        memory::MemoryStore,
+        store::{Direction, RangeQuery, ReadableKeyValueStore as _, WritableKeyValueStore as _},
+    };
+
+    fn make_config(byte: u8) -> StorageEncryptionConfig {
+        StorageEncryptionConfig {
+            key: EncryptionKey::new([byte; 32]),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_encryption_hides_keys_and_values() {
+        let inner = MemoryStore::new_for_testing();
+        let store = EncryptionStore::new(inner.clone(), &make_config(1));
+        let mut batch = Batch::new();
+        batch.put_key_value_bytes(b"chain-a".to_vec(), b"secret value".to_vec());
+        batch.put_key_value_bytes(b"chain-b".to_vec(), b"other value".to_vec());
+        batch.put_key_value_bytes(b"blob".to_vec(), b"blob value".to_vec());
+        store.write_batch(batch).await.unwrap();
+
+        let stored = inner.find_key_values_by_prefix(&[]).await.unwrap();
+        assert_eq!(stored.len(), 3);
+        for (key, value) in &stored {
+            assert!(!key.starts_with(b"chain") && !key.starts_with(b"blob"));
+            assert!(!value.windows(5).any(|window| window == b"value"));
+        }
+
+        assert_eq!(
+            store.read_value_bytes(b"chain-a").await.unwrap(),
+            Some(b"secret value".to_vec())
+        );
+        assert_eq!(
+            store.find_keys_by_prefix(b"chain-").await.unwrap(),
+            vec![b"a".to_vec(), b"b".to_vec()]
+        );
+        assert_eq!(
+            store.find_key_values_by_prefix(b"b").await.unwrap(),
+            vec![(b"lob".to_vec(), b"blob value".to_vec())]
+        );
+
+        let mut batch = Batch::new();
+        batch.delete_key_prefix(b"chain".to_vec());
+        store.write_batch(batch).await.unwrap();
+        assert_eq!(
+            store.find_keys_by_prefix(&[]).await.unwrap(),
+            vec![b"blob".to_vec()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_encryption_ranges_and_streams() {
+        let store = EncryptionStore::new(MemoryStore::new_for_testing(), &make_config(1));
+        let mut batch = Batch::new();
+        for key in [
+            &[0, 1, 1][..],
+            &[0, 1, 2],
+            &[0, 1, 2, 0],
+            &[0, 1, 3],
+            &[0, 2],
+            &[1],
+        ] {
+            batch.put_key_value_bytes(key.to_vec(), key.to_vec());
+        }
+        store.write_batch(batch).await.unwrap();
+
+        let query = RangeQuery::from_bounds(vec![1, 2]..vec![1, 3]);
+        assert_eq!(
+            store.find_keys_by_range(&[0], &query).await.unwrap(),
+            vec![vec![1, 2], vec![1, 2, 0]]
+        );
+        let query = RangeQuery::from_bounds(vec![1, 2]..)
+            .with_limit(2)
+            .with_direction(Direction::Reverse);
+        assert_eq!(
+            store.find_key_values_by_range(&[0], &query).await.unwrap(),
+            vec![(vec![2], vec![0, 2]), (vec![1, 3], vec![0, 1, 3])]
+        );
+
+        let keys = store
+            .find_keys_by_prefix_stream(&[0, 1], 1)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(keys, vec![vec![1], vec![2], vec![2, 0], vec![3]]);
+        let key_values = store
+            .find_key_values_by_prefix_stream(&[0], 2)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(key_values.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_encryption_rejects_wrong_key_and_moved_values() {
+        let inner = MemoryStore::new_for_testing();
+        let store = EncryptionStore::new(inner.clone(), &make_config(1));
+        let mut batch = Batch::new();
+        batch.put_key_value_bytes(vec![0], vec![1, 2, 3]);
+        batch.put_key_value_bytes(vec![1], vec![4, 5, 6]);
+        store.write_batch(batch).await.unwrap();
+
+        let other_store = EncryptionStore::new(inner.clone(), &make_config(2));
+        let masked_keys = inner.find_keys_by_prefix(&[]).await.unwrap();
+        let values = inner
+            .read_multi_values_bytes(masked_keys.clone())
+            .await
+            .unwrap();
+        assert!(other_store
+            .decrypt(&[0], values[0].as_ref().unwrap())
+            .is_err());
+
+        // Swapping the stored values is detected.
+        let mut batch = Batch::new();
+        batch.put_key_value_bytes(masked_keys[0].clone(), values[1].clone().unwrap());
+        batch.put_key_value_bytes(masked_keys[1].clone(), values[0].clone().unwrap());
+        inner.write_batch(batch).await.unwrap();
+        assert!(store.read_value_bytes(&[0]).await.is_err());
+    }
+
+    #[test]
+    fn test_encryption_key_from_str() {
+        let key = EncryptionKey::from_str(&"ab".repeat(32)).unwrap();
+        assert_eq!(key, EncryptionKey::new([0xab; 32]));
+        assert!(EncryptionKey::from_str("abab").is_err());
+        assert!(EncryptionKey::from_str("not hex").is_err());
+        let key1 = EncryptionKey::from_passphrase(b"passphrase", b"linera-salt").unwrap();
+        let key2 = EncryptionKey::from_passphrase(b"passphrase", b"linera-salt").unwrap();
+        assert_eq!(key1, key2);
+        assert!(EncryptionKey::from_passphrase(b"passphrase", b"salt").is_err());
+    }
+}
@@ -12,10 +12,10 @@ use thiserror::Error;
 
 use crate::{
     batch::{Batch, WriteOperation},
-    common::get_upper_bound_option,
+    common::{copy_namespace, get_range_bounds, get_upper_bound_option},
     store::{
-        KeyValueDatabase, KeyValueStoreError, ReadableKeyValueStore, WithError,
-        WritableKeyValueStore,
+        Direction, KeyValueDatabase, KeyValueStoreError, RangeQuery, ReadableKeyValueStore,
+        WithError, WritableKeyValueStore,
     },
 };
 
@@ -104,6 +104,71 @@ fn prefix_to_range(prefix: &[u8]) -> Result<web_sys::IdbKeyRange, wasm_bindgen::
     }
 }
 
+fn query_to_range(
+    prefix: &[u8],
+    query: &RangeQuery,
+) -> Result<web_sys::IdbKeyRange, wasm_bindgen::JsValue> {
+    let (lower, upper) = get_range_bounds(prefix, query);
+    let lower = js_sys::Uint8Array::from(&lower[..]);
+    if let Some(upper) = upper {
+        let upper = js_sys::Uint8Array::from(&upper[..]);
+        web_sys::IdbKeyRange::bound_with_lower_open_and_upper_open(
+            &lower.into(),
+            &upper.into(),
+            false,
+            true,
+        )
+    } else {
+        web_sys::IdbKeyRange::lower_bound(&lower.into())
+    }
+}
+
+impl IndexedDbStore {
+    /// Collects the entries selected by a range query with a cursor going in the
+    /// direction of the query.
+    async fn find_key_values_by_range_internal(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, IndexedDbStoreError> {
+        let mut key_values = vec![];
+        if query.is_empty() {
+            return Ok(key_values);
+        }
+        let key_prefix = self.full_key(key_prefix);
+        let range = query_to_range(&key_prefix, query)?;
+        let direction = match query.direction {
+            Direction::Forward => web_sys::IdbCursorDirection::Next,
+            Direction::Reverse => web_sys::IdbCursorDirection::Prev,
+        };
+        let limit = query.limit.unwrap_or(usize::MAX);
+        let transaction = self.database.transaction_on_one(&self.object_store_name)?;
+        let object_store = transaction.object_store(&self.object_store_name)?;
+        let Some(cursor) = object_store
+            .open_cursor_with_range_and_direction_owned(range, direction)?
+            .await?
+        else {
+            return Ok(key_values);
+        };
+
+        while key_values.len() < limit {
+            let Some(key) = cursor.primary_key() else {
+                break;
+            };
+            let key = js_sys::Uint8Array::new(&key);
+            key_values.push((
+                key.subarray(key_prefix.len() as u32, key.length()).to_vec(),
+                js_sys::Uint8Array::new(&cursor.value()).to_vec(),
+            ));
+            if !cursor.continue_cursor()?.await? {
+                break;
+            }
+        }
+
+        Ok(key_values)
+    }
+}
+
 impl WithError for IndexedDbStore {
     type Error = IndexedDbStoreError;
 }
@@ -199,6 +264,26 @@ impl ReadableKeyValueStore for IndexedDbStore {
 
         Ok(key_values)
     }
+
+    async fn find_keys_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<Vec<u8>>, IndexedDbStoreError> {
+        let key_values = self
+            .find_key_values_by_range_internal(key_prefix, query)
+            .await?;
+        Ok(key_values.into_iter().map(|(key, _)| key).collect())
+    }
+
+    async fn find_key_values_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, IndexedDbStoreError> {
+        self.find_key_values_by_range_internal(key_prefix, query)
+            .await
+    }
 }
 
 impl WritableKeyValueStore for IndexedDbStore {
@@ -333,6 +418,14 @@ impl KeyValueDatabase for IndexedDbDatabase {
             .database
             .delete_object_store(namespace)?)
     }
+
+    async fn snapshot(
+        config: &Self::Config,
+        namespace: &str,
+        snapshot: &str,
+    ) -> Result<(), IndexedDbStoreError> {
+        copy_namespace::<Self>(config, namespace, snapshot).await
+    }
 }
 
 #[cfg(with_testing)]
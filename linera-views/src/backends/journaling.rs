@@ -26,7 +26,7 @@ use thiserror::Error;
 use crate::{
     batch::{Batch, BatchValueWriter, DeletePrefixExpander, SimplifiedBatch},
     store::{
-        DirectKeyValueStore, KeyValueDatabase, ReadableKeyValueStore, WithError,
+        DirectKeyValueStore, KeyValueDatabase, RangeQuery, ReadableKeyValueStore, WithError,
         WritableKeyValueStore,
     },
     views::MIN_VIEW_TAG,
@@ -152,6 +152,22 @@ where
     ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
         self.store.find_key_values_by_prefix(key_prefix).await
     }
+
+    async fn find_keys_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        self.store.find_keys_by_range(key_prefix, query).await
+    }
+
+    async fn find_key_values_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
+        self.store.find_key_values_by_range(key_prefix, query).await
+    }
 }
 
 impl<D> KeyValueDatabase for JournalingKeyValueDatabase<D>
@@ -212,6 +228,14 @@ where
     async fn delete(config: &Self::Config, namespace: &str) -> Result<(), Self::Error> {
         D::delete(config, namespace).await
     }
+
+    async fn snapshot(
+        config: &Self::Config,
+        namespace: &str,
+        snapshot: &str,
+    ) -> Result<(), Self::Error> {
+        D::snapshot(config, namespace, snapshot).await
+    }
 }
 
 impl<S> WritableKeyValueStore for JournalingKeyValueStore<S>
@@ -18,7 +18,9 @@ use crate::store::TestKeyValueDatabase;
 use crate::{
     batch::{Batch, WriteOperation},
     common::get_interval,
-    store::{KeyValueDatabase, ReadableKeyValueStore, WithError, WritableKeyValueStore},
+    store::{
+        KeyValueDatabase, RangeQuery, ReadableKeyValueStore, WithError, WritableKeyValueStore,
+    },
 };
 
 #[cfg(with_metrics)]
@@ -436,6 +438,22 @@ where
     ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
         self.store.find_key_values_by_prefix(key_prefix).await
     }
+
+    async fn find_keys_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        self.store.find_keys_by_range(key_prefix, query).await
+    }
+
+    async fn find_key_values_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
+        self.store.find_key_values_by_range(key_prefix, query).await
+    }
 }
 
 impl<K> WritableKeyValueStore for LruCachingStore<K>
@@ -551,6 +569,14 @@ where
     async fn delete(config: &Self::Config, namespace: &str) -> Result<(), Self::Error> {
         D::delete(&config.inner_config, namespace).await
     }
+
+    async fn snapshot(
+        config: &Self::Config,
+        namespace: &str,
+        snapshot: &str,
+    ) -> Result<(), Self::Error> {
+        D::snapshot(&config.inner_config, namespace, snapshot).await
+    }
 }
 
 impl<S> LruCachingStore<S> {
@@ -15,10 +15,10 @@ use thiserror::Error;
 use crate::store::TestKeyValueDatabase;
 use crate::{
     batch::{Batch, WriteOperation},
-    common::get_interval,
+    common::{get_interval, get_range_interval},
     store::{
-        KeyValueDatabase, KeyValueStoreError, ReadableKeyValueStore, WithError,
-        WritableKeyValueStore,
+        Direction, KeyValueDatabase, KeyValueStoreError, RangeQuery, ReadableKeyValueStore,
+        WithError, WritableKeyValueStore,
     },
 };
 
@@ -100,6 +100,24 @@ impl MemoryDatabases {
     fn sync_delete(&mut self, namespace: &str) {
         self.databases.remove(namespace);
     }
+
+    fn sync_snapshot(&mut self, namespace: &str, snapshot: &str) -> Result<(), MemoryStoreError> {
+        if self.sync_exists(snapshot) {
+            return Err(MemoryStoreError::StoreAlreadyExists);
+        }
+        let Some(stores) = self.databases.get(namespace) else {
+            return Err(MemoryStoreError::NamespaceNotFound);
+        };
+        let stores = stores
+            .iter()
+            .map(|(root_key, map)| {
+                let map = map.read().expect("MemoryStore lock should not be poisoned");
+                (root_key.clone(), Arc::new(RwLock::new(map.clone())))
+            })
+            .collect();
+        self.databases.insert(snapshot.to_string(), stores);
+        Ok(())
+    }
 }
 
 /// The global table of namespaces.
@@ -204,6 +222,63 @@ impl ReadableKeyValueStore for MemoryStore {
         }
         Ok(key_values)
     }
+
+    async fn find_keys_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<Vec<u8>>, MemoryStoreError> {
+        Ok(self
+            .find_key_values_by_range_internal(key_prefix, query, false)
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect())
+    }
+
+    async fn find_key_values_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, MemoryStoreError> {
+        Ok(self.find_key_values_by_range_internal(key_prefix, query, true))
+    }
+}
+
+impl MemoryStore {
+    /// Collects the entries selected by a range query. The values are left empty
+    /// unless `with_values` is set.
+    fn find_key_values_by_range_internal(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+        with_values: bool,
+    ) -> Vec<(Vec<u8>, Vec<u8>)> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let map = self
+            .map
+            .read()
+            .expect("MemoryStore lock should not be poisoned");
+        let len = key_prefix.len();
+        let limit = query.limit.unwrap_or(usize::MAX);
+        let range = map.range(get_range_interval(key_prefix, query));
+        let entries: Box<dyn Iterator<Item = _>> = match query.direction {
+            Direction::Forward => Box::new(range),
+            Direction::Reverse => Box::new(range.rev()),
+        };
+        entries
+            .take(limit)
+            .map(|(key, value)| {
+                let value = if with_values {
+                    value.clone()
+                } else {
+                    Vec::new()
+                };
+                (key[len..].to_vec(), value)
+            })
+            .collect()
+    }
 }
 
 impl WritableKeyValueStore for MemoryStore {
@@ -339,6 +414,17 @@ impl KeyValueDatabase for MemoryDatabase {
         databases.sync_delete(namespace);
         Ok(())
     }
+
+    async fn snapshot(
+        _config: &Self::Config,
+        namespace: &str,
+        snapshot: &str,
+    ) -> Result<(), MemoryStoreError> {
+        let mut databases = MEMORY_DATABASES
+            .lock()
+            .expect("MEMORY_DATABASES lock should not be poisoned");
+        databases.sync_snapshot(namespace, snapshot)
+    }
 }
 
 #[cfg(with_testing)]
@@ -18,7 +18,9 @@ use prometheus::{HistogramVec, IntCounterVec};
 use crate::store::TestKeyValueDatabase;
 use crate::{
     batch::Batch,
-    store::{KeyValueDatabase, ReadableKeyValueStore, WithError, WritableKeyValueStore},
+    store::{
+        KeyValueDatabase, RangeQuery, ReadableKeyValueStore, WithError, WritableKeyValueStore,
+    },
 };
 
 #[derive(Clone)]
@@ -30,6 +32,8 @@ pub struct KeyValueStoreMetrics {
     read_multi_values_bytes_latency: HistogramVec,
     find_keys_by_prefix_latency: HistogramVec,
     find_key_values_by_prefix_latency: HistogramVec,
+    find_keys_by_range_latency: HistogramVec,
+    find_key_values_by_range_latency: HistogramVec,
     write_batch_latency: HistogramVec,
     clear_journal_latency: HistogramVec,
     connect_latency: HistogramVec,
@@ -41,6 +45,7 @@ pub struct KeyValueStoreMetrics {
     exists_latency: HistogramVec,
     create_latency: HistogramVec,
     delete_latency: HistogramVec,
+    snapshot_latency: HistogramVec,
     read_value_none_cases: IntCounterVec,
     read_value_key_size: HistogramVec,
     read_value_value_size: HistogramVec,
@@ -55,6 +60,8 @@ pub struct KeyValueStoreMetrics {
     find_key_values_by_prefix_prefix_size: HistogramVec,
     find_key_values_by_prefix_num_keys: HistogramVec,
     find_key_values_by_prefix_key_values_size: HistogramVec,
+    find_keys_by_range_num_keys: HistogramVec,
+    find_key_values_by_range_num_keys: HistogramVec,
     write_batch_size: HistogramVec,
     list_all_sizes: HistogramVec,
     exists_true_cases: IntCounterVec,
@@ -116,6 +123,14 @@ impl KeyValueStoreMetrics {
         let entry2 = format!("{} find key values by prefix latency", title_name);
         let find_key_values_by_prefix_latency = register_histogram_vec(&entry1, &entry2, &[], None);
 
+        let entry1 = format!("{}_find_keys_by_range_latency", var_name);
+        let entry2 = format!("{} find keys by range latency", title_name);
+        let find_keys_by_range_latency = register_histogram_vec(&entry1, &entry2, &[], None);
+
+        let entry1 = format!("{}_find_key_values_by_range_latency", var_name);
+        let entry2 = format!("{} find key values by range latency", title_name);
+        let find_key_values_by_range_latency = register_histogram_vec(&entry1, &entry2, &[], None);
+
         let entry1 = format!("{}_write_batch_latency", var_name);
         let entry2 = format!("{} write batch latency", title_name);
         let write_batch_latency = register_histogram_vec(&entry1, &entry2, &[], None);
@@ -160,6 +175,10 @@ impl KeyValueStoreMetrics {
         let entry2 = format!("{} delete latency", title_name);
         let delete_latency = register_histogram_vec(&entry1, &entry2, &[], None);
 
+        let entry1 = format!("{}_snapshot_latency", var_name);
+        let entry2 = format!("{} snapshot latency", title_name);
+        let snapshot_latency = register_histogram_vec(&entry1, &entry2, &[], None);
+
         let entry1 = format!("{}_read_value_none_cases", var_name);
         let entry2 = format!("{} read value none cases", title_name);
         let read_value_none_cases = register_int_counter_vec(&entry1, &entry2, &[]);
@@ -219,6 +238,14 @@ impl KeyValueStoreMetrics {
         let find_key_values_by_prefix_key_values_size =
             register_histogram_vec(&entry1, &entry2, &[], None);
 
+        let entry1 = format!("{}_find_keys_by_range_num_keys", var_name);
+        let entry2 = format!("{} find keys by range num keys", title_name);
+        let find_keys_by_range_num_keys = register_histogram_vec(&entry1, &entry2, &[], None);
+
+        let entry1 = format!("{}_find_key_values_by_range_num_keys", var_name);
+        let entry2 = format!("{} find key values by range num keys", title_name);
+        let find_key_values_by_range_num_keys = register_histogram_vec(&entry1, &entry2, &[], None);
+
         let entry1 = format!("{}_write_batch_size", var_name);
         let entry2 = format!("{} write batch size", title_name);
         let write_batch_size = register_histogram_vec(&entry1, &entry2, &[], None);
@@ -238,6 +265,8 @@ impl KeyValueStoreMetrics {
             read_multi_values_bytes_latency,
             find_keys_by_prefix_latency,
             find_key_values_by_prefix_latency,
+            find_keys_by_range_latency,
+            find_key_values_by_range_latency,
             write_batch_latency,
             clear_journal_latency,
             connect_latency,
@@ -249,6 +278,7 @@ impl KeyValueStoreMetrics {
             exists_latency,
             create_latency,
             delete_latency,
+            snapshot_latency,
             read_value_none_cases,
             read_value_key_size,
             read_value_value_size,
@@ -263,6 +293,8 @@ impl KeyValueStoreMetrics {
             find_key_values_by_prefix_prefix_size,
             find_key_values_by_prefix_num_keys,
             find_key_values_by_prefix_key_values_size,
+            find_keys_by_range_num_keys,
+            find_key_values_by_range_num_keys,
             write_batch_size,
             list_all_sizes,
             exists_true_cases,
@@ -426,6 +458,40 @@ where
             .observe(key_values_size as f64);
         Ok(result)
     }
+
+    async fn find_keys_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        let _latency = self.counter.find_keys_by_range_latency.measure_latency();
+        let result = self.store.find_keys_by_range(key_prefix, query).await?;
+        self.counter
+            .find_keys_by_range_num_keys
+            .with_label_values(&[])
+            .observe(result.len() as f64);
+        Ok(result)
+    }
+
+    async fn find_key_values_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
+        let _latency = self
+            .counter
+            .find_key_values_by_range_latency
+            .measure_latency();
+        let result = self
+            .store
+            .find_key_values_by_range(key_prefix, query)
+            .await?;
+        self.counter
+            .find_key_values_by_range_num_keys
+            .with_label_values(&[])
+            .observe(result.len() as f64);
+        Ok(result)
+    }
 }
 
 impl<S> WritableKeyValueStore for MeteredStore<S>
@@ -538,6 +604,17 @@ where
         let _latency = counter.delete_latency.measure_latency();
         D::delete(config, namespace).await
     }
+
+    async fn snapshot(
+        config: &Self::Config,
+        namespace: &str,
+        snapshot: &str,
+    ) -> Result<(), Self::Error> {
+        let name = D::get_name();
+        let counter = get_counter(&name);
+        let _latency = counter.snapshot_latency.measure_latency();
+        D::snapshot(config, namespace, snapshot).await
+    }
 }
 
 #[cfg(with_testing)]
@@ -12,6 +12,11 @@ pub mod memory;
 
 pub mod lru_caching;
 
+pub mod compression;
+
+#[cfg(with_encryption)]
+pub mod encryption;
+
 pub mod dual;
 
 #[cfg(with_scylladb)]
@@ -20,6 +25,9 @@ pub mod scylla_db;
 #[cfg(with_rocksdb)]
 pub mod rocks_db;
 
+#[cfg(with_redb)]
+pub mod redb_db;
+
 #[cfg(with_dynamodb)]
 pub mod dynamo_db;
 
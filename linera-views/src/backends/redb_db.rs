@@ -0,0 +1,618 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Implements [`crate::store::KeyValueStore`] for the embedded redb database.
+//!
+//! Every namespace is a single redb file in the directory of the configuration. redb is
+//! written in Rust, so this backend is lighter to build and to run than RocksDB. It is
+//! meant for single-node deployments and tests.
+
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    ops::Bound,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, LazyLock, Mutex, Weak,
+    },
+};
+
+use linera_base::ensure;
+use redb::{ReadableTable as _, TableDefinition};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+pub use crate::common::PathWithGuard;
+#[cfg(with_metrics)]
+use crate::metering::MeteredDatabase;
+#[cfg(with_testing)]
+use crate::store::TestKeyValueDatabase;
+use crate::{
+    batch::{Batch, WriteOperation},
+    common::{get_range_bounds, get_upper_bound_option},
+    lru_caching::{LruCachingConfig, LruCachingDatabase},
+    store::{
+        Direction, KeyValueDatabase, KeyValueStoreError, RangeQuery, ReadableKeyValueStore,
+        WithError, WritableKeyValueStore,
+    },
+    value_splitting::{ValueSplittingDatabase, ValueSplittingError},
+};
+
+/// The prefixes being used in the system
+static ROOT_KEY_DOMAIN: [u8; 1] = [0];
+static STORED_ROOT_KEYS_PREFIX: u8 = 1;
+
+/// The number of streams for the test
+#[cfg(with_testing)]
+const TEST_REDB_MAX_STREAM_QUERIES: usize = 10;
+
+/// The extension of the files of the namespaces.
+const NAMESPACE_EXTENSION: &str = "redb";
+
+/// The single table holding the entries of a namespace.
+const TABLE: TableDefinition<&[u8], &[u8]> = TableDefinition::new("linera");
+
+// The lengths of keys and values are stored on 32 bits by redb.
+// For offset reasons we decrease by 400
+const MAX_VALUE_SIZE: usize = 3 * 1024 * 1024 * 1024 - 400;
+
+// The maximum size of keys is chosen as for RocksDB
+const MAX_KEY_SIZE: usize = 8 * 1024 * 1024 - 400;
+
+/// The databases that are open in this process. A redb file can only be opened once at a
+/// time, so connections to the same namespace share the same database.
+static OPEN_DATABASES: LazyLock<Mutex<HashMap<PathBuf, Weak<redb::Database>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn check_key_size(key: &[u8]) -> Result<(), RedbStoreInternalError> {
+    ensure!(
+        key.len() <= MAX_KEY_SIZE,
+        RedbStoreInternalError::KeyTooLong
+    );
+    Ok(())
+}
+
+/// Runs a blocking database operation outside of the asynchronous runtime.
+async fn spawn<F, O>(f: F) -> Result<O, RedbStoreInternalError>
+where
+    F: FnOnce() -> Result<O, RedbStoreInternalError> + Send + 'static,
+    O: Send + 'static,
+{
+    tokio::task::spawn_blocking(f).await?
+}
+
+#[derive(Clone)]
+struct RedbStoreExecutor {
+    db: Arc<redb::Database>,
+    start_key: Vec<u8>,
+}
+
+impl RedbStoreExecutor {
+    fn full_key(&self, key: &[u8]) -> Result<Vec<u8>, RedbStoreInternalError> {
+        check_key_size(key)?;
+        let mut full_key = self.start_key.clone();
+        full_key.extend(key);
+        Ok(full_key)
+    }
+
+    fn read_multi_values_bytes_internal(
+        &self,
+        keys: Vec<Vec<u8>>,
+    ) -> Result<Vec<Option<Vec<u8>>>, RedbStoreInternalError> {
+        let transaction = self.db.begin_read()?;
+        let table = transaction.open_table(TABLE)?;
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            let full_key = self.full_key(&key)?;
+            let value = table.get(full_key.as_slice())?;
+            values.push(value.map(|value| value.value().to_vec()));
+        }
+        Ok(values)
+    }
+
+    fn contains_keys_internal(
+        &self,
+        keys: Vec<Vec<u8>>,
+    ) -> Result<Vec<bool>, RedbStoreInternalError> {
+        let transaction = self.db.begin_read()?;
+        let table = transaction.open_table(TABLE)?;
+        let mut results = Vec::with_capacity(keys.len());
+        for key in keys {
+            let full_key = self.full_key(&key)?;
+            results.push(table.get(full_key.as_slice())?.is_some());
+        }
+        Ok(results)
+    }
+
+    #[expect(clippy::type_complexity)]
+    fn find_key_values_by_range_internal(
+        &self,
+        key_prefix: Vec<u8>,
+        query: RangeQuery,
+        with_values: bool,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, RedbStoreInternalError> {
+        let prefix = self.full_key(&key_prefix)?;
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let len = prefix.len();
+        let (lower_bound, upper_bound) = get_range_bounds(&prefix, &query);
+        let upper_bound = match &upper_bound {
+            Some(upper_bound) => Bound::Excluded(upper_bound.as_slice()),
+            None => Bound::Unbounded,
+        };
+        let transaction = self.db.begin_read()?;
+        let table = transaction.open_table(TABLE)?;
+        let range = table.range::<&[u8]>((Bound::Included(lower_bound.as_slice()), upper_bound))?;
+        let entries: Box<dyn Iterator<Item = _>> = match query.direction {
+            Direction::Forward => Box::new(range),
+            Direction::Reverse => Box::new(range.rev()),
+        };
+        let limit = query.limit.unwrap_or(usize::MAX);
+        let mut key_values = Vec::new();
+        for entry in entries.take(limit) {
+            let (key, value) = entry?;
+            let value = if with_values {
+                value.value().to_vec()
+            } else {
+                Vec::new()
+            };
+            key_values.push((key.value()[len..].to_vec(), value));
+        }
+        Ok(key_values)
+    }
+
+    fn write_batch_internal(
+        &self,
+        batch: Batch,
+        write_root_key: bool,
+    ) -> Result<(), RedbStoreInternalError> {
+        let transaction = self.db.begin_write()?;
+        {
+            let mut table = transaction.open_table(TABLE)?;
+            for operation in batch.operations {
+                match operation {
+                    WriteOperation::Delete { key } => {
+                        let full_key = self.full_key(&key)?;
+                        table.remove(full_key.as_slice())?;
+                    }
+                    WriteOperation::Put { key, value } => {
+                        let full_key = self.full_key(&key)?;
+                        table.insert(full_key.as_slice(), value.as_slice())?;
+                    }
+                    WriteOperation::DeletePrefix { key_prefix } => {
+                        let full_key1 = self.full_key(&key_prefix)?;
+                        let full_key2 = get_upper_bound_option(&full_key1)
+                            .expect("the first entry cannot be 255");
+                        let keys = table
+                            .range::<&[u8]>(full_key1.as_slice()..full_key2.as_slice())?
+                            .map(|entry| Ok(entry?.0.value().to_vec()))
+                            .collect::<Result<Vec<_>, RedbStoreInternalError>>()?;
+                        for key in keys {
+                            table.remove(key.as_slice())?;
+                        }
+                    }
+                }
+            }
+            if write_root_key {
+                let mut full_key = self.start_key.clone();
+                full_key[0] = STORED_ROOT_KEYS_PREFIX;
+                let empty: &[u8] = &[];
+                table.insert(full_key.as_slice(), empty)?;
+            }
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+}
+
+/// The inner client
+#[derive(Clone)]
+pub struct RedbStoreInternal {
+    executor: RedbStoreExecutor,
+    _path_with_guard: PathWithGuard,
+    max_stream_queries: usize,
+    root_key_written: Arc<AtomicBool>,
+}
+
+/// Database-level connection to redb for managing namespaces and partitions.
+#[derive(Clone)]
+pub struct RedbDatabaseInternal {
+    executor: RedbStoreExecutor,
+    _path_with_guard: PathWithGuard,
+    max_stream_queries: usize,
+}
+
+impl WithError for RedbDatabaseInternal {
+    type Error = RedbStoreInternalError;
+}
+
+/// The initial configuration of the system
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RedbStoreInternalConfig {
+    /// The path to the directory containing the namespaces
+    pub path_with_guard: PathWithGuard,
+    /// Preferred buffer size for async streams.
+    pub max_stream_queries: usize,
+}
+
+impl RedbDatabaseInternal {
+    fn check_namespace(namespace: &str) -> Result<(), RedbStoreInternalError> {
+        if !namespace
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || character == '_')
+        {
+            return Err(RedbStoreInternalError::InvalidNamespace);
+        }
+        Ok(())
+    }
+
+    fn namespace_path(
+        config: &RedbStoreInternalConfig,
+        namespace: &str,
+    ) -> Result<PathBuf, RedbStoreInternalError> {
+        Self::check_namespace(namespace)?;
+        let mut path_buf = config.path_with_guard.path_buf.clone();
+        path_buf.push(namespace);
+        path_buf.set_extension(NAMESPACE_EXTENSION);
+        Ok(path_buf)
+    }
+
+    /// Opens the database of a namespace, creating it if needed, or returns the one
+    /// already open in this process.
+    fn open(path: &Path) -> Result<Arc<redb::Database>, RedbStoreInternalError> {
+        let mut databases = OPEN_DATABASES
+            .lock()
+            .expect("the lock should not be poisoned");
+        if let Some(db) = databases.get(path).and_then(Weak::upgrade) {
+            return Ok(db);
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let db = redb::Database::create(path)?;
+        // Creating the table ensures that the read transactions can open it.
+        let transaction = db.begin_write()?;
+        transaction.open_table(TABLE)?;
+        transaction.commit()?;
+        let db = Arc::new(db);
+        databases.retain(|_, db| db.strong_count() > 0);
+        databases.insert(path.to_path_buf(), Arc::downgrade(&db));
+        Ok(db)
+    }
+
+    fn build(
+        config: &RedbStoreInternalConfig,
+        namespace: &str,
+    ) -> Result<RedbDatabaseInternal, RedbStoreInternalError> {
+        let path_buf = Self::namespace_path(config, namespace)?;
+        let db = Self::open(&path_buf)?;
+        let executor = RedbStoreExecutor {
+            db,
+            start_key: ROOT_KEY_DOMAIN.to_vec(),
+        };
+        Ok(RedbDatabaseInternal {
+            executor,
+            _path_with_guard: config.path_with_guard.clone(),
+            max_stream_queries: config.max_stream_queries,
+        })
+    }
+}
+
+impl WithError for RedbStoreInternal {
+    type Error = RedbStoreInternalError;
+}
+
+impl ReadableKeyValueStore for RedbStoreInternal {
+    const MAX_KEY_SIZE: usize = MAX_KEY_SIZE;
+
+    fn max_stream_queries(&self) -> usize {
+        self.max_stream_queries
+    }
+
+    async fn read_value_bytes(
+        &self,
+        key: &[u8],
+    ) -> Result<Option<Vec<u8>>, RedbStoreInternalError> {
+        let executor = self.executor.clone();
+        let keys = vec![key.to_vec()];
+        let mut values = spawn(move || executor.read_multi_values_bytes_internal(keys)).await?;
+        Ok(values.pop().flatten())
+    }
+
+    async fn contains_key(&self, key: &[u8]) -> Result<bool, RedbStoreInternalError> {
+        let executor = self.executor.clone();
+        let keys = vec![key.to_vec()];
+        let results = spawn(move || executor.contains_keys_internal(keys)).await?;
+        Ok(results[0])
+    }
+
+    async fn contains_keys(&self, keys: Vec<Vec<u8>>) -> Result<Vec<bool>, RedbStoreInternalError> {
+        let executor = self.executor.clone();
+        spawn(move || executor.contains_keys_internal(keys)).await
+    }
+
+    async fn read_multi_values_bytes(
+        &self,
+        keys: Vec<Vec<u8>>,
+    ) -> Result<Vec<Option<Vec<u8>>>, RedbStoreInternalError> {
+        let executor = self.executor.clone();
+        spawn(move || executor.read_multi_values_bytes_internal(keys)).await
+    }
+
+    async fn find_keys_by_prefix(
+        &self,
+        key_prefix: &[u8],
+    ) -> Result<Vec<Vec<u8>>, RedbStoreInternalError> {
+        self.find_keys_by_range(key_prefix, &RangeQuery::default())
+            .await
+    }
+
+    async fn find_key_values_by_prefix(
+        &self,
+        key_prefix: &[u8],
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, RedbStoreInternalError> {
+        self.find_key_values_by_range(key_prefix, &RangeQuery::default())
+            .await
+    }
+
+    async fn find_keys_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<Vec<u8>>, RedbStoreInternalError> {
+        let executor = self.executor.clone();
+        let key_prefix = key_prefix.to_vec();
+        let query = query.clone();
+        let key_values =
+            spawn(move || executor.find_key_values_by_range_internal(key_prefix, query, false))
+                .await?;
+        Ok(key_values.into_iter().map(|(key, _)| key).collect())
+    }
+
+    async fn find_key_values_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, RedbStoreInternalError> {
+        let executor = self.executor.clone();
+        let key_prefix = key_prefix.to_vec();
+        let query = query.clone();
+        spawn(move || executor.find_key_values_by_range_internal(key_prefix, query, true)).await
+    }
+}
+
+impl WritableKeyValueStore for RedbStoreInternal {
+    const MAX_VALUE_SIZE: usize = MAX_VALUE_SIZE;
+
+    async fn write_batch(&self, batch: Batch) -> Result<(), RedbStoreInternalError> {
+        let write_root_key = !self.root_key_written.fetch_or(true, Ordering::SeqCst);
+        let executor = self.executor.clone();
+        spawn(move || executor.write_batch_internal(batch, write_root_key)).await
+    }
+
+    async fn clear_journal(&self) -> Result<(), RedbStoreInternalError> {
+        Ok(())
+    }
+}
+
+impl KeyValueDatabase for RedbDatabaseInternal {
+    type Config = RedbStoreInternalConfig;
+    type Store = RedbStoreInternal;
+
+    fn get_name() -> String {
+        "redb internal".to_string()
+    }
+
+    async fn connect(
+        config: &Self::Config,
+        namespace: &str,
+    ) -> Result<Self, RedbStoreInternalError> {
+        let config = config.clone();
+        let namespace = namespace.to_string();
+        spawn(move || Self::build(&config, &namespace)).await
+    }
+
+    fn open_shared(&self, root_key: &[u8]) -> Result<Self::Store, RedbStoreInternalError> {
+        let mut start_key = ROOT_KEY_DOMAIN.to_vec();
+        start_key.extend(root_key);
+        let mut executor = self.executor.clone();
+        executor.start_key = start_key;
+        Ok(RedbStoreInternal {
+            executor,
+            _path_with_guard: self._path_with_guard.clone(),
+            max_stream_queries: self.max_stream_queries,
+            root_key_written: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    fn open_exclusive(&self, root_key: &[u8]) -> Result<Self::Store, RedbStoreInternalError> {
+        self.open_shared(root_key)
+    }
+
+    async fn list_all(config: &Self::Config) -> Result<Vec<String>, RedbStoreInternalError> {
+        let path = &config.path_with_guard.path_buf;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let mut namespaces = Vec::new();
+        for entry in std::fs::read_dir(path)? {
+            let path = entry?.path();
+            if path.extension().and_then(|extension| extension.to_str())
+                != Some(NAMESPACE_EXTENSION)
+            {
+                continue;
+            }
+            let Some(namespace) = path.file_stem() else {
+                continue;
+            };
+            let namespace = namespace
+                .to_os_string()
+                .into_string()
+                .map_err(RedbStoreInternalError::IntoStringError)?;
+            namespaces.push(namespace);
+        }
+        Ok(namespaces)
+    }
+
+    async fn list_root_keys(
+        config: &Self::Config,
+        namespace: &str,
+    ) -> Result<Vec<Vec<u8>>, RedbStoreInternalError> {
+        let mut database = Self::connect(config, namespace).await?;
+        database.executor.start_key = vec![STORED_ROOT_KEYS_PREFIX];
+        let executor = database.executor;
+        let key_values = spawn(move || {
+            executor.find_key_values_by_range_internal(Vec::new(), RangeQuery::default(), false)
+        })
+        .await?;
+        Ok(key_values.into_iter().map(|(key, _)| key).collect())
+    }
+
+    async fn exists(
+        config: &Self::Config,
+        namespace: &str,
+    ) -> Result<bool, RedbStoreInternalError> {
+        let path_buf = Self::namespace_path(config, namespace)?;
+        Ok(path_buf.exists())
+    }
+
+    async fn create(config: &Self::Config, namespace: &str) -> Result<(), RedbStoreInternalError> {
+        let path_buf = Self::namespace_path(config, namespace)?;
+        if path_buf.exists() {
+            return Err(RedbStoreInternalError::StoreAlreadyExists);
+        }
+        spawn(move || {
+            Self::open(&path_buf)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn delete(config: &Self::Config, namespace: &str) -> Result<(), RedbStoreInternalError> {
+        let path_buf = Self::namespace_path(config, namespace)?;
+        OPEN_DATABASES
+            .lock()
+            .expect("the lock should not be poisoned")
+            .remove(&path_buf);
+        std::fs::remove_file(path_buf)?;
+        Ok(())
+    }
+
+    async fn snapshot(
+        config: &Self::Config,
+        namespace: &str,
+        snapshot: &str,
+    ) -> Result<(), RedbStoreInternalError> {
+        let path_buf = Self::namespace_path(config, namespace)?;
+        let snapshot_path_buf = Self::namespace_path(config, snapshot)?;
+        if snapshot_path_buf.exists() {
+            return Err(RedbStoreInternalError::StoreAlreadyExists);
+        }
+        spawn(move || {
+            let db = Self::open(&path_buf)?;
+            let snapshot_db = Self::open(&snapshot_path_buf)?;
+            // The read transaction sees the state of the last commit, so the copy is
+            // point-in-time even if the namespace is written concurrently.
+            let read_transaction = db.begin_read()?;
+            let table = read_transaction.open_table(TABLE)?;
+            let write_transaction = snapshot_db.begin_write()?;
+            {
+                let mut snapshot_table = write_transaction.open_table(TABLE)?;
+                for entry in table.range::<&[u8]>(..)? {
+                    let (key, value) = entry?;
+                    snapshot_table.insert(key.value(), value.value())?;
+                }
+            }
+            write_transaction.commit()?;
+            Ok(())
+        })
+        .await
+    }
+}
+
+#[cfg(with_testing)]
+impl TestKeyValueDatabase for RedbDatabaseInternal {
+    async fn new_test_config() -> Result<RedbStoreInternalConfig, RedbStoreInternalError> {
+        let path_with_guard = PathWithGuard::new_testing();
+        let max_stream_queries = TEST_REDB_MAX_STREAM_QUERIES;
+        Ok(RedbStoreInternalConfig {
+            path_with_guard,
+            max_stream_queries,
+        })
+    }
+}
+
+/// The error type for [`RedbStoreInternal`]
+#[derive(Error, Debug)]
+pub enum RedbStoreInternalError {
+    /// Store already exists
+    #[error("Store already exists")]
+    StoreAlreadyExists,
+
+    /// Tokio join error in redb.
+    #[error("tokio join error: {0}")]
+    TokioJoinError(#[from] tokio::task::JoinError),
+
+    /// Error when opening a redb database.
+    #[error("redb database error: {0}")]
+    Database(#[from] redb::DatabaseError),
+
+    /// Error when starting a redb transaction.
+    #[error("redb transaction error: {0}")]
+    Transaction(#[from] redb::TransactionError),
+
+    /// Error when opening the redb table.
+    #[error("redb table error: {0}")]
+    Table(#[from] redb::TableError),
+
+    /// Error when accessing the redb storage.
+    #[error("redb storage error: {0}")]
+    Storage(#[from] redb::StorageError),
+
+    /// Error when committing a redb transaction.
+    #[error("redb commit error: {0}")]
+    Commit(#[from] redb::CommitError),
+
+    /// Error converting `OsString` to `String`
+    #[error("error in the conversion from OsString: {0:?}")]
+    IntoStringError(OsString),
+
+    /// The key must have at most 8 MiB
+    #[error("The key must have at most 8 MiB")]
+    KeyTooLong,
+
+    /// Namespace contains forbidden characters
+    #[error("Namespace contains forbidden characters")]
+    InvalidNamespace,
+
+    /// Filesystem error
+    #[error("Filesystem error: {0}")]
+    FsError(#[from] std::io::Error),
+
+    /// BCS serialization error.
+    #[error(transparent)]
+    BcsError(#[from] bcs::Error),
+}
+
+impl KeyValueStoreError for RedbStoreInternalError {
+    const BACKEND: &'static str = "redb";
+}
+
+/// The composed error type for the `RedbStore`
+pub type RedbStoreError = ValueSplittingError<RedbStoreInternalError>;
+
+/// The composed config type for the `RedbStore`
+pub type RedbStoreConfig = LruCachingConfig<RedbStoreInternalConfig>;
+
+/// The `RedbDatabase` composed type with metrics
+#[cfg(with_metrics)]
+pub type RedbDatabase = MeteredDatabase<
+    LruCachingDatabase<
+        MeteredDatabase<ValueSplittingDatabase<MeteredDatabase<RedbDatabaseInternal>>>,
+    >,
+>;
+/// The `RedbDatabase` composed type
+#[cfg(not(with_metrics))]
+pub type RedbDatabase = LruCachingDatabase<ValueSplittingDatabase<RedbDatabaseInternal>>;
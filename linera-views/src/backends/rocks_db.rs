@@ -6,7 +6,7 @@
 use std::{
     ffi::OsString,
     fmt::Display,
-    path::PathBuf,
+    path::Path,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -17,20 +17,20 @@ use linera_base::ensure;
 use rocksdb::{BlockBasedOptions, Cache, DBCompactionStyle, SliceTransform};
 use serde::{Deserialize, Serialize};
 use sysinfo::{CpuRefreshKind, MemoryRefreshKind, RefreshKind, System};
-use tempfile::TempDir;
 use thiserror::Error;
 
+pub use crate::common::PathWithGuard;
 #[cfg(with_metrics)]
 use crate::metering::MeteredDatabase;
 #[cfg(with_testing)]
 use crate::store::TestKeyValueDatabase;
 use crate::{
     batch::{Batch, WriteOperation},
-    common::get_upper_bound_option,
+    common::{get_range_bounds, get_upper_bound_option},
     lru_caching::{LruCachingConfig, LruCachingDatabase},
     store::{
-        KeyValueDatabase, KeyValueStoreError, ReadableKeyValueStore, WithError,
-        WritableKeyValueStore,
+        Direction, KeyValueDatabase, KeyValueStoreError, RangeQuery, ReadableKeyValueStore,
+        WithError, WritableKeyValueStore,
     },
     value_splitting::{ValueSplittingDatabase, ValueSplittingError},
 };
@@ -58,6 +58,116 @@ const HYPER_CLOCK_CACHE_BLOCK_SIZE: usize = 8 * 1024; // 8 KiB
 /// The RocksDB client that we use.
 type DB = rocksdb::DBWithThreadMode<rocksdb::MultiThreaded>;
 
+/// Returns the options of the RocksDB databases.
+fn database_options() -> rocksdb::Options {
+    let sys = System::new_with_specifics(
+        RefreshKind::nothing()
+            .with_cpu(CpuRefreshKind::everything())
+            .with_memory(MemoryRefreshKind::nothing().with_ram()),
+    );
+    let num_cpus = sys.cpus().len() as i32;
+    let total_ram = sys.total_memory() as usize;
+    let mut options = rocksdb::Options::default();
+    options.create_if_missing(true);
+    options.create_missing_column_families(true);
+    // Flush in-memory buffer to disk more often
+    options.set_write_buffer_size(WRITE_BUFFER_SIZE);
+    options.set_max_write_buffer_number(MAX_WRITE_BUFFER_NUMBER);
+    options.set_compression_type(rocksdb::DBCompressionType::Lz4);
+    options.set_level_zero_slowdown_writes_trigger(8);
+    options.set_level_zero_stop_writes_trigger(12);
+    options.set_level_zero_file_num_compaction_trigger(2);
+    // We deliberately give RocksDB one background thread *per* CPU so that
+    // flush + (N-1) compactions can hammer the NVMe at full bandwidth while
+    // still leaving enough CPU time for the foreground application threads.
+    options.increase_parallelism(num_cpus);
+    options.set_max_background_jobs(num_cpus);
+    options.set_max_subcompactions(num_cpus as u32);
+    options.set_level_compaction_dynamic_level_bytes(true);
+
+    options.set_compaction_style(DBCompactionStyle::Level);
+    options.set_target_file_size_base(2 * WRITE_BUFFER_SIZE as u64);
+
+    let mut block_options = BlockBasedOptions::default();
+    block_options.set_pin_l0_filter_and_index_blocks_in_cache(true);
+    block_options.set_cache_index_and_filter_blocks(true);
+    // Allocate 1/4 of total RAM for RocksDB block cache, which is a reasonable balance:
+    // - Large enough to significantly improve read performance by caching frequently accessed blocks
+    // - Small enough to leave memory for other system components
+    // - Follows common practice for database caching in server environments
+    // - Prevents excessive memory pressure that could lead to swapping or OOM conditions
+    block_options.set_block_cache(&Cache::new_hyper_clock_cache(
+        total_ram / 4,
+        HYPER_CLOCK_CACHE_BLOCK_SIZE,
+    ));
+
+    // Configure bloom filters for prefix iteration optimization
+    block_options.set_bloom_filter(10.0, false);
+    block_options.set_whole_key_filtering(false);
+
+    // 32KB blocks instead of default 4KB - reduces iterator seeks
+    block_options.set_block_size(32 * 1024);
+    // Use latest format for better compression and performance
+    block_options.set_format_version(5);
+
+    options.set_block_based_table_factory(&block_options);
+
+    // Configure prefix extraction for bloom filter optimization
+    // Use 8 bytes: ROOT_KEY_DOMAIN (1 byte) + BCS variant (1-2 bytes) + identifier start (4-5 bytes)
+    let prefix_extractor = SliceTransform::create_fixed_prefix(8);
+    options.set_prefix_extractor(prefix_extractor);
+
+    // 12.5% of memtable size for bloom filter
+    options.set_memtable_prefix_bloom_ratio(0.125);
+    // Skip bloom filter for memtable when key exists
+    options.set_optimize_filters_for_hits(true);
+    // Use memory-mapped files for faster reads
+    options.set_allow_mmap_reads(true);
+    // Don't use random access pattern since we do prefix scans
+    options.set_advise_random_on_open(false);
+    options
+}
+
+/// Opens the database at `primary_path` as a secondary instance, caught up with the
+/// primary one. A secondary instance does not take the lock of the database, so it can be
+/// opened while another process uses it. It keeps its own logs in the returned directory,
+/// which must outlive it.
+fn open_secondary(
+    primary_path: &Path,
+) -> Result<(DB, tempfile::TempDir), RocksDbStoreInternalError> {
+    let secondary_dir = tempfile::tempdir()?;
+    let mut options = database_options();
+    options.create_if_missing(false);
+    // A secondary instance must keep all its files open to follow the primary one.
+    options.set_max_open_files(-1);
+    let db = DB::open_as_secondary(&options, primary_path, secondary_dir.path())?;
+    db.try_catch_up_with_primary()?;
+    Ok((db, secondary_dir))
+}
+
+/// Copies the database at `primary_path` into a new database at `target_path`, as of
+/// the time of the call. The source may be in use by another process.
+fn copy_database(primary_path: &Path, target_path: &Path) -> Result<(), RocksDbStoreInternalError> {
+    /// The number of entries written to the copy at once.
+    const BATCH_SIZE: usize = 1000;
+    let (source, _secondary_dir) = open_secondary(primary_path)?;
+    let mut options = database_options();
+    options.set_error_if_exists(true);
+    let target = DB::open(&options, target_path)?;
+    let mut batch = rocksdb::WriteBatchWithTransaction::default();
+    // The iterator reads the state of the secondary instance when it was created.
+    for entry in source.iterator(rocksdb::IteratorMode::Start) {
+        let (key, value) = entry?;
+        batch.put(key, value);
+        if batch.len() >= BATCH_SIZE {
+            target.write(std::mem::take(&mut batch))?;
+        }
+    }
+    target.write(batch)?;
+    target.flush()?;
+    Ok(())
+}
+
 /// The choice of the spawning mode.
 /// `SpawnBlocking` always works and is the safest.
 /// `BlockInPlace` can only be used in multi-threaded environment.
@@ -120,13 +230,41 @@ fn check_key_size(key: &[u8]) -> Result<(), RocksDbStoreInternalError> {
 struct RocksDbStoreExecutor {
     db: Arc<DB>,
     start_key: Vec<u8>,
+    /// The directory of the logs of the database, if it is a secondary instance.
+    secondary_dir: Option<Arc<tempfile::TempDir>>,
 }
 
 impl RocksDbStoreExecutor {
+    /// Catches up with the writes of the primary instance if the database is a secondary
+    /// one, so that the reads are not stale.
+    fn catch_up(&self) -> Result<(), RocksDbStoreInternalError> {
+        if self.secondary_dir.is_some() {
+            self.db.try_catch_up_with_primary()?;
+        }
+        Ok(())
+    }
+
+    fn read_value_bytes_internal(
+        &self,
+        key: Vec<u8>,
+    ) -> Result<Option<Vec<u8>>, RocksDbStoreInternalError> {
+        self.catch_up()?;
+        Ok(self.db.get(&key)?)
+    }
+
+    fn contains_key_internal(&self, key: Vec<u8>) -> Result<bool, RocksDbStoreInternalError> {
+        self.catch_up()?;
+        if !self.db.key_may_exist(&key) {
+            return Ok(false);
+        }
+        Ok(self.db.get(&key)?.is_some())
+    }
+
     fn contains_keys_internal(
         &self,
         keys: Vec<Vec<u8>>,
     ) -> Result<Vec<bool>, RocksDbStoreInternalError> {
+        self.catch_up()?;
         let size = keys.len();
         let mut results = vec![false; size];
         let mut indices = Vec::new();
@@ -154,6 +292,7 @@ impl RocksDbStoreExecutor {
         for key in &keys {
             check_key_size(key)?;
         }
+        self.catch_up()?;
         let full_keys = keys
             .into_iter()
             .map(|key| {
@@ -188,6 +327,7 @@ impl RocksDbStoreExecutor {
         key_prefix: Vec<u8>,
     ) -> Result<Vec<Vec<u8>>, RocksDbStoreInternalError> {
         check_key_size(&key_prefix)?;
+        self.catch_up()?;
 
         let mut prefix = self.start_key.clone();
         prefix.extend(key_prefix);
@@ -208,6 +348,7 @@ impl RocksDbStoreExecutor {
         key_prefix: Vec<u8>,
     ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, RocksDbStoreInternalError> {
         check_key_size(&key_prefix)?;
+        self.catch_up()?;
         let mut prefix = self.start_key.clone();
         prefix.extend(key_prefix);
         let len = prefix.len();
@@ -222,11 +363,68 @@ impl RocksDbStoreExecutor {
         Ok(key_values)
     }
 
+    #[expect(clippy::type_complexity)]
+    fn find_key_values_by_range_internal(
+        &self,
+        key_prefix: Vec<u8>,
+        query: RangeQuery,
+        with_values: bool,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, RocksDbStoreInternalError> {
+        check_key_size(&key_prefix)?;
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.catch_up()?;
+        let mut prefix = self.start_key.clone();
+        prefix.extend(key_prefix);
+        let len = prefix.len();
+        let (lower_bound, upper_bound) = get_range_bounds(&prefix, &query);
+
+        let mut read_opts = rocksdb::ReadOptions::default();
+        read_opts.set_async_io(true);
+        // The bounds may not share the fixed prefix used by the bloom filters, and
+        // reverse iteration requires a total order anyway.
+        read_opts.set_total_order_seek(true);
+        read_opts.set_iterate_lower_bound(lower_bound.clone());
+        if let Some(upper_bound) = upper_bound {
+            read_opts.set_iterate_upper_bound(upper_bound);
+        }
+        let mut iter = self.db.raw_iterator_opt(read_opts);
+        match query.direction {
+            Direction::Forward => iter.seek(&lower_bound),
+            Direction::Reverse => iter.seek_to_last(),
+        }
+
+        let limit = query.limit.unwrap_or(usize::MAX);
+        let mut key_values = Vec::new();
+        while key_values.len() < limit {
+            let Some((key, value)) = iter.item() else {
+                break;
+            };
+            let value = if with_values {
+                value.to_vec()
+            } else {
+                Vec::new()
+            };
+            key_values.push((key[len..].to_vec(), value));
+            match query.direction {
+                Direction::Forward => iter.next(),
+                Direction::Reverse => iter.prev(),
+            }
+        }
+        iter.status()?;
+        Ok(key_values)
+    }
+
     fn write_batch_internal(
         &self,
         batch: Batch,
         write_root_key: bool,
     ) -> Result<(), RocksDbStoreInternalError> {
+        ensure!(
+            self.secondary_dir.is_none(),
+            RocksDbStoreInternalError::SecondaryInstanceWrite
+        );
         let mut inner_batch = rocksdb::WriteBatchWithTransaction::default();
         for operation in batch.operations {
             match operation {
@@ -294,6 +492,10 @@ pub struct RocksDbStoreInternalConfig {
     pub spawn_mode: RocksDbSpawnMode,
     /// Preferred buffer size for async streams.
     pub max_stream_queries: usize,
+    /// Whether to open the databases as secondary instances. They can then only be
+    /// read, while another process, such as a validator, holds and writes them.
+    #[serde(default)]
+    pub secondary: bool,
 }
 
 impl RocksDbDatabaseInternal {
@@ -336,79 +538,25 @@ impl RocksDbStoreInternal {
         path_with_guard.path_buf = path_buf.clone();
         let max_stream_queries = config.max_stream_queries;
         let spawn_mode = config.spawn_mode;
-        if !std::path::Path::exists(&path_buf) {
-            std::fs::create_dir(path_buf.clone())?;
-        }
-        let sys = System::new_with_specifics(
-            RefreshKind::nothing()
-                .with_cpu(CpuRefreshKind::everything())
-                .with_memory(MemoryRefreshKind::nothing().with_ram()),
-        );
-        let num_cpus = sys.cpus().len() as i32;
-        let total_ram = sys.total_memory() as usize;
-        let mut options = rocksdb::Options::default();
-        options.create_if_missing(true);
-        options.create_missing_column_families(true);
-        // Flush in-memory buffer to disk more often
-        options.set_write_buffer_size(WRITE_BUFFER_SIZE);
-        options.set_max_write_buffer_number(MAX_WRITE_BUFFER_NUMBER);
-        options.set_compression_type(rocksdb::DBCompressionType::Lz4);
-        options.set_level_zero_slowdown_writes_trigger(8);
-        options.set_level_zero_stop_writes_trigger(12);
-        options.set_level_zero_file_num_compaction_trigger(2);
-        // We deliberately give RocksDB one background thread *per* CPU so that
-        // flush + (N-1) compactions can hammer the NVMe at full bandwidth while
-        // still leaving enough CPU time for the foreground application threads.
-        options.increase_parallelism(num_cpus);
-        options.set_max_background_jobs(num_cpus);
-        options.set_max_subcompactions(num_cpus as u32);
-        options.set_level_compaction_dynamic_level_bytes(true);
-
-        options.set_compaction_style(DBCompactionStyle::Level);
-        options.set_target_file_size_base(2 * WRITE_BUFFER_SIZE as u64);
-
-        let mut block_options = BlockBasedOptions::default();
-        block_options.set_pin_l0_filter_and_index_blocks_in_cache(true);
-        block_options.set_cache_index_and_filter_blocks(true);
-        // Allocate 1/4 of total RAM for RocksDB block cache, which is a reasonable balance:
-        // - Large enough to significantly improve read performance by caching frequently accessed blocks
-        // - Small enough to leave memory for other system components
-        // - Follows common practice for database caching in server environments
-        // - Prevents excessive memory pressure that could lead to swapping or OOM conditions
-        block_options.set_block_cache(&Cache::new_hyper_clock_cache(
-            total_ram / 4,
-            HYPER_CLOCK_CACHE_BLOCK_SIZE,
-        ));
-
-        // Configure bloom filters for prefix iteration optimization
-        block_options.set_bloom_filter(10.0, false);
-        block_options.set_whole_key_filtering(false);
-
-        // 32KB blocks instead of default 4KB - reduces iterator seeks
-        block_options.set_block_size(32 * 1024);
-        // Use latest format for better compression and performance
-        block_options.set_format_version(5);
-
-        options.set_block_based_table_factory(&block_options);
-
-        // Configure prefix extraction for bloom filter optimization
-        // Use 8 bytes: ROOT_KEY_DOMAIN (1 byte) + BCS variant (1-2 bytes) + identifier start (4-5 bytes)
-        let prefix_extractor = SliceTransform::create_fixed_prefix(8);
-        options.set_prefix_extractor(prefix_extractor);
-
-        // 12.5% of memtable size for bloom filter
-        options.set_memtable_prefix_bloom_ratio(0.125);
-        // Skip bloom filter for memtable when key exists
-        options.set_optimize_filters_for_hits(true);
-        // Use memory-mapped files for faster reads
-        options.set_allow_mmap_reads(true);
-        // Don't use random access pattern since we do prefix scans
-        options.set_advise_random_on_open(false);
-
-        let db = DB::open(&options, path_buf)?;
+        let (db, secondary_dir) = if config.secondary {
+            if !std::path::Path::exists(&path_buf) {
+                return Err(RocksDbStoreInternalError::MissingNamespace(
+                    namespace.to_string(),
+                ));
+            }
+            let (db, secondary_dir) = open_secondary(&path_buf)?;
+            (db, Some(Arc::new(secondary_dir)))
+        } else {
+            if !std::path::Path::exists(&path_buf) {
+                std::fs::create_dir(path_buf.clone())?;
+            }
+            let options = database_options();
+            (DB::open(&options, path_buf)?, None)
+        };
         let executor = RocksDbStoreExecutor {
             db: Arc::new(db),
             start_key,
+            secondary_dir,
         };
         Ok(RocksDbStoreInternal {
             executor,
@@ -436,29 +584,21 @@ impl ReadableKeyValueStore for RocksDbStoreInternal {
         key: &[u8],
     ) -> Result<Option<Vec<u8>>, RocksDbStoreInternalError> {
         check_key_size(key)?;
-        let db = self.executor.db.clone();
+        let executor = self.executor.clone();
         let mut full_key = self.executor.start_key.to_vec();
         full_key.extend(key);
         self.spawn_mode
-            .spawn(move |x| Ok(db.get(&x)?), full_key)
+            .spawn(move |x| executor.read_value_bytes_internal(x), full_key)
             .await
     }
 
     async fn contains_key(&self, key: &[u8]) -> Result<bool, RocksDbStoreInternalError> {
         check_key_size(key)?;
-        let db = self.executor.db.clone();
+        let executor = self.executor.clone();
         let mut full_key = self.executor.start_key.to_vec();
         full_key.extend(key);
         self.spawn_mode
-            .spawn(
-                move |x| {
-                    if !db.key_may_exist(&x) {
-                        return Ok(false);
-                    }
-                    Ok(db.get(&x)?.is_some())
-                },
-                full_key,
-            )
+            .spawn(move |x| executor.contains_key_internal(x), full_key)
             .await
     }
 
@@ -509,6 +649,42 @@ impl ReadableKeyValueStore for RocksDbStoreInternal {
             )
             .await
     }
+
+    async fn find_keys_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<Vec<u8>>, RocksDbStoreInternalError> {
+        let executor = self.executor.clone();
+        let input = (key_prefix.to_vec(), query.clone());
+        let key_values = self
+            .spawn_mode
+            .spawn(
+                move |(key_prefix, query)| {
+                    executor.find_key_values_by_range_internal(key_prefix, query, false)
+                },
+                input,
+            )
+            .await?;
+        Ok(key_values.into_iter().map(|(key, _)| key).collect())
+    }
+
+    async fn find_key_values_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, RocksDbStoreInternalError> {
+        let executor = self.executor.clone();
+        let input = (key_prefix.to_vec(), query.clone());
+        self.spawn_mode
+            .spawn(
+                move |(key_prefix, query)| {
+                    executor.find_key_values_by_range_internal(key_prefix, query, true)
+                },
+                input,
+            )
+            .await
+    }
 }
 
 impl WritableKeyValueStore for RocksDbStoreInternal {
@@ -637,6 +813,41 @@ impl KeyValueDatabase for RocksDbDatabaseInternal {
         std::fs::remove_dir_all(path)?;
         Ok(())
     }
+
+    async fn snapshot(
+        config: &Self::Config,
+        namespace: &str,
+        snapshot: &str,
+    ) -> Result<(), RocksDbStoreInternalError> {
+        Self::check_namespace(snapshot)?;
+        let mut path_buf = config.path_with_guard.path_buf.clone();
+        path_buf.push(snapshot);
+        if std::path::Path::exists(&path_buf) {
+            return Err(RocksDbStoreInternalError::StoreAlreadyExists);
+        }
+        Self::check_namespace(namespace)?;
+        let mut primary_path = config.path_with_guard.path_buf.clone();
+        primary_path.push(namespace);
+        if !std::path::Path::exists(&primary_path) {
+            return Err(RocksDbStoreInternalError::MissingNamespace(
+                namespace.to_string(),
+            ));
+        }
+        // The database is locked while a validator or a client uses it, so it is read
+        // through a secondary instance and copied into the snapshot, instead of being
+        // checkpointed.
+        let result = config
+            .spawn_mode
+            .spawn(
+                |(primary_path, path_buf)| copy_database(&primary_path, &path_buf),
+                (primary_path, path_buf.clone()),
+            )
+            .await;
+        if result.is_err() && std::path::Path::exists(&path_buf) {
+            std::fs::remove_dir_all(&path_buf)?;
+        }
+        result
+    }
 }
 
 #[cfg(with_testing)]
@@ -649,6 +860,7 @@ impl TestKeyValueDatabase for RocksDbDatabaseInternal {
             path_with_guard,
             spawn_mode,
             max_stream_queries,
+            secondary: false,
         })
     }
 }
@@ -684,6 +896,14 @@ pub enum RocksDbStoreInternalError {
     #[error("Namespace contains forbidden characters")]
     InvalidNamespace,
 
+    /// The namespace does not exist
+    #[error("The namespace {0} does not exist")]
+    MissingNamespace(String),
+
+    /// Secondary instances cannot be written to
+    #[error("Cannot write to a secondary instance of the database")]
+    SecondaryInstanceWrite,
+
     /// Filesystem error
     #[error("Filesystem error: {0}")]
     FsError(#[from] std::io::Error),
@@ -693,42 +913,6 @@ pub enum RocksDbStoreInternalError {
     BcsError(#[from] bcs::Error),
 }
 
-/// A path and the guard for the temporary directory if needed
-#[derive(Clone, Debug, Deserialize, Serialize)]
-pub struct PathWithGuard {
-    /// The path to the data
-    pub path_buf: PathBuf,
-    /// The guard for the directory if one is needed
-    #[serde(skip)]
-    _dir: Option<Arc<TempDir>>,
-}
-
-impl PathWithGuard {
-    /// Creates a `PathWithGuard` from an existing path.
-    pub fn new(path_buf: PathBuf) -> Self {
-        Self {
-            path_buf,
-            _dir: None,
-        }
-    }
-
-    /// Returns the test path for RocksDB without common config.
-    #[cfg(with_testing)]
-    fn new_testing() -> PathWithGuard {
-        let dir = TempDir::new().unwrap();
-        let path_buf = dir.path().to_path_buf();
-        let _dir = Some(Arc::new(dir));
-        PathWithGuard { path_buf, _dir }
-    }
-}
-
-impl PartialEq for PathWithGuard {
-    fn eq(&self, other: &Self) -> bool {
-        self.path_buf == other.path_buf
-    }
-}
-impl Eq for PathWithGuard {}
-
 impl KeyValueStoreError for RocksDbStoreInternalError {
     const BACKEND: &'static str = "rocks_db";
 }
@@ -19,6 +19,7 @@ use linera_base::ensure;
 use scylla::{
     client::{
         execution_profile::{ExecutionProfile, ExecutionProfileHandle},
+        pager::QueryPager,
         session::Session,
         session_builder::SessionBuilder,
     },
@@ -43,12 +44,12 @@ use crate::metering::MeteredDatabase;
 use crate::store::TestKeyValueDatabase;
 use crate::{
     batch::UnorderedBatch,
-    common::{get_uleb128_size, get_upper_bound_option},
+    common::{copy_namespace, get_range_bounds, get_uleb128_size, get_upper_bound_option},
     journaling::{JournalConsistencyError, JournalingKeyValueDatabase},
     lru_caching::{LruCachingConfig, LruCachingDatabase},
     store::{
-        DirectWritableKeyValueStore, KeyValueDatabase, KeyValueStoreError, ReadableKeyValueStore,
-        WithError,
+        DirectWritableKeyValueStore, Direction, KeyValueDatabase, KeyValueStoreError, RangeQuery,
+        ReadableKeyValueStore, WithError,
     },
     value_splitting::{ValueSplittingDatabase, ValueSplittingError},
     FutureSyncExt as _,
@@ -116,6 +117,7 @@ struct ScyllaDbClient {
     find_key_values_by_prefix_bounded: PreparedStatement,
     multi_key_values: papaya::HashMap<usize, PreparedStatement>,
     multi_keys: papaya::HashMap<usize, PreparedStatement>,
+    find_by_range: papaya::HashMap<(bool, bool, Direction), PreparedStatement>,
 }
 
 impl ScyllaDbClient {
@@ -206,6 +208,7 @@ impl ScyllaDbClient {
             find_key_values_by_prefix_bounded,
             multi_key_values: papaya::HashMap::new(),
             multi_keys: papaya::HashMap::new(),
+            find_by_range: papaya::HashMap::new(),
         })
     }
 
@@ -284,6 +287,62 @@ impl ScyllaDbClient {
         Ok(prepared_statement)
     }
 
+    async fn get_find_by_range_statement(
+        &self,
+        with_values: bool,
+        bounded: bool,
+        direction: Direction,
+    ) -> Result<PreparedStatement, ScyllaDbStoreInternalError> {
+        let statement_key = (with_values, bounded, direction);
+        if let Some(prepared_statement) = self.find_by_range.pin().get(&statement_key) {
+            return Ok(prepared_statement.clone());
+        }
+        let columns = if with_values { "k,v" } else { "k" };
+        let upper_bound = if bounded { " AND k < ?" } else { "" };
+        let order = match direction {
+            Direction::Forward => "ASC",
+            Direction::Reverse => "DESC",
+        };
+        let prepared_statement = self
+            .session
+            .prepare(format!(
+                "SELECT {} FROM {}.{} WHERE root_key = ? AND k >= ?{} ORDER BY k {} LIMIT ?",
+                columns, KEYSPACE, self.namespace, upper_bound, order
+            ))
+            .await?;
+        self.find_by_range
+            .pin()
+            .insert(statement_key, prepared_statement.clone());
+        Ok(prepared_statement)
+    }
+
+    async fn execute_range_query(
+        &self,
+        root_key: &[u8],
+        key_prefix: &[u8],
+        query: &RangeQuery,
+        with_values: bool,
+    ) -> Result<QueryPager, ScyllaDbStoreInternalError> {
+        let (lower_bound, upper_bound) = get_range_bounds(key_prefix, query);
+        let limit = query
+            .limit
+            .map_or(i32::MAX, |limit| i32::try_from(limit).unwrap_or(i32::MAX));
+        let statement = self
+            .get_find_by_range_statement(with_values, upper_bound.is_some(), query.direction)
+            .await?;
+        let session = &self.session;
+        Ok(match upper_bound {
+            None => {
+                let values = (root_key.to_vec(), lower_bound, limit);
+                session.execute_iter(statement, values).await?
+            }
+            Some(upper_bound) => {
+                let values = (root_key.to_vec(), lower_bound, upper_bound, limit);
+                session.execute_iter(statement, values).await?
+            }
+        })
+    }
+
     fn check_key_size(key: &[u8]) -> Result<(), ScyllaDbStoreInternalError> {
         ensure!(
             key.len() <= MAX_KEY_SIZE,
@@ -519,6 +578,52 @@ impl ScyllaDbClient {
         }
         Ok(key_values)
     }
+
+    async fn find_keys_by_range_internal(
+        &self,
+        root_key: &[u8],
+        key_prefix: Vec<u8>,
+        query: &RangeQuery,
+    ) -> Result<Vec<Vec<u8>>, ScyllaDbStoreInternalError> {
+        Self::check_key_size(&key_prefix)?;
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let len = key_prefix.len();
+        let rows = self
+            .execute_range_query(root_key, &key_prefix, query, false)
+            .await?;
+        let mut rows = rows.rows_stream::<(Vec<u8>,)>()?;
+        let mut keys = Vec::new();
+        while let Some(row) = rows.next().await {
+            let (key,) = row?;
+            keys.push(key[len..].to_vec());
+        }
+        Ok(keys)
+    }
+
+    async fn find_key_values_by_range_internal(
+        &self,
+        root_key: &[u8],
+        key_prefix: Vec<u8>,
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ScyllaDbStoreInternalError> {
+        Self::check_key_size(&key_prefix)?;
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let len = key_prefix.len();
+        let rows = self
+            .execute_range_query(root_key, &key_prefix, query, true)
+            .await?;
+        let mut rows = rows.rows_stream::<(Vec<u8>, Vec<u8>)>()?;
+        let mut key_values = Vec::new();
+        while let Some(row) = rows.next().await {
+            let (key, value) = row?;
+            key_values.push((key[len..].to_vec(), value));
+        }
+        Ok(key_values)
+    }
 }
 
 /// The client itself and the keeping of the count of active connections.
@@ -699,6 +804,30 @@ impl ReadableKeyValueStore for ScyllaDbStoreInternal {
             .find_key_values_by_prefix_internal(&self.root_key, key_prefix.to_vec())
             .await
     }
+
+    async fn find_keys_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<Vec<u8>>, ScyllaDbStoreInternalError> {
+        let store = self.store.deref();
+        let _guard = self.acquire().await;
+        store
+            .find_keys_by_range_internal(&self.root_key, key_prefix.to_vec(), query)
+            .await
+    }
+
+    async fn find_key_values_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ScyllaDbStoreInternalError> {
+        let store = self.store.deref();
+        let _guard = self.acquire().await;
+        store
+            .find_key_values_by_range_internal(&self.root_key, key_prefix.to_vec(), query)
+            .await
+    }
 }
 
 impl DirectWritableKeyValueStore for ScyllaDbStoreInternal {
@@ -969,6 +1098,14 @@ impl KeyValueDatabase for ScyllaDbDatabaseInternal {
             .await?;
         Ok(())
     }
+
+    async fn snapshot(
+        config: &Self::Config,
+        namespace: &str,
+        snapshot: &str,
+    ) -> Result<(), ScyllaDbStoreInternalError> {
+        copy_namespace::<JournalingKeyValueDatabase<Self>>(config, namespace, snapshot).await
+    }
 }
 
 impl ScyllaDbStoreInternal {
@@ -9,8 +9,8 @@ use thiserror::Error;
 use crate::{
     batch::{Batch, WriteOperation},
     store::{
-        KeyValueDatabase, KeyValueStoreError, ReadableKeyValueStore, WithError,
-        WritableKeyValueStore,
+        Direction, KeyValueDatabase, KeyValueStoreError, RangeQuery, ReadableKeyValueStore,
+        WithError, WritableKeyValueStore,
     },
 };
 #[cfg(with_testing)]
@@ -241,6 +241,91 @@ where
         }
         Ok(key_values)
     }
+
+    async fn find_keys_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<Vec<u8>>, Self::Error> {
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        // Appending the index `0` preserves the order of the keys, so the first segments
+        // of the selected keys are exactly the first segments in the extended bounds.
+        // Since the other segments are also returned, a limited query is answered by
+        // reading pages of segments until enough first segments are found.
+        let extend = |bound: &Option<Vec<u8>>| {
+            bound.as_ref().map(|bound| {
+                let mut big_bound = bound.clone();
+                big_bound.extend(&[0, 0, 0, 0]);
+                big_bound
+            })
+        };
+        let mut big_query = RangeQuery {
+            start: extend(&query.start),
+            end: extend(&query.end),
+            limit: None,
+            direction: query.direction,
+        };
+        let limit = query.limit.unwrap_or(usize::MAX);
+        let mut keys = Vec::new();
+        loop {
+            if query.limit.is_some() {
+                big_query.limit = Some(limit - keys.len());
+            }
+            let big_keys = self
+                .store
+                .find_keys_by_range(key_prefix, &big_query)
+                .await?;
+            let is_last_page = big_query
+                .limit
+                .map_or(true, |page_size| big_keys.len() < page_size);
+            if let Some(last_big_key) = big_keys.last() {
+                match query.direction {
+                    Direction::Forward => {
+                        let mut start = last_big_key.clone();
+                        start.push(0);
+                        big_query.start = Some(start);
+                    }
+                    Direction::Reverse => big_query.end = Some(last_big_key.clone()),
+                }
+            }
+            for big_key in big_keys {
+                let len = big_key.len();
+                if Self::read_index_from_key(&big_key)? == 0 {
+                    let key = big_key[0..len - 4].to_vec();
+                    keys.push(key);
+                }
+            }
+            if is_last_page || keys.len() == limit {
+                break;
+            }
+        }
+        Ok(keys)
+    }
+
+    async fn find_key_values_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
+        let keys = self.find_keys_by_range(key_prefix, query).await?;
+        let full_keys = keys
+            .iter()
+            .map(|key| {
+                let mut full_key = key_prefix.to_vec();
+                full_key.extend(key);
+                full_key
+            })
+            .collect();
+        let values = self.read_multi_values_bytes(full_keys).await?;
+        let mut key_values = Vec::with_capacity(keys.len());
+        for (key, value) in keys.into_iter().zip(values) {
+            let value = value.ok_or(ValueSplittingError::MissingSegment)?;
+            key_values.push((key, value));
+        }
+        Ok(key_values)
+    }
 }
 
 impl<K> WritableKeyValueStore for ValueSplittingStore<K>
@@ -342,6 +427,14 @@ where
     async fn delete(config: &Self::Config, namespace: &str) -> Result<(), Self::Error> {
         Ok(D::delete(config, namespace).await?)
     }
+
+    async fn snapshot(
+        config: &Self::Config,
+        namespace: &str,
+        snapshot: &str,
+    ) -> Result<(), Self::Error> {
+        Ok(D::snapshot(config, namespace, snapshot).await?)
+    }
 }
 
 #[cfg(with_testing)]
@@ -463,6 +556,22 @@ impl ReadableKeyValueStore for LimitedTestMemoryStore {
     ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, MemoryStoreError> {
         self.inner.find_key_values_by_prefix(key_prefix).await
     }
+
+    async fn find_keys_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<Vec<u8>>, MemoryStoreError> {
+        self.inner.find_keys_by_range(key_prefix, query).await
+    }
+
+    async fn find_key_values_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, MemoryStoreError> {
+        self.inner.find_key_values_by_range(key_prefix, query).await
+    }
 }
 
 #[cfg(with_testing)]
@@ -4,16 +4,26 @@
 //! This provides some common code for the linera-views.
 
 use std::{
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
+    future::Future,
     ops::{
         Bound,
         Bound::{Excluded, Included, Unbounded},
     },
+    pin::pin,
 };
 
+use futures::{stream, Stream, TryStreamExt as _};
 use serde::de::DeserializeOwned;
 
-use crate::ViewError;
+use crate::{
+    batch::Batch,
+    store::{KeyValueDatabase, KeyValueStore, RangeQuery},
+    ViewError,
+};
+
+/// The number of entries read and written at a time by [`copy_namespace`].
+const COPY_PAGE_SIZE: usize = 1000;
 
 type HasherOutputSize = <sha3::Sha3_256 as sha3::digest::OutputSizeUser>::OutputSize;
 #[doc(hidden)]
@@ -100,6 +110,156 @@ pub(crate) fn get_interval(key_prefix: Vec<u8>) -> (Bound<Vec<u8>>, Bound<Vec<u8
     (Included(key_prefix), upper_bound)
 }
 
+/// Computes the bounds of the full keys selected by a `query` on the keys having
+/// `key_prefix` as a prefix. The lower bound is inclusive and the upper bound, if any,
+/// is exclusive.
+pub(crate) fn get_range_bounds(
+    key_prefix: &[u8],
+    query: &RangeQuery,
+) -> (Vec<u8>, Option<Vec<u8>>) {
+    let mut lower_bound = key_prefix.to_vec();
+    if let Some(start) = &query.start {
+        lower_bound.extend(start);
+    }
+    let upper_bound = match &query.end {
+        Some(end) => {
+            let mut upper_bound = key_prefix.to_vec();
+            upper_bound.extend(end);
+            Some(upper_bound)
+        }
+        None => get_upper_bound_option(key_prefix),
+    };
+    (lower_bound, upper_bound)
+}
+
+/// Computes an interval containing exactly the full keys selected by a `query` on the
+/// keys having `key_prefix` as a prefix.
+pub(crate) fn get_range_interval(
+    key_prefix: &[u8],
+    query: &RangeQuery,
+) -> (Bound<Vec<u8>>, Bound<Vec<u8>>) {
+    let (lower_bound, upper_bound) = get_range_bounds(key_prefix, query);
+    (
+        Included(lower_bound),
+        upper_bound.map_or(Unbounded, Excluded),
+    )
+}
+
+/// Returns the start of the page following a page of `num_keys` stored keys ending with
+/// `last_key`, or `None` if the page has fewer than `page_size` keys and is thus the last
+/// one.
+pub(crate) fn get_next_page_start(
+    num_keys: usize,
+    page_size: usize,
+    last_key: Option<&Vec<u8>>,
+) -> Option<Vec<u8>> {
+    if num_keys < page_size {
+        return None;
+    }
+    let mut next_start = last_key?.clone();
+    next_start.push(0);
+    Some(next_start)
+}
+
+/// Computes the interval of the keys covered by a page starting at `start` and followed
+/// by a page starting at `next_start`, if any.
+pub(crate) fn get_page_interval(
+    start: Option<Vec<u8>>,
+    next_start: Option<&Vec<u8>>,
+) -> (Bound<Vec<u8>>, Bound<Vec<u8>>) {
+    (
+        start.map_or(Unbounded, Included),
+        next_start.cloned().map_or(Unbounded, Excluded),
+    )
+}
+
+/// Merges a page of stored entries with the pending updates of the keys covered by the
+/// page. The stored entries whose key is deleted are skipped. The result is sorted by key.
+pub(crate) fn merge_page<'a, T, U: 'a>(
+    stored_entries: Vec<(Vec<u8>, T)>,
+    updates: impl Iterator<Item = (&'a Vec<u8>, &'a Update<U>)>,
+    is_deleted: impl Fn(&[u8]) -> bool,
+    value: impl Fn(&U) -> T,
+) -> Vec<(Vec<u8>, T)> {
+    let mut entries = stored_entries
+        .into_iter()
+        .filter(|(key, _)| !is_deleted(key))
+        .collect::<BTreeMap<_, _>>();
+    for (key, update) in updates {
+        match update {
+            Update::Removed => {
+                entries.remove(key);
+            }
+            Update::Set(update_value) => {
+                entries.insert(key.clone(), value(update_value));
+            }
+        }
+    }
+    entries.into_iter().collect()
+}
+
+/// Turns a function reading the page of at most `page_size` entries starting at a given
+/// key into a stream of entries. The function also returns the start of the next page,
+/// or `None` once the last page is read.
+pub(crate) fn stream_pages<T, E, F, Fut>(
+    page_size: usize,
+    mut read_page: F,
+) -> impl Stream<Item = Result<T, E>>
+where
+    F: FnMut(Option<Vec<u8>>, usize) -> Fut,
+    Fut: Future<Output = Result<(Vec<T>, Option<Vec<u8>>), E>>,
+{
+    let page_size = page_size.max(1);
+    stream::try_unfold(Some(None), move |start: Option<Option<Vec<u8>>>| {
+        let page = start.map(|start| read_page(start, page_size));
+        async move {
+            let Some(page) = page else {
+                return Ok(None);
+            };
+            let (entries, next_start) = page.await?;
+            let entries = stream::iter(entries.into_iter().map(Ok));
+            Ok(Some((entries, next_start.map(Some))))
+        }
+    })
+    .try_flatten()
+}
+
+/// Creates the namespace `snapshot` and copies the entries of `namespace` into it, root
+/// key by root key. This implements [`KeyValueDatabase::snapshot`] for the backends
+/// without native snapshots: the copy is only consistent if `namespace` is not written
+/// to in the meantime.
+pub async fn copy_namespace<D>(
+    config: &D::Config,
+    namespace: &str,
+    snapshot: &str,
+) -> Result<(), D::Error>
+where
+    D: KeyValueDatabase,
+    D::Store: KeyValueStore<Error = D::Error>,
+{
+    D::create(config, snapshot).await?;
+    let database = D::connect(config, namespace).await?;
+    let snapshot_database = D::connect(config, snapshot).await?;
+    for root_key in D::list_root_keys(config, namespace).await? {
+        let store = database.open_shared(&root_key)?;
+        let snapshot_store = snapshot_database.open_exclusive(&root_key)?;
+        let mut entries = pin!(store.find_key_values_by_prefix_stream(&[], COPY_PAGE_SIZE));
+        let mut batch = Batch::new();
+        while let Some((key, value)) = entries.try_next().await? {
+            batch.put_key_value_bytes(key, value);
+            if batch.num_operations() == COPY_PAGE_SIZE {
+                snapshot_store
+                    .write_batch(std::mem::take(&mut batch))
+                    .await?;
+            }
+        }
+        if !batch.is_empty() {
+            snapshot_store.write_batch(batch).await?;
+        }
+    }
+    Ok(())
+}
+
 /// Deserializes an optional vector of `u8`
 pub fn from_bytes_option<V: DeserializeOwned>(
     key_opt: &Option<Vec<u8>>,
@@ -311,6 +471,53 @@ pub(crate) const fn get_uleb128_size(len: usize) -> usize {
     expo
 }
 
+#[cfg(any(with_rocksdb, with_redb))]
+pub use path_with_guard::PathWithGuard;
+
+#[cfg(any(with_rocksdb, with_redb))]
+mod path_with_guard {
+    use std::{path::PathBuf, sync::Arc};
+
+    use serde::{Deserialize, Serialize};
+    use tempfile::TempDir;
+
+    /// A path and the guard for the temporary directory if needed
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct PathWithGuard {
+        /// The path to the data
+        pub path_buf: PathBuf,
+        /// The guard for the directory if one is needed
+        #[serde(skip)]
+        _dir: Option<Arc<TempDir>>,
+    }
+
+    impl PathWithGuard {
+        /// Creates a `PathWithGuard` from an existing path.
+        pub fn new(path_buf: PathBuf) -> Self {
+            Self {
+                path_buf,
+                _dir: None,
+            }
+        }
+
+        /// Returns a path to a temporary directory for testing.
+        #[cfg(with_testing)]
+        pub(crate) fn new_testing() -> PathWithGuard {
+            let dir = TempDir::new().unwrap();
+            let path_buf = dir.path().to_path_buf();
+            let _dir = Some(Arc::new(dir));
+            PathWithGuard { path_buf, _dir }
+        }
+    }
+
+    impl PartialEq for PathWithGuard {
+        fn eq(&self, other: &Self) -> bool {
+            self.path_buf == other.path_buf
+        }
+    }
+    impl Eq for PathWithGuard {}
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::BTreeSet;
@@ -219,6 +219,15 @@ impl<K: async_graphql::InputType> async_graphql::InputType for MapInput<K> {
     }
 }
 
+/// The inputs given when looking up the entries of an indexed map via GraphQL.
+#[derive(async_graphql::InputObject)]
+pub struct IndexInput {
+    /// The name of the secondary index.
+    pub index: String,
+    /// The key in the secondary index.
+    pub key: async_graphql::Json<async_graphql::Value>,
+}
+
 pub(crate) fn missing_key_error(key: &impl std::fmt::Debug) -> async_graphql::Error {
     async_graphql::Error {
         message: format!("The key={:?} is missing in collection", key),
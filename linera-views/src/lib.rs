@@ -87,6 +87,12 @@ pub mod views;
 /// Backend implementing the [`crate::store::KeyValueStore`] trait.
 pub mod backends;
 
+/// Portable archives of the namespaces of a database.
+pub mod archive;
+
+/// Migration of the namespaces of a database to another one.
+pub mod migration;
+
 /// Support for metrics.
 #[cfg(with_metrics)]
 pub mod metrics;
@@ -105,15 +111,19 @@ pub mod test_utils;
 
 #[cfg(with_dynamodb)]
 pub use backends::dynamo_db;
+#[cfg(with_encryption)]
+pub use backends::encryption;
 #[cfg(with_indexeddb)]
 pub use backends::indexed_db;
 #[cfg(with_metrics)]
 pub use backends::metering;
+#[cfg(with_redb)]
+pub use backends::redb_db;
 #[cfg(with_rocksdb)]
 pub use backends::rocks_db;
 #[cfg(with_scylladb)]
 pub use backends::scylla_db;
-pub use backends::{journaling, lru_caching, memory, value_splitting};
+pub use backends::{compression, journaling, lru_caching, memory, value_splitting};
 /// Re-exports used by the derive macros of this library.
 #[doc(hidden)]
 #[allow(deprecated)]
@@ -122,6 +132,7 @@ pub use generic_array;
 pub use sha3;
 /// Expose the created views.
 pub use views::{
-    bucket_queue_view, collection_view, hashable_wrapper, key_value_store_view, log_view, map_view,
-    queue_view, reentrant_collection_view, register_view, set_view,
+    bucket_queue_view, collection_view, hashable_wrapper, indexed_map_view, key_value_store_view,
+    log_view, map_view, ordered_map_view, proof, queue_view, reentrant_collection_view,
+    register_view, set_view,
 };
@@ -0,0 +1,552 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Migrates all the namespaces of a [`KeyValueDatabase`] to another one, possibly of a
+//! different backend.
+//!
+//! The entries of every root key are copied in batches. After a batch is written, the
+//! corresponding range of the target is read back and compared with the source, and
+//! once a root key is copied, the digests of all its entries in both databases are
+//! compared. The progress is reported after every verified batch so that an interrupted
+//! migration can resume where it stopped.
+//!
+//! The source may be written to during the migration if the deployment runs with a
+//! [`crate::backends::dual::DualWriteAssignment`]: the writes then reach both databases,
+//! and the batches that raced with them are copied again. The source must then be read
+//! without caching, and a RocksDB source, whose lock is held by the deployment, must be
+//! opened as a secondary instance. The deployments only support dual writes from RocksDB
+//! to ScyllaDB, so any other source must not be written to during the migration.
+
+use std::{collections::BTreeSet, pin::pin};
+
+use futures::TryStreamExt as _;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest as _, Sha3_256};
+use thiserror::Error;
+
+use crate::{
+    batch::Batch,
+    common::get_next_page_start,
+    store::{KeyValueDatabase, KeyValueStore, KeyValueStoreError, RangeQuery},
+    ViewError,
+};
+
+/// The parameters of a migration.
+#[derive(Clone, Debug)]
+pub struct MigrationOptions {
+    /// The maximal number of entries copied in a batch.
+    pub batch_size: usize,
+    /// The number of times a batch or a root key is copied before the verification is
+    /// considered failed.
+    pub max_attempts: usize,
+}
+
+impl Default for MigrationOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 1000,
+            max_attempts: 3,
+        }
+    }
+}
+
+/// The progress of a migration.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationProgress {
+    /// The namespaces that are migrated and verified.
+    pub completed_namespaces: BTreeSet<String>,
+    /// The namespace being migrated, if any.
+    pub current_namespace: Option<NamespaceProgress>,
+}
+
+/// The progress of the migration of a namespace.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamespaceProgress {
+    /// The namespace.
+    pub namespace: String,
+    /// The root keys that are migrated and verified.
+    pub completed_root_keys: BTreeSet<Vec<u8>>,
+    /// The root key being migrated, if any.
+    pub current_root_key: Option<RootKeyProgress>,
+}
+
+/// The progress of the migration of a root key.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RootKeyProgress {
+    /// The root key.
+    pub root_key: Vec<u8>,
+    /// The first key of the next batch to copy. The keys before it are copied.
+    pub next_key: Vec<u8>,
+}
+
+/// The work done by a migration.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MigrationSummary {
+    /// The number of namespaces migrated.
+    pub num_namespaces: usize,
+    /// The number of root keys migrated.
+    pub num_root_keys: usize,
+    /// The number of entries written to the target.
+    pub num_written_entries: u64,
+    /// The number of stale entries deleted from the target.
+    pub num_deleted_entries: u64,
+}
+
+/// The error type for the migrations.
+#[derive(Error, Debug)]
+pub enum MigrationError {
+    /// An error from the source database.
+    #[error("source database: {0}")]
+    Source(ViewError),
+
+    /// An error from the target database.
+    #[error("target database: {0}")]
+    Target(ViewError),
+
+    /// The progress could not be saved.
+    #[error("failed to save the migration progress: {0}")]
+    Progress(#[from] std::io::Error),
+
+    /// The target still differs from the source after all the attempts.
+    #[error("the root key {root_key} of namespace {namespace} could not be verified")]
+    VerificationFailed {
+        /// The namespace.
+        namespace: String,
+        /// The root key, in hexadecimal.
+        root_key: String,
+    },
+}
+
+fn source_error<E: KeyValueStoreError>(error: E) -> MigrationError {
+    MigrationError::Source(error.into())
+}
+
+fn target_error<E: KeyValueStoreError>(error: E) -> MigrationError {
+    MigrationError::Target(error.into())
+}
+
+/// Computes the digest of a sequence of entries.
+fn digest<'a>(entries: impl IntoIterator<Item = &'a (Vec<u8>, Vec<u8>)>) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    for (key, value) in entries {
+        hasher.update((key.len() as u64).to_le_bytes());
+        hasher.update(key);
+        hasher.update((value.len() as u64).to_le_bytes());
+        hasher.update(value);
+    }
+    hasher.finalize().into()
+}
+
+/// Computes the digest of all the entries of a store.
+async fn store_digest<S: KeyValueStore>(store: &S, page_size: usize) -> Result<[u8; 32], S::Error> {
+    let mut hasher = Sha3_256::new();
+    let mut entries = pin!(store.find_key_values_by_prefix_stream(&[], page_size));
+    while let Some((key, value)) = entries.try_next().await? {
+        hasher.update((key.len() as u64).to_le_bytes());
+        hasher.update(&key);
+        hasher.update((value.len() as u64).to_le_bytes());
+        hasher.update(&value);
+    }
+    Ok(hasher.finalize().into())
+}
+
+/// Migrates all the namespaces of the source database to the target database, creating
+/// the missing namespaces. The migration resumes from `progress`, which is updated and
+/// passed to `save_progress` after every verified batch.
+pub async fn migrate<D1, D2>(
+    source_config: &D1::Config,
+    target_config: &D2::Config,
+    options: &MigrationOptions,
+    progress: &mut MigrationProgress,
+    mut save_progress: impl FnMut(&MigrationProgress) -> Result<(), std::io::Error>,
+) -> Result<MigrationSummary, MigrationError>
+where
+    D1: KeyValueDatabase,
+    D1::Store: KeyValueStore,
+    D2: KeyValueDatabase,
+    D2::Store: KeyValueStore,
+{
+    let mut summary = MigrationSummary::default();
+    let mut namespaces = D1::list_all(source_config).await.map_err(source_error)?;
+    namespaces.sort();
+    for namespace in namespaces {
+        if progress.completed_namespaces.contains(&namespace) {
+            continue;
+        }
+        if progress
+            .current_namespace
+            .as_ref()
+            .is_none_or(|current| current.namespace != namespace)
+        {
+            progress.current_namespace = Some(NamespaceProgress {
+                namespace: namespace.clone(),
+                ..NamespaceProgress::default()
+            });
+        }
+        let mut namespace_progress = progress
+            .current_namespace
+            .clone()
+            .expect("the current namespace is set");
+        migrate_namespace::<D1, D2>(
+            source_config,
+            target_config,
+            options,
+            &mut namespace_progress,
+            &mut summary,
+            |namespace_progress| {
+                progress.current_namespace = Some(namespace_progress.clone());
+                save_progress(progress)
+            },
+        )
+        .await?;
+        progress.current_namespace = None;
+        progress.completed_namespaces.insert(namespace);
+        save_progress(progress)?;
+        summary.num_namespaces += 1;
+    }
+    Ok(summary)
+}
+
+async fn migrate_namespace<D1, D2>(
+    source_config: &D1::Config,
+    target_config: &D2::Config,
+    options: &MigrationOptions,
+    progress: &mut NamespaceProgress,
+    summary: &mut MigrationSummary,
+    mut save_progress: impl FnMut(&NamespaceProgress) -> Result<(), std::io::Error>,
+) -> Result<(), MigrationError>
+where
+    D1: KeyValueDatabase,
+    D1::Store: KeyValueStore,
+    D2: KeyValueDatabase,
+    D2::Store: KeyValueStore,
+{
+    let namespace = progress.namespace.clone();
+    if !D2::exists(target_config, &namespace)
+        .await
+        .map_err(target_error)?
+    {
+        D2::create(target_config, &namespace)
+            .await
+            .map_err(target_error)?;
+    }
+    let mut root_keys = D1::list_root_keys(source_config, &namespace)
+        .await
+        .map_err(source_error)?;
+    root_keys.sort();
+    root_keys.dedup();
+    let source_database = D1::connect(source_config, &namespace)
+        .await
+        .map_err(source_error)?;
+    let target_database = D2::connect(target_config, &namespace)
+        .await
+        .map_err(target_error)?;
+    for root_key in root_keys {
+        if progress.completed_root_keys.contains(&root_key) {
+            continue;
+        }
+        let source_store = source_database
+            .open_shared(&root_key)
+            .map_err(source_error)?;
+        let target_store = target_database
+            .open_exclusive(&root_key)
+            .map_err(target_error)?;
+        let mut attempt = 0;
+        loop {
+            let next_key = match &progress.current_root_key {
+                Some(current) if current.root_key == root_key => current.next_key.clone(),
+                _ => Vec::new(),
+            };
+            let mut start = Some(next_key);
+            while let Some(key) = start {
+                start = copy_batch(&source_store, &target_store, key, options, summary)
+                    .await?
+                    .ok_or_else(|| verification_error(&namespace, &root_key))?;
+                progress.current_root_key = start.clone().map(|next_key| RootKeyProgress {
+                    root_key: root_key.clone(),
+                    next_key,
+                });
+                save_progress(progress)?;
+            }
+            let source_digest = store_digest(&source_store, options.batch_size)
+                .await
+                .map_err(source_error)?;
+            let target_digest = store_digest(&target_store, options.batch_size)
+                .await
+                .map_err(target_error)?;
+            if source_digest == target_digest {
+                break;
+            }
+            attempt += 1;
+            if attempt >= options.max_attempts {
+                return Err(verification_error(&namespace, &root_key));
+            }
+            tracing::warn!(
+                "The root key {} of namespace {namespace} differs after the copy, copying it again",
+                hex::encode(&root_key)
+            );
+        }
+        progress.current_root_key = None;
+        progress.completed_root_keys.insert(root_key);
+        save_progress(progress)?;
+        summary.num_root_keys += 1;
+    }
+    Ok(())
+}
+
+fn verification_error(namespace: &str, root_key: &[u8]) -> MigrationError {
+    MigrationError::VerificationFailed {
+        namespace: namespace.to_string(),
+        root_key: hex::encode(root_key),
+    }
+}
+
+/// Makes the entries of the target starting at `start` identical to the ones of the
+/// next batch of the source, then reads them back to verify them. Returns the start of
+/// the following batch, if any, or `None` if the batch could not be verified.
+async fn copy_batch<S1, S2>(
+    source_store: &S1,
+    target_store: &S2,
+    start: Vec<u8>,
+    options: &MigrationOptions,
+    summary: &mut MigrationSummary,
+) -> Result<Option<Option<Vec<u8>>>, MigrationError>
+where
+    S1: KeyValueStore,
+    S2: KeyValueStore,
+{
+    for _ in 0..options.max_attempts {
+        let query = RangeQuery {
+            start: Some(start.clone()),
+            limit: Some(options.batch_size),
+            ..RangeQuery::default()
+        };
+        let source_entries = source_store
+            .find_key_values_by_range(&[], &query)
+            .await
+            .map_err(source_error)?;
+        let last_key = source_entries.last().map(|(key, _)| key);
+        let next_start = get_next_page_start(source_entries.len(), options.batch_size, last_key);
+        // The batch covers the keys from `start` to `next_start`, which may include
+        // stale entries of the target.
+        let query = RangeQuery {
+            start: Some(start.clone()),
+            end: next_start.clone(),
+            ..RangeQuery::default()
+        };
+        let target_entries = target_store
+            .find_key_values_by_range(&[], &query)
+            .await
+            .map_err(target_error)?;
+        if digest(&source_entries) == digest(&target_entries) {
+            return Ok(Some(next_start));
+        }
+        let source_keys = source_entries
+            .iter()
+            .map(|(key, _)| key)
+            .collect::<BTreeSet<_>>();
+        let mut batch = Batch::new();
+        for (key, _) in &target_entries {
+            if !source_keys.contains(key) {
+                batch.delete_key(key.clone());
+                summary.num_deleted_entries += 1;
+            }
+        }
+        let target_entries = target_entries.into_iter().collect::<BTreeSet<_>>();
+        for entry in source_entries {
+            if !target_entries.contains(&entry) {
+                let (key, value) = entry;
+                batch.put_key_value_bytes(key, value);
+                summary.num_written_entries += 1;
+            }
+        }
+        target_store
+            .write_batch(batch)
+            .await
+            .map_err(target_error)?;
+        let written_entries = target_store
+            .find_key_values_by_range(&[], &query)
+            .await
+            .map_err(target_error)?;
+        let source_entries = source_store
+            .find_key_values_by_range(&[], &query)
+            .await
+            .map_err(source_error)?;
+        if digest(&source_entries) == digest(&written_entries) {
+            return Ok(Some(next_start));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        memory::MemoryDatabase,
+        random::{generate_test_namespace, make_deterministic_rng},
+        store::TestKeyValueDatabase as _,
+        test_utils::get_random_key_values,
+    };
+
+    #[cfg(with_redb)]
+    async fn read_namespace<D>(
+        config: &D::Config,
+        namespace: &str,
+    ) -> Vec<(Vec<u8>, Vec<(Vec<u8>, Vec<u8>)>)>
+    where
+        D: KeyValueDatabase,
+        D::Store: KeyValueStore,
+    {
+        let mut root_keys = D::list_root_keys(config, namespace).await.unwrap();
+        root_keys.sort();
+        let database = D::connect(config, namespace).await.unwrap();
+        let mut content = Vec::new();
+        for root_key in root_keys {
+            let store = database.open_shared(&root_key).unwrap();
+            let key_values = store.find_key_values_by_prefix(&[]).await.unwrap();
+            content.push((root_key, key_values));
+        }
+        content
+    }
+
+    #[cfg(with_redb)]
+    #[tokio::test]
+    async fn test_migration_with_resumption() {
+        use crate::redb_db::RedbDatabase;
+
+        let source_config = RedbDatabase::new_test_config().await.unwrap();
+        let target_config = MemoryDatabase::new_test_config().await.unwrap();
+        let mut rng = make_deterministic_rng();
+        let mut namespaces = Vec::new();
+        for _ in 0..2 {
+            let namespace = generate_test_namespace();
+            let database = RedbDatabase::recreate_and_connect(&source_config, &namespace)
+                .await
+                .unwrap();
+            for root_key in [vec![], vec![1], vec![2, 3]] {
+                let store = database.open_exclusive(&root_key).unwrap();
+                let mut batch = Batch::new();
+                for (key, value) in get_random_key_values(&mut rng, 250) {
+                    batch.put_key_value_bytes(key, value);
+                }
+                store.write_batch(batch).await.unwrap();
+            }
+            namespaces.push(namespace);
+        }
+        // A stale entry in the target is deleted by the migration.
+        let database = MemoryDatabase::recreate_and_connect(&target_config, &namespaces[1])
+            .await
+            .unwrap();
+        let mut batch = Batch::new();
+        batch.put_key_value_bytes(vec![0], vec![0]);
+        database
+            .open_exclusive(&[1])
+            .unwrap()
+            .write_batch(batch)
+            .await
+            .unwrap();
+
+        let options = MigrationOptions {
+            batch_size: 100,
+            max_attempts: 3,
+        };
+        // The migration is interrupted after a few batches.
+        let mut progress = MigrationProgress::default();
+        let mut saved = MigrationProgress::default();
+        let mut num_saves = 0;
+        let result = migrate::<RedbDatabase, MemoryDatabase>(
+            &source_config,
+            &target_config,
+            &options,
+            &mut progress,
+            |progress| {
+                num_saves += 1;
+                if num_saves > 5 {
+                    return Err(std::io::Error::other("interrupted"));
+                }
+                saved = progress.clone();
+                Ok(())
+            },
+        )
+        .await;
+        assert!(matches!(result, Err(MigrationError::Progress(_))));
+        let current = saved.current_namespace.as_ref().unwrap();
+        assert_eq!(current.completed_root_keys.len(), 1);
+        assert!(current.current_root_key.is_some());
+
+        let mut progress = saved;
+        let summary = migrate::<RedbDatabase, MemoryDatabase>(
+            &source_config,
+            &target_config,
+            &options,
+            &mut progress,
+            |_| Ok(()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(summary.num_namespaces, 2);
+        assert_eq!(summary.num_root_keys, 5);
+        assert_eq!(summary.num_deleted_entries, 1);
+        assert_eq!(
+            progress.completed_namespaces,
+            namespaces.iter().cloned().collect()
+        );
+        assert!(progress.current_namespace.is_none());
+        for namespace in &namespaces {
+            assert_eq!(
+                read_namespace::<RedbDatabase>(&source_config, namespace).await,
+                read_namespace::<MemoryDatabase>(&target_config, namespace).await
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_copy_batch_repairs_target() {
+        let config = MemoryDatabase::new_test_config().await.unwrap();
+        let source = MemoryDatabase::recreate_and_connect(&config, &generate_test_namespace())
+            .await
+            .unwrap()
+            .open_exclusive(&[])
+            .unwrap();
+        let target = MemoryDatabase::recreate_and_connect(&config, &generate_test_namespace())
+            .await
+            .unwrap()
+            .open_exclusive(&[])
+            .unwrap();
+        let mut batch = Batch::new();
+        for key in 0..10u8 {
+            batch.put_key_value_bytes(vec![key], vec![key]);
+        }
+        source.write_batch(batch).await.unwrap();
+        let mut batch = Batch::new();
+        // Two stale entries, an outdated one and an up-to-date one.
+        batch.put_key_value_bytes(vec![3, 0], vec![]);
+        batch.put_key_value_bytes(vec![4], vec![0]);
+        batch.put_key_value_bytes(vec![5], vec![5]);
+        batch.put_key_value_bytes(vec![20], vec![20]);
+        target.write_batch(batch).await.unwrap();
+
+        let options = MigrationOptions {
+            batch_size: 8,
+            max_attempts: 3,
+        };
+        let mut summary = MigrationSummary::default();
+        let next_start = copy_batch(&source, &target, Vec::new(), &options, &mut summary)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(next_start, Some(vec![7, 0]));
+        let next_start = copy_batch(&source, &target, vec![7, 0], &options, &mut summary)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(next_start, None);
+        assert_eq!(
+            source.find_key_values_by_prefix(&[]).await.unwrap(),
+            target.find_key_values_by_prefix(&[]).await.unwrap()
+        );
+        assert_eq!(summary.num_written_entries, 9);
+        assert_eq!(summary.num_deleted_entries, 2);
+    }
+}
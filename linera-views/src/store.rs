@@ -3,15 +3,20 @@
 
 //! This provides the trait definitions for the stores.
 
-use std::{fmt::Debug, future::Future};
+use std::{
+    fmt::Debug,
+    future::Future,
+    ops::{Bound, RangeBounds},
+};
 
-use serde::{de::DeserializeOwned, Serialize};
+use futures::Stream;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 #[cfg(with_testing)]
 use crate::random::generate_test_namespace;
 use crate::{
     batch::{Batch, SimplifiedBatch},
-    common::from_bytes_option,
+    common::{from_bytes_option, get_next_page_start, stream_pages},
     ViewError,
 };
 
@@ -38,6 +43,117 @@ pub trait WithError {
     type Error: KeyValueStoreError;
 }
 
+/// The order in which the entries of a [`RangeQuery`] are returned.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Direction {
+    /// The entries are returned by increasing keys.
+    #[default]
+    Forward,
+    /// The entries are returned by decreasing keys.
+    Reverse,
+}
+
+/// A bounded query over the keys sharing a common prefix.
+///
+/// The bounds apply to the keys with the prefix removed: a key `key_prefix + suffix`
+/// is selected if `start <= suffix < end`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RangeQuery {
+    /// The inclusive lower bound of the suffixes, if any.
+    pub start: Option<Vec<u8>>,
+    /// The exclusive upper bound of the suffixes, if any.
+    pub end: Option<Vec<u8>>,
+    /// The maximal number of entries to return, if any.
+    pub limit: Option<usize>,
+    /// The order in which the entries are returned.
+    pub direction: Direction,
+}
+
+impl RangeQuery {
+    /// Creates a query selecting the keys within `range`, in increasing order and without
+    /// limit. The bounds that are not supported directly are expressed through the
+    /// successor `key + [0]` of a key.
+    pub fn from_bounds(range: impl RangeBounds<Vec<u8>>) -> Self {
+        let successor = |key: &Vec<u8>| {
+            let mut key = key.clone();
+            key.push(0);
+            key
+        };
+        let start = match range.start_bound() {
+            Bound::Included(key) => Some(key.clone()),
+            Bound::Excluded(key) => Some(successor(key)),
+            Bound::Unbounded => None,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(key) => Some(successor(key)),
+            Bound::Excluded(key) => Some(key.clone()),
+            Bound::Unbounded => None,
+        };
+        RangeQuery {
+            start,
+            end,
+            limit: None,
+            direction: Direction::Forward,
+        }
+    }
+
+    /// Limits the number of returned entries.
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Sets the order in which the entries are returned.
+    pub fn with_direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Tests whether the `suffix` of a key lies within the bounds of the query.
+    pub fn contains(&self, suffix: &[u8]) -> bool {
+        if let Some(start) = &self.start {
+            if suffix < start.as_slice() {
+                return false;
+            }
+        }
+        if let Some(end) = &self.end {
+            if suffix >= end.as_slice() {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Tests whether no key can be selected by the query.
+    pub fn is_empty(&self) -> bool {
+        if self.limit == Some(0) {
+            return true;
+        }
+        match (&self.start, &self.end) {
+            (Some(start), Some(end)) => start >= end,
+            _ => false,
+        }
+    }
+
+    /// Selects the entries of the query out of `entries`, which are sorted by increasing
+    /// suffixes, applying the bounds, the direction and the limit.
+    pub fn select<T>(&self, entries: Vec<T>, suffix: impl Fn(&T) -> &[u8]) -> Vec<T> {
+        let limit = self.limit.unwrap_or(usize::MAX);
+        let entries = entries
+            .into_iter()
+            .filter(|entry| self.contains(suffix(entry)));
+        match self.direction {
+            Direction::Forward => entries.take(limit).collect(),
+            Direction::Reverse => {
+                let mut entries = entries.collect::<Vec<_>>();
+                entries.reverse();
+                entries.truncate(limit);
+                entries
+            }
+        }
+    }
+}
+
 /// Asynchronous read key-value operations.
 #[cfg_attr(not(web), trait_variant::make(Send + Sync))]
 pub trait ReadableKeyValueStore: WithError {
@@ -71,10 +187,77 @@ pub trait ReadableKeyValueStore: WithError {
         key_prefix: &[u8],
     ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error>;
 
+    /// Finds the keys matching the prefix and the bounded `query`, in the order given by
+    /// the direction of the query. The prefix is not included in the returned keys.
+    ///
+    /// Backends must only read the selected keys, and stop once the limit is reached: the
+    /// streams below read a whole prefix through one such query per page.
+    async fn find_keys_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<Vec<u8>>, Self::Error>;
+
+    /// Finds the `(key,value)` pairs matching the prefix and the bounded `query`, in the
+    /// order given by the direction of the query. The prefix is not included in the
+    /// returned keys.
+    ///
+    /// Like [`Self::find_keys_by_range`], backends must only read the selected entries.
+    async fn find_key_values_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error>;
+
     // We can't use `async fn` here in the below implementations due to
     // https://github.com/rust-lang/impl-trait-utils/issues/17, but once that bug is fixed
     // we can revert them to `async fn` syntax, which is neater.
 
+    /// Streams the keys matching the prefix, in increasing order. The keys are read from
+    /// the store in pages of at most `page_size` keys, so that only one page is held in
+    /// memory at a time. The prefix is not included in the returned keys.
+    ///
+    /// Like the futures of this trait, the stream is `Send` outside of the web.
+    fn find_keys_by_prefix_stream<'a>(
+        &'a self,
+        key_prefix: &'a [u8],
+        page_size: usize,
+    ) -> impl Stream<Item = Result<Vec<u8>, Self::Error>> + 'a {
+        stream_pages(page_size, move |start, page_size| async move {
+            let query = RangeQuery {
+                start,
+                limit: Some(page_size),
+                ..RangeQuery::default()
+            };
+            let keys = self.find_keys_by_range(key_prefix, &query).await?;
+            let next_start = get_next_page_start(keys.len(), page_size, keys.last());
+            Ok((keys, next_start))
+        })
+    }
+
+    /// Streams the `(key,value)` pairs matching the prefix, in increasing order of the
+    /// keys. The entries are read from the store in pages of at most `page_size` entries.
+    /// The prefix is not included in the returned keys.
+    ///
+    /// Like the futures of this trait, the stream is `Send` outside of the web.
+    fn find_key_values_by_prefix_stream<'a>(
+        &'a self,
+        key_prefix: &'a [u8],
+        page_size: usize,
+    ) -> impl Stream<Item = Result<(Vec<u8>, Vec<u8>), Self::Error>> + 'a {
+        stream_pages(page_size, move |start, page_size| async move {
+            let query = RangeQuery {
+                start,
+                limit: Some(page_size),
+                ..RangeQuery::default()
+            };
+            let key_values = self.find_key_values_by_range(key_prefix, &query).await?;
+            let last_key = key_values.last().map(|(key, _)| key);
+            let next_start = get_next_page_start(key_values.len(), page_size, last_key);
+            Ok((key_values, next_start))
+        })
+    }
+
     /// Reads a single `key` and deserializes the result if present.
     fn read_value<V: DeserializeOwned>(
         &self,
@@ -190,6 +373,18 @@ pub trait KeyValueDatabase: WithError + Sized {
     /// Deletes the given namespace.
     async fn delete(config: &Self::Config, namespace: &str) -> Result<(), Self::Error>;
 
+    /// Creates the namespace `snapshot` holding a copy of `namespace`, including all its
+    /// root keys. Returns an error if `snapshot` exists.
+    ///
+    /// Backends with native support take a point-in-time snapshot. The others copy the
+    /// entries root key by root key, so the copy is only consistent if `namespace` is
+    /// not written to in the meantime.
+    async fn snapshot(
+        config: &Self::Config,
+        namespace: &str,
+        snapshot: &str,
+    ) -> Result<(), Self::Error>;
+
     /// Initializes a storage if missing and provides it.
     fn maybe_create_and_connect(
         config: &Self::Config,
@@ -329,6 +524,22 @@ pub mod inactive_store {
         ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
             panic!("attempt to read from an inactive store!")
         }
+
+        async fn find_keys_by_range(
+            &self,
+            _key_prefix: &[u8],
+            _query: &RangeQuery,
+        ) -> Result<Vec<Vec<u8>>, Self::Error> {
+            panic!("attempt to read from an inactive store!")
+        }
+
+        async fn find_key_values_by_range(
+            &self,
+            _key_prefix: &[u8],
+            _query: &RangeQuery,
+        ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, Self::Error> {
+            panic!("attempt to read from an inactive store!")
+        }
     }
 
     impl WritableKeyValueStore for InactiveStore {
@@ -9,6 +9,7 @@ pub mod performance;
 
 use std::collections::{BTreeMap, BTreeSet, HashSet};
 
+use futures::TryStreamExt as _;
 use rand::{seq::SliceRandom, Rng};
 
 use crate::{
@@ -18,8 +19,8 @@ use crate::{
     },
     random::{generate_test_namespace, make_deterministic_rng, make_nondeterministic_rng},
     store::{
-        KeyValueDatabase, KeyValueStore, ReadableKeyValueStore, TestKeyValueDatabase,
-        WritableKeyValueStore,
+        Direction, KeyValueDatabase, KeyValueStore, RangeQuery, ReadableKeyValueStore,
+        TestKeyValueDatabase, WritableKeyValueStore,
     },
 };
 
@@ -163,6 +164,8 @@ pub fn span_random_reordering_put_delete<R: Rng>(
 /// * `read_multi_values_bytes`
 /// * `find_keys_by_prefix` / `find_key_values_by_prefix`
 /// * The ordering of keys returned by `find_keys_by_prefix` and `find_key_values_by_prefix`
+/// * `find_keys_by_range` / `find_key_values_by_range`
+/// * `find_keys_by_prefix_stream` / `find_key_values_by_prefix_stream`
 pub async fn run_reads<S: KeyValueStore>(store: S, key_values: Vec<(Vec<u8>, Vec<u8>)>) {
     // We need a nontrivial key_prefix because dynamo requires a non-trivial prefix
     let mut batch = Batch::new();
@@ -202,6 +205,10 @@ pub async fn run_reads<S: KeyValueStore>(store: S, key_values: Vec<(Vec<u8>, Vec
             }
         }
         assert_eq!(set_key_value1, set_key_value2);
+        let mut key_values_by_prefix = set_key_value1.into_iter().collect::<Vec<_>>();
+        key_values_by_prefix.sort();
+        check_range_queries(&store, key_prefix, &key_values_by_prefix).await;
+        check_prefix_streams(&store, key_prefix, &key_values_by_prefix).await;
     }
     // Now checking the read_multi_values_bytes
     let mut rng = make_deterministic_rng();
@@ -243,6 +250,76 @@ pub async fn run_reads<S: KeyValueStore>(store: S, key_values: Vec<(Vec<u8>, Vec
     }
 }
 
+/// Checks the range queries on `key_prefix` against the sorted `key_values` having
+/// that prefix.
+async fn check_range_queries<S: ReadableKeyValueStore>(
+    store: &S,
+    key_prefix: &[u8],
+    key_values: &[(Vec<u8>, Vec<u8>)],
+) {
+    let len = key_values.len();
+    let mut bounds = vec![(None, None)];
+    if len > 0 {
+        let start = key_values[len / 4].0.clone();
+        let end = key_values[3 * len / 4].0.clone();
+        bounds.push((Some(start.clone()), None));
+        bounds.push((None, Some(end.clone())));
+        bounds.push((Some(start), Some(end)));
+    }
+    for (start, end) in bounds {
+        for limit in [None, Some(1), Some(len / 2 + 1)] {
+            for direction in [Direction::Forward, Direction::Reverse] {
+                let query = RangeQuery {
+                    start: start.clone(),
+                    end: end.clone(),
+                    limit,
+                    direction,
+                };
+                let expected_key_values =
+                    query.select(key_values.to_vec(), |(key, _)| key.as_slice());
+                let expected_keys = expected_key_values
+                    .iter()
+                    .map(|(key, _)| key.clone())
+                    .collect::<Vec<_>>();
+                let keys = store.find_keys_by_range(key_prefix, &query).await.unwrap();
+                assert_eq!(keys, expected_keys);
+                let found_key_values = store
+                    .find_key_values_by_range(key_prefix, &query)
+                    .await
+                    .unwrap();
+                assert_eq!(found_key_values, expected_key_values);
+            }
+        }
+    }
+}
+
+/// Checks the streaming prefix scans on `key_prefix` against the sorted `key_values`
+/// having that prefix, for several page sizes.
+async fn check_prefix_streams<S: ReadableKeyValueStore>(
+    store: &S,
+    key_prefix: &[u8],
+    key_values: &[(Vec<u8>, Vec<u8>)],
+) {
+    let expected_keys = key_values
+        .iter()
+        .map(|(key, _)| key.clone())
+        .collect::<Vec<_>>();
+    for page_size in [1, 3, key_values.len() + 1] {
+        let keys = store
+            .find_keys_by_prefix_stream(key_prefix, page_size)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(keys, expected_keys);
+        let found_key_values = store
+            .find_key_values_by_prefix_stream(key_prefix, page_size)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(found_key_values, key_values);
+    }
+}
+
 /// Generates a list of random key-values with no duplicates
 pub fn get_random_key_values_with_sizes(
     num_entries: usize,
@@ -835,6 +912,81 @@ where
     assert_eq!(keys, read_keys);
 }
 
+/// Reads the entries of a namespace by root key, skipping the root keys without entries.
+async fn read_namespace_entries<D>(
+    config: &D::Config,
+    namespace: &str,
+) -> BTreeMap<Vec<u8>, Vec<(Vec<u8>, Vec<u8>)>>
+where
+    D: KeyValueDatabase,
+    D::Store: KeyValueStore,
+{
+    let root_keys = D::list_root_keys(config, namespace)
+        .await
+        .expect("root keys");
+    let database = D::connect(config, namespace).await.expect("database");
+    let mut entries = BTreeMap::new();
+    for root_key in root_keys {
+        let store = database.open_shared(&root_key).expect("shared store");
+        let key_values = store.find_key_values_by_prefix(&[]).await.expect("entries");
+        if !key_values.is_empty() {
+            entries.insert(root_key, key_values);
+        }
+    }
+    entries
+}
+
+/// Tests that the snapshot of a namespace is not affected by later writes.
+pub async fn snapshot_admin_test<D>()
+where
+    D: TestKeyValueDatabase,
+    D::Store: KeyValueStore,
+{
+    let config = D::new_test_config().await.expect("config");
+    let namespace = generate_test_namespace();
+    D::create(&config, &namespace).await.expect("creation");
+    let prefix = vec![0];
+    {
+        let mut rng = make_deterministic_rng();
+        let database = D::connect(&config, &namespace).await.expect("database");
+        for root_key in [vec![], vec![1], vec![2, 3]] {
+            let store = database.open_exclusive(&root_key).expect("exclusive store");
+            let mut batch = Batch::new();
+            for _ in 0..10 {
+                let key = get_random_byte_vector(&mut rng, &prefix, 4);
+                let value = get_random_byte_vector(&mut rng, &[], 10);
+                batch.put_key_value_bytes(key, value);
+            }
+            store.write_batch(batch).await.expect("write batch");
+        }
+    }
+    let entries = read_namespace_entries::<D>(&config, &namespace).await;
+    assert_eq!(entries.len(), 3);
+
+    let snapshot = format!("{}_snapshot", namespace);
+    D::snapshot(&config, &namespace, &snapshot)
+        .await
+        .expect("snapshot");
+    // Taking a snapshot into an existing namespace should return an error
+    assert!(D::snapshot(&config, &namespace, &snapshot).await.is_err());
+    {
+        let database = D::connect(&config, &namespace).await.expect("database");
+        let store = database.open_exclusive(&[1]).expect("exclusive store");
+        let mut batch = Batch::new();
+        batch.delete_key_prefix(prefix);
+        batch.put_key_value_bytes(vec![0, 42], vec![42]);
+        store.write_batch(batch).await.expect("write batch");
+    }
+    assert_eq!(
+        read_namespace_entries::<D>(&config, &snapshot).await,
+        entries
+    );
+    assert_ne!(
+        read_namespace_entries::<D>(&config, &namespace).await,
+        entries
+    );
+}
+
 /// A store can be in exclusive access where it stores the absence of values
 /// or in shared access where only values are stored and (key, value) once
 /// written are never modified nor erased.
@@ -10,17 +10,24 @@ use std::{
 };
 
 use async_lock::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use futures::{Stream, StreamExt as _};
 #[cfg(with_metrics)]
 use linera_base::prometheus_util::MeasureLatency as _;
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
     batch::Batch,
-    common::{CustomSerialize, HasherOutput, Update},
+    common::{
+        get_next_page_start, get_page_interval, merge_page, stream_pages, CustomSerialize,
+        HasherOutput, Update,
+    },
     context::{BaseKey, Context},
     hashable_wrapper::WrappedHashableContainerView,
-    store::ReadableKeyValueStore as _,
-    views::{ClonableView, HashableView, Hasher, View, ViewError, MIN_VIEW_TAG},
+    store::{RangeQuery, ReadableKeyValueStore as _},
+    views::{
+        proof::{ProofStep, ViewProof},
+        ClonableView, HashableView, Hasher, View, ViewError, MIN_VIEW_TAG,
+    },
 };
 
 #[cfg(with_metrics)]
@@ -583,6 +590,67 @@ impl<W: View> ByteCollectionView<W::Context, W> {
         Ok(keys)
     }
 
+    /// Reads the page of at most `page_size` stored keys starting at `start`, merges it
+    /// with the pending updates, and returns it with the start of the next page.
+    async fn keys_page(
+        &self,
+        start: Option<Vec<u8>>,
+        page_size: usize,
+    ) -> Result<(Vec<Vec<u8>>, Option<Vec<u8>>), ViewError> {
+        let mut stored_keys = Vec::new();
+        let mut next_start = None;
+        if !self.delete_storage_first {
+            let query = RangeQuery {
+                start: start.clone(),
+                limit: Some(page_size),
+                ..RangeQuery::default()
+            };
+            let base = self.get_index_key(&[]);
+            stored_keys = self
+                .context
+                .store()
+                .find_keys_by_range(&base, &query)
+                .await?;
+            next_start = get_next_page_start(stored_keys.len(), page_size, stored_keys.last());
+        }
+        let updates = self.updates.read().await;
+        let entries = merge_page(
+            stored_keys.into_iter().map(|key| (key, ())).collect(),
+            updates.range(get_page_interval(start, next_start.as_ref())),
+            |_| false,
+            |_| (),
+        );
+        let keys = entries.into_iter().map(|(key, ())| key).collect();
+        Ok((keys, next_start))
+    }
+
+    /// Streams the keys of the collection in lexicographic order. The keys are read from
+    /// storage in pages of at most `page_size` keys.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use futures::TryStreamExt as _;
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::collection_view::ByteCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: ByteCollectionView<_, RegisterView<_, String>> =
+    ///     ByteCollectionView::load(context).await.unwrap();
+    /// view.load_entry_mut(&[0, 1]).await.unwrap();
+    /// view.load_entry_mut(&[0, 2]).await.unwrap();
+    /// let keys = view.keys_stream(1).try_collect::<Vec<_>>().await.unwrap();
+    /// assert_eq!(keys, vec![vec![0, 1], vec![0, 2]]);
+    /// # })
+    /// ```
+    pub fn keys_stream(
+        &self,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<Vec<u8>, ViewError>> + '_ {
+        stream_pages(page_size, move |start, page_size| {
+            self.keys_page(start, page_size)
+        })
+    }
+
     /// Returns the number of entries in the collection.
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -621,7 +689,7 @@ impl<W: HashableView> HashableView for ByteCollectionView<W::Context, W> {
         hasher.update_with_bcs_bytes(&count)?;
         let updates = self.updates.get_mut();
         for key in keys {
-            hasher.update_with_bytes(&key)?;
+            hasher.update_with_bcs_bytes(&key)?;
             let hash = match updates.get_mut(&key) {
                 Some(entry) => {
                     let Update::Set(view) = entry else {
@@ -653,7 +721,7 @@ impl<W: HashableView> HashableView for ByteCollectionView<W::Context, W> {
         hasher.update_with_bcs_bytes(&count)?;
         let updates = self.updates.read().await;
         for key in keys {
-            hasher.update_with_bytes(&key)?;
+            hasher.update_with_bcs_bytes(&key)?;
             let hash = match updates.get(&key) {
                 Some(entry) => {
                     let Update::Set(view) = entry else {
@@ -677,6 +745,68 @@ impl<W: HashableView> HashableView for ByteCollectionView<W::Context, W> {
     }
 }
 
+impl<W: HashableView> ByteCollectionView<W::Context, W> {
+    /// Extends a proof about the subview at `short_key` into a proof about the
+    /// collection. The proof contains the keys and the hashes of all the other subviews.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::collection_view::ByteCollectionView;
+    /// # use linera_views::proof::ProofPath;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: ByteCollectionView<_, RegisterView<_, String>> =
+    ///     ByteCollectionView::load(context).await.unwrap();
+    /// view.load_entry_mut(&[0, 1]).await.unwrap().set(String::from("Hello"));
+    /// view.load_entry_mut(&[0, 2]).await.unwrap();
+    /// let proof = view.try_load_entry(&[0, 1]).await.unwrap().unwrap().prove().unwrap();
+    /// let proof = view.prove_entry(&[0, 1], proof).await.unwrap();
+    /// let hash = view.hash().await.unwrap();
+    /// let path = ProofPath::new().collection_entry(vec![0, 1]).register();
+    /// assert!(proof.verify(&hash, &path).is_ok());
+    /// assert_eq!(proof.register_value::<String>().unwrap(), "Hello");
+    /// # })
+    /// ```
+    pub async fn prove_entry(
+        &self,
+        short_key: &[u8],
+        proof: ViewProof,
+    ) -> Result<ViewProof, ViewError> {
+        let keys = self.keys().await?;
+        if !keys.iter().any(|key| key == short_key) {
+            return Err(ViewError::NotFound(format!("entry {short_key:?}")));
+        }
+        let updates = self.updates.read().await;
+        let mut siblings = Vec::new();
+        for key in keys {
+            if key == short_key {
+                continue;
+            }
+            let hash = match updates.get(&key) {
+                Some(entry) => {
+                    let Update::Set(view) = entry else {
+                        unreachable!();
+                    };
+                    view.hash().await?
+                }
+                None => {
+                    let key = self
+                        .context
+                        .base_key()
+                        .base_tag_index(KeyTag::Subview as u8, &key);
+                    let context = self.context.clone_with_base_key(key);
+                    let view = W::load(context).await?;
+                    view.hash().await?
+                }
+            };
+            siblings.push((key, hash.as_ref().to_vec()));
+        }
+        let key = short_key.to_vec();
+        Ok(proof.with_step(ProofStep::CollectionEntry { key, siblings }))
+    }
+}
+
 /// A view that supports accessing a collection of views of the same kind, indexed by a
 /// key, one subview at a time.
 #[derive(Debug)]
@@ -924,6 +1054,34 @@ where
         Ok(indices)
     }
 
+    /// Streams the indices of the collection in the order determined by the
+    /// serialization. The indices are read from storage in pages of at most `page_size`
+    /// indices.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use futures::TryStreamExt as _;
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::collection_view::CollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: CollectionView<_, u64, RegisterView<_, String>> =
+    ///     CollectionView::load(context).await.unwrap();
+    /// view.load_entry_mut(&23).await.unwrap();
+    /// view.load_entry_mut(&25).await.unwrap();
+    /// let indices = view.indices_stream(1).try_collect::<Vec<_>>().await.unwrap();
+    /// assert_eq!(indices.len(), 2);
+    /// # })
+    /// ```
+    pub fn indices_stream(
+        &self,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<I, ViewError>> + '_ {
+        self.collection
+            .keys_stream(page_size)
+            .map(|key| Ok(BaseKey::deserialize_value(&key?)?))
+    }
+
     /// Returns the number of entries in the collection.
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -1034,6 +1192,38 @@ where
     }
 }
 
+impl<I: Serialize, W: HashableView> CollectionView<W::Context, I, W> {
+    /// Extends a proof about the subview at `index` into a proof about the collection.
+    /// The proof contains the keys and the hashes of all the other subviews.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::collection_view::CollectionView;
+    /// # use linera_views::proof::ProofPath;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: CollectionView<_, u64, RegisterView<_, String>> =
+    ///     CollectionView::load(context).await.unwrap();
+    /// view.load_entry_mut(&23).await.unwrap().set(String::from("Hello"));
+    /// let proof = view.try_load_entry(&23).await.unwrap().unwrap().prove().unwrap();
+    /// let proof = view.prove_entry(&23, proof).await.unwrap();
+    /// let hash = view.hash().await.unwrap();
+    /// let key = bcs::to_bytes(&23_u64).unwrap();
+    /// let path = ProofPath::new().collection_entry(key).register();
+    /// assert!(proof.verify(&hash, &path).is_ok());
+    /// # })
+    /// ```
+    pub async fn prove_entry<Q>(&self, index: &Q, proof: ViewProof) -> Result<ViewProof, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let short_key = BaseKey::derive_short_key(index)?;
+        self.collection.prove_entry(&short_key, proof).await
+    }
+}
+
 /// A map view that serializes the indices.
 #[derive(Debug)]
 pub struct CustomCollectionView<C, I, W> {
@@ -1270,6 +1460,34 @@ impl<I: CustomSerialize + Send, W: View> CustomCollectionView<W::Context, I, W>
         Ok(indices)
     }
 
+    /// Streams the indices of the collection in the order determined by the custom
+    /// serialization. The indices are read from storage in pages of at most `page_size`
+    /// indices.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use futures::TryStreamExt as _;
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::collection_view::CustomCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: CustomCollectionView<_, u128, RegisterView<_, String>> =
+    ///     CustomCollectionView::load(context).await.unwrap();
+    /// view.load_entry_mut(&25).await.unwrap();
+    /// view.load_entry_mut(&23).await.unwrap();
+    /// let indices = view.indices_stream(1).try_collect::<Vec<_>>().await.unwrap();
+    /// assert_eq!(indices, vec![23, 25]);
+    /// # })
+    /// ```
+    pub fn indices_stream(
+        &self,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<I, ViewError>> + '_ {
+        self.collection
+            .keys_stream(page_size)
+            .map(|key| I::from_custom_bytes(&key?))
+    }
+
     /// Returns the number of entries in the collection.
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -1382,6 +1600,40 @@ where
     }
 }
 
+impl<I: CustomSerialize, W: HashableView> CustomCollectionView<W::Context, I, W> {
+    /// Extends a proof about the subview at `index` into a proof about the collection.
+    /// The proof contains the keys, in the custom serialization, and the hashes of all
+    /// the other subviews.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::collection_view::CustomCollectionView;
+    /// # use linera_views::common::CustomSerialize as _;
+    /// # use linera_views::proof::ProofPath;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: CustomCollectionView<_, u128, RegisterView<_, String>> =
+    ///     CustomCollectionView::load(context).await.unwrap();
+    /// view.load_entry_mut(&23).await.unwrap().set(String::from("Hello"));
+    /// let proof = view.try_load_entry(&23).await.unwrap().unwrap().prove().unwrap();
+    /// let proof = view.prove_entry(&23, proof).await.unwrap();
+    /// let hash = view.hash().await.unwrap();
+    /// let key = 23_u128.to_custom_bytes().unwrap();
+    /// let path = ProofPath::new().collection_entry(key).register();
+    /// assert!(proof.verify(&hash, &path).is_ok());
+    /// # })
+    /// ```
+    pub async fn prove_entry<Q>(&self, index: &Q, proof: ViewProof) -> Result<ViewProof, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: CustomSerialize,
+    {
+        let short_key = index.to_custom_bytes()?;
+        self.collection.prove_entry(&short_key, proof).await
+    }
+}
+
 /// Type wrapping `ByteCollectionView` while memoizing the hash.
 pub type HashedByteCollectionView<C, W> =
     WrappedHashableContainerView<C, ByteCollectionView<C, W>, HasherOutput>;
@@ -0,0 +1,744 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `IndexedMapView` implements a map whose entries can also be looked up by
+//! secondary indices on their values.
+//!
+//! Each secondary index is a type implementing [`SecondaryIndex`], which extracts an
+//! index key from a value. The indices of a view are given as a tuple, and the index
+//! entries are updated together with the entries of the map, so that they are written
+//! in the same batch by `flush`.
+//!
+//! ```rust
+//! # tokio_test::block_on(async {
+//! # use linera_views::context::MemoryContext;
+//! # use linera_views::indexed_map_view::{IndexedMapView, SecondaryIndex};
+//! # use linera_views::views::View;
+//! # let context = MemoryContext::new_for_testing(());
+//! #[derive(Clone, serde::Serialize, serde::Deserialize)]
+//! struct Order {
+//!     owner: String,
+//!     price: u64,
+//! }
+//!
+//! struct ByOwner;
+//!
+//! impl SecondaryIndex<Order> for ByOwner {
+//!     const NAME: &'static str = "owner";
+//!     const TAG: u8 = 0;
+//!     type Key = String;
+//!
+//!     fn index_key(order: &Order) -> Option<String> {
+//!         Some(order.owner.clone())
+//!     }
+//! }
+//!
+//! let mut orders: IndexedMapView<_, u32, Order, (ByOwner,)> =
+//!     IndexedMapView::load(context).await.unwrap();
+//! let order = Order { owner: "Alice".to_string(), price: 10 };
+//! orders.insert(&(7 as u32), order).await.unwrap();
+//! let keys = orders.keys_by_index::<ByOwner>(&"Alice".to_string()).await.unwrap();
+//! assert_eq!(keys, vec![7]);
+//! # })
+//! ```
+
+use std::{
+    borrow::Borrow,
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    batch::Batch,
+    common::{get_upper_bound_option, HasherOutput},
+    context::{BaseKey, Context},
+    hashable_wrapper::WrappedHashableContainerView,
+    map_view::ByteMapView,
+    store::{Direction, RangeQuery},
+    views::{ClonableView, HashableView, Hasher, ReplaceContext, View, ViewError, MIN_VIEW_TAG},
+};
+
+/// A secondary index of an [`IndexedMapView`] whose values have type `V`.
+pub trait SecondaryIndex<V> {
+    /// The name of the index, used to select it in GraphQL queries.
+    const NAME: &'static str;
+
+    /// The tag of the index in storage. The indices of a view must have distinct tags.
+    const TAG: u8;
+
+    /// The type of the index keys.
+    type Key: Serialize + DeserializeOwned + Send + Sync;
+
+    /// Extracts the index key of a value. The values without a key are not indexed.
+    fn index_key(value: &V) -> Option<Self::Key>;
+
+    /// Serializes an index key. The index is ordered by the serialized keys, and the
+    /// serialization must be prefix-free. The default is BCS; an order-preserving
+    /// serialization may be used to iterate over ranges of numbers, for instance.
+    fn serialize_key(key: &Self::Key) -> Result<Vec<u8>, ViewError> {
+        Ok(bcs::to_bytes(key)?)
+    }
+
+    /// Deserializes an index key serialized by [`SecondaryIndex::serialize_key`].
+    fn deserialize_key(bytes: &[u8]) -> Result<Self::Key, ViewError> {
+        Ok(bcs::from_bytes(bytes)?)
+    }
+}
+
+/// The secondary indices of an [`IndexedMapView`]. This is implemented for the tuples of
+/// up to four [`SecondaryIndex`] types.
+pub trait SecondaryIndices<V> {
+    /// Returns the tags of the indices of `value`, with its serialized index keys.
+    fn index_entries(value: &V) -> Result<Vec<(u8, Vec<u8>)>, ViewError>;
+
+    /// Returns whether one of the indices has the given tag.
+    fn contains_tag(tag: u8) -> bool;
+
+    /// Returns the tag of the index with the given name, with the serialization of an
+    /// index key given in GraphQL.
+    #[cfg(with_graphql)]
+    fn graphql_index_key(
+        name: &str,
+        key: async_graphql::Value,
+    ) -> Result<(u8, Vec<u8>), async_graphql::Error>;
+}
+
+macro_rules! impl_secondary_indices {
+    ($($index:ident),+) => {
+        impl<V, $($index: SecondaryIndex<V>),+> SecondaryIndices<V> for ($($index,)+) {
+            fn index_entries(value: &V) -> Result<Vec<(u8, Vec<u8>)>, ViewError> {
+                let mut entries = Vec::new();
+                $(
+                    if let Some(key) = <$index as SecondaryIndex<V>>::index_key(value) {
+                        let bytes = <$index as SecondaryIndex<V>>::serialize_key(&key)?;
+                        entries.push((<$index as SecondaryIndex<V>>::TAG, bytes));
+                    }
+                )+
+                Ok(entries)
+            }
+
+            fn contains_tag(tag: u8) -> bool {
+                $(<$index as SecondaryIndex<V>>::TAG == tag)||+
+            }
+
+            #[cfg(with_graphql)]
+            fn graphql_index_key(
+                name: &str,
+                key: async_graphql::Value,
+            ) -> Result<(u8, Vec<u8>), async_graphql::Error> {
+                $(
+                    if <$index as SecondaryIndex<V>>::NAME == name {
+                        let key = async_graphql::from_value::<
+                            <$index as SecondaryIndex<V>>::Key,
+                        >(key)?;
+                        let bytes = <$index as SecondaryIndex<V>>::serialize_key(&key)?;
+                        return Ok((<$index as SecondaryIndex<V>>::TAG, bytes));
+                    }
+                )+
+                Err(async_graphql::Error::new(format!("unknown index {name}")))
+            }
+        }
+    };
+}
+
+impl_secondary_indices!(I1);
+impl_secondary_indices!(I1, I2);
+impl_secondary_indices!(I1, I2, I3);
+impl_secondary_indices!(I1, I2, I3, I4);
+
+/// The entries of the map and of the indices are stored under distinct prefixes.
+#[repr(u8)]
+enum KeyTag {
+    /// Prefix for the entries of the map.
+    Map = MIN_VIEW_TAG,
+    /// Prefix for the entries of the secondary indices.
+    Index,
+}
+
+/// A map view whose keys have type `K` and whose values have type `V`, with the
+/// secondary indices `I`. The ordering of the entries is the one of the BCS serialized
+/// keys, as in a [`crate::map_view::MapView`].
+#[derive(Debug)]
+pub struct IndexedMapView<C, K, V, I> {
+    context: C,
+    /// The entries of the map.
+    map: ByteMapView<C, V>,
+    /// The entries of the indices. The key of an entry is made of the tag of the index,
+    /// the serialized index key and the serialized key of the map. Its value is the length
+    /// of the serialized index key.
+    index: ByteMapView<C, u32>,
+    _phantom: PhantomData<(K, I)>,
+}
+
+impl<C, C2, K, V, I> ReplaceContext<C2> for IndexedMapView<C, K, V, I>
+where
+    C: Context,
+    C2: Context,
+    K: Send + Sync,
+    V: Send + Sync + Serialize + Clone,
+    I: Send + Sync,
+{
+    type Target = IndexedMapView<C2, K, V, I>;
+
+    async fn with_context(
+        &mut self,
+        ctx: impl FnOnce(&Self::Context) -> C2 + Clone,
+    ) -> Self::Target {
+        IndexedMapView {
+            context: ctx.clone()(&self.context),
+            map: self.map.with_context(ctx.clone()).await,
+            index: self.index.with_context(ctx).await,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<C, K, V, I> View for IndexedMapView<C, K, V, I>
+where
+    C: Context,
+    K: Send + Sync,
+    V: Send + Sync + Serialize,
+    I: Send + Sync,
+{
+    const NUM_INIT_KEYS: usize = 0;
+
+    type Context = C;
+
+    fn context(&self) -> &C {
+        &self.context
+    }
+
+    fn pre_load(_context: &C) -> Result<Vec<Vec<u8>>, ViewError> {
+        Ok(Vec::new())
+    }
+
+    fn post_load(context: C, _values: &[Option<Vec<u8>>]) -> Result<Self, ViewError> {
+        let map_key = context.base_key().base_tag(KeyTag::Map as u8);
+        let map = ByteMapView::post_load(context.clone_with_base_key(map_key), &[])?;
+        let index_key = context.base_key().base_tag(KeyTag::Index as u8);
+        let index = ByteMapView::post_load(context.clone_with_base_key(index_key), &[])?;
+        Ok(Self {
+            context,
+            map,
+            index,
+            _phantom: PhantomData,
+        })
+    }
+
+    async fn load(context: C) -> Result<Self, ViewError> {
+        Self::post_load(context, &[])
+    }
+
+    fn rollback(&mut self) {
+        self.map.rollback();
+        self.index.rollback();
+    }
+
+    async fn has_pending_changes(&self) -> bool {
+        self.map.has_pending_changes().await || self.index.has_pending_changes().await
+    }
+
+    fn flush(&mut self, batch: &mut Batch) -> Result<bool, ViewError> {
+        let delete_map = self.map.flush(batch)?;
+        let delete_index = self.index.flush(batch)?;
+        Ok(delete_map && delete_index)
+    }
+
+    fn clear(&mut self) {
+        self.map.clear();
+        self.index.clear();
+    }
+}
+
+impl<C: Clone, K, V, I> ClonableView for IndexedMapView<C, K, V, I>
+where
+    Self: View,
+    ByteMapView<C, V>: ClonableView,
+    ByteMapView<C, u32>: ClonableView,
+{
+    fn clone_unchecked(&mut self) -> Self {
+        IndexedMapView {
+            context: self.context.clone(),
+            map: self.map.clone_unchecked(),
+            index: self.index.clone_unchecked(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// Returns the key of an index entry.
+fn index_entry_key(tag: u8, index_key: &[u8], short_key: &[u8]) -> Vec<u8> {
+    let mut key = Vec::with_capacity(1 + index_key.len() + short_key.len());
+    key.push(tag);
+    key.extend_from_slice(index_key);
+    key.extend_from_slice(short_key);
+    key
+}
+
+/// Splits the key of an index entry, whose tag has been removed, into the serialized
+/// index key and the serialized key of the map.
+fn split_index_entry_key(key: &[u8], index_key_len: u32) -> Result<(&[u8], &[u8]), ViewError> {
+    let index_key_len = index_key_len as usize;
+    if key.len() < index_key_len {
+        return Err(ViewError::InconsistentEntries);
+    }
+    Ok(key.split_at(index_key_len))
+}
+
+impl<C, K, V, I> IndexedMapView<C, K, V, I>
+where
+    C: Context,
+    K: Serialize,
+    V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+    I: SecondaryIndices<V>,
+{
+    /// Inserts or resets the value of a key, and updates the indices.
+    pub async fn insert<Q>(&mut self, key: &Q, value: V) -> Result<(), ViewError>
+    where
+        K: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let short_key = BaseKey::derive_short_key(key)?;
+        self.remove_index_entries(&short_key).await?;
+        for (tag, index_key) in I::index_entries(&value)? {
+            let index_key_len =
+                u32::try_from(index_key.len()).map_err(|_| ViewError::KeyTooLong)?;
+            let entry_key = index_entry_key(tag, &index_key, &short_key);
+            self.index.insert(entry_key, index_key_len);
+        }
+        self.map.insert(short_key, value);
+        Ok(())
+    }
+
+    /// Removes the value of a key and its index entries. If absent then the operation
+    /// does nothing.
+    pub async fn remove<Q>(&mut self, key: &Q) -> Result<(), ViewError>
+    where
+        K: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let short_key = BaseKey::derive_short_key(key)?;
+        self.remove_index_entries(&short_key).await?;
+        self.map.remove(short_key);
+        Ok(())
+    }
+
+    /// Removes the index entries of the current value of a key, if any.
+    async fn remove_index_entries(&mut self, short_key: &[u8]) -> Result<(), ViewError> {
+        if let Some(value) = self.map.get(short_key).await? {
+            for (tag, index_key) in I::index_entries(&value)? {
+                self.index
+                    .remove(index_entry_key(tag, &index_key, short_key));
+            }
+        }
+        Ok(())
+    }
+
+    /// Obtains the extra data.
+    pub fn extra(&self) -> &C::Extra {
+        self.context.extra()
+    }
+
+    /// Returns whether the map contains a key.
+    pub async fn contains_key<Q>(&self, key: &Q) -> Result<bool, ViewError>
+    where
+        K: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let short_key = BaseKey::derive_short_key(key)?;
+        self.map.contains_key(&short_key).await
+    }
+
+    /// Reads the value of a key, if any.
+    pub async fn get<Q>(&self, key: &Q) -> Result<Option<V>, ViewError>
+    where
+        K: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let short_key = BaseKey::derive_short_key(key)?;
+        self.map.get(&short_key).await
+    }
+
+    /// Reads the values of the given keys, if any.
+    pub async fn multi_get<'a, Q>(
+        &self,
+        keys: impl IntoIterator<Item = &'a Q>,
+    ) -> Result<Vec<Option<V>>, ViewError>
+    where
+        K: Borrow<Q>,
+        Q: Serialize + 'a,
+    {
+        let short_keys = keys
+            .into_iter()
+            .map(|key| BaseKey::derive_short_key(key))
+            .collect::<Result<_, _>>()?;
+        self.map.multi_get(short_keys).await
+    }
+
+    /// Checks that `J` is one of the indices of the view.
+    fn check_index<J: SecondaryIndex<V>>() -> Result<(), ViewError> {
+        if !I::contains_tag(J::TAG) {
+            return Err(ViewError::NotFound(format!("secondary index {}", J::NAME)));
+        }
+        Ok(())
+    }
+
+    /// Returns the serialized keys of the map whose index key is `index_key`, for the
+    /// index with the given tag.
+    async fn short_keys_by_index(
+        &self,
+        tag: u8,
+        index_key: &[u8],
+    ) -> Result<Vec<Vec<u8>>, ViewError> {
+        let mut prefix = vec![tag];
+        prefix.extend_from_slice(index_key);
+        let entries = self.index.key_values_by_prefix(prefix).await?;
+        let mut short_keys = Vec::new();
+        for (key, index_key_len) in entries {
+            let (entry_index_key, short_key) = split_index_entry_key(&key[1..], index_key_len)?;
+            // With a serialization that is not prefix-free, other index keys may start
+            // with `index_key`.
+            if entry_index_key == index_key {
+                short_keys.push(short_key.to_vec());
+            }
+        }
+        Ok(short_keys)
+    }
+
+    /// Reads the values of serialized keys that are in the index.
+    async fn indexed_values(&self, short_keys: Vec<Vec<u8>>) -> Result<Vec<V>, ViewError> {
+        self.map
+            .multi_get(short_keys)
+            .await?
+            .into_iter()
+            .map(|value| value.ok_or(ViewError::InconsistentEntries))
+            .collect()
+    }
+}
+
+impl<C, K, V, I> IndexedMapView<C, K, V, I>
+where
+    C: Context,
+    K: Serialize + DeserializeOwned + Send,
+    V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+    I: SecondaryIndices<V>,
+{
+    /// Returns the keys of the map, in the order of their serialization.
+    pub async fn keys(&self) -> Result<Vec<K>, ViewError> {
+        self.map
+            .keys()
+            .await?
+            .iter()
+            .map(|key| Ok(BaseKey::deserialize_value(key)?))
+            .collect()
+    }
+
+    /// Returns the keys and values of the map, in the order of the serialized keys.
+    pub async fn key_values(&self) -> Result<Vec<(K, V)>, ViewError> {
+        self.map
+            .key_values()
+            .await?
+            .into_iter()
+            .map(|(key, value)| Ok((BaseKey::deserialize_value(&key)?, value)))
+            .collect()
+    }
+
+    /// Returns the number of entries in the map.
+    pub async fn count(&self) -> Result<usize, ViewError> {
+        self.map.count().await
+    }
+
+    /// Returns the keys of the entries whose key in the index `J` is `index_key`, in the
+    /// order of their serialization.
+    pub async fn keys_by_index<J>(&self, index_key: &J::Key) -> Result<Vec<K>, ViewError>
+    where
+        J: SecondaryIndex<V>,
+    {
+        Self::check_index::<J>()?;
+        let index_key = J::serialize_key(index_key)?;
+        self.short_keys_by_index(J::TAG, &index_key)
+            .await?
+            .iter()
+            .map(|key| Ok(BaseKey::deserialize_value(key)?))
+            .collect()
+    }
+
+    /// Returns the keys and values of the entries whose key in the index `J` is
+    /// `index_key`, in the order of the serialized keys.
+    pub async fn key_values_by_index<J>(&self, index_key: &J::Key) -> Result<Vec<(K, V)>, ViewError>
+    where
+        J: SecondaryIndex<V>,
+    {
+        Self::check_index::<J>()?;
+        let index_key = J::serialize_key(index_key)?;
+        let short_keys = self.short_keys_by_index(J::TAG, &index_key).await?;
+        let keys = short_keys
+            .iter()
+            .map(|key| BaseKey::deserialize_value(key))
+            .collect::<Result<Vec<K>, _>>()?;
+        let values = self.indexed_values(short_keys).await?;
+        Ok(keys.into_iter().zip(values).collect())
+    }
+
+    /// Returns the index keys and the keys of the entries whose key in the index `J` is
+    /// within `range`, in the given direction and up to `limit` entries. The entries are
+    /// ordered by serialized index key, then by serialized key.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::indexed_map_view::{IndexedMapView, SecondaryIndex};
+    /// # use linera_views::store::Direction;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// struct ByLength;
+    ///
+    /// impl SecondaryIndex<String> for ByLength {
+    ///     const NAME: &'static str = "length";
+    ///     const TAG: u8 = 0;
+    ///     type Key = u8;
+    ///
+    ///     fn index_key(value: &String) -> Option<u8> {
+    ///         u8::try_from(value.len()).ok()
+    ///     }
+    /// }
+    ///
+    /// let mut map: IndexedMapView<_, u32, String, (ByLength,)> =
+    ///     IndexedMapView::load(context).await.unwrap();
+    /// map.insert(&(1 as u32), String::from("Ciao")).await.unwrap();
+    /// map.insert(&(2 as u32), String::from("Bonjour")).await.unwrap();
+    /// map.insert(&(3 as u32), String::from("Hallo")).await.unwrap();
+    /// let entries = map
+    ///     .keys_by_index_range::<ByLength, _>(5.., None, Direction::Forward)
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(entries, vec![(5, 3), (7, 2)]);
+    /// # })
+    /// ```
+    pub async fn keys_by_index_range<J, R>(
+        &self,
+        range: R,
+        limit: Option<usize>,
+        direction: Direction,
+    ) -> Result<Vec<(J::Key, K)>, ViewError>
+    where
+        J: SecondaryIndex<V>,
+        R: RangeBounds<J::Key>,
+    {
+        let entries = self
+            .short_keys_by_index_range::<J, R>(range, limit, direction)
+            .await?;
+        entries
+            .into_iter()
+            .map(|(index_key, short_key)| Ok((index_key, BaseKey::deserialize_value(&short_key)?)))
+            .collect()
+    }
+
+    /// Returns the index keys, the keys and the values of the entries whose key in the
+    /// index `J` is within `range`, in the given direction and up to `limit` entries.
+    pub async fn key_values_by_index_range<J, R>(
+        &self,
+        range: R,
+        limit: Option<usize>,
+        direction: Direction,
+    ) -> Result<Vec<(J::Key, K, V)>, ViewError>
+    where
+        J: SecondaryIndex<V>,
+        R: RangeBounds<J::Key>,
+    {
+        let entries = self
+            .short_keys_by_index_range::<J, R>(range, limit, direction)
+            .await?;
+        let short_keys = entries.iter().map(|(_, key)| key.clone()).collect();
+        let values = self.indexed_values(short_keys).await?;
+        entries
+            .into_iter()
+            .zip(values)
+            .map(|((index_key, short_key), value)| {
+                Ok((index_key, BaseKey::deserialize_value(&short_key)?, value))
+            })
+            .collect()
+    }
+
+    /// Returns the index keys and the serialized keys of the map within `range` for the
+    /// index `J`.
+    async fn short_keys_by_index_range<J, R>(
+        &self,
+        range: R,
+        limit: Option<usize>,
+        direction: Direction,
+    ) -> Result<Vec<(J::Key, Vec<u8>)>, ViewError>
+    where
+        J: SecondaryIndex<V>,
+        R: RangeBounds<J::Key>,
+    {
+        Self::check_index::<J>()?;
+        let prefix = vec![J::TAG];
+        let with_prefix = |index_key: &J::Key| -> Result<Vec<u8>, ViewError> {
+            let mut key = prefix.clone();
+            key.extend(J::serialize_key(index_key)?);
+            Ok(key)
+        };
+        // The entries of an index key are all the ones starting with it.
+        let start = match range.start_bound() {
+            Bound::Included(index_key) => Some(with_prefix(index_key)?),
+            Bound::Excluded(index_key) => match get_upper_bound_option(&with_prefix(index_key)?) {
+                Some(start) => Some(start),
+                None => return Ok(Vec::new()),
+            },
+            Bound::Unbounded => Some(prefix.clone()),
+        };
+        let end = match range.end_bound() {
+            Bound::Included(index_key) => get_upper_bound_option(&with_prefix(index_key)?),
+            Bound::Excluded(index_key) => Some(with_prefix(index_key)?),
+            Bound::Unbounded => get_upper_bound_option(&prefix),
+        };
+        let query = RangeQuery {
+            start,
+            end,
+            limit,
+            direction,
+        };
+        let entries = self.index.key_values_by_range(&query).await?;
+        entries
+            .into_iter()
+            .map(|(key, index_key_len)| {
+                let (index_key, short_key) = split_index_entry_key(&key[1..], index_key_len)?;
+                Ok((J::deserialize_key(index_key)?, short_key.to_vec()))
+            })
+            .collect()
+    }
+}
+
+impl<C, K, V, I> HashableView for IndexedMapView<C, K, V, I>
+where
+    Self: View,
+    ByteMapView<C, V>: HashableView,
+{
+    type Hasher = <ByteMapView<C, V> as HashableView>::Hasher;
+
+    // The indices are determined by the entries of the map, so only the latter are hashed.
+    async fn hash_mut(&mut self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        self.map.hash_mut().await
+    }
+
+    async fn hash(&self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        self.map.hash().await
+    }
+}
+
+/// Type wrapping `IndexedMapView` while memoizing the hash.
+pub type HashedIndexedMapView<C, K, V, I> =
+    WrappedHashableContainerView<C, IndexedMapView<C, K, V, I>, HasherOutput>;
+
+#[cfg(with_graphql)]
+mod graphql {
+    use std::borrow::Cow;
+
+    use super::{IndexedMapView, SecondaryIndices};
+    use crate::{
+        context::Context,
+        graphql::{hash_name, mangle, Entry, IndexInput, MapInput},
+    };
+
+    impl<C: Send + Sync, K: async_graphql::OutputType, V: async_graphql::OutputType, I>
+        async_graphql::TypeName for IndexedMapView<C, K, V, I>
+    {
+        fn type_name() -> Cow<'static, str> {
+            format!(
+                "IndexedMapView_{}_{}_{:08x}",
+                mangle(K::type_name()),
+                mangle(V::type_name()),
+                hash_name::<(K, V)>(),
+            )
+            .into()
+        }
+    }
+
+    #[async_graphql::Object(cache_control(no_cache), name_type)]
+    impl<C, K, V, I> IndexedMapView<C, K, V, I>
+    where
+        C: Context,
+        K: async_graphql::OutputType
+            + async_graphql::InputType
+            + serde::ser::Serialize
+            + serde::de::DeserializeOwned
+            + std::fmt::Debug
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+        V: async_graphql::OutputType
+            + serde::ser::Serialize
+            + serde::de::DeserializeOwned
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+        I: SecondaryIndices<V> + Send + Sync,
+    {
+        #[graphql(derived(name = "keys"))]
+        async fn keys_(&self, count: Option<usize>) -> Result<Vec<K>, async_graphql::Error> {
+            let keys = self.keys().await?;
+            let it = keys.iter().cloned();
+            Ok(if let Some(count) = count {
+                it.take(count).collect()
+            } else {
+                it.collect()
+            })
+        }
+
+        #[graphql(derived(name = "count"))]
+        async fn count_(&self) -> Result<u32, async_graphql::Error> {
+            Ok(self.count().await? as u32)
+        }
+
+        async fn entry(&self, key: K) -> Result<Entry<K, Option<V>>, async_graphql::Error> {
+            Ok(Entry {
+                value: self.get(&key).await?,
+                key,
+            })
+        }
+
+        async fn entries(
+            &self,
+            input: Option<MapInput<K>>,
+        ) -> Result<Vec<Entry<K, Option<V>>>, async_graphql::Error> {
+            let keys = input
+                .and_then(|input| input.filters)
+                .and_then(|filters| filters.keys);
+            let keys = if let Some(keys) = keys {
+                keys
+            } else {
+                self.keys().await?
+            };
+
+            let values = self.multi_get(&keys).await?;
+            Ok(values
+                .into_iter()
+                .zip(keys)
+                .map(|(value, key)| Entry { value, key })
+                .collect())
+        }
+
+        /// The entries whose key in the given secondary index is `input.key`.
+        async fn entries_by_index(
+            &self,
+            input: IndexInput,
+        ) -> Result<Vec<Entry<K, V>>, async_graphql::Error> {
+            let (tag, index_key) = I::graphql_index_key(&input.index, input.key.0)?;
+            let short_keys = self.short_keys_by_index(tag, &index_key).await?;
+            let keys = short_keys
+                .iter()
+                .map(|key| crate::context::BaseKey::deserialize_value(key))
+                .collect::<Result<Vec<K>, _>>()?;
+            let values = self.indexed_values(short_keys).await?;
+            Ok(keys
+                .into_iter()
+                .zip(values)
+                .map(|(key, value)| Entry { key, value })
+                .collect())
+        }
+    }
+}
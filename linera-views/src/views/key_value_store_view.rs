@@ -23,12 +23,12 @@ use serde::{Deserialize, Serialize};
 use crate::{
     batch::{Batch, WriteOperation},
     common::{
-        from_bytes_option, from_bytes_option_or_default, get_interval, get_upper_bound,
-        DeletionSet, HasherOutput, SuffixClosedSetIterator, Update,
+        from_bytes_option, from_bytes_option_or_default, get_interval, get_range_interval,
+        get_upper_bound, DeletionSet, HasherOutput, SuffixClosedSetIterator, Update,
     },
     context::Context,
     map_view::ByteMapView,
-    store::ReadableKeyValueStore,
+    store::{Direction, RangeQuery, ReadableKeyValueStore},
     views::{ClonableView, HashableView, Hasher, ReplaceContext, View, ViewError, MIN_VIEW_TAG},
 };
 
@@ -1152,6 +1152,141 @@ impl<C: Context> KeyValueStoreView<C> {
         Ok(key_values)
     }
 
+    /// Returns the keys matching the given prefix that lie within the bounds of the
+    /// `query`, in the direction of the query. The prefix is not included in the returned
+    /// keys.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::key_value_store_view::KeyValueStoreView;
+    /// # use linera_views::store::{Direction, RangeQuery};
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view = KeyValueStoreView::load(context).await.unwrap();
+    /// view.insert(vec![0, 1], vec![34]).await.unwrap();
+    /// view.insert(vec![0, 2], vec![42]).await.unwrap();
+    /// view.insert(vec![0, 3], vec![51]).await.unwrap();
+    /// let query = RangeQuery::from_bounds(..vec![3]).with_direction(Direction::Reverse);
+    /// let keys = view.find_keys_by_range(&[0], &query).await.unwrap();
+    /// assert_eq!(keys, vec![vec![2], vec![1]]);
+    /// # })
+    /// ```
+    pub async fn find_keys_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<Vec<u8>>, ViewError> {
+        let entries = self.find_entries_by_range(key_prefix, query, false).await?;
+        Ok(entries.into_iter().map(|(key, _)| key).collect())
+    }
+
+    /// Returns the key-value pairs matching the given prefix that lie within the bounds
+    /// of the `query`, in the direction of the query. The prefix is not included in the
+    /// returned keys.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::key_value_store_view::KeyValueStoreView;
+    /// # use linera_views::store::RangeQuery;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view = KeyValueStoreView::load(context).await.unwrap();
+    /// view.insert(vec![0, 1], vec![34]).await.unwrap();
+    /// view.insert(vec![0, 2], vec![42]).await.unwrap();
+    /// view.insert(vec![0, 3], vec![51]).await.unwrap();
+    /// let query = RangeQuery::from_bounds(vec![2]..).with_limit(1);
+    /// let key_values = view.find_key_values_by_range(&[0], &query).await.unwrap();
+    /// assert_eq!(key_values, vec![(vec![2], vec![42])]);
+    /// # })
+    /// ```
+    pub async fn find_key_values_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ViewError> {
+        self.find_entries_by_range(key_prefix, query, true).await
+    }
+
+    /// Merges the stored entries within the range of the `query` with the pending
+    /// updates. If `with_values` is false, the returned values are empty.
+    async fn find_entries_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+        with_values: bool,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ViewError> {
+        ensure!(
+            key_prefix.len() <= self.max_key_size(),
+            ViewError::KeyTooLong
+        );
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let len = key_prefix.len();
+        let mut entries = BTreeMap::new();
+        if !self.deletion_set.delete_storage_first {
+            // Every pending update hides at most one stored entry. Deleted prefixes may
+            // hide any number of them.
+            let limit = if self.deletion_set.deleted_prefixes.is_empty() {
+                let num_updates = self
+                    .updates
+                    .range(get_range_interval(key_prefix, query))
+                    .count();
+                query.limit.map(|limit| limit.saturating_add(num_updates))
+            } else {
+                None
+            };
+            let storage_query = RangeQuery {
+                limit,
+                ..query.clone()
+            };
+            let key_prefix_full = self
+                .context
+                .base_key()
+                .base_tag_index(KeyTag::Index as u8, key_prefix);
+            let store = self.context.store();
+            let stored_entries = if with_values {
+                store
+                    .find_key_values_by_range(&key_prefix_full, &storage_query)
+                    .await?
+            } else {
+                store
+                    .find_keys_by_range(&key_prefix_full, &storage_query)
+                    .await?
+                    .into_iter()
+                    .map(|key| (key, Vec::new()))
+                    .collect()
+            };
+            for (key, value) in stored_entries {
+                let mut key_with_prefix = key_prefix.to_vec();
+                key_with_prefix.extend_from_slice(&key);
+                if !self.deletion_set.contains_prefix_of(&key_with_prefix) {
+                    entries.insert(key, value);
+                }
+            }
+        }
+        for (update_key, update_value) in self.updates.range(get_range_interval(key_prefix, query))
+        {
+            let key = update_key[len..].to_vec();
+            match update_value {
+                Update::Removed => {
+                    entries.remove(&key);
+                }
+                Update::Set(value) if with_values => {
+                    entries.insert(key, value.clone());
+                }
+                Update::Set(_) => {
+                    entries.insert(key, Vec::new());
+                }
+            }
+        }
+        let limit = query.limit.unwrap_or(usize::MAX);
+        Ok(match query.direction {
+            Direction::Forward => entries.into_iter().take(limit).collect(),
+            Direction::Reverse => entries.into_iter().rev().take(limit).collect(),
+        })
+    }
+
     async fn compute_hash(&self) -> Result<<sha3::Sha3_256 as Hasher>::Output, ViewError> {
         #[cfg(with_metrics)]
         let _hash_latency = metrics::KEY_VALUE_STORE_VIEW_HASH_LATENCY.measure_latency();
@@ -1275,6 +1410,24 @@ impl<C: Context> ReadableKeyValueStore for ViewContainer<C> {
         let view = self.view.read().await;
         Ok(view.find_key_values_by_prefix(key_prefix).await?)
     }
+
+    async fn find_keys_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<Vec<u8>>, ViewContainerError> {
+        let view = self.view.read().await;
+        Ok(view.find_keys_by_range(key_prefix, query).await?)
+    }
+
+    async fn find_key_values_by_range(
+        &self,
+        key_prefix: &[u8],
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, ViewContainerError> {
+        let view = self.view.read().await;
+        Ok(view.find_key_values_by_range(key_prefix, query).await?)
+    }
 }
 
 #[cfg(with_testing)]
@@ -13,7 +13,7 @@ use crate::{
     context::Context,
     hashable_wrapper::WrappedHashableContainerView,
     store::ReadableKeyValueStore as _,
-    views::{ClonableView, HashableView, Hasher, View, ViewError, MIN_VIEW_TAG},
+    views::{proof::ViewProof, ClonableView, HashableView, Hasher, View, ViewError, MIN_VIEW_TAG},
 };
 
 #[cfg(with_metrics)]
@@ -346,6 +346,29 @@ where
             )
         }
     }
+
+    /// Creates a proof that the log holds its current value at position `index`. The
+    /// proof contains all the values of the log.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::log_view::LogView;
+    /// # use linera_views::proof::ProofPath;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut log = LogView::load(context).await.unwrap();
+    /// log.push(34);
+    /// log.push(42);
+    /// let proof = log.prove(1).await.unwrap();
+    /// let hash = log.hash().await.unwrap();
+    /// assert!(proof.verify(&hash, &ProofPath::new().sequence_element(1)).is_ok());
+    /// assert_eq!(proof.sequence_element::<u32>().unwrap(), (1, 42));
+    /// # })
+    /// ```
+    pub async fn prove(&self, index: usize) -> Result<ViewProof, ViewError> {
+        let elements = self.read(..).await?;
+        ViewProof::for_sequence(index, &elements)
+    }
 }
 
 impl<C, T> HashableView for LogView<C, T>
@@ -42,20 +42,26 @@ use std::{
     collections::{btree_map::Entry, BTreeMap},
     marker::PhantomData,
     mem,
+    ops::{Bound, RangeBounds},
 };
 
+use futures::{Stream, StreamExt as _};
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
     batch::Batch,
     common::{
-        from_bytes_option, get_interval, CustomSerialize, DeletionSet, HasherOutput,
+        from_bytes_option, get_interval, get_next_page_start, get_page_interval,
+        get_range_interval, merge_page, stream_pages, CustomSerialize, DeletionSet, HasherOutput,
         SuffixClosedSetIterator, Update,
     },
     context::{BaseKey, Context},
     hashable_wrapper::WrappedHashableContainerView,
-    store::ReadableKeyValueStore as _,
-    views::{ClonableView, HashableView, Hasher, ReplaceContext, View, ViewError},
+    store::{Direction, RangeQuery, ReadableKeyValueStore as _},
+    views::{
+        proof::{ProofLeaf, ViewProof},
+        ClonableView, HashableView, Hasher, ReplaceContext, View, ViewError,
+    },
 };
 
 /// A view that supports inserting and removing values indexed by `Vec<u8>`.
@@ -693,6 +699,144 @@ where
         }
         Ok(())
     }
+    /// Returns the keys within the bounds of the `query` together with their values or
+    /// serializations, in the direction of the query. Only as many entries are read from
+    /// storage as can be needed once merged with the pending updates.
+    async fn key_value_or_bytes_by_range<'a>(
+        &'a self,
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, ValueOrBytes<'a, V>)>, ViewError> {
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut stored_key_values = Vec::new();
+        if !self.deletion_set.delete_storage_first {
+            // Every pending update hides at most one stored entry. Deleted prefixes may
+            // hide any number of them.
+            let limit = if self.deletion_set.deleted_prefixes.is_empty() {
+                let num_updates = self.updates.range(get_range_interval(&[], query)).count();
+                query.limit.map(|limit| limit.saturating_add(num_updates))
+            } else {
+                None
+            };
+            let storage_query = RangeQuery {
+                limit,
+                ..query.clone()
+            };
+            let base = self.context.base_key().bytes.clone();
+            stored_key_values = self
+                .context
+                .store()
+                .find_key_values_by_range(&base, &storage_query)
+                .await?;
+        }
+        let mut entries = BTreeMap::new();
+        for (key, bytes) in stored_key_values {
+            if !self.deletion_set.contains_prefix_of(&key) {
+                entries.insert(key, ValueOrBytes::Bytes(bytes));
+            }
+        }
+        for (key, update) in self.updates.range(get_range_interval(&[], query)) {
+            match update {
+                Update::Removed => {
+                    entries.remove(key);
+                }
+                Update::Set(value) => {
+                    entries.insert(key.clone(), ValueOrBytes::Value(value));
+                }
+            }
+        }
+        let limit = query.limit.unwrap_or(usize::MAX);
+        Ok(match query.direction {
+            Direction::Forward => entries.into_iter().take(limit).collect(),
+            Direction::Reverse => entries.into_iter().rev().take(limit).collect(),
+        })
+    }
+
+    /// Returns the keys of the map within the bounds of the `query`, in the direction
+    /// of the query.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::ByteMapView;
+    /// # use linera_views::store::{Direction, RangeQuery};
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map = ByteMapView::load(context).await.unwrap();
+    /// map.insert(vec![0, 1], String::from("Hello"));
+    /// map.insert(vec![1, 2], String::from("Bonjour"));
+    /// map.insert(vec![2, 2], String::from("Hallo"));
+    /// let query = RangeQuery::from_bounds(vec![1]..)
+    ///     .with_limit(1)
+    ///     .with_direction(Direction::Reverse);
+    /// assert_eq!(map.keys_by_range(&query).await.unwrap(), vec![vec![2, 2]]);
+    /// # })
+    /// ```
+    pub async fn keys_by_range(&self, query: &RangeQuery) -> Result<Vec<Vec<u8>>, ViewError> {
+        let entries = self.key_value_or_bytes_by_range(query).await?;
+        Ok(entries.into_iter().map(|(key, _)| key).collect())
+    }
+
+    /// Reads the page of at most `page_size` stored keys starting at `start`, merges it
+    /// with the pending updates, and returns it with the start of the next page.
+    async fn keys_page(
+        &self,
+        start: Option<Vec<u8>>,
+        page_size: usize,
+    ) -> Result<(Vec<Vec<u8>>, Option<Vec<u8>>), ViewError> {
+        let mut stored_keys = Vec::new();
+        let mut next_start = None;
+        if !self.deletion_set.delete_storage_first {
+            let query = RangeQuery {
+                start: start.clone(),
+                limit: Some(page_size),
+                ..RangeQuery::default()
+            };
+            let base = self.context.base_key().bytes.clone();
+            stored_keys = self
+                .context
+                .store()
+                .find_keys_by_range(&base, &query)
+                .await?;
+            next_start = get_next_page_start(stored_keys.len(), page_size, stored_keys.last());
+        }
+        let entries = merge_page(
+            stored_keys.into_iter().map(|key| (key, ())).collect(),
+            self.updates
+                .range(get_page_interval(start, next_start.as_ref())),
+            |key| self.deletion_set.contains_prefix_of(key),
+            |_| (),
+        );
+        let keys = entries.into_iter().map(|(key, ())| key).collect();
+        Ok((keys, next_start))
+    }
+
+    /// Streams the keys of the map in lexicographic order. The keys are read from storage
+    /// in pages of at most `page_size` keys, so that large maps can be visited with
+    /// bounded memory.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use futures::TryStreamExt as _;
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::ByteMapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map = ByteMapView::load(context).await.unwrap();
+    /// map.insert(vec![0, 1], String::from("Hello"));
+    /// map.insert(vec![1, 2], String::from("Bonjour"));
+    /// let keys = map.keys_stream(1).try_collect::<Vec<_>>().await.unwrap();
+    /// assert_eq!(keys, vec![vec![0, 1], vec![1, 2]]);
+    /// # })
+    /// ```
+    pub fn keys_stream(
+        &self,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<Vec<u8>, ViewError>> + '_ {
+        stream_pages(page_size, move |start, page_size| {
+            self.keys_page(start, page_size)
+        })
+    }
+
     /// Applies a function f on each index/value pair matching a prefix. Keys
     /// and values are visited in the lexicographic order. The shortened index
     /// is send to the function f and if it returns false then the loop ends
@@ -866,6 +1010,161 @@ where
     pub async fn key_values(&self) -> Result<Vec<(Vec<u8>, V)>, ViewError> {
         self.key_values_by_prefix(Vec::new()).await
     }
+
+    /// Returns the keys and values of the map within the bounds of the `query`, in the
+    /// direction of the query.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::ByteMapView;
+    /// # use linera_views::store::{Direction, RangeQuery};
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map = ByteMapView::load(context).await.unwrap();
+    /// map.insert(vec![0, 1], String::from("Hello"));
+    /// map.insert(vec![1, 2], String::from("Bonjour"));
+    /// map.insert(vec![2, 2], String::from("Hallo"));
+    /// let query = RangeQuery::from_bounds(vec![0, 2]..vec![2]).with_direction(Direction::Reverse);
+    /// assert_eq!(
+    ///     map.key_values_by_range(&query).await.unwrap(),
+    ///     vec![(vec![1, 2], String::from("Bonjour"))]
+    /// );
+    /// # })
+    /// ```
+    pub async fn key_values_by_range(
+        &self,
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, V)>, ViewError> {
+        let entries = self.key_value_or_bytes_by_range(query).await?;
+        entries
+            .into_iter()
+            .map(|(key, value)| Ok((key, value.to_value()?.into_owned())))
+            .collect()
+    }
+
+    /// Reads the page of at most `page_size` stored entries starting at `start`, merges
+    /// it with the pending updates, and returns it with the start of the next page.
+    async fn key_values_page(
+        &self,
+        start: Option<Vec<u8>>,
+        page_size: usize,
+    ) -> Result<(Vec<(Vec<u8>, V)>, Option<Vec<u8>>), ViewError> {
+        let mut stored_key_values = Vec::new();
+        let mut next_start = None;
+        if !self.deletion_set.delete_storage_first {
+            let query = RangeQuery {
+                start: start.clone(),
+                limit: Some(page_size),
+                ..RangeQuery::default()
+            };
+            let base = self.context.base_key().bytes.clone();
+            let key_values = self
+                .context
+                .store()
+                .find_key_values_by_range(&base, &query)
+                .await?;
+            let last_key = key_values.last().map(|(key, _)| key);
+            next_start = get_next_page_start(key_values.len(), page_size, last_key);
+            for (key, bytes) in key_values {
+                if !self.deletion_set.contains_prefix_of(&key) {
+                    stored_key_values.push((key, bcs::from_bytes(&bytes)?));
+                }
+            }
+        }
+        let entries = merge_page(
+            stored_key_values,
+            self.updates
+                .range(get_page_interval(start, next_start.as_ref())),
+            |_| false,
+            V::clone,
+        );
+        Ok((entries, next_start))
+    }
+
+    /// Streams the keys and values of the map in lexicographic order. The entries are
+    /// read from storage in pages of at most `page_size` entries.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use futures::TryStreamExt as _;
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::ByteMapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map = ByteMapView::load(context).await.unwrap();
+    /// map.insert(vec![0, 1], String::from("Hello"));
+    /// map.insert(vec![1, 2], String::from("Bonjour"));
+    /// let key_values = map.key_values_stream(1).try_collect::<Vec<_>>().await.unwrap();
+    /// assert_eq!(
+    ///     key_values,
+    ///     vec![
+    ///         (vec![0, 1], String::from("Hello")),
+    ///         (vec![1, 2], String::from("Bonjour"))
+    ///     ]
+    /// );
+    /// # })
+    /// ```
+    pub fn key_values_stream(
+        &self,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<(Vec<u8>, V), ViewError>> + '_ {
+        stream_pages(page_size, move |start, page_size| {
+            self.key_values_page(start, page_size)
+        })
+    }
+
+    /// Creates a proof that `short_key` is present in the map with its current value, or
+    /// absent from the map. The proof contains all the entries of the map.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::ByteMapView;
+    /// # use linera_views::proof::ProofPath;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map = ByteMapView::load(context).await.unwrap();
+    /// map.insert(vec![0, 1], String::from("Hello"));
+    /// let proof = map.prove_key(&[0, 1]).await.unwrap();
+    /// let hash = map.hash().await.unwrap();
+    /// assert!(proof.verify(&hash, &ProofPath::new().map_entry(vec![0, 1])).is_ok());
+    /// # })
+    /// ```
+    pub async fn prove_key(&self, short_key: &[u8]) -> Result<ViewProof, ViewError> {
+        let mut entries = Vec::new();
+        self.for_each_key_value_or_bytes(
+            |key, value| {
+                entries.push((key.to_vec(), value.into_bytes()?));
+                Ok(())
+            },
+            Vec::new(),
+        )
+        .await?;
+        let key = short_key.to_vec();
+        Ok(ViewProof::new(ProofLeaf::Map { key, entries }))
+    }
+}
+
+/// Builds the range query selecting the indices within `range`, according to the order
+/// of their serializations.
+pub(crate) fn serialize_range<Q: ?Sized>(
+    range: impl RangeBounds<Q>,
+    limit: Option<usize>,
+    direction: Direction,
+    serialize: impl Fn(&Q) -> Result<Vec<u8>, ViewError>,
+) -> Result<RangeQuery, ViewError> {
+    let serialize_bound = |bound: Bound<&Q>| -> Result<Bound<Vec<u8>>, ViewError> {
+        Ok(match bound {
+            Bound::Included(index) => Bound::Included(serialize(index)?),
+            Bound::Excluded(index) => Bound::Excluded(serialize(index)?),
+            Bound::Unbounded => Bound::Unbounded,
+        })
+    };
+    let start = serialize_bound(range.start_bound())?;
+    let end = serialize_bound(range.end_bound())?;
+    let query = RangeQuery {
+        limit,
+        ..RangeQuery::from_bounds((start, end))
+    };
+    Ok(query.with_direction(direction))
 }
 
 impl<C, V> ByteMapView<C, V>
@@ -1453,6 +1752,174 @@ where
     pub async fn count(&self) -> Result<usize, ViewError> {
         self.map.count().await
     }
+
+    /// Obtains the `(index,value)` pairs whose indices are within `range`, in the given
+    /// direction and up to `limit` entries. The bounds and the order are the ones of the
+    /// serialized indices.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::MapView;
+    /// # use linera_views::store::Direction;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map: MapView<_, String, _> = MapView::load(context).await.unwrap();
+    /// map.insert("Italian", String::from("Ciao"));
+    /// map.insert("French", String::from("Bonjour"));
+    /// map.insert("German", String::from("Hallo"));
+    /// let range = "French".to_string()..="German".to_string();
+    /// let index_values = map
+    ///     .index_values_by_range(range, Some(1), Direction::Reverse)
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     index_values,
+    ///     vec![("German".to_string(), "Hallo".to_string())]
+    /// );
+    /// # })
+    /// ```
+    pub async fn index_values_by_range<R, Q>(
+        &self,
+        range: R,
+        limit: Option<usize>,
+        direction: Direction,
+    ) -> Result<Vec<(I, V)>, ViewError>
+    where
+        R: RangeBounds<Q>,
+        I: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let query = serialize_range(range, limit, direction, |index| {
+            Ok(BaseKey::derive_short_key(index)?)
+        })?;
+        let key_values = self.map.key_values_by_range(&query).await?;
+        key_values
+            .into_iter()
+            .map(|(key, value)| Ok((BaseKey::deserialize_value(&key)?, value)))
+            .collect()
+    }
+
+    /// Obtains the indices within `range`, in the given direction and up to `limit`
+    /// entries. The bounds and the order are the ones of the serialized indices.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::MapView;
+    /// # use linera_views::store::Direction;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map: MapView<_, String, _> = MapView::load(context).await.unwrap();
+    /// map.insert("Italian", String::from("Ciao"));
+    /// map.insert("French", String::from("Bonjour"));
+    /// let indices = map
+    ///     .indices_by_range::<_, str>(.., None, Direction::Forward)
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(indices, vec!["French".to_string(), "Italian".to_string()]);
+    /// # })
+    /// ```
+    pub async fn indices_by_range<R, Q>(
+        &self,
+        range: R,
+        limit: Option<usize>,
+        direction: Direction,
+    ) -> Result<Vec<I>, ViewError>
+    where
+        R: RangeBounds<Q>,
+        I: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let query = serialize_range(range, limit, direction, |index| {
+            Ok(BaseKey::derive_short_key(index)?)
+        })?;
+        let keys = self.map.keys_by_range(&query).await?;
+        keys.iter()
+            .map(|key| Ok(BaseKey::deserialize_value(key)?))
+            .collect()
+    }
+
+    /// Streams the indices of the map, in the order determined by their serialization.
+    /// The indices are read from storage in pages of at most `page_size` indices.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use futures::TryStreamExt as _;
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::MapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map: MapView<_, String, _> = MapView::load(context).await.unwrap();
+    /// map.insert("Italian", String::from("Ciao"));
+    /// map.insert("French", String::from("Bonjour"));
+    /// let indices = map.indices_stream(10).try_collect::<Vec<_>>().await.unwrap();
+    /// assert_eq!(indices, vec!["French".to_string(), "Italian".to_string()]);
+    /// # })
+    /// ```
+    pub fn indices_stream(
+        &self,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<I, ViewError>> + '_ {
+        self.map
+            .keys_stream(page_size)
+            .map(|key| Ok(BaseKey::deserialize_value(&key?)?))
+    }
+
+    /// Streams the `(index,value)` pairs of the map, in the order determined by the
+    /// serialization of the indices. The entries are read from storage in pages of at
+    /// most `page_size` entries.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use futures::TryStreamExt as _;
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::MapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map: MapView<_, String, _> = MapView::load(context).await.unwrap();
+    /// map.insert("Italian", String::from("Ciao"));
+    /// let index_values = map.index_values_stream(10).try_collect::<Vec<_>>().await.unwrap();
+    /// assert_eq!(
+    ///     index_values,
+    ///     vec![("Italian".to_string(), "Ciao".to_string())]
+    /// );
+    /// # })
+    /// ```
+    pub fn index_values_stream(
+        &self,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<(I, V), ViewError>> + '_ {
+        self.map.key_values_stream(page_size).map(|entry| {
+            let (key, value) = entry?;
+            Ok((BaseKey::deserialize_value(&key)?, value))
+        })
+    }
+
+    /// Creates a proof that `index` is present in the map with its current value, or
+    /// absent from the map. The proof contains all the entries of the map.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::MapView;
+    /// # use linera_views::proof::ProofPath;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map: MapView<_, u32, _> = MapView::load(context).await.unwrap();
+    /// map.insert(&37, String::from("Hello"));
+    /// let proof = map.prove(&37).await.unwrap();
+    /// let hash = map.hash().await.unwrap();
+    /// let key = bcs::to_bytes(&37_u32).unwrap();
+    /// assert!(proof.verify(&hash, &ProofPath::new().map_entry(key)).is_ok());
+    /// let (index, value) = proof.map_entry::<u32, String>().unwrap();
+    /// assert_eq!(index, 37);
+    /// assert_eq!(value, Some(String::from("Hello")));
+    /// # })
+    /// ```
+    pub async fn prove<Q>(&self, index: &Q) -> Result<ViewProof, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let short_key = BaseKey::derive_short_key(index)?;
+        self.map.prove_key(&short_key).await
+    }
 }
 
 impl<C, I, V> MapView<C, I, V>
@@ -1971,6 +2438,163 @@ where
     pub async fn count(&self) -> Result<usize, ViewError> {
         self.map.count().await
     }
+
+    /// Obtains the `(index,value)` pairs whose indices are within `range`, in the given
+    /// direction and up to `limit` entries. The bounds and the order are the ones of the
+    /// custom serialization.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::CustomMapView;
+    /// # use linera_views::store::Direction;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map: CustomMapView<_, u128, String> = CustomMapView::load(context).await.unwrap();
+    /// map.insert(&(24 as u128), String::from("Ciao"));
+    /// map.insert(&(37 as u128), String::from("Bonjour"));
+    /// map.insert(&(300 as u128), String::from("Hallo"));
+    /// let index_values = map
+    ///     .index_values_by_range(30..=300, Some(2), Direction::Reverse)
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     index_values,
+    ///     vec![
+    ///         (300 as u128, "Hallo".to_string()),
+    ///         (37 as u128, "Bonjour".to_string())
+    ///     ]
+    /// );
+    /// # })
+    /// ```
+    pub async fn index_values_by_range<R>(
+        &self,
+        range: R,
+        limit: Option<usize>,
+        direction: Direction,
+    ) -> Result<Vec<(I, V)>, ViewError>
+    where
+        R: RangeBounds<I>,
+    {
+        let query = serialize_range(range, limit, direction, I::to_custom_bytes)?;
+        let key_values = self.map.key_values_by_range(&query).await?;
+        key_values
+            .into_iter()
+            .map(|(key, value)| Ok((I::from_custom_bytes(&key)?, value)))
+            .collect()
+    }
+
+    /// Obtains the indices within `range`, in the given direction and up to `limit`
+    /// entries. The bounds and the order are the ones of the custom serialization.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::CustomMapView;
+    /// # use linera_views::store::Direction;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map: CustomMapView<_, u128, String> = CustomMapView::load(context).await.unwrap();
+    /// map.insert(&(24 as u128), String::from("Ciao"));
+    /// map.insert(&(37 as u128), String::from("Bonjour"));
+    /// let indices = map
+    ///     .indices_by_range(..37, None, Direction::Forward)
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(indices, vec![24 as u128]);
+    /// # })
+    /// ```
+    pub async fn indices_by_range<R>(
+        &self,
+        range: R,
+        limit: Option<usize>,
+        direction: Direction,
+    ) -> Result<Vec<I>, ViewError>
+    where
+        R: RangeBounds<I>,
+    {
+        let query = serialize_range(range, limit, direction, I::to_custom_bytes)?;
+        let keys = self.map.keys_by_range(&query).await?;
+        keys.iter().map(|key| I::from_custom_bytes(key)).collect()
+    }
+
+    /// Streams the indices of the map, in the order determined by the custom
+    /// serialization. The indices are read from storage in pages of at most `page_size`
+    /// indices.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use futures::TryStreamExt as _;
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::CustomMapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map: CustomMapView<_, u128, String> = CustomMapView::load(context).await.unwrap();
+    /// map.insert(&(34 as u128), String::from("Ciao"));
+    /// map.insert(&(12 as u128), String::from("Bonjour"));
+    /// let indices = map.indices_stream(1).try_collect::<Vec<_>>().await.unwrap();
+    /// assert_eq!(indices, vec![12 as u128, 34 as u128]);
+    /// # })
+    /// ```
+    pub fn indices_stream(
+        &self,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<I, ViewError>> + '_ {
+        self.map
+            .keys_stream(page_size)
+            .map(|key| I::from_custom_bytes(&key?))
+    }
+
+    /// Streams the `(index,value)` pairs of the map, in the order determined by the custom
+    /// serialization of the indices. The entries are read from storage in pages of at
+    /// most `page_size` entries.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use futures::TryStreamExt as _;
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::map_view::CustomMapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map: CustomMapView<_, u128, String> = CustomMapView::load(context).await.unwrap();
+    /// map.insert(&(34 as u128), String::from("Ciao"));
+    /// let index_values = map.index_values_stream(1).try_collect::<Vec<_>>().await.unwrap();
+    /// assert_eq!(index_values, vec![(34 as u128, String::from("Ciao"))]);
+    /// # })
+    /// ```
+    pub fn index_values_stream(
+        &self,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<(I, V), ViewError>> + '_ {
+        self.map.key_values_stream(page_size).map(|entry| {
+            let (key, value) = entry?;
+            Ok((I::from_custom_bytes(&key)?, value))
+        })
+    }
+
+    /// Creates a proof that `index` is present in the map with its current value, or
+    /// absent from the map. The proof contains all the entries of the map, with keys in
+    /// the custom serialization.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::common::CustomSerialize as _;
+    /// # use linera_views::map_view::CustomMapView;
+    /// # use linera_views::proof::ProofPath;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map: CustomMapView<_, u128, String> = CustomMapView::load(context).await.unwrap();
+    /// map.insert(&(34 as u128), String::from("Ciao"));
+    /// let proof = map.prove(&(34 as u128)).await.unwrap();
+    /// let hash = map.hash().await.unwrap();
+    /// let key = (34 as u128).to_custom_bytes().unwrap();
+    /// assert!(proof.verify(&hash, &ProofPath::new().map_entry(key)).is_ok());
+    /// # })
+    /// ```
+    pub async fn prove<Q>(&self, index: &Q) -> Result<ViewProof, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: CustomSerialize,
+    {
+        let short_key = index.to_custom_bytes()?;
+        self.map.prove_key(&short_key).await
+    }
 }
 
 impl<C, I, V> CustomMapView<C, I, V>
@@ -30,6 +30,12 @@ pub mod queue_view;
 /// The `MapView` implements a map with ordered keys.
 pub mod map_view;
 
+/// The `IndexedMapView` implements a map with secondary indices on its values.
+pub mod indexed_map_view;
+
+/// The `OrderedMapView` implements a map with order statistics on its keys.
+pub mod ordered_map_view;
+
 /// The `SetView` implements a set with ordered entries.
 pub mod set_view;
 
@@ -45,6 +51,9 @@ pub mod key_value_store_view;
 /// Wrapping a view to compute a hash.
 pub mod hashable_wrapper;
 
+/// Inclusion proofs for the data of hashable views.
+pub mod proof;
+
 /// The minimum value for the view tags. Values in `0..MIN_VIEW_TAG` are used for other purposes.
 pub const MIN_VIEW_TAG: u8 = 1;
 
@@ -0,0 +1,1396 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! The `OrderedMapView` implements a map with order statistics: the rank of a key,
+//! the key of a given rank and the number of keys in a range are obtained in a
+//! logarithmic number of round-trips to storage.
+//!
+//! Besides the entries of the map, the view stores a B-tree of the keys in which every
+//! internal node records the number of keys in each of its subtrees. There are 3
+//! different variants:
+//! * The [`ByteOrderedMapView`][class1] whose keys are the `Vec<u8>` and the values are a serializable type `V`.
+//!   The ordering of the entries is via the lexicographic order of the keys.
+//! * The [`OrderedMapView`][class2] whose keys are a serializable type `K` and the value a serializable type `V`.
+//!   The ordering is via the order of the BCS serialized keys.
+//! * The [`CustomOrderedMapView`][class3] whose keys are a serializable type `K` and the value a serializable type `V`.
+//!   The ordering is via the order of the custom serialized keys.
+//!
+//! [class1]: ByteOrderedMapView
+//! [class2]: OrderedMapView
+//! [class3]: CustomOrderedMapView
+
+use std::{
+    borrow::Borrow,
+    marker::PhantomData,
+    ops::{Range, RangeBounds},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    batch::Batch,
+    common::{CustomSerialize, HasherOutput},
+    context::{BaseKey, Context},
+    hashable_wrapper::WrappedHashableContainerView,
+    map_view::{serialize_range, ByteMapView},
+    register_view::RegisterView,
+    store::{Direction, RangeQuery, ReadableKeyValueStore as _},
+    views::{ClonableView, HashableView, Hasher, ReplaceContext, View, ViewError, MIN_VIEW_TAG},
+};
+
+/// The maximal number of keys in a leaf of the tree, and of children of an internal node.
+const MAX_NODE_SIZE: usize = 32;
+
+/// Key tags to create the sub-keys of an `OrderedMapView` on top of the base key.
+#[repr(u8)]
+enum KeyTag {
+    /// Prefix for the metadata of the tree.
+    Metadata = MIN_VIEW_TAG,
+    /// Prefix for the nodes of the tree.
+    Node,
+    /// Prefix for the entries of the map.
+    Map,
+}
+
+/// The metadata of the tree of keys.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct TreeMetadata {
+    /// The identifier of the root node, if the map is not empty.
+    root: Option<u64>,
+    /// The identifier of the next node to be created.
+    next_id: u64,
+    /// The number of keys in the tree.
+    count: u64,
+}
+
+/// A node of the tree of keys.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum Node {
+    /// A leaf, with its keys in increasing order.
+    Leaf(Vec<Vec<u8>>),
+    /// An internal node, with its children in increasing order of keys.
+    Internal(Vec<Child>),
+}
+
+/// A child of an internal node of the tree.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Child {
+    /// The keys of the child are at least `separator`, and the ones of the previous
+    /// child are smaller. The separator of the first child of a node is not used.
+    separator: Vec<u8>,
+    /// The identifier of the child node.
+    id: u64,
+    /// The number of keys in the subtree of the child.
+    count: u64,
+}
+
+/// Returns the position of the child whose subtree may contain `key`.
+fn child_position(children: &[Child], key: &[u8]) -> usize {
+    children
+        .get(1..)
+        .unwrap_or_default()
+        .partition_point(|child| child.separator.as_slice() <= key)
+}
+
+/// Returns the key of a node in the map of nodes.
+fn node_key(id: u64) -> Vec<u8> {
+    id.to_be_bytes().to_vec()
+}
+
+/// A map view whose keys are byte vectors, supporting order statistics.
+///
+/// Removing keys does not merge the nodes of the tree, but the nodes that become
+/// empty are deleted and the height of the tree decreases when the root has a single
+/// child.
+#[derive(Debug)]
+pub struct ByteOrderedMapView<C, V> {
+    context: C,
+    /// The root, the number of keys and the next node identifier of the tree.
+    metadata: RegisterView<C, TreeMetadata>,
+    /// The nodes of the tree, indexed by their identifiers.
+    nodes: ByteMapView<C, Node>,
+    /// The entries of the map.
+    map: ByteMapView<C, V>,
+}
+
+impl<C, C2, V> ReplaceContext<C2> for ByteOrderedMapView<C, V>
+where
+    C: Context,
+    C2: Context,
+    V: Send + Sync + Serialize + Clone,
+{
+    type Target = ByteOrderedMapView<C2, V>;
+
+    async fn with_context(
+        &mut self,
+        ctx: impl FnOnce(&Self::Context) -> C2 + Clone,
+    ) -> Self::Target {
+        ByteOrderedMapView {
+            context: ctx.clone()(&self.context),
+            metadata: self.metadata.with_context(ctx.clone()).await,
+            nodes: self.nodes.with_context(ctx.clone()).await,
+            map: self.map.with_context(ctx).await,
+        }
+    }
+}
+
+impl<C, V> View for ByteOrderedMapView<C, V>
+where
+    C: Context,
+    V: Send + Sync + Serialize,
+{
+    const NUM_INIT_KEYS: usize = 1;
+
+    type Context = C;
+
+    fn context(&self) -> &C {
+        &self.context
+    }
+
+    fn pre_load(context: &C) -> Result<Vec<Vec<u8>>, ViewError> {
+        Ok(vec![context.base_key().base_tag(KeyTag::Metadata as u8)])
+    }
+
+    fn post_load(context: C, values: &[Option<Vec<u8>>]) -> Result<Self, ViewError> {
+        let metadata_key = context.base_key().base_tag(KeyTag::Metadata as u8);
+        let metadata = RegisterView::post_load(context.clone_with_base_key(metadata_key), values)?;
+        let nodes_key = context.base_key().base_tag(KeyTag::Node as u8);
+        let nodes = ByteMapView::post_load(context.clone_with_base_key(nodes_key), &[])?;
+        let map_key = context.base_key().base_tag(KeyTag::Map as u8);
+        let map = ByteMapView::post_load(context.clone_with_base_key(map_key), &[])?;
+        Ok(Self {
+            context,
+            metadata,
+            nodes,
+            map,
+        })
+    }
+
+    async fn load(context: C) -> Result<Self, ViewError> {
+        let keys = Self::pre_load(&context)?;
+        let values = context.store().read_multi_values_bytes(keys).await?;
+        Self::post_load(context, &values)
+    }
+
+    fn rollback(&mut self) {
+        self.metadata.rollback();
+        self.nodes.rollback();
+        self.map.rollback();
+    }
+
+    async fn has_pending_changes(&self) -> bool {
+        self.metadata.has_pending_changes().await
+            || self.nodes.has_pending_changes().await
+            || self.map.has_pending_changes().await
+    }
+
+    fn flush(&mut self, batch: &mut Batch) -> Result<bool, ViewError> {
+        let delete_metadata = self.metadata.flush(batch)?;
+        let delete_nodes = self.nodes.flush(batch)?;
+        let delete_map = self.map.flush(batch)?;
+        Ok(delete_metadata && delete_nodes && delete_map)
+    }
+
+    fn clear(&mut self) {
+        self.metadata.clear();
+        self.nodes.clear();
+        self.map.clear();
+    }
+}
+
+impl<C, V> ClonableView for ByteOrderedMapView<C, V>
+where
+    C: Context,
+    V: Clone + Send + Sync + Serialize,
+{
+    fn clone_unchecked(&mut self) -> Self {
+        ByteOrderedMapView {
+            context: self.context.clone(),
+            metadata: self.metadata.clone_unchecked(),
+            nodes: self.nodes.clone_unchecked(),
+            map: self.map.clone_unchecked(),
+        }
+    }
+}
+
+impl<C, V> ByteOrderedMapView<C, V>
+where
+    C: Context,
+    V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    /// Inserts or resets the value of a key.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::ordered_map_view::ByteOrderedMapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map = ByteOrderedMapView::load(context).await.unwrap();
+    /// map.insert(vec![0, 1], String::from("Hello")).await.unwrap();
+    /// assert_eq!(map.get(&[0, 1]).await.unwrap(), Some(String::from("Hello")));
+    /// # })
+    /// ```
+    pub async fn insert(&mut self, short_key: Vec<u8>, value: V) -> Result<(), ViewError> {
+        if !self.map.contains_key(&short_key).await? {
+            self.insert_key(short_key.clone()).await?;
+        }
+        self.map.insert(short_key, value);
+        Ok(())
+    }
+
+    /// Removes the value of a key. If absent then the operation does nothing.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::ordered_map_view::ByteOrderedMapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map = ByteOrderedMapView::load(context).await.unwrap();
+    /// map.insert(vec![0, 1], String::from("Hello")).await.unwrap();
+    /// map.remove(vec![0, 1]).await.unwrap();
+    /// assert_eq!(map.get(&[0, 1]).await.unwrap(), None);
+    /// assert_eq!(map.count(), 0);
+    /// # })
+    /// ```
+    pub async fn remove(&mut self, short_key: Vec<u8>) -> Result<(), ViewError> {
+        if self.map.contains_key(&short_key).await? {
+            self.remove_key(&short_key).await?;
+            self.map.remove(short_key);
+        }
+        Ok(())
+    }
+
+    /// Obtains the extra data.
+    pub fn extra(&self) -> &C::Extra {
+        self.context.extra()
+    }
+
+    /// Returns whether the map contains a key.
+    pub async fn contains_key(&self, short_key: &[u8]) -> Result<bool, ViewError> {
+        self.map.contains_key(short_key).await
+    }
+
+    /// Reads the value of a key, if any.
+    pub async fn get(&self, short_key: &[u8]) -> Result<Option<V>, ViewError> {
+        self.map.get(short_key).await
+    }
+
+    /// Returns the number of entries in the map. This does not access storage.
+    pub fn count(&self) -> usize {
+        self.metadata.get().count as usize
+    }
+
+    /// Returns the number of keys of the map that are smaller than `short_key`.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::ordered_map_view::ByteOrderedMapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map = ByteOrderedMapView::load(context).await.unwrap();
+    /// map.insert(vec![1], String::from("Hello")).await.unwrap();
+    /// map.insert(vec![3], String::from("Bonjour")).await.unwrap();
+    /// assert_eq!(map.rank(&[0]).await.unwrap(), 0);
+    /// assert_eq!(map.rank(&[3]).await.unwrap(), 1);
+    /// assert_eq!(map.rank(&[4]).await.unwrap(), 2);
+    /// # })
+    /// ```
+    pub async fn rank(&self, short_key: &[u8]) -> Result<usize, ViewError> {
+        let Some(mut id) = self.metadata.get().root else {
+            return Ok(0);
+        };
+        let mut rank = 0;
+        loop {
+            match self.read_node(id).await? {
+                Node::Internal(children) => {
+                    let position = child_position(&children, short_key);
+                    rank += children[..position]
+                        .iter()
+                        .map(|child| child.count)
+                        .sum::<u64>();
+                    id = children
+                        .get(position)
+                        .ok_or(ViewError::InconsistentEntries)?
+                        .id;
+                }
+                Node::Leaf(keys) => {
+                    let position = keys.partition_point(|key| key.as_slice() < short_key);
+                    return Ok(rank as usize + position);
+                }
+            }
+        }
+    }
+
+    /// Returns the entry of rank `index`, i.e. the one with `index` smaller keys, if any.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::ordered_map_view::ByteOrderedMapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map = ByteOrderedMapView::load(context).await.unwrap();
+    /// map.insert(vec![3], String::from("Bonjour")).await.unwrap();
+    /// map.insert(vec![1], String::from("Hello")).await.unwrap();
+    /// assert_eq!(
+    ///     map.nth(1).await.unwrap(),
+    ///     Some((vec![3], String::from("Bonjour")))
+    /// );
+    /// assert_eq!(map.nth(2).await.unwrap(), None);
+    /// # })
+    /// ```
+    pub async fn nth(&self, index: usize) -> Result<Option<(Vec<u8>, V)>, ViewError> {
+        let Some(short_key) = self.nth_key(index).await? else {
+            return Ok(None);
+        };
+        let value = self
+            .map
+            .get(&short_key)
+            .await?
+            .ok_or(ViewError::InconsistentEntries)?;
+        Ok(Some((short_key, value)))
+    }
+
+    /// Returns the number of keys of the map within `range`.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::ordered_map_view::ByteOrderedMapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map = ByteOrderedMapView::load(context).await.unwrap();
+    /// map.insert(vec![1], String::from("Hello")).await.unwrap();
+    /// map.insert(vec![3], String::from("Bonjour")).await.unwrap();
+    /// map.insert(vec![5], String::from("Hallo")).await.unwrap();
+    /// assert_eq!(map.count_range(vec![1]..=vec![3]).await.unwrap(), 2);
+    /// assert_eq!(map.count_range(vec![2]..).await.unwrap(), 2);
+    /// # })
+    /// ```
+    pub async fn count_range(&self, range: impl RangeBounds<Vec<u8>>) -> Result<usize, ViewError> {
+        self.count_query(&RangeQuery::from_bounds(range)).await
+    }
+
+    /// Returns the entries whose keys are within the bounds of `query`, in its direction
+    /// and up to its limit. This is a single range query to storage.
+    pub async fn key_values_by_range(
+        &self,
+        query: &RangeQuery,
+    ) -> Result<Vec<(Vec<u8>, V)>, ViewError> {
+        self.map.key_values_by_range(query).await
+    }
+
+    /// Returns the entries whose ranks are within `ranks`, in increasing order.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::ordered_map_view::ByteOrderedMapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map = ByteOrderedMapView::load(context).await.unwrap();
+    /// map.insert(vec![1], String::from("Hello")).await.unwrap();
+    /// map.insert(vec![3], String::from("Bonjour")).await.unwrap();
+    /// map.insert(vec![5], String::from("Hallo")).await.unwrap();
+    /// let key_values = map.key_values_by_rank(1..5).await.unwrap();
+    /// assert_eq!(
+    ///     key_values,
+    ///     vec![
+    ///         (vec![3], String::from("Bonjour")),
+    ///         (vec![5], String::from("Hallo"))
+    ///     ]
+    /// );
+    /// # })
+    /// ```
+    pub async fn key_values_by_rank(
+        &self,
+        ranks: Range<usize>,
+    ) -> Result<Vec<(Vec<u8>, V)>, ViewError> {
+        if ranks.is_empty() {
+            return Ok(Vec::new());
+        }
+        let Some(start) = self.nth_key(ranks.start).await? else {
+            return Ok(Vec::new());
+        };
+        let query = RangeQuery {
+            start: Some(start),
+            end: None,
+            limit: Some(ranks.len()),
+            direction: Direction::Forward,
+        };
+        self.map.key_values_by_range(&query).await
+    }
+
+    /// Returns the number of keys within the bounds of `query`, ignoring its limit and
+    /// direction.
+    async fn count_query(&self, query: &RangeQuery) -> Result<usize, ViewError> {
+        let start = match &query.start {
+            Some(start) => self.rank(start).await?,
+            None => 0,
+        };
+        let end = match &query.end {
+            Some(end) => self.rank(end).await?,
+            None => self.count(),
+        };
+        Ok(end.saturating_sub(start))
+    }
+
+    /// Returns the key of rank `index`, if any.
+    async fn nth_key(&self, index: usize) -> Result<Option<Vec<u8>>, ViewError> {
+        let Some(mut id) = self.metadata.get().root else {
+            return Ok(None);
+        };
+        let mut index = index as u64;
+        loop {
+            match self.read_node(id).await? {
+                Node::Internal(children) => {
+                    let mut next_id = None;
+                    for child in children {
+                        if index < child.count {
+                            next_id = Some(child.id);
+                            break;
+                        }
+                        index -= child.count;
+                    }
+                    match next_id {
+                        Some(child_id) => id = child_id,
+                        None => return Ok(None),
+                    }
+                }
+                Node::Leaf(mut keys) => {
+                    let index = index as usize;
+                    return Ok((index < keys.len()).then(|| keys.swap_remove(index)));
+                }
+            }
+        }
+    }
+
+    /// Reads a node of the tree.
+    async fn read_node(&self, id: u64) -> Result<Node, ViewError> {
+        self.nodes
+            .get(&node_key(id))
+            .await?
+            .ok_or(ViewError::InconsistentEntries)
+    }
+
+    /// Obtains a mutable reference to a node of the tree.
+    async fn node_mut(&mut self, id: u64) -> Result<&mut Node, ViewError> {
+        self.nodes
+            .get_mut(&node_key(id))
+            .await?
+            .ok_or(ViewError::InconsistentEntries)
+    }
+
+    /// Obtains a mutable reference to the children of an internal node of the tree.
+    async fn children_mut(&mut self, id: u64) -> Result<&mut Vec<Child>, ViewError> {
+        match self.node_mut(id).await? {
+            Node::Internal(children) => Ok(children),
+            Node::Leaf(_) => Err(ViewError::InconsistentEntries),
+        }
+    }
+
+    /// Creates a node of the tree and returns its identifier.
+    fn create_node(&mut self, node: Node) -> u64 {
+        let metadata = self.metadata.get_mut();
+        let id = metadata.next_id;
+        metadata.next_id += 1;
+        self.nodes.insert(node_key(id), node);
+        id
+    }
+
+    /// Inserts a key that is not in the tree.
+    async fn insert_key(&mut self, short_key: Vec<u8>) -> Result<(), ViewError> {
+        self.metadata.get_mut().count += 1;
+        let Some(root) = self.metadata.get().root else {
+            let id = self.create_node(Node::Leaf(vec![short_key]));
+            self.metadata.get_mut().root = Some(id);
+            return Ok(());
+        };
+        // The internal nodes from the root to the leaf, with the positions of the
+        // children on the way.
+        let mut path = Vec::new();
+        let mut id = root;
+        loop {
+            match self.node_mut(id).await? {
+                Node::Internal(children) => {
+                    let position = child_position(children, &short_key);
+                    let child = children
+                        .get_mut(position)
+                        .ok_or(ViewError::InconsistentEntries)?;
+                    child.count += 1;
+                    path.push((id, position));
+                    id = child.id;
+                }
+                Node::Leaf(keys) => {
+                    let Err(position) = keys.binary_search(&short_key) else {
+                        return Err(ViewError::InconsistentEntries);
+                    };
+                    keys.insert(position, short_key);
+                    break;
+                }
+            }
+        }
+        // Splits the nodes that became too large, from the leaf upwards.
+        while let Some((left_count, right)) = self.split_node(id).await? {
+            match path.pop() {
+                Some((parent, position)) => {
+                    let children = self.children_mut(parent).await?;
+                    children[position].count -= right.count;
+                    children.insert(position + 1, right);
+                    id = parent;
+                }
+                None => {
+                    let left = Child {
+                        separator: Vec::new(),
+                        id,
+                        count: left_count,
+                    };
+                    let root = self.create_node(Node::Internal(vec![left, right]));
+                    self.metadata.get_mut().root = Some(root);
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Splits a node if it is too large. Returns the number of keys remaining in the node
+    /// and the new node holding the other half.
+    async fn split_node(&mut self, id: u64) -> Result<Option<(u64, Child)>, ViewError> {
+        let (left_count, right_count, separator, right) = match self.node_mut(id).await? {
+            Node::Leaf(keys) => {
+                if keys.len() <= MAX_NODE_SIZE {
+                    return Ok(None);
+                }
+                let right = keys.split_off(keys.len() / 2);
+                let separator = right[0].clone();
+                let left_count = keys.len() as u64;
+                let right_count = right.len() as u64;
+                (left_count, right_count, separator, Node::Leaf(right))
+            }
+            Node::Internal(children) => {
+                if children.len() <= MAX_NODE_SIZE {
+                    return Ok(None);
+                }
+                let right = children.split_off(children.len() / 2);
+                let separator = right[0].separator.clone();
+                let left_count = children.iter().map(|child| child.count).sum();
+                let right_count = right.iter().map(|child| child.count).sum();
+                (left_count, right_count, separator, Node::Internal(right))
+            }
+        };
+        let right_id = self.create_node(right);
+        let right = Child {
+            separator,
+            id: right_id,
+            count: right_count,
+        };
+        Ok(Some((left_count, right)))
+    }
+
+    /// Removes a key that is in the tree.
+    async fn remove_key(&mut self, short_key: &[u8]) -> Result<(), ViewError> {
+        let metadata = self.metadata.get_mut();
+        metadata.count = metadata
+            .count
+            .checked_sub(1)
+            .ok_or(ViewError::InconsistentEntries)?;
+        let mut id = metadata.root.ok_or(ViewError::InconsistentEntries)?;
+        let mut path = Vec::new();
+        let mut is_empty = loop {
+            match self.node_mut(id).await? {
+                Node::Internal(children) => {
+                    let position = child_position(children, short_key);
+                    let child = children
+                        .get_mut(position)
+                        .ok_or(ViewError::InconsistentEntries)?;
+                    child.count = child
+                        .count
+                        .checked_sub(1)
+                        .ok_or(ViewError::InconsistentEntries)?;
+                    path.push((id, position));
+                    id = child.id;
+                }
+                Node::Leaf(keys) => {
+                    let position = keys
+                        .binary_search_by(|key| key.as_slice().cmp(short_key))
+                        .map_err(|_| ViewError::InconsistentEntries)?;
+                    keys.remove(position);
+                    break keys.is_empty();
+                }
+            }
+        };
+        // Deletes the nodes that became empty, from the leaf upwards.
+        while is_empty {
+            self.nodes.remove(node_key(id));
+            match path.pop() {
+                Some((parent, position)) => {
+                    let children = self.children_mut(parent).await?;
+                    children.remove(position);
+                    is_empty = children.is_empty();
+                    id = parent;
+                }
+                None => {
+                    self.metadata.get_mut().root = None;
+                    return Ok(());
+                }
+            }
+        }
+        // Removes the roots with a single child.
+        while let Some(root) = self.metadata.get().root {
+            let Node::Internal(children) = self.node_mut(root).await? else {
+                break;
+            };
+            let [child] = children.as_slice() else {
+                break;
+            };
+            let child_id = child.id;
+            self.nodes.remove(node_key(root));
+            self.metadata.get_mut().root = Some(child_id);
+        }
+        Ok(())
+    }
+}
+
+impl<C, V> HashableView for ByteOrderedMapView<C, V>
+where
+    C: Context,
+    V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    type Hasher = sha3::Sha3_256;
+
+    // The tree is determined by the entries of the map and the history of the
+    // operations, so only the entries are hashed.
+    async fn hash_mut(&mut self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        self.map.hash_mut().await
+    }
+
+    async fn hash(&self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        self.map.hash().await
+    }
+}
+
+/// Type wrapping `ByteOrderedMapView` while memoizing the hash.
+pub type HashedByteOrderedMapView<C, V> =
+    WrappedHashableContainerView<C, ByteOrderedMapView<C, V>, HasherOutput>;
+
+/// An ordered map view whose keys have type `I`. The ordering of the entries is the one
+/// of the BCS serialized keys.
+#[derive(Debug)]
+pub struct OrderedMapView<C, I, V> {
+    map: ByteOrderedMapView<C, V>,
+    _phantom: PhantomData<I>,
+}
+
+impl<C, C2, I, V> ReplaceContext<C2> for OrderedMapView<C, I, V>
+where
+    C: Context,
+    C2: Context,
+    I: Send + Sync,
+    V: Send + Sync + Serialize + Clone,
+{
+    type Target = OrderedMapView<C2, I, V>;
+
+    async fn with_context(
+        &mut self,
+        ctx: impl FnOnce(&Self::Context) -> C2 + Clone,
+    ) -> Self::Target {
+        OrderedMapView {
+            map: self.map.with_context(ctx).await,
+            _phantom: self._phantom,
+        }
+    }
+}
+
+impl<C, I, V> View for OrderedMapView<C, I, V>
+where
+    C: Context,
+    I: Send + Sync,
+    V: Send + Sync + Serialize,
+{
+    const NUM_INIT_KEYS: usize = ByteOrderedMapView::<C, V>::NUM_INIT_KEYS;
+
+    type Context = C;
+
+    fn context(&self) -> &C {
+        self.map.context()
+    }
+
+    fn pre_load(context: &C) -> Result<Vec<Vec<u8>>, ViewError> {
+        ByteOrderedMapView::<C, V>::pre_load(context)
+    }
+
+    fn post_load(context: C, values: &[Option<Vec<u8>>]) -> Result<Self, ViewError> {
+        let map = ByteOrderedMapView::post_load(context, values)?;
+        Ok(OrderedMapView {
+            map,
+            _phantom: PhantomData,
+        })
+    }
+
+    async fn load(context: C) -> Result<Self, ViewError> {
+        let keys = Self::pre_load(&context)?;
+        let values = context.store().read_multi_values_bytes(keys).await?;
+        Self::post_load(context, &values)
+    }
+
+    fn rollback(&mut self) {
+        self.map.rollback()
+    }
+
+    async fn has_pending_changes(&self) -> bool {
+        self.map.has_pending_changes().await
+    }
+
+    fn flush(&mut self, batch: &mut Batch) -> Result<bool, ViewError> {
+        self.map.flush(batch)
+    }
+
+    fn clear(&mut self) {
+        self.map.clear()
+    }
+}
+
+impl<C, I, V> ClonableView for OrderedMapView<C, I, V>
+where
+    C: Context,
+    I: Send + Sync,
+    V: Clone + Send + Sync + Serialize,
+{
+    fn clone_unchecked(&mut self) -> Self {
+        OrderedMapView {
+            map: self.map.clone_unchecked(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<C, I, V> OrderedMapView<C, I, V>
+where
+    C: Context,
+    I: Serialize,
+    V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    /// Inserts or resets the value of an index.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::ordered_map_view::OrderedMapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map: OrderedMapView<_, u32, _> = OrderedMapView::load(context).await.unwrap();
+    /// map.insert(&(24 as u32), String::from("Hello")).await.unwrap();
+    /// assert_eq!(map.get(&(24 as u32)).await.unwrap(), Some(String::from("Hello")));
+    /// # })
+    /// ```
+    pub async fn insert<Q>(&mut self, index: &Q, value: V) -> Result<(), ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let short_key = BaseKey::derive_short_key(index)?;
+        self.map.insert(short_key, value).await
+    }
+
+    /// Removes the value of an index. If absent then the operation does nothing.
+    pub async fn remove<Q>(&mut self, index: &Q) -> Result<(), ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let short_key = BaseKey::derive_short_key(index)?;
+        self.map.remove(short_key).await
+    }
+
+    /// Obtains the extra data.
+    pub fn extra(&self) -> &C::Extra {
+        self.map.extra()
+    }
+
+    /// Returns whether the map contains an index.
+    pub async fn contains_key<Q>(&self, index: &Q) -> Result<bool, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let short_key = BaseKey::derive_short_key(index)?;
+        self.map.contains_key(&short_key).await
+    }
+
+    /// Reads the value of an index, if any.
+    pub async fn get<Q>(&self, index: &Q) -> Result<Option<V>, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let short_key = BaseKey::derive_short_key(index)?;
+        self.map.get(&short_key).await
+    }
+
+    /// Returns the number of entries in the map. This does not access storage.
+    pub fn count(&self) -> usize {
+        self.map.count()
+    }
+
+    /// Returns the number of indices of the map whose serialization is smaller than the
+    /// one of `index`.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::ordered_map_view::OrderedMapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map: OrderedMapView<_, String, _> = OrderedMapView::load(context).await.unwrap();
+    /// map.insert("Italian", String::from("Ciao")).await.unwrap();
+    /// map.insert("French", String::from("Bonjour")).await.unwrap();
+    /// assert_eq!(map.rank("German").await.unwrap(), 1);
+    /// # })
+    /// ```
+    pub async fn rank<Q>(&self, index: &Q) -> Result<usize, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let short_key = BaseKey::derive_short_key(index)?;
+        self.map.rank(&short_key).await
+    }
+
+    /// Returns the number of indices within `range`. The bounds are the ones of the
+    /// serialized indices.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::ordered_map_view::OrderedMapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map: OrderedMapView<_, String, _> = OrderedMapView::load(context).await.unwrap();
+    /// map.insert("Italian", String::from("Ciao")).await.unwrap();
+    /// map.insert("French", String::from("Bonjour")).await.unwrap();
+    /// map.insert("German", String::from("Hallo")).await.unwrap();
+    /// let range = "French".to_string()..="German".to_string();
+    /// assert_eq!(map.count_range(range).await.unwrap(), 2);
+    /// # })
+    /// ```
+    pub async fn count_range<R, Q>(&self, range: R) -> Result<usize, ViewError>
+    where
+        R: RangeBounds<Q>,
+        I: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let query = serialize_range(range, None, Direction::Forward, |index| {
+            Ok(BaseKey::derive_short_key(index)?)
+        })?;
+        self.map.count_query(&query).await
+    }
+}
+
+impl<C, I, V> OrderedMapView<C, I, V>
+where
+    C: Context,
+    I: Serialize + DeserializeOwned,
+    V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    /// Returns the entry of rank `index` in the order of the serialized indices, if any.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::ordered_map_view::OrderedMapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map: OrderedMapView<_, String, _> = OrderedMapView::load(context).await.unwrap();
+    /// map.insert("Italian", String::from("Ciao")).await.unwrap();
+    /// map.insert("French", String::from("Bonjour")).await.unwrap();
+    /// assert_eq!(
+    ///     map.nth(0).await.unwrap(),
+    ///     Some(("French".to_string(), "Bonjour".to_string()))
+    /// );
+    /// # })
+    /// ```
+    pub async fn nth(&self, index: usize) -> Result<Option<(I, V)>, ViewError> {
+        let Some((short_key, value)) = self.map.nth(index).await? else {
+            return Ok(None);
+        };
+        Ok(Some((BaseKey::deserialize_value(&short_key)?, value)))
+    }
+
+    /// Obtains the `(index,value)` pairs whose indices are within `range`, in the given
+    /// direction and up to `limit` entries. The bounds and the order are the ones of the
+    /// serialized indices.
+    pub async fn index_values_by_range<R, Q>(
+        &self,
+        range: R,
+        limit: Option<usize>,
+        direction: Direction,
+    ) -> Result<Vec<(I, V)>, ViewError>
+    where
+        R: RangeBounds<Q>,
+        I: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let query = serialize_range(range, limit, direction, |index| {
+            Ok(BaseKey::derive_short_key(index)?)
+        })?;
+        let key_values = self.map.key_values_by_range(&query).await?;
+        key_values
+            .into_iter()
+            .map(|(key, value)| Ok((BaseKey::deserialize_value(&key)?, value)))
+            .collect()
+    }
+
+    /// Obtains the `(index,value)` pairs whose ranks are within `ranks`, in the order of
+    /// the serialized indices.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::ordered_map_view::OrderedMapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map: OrderedMapView<_, String, _> = OrderedMapView::load(context).await.unwrap();
+    /// map.insert("Italian", String::from("Ciao")).await.unwrap();
+    /// map.insert("French", String::from("Bonjour")).await.unwrap();
+    /// map.insert("German", String::from("Hallo")).await.unwrap();
+    /// let index_values = map.index_values_by_rank(1..2).await.unwrap();
+    /// assert_eq!(
+    ///     index_values,
+    ///     vec![("German".to_string(), "Hallo".to_string())]
+    /// );
+    /// # })
+    /// ```
+    pub async fn index_values_by_rank(
+        &self,
+        ranks: Range<usize>,
+    ) -> Result<Vec<(I, V)>, ViewError> {
+        let key_values = self.map.key_values_by_rank(ranks).await?;
+        key_values
+            .into_iter()
+            .map(|(key, value)| Ok((BaseKey::deserialize_value(&key)?, value)))
+            .collect()
+    }
+}
+
+impl<C, I, V> HashableView for OrderedMapView<C, I, V>
+where
+    C: Context,
+    I: Send + Sync + Serialize,
+    V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    type Hasher = sha3::Sha3_256;
+
+    async fn hash_mut(&mut self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        self.map.hash_mut().await
+    }
+
+    async fn hash(&self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        self.map.hash().await
+    }
+}
+
+/// Type wrapping `OrderedMapView` while memoizing the hash.
+pub type HashedOrderedMapView<C, I, V> =
+    WrappedHashableContainerView<C, OrderedMapView<C, I, V>, HasherOutput>;
+
+/// An ordered map view whose keys have type `I`. The ordering of the entries is the one
+/// of the custom serialized keys.
+#[derive(Debug)]
+pub struct CustomOrderedMapView<C, I, V> {
+    map: ByteOrderedMapView<C, V>,
+    _phantom: PhantomData<I>,
+}
+
+impl<C, C2, I, V> ReplaceContext<C2> for CustomOrderedMapView<C, I, V>
+where
+    C: Context,
+    C2: Context,
+    I: Send + Sync,
+    V: Send + Sync + Serialize + Clone,
+{
+    type Target = CustomOrderedMapView<C2, I, V>;
+
+    async fn with_context(
+        &mut self,
+        ctx: impl FnOnce(&Self::Context) -> C2 + Clone,
+    ) -> Self::Target {
+        CustomOrderedMapView {
+            map: self.map.with_context(ctx).await,
+            _phantom: self._phantom,
+        }
+    }
+}
+
+impl<C, I, V> View for CustomOrderedMapView<C, I, V>
+where
+    C: Context,
+    I: Send + Sync,
+    V: Send + Sync + Serialize,
+{
+    const NUM_INIT_KEYS: usize = ByteOrderedMapView::<C, V>::NUM_INIT_KEYS;
+
+    type Context = C;
+
+    fn context(&self) -> &C {
+        self.map.context()
+    }
+
+    fn pre_load(context: &C) -> Result<Vec<Vec<u8>>, ViewError> {
+        ByteOrderedMapView::<C, V>::pre_load(context)
+    }
+
+    fn post_load(context: C, values: &[Option<Vec<u8>>]) -> Result<Self, ViewError> {
+        let map = ByteOrderedMapView::post_load(context, values)?;
+        Ok(CustomOrderedMapView {
+            map,
+            _phantom: PhantomData,
+        })
+    }
+
+    async fn load(context: C) -> Result<Self, ViewError> {
+        let keys = Self::pre_load(&context)?;
+        let values = context.store().read_multi_values_bytes(keys).await?;
+        Self::post_load(context, &values)
+    }
+
+    fn rollback(&mut self) {
+        self.map.rollback()
+    }
+
+    async fn has_pending_changes(&self) -> bool {
+        self.map.has_pending_changes().await
+    }
+
+    fn flush(&mut self, batch: &mut Batch) -> Result<bool, ViewError> {
+        self.map.flush(batch)
+    }
+
+    fn clear(&mut self) {
+        self.map.clear()
+    }
+}
+
+impl<C, I, V> ClonableView for CustomOrderedMapView<C, I, V>
+where
+    C: Context,
+    I: Send + Sync,
+    V: Clone + Send + Sync + Serialize,
+{
+    fn clone_unchecked(&mut self) -> Self {
+        CustomOrderedMapView {
+            map: self.map.clone_unchecked(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<C, I, V> CustomOrderedMapView<C, I, V>
+where
+    C: Context,
+    I: CustomSerialize,
+    V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    /// Inserts or resets the value of an index.
+    pub async fn insert(&mut self, index: &I, value: V) -> Result<(), ViewError> {
+        let short_key = index.to_custom_bytes()?;
+        self.map.insert(short_key, value).await
+    }
+
+    /// Removes the value of an index. If absent then the operation does nothing.
+    pub async fn remove(&mut self, index: &I) -> Result<(), ViewError> {
+        let short_key = index.to_custom_bytes()?;
+        self.map.remove(short_key).await
+    }
+
+    /// Obtains the extra data.
+    pub fn extra(&self) -> &C::Extra {
+        self.map.extra()
+    }
+
+    /// Returns whether the map contains an index.
+    pub async fn contains_key(&self, index: &I) -> Result<bool, ViewError> {
+        let short_key = index.to_custom_bytes()?;
+        self.map.contains_key(&short_key).await
+    }
+
+    /// Reads the value of an index, if any.
+    pub async fn get(&self, index: &I) -> Result<Option<V>, ViewError> {
+        let short_key = index.to_custom_bytes()?;
+        self.map.get(&short_key).await
+    }
+
+    /// Returns the number of entries in the map. This does not access storage.
+    pub fn count(&self) -> usize {
+        self.map.count()
+    }
+
+    /// Returns the number of indices of the map that are smaller than `index` in the
+    /// order of the custom serialization.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::ordered_map_view::CustomOrderedMapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map: CustomOrderedMapView<_, u128, String> =
+    ///     CustomOrderedMapView::load(context).await.unwrap();
+    /// map.insert(&(300 as u128), String::from("Hallo")).await.unwrap();
+    /// map.insert(&(37 as u128), String::from("Bonjour")).await.unwrap();
+    /// assert_eq!(map.rank(&(100 as u128)).await.unwrap(), 1);
+    /// # })
+    /// ```
+    pub async fn rank(&self, index: &I) -> Result<usize, ViewError> {
+        let short_key = index.to_custom_bytes()?;
+        self.map.rank(&short_key).await
+    }
+
+    /// Returns the entry of rank `index` in the order of the custom serialization, if any.
+    pub async fn nth(&self, index: usize) -> Result<Option<(I, V)>, ViewError> {
+        let Some((short_key, value)) = self.map.nth(index).await? else {
+            return Ok(None);
+        };
+        Ok(Some((I::from_custom_bytes(&short_key)?, value)))
+    }
+
+    /// Returns the number of indices within `range`. The bounds are the ones of the
+    /// custom serialization.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::ordered_map_view::CustomOrderedMapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map: CustomOrderedMapView<_, u128, String> =
+    ///     CustomOrderedMapView::load(context).await.unwrap();
+    /// map.insert(&(24 as u128), String::from("Ciao")).await.unwrap();
+    /// map.insert(&(37 as u128), String::from("Bonjour")).await.unwrap();
+    /// map.insert(&(300 as u128), String::from("Hallo")).await.unwrap();
+    /// assert_eq!(map.count_range(30..).await.unwrap(), 2);
+    /// # })
+    /// ```
+    pub async fn count_range<R>(&self, range: R) -> Result<usize, ViewError>
+    where
+        R: RangeBounds<I>,
+    {
+        let query = serialize_range(range, None, Direction::Forward, I::to_custom_bytes)?;
+        self.map.count_query(&query).await
+    }
+
+    /// Obtains the `(index,value)` pairs whose indices are within `range`, in the given
+    /// direction and up to `limit` entries. The bounds and the order are the ones of the
+    /// custom serialization.
+    pub async fn index_values_by_range<R>(
+        &self,
+        range: R,
+        limit: Option<usize>,
+        direction: Direction,
+    ) -> Result<Vec<(I, V)>, ViewError>
+    where
+        R: RangeBounds<I>,
+    {
+        let query = serialize_range(range, limit, direction, I::to_custom_bytes)?;
+        let key_values = self.map.key_values_by_range(&query).await?;
+        key_values
+            .into_iter()
+            .map(|(key, value)| Ok((I::from_custom_bytes(&key)?, value)))
+            .collect()
+    }
+
+    /// Obtains the `(index,value)` pairs whose ranks are within `ranks`, in the order of
+    /// the custom serialization.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::ordered_map_view::CustomOrderedMapView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut map: CustomOrderedMapView<_, u128, String> =
+    ///     CustomOrderedMapView::load(context).await.unwrap();
+    /// map.insert(&(24 as u128), String::from("Ciao")).await.unwrap();
+    /// map.insert(&(37 as u128), String::from("Bonjour")).await.unwrap();
+    /// map.insert(&(300 as u128), String::from("Hallo")).await.unwrap();
+    /// let index_values = map.index_values_by_rank(0..2).await.unwrap();
+    /// assert_eq!(
+    ///     index_values,
+    ///     vec![
+    ///         (24 as u128, String::from("Ciao")),
+    ///         (37 as u128, String::from("Bonjour"))
+    ///     ]
+    /// );
+    /// # })
+    /// ```
+    pub async fn index_values_by_rank(
+        &self,
+        ranks: Range<usize>,
+    ) -> Result<Vec<(I, V)>, ViewError> {
+        let key_values = self.map.key_values_by_rank(ranks).await?;
+        key_values
+            .into_iter()
+            .map(|(key, value)| Ok((I::from_custom_bytes(&key)?, value)))
+            .collect()
+    }
+}
+
+impl<C, I, V> HashableView for CustomOrderedMapView<C, I, V>
+where
+    C: Context,
+    I: Send + Sync + CustomSerialize,
+    V: Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    type Hasher = sha3::Sha3_256;
+
+    async fn hash_mut(&mut self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        self.map.hash_mut().await
+    }
+
+    async fn hash(&self) -> Result<<Self::Hasher as Hasher>::Output, ViewError> {
+        self.map.hash().await
+    }
+}
+
+/// Type wrapping `CustomOrderedMapView` while memoizing the hash.
+pub type HashedCustomOrderedMapView<C, I, V> =
+    WrappedHashableContainerView<C, CustomOrderedMapView<C, I, V>, HasherOutput>;
+
+#[cfg(with_graphql)]
+mod graphql {
+    use std::borrow::Cow;
+
+    use super::{CustomOrderedMapView, OrderedMapView};
+    use crate::{
+        context::Context,
+        graphql::{hash_name, mangle, Entry},
+    };
+
+    impl<C: Send + Sync, I: async_graphql::OutputType, V: async_graphql::OutputType>
+        async_graphql::TypeName for OrderedMapView<C, I, V>
+    {
+        fn type_name() -> Cow<'static, str> {
+            format!(
+                "OrderedMapView_{}_{}_{:08x}",
+                mangle(I::type_name()),
+                mangle(V::type_name()),
+                hash_name::<(I, V)>(),
+            )
+            .into()
+        }
+    }
+
+    #[async_graphql::Object(cache_control(no_cache), name_type)]
+    impl<C, I, V> OrderedMapView<C, I, V>
+    where
+        C: Context,
+        I: async_graphql::OutputType
+            + async_graphql::InputType
+            + serde::ser::Serialize
+            + serde::de::DeserializeOwned
+            + std::fmt::Debug
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+        V: async_graphql::OutputType
+            + serde::ser::Serialize
+            + serde::de::DeserializeOwned
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+    {
+        #[graphql(derived(name = "count"))]
+        async fn count_(&self) -> Result<u32, async_graphql::Error> {
+            Ok(self.count() as u32)
+        }
+
+        async fn entry(&self, key: I) -> Result<Entry<I, Option<V>>, async_graphql::Error> {
+            Ok(Entry {
+                value: self.get(&key).await?,
+                key,
+            })
+        }
+
+        /// The number of keys smaller than `key`.
+        #[graphql(derived(name = "rank"))]
+        async fn rank_(&self, key: I) -> Result<u32, async_graphql::Error> {
+            Ok(self.rank(&key).await? as u32)
+        }
+
+        /// The entries whose ranks are at least `start`, up to `count` entries.
+        async fn entries_by_rank(
+            &self,
+            start: u32,
+            count: u32,
+        ) -> Result<Vec<Entry<I, V>>, async_graphql::Error> {
+            let start = start as usize;
+            let index_values = self
+                .index_values_by_rank(start..start.saturating_add(count as usize))
+                .await?;
+            Ok(index_values
+                .into_iter()
+                .map(|(key, value)| Entry { key, value })
+                .collect())
+        }
+    }
+
+    impl<C: Send + Sync, I: async_graphql::OutputType, V: async_graphql::OutputType>
+        async_graphql::TypeName for CustomOrderedMapView<C, I, V>
+    {
+        fn type_name() -> Cow<'static, str> {
+            format!(
+                "CustomOrderedMapView_{}_{}_{:08x}",
+                mangle(I::type_name()),
+                mangle(V::type_name()),
+                hash_name::<(I, V)>(),
+            )
+            .into()
+        }
+    }
+
+    #[async_graphql::Object(cache_control(no_cache), name_type)]
+    impl<C, I, V> CustomOrderedMapView<C, I, V>
+    where
+        C: Context,
+        I: async_graphql::OutputType
+            + async_graphql::InputType
+            + crate::common::CustomSerialize
+            + std::fmt::Debug
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+        V: async_graphql::OutputType
+            + serde::ser::Serialize
+            + serde::de::DeserializeOwned
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+    {
+        #[graphql(derived(name = "count"))]
+        async fn count_(&self) -> Result<u32, async_graphql::Error> {
+            Ok(self.count() as u32)
+        }
+
+        async fn entry(&self, key: I) -> Result<Entry<I, Option<V>>, async_graphql::Error> {
+            Ok(Entry {
+                value: self.get(&key).await?,
+                key,
+            })
+        }
+
+        /// The number of keys smaller than `key`.
+        #[graphql(derived(name = "rank"))]
+        async fn rank_(&self, key: I) -> Result<u32, async_graphql::Error> {
+            Ok(self.rank(&key).await? as u32)
+        }
+
+        /// The entries whose ranks are at least `start`, up to `count` entries.
+        async fn entries_by_rank(
+            &self,
+            start: u32,
+            count: u32,
+        ) -> Result<Vec<Entry<I, V>>, async_graphql::Error> {
+            let start = start as usize;
+            let index_values = self
+                .index_values_by_rank(start..start.saturating_add(count as usize))
+                .await?;
+            Ok(index_values
+                .into_iter()
+                .map(|(key, value)| Entry { key, value })
+                .collect())
+        }
+    }
+}
@@ -0,0 +1,516 @@
+// Copyright (c) Zefchain Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Inclusion proofs for the values of a [`HashableView`].
+//!
+//! A [`ViewProof`] consists of a leaf, which holds the data of a register, a map or a
+//! log/queue, and of the steps needed to recompute the hashes of the enclosing views
+//! up to the root. Verifying a proof recomputes the root hash exactly as
+//! [`HashableView::hash`] does, so a proof can be checked against the hash of a view
+//! (or, with [`ViewProof::verify_crypto_hash`], against its crypto-hash) without
+//! accessing the storage. The verifier also passes the [`ProofPath`] it expects, i.e.
+//! the fields, the collection entries and the position in the leaf that the proof must
+//! be about, so that a valid proof of some other data is rejected.
+//!
+//! The proofs are not succinct: the views hash all their entries in sequence rather than
+//! in a Merkle tree, so a map leaf carries all the entries of the map, a log or queue
+//! leaf all of its elements, and a collection step the keys and hashes of all the other
+//! entries. The size of a proof is thus linear in the size of the views along its path.
+//!
+//! Collections hash each key with a length prefix, followed by the hash of its entry, and
+//! structs hash the hashes of their fields in order, so the verifier rejects any hash that
+//! doesn't have the size of a [`CryptoHash`]. Maps, however, hash their keys and values
+//! without length prefixes. A verified proof is therefore only meaningful if the keys and
+//! values of a map leaf are self-delimiting, which is the case for BCS-serialized types.
+//! The typed accessors [`ViewProof::register_value`], [`ViewProof::map_entry`] and
+//! [`ViewProof::sequence_element`] check this by decoding every entry of the leaf strictly.
+
+use linera_base::crypto::CryptoHash;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    common::HasherOutput,
+    views::{CryptoHashView, HashableView, Hasher as _},
+    ViewError,
+};
+
+/// The size in bytes of the hashes of the views.
+const HASH_SIZE: usize = std::mem::size_of::<CryptoHash>();
+
+/// An error when verifying or decoding a [`ViewProof`].
+#[derive(Debug, Error)]
+pub enum ProofError {
+    /// The hash computed from the proof differs from the expected one.
+    #[error("the proof does not match the expected hash")]
+    HashMismatch,
+
+    /// The entries of a map or collection are not strictly ordered by key.
+    #[error("the entries of the proof are not strictly sorted by key")]
+    UnsortedEntries,
+
+    /// The proved key of a collection is also listed among the other entries.
+    #[error("the proved key is listed twice in a collection entry step")]
+    DuplicateKey,
+
+    /// A hash in the proof does not have the size of a hash.
+    #[error("a hash in the proof has {0} bytes instead of 32")]
+    InvalidHashSize(usize),
+
+    /// An index in the proof is out of range.
+    #[error("the index {index} is out of range for a length of {len}")]
+    IndexOutOfRange {
+        /// The index used in the proof.
+        index: usize,
+        /// The number of elements.
+        len: usize,
+    },
+
+    /// The proof is not about the expected data.
+    #[error("the proof is about {actual:?} instead of {expected:?}")]
+    PathMismatch {
+        /// The path expected by the verifier.
+        expected: ProofPath,
+        /// The path of the proof.
+        actual: ProofPath,
+    },
+
+    /// The requested field does not exist.
+    #[error("there is no field {0}")]
+    UnknownField(String),
+
+    /// The leaf of the proof is not of the requested kind.
+    #[error("the proof leaf is not a {0}")]
+    UnexpectedLeaf(&'static str),
+
+    /// The data of the proof cannot be decoded with the requested types.
+    #[error(transparent)]
+    BcsError(#[from] bcs::Error),
+
+    /// Hashing failed.
+    #[error(transparent)]
+    ViewError(#[from] ViewError),
+}
+
+/// The data of a view at the bottom of a [`ViewProof`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProofLeaf {
+    /// The serialized value of a register.
+    Register {
+        /// The BCS bytes of the value.
+        value: Vec<u8>,
+    },
+    /// All the entries of a map, for a key that may be present or absent.
+    Map {
+        /// The serialized key being proved.
+        key: Vec<u8>,
+        /// The serialized entries of the map, sorted by key.
+        entries: Vec<(Vec<u8>, Vec<u8>)>,
+    },
+    /// All the elements of a log or a queue, for a given position.
+    Sequence {
+        /// The position of the element being proved.
+        index: usize,
+        /// The BCS bytes of the elements.
+        elements: Vec<Vec<u8>>,
+    },
+}
+
+/// A step from the hash of a view to the hash of the view containing it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProofStep {
+    /// The view is a field of a struct with derived hashing.
+    Field {
+        /// The position of the field in the struct.
+        index: usize,
+        /// The hashes of the other fields, in order.
+        sibling_hashes: Vec<Vec<u8>>,
+    },
+    /// The view is an entry of a collection.
+    CollectionEntry {
+        /// The serialized key of the entry.
+        key: Vec<u8>,
+        /// The keys and hashes of the other entries, sorted by key.
+        siblings: Vec<(Vec<u8>, Vec<u8>)>,
+    },
+}
+
+/// An element of a [`ProofPath`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PathElement {
+    /// The field at this position in a struct with derived hashing.
+    Field(usize),
+    /// The entry of a collection with this serialized key.
+    CollectionEntry(Vec<u8>),
+    /// The value of a register.
+    Register,
+    /// The entry of a map with this serialized key, which may be absent.
+    MapEntry(Vec<u8>),
+    /// The element at this position of a log or a queue.
+    SequenceElement(usize),
+}
+
+/// The location of the data proved by a [`ViewProof`], from the root view down to the
+/// leaf.
+///
+/// The keys are serialized as in the views: with BCS for [`MapView`] and
+/// [`CollectionView`], and with [`CustomSerialize`] for their custom variants.
+///
+/// [`MapView`]: crate::map_view::MapView
+/// [`CollectionView`]: crate::collection_view::CollectionView
+/// [`CustomSerialize`]: crate::common::CustomSerialize
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofPath(pub Vec<PathElement>);
+
+impl ProofPath {
+    /// Creates the path of the root view.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Goes down to the field at position `index`.
+    pub fn field(mut self, index: usize) -> Self {
+        self.0.push(PathElement::Field(index));
+        self
+    }
+
+    /// Goes down to the field `name` of a view of type `V`.
+    pub fn named_field<V: ProvableFieldsView>(self, name: &str) -> Result<Self, ProofError> {
+        let index = V::FIELD_NAMES
+            .iter()
+            .position(|field_name| *field_name == name)
+            .ok_or_else(|| ProofError::UnknownField(name.to_string()))?;
+        Ok(self.field(index))
+    }
+
+    /// Goes down to the entry of a collection with the serialized key `key`.
+    pub fn collection_entry(mut self, key: Vec<u8>) -> Self {
+        self.0.push(PathElement::CollectionEntry(key));
+        self
+    }
+
+    /// Ends at the value of a register.
+    pub fn register(mut self) -> Self {
+        self.0.push(PathElement::Register);
+        self
+    }
+
+    /// Ends at the entry of a map with the serialized key `key`.
+    pub fn map_entry(mut self, key: Vec<u8>) -> Self {
+        self.0.push(PathElement::MapEntry(key));
+        self
+    }
+
+    /// Ends at the element at position `index` of a log or a queue.
+    pub fn sequence_element(mut self, index: usize) -> Self {
+        self.0.push(PathElement::SequenceElement(index));
+        self
+    }
+}
+
+/// A proof that some data is part of a view with a given hash.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ViewProof {
+    /// The data being proved.
+    pub leaf: ProofLeaf,
+    /// The steps from the leaf up to the root view.
+    pub steps: Vec<ProofStep>,
+}
+
+/// A view with derived hashing whose fields can be proved individually.
+#[cfg_attr(not(web), trait_variant::make(Send))]
+pub trait ProvableFieldsView: HashableView {
+    /// The names of the fields, in the order used for hashing.
+    const FIELD_NAMES: &'static [&'static str];
+
+    /// Computes the hashes of the fields, in the order used for hashing.
+    async fn field_hashes(&self) -> Result<Vec<Vec<u8>>, ViewError>;
+
+    /// Extends a proof about the field `name` into a proof about this view.
+    async fn prove_field(&self, name: &str, proof: ViewProof) -> Result<ViewProof, ViewError>;
+}
+
+/// A [`CryptoHashView`] whose crypto-hash can be recomputed from its hash.
+pub trait ProvableCryptoHashView: CryptoHashView {
+    /// Returns the crypto-hash of a view of this type whose hash is `hash`, or `None`
+    /// if `hash` does not have the length of a hash.
+    fn crypto_hash_from_hash(hash: &[u8]) -> Option<CryptoHash>;
+}
+
+impl ViewProof {
+    /// Creates a proof with no steps.
+    pub fn new(leaf: ProofLeaf) -> Self {
+        ViewProof {
+            leaf,
+            steps: Vec::new(),
+        }
+    }
+
+    /// Adds a step on top of the proof.
+    pub fn with_step(mut self, step: ProofStep) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Adds a step for the field `name` of a struct, given the names and the hashes of
+    /// all its fields.
+    pub fn with_field(
+        self,
+        field_names: &[&str],
+        mut field_hashes: Vec<Vec<u8>>,
+        name: &str,
+    ) -> Result<Self, ViewError> {
+        let index = field_names
+            .iter()
+            .position(|field_name| *field_name == name)
+            .ok_or_else(|| ViewError::NotFound(format!("field {name}")))?;
+        field_hashes.remove(index);
+        Ok(self.with_step(ProofStep::Field {
+            index,
+            sibling_hashes: field_hashes,
+        }))
+    }
+
+    /// Creates a proof for the element at `index` of a log or a queue holding
+    /// `elements`.
+    pub(crate) fn for_sequence<T: Serialize>(
+        index: usize,
+        elements: &[T],
+    ) -> Result<Self, ViewError> {
+        if index >= elements.len() {
+            return Err(ViewError::NotFound(format!("index {index}")));
+        }
+        let elements = elements
+            .iter()
+            .map(bcs::to_bytes)
+            .collect::<Result<_, _>>()?;
+        Ok(ViewProof::new(ProofLeaf::Sequence { index, elements }))
+    }
+
+    /// Returns the location of the proved data in the root view.
+    pub fn path(&self) -> ProofPath {
+        let mut elements = self
+            .steps
+            .iter()
+            .rev()
+            .map(|step| match step {
+                ProofStep::Field { index, .. } => PathElement::Field(*index),
+                ProofStep::CollectionEntry { key, .. } => PathElement::CollectionEntry(key.clone()),
+            })
+            .collect::<Vec<_>>();
+        elements.push(match &self.leaf {
+            ProofLeaf::Register { .. } => PathElement::Register,
+            ProofLeaf::Map { key, .. } => PathElement::MapEntry(key.clone()),
+            ProofLeaf::Sequence { index, .. } => PathElement::SequenceElement(*index),
+        });
+        ProofPath(elements)
+    }
+
+    /// Recomputes the hash of the root view from the proof.
+    pub fn root_hash(&self) -> Result<Vec<u8>, ProofError> {
+        let mut hash = self.leaf.hash()?;
+        for step in &self.steps {
+            hash = step.hash(hash)?;
+        }
+        Ok(hash)
+    }
+
+    /// Checks that the proof is about the data at `expected_path`, and that it leads to
+    /// the hash `expected_hash` of the root view.
+    pub fn verify(
+        &self,
+        expected_hash: &[u8],
+        expected_path: &ProofPath,
+    ) -> Result<(), ProofError> {
+        self.check_path(expected_path)?;
+        if self.root_hash()? != expected_hash {
+            return Err(ProofError::HashMismatch);
+        }
+        Ok(())
+    }
+
+    /// Checks that the proof is about the data at `expected_path`, and that it leads to
+    /// the crypto-hash `expected_hash` of a root view of type `V`.
+    pub fn verify_crypto_hash<V: ProvableCryptoHashView>(
+        &self,
+        expected_hash: &CryptoHash,
+        expected_path: &ProofPath,
+    ) -> Result<(), ProofError> {
+        self.check_path(expected_path)?;
+        let root_hash = self.root_hash()?;
+        if V::crypto_hash_from_hash(&root_hash).as_ref() != Some(expected_hash) {
+            return Err(ProofError::HashMismatch);
+        }
+        Ok(())
+    }
+
+    fn check_path(&self, expected_path: &ProofPath) -> Result<(), ProofError> {
+        let path = self.path();
+        if path != *expected_path {
+            return Err(ProofError::PathMismatch {
+                expected: expected_path.clone(),
+                actual: path,
+            });
+        }
+        Ok(())
+    }
+
+    /// Decodes the value of a register proof.
+    pub fn register_value<T: DeserializeOwned>(&self) -> Result<T, ProofError> {
+        let ProofLeaf::Register { value } = &self.leaf else {
+            return Err(ProofError::UnexpectedLeaf("register"));
+        };
+        Ok(bcs::from_bytes(value)?)
+    }
+
+    /// Decodes the key of a map proof together with its value, if the key is present.
+    /// All the entries are decoded so that they are known to be unambiguous.
+    pub fn map_entry<I, V>(&self) -> Result<(I, Option<V>), ProofError>
+    where
+        I: DeserializeOwned,
+        V: DeserializeOwned,
+    {
+        let ProofLeaf::Map { key, entries } = &self.leaf else {
+            return Err(ProofError::UnexpectedLeaf("map"));
+        };
+        let mut found = None;
+        for (entry_key, entry_value) in entries {
+            bcs::from_bytes::<I>(entry_key)?;
+            let value = bcs::from_bytes::<V>(entry_value)?;
+            if entry_key == key {
+                found = Some(value);
+            }
+        }
+        Ok((bcs::from_bytes(key)?, found))
+    }
+
+    /// Decodes the position and the value of the element of a log or queue proof.
+    /// All the elements are decoded so that they are known to be unambiguous.
+    pub fn sequence_element<T: DeserializeOwned>(&self) -> Result<(usize, T), ProofError> {
+        let ProofLeaf::Sequence { index, elements } = &self.leaf else {
+            return Err(ProofError::UnexpectedLeaf("sequence"));
+        };
+        let mut found = None;
+        for (position, element) in elements.iter().enumerate() {
+            let value = bcs::from_bytes::<T>(element)?;
+            if position == *index {
+                found = Some(value);
+            }
+        }
+        let value = found.ok_or(ProofError::IndexOutOfRange {
+            index: *index,
+            len: elements.len(),
+        })?;
+        Ok((*index, value))
+    }
+}
+
+impl ProofLeaf {
+    /// Computes the hash of the view holding this data.
+    pub fn hash(&self) -> Result<Vec<u8>, ProofError> {
+        let mut hasher = sha3::Sha3_256::default();
+        match self {
+            ProofLeaf::Register { value } => {
+                hasher.update_with_bytes(value)?;
+            }
+            ProofLeaf::Map { entries, .. } => {
+                check_strictly_sorted(entries)?;
+                for (key, value) in entries {
+                    hasher.update_with_bytes(key)?;
+                    hasher.update_with_bytes(value)?;
+                }
+                hasher.update_with_bcs_bytes(&(entries.len() as u32))?;
+            }
+            ProofLeaf::Sequence { index, elements } => {
+                if *index >= elements.len() {
+                    return Err(ProofError::IndexOutOfRange {
+                        index: *index,
+                        len: elements.len(),
+                    });
+                }
+                // This is the BCS serialization of the vector of elements.
+                hasher.update_with_bytes(&uleb128_bytes(elements.len()))?;
+                for element in elements {
+                    hasher.update_with_bytes(element)?;
+                }
+            }
+        }
+        Ok(finalize(hasher))
+    }
+}
+
+impl ProofStep {
+    /// Computes the hash of the enclosing view from the hash of the proved view.
+    pub fn hash(&self, hash: Vec<u8>) -> Result<Vec<u8>, ProofError> {
+        let mut hasher = sha3::Sha3_256::default();
+        match self {
+            ProofStep::Field {
+                index,
+                sibling_hashes,
+            } => {
+                if *index > sibling_hashes.len() {
+                    return Err(ProofError::IndexOutOfRange {
+                        index: *index,
+                        len: sibling_hashes.len() + 1,
+                    });
+                }
+                let (before, after) = sibling_hashes.split_at(*index);
+                for field_hash in before.iter().chain([&hash]).chain(after) {
+                    check_hash_size(field_hash)?;
+                    hasher.update_with_bytes(field_hash)?;
+                }
+            }
+            ProofStep::CollectionEntry { key, siblings } => {
+                check_strictly_sorted(siblings)?;
+                let position = match siblings.binary_search_by(|(sibling, _)| sibling.cmp(key)) {
+                    Ok(_) => return Err(ProofError::DuplicateKey),
+                    Err(position) => position,
+                };
+                let count = (siblings.len() + 1) as u32;
+                hasher.update_with_bcs_bytes(&count)?;
+                let entry = (key.clone(), hash);
+                let (before, after) = siblings.split_at(position);
+                for (entry_key, entry_hash) in before.iter().chain([&entry]).chain(after) {
+                    check_hash_size(entry_hash)?;
+                    hasher.update_with_bcs_bytes(entry_key)?;
+                    hasher.update_with_bytes(entry_hash)?;
+                }
+            }
+        }
+        Ok(finalize(hasher))
+    }
+}
+
+fn finalize(hasher: sha3::Sha3_256) -> Vec<u8> {
+    let output: HasherOutput = crate::views::Hasher::finalize(hasher);
+    output.to_vec()
+}
+
+fn check_hash_size(hash: &[u8]) -> Result<(), ProofError> {
+    if hash.len() != HASH_SIZE {
+        return Err(ProofError::InvalidHashSize(hash.len()));
+    }
+    Ok(())
+}
+
+fn check_strictly_sorted<T>(entries: &[(Vec<u8>, T)]) -> Result<(), ProofError> {
+    if entries.windows(2).any(|pair| pair[0].0 >= pair[1].0) {
+        return Err(ProofError::UnsortedEntries);
+    }
+    Ok(())
+}
+
+/// Returns the ULEB128 encoding of a length, as used by BCS for sequences.
+fn uleb128_bytes(len: usize) -> Vec<u8> {
+    let mut len = len as u64;
+    let mut bytes = Vec::new();
+    loop {
+        let byte = (len & 0x7f) as u8;
+        len >>= 7;
+        if len == 0 {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
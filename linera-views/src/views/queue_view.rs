@@ -16,7 +16,7 @@ use crate::{
     context::Context,
     hashable_wrapper::WrappedHashableContainerView,
     store::ReadableKeyValueStore as _,
-    views::{ClonableView, HashableView, Hasher, View, ViewError, MIN_VIEW_TAG},
+    views::{proof::ViewProof, ClonableView, HashableView, Hasher, View, ViewError, MIN_VIEW_TAG},
 };
 
 #[cfg(with_metrics)]
@@ -413,6 +413,29 @@ where
         self.read_front(count).await
     }
 
+    /// Creates a proof that the queue holds its current value at position `index`,
+    /// counting from the front. The proof contains all the elements of the queue.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::proof::ProofPath;
+    /// # use linera_views::queue_view::QueueView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut queue = QueueView::load(context).await.unwrap();
+    /// queue.push_back(34);
+    /// queue.push_back(37);
+    /// let proof = queue.prove(0).await.unwrap();
+    /// let hash = queue.hash().await.unwrap();
+    /// assert!(proof.verify(&hash, &ProofPath::new().sequence_element(0)).is_ok());
+    /// assert_eq!(proof.sequence_element::<u32>().unwrap(), (0, 34));
+    /// # })
+    /// ```
+    pub async fn prove(&self, index: usize) -> Result<ViewProof, ViewError> {
+        let elements = self.elements().await?;
+        ViewProof::for_sequence(index, &elements)
+    }
+
     async fn load_all(&mut self) -> Result<(), ViewError> {
         if !self.delete_storage_first {
             let stored_remainder = self.stored_count();
@@ -21,7 +21,10 @@ use crate::{
     context::{BaseKey, Context},
     hashable_wrapper::WrappedHashableContainerView,
     store::ReadableKeyValueStore as _,
-    views::{ClonableView, HashableView, Hasher, ReplaceContext, View, ViewError, MIN_VIEW_TAG},
+    views::{
+        proof::{ProofStep, ViewProof},
+        ClonableView, HashableView, Hasher, ReplaceContext, View, ViewError, MIN_VIEW_TAG,
+    },
 };
 
 #[cfg(with_metrics)]
@@ -968,7 +971,7 @@ impl<W: HashableView> HashableView for ReentrantByteCollectionView<W::Context, W
         let count = keys.len() as u32;
         hasher.update_with_bcs_bytes(&count)?;
         for key in keys {
-            hasher.update_with_bytes(&key)?;
+            hasher.update_with_bcs_bytes(&key)?;
             let hash = if let Some(entry) = self.updates.get_mut(&key) {
                 let Update::Set(view) = entry else {
                     unreachable!();
@@ -999,7 +1002,7 @@ impl<W: HashableView> HashableView for ReentrantByteCollectionView<W::Context, W
         let count = keys.len() as u32;
         hasher.update_with_bcs_bytes(&count)?;
         for key in keys {
-            hasher.update_with_bytes(&key)?;
+            hasher.update_with_bcs_bytes(&key)?;
             let hash = if let Some(entry) = self.updates.get(&key) {
                 let Update::Set(view) = entry else {
                     unreachable!();
@@ -1023,6 +1026,66 @@ impl<W: HashableView> HashableView for ReentrantByteCollectionView<W::Context, W
     }
 }
 
+impl<W: HashableView> ReentrantByteCollectionView<W::Context, W> {
+    /// Extends a proof about the subview at `short_key` into a proof about the
+    /// collection. The proof contains the keys and the hashes of all the other subviews.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::proof::ProofPath;
+    /// # use linera_views::reentrant_collection_view::ReentrantByteCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: ReentrantByteCollectionView<_, RegisterView<_, String>> =
+    ///     ReentrantByteCollectionView::load(context).await.unwrap();
+    /// view.try_load_entry_mut(&[0, 1]).await.unwrap().set(String::from("Hello"));
+    /// view.try_load_entry_mut(&[0, 2]).await.unwrap();
+    /// let proof = view.try_load_entry(&[0, 1]).await.unwrap().unwrap().prove().unwrap();
+    /// let proof = view.prove_entry(&[0, 1], proof).await.unwrap();
+    /// let hash = view.hash().await.unwrap();
+    /// let path = ProofPath::new().collection_entry(vec![0, 1]).register();
+    /// assert!(proof.verify(&hash, &path).is_ok());
+    /// # })
+    /// ```
+    pub async fn prove_entry(
+        &self,
+        short_key: &[u8],
+        proof: ViewProof,
+    ) -> Result<ViewProof, ViewError> {
+        let keys = self.keys().await?;
+        if !keys.iter().any(|key| key == short_key) {
+            return Err(ViewError::NotFound(format!("entry {short_key:?}")));
+        }
+        let mut siblings = Vec::new();
+        for key in keys {
+            if key == short_key {
+                continue;
+            }
+            let hash = if let Some(entry) = self.updates.get(&key) {
+                let Update::Set(view) = entry else {
+                    unreachable!();
+                };
+                let view = view
+                    .try_read_arc()
+                    .ok_or_else(|| ViewError::TryLockError(key.clone()))?;
+                view.hash().await?
+            } else {
+                let key = self
+                    .context
+                    .base_key()
+                    .base_tag_index(KeyTag::Subview as u8, &key);
+                let context = self.context.clone_with_base_key(key);
+                let view = W::load(context).await?;
+                view.hash().await?
+            };
+            siblings.push((key, hash.as_ref().to_vec()));
+        }
+        let key = short_key.to_vec();
+        Ok(proof.with_step(ProofStep::CollectionEntry { key, siblings }))
+    }
+}
+
 /// A view that supports accessing a collection of views of the same kind, indexed by keys,
 /// possibly several subviews at a time.
 #[derive(Debug)]
@@ -1549,6 +1612,42 @@ where
     }
 }
 
+impl<I, W> ReentrantCollectionView<W::Context, I, W>
+where
+    W: HashableView,
+    I: Serialize,
+{
+    /// Extends a proof about the subview at `index` into a proof about the collection.
+    /// The proof contains the keys and the hashes of all the other subviews.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::proof::ProofPath;
+    /// # use linera_views::reentrant_collection_view::ReentrantCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: ReentrantCollectionView<_, u64, RegisterView<_, String>> =
+    ///     ReentrantCollectionView::load(context).await.unwrap();
+    /// view.try_load_entry_mut(&23).await.unwrap().set(String::from("Hello"));
+    /// let proof = view.try_load_entry(&23).await.unwrap().unwrap().prove().unwrap();
+    /// let proof = view.prove_entry(&23, proof).await.unwrap();
+    /// let hash = view.hash().await.unwrap();
+    /// let key = bcs::to_bytes(&23_u64).unwrap();
+    /// let path = ProofPath::new().collection_entry(key).register();
+    /// assert!(proof.verify(&hash, &path).is_ok());
+    /// # })
+    /// ```
+    pub async fn prove_entry<Q>(&self, index: &Q, proof: ViewProof) -> Result<ViewProof, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: Serialize + ?Sized,
+    {
+        let short_key = BaseKey::derive_short_key(index)?;
+        self.collection.prove_entry(&short_key, proof).await
+    }
+}
+
 /// A view that supports accessing a collection of views of the same kind, indexed by an ordered key,
 /// possibly several subviews at a time.
 #[derive(Debug)]
@@ -2056,6 +2155,44 @@ where
     }
 }
 
+impl<I, W> ReentrantCustomCollectionView<W::Context, I, W>
+where
+    W: HashableView,
+    I: CustomSerialize,
+{
+    /// Extends a proof about the subview at `index` into a proof about the collection.
+    /// The proof contains the keys, in the custom serialization, and the hashes of all
+    /// the other subviews.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::common::CustomSerialize as _;
+    /// # use linera_views::proof::ProofPath;
+    /// # use linera_views::reentrant_collection_view::ReentrantCustomCollectionView;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut view: ReentrantCustomCollectionView<_, u128, RegisterView<_, String>> =
+    ///     ReentrantCustomCollectionView::load(context).await.unwrap();
+    /// view.try_load_entry_mut(&23).await.unwrap().set(String::from("Hello"));
+    /// let proof = view.try_load_entry(&23).await.unwrap().unwrap().prove().unwrap();
+    /// let proof = view.prove_entry(&23, proof).await.unwrap();
+    /// let hash = view.hash().await.unwrap();
+    /// let key = 23_u128.to_custom_bytes().unwrap();
+    /// let path = ProofPath::new().collection_entry(key).register();
+    /// assert!(proof.verify(&hash, &path).is_ok());
+    /// # })
+    /// ```
+    pub async fn prove_entry<Q>(&self, index: &Q, proof: ViewProof) -> Result<ViewProof, ViewError>
+    where
+        I: Borrow<Q>,
+        Q: CustomSerialize,
+    {
+        let short_key = index.to_custom_bytes()?;
+        self.collection.prove_entry(&short_key, proof).await
+    }
+}
+
 /// Type wrapping `ReentrantByteCollectionView` while memoizing the hash.
 pub type HashedReentrantByteCollectionView<C, W> =
     WrappedHashableContainerView<C, ReentrantByteCollectionView<C, W>, HasherOutput>;
@@ -11,7 +11,10 @@ use crate::{
     context::Context,
     hashable_wrapper::WrappedHashableContainerView,
     store::ReadableKeyValueStore as _,
-    views::{ClonableView, HashableView, Hasher, ReplaceContext, View},
+    views::{
+        proof::{ProofLeaf, ViewProof},
+        ClonableView, HashableView, Hasher, ReplaceContext, View,
+    },
     ViewError,
 };
 
@@ -222,6 +225,27 @@ where
         }
     }
 
+    /// Creates a proof that the register holds its current value.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::proof::ProofPath;
+    /// # use linera_views::register_view::RegisterView;
+    /// # use linera_views::views::{HashableView, View};
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut register = RegisterView::<_, u32>::load(context).await.unwrap();
+    /// register.set(5);
+    /// let proof = register.prove().unwrap();
+    /// let hash = register.hash().await.unwrap();
+    /// assert!(proof.verify(&hash, &ProofPath::new().register()).is_ok());
+    /// assert_eq!(proof.register_value::<u32>().unwrap(), 5);
+    /// # })
+    /// ```
+    pub fn prove(&self) -> Result<ViewProof, ViewError> {
+        let value = bcs::to_bytes(self.get())?;
+        Ok(ViewProof::new(ProofLeaf::Register { value }))
+    }
+
     fn compute_hash(&self) -> Result<<sha3::Sha3_256 as Hasher>::Output, ViewError> {
         #[cfg(with_metrics)]
         let _hash_latency = metrics::REGISTER_VIEW_HASH_RUNTIME.measure_latency();
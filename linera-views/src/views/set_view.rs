@@ -3,16 +3,20 @@
 
 use std::{borrow::Borrow, collections::BTreeMap, marker::PhantomData, mem};
 
+use futures::{Stream, StreamExt as _};
 #[cfg(with_metrics)]
 use linera_base::prometheus_util::MeasureLatency as _;
 use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
     batch::Batch,
-    common::{CustomSerialize, HasherOutput, Update},
+    common::{
+        get_next_page_start, get_page_interval, merge_page, stream_pages, CustomSerialize,
+        HasherOutput, Update,
+    },
     context::{BaseKey, Context},
     hashable_wrapper::WrappedHashableContainerView,
-    store::ReadableKeyValueStore as _,
+    store::{RangeQuery, ReadableKeyValueStore as _},
     views::{ClonableView, HashableView, Hasher, ReplaceContext, View, ViewError},
 };
 
@@ -229,6 +233,64 @@ impl<C: Context> ByteSetView<C> {
         Ok(keys)
     }
 
+    /// Reads the page of at most `page_size` stored keys starting at `start`, merges it
+    /// with the pending updates, and returns it with the start of the next page.
+    async fn keys_page(
+        &self,
+        start: Option<Vec<u8>>,
+        page_size: usize,
+    ) -> Result<(Vec<Vec<u8>>, Option<Vec<u8>>), ViewError> {
+        let mut stored_keys = Vec::new();
+        let mut next_start = None;
+        if !self.delete_storage_first {
+            let query = RangeQuery {
+                start: start.clone(),
+                limit: Some(page_size),
+                ..RangeQuery::default()
+            };
+            let base = &self.context.base_key().bytes;
+            stored_keys = self
+                .context
+                .store()
+                .find_keys_by_range(base, &query)
+                .await?;
+            next_start = get_next_page_start(stored_keys.len(), page_size, stored_keys.last());
+        }
+        let entries = merge_page(
+            stored_keys.into_iter().map(|key| (key, ())).collect(),
+            self.updates
+                .range(get_page_interval(start, next_start.as_ref())),
+            |_| false,
+            |_| (),
+        );
+        let keys = entries.into_iter().map(|(key, ())| key).collect();
+        Ok((keys, next_start))
+    }
+
+    /// Streams the keys of the set in lexicographic order. The keys are read from
+    /// storage in pages of at most `page_size` keys.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use futures::TryStreamExt as _;
+    /// # use linera_views::{context::MemoryContext, set_view::ByteSetView};
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut set = ByteSetView::load(context).await.unwrap();
+    /// set.insert(vec![0, 1]);
+    /// set.insert(vec![0, 2]);
+    /// let keys = set.keys_stream(1).try_collect::<Vec<_>>().await.unwrap();
+    /// assert_eq!(keys, vec![vec![0, 1], vec![0, 2]]);
+    /// # })
+    /// ```
+    pub fn keys_stream(
+        &self,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<Vec<u8>, ViewError>> + '_ {
+        stream_pages(page_size, move |start, page_size| {
+            self.keys_page(start, page_size)
+        })
+    }
+
     /// Returns the number of entries in the set.
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -543,6 +605,29 @@ impl<C: Context, I: Serialize + DeserializeOwned + Send> SetView<C, I> {
         Ok(indices)
     }
 
+    /// Streams the indices of the set, in the order determined by serialization. The
+    /// indices are read from storage in pages of at most `page_size` indices.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use futures::TryStreamExt as _;
+    /// # use linera_views::{context::MemoryContext, set_view::SetView};
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut set: SetView<_, u32> = SetView::load(context).await.unwrap();
+    /// set.insert(&(34 as u32));
+    /// let indices = set.indices_stream(10).try_collect::<Vec<_>>().await.unwrap();
+    /// assert_eq!(indices, vec![34 as u32]);
+    /// # })
+    /// ```
+    pub fn indices_stream(
+        &self,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<I, ViewError>> + '_ {
+        self.set
+            .keys_stream(page_size)
+            .map(|key| Ok(BaseKey::deserialize_value(&key?)?))
+    }
+
     /// Returns the number of entries in the set.
     /// ```rust
     /// # tokio_test::block_on(async {
@@ -821,6 +906,32 @@ where
         Ok(indices)
     }
 
+    /// Streams the indices of the set, in the order determined by the custom
+    /// serialization. The indices are read from storage in pages of at most `page_size`
+    /// indices.
+    /// ```rust
+    /// # tokio_test::block_on(async {
+    /// # use futures::TryStreamExt as _;
+    /// # use linera_views::context::MemoryContext;
+    /// # use linera_views::set_view::CustomSetView;
+    /// # use linera_views::views::View;
+    /// # let context = MemoryContext::new_for_testing(());
+    /// let mut set = CustomSetView::<_, u128>::load(context).await.unwrap();
+    /// set.insert(&(37 as u128));
+    /// set.insert(&(34 as u128));
+    /// let indices = set.indices_stream(1).try_collect::<Vec<_>>().await.unwrap();
+    /// assert_eq!(indices, vec![34 as u128, 37 as u128]);
+    /// # })
+    /// ```
+    pub fn indices_stream(
+        &self,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<I, ViewError>> + '_ {
+        self.set
+            .keys_stream(page_size)
+            .map(|key| I::from_custom_bytes(&key?))
+    }
+
     /// Returns the number of entries of the set.
     /// ```rust
     /// # tokio_test::block_on(async {
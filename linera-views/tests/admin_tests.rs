@@ -5,6 +5,8 @@ use std::marker::PhantomData;
 
 #[cfg(with_dynamodb)]
 use linera_views::dynamo_db::DynamoDbDatabase;
+#[cfg(with_redb)]
+use linera_views::redb_db::RedbDatabase;
 #[cfg(with_rocksdb)]
 use linera_views::rocks_db::RocksDbDatabase;
 #[cfg(with_scylladb)]
@@ -12,12 +14,13 @@ use linera_views::scylla_db::ScyllaDbDatabase;
 use linera_views::{
     memory::MemoryDatabase,
     store::{KeyValueStore, TestKeyValueDatabase},
-    test_utils::{namespace_admin_test, root_key_admin_test},
+    test_utils::{namespace_admin_test, root_key_admin_test, snapshot_admin_test},
 };
 use test_case::test_case;
 
 #[test_case(PhantomData::<MemoryDatabase>; "MemoryDatabase")]
 #[cfg_attr(with_rocksdb, test_case(PhantomData::<RocksDbDatabase>; "RocksDbDatabase"))]
+#[cfg_attr(with_redb, test_case(PhantomData::<RedbDatabase>; "RedbDatabase"))]
 #[cfg_attr(with_dynamodb, test_case(PhantomData::<DynamoDbDatabase>; "DynamoDbDatabase"))]
 #[cfg_attr(with_scylladb, test_case(PhantomData::<ScyllaDbDatabase>; "ScyllaDbDatabase"))]
 #[tokio::test]
@@ -30,6 +33,7 @@ where
 
 #[test_case(PhantomData::<MemoryDatabase>; "MemoryDatabase")]
 #[cfg_attr(with_rocksdb, test_case(PhantomData::<RocksDbDatabase>; "RocksDbDatabase"))]
+#[cfg_attr(with_redb, test_case(PhantomData::<RedbDatabase>; "RedbDatabase"))]
 #[cfg_attr(with_dynamodb, test_case(PhantomData::<DynamoDbDatabase>; "DynamoDbDatabase"))]
 #[cfg_attr(with_scylladb, test_case(PhantomData::<ScyllaDbDatabase>; "ScyllaDbDatabase"))]
 #[tokio::test]
@@ -39,3 +43,16 @@ where
 {
     root_key_admin_test::<K>().await;
 }
+
+#[test_case(PhantomData::<MemoryDatabase>; "MemoryDatabase")]
+#[cfg_attr(with_rocksdb, test_case(PhantomData::<RocksDbDatabase>; "RocksDbDatabase"))]
+#[cfg_attr(with_redb, test_case(PhantomData::<RedbDatabase>; "RedbDatabase"))]
+#[cfg_attr(with_dynamodb, test_case(PhantomData::<DynamoDbDatabase>; "DynamoDbDatabase"))]
+#[cfg_attr(with_scylladb, test_case(PhantomData::<ScyllaDbDatabase>; "ScyllaDbDatabase"))]
+#[tokio::test]
+async fn snapshot_admin_test_cases<K: TestKeyValueDatabase>(_view_type: PhantomData<K>)
+where
+    K::Store: KeyValueStore,
+{
+    snapshot_admin_test::<K>().await;
+}
@@ -3,14 +3,26 @@
 
 use anyhow::Result;
 use linera_views::{
+    collection_view::CollectionView,
     common::HasherOutput,
     context::MemoryContext,
     hashable_wrapper::WrappedHashableContainerView,
+    log_view::LogView,
+    map_view::MapView,
+    proof::{ProofError, ProofLeaf, ProofPath, ProofStep, ProvableFieldsView as _},
     register_view::{HashedRegisterView, RegisterView},
-    views::{HashableView, View},
+    views::{CryptoHashView as _, HashableView, View},
 };
 use linera_views_derive::CryptoHashRootView;
 
+#[derive(CryptoHashRootView)]
+struct ProofTestType<C> {
+    pub register: RegisterView<C, u64>,
+    pub map: MapView<C, String, u64>,
+    pub log: LogView<C, u32>,
+    pub collection: CollectionView<C, u8, RegisterView<C, String>>,
+}
+
 #[derive(CryptoHashRootView)]
 struct TestType<C> {
     pub inner: RegisterView<C, String>,
@@ -41,3 +53,127 @@ async fn check_hashable_hash() -> Result<()> {
     assert_eq!(hash0, view.hash().await?);
     Ok(())
 }
+
+#[tokio::test]
+async fn check_view_proofs() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut view = ProofTestType::load(context).await?;
+    view.register.set(7);
+    view.map.insert("alice", 100)?;
+    view.map.insert("bob", 200)?;
+    view.log.push(5);
+    view.log.push(6);
+    view.collection
+        .load_entry_mut(&1)
+        .await?
+        .set("one".to_string());
+    view.collection
+        .load_entry_mut(&2)
+        .await?
+        .set("two".to_string());
+    let hash = view.hash().await?;
+    let crypto_hash = view.crypto_hash().await?;
+    let root = ProofPath::new();
+
+    let proof = view.register.prove()?;
+    let proof = view.prove_field("register", proof).await?;
+    let path = root
+        .clone()
+        .named_field::<ProofTestType<MemoryContext<()>>>("register")?
+        .register();
+    proof.verify(&hash, &path)?;
+    proof.verify_crypto_hash::<ProofTestType<_>>(&crypto_hash, &path)?;
+    assert_eq!(proof.register_value::<u64>()?, 7);
+
+    let proof = view.map.prove("bob").await?;
+    let proof = view.prove_field("map", proof).await?;
+    let bob_path = root.clone().field(1).map_entry(bcs::to_bytes("bob")?);
+    proof.verify(&hash, &bob_path)?;
+    assert_eq!(
+        proof.map_entry::<String, u64>()?,
+        ("bob".to_string(), Some(200))
+    );
+
+    let proof = view.map.prove("carol").await?;
+    let proof = view.prove_field("map", proof).await?;
+    proof.verify(
+        &hash,
+        &root.clone().field(1).map_entry(bcs::to_bytes("carol")?),
+    )?;
+    assert_eq!(
+        proof.map_entry::<String, u64>()?,
+        ("carol".to_string(), None)
+    );
+    // A valid proof about another key is rejected.
+    assert!(matches!(
+        proof.verify(&hash, &bob_path),
+        Err(ProofError::PathMismatch { .. })
+    ));
+
+    let proof = view.log.prove(1).await?;
+    let proof = view.prove_field("log", proof).await?;
+    proof.verify(&hash, &root.clone().field(2).sequence_element(1))?;
+    assert_eq!(proof.sequence_element::<u32>()?, (1, 6));
+    assert!(matches!(
+        proof.verify(&hash, &root.clone().field(2).sequence_element(0)),
+        Err(ProofError::PathMismatch { .. })
+    ));
+    assert!(view.log.prove(2).await.is_err());
+
+    let proof = view.collection.try_load_entry(&2).await?.unwrap().prove()?;
+    let proof = view.collection.prove_entry(&2, proof).await?;
+    let proof = view.prove_field("collection", proof).await?;
+    let path = root
+        .clone()
+        .field(3)
+        .collection_entry(bcs::to_bytes(&2u8)?)
+        .register();
+    proof.verify(&hash, &path)?;
+    proof.verify_crypto_hash::<ProofTestType<_>>(&crypto_hash, &path)?;
+    assert_eq!(proof.register_value::<String>()?, "two");
+    assert!(view.prove_field("missing", proof.clone()).await.is_err());
+    // A valid proof about another field or another entry is rejected.
+    let other_entry = root
+        .clone()
+        .field(3)
+        .collection_entry(bcs::to_bytes(&1u8)?)
+        .register();
+    assert!(matches!(
+        proof.verify(&hash, &other_entry),
+        Err(ProofError::PathMismatch { .. })
+    ));
+    assert!(matches!(
+        proof.verify(&hash, &root.clone().field(0).register()),
+        Err(ProofError::PathMismatch { .. })
+    ));
+
+    // A proof can't shift the hashes of other fields into a sibling of the wrong size.
+    let field_hashes = view.field_hashes().await?;
+    let register_proof = view.register.prove()?;
+    let forged = register_proof.with_step(ProofStep::Field {
+        index: 1,
+        sibling_hashes: vec![Vec::new(), field_hashes[1..].concat()],
+    });
+    let forged_path = root.clone().field(1).register();
+    assert!(matches!(
+        forged.verify(&hash, &forged_path),
+        Err(ProofError::InvalidHashSize(0))
+    ));
+
+    let mut tampered = proof.clone();
+    tampered.leaf = ProofLeaf::Register {
+        value: bcs::to_bytes("three")?,
+    };
+    assert!(matches!(
+        tampered.verify(&hash, &path),
+        Err(ProofError::HashMismatch)
+    ));
+
+    view.map.insert("carol", 300)?;
+    let hash = view.hash().await?;
+    assert!(matches!(
+        proof.verify(&hash, &path),
+        Err(ProofError::HashMismatch)
+    ));
+    Ok(())
+}
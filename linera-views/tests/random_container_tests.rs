@@ -4,6 +4,7 @@
 use std::collections::{BTreeMap, BTreeSet};
 
 use anyhow::Result;
+use futures::TryStreamExt as _;
 use linera_views::{
     bucket_queue_view::HashedBucketQueueView,
     collection_view::HashedCollectionView,
@@ -14,6 +15,7 @@ use linera_views::{
     random::make_deterministic_rng,
     reentrant_collection_view::HashedReentrantCollectionView,
     register_view::RegisterView,
+    store::{Direction, RangeQuery},
     views::{CryptoHashRootView, CryptoHashView, RootView, View},
 };
 use rand::{distributions::Uniform, Rng, RngCore};
@@ -118,6 +120,13 @@ async fn classic_collection_view_check() -> Result<()> {
             // Checking the keys
             let key_values = view.key_values().await;
             assert_eq!(key_values, new_map);
+            let page_size = rng.gen_range(1..5);
+            let indices = view
+                .v
+                .indices_stream(page_size)
+                .try_collect::<Vec<_>>()
+                .await?;
+            assert_eq!(indices, new_map.keys().copied().collect::<Vec<_>>());
         }
         if save {
             if map != new_map {
@@ -360,6 +369,23 @@ async fn run_map_view_mutability<R: RngCore + Clone>(rng: &mut R) -> Result<()>
             }
             let new_key_values = view.map.key_values().await?;
             assert_eq!(new_state_vec, new_key_values);
+            let page_size = rng.gen_range(1..5);
+            let streamed_key_values = view
+                .map
+                .key_values_stream(page_size)
+                .try_collect::<Vec<_>>()
+                .await?;
+            assert_eq!(new_state_vec, streamed_key_values);
+            let streamed_keys = view
+                .map
+                .keys_stream(page_size)
+                .try_collect::<Vec<_>>()
+                .await?;
+            let new_state_keys = new_state_vec
+                .iter()
+                .map(|(key, _)| key.clone())
+                .collect::<Vec<_>>();
+            assert_eq!(new_state_keys, streamed_keys);
             for u in 0..4 {
                 let part_state_vec = new_state_vec
                     .iter()
@@ -369,6 +395,24 @@ async fn run_map_view_mutability<R: RngCore + Clone>(rng: &mut R) -> Result<()>
                 let part_key_values = view.map.key_values_by_prefix(vec![u]).await?;
                 assert_eq!(part_state_vec, part_key_values);
             }
+            let start = vec![rng.gen_range(0..4)];
+            let end = vec![rng.gen_range(0..5)];
+            let limit = rng.gen_range(0..4);
+            for direction in [Direction::Forward, Direction::Reverse] {
+                let query = RangeQuery::from_bounds(start.clone()..end.clone())
+                    .with_limit(limit)
+                    .with_direction(direction);
+                let range_state_vec =
+                    query.select(new_state_vec.clone(), |(key, _)| key.as_slice());
+                let range_key_values = view.map.key_values_by_range(&query).await?;
+                assert_eq!(range_state_vec, range_key_values);
+                let range_keys = view.map.keys_by_range(&query).await?;
+                let range_state_keys = range_state_vec
+                    .into_iter()
+                    .map(|(key, _)| key)
+                    .collect::<Vec<_>>();
+                assert_eq!(range_state_keys, range_keys);
+            }
             let keys_vec = all_keys.iter().cloned().collect::<Vec<_>>();
             let values = view.map.multi_get(keys_vec.clone()).await?;
             for i in 0..keys_vec.len() {
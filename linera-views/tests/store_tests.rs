@@ -3,7 +3,9 @@
 
 use linera_views::{
     batch::Batch,
+    compression::CompressionMemoryDatabase,
     context::{Context as _, MemoryContext},
+    encryption::EncryptionMemoryDatabase,
     key_value_store_view::ViewContainer,
     memory::MemoryDatabase,
     random::make_deterministic_rng,
@@ -64,6 +66,22 @@ async fn test_reads_memory() {
     }
 }
 
+#[tokio::test]
+async fn test_reads_compressed_memory() {
+    for scenario in get_random_test_scenarios() {
+        let store = CompressionMemoryDatabase::new_test_store().await.unwrap();
+        run_reads(store, scenario).await;
+    }
+}
+
+#[tokio::test]
+async fn test_reads_encrypted_memory() {
+    for scenario in get_random_test_scenarios() {
+        let store = EncryptionMemoryDatabase::new_test_store().await.unwrap();
+        run_reads(store, scenario).await;
+    }
+}
+
 #[cfg(with_rocksdb)]
 #[tokio::test]
 async fn test_reads_rocks_db() {
@@ -75,6 +93,17 @@ async fn test_reads_rocks_db() {
     }
 }
 
+#[cfg(with_redb)]
+#[tokio::test]
+async fn test_reads_redb() {
+    for scenario in get_random_test_scenarios() {
+        let store = linera_views::redb_db::RedbDatabase::new_test_store()
+            .await
+            .unwrap();
+        run_reads(store, scenario).await;
+    }
+}
+
 #[cfg(with_dynamodb)]
 #[tokio::test]
 async fn test_reads_dynamo_db() {
@@ -156,6 +185,18 @@ async fn test_memory_writes_from_blank() {
     run_writes_from_blank(&store).await;
 }
 
+#[tokio::test]
+async fn test_compressed_memory_writes_from_blank() {
+    let store = CompressionMemoryDatabase::new_test_store().await.unwrap();
+    run_writes_from_blank(&store).await;
+}
+
+#[tokio::test]
+async fn test_encrypted_memory_writes_from_blank() {
+    let store = EncryptionMemoryDatabase::new_test_store().await.unwrap();
+    run_writes_from_blank(&store).await;
+}
+
 #[tokio::test]
 async fn test_key_value_store_view_memory_writes_from_blank() {
     let context = MemoryContext::new_for_testing(());
@@ -172,6 +213,15 @@ async fn test_rocks_db_writes_from_blank() {
     run_writes_from_blank(&store).await;
 }
 
+#[cfg(with_redb)]
+#[tokio::test]
+async fn test_redb_writes_from_blank() {
+    let store = linera_views::redb_db::RedbDatabase::new_test_store()
+        .await
+        .unwrap();
+    run_writes_from_blank(&store).await;
+}
+
 #[cfg(with_dynamodb)]
 #[tokio::test]
 async fn test_dynamo_db_writes_from_blank() {
@@ -276,6 +326,17 @@ async fn test_rocks_db_big_write_read() {
     run_big_write_read(store, target_size, value_sizes).await;
 }
 
+#[cfg(with_redb)]
+#[tokio::test]
+async fn test_redb_big_write_read() {
+    let store = linera_views::redb_db::RedbDatabase::new_test_store()
+        .await
+        .unwrap();
+    let value_sizes = vec![100, 1000, 200000, 5000000];
+    let target_size = 20000000;
+    run_big_write_read(store, target_size, value_sizes).await;
+}
+
 #[cfg(with_indexeddb)]
 #[wasm_bindgen_test]
 async fn test_indexed_db_big_write_read() {
@@ -314,6 +375,58 @@ async fn test_rocks_db_writes_from_state() {
     run_writes_from_state(&store).await;
 }
 
+#[cfg(with_rocksdb)]
+#[tokio::test]
+async fn test_rocks_db_secondary_instance() {
+    use linera_views::{
+        random::generate_test_namespace,
+        rocks_db::{RocksDbDatabaseInternal, RocksDbStoreInternalConfig},
+        store::KeyValueDatabase as _,
+    };
+
+    let config = RocksDbDatabaseInternal::new_test_config().await.unwrap();
+    let namespace = generate_test_namespace();
+    let database = RocksDbDatabaseInternal::recreate_and_connect(&config, &namespace)
+        .await
+        .unwrap();
+    let store = database.open_shared(&[]).unwrap();
+    let mut batch = Batch::new();
+    batch.put_key_value_bytes(vec![1], vec![1]);
+    store.write_batch(batch).await.unwrap();
+
+    // The primary instance holds the lock of the database.
+    let secondary_config = RocksDbStoreInternalConfig {
+        secondary: true,
+        ..config
+    };
+    let secondary = RocksDbDatabaseInternal::connect(&secondary_config, &namespace)
+        .await
+        .unwrap()
+        .open_shared(&[])
+        .unwrap();
+    assert_eq!(
+        secondary.read_value_bytes(&[1]).await.unwrap(),
+        Some(vec![1])
+    );
+    let mut batch = Batch::new();
+    batch.put_key_value_bytes(vec![2], vec![2]);
+    store.write_batch(batch).await.unwrap();
+    assert_eq!(
+        secondary.find_keys_by_prefix(&[]).await.unwrap(),
+        vec![vec![1], vec![2]]
+    );
+    assert!(secondary.write_batch(Batch::new()).await.is_err());
+}
+
+#[cfg(with_redb)]
+#[tokio::test]
+async fn test_redb_writes_from_state() {
+    let store = linera_views::redb_db::RedbDatabase::new_test_store()
+        .await
+        .unwrap();
+    run_writes_from_state(&store).await;
+}
+
 #[cfg(with_indexeddb)]
 #[wasm_bindgen_test]
 async fn test_indexed_db_writes_from_state() {
@@ -350,3 +463,18 @@ async fn test_scylladb_access() {
 async fn test_dynamodb_access() {
     access_admin_test::<linera_views::dynamo_db::DynamoDbDatabase>().await
 }
+
+/// Checks that the prefix streams of any store can be used in futures sent to other
+/// threads.
+#[cfg(not(web))]
+#[test]
+fn test_prefix_streams_are_send() {
+    fn assert_send<T: Send>(_: &T) {}
+
+    fn check_streams<S: linera_views::store::ReadableKeyValueStore>(store: &S) {
+        assert_send(&store.find_keys_by_prefix_stream(&[], 10));
+        assert_send(&store.find_key_values_by_prefix_stream(&[], 10));
+    }
+
+    let _ = check_streams::<linera_views::memory::MemoryStore>;
+}
@@ -1,7 +1,7 @@
 // Copyright (c) Zefchain Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use anyhow::Result;
 #[cfg(with_dynamodb)]
@@ -17,17 +17,22 @@ use linera_views::{
     },
     collection_view::HashedCollectionView,
     context::{Context, MemoryContext, ViewContext},
+    indexed_map_view::{IndexedMapView, SecondaryIndex},
     key_value_store_view::{KeyValueStoreView, ViewContainer},
     log_view::HashedLogView,
     lru_caching::LruCachingMemoryDatabase,
     map_view::{ByteMapView, HashedMapView},
     memory::MemoryDatabase,
+    ordered_map_view::CustomOrderedMapView,
     queue_view::HashedQueueView,
     random::make_deterministic_rng,
     reentrant_collection_view::HashedReentrantCollectionView,
     register_view::HashedRegisterView,
     set_view::HashedSetView,
-    store::{KeyValueDatabase, TestKeyValueDatabase as _, WritableKeyValueStore as _},
+    store::{
+        Direction, KeyValueDatabase, ReadableKeyValueStore as _, TestKeyValueDatabase as _,
+        WritableKeyValueStore as _,
+    },
     test_utils::{
         get_random_byte_vector, get_random_key_value_operations, get_random_key_values,
         span_random_reordering_put_delete,
@@ -646,6 +651,187 @@ async fn test_byte_map_view() -> Result<()> {
     Ok(())
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Order {
+    owner: String,
+    price: Option<u64>,
+}
+
+pub struct ByOwner;
+
+impl SecondaryIndex<Order> for ByOwner {
+    const NAME: &'static str = "owner";
+    const TAG: u8 = 0;
+    type Key = String;
+
+    fn index_key(order: &Order) -> Option<String> {
+        Some(order.owner.clone())
+    }
+}
+
+pub struct ByPrice;
+
+impl SecondaryIndex<Order> for ByPrice {
+    const NAME: &'static str = "price";
+    const TAG: u8 = 1;
+    type Key = u64;
+
+    fn index_key(order: &Order) -> Option<u64> {
+        order.price
+    }
+
+    fn serialize_key(price: &u64) -> Result<Vec<u8>, ViewError> {
+        Ok(price.to_be_bytes().to_vec())
+    }
+
+    fn deserialize_key(bytes: &[u8]) -> Result<u64, ViewError> {
+        let bytes = bytes
+            .try_into()
+            .map_err(|_| ViewError::InconsistentEntries)?;
+        Ok(u64::from_be_bytes(bytes))
+    }
+}
+
+#[derive(CryptoHashRootView)]
+pub struct IndexedMapStateView<C> {
+    pub orders: IndexedMapView<C, u32, Order, (ByOwner, ByPrice)>,
+}
+
+fn order(owner: &str, price: Option<u64>) -> Order {
+    Order {
+        owner: owner.to_string(),
+        price,
+    }
+}
+
+#[tokio::test]
+async fn test_indexed_map_view() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let alice = "Alice".to_string();
+    let bob = "Bob".to_string();
+    {
+        let mut view = IndexedMapStateView::load(context.clone()).await?;
+        view.orders.insert(&1, order("Alice", Some(300))).await?;
+        view.orders.insert(&2, order("Bob", Some(20))).await?;
+        view.orders.insert(&3, order("Alice", None)).await?;
+        assert_eq!(view.orders.keys_by_index::<ByOwner>(&alice).await?, [1, 3]);
+        view.save().await?;
+    }
+    {
+        let mut view = IndexedMapStateView::load(context.clone()).await?;
+        assert_eq!(view.orders.keys_by_index::<ByOwner>(&alice).await?, [1, 3]);
+        // Reassigning an order moves it between the index keys.
+        view.orders.insert(&1, order("Bob", Some(5))).await?;
+        assert_eq!(view.orders.keys_by_index::<ByOwner>(&alice).await?, [3]);
+        assert_eq!(
+            view.orders.key_values_by_index::<ByOwner>(&bob).await?,
+            [(1, order("Bob", Some(5))), (2, order("Bob", Some(20)))]
+        );
+        // The prices are serialized in big-endian, so the index follows their order.
+        assert_eq!(
+            view.orders
+                .keys_by_index_range::<ByPrice, _>(.., None, Direction::Forward)
+                .await?,
+            [(5, 1), (20, 2)]
+        );
+        view.save().await?;
+    }
+    {
+        let mut view = IndexedMapStateView::load(context.clone()).await?;
+        view.orders.insert(&4, order("Carol", Some(300))).await?;
+        view.orders.remove(&2).await?;
+        assert_eq!(
+            view.orders
+                .keys_by_index_range::<ByPrice, _>(5..=300, Some(2), Direction::Reverse)
+                .await?,
+            [(300, 4), (5, 1)]
+        );
+        assert_eq!(
+            view.orders
+                .key_values_by_index_range::<ByPrice, _>(6.., None, Direction::Forward)
+                .await?,
+            [(300, 4, order("Carol", Some(300)))]
+        );
+        view.save().await?;
+    }
+    {
+        let mut view = IndexedMapStateView::load(context.clone()).await?;
+        assert_eq!(view.orders.keys_by_index::<ByOwner>(&bob).await?, [1]);
+        assert_eq!(view.orders.count().await?, 3);
+        view.orders.clear();
+        assert!(view.orders.keys_by_index::<ByOwner>(&bob).await?.is_empty());
+        view.save().await?;
+    }
+    let keys = context.store().find_keys_by_prefix(&[]).await?;
+    assert!(keys.is_empty());
+    Ok(())
+}
+
+#[derive(CryptoHashRootView)]
+pub struct OrderedMapStateView<C> {
+    pub scores: CustomOrderedMapView<C, u128, u64>,
+}
+
+#[tokio::test]
+async fn test_ordered_map_view() -> Result<()> {
+    let context = MemoryContext::new_for_testing(());
+    let mut rng = make_deterministic_rng();
+    let mut reference = BTreeMap::new();
+    for round in 0..6 {
+        let mut view = OrderedMapStateView::load(context.clone()).await?;
+        let hash = view.hash_mut().await?;
+        // Enough operations to split the leaves and the internal nodes of the tree.
+        for _ in 0..1500 {
+            let key = rng.gen_range(0..3000u128);
+            if round < 3 || rng.gen_bool(0.3) {
+                let value = rng.gen::<u64>();
+                view.scores.insert(&key, value).await?;
+                reference.insert(key, value);
+            } else {
+                view.scores.remove(&key).await?;
+                reference.remove(&key);
+            }
+        }
+        let keys = reference.keys().copied().collect::<Vec<_>>();
+        assert_eq!(view.scores.count(), reference.len());
+        for _ in 0..50 {
+            let key = rng.gen_range(0..3100u128);
+            let rank = keys.partition_point(|k| *k < key);
+            assert_eq!(view.scores.rank(&key).await?, rank);
+            let index = rng.gen_range(0..reference.len() + 10);
+            let entry = keys.get(index).map(|k| (*k, reference[k]));
+            assert_eq!(view.scores.nth(index).await?, entry);
+            let end = key + rng.gen_range(0..500u128);
+            let count = reference.range(key..end).count();
+            assert_eq!(view.scores.count_range(key..end).await?, count);
+            let count = reference.range(key..=end).count();
+            assert_eq!(view.scores.count_range(key..=end).await?, count);
+            let entries = reference
+                .iter()
+                .skip(index)
+                .take(20)
+                .map(|(k, v)| (*k, *v))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                view.scores.index_values_by_rank(index..index + 20).await?,
+                entries
+            );
+        }
+        view.save().await?;
+        let mut view = OrderedMapStateView::load(context.clone()).await?;
+        assert_eq!(view.scores.count(), reference.len());
+        assert_ne!(view.hash_mut().await?, hash);
+    }
+    let mut view = OrderedMapStateView::load(context.clone()).await?;
+    view.scores.clear();
+    assert_eq!(view.scores.count(), 0);
+    assert_eq!(view.scores.nth(0).await?, None);
+    view.save().await?;
+    let keys = context.store().find_keys_by_prefix(&[]).await?;
+    assert!(keys.is_empty());
+    Ok(())
+}
+
 #[cfg(test)]
 async fn test_views_in_lru_memory_param(config: &TestConfig) -> Result<()> {
     tracing::warn!("Testing config {:?} with lru memory", config);
@@ -24,7 +24,12 @@ pub mod signer;
 use std::{collections::HashMap, future::Future, sync::Arc, time::Duration};
 
 use futures::{future::FutureExt as _, lock::Mutex as AsyncMutex, stream::StreamExt};
-use linera_base::identifiers::{AccountOwner, ApplicationId};
+use linera_base::{
+    crypto::CryptoHash,
+    data_types::Event,
+    identifiers::{AccountOwner, ApplicationId, ChainId},
+};
+use linera_chain::types::BlockHeader;
 use linera_client::{
     chain_listener::{ChainListener, ChainListenerConfig, ClientContext as _},
     client_options::ClientContextOptions,
@@ -32,8 +37,10 @@ use linera_client::{
 };
 use linera_core::{
     data_types::ClientOutcome,
+    light_client::LightClient,
     node::{ValidatorNode as _, ValidatorNodeProvider as _},
 };
+use linera_execution::OutgoingMessage;
 use linera_faucet_client::Faucet;
 use linera_persistent as persistent;
 use linera_views::store::WithError;
@@ -188,6 +195,7 @@ pub struct Client {
     // expose concurrency to the browser, which must always run all
     // futures on the global task queue.
     client_context: Arc<AsyncMutex<ClientContext>>,
+    light_client: Arc<AsyncMutex<LightClient>>,
 }
 
 /// The subset of the client API that should be exposed to application
@@ -206,6 +214,15 @@ struct TransferParams {
     recipient: linera_base::identifiers::Account,
 }
 
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VerifiedBlock<'a> {
+    hash: CryptoHash,
+    header: &'a BlockHeader,
+    outgoing_messages: &'a [Vec<OutgoingMessage>],
+    events: &'a [Vec<Event>],
+}
+
 #[wasm_bindgen]
 impl Client {
     /// Creates a new client and connects to the network.
@@ -220,11 +237,12 @@ impl Client {
         skip_process_inbox: bool,
     ) -> Result<Client, JsError> {
         let mut storage = get_storage().await?;
-        wallet
-            .0
-            .genesis_config()
-            .initialize_storage(&mut storage)
-            .await?;
+        let genesis_config = wallet.0.genesis_config();
+        genesis_config.initialize_storage(&mut storage).await?;
+        let light_client = Arc::new(AsyncMutex::new(LightClient::from_network_description(
+            &genesis_config.network_description(),
+            &genesis_config.committee_blob(),
+        )?));
         // The `Arc` here is useless, but it is required by the `ChainListener` API.
         #[expect(clippy::arc_with_non_send_sync)]
         let client_context = Arc::new(AsyncMutex::new(ClientContext::new(
@@ -256,7 +274,10 @@ impl Client {
             .boxed_local(),
         );
         log::info!("Linera Web client successfully initialized");
-        Ok(Self { client_context })
+        Ok(Self {
+            client_context,
+            light_client,
+        })
     }
 
     /// Sets a callback to be called when a notification is received
@@ -374,6 +395,38 @@ impl Client {
         )?)
     }
 
+    /// Verifies the new blocks of a chain from their certificates, without executing
+    /// them, and returns their headers, outgoing messages and events.
+    ///
+    /// # Errors
+    /// If the chain ID is invalid, if the validator is unreachable, or if a certificate
+    /// fails verification.
+    #[wasm_bindgen(js_name = verifyChain)]
+    pub async fn verify_chain(&self, chain_id: &str) -> JsResult<JsValue> {
+        let chain_id: ChainId = chain_id.parse()?;
+        let node_provider = self.client_context.lock().await.make_node_provider();
+        let mut light_client = self.light_client.lock().await;
+        let address = light_client
+            .committees()
+            .values()
+            .next_back()
+            .and_then(|committee| committee.validators().values().next())
+            .map(|validator| validator.network_address.clone())
+            .ok_or_else(|| JsError::new("no validator is known"))?;
+        let node = node_provider.make_node(&address)?;
+        let blocks = light_client.synchronize_chain(&node, chain_id).await?;
+        let blocks = blocks
+            .iter()
+            .map(|block| VerifiedBlock {
+                hash: block.hash(),
+                header: block.header(),
+                outgoing_messages: block.outgoing_messages(),
+                events: block.events(),
+            })
+            .collect::<Vec<_>>();
+        Ok(blocks.serialize(&RESPONSE_SERIALIZER)?)
+    }
+
     /// Gets an object implementing the API for Web frontends.
     #[wasm_bindgen]
     #[must_use]
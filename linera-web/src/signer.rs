@@ -5,7 +5,7 @@
 use std::{fmt::Display, str::FromStr};
 
 use linera_base::{
-    crypto::{AccountSignature, CryptoHash, EvmSignature, Signer},
+    crypto::{AccountSignature, CryptoHash, EvmSignature, SignedContent, Signer},
     identifiers::AccountOwner,
 };
 use wasm_bindgen::prelude::*;
@@ -117,4 +117,13 @@ impl Signer for JsSigner {
             .map_err(|_| JsSignerError::UnexpectedSignatureFormat)?;
         Ok(AccountSignature::EvmSecp256k1 { signature, address })
     }
+
+    async fn sign_content(
+        &self,
+        owner: &AccountOwner,
+        value: &CryptoHash,
+        _content: SignedContent<'_>,
+    ) -> Result<AccountSignature, Self::Error> {
+        Signer::sign(self, owner, value).await
+    }
 }